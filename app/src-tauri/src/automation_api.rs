@@ -0,0 +1,291 @@
+//! Local HTTP API for automation tools such as Raycast, AutoHotkey or a Stream Deck.
+//!
+//! When `automation_api_enabled` is on, a server on `127.0.0.1` answers:
+//!
+//! - `POST /recording/start`, `/recording/stop` and `/recording/toggle`, returning
+//!   `{"recording": bool}`. With `?paste=false`, the dictation started is only
+//!   copied to the clipboard
+//! - `GET /transcript/last`: the latest history entry
+//! - `POST /cleanup` with `{"text": "..."}`: the text cleaned up like a dictation,
+//!   as `{"text": "..."}`, with the prompt, locale and vocabulary from settings
+//!
+//! Every request must send `Authorization: Bearer <automation_api_token>`. Errors
+//! are returned as `{"error": "..."}`.
+
+use crate::commands::settings::persist_store;
+use crate::history::HistoryStorage;
+use crate::pipeline::REQUEST_ID_HEADER;
+use crate::settings::{SettingsManager, SETTINGS_FILE_NAME};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+use tiny_http::{Header, Request, Response, Server};
+
+/// Port the API listens on when the setting is missing
+pub const DEFAULT_AUTOMATION_API_PORT: u16 = 8766;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// Time the server's LLM gets to clean up submitted text
+const CLEANUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Source named in logs for recordings started through the API
+const SOURCE: &str = "Automation API";
+
+/// What a request asks for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Endpoint {
+    StartRecording,
+    StopRecording,
+    ToggleRecording,
+    LastTranscript,
+    Cleanup,
+}
+
+#[derive(Deserialize)]
+struct TextBody {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct CleanupRequest<'a> {
+    text: &'a str,
+    provider: Option<String>,
+    sections: Option<Value>,
+    locale: Option<Value>,
+    vocabulary: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct CleanupResponse {
+    text: String,
+}
+
+/// The endpoint for a method and URL, ignoring any query string and trailing slash
+pub fn route(method: &str, url: &str) -> Option<Endpoint> {
+    let path = url.split('?').next().unwrap_or(url);
+    let path = path.strip_suffix('/').unwrap_or(path);
+    match (method.to_ascii_uppercase().as_str(), path) {
+        ("POST", "/recording/start") => Some(Endpoint::StartRecording),
+        ("POST", "/recording/stop") => Some(Endpoint::StopRecording),
+        ("POST", "/recording/toggle") => Some(Endpoint::ToggleRecording),
+        ("GET", "/transcript/last") => Some(Endpoint::LastTranscript),
+        ("POST", "/cleanup") => Some(Endpoint::Cleanup),
+        _ => None,
+    }
+}
+
+/// A boolean query parameter of a URL, if it is given as `true` or `false`
+pub fn query_flag(url: &str, name: &str) -> Option<bool> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .find_map(|pair| match pair.split_once('=') {
+            Some((key, "true")) if key == name => Some(true),
+            Some((key, "false")) if key == name => Some(false),
+            _ => None,
+        })
+}
+
+/// Whether an `Authorization` header carries the token; an empty token allows nothing
+pub fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+    let Some(given) = authorization.and_then(|value| value.trim().strip_prefix("Bearer ")) else {
+        return false;
+    };
+    let (given, token) = (given.trim().as_bytes(), token.as_bytes());
+    // Compare every byte so the time taken doesn't hint at how much matched
+    !token.is_empty()
+        && given.len() == token.len()
+        && given
+            .iter()
+            .zip(token)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// A new random token
+pub fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 24];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate API token: {}", e))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Replace the API token with a new one and save it
+pub fn regenerate_token(app: &AppHandle) -> Result<String, String> {
+    let token = generate_token()?;
+    let store = app
+        .store(SETTINGS_FILE_NAME)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.set("automation_api_token", json!(token));
+    persist_store(app, &app.state::<SettingsManager>())?;
+    Ok(token)
+}
+
+/// The stored token, generating one the first time
+pub(crate) fn ensure_token(app: &AppHandle) -> Result<String, String> {
+    match stored_token(app) {
+        Some(token) => Ok(token),
+        None => regenerate_token(app),
+    }
+}
+
+pub(crate) fn stored_token(app: &AppHandle) -> Option<String> {
+    crate::settings::read_setting::<Option<String>>(app, "automation_api_token")
+        .filter(|token| !token.is_empty())
+}
+
+fn error(message: impl Into<String>) -> Value {
+    json!({ "error": message.into() })
+}
+
+fn is_recording(app: &AppHandle) -> bool {
+    crate::recording_state::is_recording(app)
+}
+
+/// Start or stop recording, then report whether it is recording. A dictation started
+/// without `paste` is only copied.
+fn set_recording(app: &AppHandle, recording: bool, paste: bool) -> (u16, Value) {
+    let was_recording = is_recording(app);
+    #[cfg(desktop)]
+    crate::set_recording(app, recording, SOURCE);
+    if recording && !was_recording {
+        app.state::<AppState>()
+            .copy_only_dictation
+            .store(!paste, Ordering::SeqCst);
+    }
+    (200, json!({ "recording": is_recording(app) }))
+}
+
+fn last_transcript(app: &AppHandle) -> (u16, Value) {
+    let Some(history) = app.try_state::<HistoryStorage>() else {
+        return (503, error("History isn't available"));
+    };
+    match history.get_all(Some(1)) {
+        Ok(entries) => match entries.into_iter().next() {
+            Some(entry) => (200, json!(entry)),
+            None => (404, error("There are no dictations yet")),
+        },
+        Err(e) => (500, error(e)),
+    }
+}
+
+/// Ask the server's LLM to clean up text with the prompt settings of dictations
+pub(crate) fn clean_up(app: &AppHandle, text: &str) -> Result<String, String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(CLEANUP_TIMEOUT))
+        .build()
+        .into();
+    let request = CleanupRequest {
+        text,
+        provider: crate::settings::read_setting(app, "llm_provider"),
+        sections: crate::settings::read_setting(app, "cleanup_prompt_sections"),
+        locale: crate::settings::read_setting(app, "locale_preferences"),
+        vocabulary: crate::settings::read_setting(app, "stt_vocabulary"),
+    };
+    let request = serde_json::to_value(&request).map_err(|e| e.to_string())?;
+    // Aborted at shutdown rather than keeping the app waiting on the provider
+    let response: CleanupResponse =
+        crate::pipeline::shutdown_token(app).run_request(move |id| {
+            agent
+                .post(format!("{}/api/cleanup", crate::commands::text::SERVER_URL))
+                .header(REQUEST_ID_HEADER, id)
+                .send_json(&request)
+                .map_err(|e| format!("Failed to clean up text: {}", e))?
+                .body_mut()
+                .read_json()
+                .map_err(|e| format!("Failed to read cleaned up text: {}", e))
+        })?;
+    Ok(response.text)
+}
+
+fn cleanup(app: &AppHandle, request: &mut Request) -> (u16, Value) {
+    let mut body = String::new();
+    if let Err(e) = request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+    {
+        return (400, error(format!("Failed to read request: {}", e)));
+    }
+    let text = match serde_json::from_str::<TextBody>(&body) {
+        Ok(body) => body.text,
+        Err(e) => return (400, error(format!("Expected {{\"text\": ...}}: {}", e))),
+    };
+    if text.trim().is_empty() {
+        return (400, error("The text is empty"));
+    }
+    match clean_up(app, &text) {
+        Ok(text) => (200, json!({ "text": text })),
+        Err(e) => (502, error(e)),
+    }
+}
+
+fn handle(app: &AppHandle, request: &mut Request) -> (u16, Value) {
+    let authorization = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .map(|header| header.value.as_str());
+    let token = stored_token(app).unwrap_or_default();
+    if !is_authorized(authorization, &token) {
+        return (401, error("Missing or wrong API token"));
+    }
+
+    let paste = query_flag(request.url(), "paste").unwrap_or(true);
+    match route(request.method().as_str(), request.url()) {
+        Some(Endpoint::StartRecording) => set_recording(app, true, paste),
+        Some(Endpoint::StopRecording) => set_recording(app, false, paste),
+        Some(Endpoint::ToggleRecording) => set_recording(app, !is_recording(app), paste),
+        Some(Endpoint::LastTranscript) => last_transcript(app),
+        Some(Endpoint::Cleanup) => cleanup(app, request),
+        None => (404, error("Unknown endpoint")),
+    }
+}
+
+fn respond(app: &AppHandle, mut request: Request) {
+    let (status, body) = handle(app, &mut request);
+    let content_type = Header::from_bytes("Content-Type", "application/json")
+        .expect("Content type header is valid");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(e) = request.respond(response) {
+        log::debug!("Failed to answer automation request: {}", e);
+    }
+}
+
+/// Serve the API on a background thread, when it is enabled
+pub fn spawn(app: AppHandle) {
+    if !crate::settings::read_setting::<bool>(&app, "automation_api_enabled") {
+        return;
+    }
+    if let Err(e) = ensure_token(&app) {
+        log::error!("Automation API not started: {}", e);
+        return;
+    }
+    let port = crate::settings::read_setting::<Option<u16>>(&app, "automation_api_port")
+        .unwrap_or(DEFAULT_AUTOMATION_API_PORT);
+    let server = match Server::http(("127.0.0.1", port)) {
+        Ok(server) => server,
+        Err(e) => {
+            log::error!("Failed to start automation API on port {}: {}", port, e);
+            return;
+        }
+    };
+    log::info!("Automation API listening on 127.0.0.1:{}", port);
+
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            // Cleanup waits on the LLM, so requests don't queue behind each other
+            let app = app.clone();
+            thread::spawn(move || respond(&app, request));
+        }
+    });
+}
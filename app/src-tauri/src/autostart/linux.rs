@@ -0,0 +1,47 @@
+//! Linux launch-at-login via an XDG autostart desktop file.
+
+use super::AutostartEntry;
+use std::fs;
+use std::path::PathBuf;
+
+/// Path of the autostart desktop file for this app
+fn desktop_file_path(entry: &AutostartEntry) -> Result<PathBuf, String> {
+    let config_dir = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| std::env::var("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map_err(|_| "Neither XDG_CONFIG_HOME nor HOME is set".to_string())?;
+    Ok(config_dir
+        .join("autostart")
+        .join(format!("{}.desktop", entry.identifier)))
+}
+
+pub fn enable(entry: &AutostartEntry) -> Result<(), String> {
+    let path = desktop_file_path(entry)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create autostart directory: {}", e))?;
+    }
+
+    let contents = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\"\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+        entry.app_name,
+        entry.executable.to_string_lossy()
+    );
+
+    fs::write(&path, contents).map_err(|e| format!("Failed to write autostart file: {}", e))?;
+    log::info!("Launch at login enabled via {:?}", path);
+    Ok(())
+}
+
+pub fn disable(entry: &AutostartEntry) -> Result<(), String> {
+    let path = desktop_file_path(entry)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove autostart file: {}", e))?;
+    }
+    log::info!("Launch at login disabled");
+    Ok(())
+}
+
+pub fn is_enabled(entry: &AutostartEntry) -> bool {
+    desktop_file_path(entry).is_ok_and(|path| path.exists())
+}
@@ -0,0 +1,66 @@
+//! macOS launch-at-login via a per-user LaunchAgent.
+
+use super::AutostartEntry;
+use std::fs;
+use std::path::PathBuf;
+
+/// Path of the LaunchAgent plist for this app
+fn launch_agent_path(entry: &AutostartEntry) -> Result<PathBuf, String> {
+    let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+    Ok(PathBuf::from(home)
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", entry.identifier)))
+}
+
+/// Minimal XML escaping for plist string values
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub fn enable(entry: &AutostartEntry) -> Result<(), String> {
+    let path = launch_agent_path(entry)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create LaunchAgents directory: {}", e))?;
+    }
+
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{program}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        label = escape_xml(&entry.identifier),
+        program = escape_xml(&entry.executable.to_string_lossy()),
+    );
+
+    fs::write(&path, plist).map_err(|e| format!("Failed to write LaunchAgent: {}", e))?;
+    log::info!("Launch at login enabled via {:?}", path);
+    Ok(())
+}
+
+pub fn disable(entry: &AutostartEntry) -> Result<(), String> {
+    let path = launch_agent_path(entry)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove LaunchAgent: {}", e))?;
+    }
+    log::info!("Launch at login disabled");
+    Ok(())
+}
+
+pub fn is_enabled(entry: &AutostartEntry) -> bool {
+    launch_agent_path(entry).is_ok_and(|path| path.exists())
+}
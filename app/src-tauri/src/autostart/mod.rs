@@ -0,0 +1,89 @@
+//! Launch-at-login integration.
+//!
+//! Registers the app with the platform's login mechanism: a LaunchAgent on macOS,
+//! the `Run` registry key on Windows, and an XDG autostart desktop file on Linux.
+
+use std::path::PathBuf;
+
+// Platform-specific implementations
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+/// Identifies the app to the platform login mechanism
+#[derive(Debug, Clone)]
+pub struct AutostartEntry {
+    /// Human-readable app name (e.g. "Tambourine")
+    pub app_name: String,
+    /// Reverse-DNS bundle identifier (e.g. "com.tambourine-voice.app")
+    pub identifier: String,
+    /// Executable to launch at login
+    pub executable: PathBuf,
+}
+
+impl AutostartEntry {
+    /// Build an entry for the running app
+    pub fn for_app(app: &tauri::AppHandle) -> Result<Self, String> {
+        let config = app.config();
+        Ok(Self {
+            app_name: config
+                .product_name
+                .clone()
+                .unwrap_or_else(|| "Tambourine".to_string()),
+            identifier: config.identifier.clone(),
+            executable: std::env::current_exe()
+                .map_err(|e| format!("Failed to locate executable: {}", e))?,
+        })
+    }
+}
+
+/// Register the app to launch at login
+pub fn enable(entry: &AutostartEntry) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::enable(entry)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::enable(entry)
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        linux::enable(entry)
+    }
+}
+
+/// Remove the app from launch at login
+pub fn disable(entry: &AutostartEntry) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::disable(entry)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::disable(entry)
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        linux::disable(entry)
+    }
+}
+
+/// Check whether the app is currently registered to launch at login
+pub fn is_enabled(entry: &AutostartEntry) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        windows::is_enabled(entry)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::is_enabled(entry)
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        linux::is_enabled(entry)
+    }
+}
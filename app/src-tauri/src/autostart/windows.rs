@@ -0,0 +1,56 @@
+//! Windows launch-at-login via the per-user `Run` registry key.
+
+use super::AutostartEntry;
+use std::os::windows::process::CommandExt;
+use std::process::{Command, Stdio};
+
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+/// Prevents a console window from flashing when running reg.exe
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+fn reg(args: &[&str]) -> Result<bool, String> {
+    Command::new("reg")
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .creation_flags(CREATE_NO_WINDOW)
+        .status()
+        .map(|status| status.success())
+        .map_err(|e| format!("Failed to run reg.exe: {}", e))
+}
+
+pub fn enable(entry: &AutostartEntry) -> Result<(), String> {
+    let command = format!("\"{}\"", entry.executable.to_string_lossy());
+    let added = reg(&[
+        "add",
+        RUN_KEY,
+        "/v",
+        &entry.app_name,
+        "/t",
+        "REG_SZ",
+        "/d",
+        &command,
+        "/f",
+    ])?;
+    if !added {
+        return Err("Failed to add registry Run entry".to_string());
+    }
+    log::info!("Launch at login enabled via registry Run key");
+    Ok(())
+}
+
+pub fn disable(entry: &AutostartEntry) -> Result<(), String> {
+    if is_enabled(entry) {
+        let deleted = reg(&["delete", RUN_KEY, "/v", &entry.app_name, "/f"])?;
+        if !deleted {
+            return Err("Failed to remove registry Run entry".to_string());
+        }
+    }
+    log::info!("Launch at login disabled");
+    Ok(())
+}
+
+pub fn is_enabled(entry: &AutostartEntry) -> bool {
+    reg(&["query", RUN_KEY, "/v", &entry.app_name]).unwrap_or(false)
+}
@@ -0,0 +1,574 @@
+//! Inserting dictations into web pages through a companion browser extension.
+//!
+//! Synthetic paste is unreliable in some web apps (rich editors that rebuild the
+//! field, sites that block paste), so when `browser_extension_enabled` is on the
+//! extension can take the text instead and insert it at the caret of the focused
+//! field itself. The browser starts this executable as its native messaging host
+//! (`com.tambourine_voice.host`), which relays messages between the extension and
+//! the running app's socket on `ws://127.0.0.1:<browser_extension_port>`,
+//! authorized with the automation API token.
+//!
+//! Messages are JSON, the same on both legs:
+//!
+//! - app to extension: `{"type": "insert", "id": "...", "text": "..."}` when a
+//!   dictation finished while the browser had focus
+//! - extension to app: `{"type": "inserted", "id": "...", "ok": true}`, or `"ok":
+//!   false` with an `error` when no editable field of the active tab has focus, so
+//!   the text is pasted the regular way
+//! - host to extension: `{"type": "error", "message": "..."}` when the app can't be
+//!   reached, just before the host exits
+//!
+//! The host manifests naming the extensions in `browser_extension_ids` are written
+//! for the installed browsers by [`install_host`].
+
+use crate::automation_api::is_authorized;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tungstenite::handshake::server::{Request, Response};
+use tungstenite::Message;
+
+/// Name the extension connects to the native messaging host by
+pub const NATIVE_HOST_NAME: &str = "com.tambourine_voice.host";
+
+/// Port the socket listens on when the setting is missing
+pub const DEFAULT_BROWSER_EXTENSION_PORT: u16 = 8769;
+
+/// Largest message a host may send to the browser
+pub const MAX_FRAME_TO_BROWSER: usize = 1024 * 1024;
+
+/// Largest message the browser may send to a host
+const MAX_FRAME_FROM_BROWSER: usize = 64 * 1024 * 1024;
+
+/// Time the extension gets to report whether it inserted the text
+const INSERT_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// How long a connection waits for a message before sending queued ones
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Browsers the extension runs in, by executable (Windows) or application (macOS) name
+const BROWSERS: &[&str] = &[
+    "chrome",
+    "google chrome",
+    "chromium",
+    "msedge",
+    "microsoft edge",
+    "brave",
+    "brave browser",
+    "vivaldi",
+    "opera",
+    "arc",
+    "firefox",
+    "firefox developer edition",
+    "librewolf",
+    "zen",
+];
+
+/// Whether an app is a browser the extension may be running in
+pub fn is_browser(app: &str) -> bool {
+    let app = app.trim().to_lowercase();
+    let app = app.strip_suffix(".exe").unwrap_or(&app);
+    BROWSERS.contains(&app)
+}
+
+/// What the extension sends to the app
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExtensionMessage {
+    Hello {
+        #[serde(default)]
+        browser: Option<String>,
+    },
+    Inserted {
+        id: String,
+        ok: bool,
+        #[serde(default)]
+        error: Option<String>,
+    },
+}
+
+/// The message asking the extension to insert text
+pub fn insert_message(id: &str, text: &str) -> String {
+    json!({ "type": "insert", "id": id, "text": text }).to_string()
+}
+
+/// Read one native messaging frame (a length in native byte order, then JSON).
+/// Returns `None` once the browser closed the pipe.
+pub fn read_frame(reader: &mut impl Read) -> Result<Option<String>, String> {
+    let mut length = [0u8; 4];
+    match reader.read_exact(&mut length) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(format!("Failed to read from the browser: {}", e)),
+    }
+    let length = u32::from_ne_bytes(length) as usize;
+    if length > MAX_FRAME_FROM_BROWSER {
+        return Err(format!(
+            "Message from the browser too large ({} bytes)",
+            length
+        ));
+    }
+    let mut message = vec![0u8; length];
+    reader
+        .read_exact(&mut message)
+        .map_err(|e| format!("Failed to read from the browser: {}", e))?;
+    String::from_utf8(message)
+        .map(Some)
+        .map_err(|_| "Message from the browser is not UTF-8".to_string())
+}
+
+/// Write one native messaging frame
+pub fn write_frame(writer: &mut impl Write, message: &str) -> Result<(), String> {
+    if message.len() > MAX_FRAME_TO_BROWSER {
+        return Err(format!(
+            "Message for the browser too large ({} bytes)",
+            message.len()
+        ));
+    }
+    writer
+        .write_all(&(message.len() as u32).to_ne_bytes())
+        .and_then(|()| writer.write_all(message.as_bytes()))
+        .and_then(|()| writer.flush())
+        .map_err(|e| format!("Failed to write to the browser: {}", e))
+}
+
+/// Connected extensions and inserts waiting for their answer
+#[derive(Default)]
+pub struct BrowserExtensions {
+    senders: Mutex<Vec<mpsc::Sender<String>>>,
+    pending: Mutex<HashMap<String, mpsc::Sender<bool>>>,
+}
+
+impl BrowserExtensions {
+    fn add(&self, sender: mpsc::Sender<String>) {
+        if let Ok(mut senders) = self.senders.lock() {
+            senders.push(sender);
+        }
+    }
+
+    /// Queue a message for every connected extension, returning how many got it
+    fn broadcast(&self, message: &str) -> usize {
+        let Ok(mut senders) = self.senders.lock() else {
+            return 0;
+        };
+        senders.retain(|sender| sender.send(message.to_string()).is_ok());
+        senders.len()
+    }
+
+    fn answer(&self, id: &str, ok: bool) {
+        if let Some(waiting) = self
+            .pending
+            .lock()
+            .ok()
+            .and_then(|pending| pending.get(id).cloned())
+        {
+            let _ = waiting.send(ok);
+        }
+    }
+
+    /// Ask the extensions to insert text, waiting until one did or all declined
+    fn insert(&self, text: &str) -> bool {
+        let id = uuid::Uuid::new_v4().to_string();
+        let (sender, receiver) = mpsc::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id.clone(), sender);
+        }
+        let asked = self.broadcast(&insert_message(&id, text));
+
+        let deadline = Instant::now() + INSERT_TIMEOUT;
+        let mut declined = 0;
+        let mut inserted = false;
+        while declined < asked {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(remaining) {
+                Ok(true) => {
+                    inserted = true;
+                    break;
+                }
+                Ok(false) => declined += 1,
+                Err(_) => {
+                    log::warn!("Browser extension didn't answer in time");
+                    break;
+                }
+            }
+        }
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&id);
+        }
+        inserted
+    }
+}
+
+/// Let the browser extension insert text into the focused web page field, if the
+/// browser has focus and an extension is connected. Returns false when the text
+/// must be pasted the regular way.
+pub fn try_insert(app: &AppHandle, text: &str, target_app: Option<&str>) -> bool {
+    // Without a known app (Linux) the extension checks the page has focus itself
+    if target_app.is_some_and(|target| !is_browser(target)) {
+        return false;
+    }
+    let Some(extensions) = app.try_state::<BrowserExtensions>() else {
+        return false;
+    };
+    let inserted = extensions.insert(text);
+    if inserted {
+        log::debug!("Dictation inserted by the browser extension");
+    }
+    inserted
+}
+
+/// Relay messages between one host process and the app until it disconnects
+fn serve(app: &AppHandle, stream: TcpStream) {
+    let token = crate::automation_api::stored_token(app).unwrap_or_default();
+    let check_token = |request: &Request, response: Response| {
+        let given = crate::stream_deck::query_token(request.uri().query())
+            .map(|token| format!("Bearer {}", token));
+        if !is_authorized(given.as_deref(), &token) {
+            return Err(crate::stream_deck::unauthorized());
+        }
+        Ok(response)
+    };
+    let mut socket = match tungstenite::accept_hdr(stream, check_token) {
+        Ok(socket) => socket,
+        Err(e) => {
+            log::debug!("Browser extension connection refused: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)) {
+        log::warn!("Failed to set up browser extension connection: {}", e);
+        return;
+    }
+
+    let extensions = app.state::<BrowserExtensions>();
+    let (sender, receiver) = mpsc::channel();
+    extensions.add(sender);
+
+    loop {
+        while let Ok(message) = receiver.try_recv() {
+            if socket.send(Message::text(message)).is_err() {
+                return;
+            }
+        }
+        match socket.read() {
+            Ok(Message::Text(text)) => match serde_json::from_str::<ExtensionMessage>(&text) {
+                Ok(ExtensionMessage::Hello { browser }) => log::info!(
+                    "Browser extension connected ({})",
+                    browser.as_deref().unwrap_or("unknown browser")
+                ),
+                Ok(ExtensionMessage::Inserted { id, ok, error }) => {
+                    if let Some(error) = error.filter(|_| !ok) {
+                        log::debug!("Browser extension didn't insert: {}", error);
+                    }
+                    extensions.answer(&id, ok);
+                }
+                Err(e) => log::debug!("Unknown browser extension message: {}", e),
+            },
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => {
+                log::debug!("Browser extension connection closed: {}", e);
+                break;
+            }
+        }
+    }
+    log::info!("Browser extension disconnected");
+}
+
+/// Accept host connections on a background thread, when the extension is enabled
+pub fn spawn(app: AppHandle) {
+    if !crate::settings::read_setting::<bool>(&app, "browser_extension_enabled") {
+        return;
+    }
+    if let Err(e) = crate::automation_api::ensure_token(&app) {
+        log::error!("Browser extension socket not started: {}", e);
+        return;
+    }
+    let port = crate::settings::read_setting::<Option<u16>>(&app, "browser_extension_port")
+        .unwrap_or(DEFAULT_BROWSER_EXTENSION_PORT);
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!(
+                "Failed to start browser extension socket on port {}: {}",
+                port,
+                e
+            );
+            return;
+        }
+    };
+    log::info!("Browser extension socket listening on 127.0.0.1:{}", port);
+
+    app.manage(BrowserExtensions::default());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let app = app.clone();
+                    thread::spawn(move || serve(&app, stream));
+                }
+                Err(e) => log::debug!("Failed to accept browser extension connection: {}", e),
+            }
+        }
+    });
+}
+
+/// Relay native messages between the browser (stdin and stdout) and the running
+/// app, until the browser closes the pipe
+pub fn run_host(port: u16, token: &str) -> Result<(), String> {
+    let url = format!("ws://127.0.0.1:{}/?token={}", port, token);
+    let (mut socket, _) = match tungstenite::connect(url) {
+        Ok(connection) => connection,
+        Err(e) => {
+            let message = format!("Failed to reach Tambourine (is it running?): {}", e);
+            let notice = json!({ "type": "error", "message": message }).to_string();
+            let _ = write_frame(&mut io::stdout().lock(), &notice);
+            return Err(message);
+        }
+    };
+    if let tungstenite::stream::MaybeTlsStream::Plain(stream) = socket.get_ref() {
+        stream
+            .set_read_timeout(Some(POLL_INTERVAL))
+            .map_err(|e| format!("Failed to set up the connection: {}", e))?;
+    }
+
+    // Reading stdin blocks, so it happens on its own thread
+    let (sender, receiver) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        loop {
+            match read_frame(&mut stdin) {
+                Ok(Some(message)) => {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    break;
+                }
+            }
+        }
+    });
+
+    let mut stdout = io::stdout().lock();
+    loop {
+        loop {
+            match receiver.try_recv() {
+                Ok(message) => socket
+                    .send(Message::text(message))
+                    .map_err(|e| format!("Failed to send to Tambourine: {}", e))?,
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    let _ = socket.close(None);
+                    return Ok(());
+                }
+            }
+        }
+        match socket.read() {
+            Ok(Message::Text(text)) => write_frame(&mut stdout, &text)?,
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => return Err(format!("Lost the connection to Tambourine: {}", e)),
+        }
+    }
+}
+
+/// Kind of browser an extension ID belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrowserFamily {
+    /// Chrome, Edge, Brave and other Chromium browsers
+    Chromium,
+    Firefox,
+}
+
+/// Firefox extension IDs look like e-mail addresses or GUIDs in braces
+pub fn extension_family(id: &str) -> BrowserFamily {
+    if id.contains('@') || id.starts_with('{') {
+        BrowserFamily::Firefox
+    } else {
+        BrowserFamily::Chromium
+    }
+}
+
+/// The native messaging host manifest allowing the extensions of one family
+pub fn host_manifest(family: BrowserFamily, host_path: &Path, ids: &[String]) -> Value {
+    let mut manifest = json!({
+        "name": NATIVE_HOST_NAME,
+        "description": "Tambourine dictation",
+        "path": host_path,
+        "type": "stdio",
+    });
+    let ids = ids
+        .iter()
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty() && extension_family(id) == family);
+    match family {
+        BrowserFamily::Chromium => {
+            manifest["allowed_origins"] = ids
+                .map(|id| format!("chrome-extension://{}/", id))
+                .collect();
+        }
+        BrowserFamily::Firefox => {
+            manifest["allowed_extensions"] = ids.collect();
+        }
+    }
+    manifest
+}
+
+/// Per-user directories the browsers of a family look for host manifests in, each
+/// with the browser profile directory that shows the browser is installed
+#[cfg(not(target_os = "windows"))]
+fn manifest_dirs(family: BrowserFamily) -> Vec<(std::path::PathBuf, std::path::PathBuf)> {
+    use std::path::PathBuf;
+
+    let Some(home) = std::env::var_os("HOME").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    #[cfg(target_os = "macos")]
+    let (base, browsers): (PathBuf, &[&str]) = match family {
+        BrowserFamily::Chromium => (
+            home.join("Library/Application Support"),
+            &[
+                "Google/Chrome",
+                "Chromium",
+                "Microsoft Edge",
+                "BraveSoftware/Brave-Browser",
+                "Vivaldi",
+            ],
+        ),
+        BrowserFamily::Firefox => (home.join("Library/Application Support"), &["Mozilla"]),
+    };
+    #[cfg(not(target_os = "macos"))]
+    let (base, browsers): (PathBuf, &[&str]) = match family {
+        BrowserFamily::Chromium => (
+            home.join(".config"),
+            &[
+                "google-chrome",
+                "chromium",
+                "microsoft-edge",
+                "BraveSoftware/Brave-Browser",
+                "vivaldi",
+            ],
+        ),
+        BrowserFamily::Firefox => (home, &[".mozilla"]),
+    };
+    let hosts_dir = match (family, cfg!(target_os = "macos")) {
+        (BrowserFamily::Firefox, false) => "native-messaging-hosts",
+        _ => "NativeMessagingHosts",
+    };
+    browsers
+        .iter()
+        .map(|browser| {
+            let profile = base.join(browser);
+            (profile.join(hosts_dir), profile)
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn install_manifest(
+    _app: &AppHandle,
+    family: BrowserFamily,
+    manifest: &str,
+) -> Result<Vec<String>, String> {
+    let mut installed = Vec::new();
+    for (dir, profile) in manifest_dirs(family) {
+        if !profile.is_dir() {
+            continue;
+        }
+        let path = dir.join(format!("{}.json", NATIVE_HOST_NAME));
+        std::fs::create_dir_all(&dir)
+            .and_then(|()| std::fs::write(&path, manifest))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        installed.push(path.display().to_string());
+    }
+    Ok(installed)
+}
+
+/// Windows browsers find host manifests through the registry
+#[cfg(target_os = "windows")]
+fn install_manifest(
+    app: &AppHandle,
+    family: BrowserFamily,
+    manifest: &str,
+) -> Result<Vec<String>, String> {
+    use std::os::windows::process::CommandExt;
+
+    /// Keeps reg.exe from flashing a console window
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let keys: &[&str] = match family {
+        BrowserFamily::Chromium => &[
+            r"HKCU\Software\Google\Chrome\NativeMessagingHosts",
+            r"HKCU\Software\Microsoft\Edge\NativeMessagingHosts",
+        ],
+        BrowserFamily::Firefox => &[r"HKCU\Software\Mozilla\NativeMessagingHosts"],
+    };
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to find the app data directory: {}", e))?
+        .join("native-messaging");
+    let file_name = match family {
+        BrowserFamily::Chromium => "chromium.json",
+        BrowserFamily::Firefox => "firefox.json",
+    };
+    let path = dir.join(file_name);
+    std::fs::create_dir_all(&dir)
+        .and_then(|()| std::fs::write(&path, manifest))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let mut installed = Vec::new();
+    for key in keys {
+        let key = format!(r"{}\{}", key, NATIVE_HOST_NAME);
+        let status = std::process::Command::new("reg")
+            .args(["add", &key, "/ve", "/t", "REG_SZ", "/f", "/d"])
+            .arg(&path)
+            .creation_flags(CREATE_NO_WINDOW)
+            .status()
+            .map_err(|e| format!("Failed to run reg: {}", e))?;
+        if !status.success() {
+            return Err(format!("Failed to register {} ({})", key, status));
+        }
+        installed.push(key);
+    }
+    Ok(installed)
+}
+
+/// Register this executable as the native messaging host of the extensions in
+/// `browser_extension_ids`, returning where it was registered
+pub fn install_host(app: &AppHandle) -> Result<Vec<String>, String> {
+    let ids = crate::settings::read_setting::<Vec<String>>(app, "browser_extension_ids");
+    if ids.iter().all(|id| id.trim().is_empty()) {
+        return Err("Add the browser extension's ID first".to_string());
+    }
+    let host_path = std::env::current_exe()
+        .map_err(|e| format!("Failed to find the app's executable: {}", e))?;
+
+    let mut installed = Vec::new();
+    for family in [BrowserFamily::Chromium, BrowserFamily::Firefox] {
+        if !ids.iter().any(|id| extension_family(id.trim()) == family) {
+            continue;
+        }
+        let manifest = host_manifest(family, &host_path, &ids);
+        let manifest = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+        installed.extend(install_manifest(app, family, &manifest)?);
+    }
+    if installed.is_empty() {
+        return Err("No supported browser found".to_string());
+    }
+    log::info!("Installed browser extension host: {}", installed.join(", "));
+    Ok(installed)
+}
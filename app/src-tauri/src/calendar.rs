@@ -0,0 +1,332 @@
+//! Meeting prompts from a calendar.
+//!
+//! With `calendar_ics_url` set to an ICS feed (an `https://` or `webcal://` link,
+//! such as the secret address Google Calendar, Outlook and iCloud offer for sharing,
+//! or a local `.ics` file), a background job checks for events about to start. When
+//! one starts within `meeting_prompt_minutes`, the app posts a notification and
+//! shows the main window asking whether to begin meeting transcription, which
+//! starts a session named after the event, opens the live transcript and records.
+//!
+//! Times with a `TZID` are read as local time. Events repeating daily or weekly
+//! (`RRULE` with `INTERVAL`, `COUNT` and `UNTIL`) are followed; other repeats only
+//! prompt for their first occurrence. All-day events never prompt.
+
+use crate::notifications::{notify, NotificationKind};
+use chrono::{
+    DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event asking the main window to offer meeting transcription
+pub const MEETING_STARTING_EVENT: &str = "meeting-starting";
+
+/// How often the job looks for meetings about to start
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the calendar is downloaded again
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Meetings that started this long ago are still offered, e.g. right after launch
+const LATE_GRACE_MINUTES: i64 = 5;
+
+/// Time the calendar feed gets to download
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How far to look ahead for meetings when nothing is set
+pub const DEFAULT_MEETING_PROMPT_MINUTES: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+}
+
+/// A simple repeat of an event
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    /// Number of occurrences, the first included
+    pub count: Option<u32>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// A timed event from the calendar
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub title: String,
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub recurrence: Option<Recurrence>,
+}
+
+/// Join folded lines (continuations start with a space or tab)
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Undo the escaping of ICS text values
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// A date-time value: UTC with a trailing `Z`, otherwise local time. Dates without a
+/// time (all-day) are refused.
+pub fn parse_date_time(value: &str) -> Option<DateTime<Utc>> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(naive.and_utc());
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+}
+
+fn parse_recurrence(value: &str) -> Option<Recurrence> {
+    let mut recurrence = Recurrence {
+        frequency: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+    };
+    let mut frequency = None;
+    for part in value.split(';') {
+        let Some((key, value)) = part.split_once('=') else {
+            continue;
+        };
+        match key {
+            "FREQ" => {
+                frequency = match value {
+                    "DAILY" => Some(Frequency::Daily),
+                    "WEEKLY" => Some(Frequency::Weekly),
+                    _ => None,
+                }
+            }
+            "INTERVAL" => recurrence.interval = value.parse().ok()?,
+            "COUNT" => recurrence.count = value.parse().ok(),
+            "UNTIL" => {
+                recurrence.until = parse_date_time(value).or_else(|| {
+                    // A date-only UNTIL includes that whole day
+                    let day = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+                    Some(day.and_hms_opt(23, 59, 59)?.and_utc())
+                })
+            }
+            _ => {}
+        }
+    }
+    recurrence.frequency = frequency?;
+    Some(recurrence)
+}
+
+/// The timed events of an ICS calendar
+pub fn parse_ics(ics: &str) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<Vec<(String, String)>> = None;
+    for line in unfold(ics) {
+        match line.trim_end() {
+            "BEGIN:VEVENT" => current = Some(Vec::new()),
+            "END:VEVENT" => {
+                if let Some(event) = current.take().and_then(|props| event_from(&props)) {
+                    events.push(event);
+                }
+            }
+            line => {
+                let Some(props) = current.as_mut() else {
+                    continue;
+                };
+                let Some((name, value)) = line.split_once(':') else {
+                    continue;
+                };
+                // Parameters such as `;TZID=...` follow the property name
+                let name = name.split(';').next().unwrap_or(name).to_ascii_uppercase();
+                props.push((name, value.to_string()));
+            }
+        }
+    }
+    events
+}
+
+fn event_from(props: &[(String, String)]) -> Option<CalendarEvent> {
+    let get = |name: &str| {
+        props
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    if get("STATUS") == Some("CANCELLED") {
+        return None;
+    }
+    let start = parse_date_time(get("DTSTART")?)?;
+    Some(CalendarEvent {
+        uid: get("UID").unwrap_or_default().to_string(),
+        title: get("SUMMARY")
+            .map(unescape)
+            .filter(|title| !title.trim().is_empty())
+            .unwrap_or_else(|| "Meeting".to_string()),
+        start,
+        end: get("DTEND").and_then(parse_date_time),
+        recurrence: get("RRULE").and_then(parse_recurrence),
+    })
+}
+
+/// Days between occurrences
+fn step_days(rule: &Recurrence) -> i64 {
+    let days = match rule.frequency {
+        Frequency::Daily => 1,
+        Frequency::Weekly => 7,
+    };
+    days * rule.interval.max(1) as i64
+}
+
+/// The start of the `index`th occurrence, stepping in local time to keep the time of
+/// day across daylight saving changes
+fn nth_start(event: &CalendarEvent, rule: &Recurrence, index: i64) -> Option<DateTime<Utc>> {
+    let days = step_days(rule);
+    let local =
+        event.start.with_timezone(&Local).naive_local() + ChronoDuration::days(days * index);
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+}
+
+/// The occurrence of an event starting between `from` and `to`, if there is one
+pub fn occurrence_between(
+    event: &CalendarEvent,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Option<CalendarEvent> {
+    let Some(rule) = &event.recurrence else {
+        return (from..=to).contains(&event.start).then(|| event.clone());
+    };
+    // Skip ahead to about the first occurrence after `from`
+    let mut index = ((from - event.start).num_days() / step_days(rule) - 1).max(0);
+    loop {
+        let start = nth_start(event, rule, index)?;
+        let past_count = rule.count.is_some_and(|count| index >= count as i64);
+        let past_until = rule.until.is_some_and(|until| start > until);
+        if start > to || past_count || past_until {
+            return None;
+        }
+        if start >= from {
+            return Some(CalendarEvent {
+                start,
+                end: event.end.map(|end| start + (end - event.start)),
+                ..event.clone()
+            });
+        }
+        index += 1;
+    }
+}
+
+/// Meetings starting within `lead_minutes` of `now`, or that started moments ago
+pub fn starting_soon(
+    events: &[CalendarEvent],
+    now: DateTime<Utc>,
+    lead_minutes: u32,
+) -> Vec<CalendarEvent> {
+    let from = now - ChronoDuration::minutes(LATE_GRACE_MINUTES);
+    let to = now + ChronoDuration::minutes(lead_minutes as i64);
+    events
+        .iter()
+        .filter_map(|event| occurrence_between(event, from, to))
+        .collect()
+}
+
+/// Download or read the calendar
+fn fetch(app: &AppHandle, source: &str) -> Result<String, String> {
+    let source = source.trim();
+    if let Some(rest) = source.strip_prefix("webcal://") {
+        return fetch(app, &format!("https://{}", rest));
+    }
+    if source.starts_with("https://") || source.starts_with("http://") {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(FETCH_TIMEOUT))
+            .build()
+            .into();
+        return agent
+            .get(source)
+            .call()
+            .map_err(|e| format!("Failed to download calendar: {}", e))?
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| format!("Failed to read calendar: {}", e));
+    }
+    let home = app.path().home_dir().ok();
+    let path = crate::output_file::expand_path(source, Local::now(), home.as_deref());
+    std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read calendar {}: {}", path.display(), e))
+}
+
+/// Offer transcription for a meeting that is about to start
+fn prompt(app: &AppHandle, meeting: &CalendarEvent) {
+    log::info!("Meeting starting: {}", meeting.title);
+    notify(app, NotificationKind::MeetingStarting, &meeting.title);
+    #[cfg(desktop)]
+    crate::tray_menu::show_main_window(app);
+    let _ = app.emit(MEETING_STARTING_EVENT, meeting);
+}
+
+/// Watch the configured calendar for meetings in the background
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || {
+        let mut events: Vec<CalendarEvent> = Vec::new();
+        let mut fetched: Option<(String, Instant)> = None;
+        let mut prompted: HashSet<(String, DateTime<Utc>)> = HashSet::new();
+        loop {
+            let source = crate::settings::read_setting::<Option<String>>(&app, "calendar_ics_url")
+                .filter(|source| !source.trim().is_empty());
+            if let Some(source) = source {
+                let stale = !fetched.as_ref().is_some_and(|(fetched_source, at)| {
+                    *fetched_source == source && at.elapsed() < REFRESH_INTERVAL
+                });
+                if stale {
+                    match fetch(&app, &source) {
+                        Ok(ics) => events = parse_ics(&ics),
+                        Err(e) => log::warn!("{}", e),
+                    }
+                    // Failures wait for the next refresh too, rather than retrying every check
+                    fetched = Some((source, Instant::now()));
+                }
+
+                let lead =
+                    crate::settings::read_setting::<Option<u32>>(&app, "meeting_prompt_minutes")
+                        .unwrap_or(DEFAULT_MEETING_PROMPT_MINUTES);
+                for meeting in starting_soon(&events, Utc::now(), lead) {
+                    if prompted.insert((meeting.uid.clone(), meeting.start)) {
+                        prompt(&app, &meeting);
+                    }
+                }
+            }
+            thread::sleep(CHECK_INTERVAL);
+        }
+    });
+}
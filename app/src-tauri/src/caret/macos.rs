@@ -0,0 +1,261 @@
+//! Caret position via the macOS Accessibility API.
+//!
+//! Asks the focused UI element for the bounds of its selected text range. Requires
+//! the Accessibility permission the app already needs for pasting.
+
+use std::ffi::{c_char, c_void, CString};
+
+type CFTypeRef = *const c_void;
+type CFStringRef = *const c_void;
+type AXUIElementRef = *const c_void;
+
+const AX_ERROR_SUCCESS: i32 = 0;
+const AX_VALUE_TYPE_CG_RECT: u32 = 3;
+const AX_VALUE_TYPE_CF_RANGE: u32 = 4;
+const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+#[repr(C)]
+#[derive(Default)]
+struct CGRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+struct CFRange {
+    location: isize,
+    length: isize,
+}
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXUIElementCreateSystemWide() -> AXUIElementRef;
+    fn AXUIElementCopyAttributeValue(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        value: *mut CFTypeRef,
+    ) -> i32;
+    fn AXUIElementCopyParameterizedAttributeValue(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        parameter: CFTypeRef,
+        value: *mut CFTypeRef,
+    ) -> i32;
+    fn AXValueGetValue(value: CFTypeRef, value_type: u32, out: *mut c_void) -> bool;
+    fn AXUIElementIsAttributeSettable(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        settable: *mut u8,
+    ) -> i32;
+    fn AXUIElementSetAttributeValue(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        value: CFTypeRef,
+    ) -> i32;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFStringCreateWithCString(
+        allocator: CFTypeRef,
+        c_str: *const c_char,
+        encoding: u32,
+    ) -> CFStringRef;
+    fn CFRelease(cf: CFTypeRef);
+    fn CFGetTypeID(cf: CFTypeRef) -> usize;
+    fn CFStringGetTypeID() -> usize;
+    fn CFStringGetLength(string: CFStringRef) -> isize;
+    fn CFStringGetCharacters(string: CFStringRef, range: CFRange, buffer: *mut u16);
+}
+
+/// Owned Core Foundation reference, released on drop
+struct CfOwned(CFTypeRef);
+
+impl CfOwned {
+    fn new(ptr: CFTypeRef) -> Option<Self> {
+        (!ptr.is_null()).then_some(Self(ptr))
+    }
+}
+
+impl Drop for CfOwned {
+    fn drop(&mut self) {
+        // SAFETY: the pointer came from a Create/Copy call and is released exactly once
+        unsafe { CFRelease(self.0) }
+    }
+}
+
+fn cf_string(value: &str) -> Option<CfOwned> {
+    let c_str = CString::new(value).ok()?;
+    // SAFETY: c_str is a valid NUL-terminated UTF-8 string for the duration of the call
+    CfOwned::new(unsafe {
+        CFStringCreateWithCString(std::ptr::null(), c_str.as_ptr(), CF_STRING_ENCODING_UTF8)
+    })
+}
+
+fn copy_attribute(element: CFTypeRef, attribute: &str) -> Option<CfOwned> {
+    let attribute = cf_string(attribute)?;
+    let mut value: CFTypeRef = std::ptr::null();
+    // SAFETY: element is a live AXUIElement and value is a valid out-pointer
+    let result = unsafe { AXUIElementCopyAttributeValue(element, attribute.0, &mut value) };
+    if result != AX_ERROR_SUCCESS {
+        return None;
+    }
+    CfOwned::new(value)
+}
+
+fn focused_element() -> Option<CfOwned> {
+    // SAFETY: returns a new reference that CfOwned releases
+    let system = CfOwned::new(unsafe { AXUIElementCreateSystemWide() })?;
+    copy_attribute(system.0, "AXFocusedUIElement")
+}
+
+pub fn text_field_focused() -> Option<bool> {
+    let focused = focused_element()?;
+    // Only elements holding editable text have a selected text range
+    Some(copy_attribute(focused.0, "AXSelectedTextRange").is_some())
+}
+
+/// Contents of a Core Foundation string, or `None` for any other type
+fn string_value(value: &CfOwned) -> Option<String> {
+    // SAFETY: value is live and only read as a string after its type is checked; the
+    // buffer is sized to the string's length
+    unsafe {
+        if CFGetTypeID(value.0) != CFStringGetTypeID() {
+            return None;
+        }
+        let length = CFStringGetLength(value.0);
+        let mut buffer = vec![0u16; length.max(0) as usize];
+        CFStringGetCharacters(
+            value.0,
+            CFRange {
+                location: 0,
+                length,
+            },
+            buffer.as_mut_ptr(),
+        );
+        Some(String::from_utf16_lossy(&buffer))
+    }
+}
+
+pub fn secure_field_focused() -> Option<bool> {
+    // Secure input mode is system-wide and can be left on by another app (a terminal,
+    // a password manager), so only the focused element itself is asked. An element
+    // whose role can't be read is unknown rather than safe.
+    let focused = focused_element()?;
+    copy_attribute(focused.0, "AXRole").and_then(|role| string_value(&role))?;
+    let subrole = copy_attribute(focused.0, "AXSubrole").and_then(|s| string_value(&s));
+    Some(subrole.as_deref() == Some("AXSecureTextField"))
+}
+
+/// Selected range of a text element, in UTF-16 units
+fn selected_range(element: &CfOwned) -> Option<CFRange> {
+    let range = copy_attribute(element.0, "AXSelectedTextRange")?;
+    let mut value = CFRange::default();
+    // SAFETY: range is an AXValue and value matches the CFRange layout
+    let ok = unsafe {
+        AXValueGetValue(
+            range.0,
+            AX_VALUE_TYPE_CF_RANGE,
+            &mut value as *mut CFRange as *mut c_void,
+        )
+    };
+    ok.then_some(value)
+}
+
+pub fn text_selected() -> Option<bool> {
+    let focused = focused_element()?;
+    selected_range(&focused).map(|range| range.length > 0)
+}
+
+pub fn text_before_caret(max_chars: usize) -> Option<String> {
+    let focused = focused_element()?;
+    let range = selected_range(&focused)?;
+    let value = copy_attribute(focused.0, "AXValue")?;
+
+    // SAFETY: value is live; it is only read as a string after its type is checked and
+    // the buffer is sized to the range being copied
+    unsafe {
+        if CFGetTypeID(value.0) != CFStringGetTypeID() {
+            return None;
+        }
+        // Up to two UTF-16 units per character
+        let end = range.location.clamp(0, CFStringGetLength(value.0));
+        let begin = (end - max_chars as isize * 2).max(0);
+        let mut buffer = vec![0u16; (end - begin) as usize];
+        CFStringGetCharacters(
+            value.0,
+            CFRange {
+                location: begin,
+                length: end - begin,
+            },
+            buffer.as_mut_ptr(),
+        );
+        Some(String::from_utf16_lossy(&buffer))
+    }
+}
+
+pub fn insert_text(text: &str) -> Result<(), String> {
+    let focused = focused_element().ok_or("No focused element")?;
+    let attribute = cf_string("AXSelectedText").ok_or("Failed to create attribute name")?;
+    let value = cf_string(text).ok_or("Text can't be passed to the Accessibility API")?;
+
+    let mut settable = 0u8;
+    // SAFETY: focused and attribute are live and settable is a valid out-pointer
+    let result = unsafe { AXUIElementIsAttributeSettable(focused.0, attribute.0, &mut settable) };
+    if result != AX_ERROR_SUCCESS || settable == 0 {
+        return Err("Focused element doesn't accept text".to_string());
+    }
+
+    // Setting the selected text replaces the selection, or inserts at the cursor
+    // SAFETY: all references are live for the call
+    let result = unsafe { AXUIElementSetAttributeValue(focused.0, attribute.0, value.0) };
+    if result != AX_ERROR_SUCCESS {
+        return Err(format!("Failed to insert text (AXError {})", result));
+    }
+    Ok(())
+}
+
+pub fn caret_position() -> Option<tauri::Position> {
+    // SAFETY: returns a new reference that CfOwned releases
+    let system = CfOwned::new(unsafe { AXUIElementCreateSystemWide() })?;
+    let focused = copy_attribute(system.0, "AXFocusedUIElement")?;
+    let range = copy_attribute(focused.0, "AXSelectedTextRange")?;
+
+    let bounds_attribute = cf_string("AXBoundsForRange")?;
+    let mut bounds: CFTypeRef = std::ptr::null();
+    // SAFETY: all references are live for the call and bounds is a valid out-pointer
+    let result = unsafe {
+        AXUIElementCopyParameterizedAttributeValue(
+            focused.0,
+            bounds_attribute.0,
+            range.0,
+            &mut bounds,
+        )
+    };
+    if result != AX_ERROR_SUCCESS {
+        return None;
+    }
+    let bounds = CfOwned::new(bounds)?;
+
+    let mut rect = CGRect::default();
+    // SAFETY: bounds is an AXValue and rect matches the CGRect layout
+    let ok = unsafe {
+        AXValueGetValue(
+            bounds.0,
+            AX_VALUE_TYPE_CG_RECT,
+            &mut rect as *mut CGRect as *mut c_void,
+        )
+    };
+    if !ok {
+        return None;
+    }
+
+    // Accessibility coordinates are points with a top-left origin, i.e. logical pixels
+    Some(tauri::Position::Logical(tauri::LogicalPosition {
+        x: rect.x,
+        y: rect.y + rect.height,
+    }))
+}
@@ -0,0 +1,119 @@
+//! Text caret position of the focused application.
+//!
+//! Used to show the overlay next to where the user is typing. Uses the
+//! Accessibility API on macOS and the system caret on Windows. Many apps
+//! (browsers, Electron) don't expose their caret this way, and Linux has no
+//! implementation, so callers fall back to the mouse cursor when this returns
+//! `None`.
+//!
+//! The same queries tell whether a text field has focus at all, which a paste
+//! can require before it goes ahead, whether it has text selected, and what
+//! text comes just before the cursor, and whether it is a password field that
+//! dictated text must not go into. Text can also be written into the field
+//! directly, without simulating a paste.
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+/// Screen position of the caret in the focused text field, if it can be determined
+pub fn caret_position() -> Option<tauri::Position> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::caret_position()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::caret_position()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Whether the focused element takes text input, or `None` when it can't be told.
+/// Apps that draw their own caret (browsers, Electron) read as no text field on Windows.
+pub fn text_field_focused() -> Option<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::text_field_focused()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::text_field_focused()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Whether the focused field is a password field, or `None` when it can't be told
+pub fn secure_field_focused() -> Option<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::secure_field_focused()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::secure_field_focused()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Whether the focused text field has a non-empty selection, or `None` when it can't be
+/// told. On Windows only standard edit controls report their selection.
+pub fn text_selected() -> Option<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::text_selected()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::text_selected()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Up to `max_chars` characters before the cursor (or before the selection) in the
+/// focused text field, or `None` when they can't be read. Empty at the start of a field.
+pub fn text_before_caret(max_chars: usize) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    let text = windows::text_before_caret(max_chars);
+    #[cfg(target_os = "macos")]
+    let text = macos::text_before_caret(max_chars);
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let text: Option<String> = None;
+
+    text.map(|text| {
+        let skip = text.chars().count().saturating_sub(max_chars);
+        text.chars().skip(skip).collect()
+    })
+}
+
+/// Write text into the focused text field at the cursor, replacing any selection,
+/// through the Accessibility API (macOS), or the edit control or UI Automation
+/// (Windows, where UI Automation only takes text that replaces the whole field)
+pub fn insert_text(text: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::insert_text(text)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::insert_text(text)
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = text;
+        Err("Accessibility insertion is not supported on this platform".to_string())
+    }
+}
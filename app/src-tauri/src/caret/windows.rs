@@ -0,0 +1,257 @@
+//! Caret position via the Win32 system caret of the foreground thread.
+//!
+//! Text insertion replaces the selection of a standard edit control in place, as
+//! typing would. UI Automation has no way to write at a text range, only to set
+//! the whole value, so other fields take the text that way only when it replaces
+//! everything in them; for text around the cursor the caller pastes instead of
+//! rewriting the field (which would lose its undo history and formatting).
+
+use windows::core::BSTR;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::{LPARAM, POINT, WPARAM};
+use windows::Win32::Graphics::Gdi::ClientToScreen;
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+};
+use windows::Win32::UI::Accessibility::{
+    CUIAutomation, IUIAutomation, IUIAutomationElement, IUIAutomationTextPattern,
+    IUIAutomationValuePattern, TextPatternRangeEndpoint_End, TextPatternRangeEndpoint_Start,
+    UIA_TextPatternId, UIA_ValuePatternId,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetClassNameW, GetForegroundWindow, GetGUIThreadInfo, GetWindowLongW, GetWindowThreadProcessId,
+    SendMessageW, GUITHREADINFO, GWL_STYLE, WM_GETTEXT, WM_GETTEXTLENGTH,
+};
+
+/// `EM_GETSEL`: selection start and end of an edit control, written to the
+/// `DWORD`s its parameters point to
+const EM_GETSEL: u32 = 0x00B0;
+
+/// `EM_REPLACESEL`: replace the selection of an edit control; a nonzero `wParam`
+/// keeps the change undoable
+const EM_REPLACESEL: u32 = 0x00C2;
+
+/// `ES_PASSWORD`: edit control style that masks its text
+const ES_PASSWORD: i32 = 0x0020;
+
+/// `ES_READONLY`: edit control style that refuses changes
+const ES_READONLY: i32 = 0x0800;
+
+/// GUI thread info of the foreground window, if there is one
+fn foreground_thread_info() -> Option<GUITHREADINFO> {
+    // SAFETY: Win32 queries on the foreground window's GUI thread; the out-param is an
+    // initialized local
+    unsafe {
+        let foreground = GetForegroundWindow();
+        if foreground.is_invalid() {
+            return None;
+        }
+        let thread_id = GetWindowThreadProcessId(foreground, None);
+
+        let mut info = GUITHREADINFO {
+            cbSize: std::mem::size_of::<GUITHREADINFO>() as u32,
+            ..Default::default()
+        };
+        GetGUIThreadInfo(thread_id, &mut info).ok()?;
+        Some(info)
+    }
+}
+
+pub fn text_field_focused() -> Option<bool> {
+    foreground_thread_info().map(|info| !info.hwndCaret.is_invalid())
+}
+
+/// The focused control, if it is a standard edit control that answers `EM_GETSEL`
+fn focused_edit_control() -> Option<HWND> {
+    let focus = foreground_thread_info()?.hwndFocus;
+    if focus.is_invalid() {
+        return None;
+    }
+
+    let mut class = [0u16; 64];
+    // SAFETY: focus is a window handle and the class name buffer outlives the call
+    let length = unsafe { GetClassNameW(focus, &mut class) };
+    let class = String::from_utf16_lossy(&class[..length.max(0) as usize]);
+    (class.starts_with("Edit") || class.starts_with("RichEdit")).then_some(focus)
+}
+
+/// Selection start and end of an edit control, in UTF-16 units. Read through the
+/// out-pointers, as the packed result only has 16 bits per position.
+fn edit_selection(control: HWND) -> (usize, usize) {
+    let mut start = 0u32;
+    let mut end = 0u32;
+    // SAFETY: both pointers are to initialized locals that outlive the call; the
+    // system marshals them for controls in other processes
+    unsafe {
+        SendMessageW(
+            control,
+            EM_GETSEL,
+            Some(WPARAM(&mut start as *mut u32 as usize)),
+            Some(LPARAM(&mut end as *mut u32 as isize)),
+        );
+    }
+    (start as usize, end as usize)
+}
+
+pub fn text_selected() -> Option<bool> {
+    let (start, end) = edit_selection(focused_edit_control()?);
+    Some(end > start)
+}
+
+pub fn text_before_caret(max_chars: usize) -> Option<String> {
+    let control = focused_edit_control()?;
+    let (start, _) = edit_selection(control);
+
+    // SAFETY: the buffer is sized from WM_GETTEXTLENGTH plus the terminator and
+    // outlives the WM_GETTEXT call that fills it
+    let (buffer, copied) = unsafe {
+        let length = SendMessageW(control, WM_GETTEXTLENGTH, None, None).0.max(0) as usize;
+        let mut buffer = vec![0u16; length + 1];
+        let copied = SendMessageW(
+            control,
+            WM_GETTEXT,
+            Some(WPARAM(buffer.len())),
+            Some(LPARAM(buffer.as_mut_ptr() as isize)),
+        )
+        .0
+        .max(0) as usize;
+        (buffer, copied)
+    };
+
+    // Up to two UTF-16 units per character
+    let end = start.min(copied);
+    let begin = end.saturating_sub(max_chars * 2);
+    Some(String::from_utf16_lossy(&buffer[begin..end]))
+}
+
+/// Focused element according to UI Automation
+fn focused_automation_element() -> Result<IUIAutomationElement, String> {
+    // SAFETY: COM is initialized on this thread before UI Automation is created
+    unsafe {
+        // Ignore the error if COM is already initialized on this thread
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let automation: IUIAutomation =
+            CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| format!("Failed to start UI Automation: {}", e))?;
+        automation
+            .GetFocusedElement()
+            .map_err(|e| format!("No focused element: {}", e))
+    }
+}
+
+pub fn secure_field_focused() -> Option<bool> {
+    if let Some(control) = focused_edit_control() {
+        // SAFETY: control is a window handle; reading its style has no side effects
+        let style = unsafe { GetWindowLongW(control, GWL_STYLE) };
+        return Some(style & ES_PASSWORD != 0);
+    }
+    let element = focused_automation_element().ok()?;
+    // SAFETY: a property read on a live UI Automation element
+    unsafe { element.CurrentIsPassword() }
+        .ok()
+        .map(|is_password| is_password.as_bool())
+}
+
+pub fn insert_text(text: &str) -> Result<(), String> {
+    match focused_edit_control() {
+        Some(control) => replace_edit_selection(control, text),
+        None => set_whole_value(text),
+    }
+}
+
+/// Replace the selection of an edit control with the text, undoably
+fn replace_edit_selection(control: HWND, text: &str) -> Result<(), String> {
+    // SAFETY: control is a window handle; reading its style has no side effects
+    let style = unsafe { GetWindowLongW(control, GWL_STYLE) };
+    if style & ES_READONLY != 0 {
+        return Err("Focused field is read-only".to_string());
+    }
+    let text: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    // SAFETY: the text is null-terminated and outlives the call, which the system
+    // marshals for controls in other processes
+    unsafe {
+        SendMessageW(
+            control,
+            EM_REPLACESEL,
+            Some(WPARAM(1)),
+            Some(LPARAM(text.as_ptr() as isize)),
+        );
+    }
+    Ok(())
+}
+
+/// Set the value of the focused UI Automation element, when the text replaces all
+/// of it: an empty field, or one whose text is all selected
+fn set_whole_value(text: &str) -> Result<(), String> {
+    let element = focused_automation_element()?;
+    // SAFETY: COM calls on interfaces obtained from UI Automation, used on this thread
+    // only while they are alive
+    unsafe {
+        let value: IUIAutomationValuePattern = element
+            .GetCurrentPatternAs(UIA_ValuePatternId)
+            .map_err(|_| "Focused element has no editable value".to_string())?;
+        if value
+            .CurrentIsReadOnly()
+            .map_err(|e| e.to_string())?
+            .as_bool()
+        {
+            return Err("Focused element is read-only".to_string());
+        }
+        let text_pattern: IUIAutomationTextPattern = element
+            .GetCurrentPatternAs(UIA_TextPatternId)
+            .map_err(|_| "Focused element doesn't expose its selection".to_string())?;
+
+        let selections = text_pattern.GetSelection().map_err(|e| e.to_string())?;
+        if selections.Length().map_err(|e| e.to_string())? < 1 {
+            return Err("Focused element has no cursor".to_string());
+        }
+        let selection = selections.GetElement(0).map_err(|e| e.to_string())?;
+        let document = text_pattern.DocumentRange().map_err(|e| e.to_string())?;
+        let covers_document = selection
+            .CompareEndpoints(
+                TextPatternRangeEndpoint_Start,
+                &document,
+                TextPatternRangeEndpoint_Start,
+            )
+            .map_err(|e| e.to_string())?
+            == 0
+            && selection
+                .CompareEndpoints(
+                    TextPatternRangeEndpoint_End,
+                    &document,
+                    TextPatternRangeEndpoint_End,
+                )
+                .map_err(|e| e.to_string())?
+                == 0;
+        if !covers_document {
+            return Err("UI Automation can't insert text at the cursor".to_string());
+        }
+
+        value
+            .SetValue(&BSTR::from(text))
+            .map_err(|e| format!("Failed to insert text: {}", e))
+    }
+}
+
+pub fn caret_position() -> Option<tauri::Position> {
+    let info = foreground_thread_info()?;
+    if info.hwndCaret.is_invalid() {
+        return None;
+    }
+
+    // SAFETY: hwndCaret is a window handle and point is an initialized local
+    unsafe {
+        // Bottom-left of the caret rectangle, in screen coordinates
+        let mut point = POINT {
+            x: info.rcCaret.left,
+            y: info.rcCaret.bottom,
+        };
+        if !ClientToScreen(info.hwndCaret, &mut point).as_bool() {
+            return None;
+        }
+        Some(tauri::Position::Physical(tauri::PhysicalPosition {
+            x: point.x,
+            y: point.y,
+        }))
+    }
+}
@@ -0,0 +1,376 @@
+//! Command line companion mode, for scripting dictation with the app's configuration.
+//!
+//! - `tambourine --transcribe memo.wav [more.wav ...] [--raw]` transcribes WAV
+//!   recordings through the server with the configured providers, prompt, locale and
+//!   vocabulary, printing each transcript (`--raw` skips the cleanup)
+//! - `tambourine --listen [--paste]` dictates through the running app, using its
+//!   automation API: recording stops on Enter and the transcript is printed. It is
+//!   only copied to the clipboard unless `--paste` is given
+//!
+//! - started by a browser as the native messaging host of the companion extension
+//!   (`chrome-extension://...` or a Firefox host manifest as the first argument), it
+//!   relays messages to the running app (see `browser_extension`)
+//!
+//! Settings are read from the app's settings file, with admin-locked values applied.
+
+use crate::automation_api::DEFAULT_AUTOMATION_API_PORT;
+use crate::browser_extension::{self, DEFAULT_BROWSER_EXTENSION_PORT, NATIVE_HOST_NAME};
+use crate::commands::text::SERVER_URL;
+use crate::settings::{default_policy_path, SettingsManager, SettingsPolicy, SETTINGS_FILE_NAME};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The app's bundle identifier, naming its data directory
+const APP_IDENTIFIER: &str = "com.tambourine-voice.app";
+
+/// Time the server gets to transcribe and clean up one recording
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Time the running app gets to finish a dictation after recording stops
+const DICTATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often the running app is asked whether the dictation has finished
+const DICTATION_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+const USAGE: &str = "Usage:
+  tambourine --transcribe <file.wav>... [--raw]
+      Transcribe recordings with the configured providers and print the text.
+      --raw prints the transcript without the cleanup.
+  tambourine --listen [--paste]
+      Dictate through the running app (with the automation API enabled), stopping
+      on Enter, and print the text. --paste also pastes it like a dictation.";
+
+/// What the command line asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Transcribe { files: Vec<PathBuf>, raw: bool },
+    Listen { paste: bool },
+    NativeMessagingHost,
+    Help,
+}
+
+/// The command in the arguments (without the program name), or none to start the app
+pub fn parse_args(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let Some(first) = args.first() else {
+        return Ok(None);
+    };
+    let rest = &args[1..];
+    match first.as_str() {
+        "--help" | "-h" => Ok(Some(CliCommand::Help)),
+        "--transcribe" => {
+            let mut files = Vec::new();
+            let mut raw = false;
+            for arg in rest {
+                match arg.as_str() {
+                    "--raw" => raw = true,
+                    flag if flag.starts_with("--") => {
+                        return Err(format!("Unknown option for --transcribe: {}", flag))
+                    }
+                    file => files.push(PathBuf::from(file)),
+                }
+            }
+            if files.is_empty() {
+                return Err("--transcribe needs at least one recording".to_string());
+            }
+            Ok(Some(CliCommand::Transcribe { files, raw }))
+        }
+        "--listen" => match rest {
+            [] => Ok(Some(CliCommand::Listen { paste: false })),
+            [flag] if flag == "--paste" => Ok(Some(CliCommand::Listen { paste: true })),
+            _ => Err(format!("Unknown options for --listen: {}", rest.join(" "))),
+        },
+        // Chrome passes the extension's origin, Firefox the path of the host manifest
+        origin if origin.starts_with("chrome-extension://") => {
+            Ok(Some(CliCommand::NativeMessagingHost))
+        }
+        manifest if is_host_manifest(Path::new(manifest)) => {
+            Ok(Some(CliCommand::NativeMessagingHost))
+        }
+        // Anything else is left to the app (e.g. arguments added by the OS)
+        _ => Ok(None),
+    }
+}
+
+/// Whether a path names this app's native messaging host manifest
+fn is_host_manifest(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+        && path
+            .file_stem()
+            .is_some_and(|stem| stem == NATIVE_HOST_NAME)
+}
+
+/// Run a command line command, returning the process exit code
+pub fn run(command: CliCommand) -> i32 {
+    // The native messaging host talks to the browser through its own pipes
+    #[cfg(target_os = "windows")]
+    if command != CliCommand::NativeMessagingHost {
+        attach_console();
+    }
+
+    let result = match command {
+        CliCommand::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        CliCommand::Transcribe { files, raw } => load_settings().and_then(|settings| {
+            let mut failed = false;
+            for file in &files {
+                match transcribe(&settings, file, raw) {
+                    Ok(text) if files.len() == 1 => println!("{}", text),
+                    Ok(text) => println!("{}:\n{}\n", file.display(), text),
+                    Err(e) => {
+                        eprintln!("{}: {}", file.display(), e);
+                        failed = true;
+                    }
+                }
+            }
+            if failed {
+                Err("Some recordings couldn't be transcribed".to_string())
+            } else {
+                Ok(())
+            }
+        }),
+        CliCommand::Listen { paste } => load_settings().and_then(|settings| {
+            let text = listen(&settings, paste)?;
+            println!("{}", text);
+            Ok(())
+        }),
+        CliCommand::NativeMessagingHost => load_settings().and_then(|settings| {
+            if !setting::<bool>(&settings, "browser_extension_enabled") {
+                return Err("The browser extension is off in the app's settings".to_string());
+            }
+            let token: Option<String> = setting(&settings, "automation_api_token");
+            let token = token.ok_or("The automation API has no token yet; start the app first")?;
+            let port = setting::<Option<u16>>(&settings, "browser_extension_port")
+                .unwrap_or(DEFAULT_BROWSER_EXTENSION_PORT);
+            browser_extension::run_host(port, &token)
+        }),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Release builds on Windows have no console of their own, so write to the caller's
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // Fails when there is already a console (debug builds), which is fine
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+/// The app data directory, as Tauri resolves it for this app
+fn app_data_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let base = std::env::var_os("APPDATA").map(PathBuf::from);
+    #[cfg(target_os = "macos")]
+    let base = std::env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join("Library/Application Support"));
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    base.map(|base| base.join(APP_IDENTIFIER))
+}
+
+/// Settings from the settings file in `dir`, with the admin policy's locked values
+pub fn settings_in(dir: &Path, policy: &SettingsPolicy) -> Result<Map<String, Value>, String> {
+    let path = dir.join(SETTINGS_FILE_NAME);
+    let mut settings = match fs::read_to_string(&path) {
+        Ok(content) => SettingsManager::parse_settings(&content)
+            .ok_or_else(|| format!("{} is not a valid settings file", path.display()))?,
+        // Not set up yet, so everything is at its default
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Map::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    settings.extend(policy.locked.clone());
+    Ok(settings)
+}
+
+fn load_settings() -> Result<Map<String, Value>, String> {
+    let dir = app_data_dir().ok_or("Couldn't find the app's data directory")?;
+    settings_in(&dir, &SettingsPolicy::load(&default_policy_path()))
+}
+
+/// A setting's value, or the type's default when missing or invalid
+fn setting<T: serde::de::DeserializeOwned + Default>(
+    settings: &Map<String, Value>,
+    key: &str,
+) -> T {
+    settings
+        .get(key)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Serialize)]
+struct TranscribeRequest {
+    audio: String,
+    stt_provider: Option<String>,
+    cleanup: bool,
+    provider: Option<String>,
+    sections: Option<Value>,
+    locale: Option<Value>,
+    vocabulary: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TranscribeResponse {
+    text: String,
+}
+
+/// Transcribe a WAV recording through the server, cleaned up unless `raw`
+fn transcribe(settings: &Map<String, Value>, file: &Path, raw: bool) -> Result<String, String> {
+    let audio = fs::read(file).map_err(|e| format!("Failed to read recording: {}", e))?;
+    let request = TranscribeRequest {
+        audio: base64::engine::general_purpose::STANDARD.encode(audio),
+        stt_provider: setting(settings, "stt_provider"),
+        cleanup: !raw,
+        provider: setting(settings, "llm_provider"),
+        sections: setting(settings, "cleanup_prompt_sections"),
+        locale: setting(settings, "locale_preferences"),
+        vocabulary: setting(settings, "stt_vocabulary"),
+    };
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(TRANSCRIBE_TIMEOUT))
+        .build()
+        .into();
+    let response: TranscribeResponse = agent
+        .post(format!("{}/api/transcribe", SERVER_URL))
+        .send_json(&request)
+        .map_err(|e| format!("Failed to transcribe (is the server running?): {}", e))?
+        .body_mut()
+        .read_json()
+        .map_err(|e| format!("Failed to read transcript: {}", e))?;
+    Ok(response.text)
+}
+
+/// A client for the running app's automation API
+struct AutomationClient {
+    agent: ureq::Agent,
+    base_url: String,
+    authorization: String,
+}
+
+impl AutomationClient {
+    fn new(settings: &Map<String, Value>) -> Result<Self, String> {
+        if !setting::<bool>(settings, "automation_api_enabled") {
+            return Err(
+                "--listen needs the automation API, which is off in the app's settings".to_string(),
+            );
+        }
+        let token: Option<String> = setting(settings, "automation_api_token");
+        let token = token.ok_or("The automation API has no token yet; start the app first")?;
+        let port = setting::<Option<u16>>(settings, "automation_api_port")
+            .unwrap_or(DEFAULT_AUTOMATION_API_PORT);
+        Ok(Self {
+            agent: ureq::Agent::config_builder()
+                .timeout_global(Some(Duration::from_secs(10)))
+                .http_status_as_error(false)
+                .build()
+                .into(),
+            base_url: format!("http://127.0.0.1:{}", port),
+            authorization: format!("Bearer {}", token),
+        })
+    }
+
+    /// Send a request, returning the status and JSON answer
+    fn request(&self, method: &str, path: &str) -> Result<(u16, Value), String> {
+        let url = format!("{}{}", self.base_url, path);
+        let response = match method {
+            "POST" => self
+                .agent
+                .post(&url)
+                .header("Authorization", &self.authorization)
+                .send_empty(),
+            _ => self
+                .agent
+                .get(&url)
+                .header("Authorization", &self.authorization)
+                .call(),
+        };
+        let mut response =
+            response.map_err(|e| format!("Failed to reach the app (is it running?): {}", e))?;
+        let status = response.status().as_u16();
+        let body: Value = response
+            .body_mut()
+            .read_json()
+            .map_err(|e| format!("Failed to read the app's answer: {}", e))?;
+        Ok((status, body))
+    }
+
+    /// Send a request, returning the JSON answer of a successful one
+    fn call(&self, method: &str, path: &str) -> Result<Value, String> {
+        match self.request(method, path)? {
+            (200, body) => Ok(body),
+            (status, body) => Err(error_message(status, &body)),
+        }
+    }
+
+    /// The latest history entry, if there is one
+    fn last_entry(&self) -> Result<Option<Value>, String> {
+        match self.request("GET", "/transcript/last")? {
+            (200, entry) => Ok(Some(entry)),
+            (404, _) => Ok(None),
+            (status, body) => Err(error_message(status, &body)),
+        }
+    }
+}
+
+/// The error the app answered with
+fn error_message(status: u16, body: &Value) -> String {
+    body["error"]
+        .as_str()
+        .map(String::from)
+        .unwrap_or_else(|| format!("The app answered {}", status))
+}
+
+/// Dictate through the running app and wait for the transcript
+fn listen(settings: &Map<String, Value>, paste: bool) -> Result<String, String> {
+    let client = AutomationClient::new(settings)?;
+    let previous_id = client
+        .last_entry()?
+        .and_then(|entry| entry["id"].as_str().map(String::from));
+
+    let start = if paste {
+        "/recording/start"
+    } else {
+        "/recording/start?paste=false"
+    };
+    if client.call("POST", start)?["recording"] != Value::Bool(true) {
+        return Err("The app didn't start recording".to_string());
+    }
+    eprint!("Listening, press Enter to stop... ");
+    let _ = io::stderr().flush();
+    let mut line = String::new();
+    let _ = io::stdin().lock().read_line(&mut line);
+    client.call("POST", "/recording/stop")?;
+    eprintln!("Transcribing...");
+
+    let deadline = Instant::now() + DICTATION_TIMEOUT;
+    while Instant::now() < deadline {
+        if let Some(entry) = client.last_entry()? {
+            if entry["id"].as_str() != previous_id.as_deref() {
+                let text = entry["revised_text"]
+                    .as_str()
+                    .or(entry["text"].as_str())
+                    .unwrap_or_default();
+                return Ok(text.to_string());
+            }
+        }
+        thread::sleep(DICTATION_POLL_INTERVAL);
+    }
+    Err("The dictation didn't finish in time".to_string())
+}
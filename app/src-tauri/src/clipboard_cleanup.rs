@@ -0,0 +1,86 @@
+//! Cleaning up copied text with a hotkey.
+//!
+//! With `clipboard_cleanup_enabled` on, the clipboard cleanup hotkey takes the
+//! text on the clipboard, runs it through the same LLM cleanup as dictations and
+//! puts the result back on the clipboard, ready to paste over the original. This
+//! makes the app a text fixer for anything that can be copied, not only speech.
+
+use crate::notifications::{notify, snippet, NotificationKind, SNIPPET_MAX_CHARS};
+use crate::state::AppState;
+use arboard::Clipboard;
+use std::sync::atomic::Ordering;
+use std::thread;
+use tauri::{AppHandle, Manager};
+
+/// Longest copied text sent for cleanup, in characters
+pub const MAX_CLIPBOARD_CLEANUP_CHARS: usize = 20_000;
+
+/// The copied text to clean up, refusing text that is empty, too long, or what the
+/// last cleanup already put on the clipboard
+pub fn text_to_clean(copied: Option<String>, last_cleaned: Option<&str>) -> Result<String, String> {
+    let text = copied
+        .filter(|text| !text.trim().is_empty())
+        .ok_or("Copy some text to clean up first")?;
+    if last_cleaned == Some(text.as_str()) {
+        return Err("The clipboard already holds the cleaned up text".to_string());
+    }
+    if text.chars().count() > MAX_CLIPBOARD_CLEANUP_CHARS {
+        return Err(format!(
+            "The copied text is too long to clean up (over {} characters)",
+            MAX_CLIPBOARD_CLEANUP_CHARS
+        ));
+    }
+    Ok(text)
+}
+
+fn clean_clipboard(app: &AppHandle) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    let last_cleaned = state
+        .last_clipboard_cleanup
+        .lock()
+        .ok()
+        .and_then(|last| last.clone());
+    let text = text_to_clean(clipboard.get_text().ok(), last_cleaned.as_deref())?;
+
+    let cleaned = crate::automation_api::clean_up(app, &text)?;
+    clipboard
+        .set_text(cleaned.clone())
+        .map_err(|e| format!("Failed to copy cleaned up text: {}", e))?;
+    if let Ok(mut last) = state.last_clipboard_cleanup.lock() {
+        *last = Some(cleaned.clone());
+    }
+    Ok(cleaned)
+}
+
+/// Clean up the clipboard in the background, unless a cleanup is already running
+pub fn run(app: &AppHandle) {
+    if !crate::settings::read_setting::<bool>(app, "clipboard_cleanup_enabled") {
+        return;
+    }
+    let state = app.state::<AppState>();
+    if state.clipboard_cleanup_running.swap(true, Ordering::SeqCst) {
+        log::info!("ClipboardCleanup: a cleanup is already running");
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || {
+        match clean_clipboard(&app) {
+            Ok(cleaned) => {
+                log::info!("ClipboardCleanup: cleaned {} chars", cleaned.len());
+                notify(
+                    &app,
+                    NotificationKind::ClipboardCleaned,
+                    &snippet(&cleaned, SNIPPET_MAX_CHARS),
+                );
+            }
+            Err(e) => {
+                log::warn!("Failed to clean up clipboard: {}", e);
+                notify(&app, NotificationKind::ProviderError, &e);
+            }
+        }
+        app.state::<AppState>()
+            .clipboard_cleanup_running
+            .store(false, Ordering::SeqCst);
+    });
+}
@@ -0,0 +1,43 @@
+//! Saving and restoring the clipboard around a paste.
+//!
+//! Pasting goes through the clipboard, so whatever the user had copied is
+//! captured first and put back afterwards. Besides plain text this keeps HTML
+//! (rich text copied from browsers and office apps, with its plain-text
+//! fallback) and images. Other formats, such as copied files, can't be read
+//! through `arboard` and are lost.
+
+use arboard::{Clipboard, ImageData};
+
+/// Clipboard contents captured before a paste
+#[derive(Default)]
+pub struct ClipboardSnapshot {
+    text: Option<String>,
+    html: Option<String>,
+    image: Option<ImageData<'static>>,
+}
+
+impl ClipboardSnapshot {
+    /// Read everything that can be restored later
+    pub fn capture(clipboard: &mut Clipboard) -> Self {
+        Self {
+            text: clipboard.get_text().ok(),
+            html: clipboard.get().html().ok(),
+            image: clipboard.get_image().ok().map(ImageData::to_owned_img),
+        }
+    }
+
+    /// Put the captured contents back, richest format first. An empty snapshot clears
+    /// the clipboard so the dictated text doesn't linger there.
+    pub fn restore(self, clipboard: &mut Clipboard) -> Result<(), String> {
+        let result = if let Some(html) = self.html {
+            clipboard.set_html(html, self.text)
+        } else if let Some(image) = self.image {
+            clipboard.set_image(image)
+        } else if let Some(text) = self.text {
+            clipboard.set_text(text)
+        } else {
+            clipboard.clear()
+        };
+        result.map_err(|e| format!("Failed to restore clipboard: {}", e))
+    }
+}
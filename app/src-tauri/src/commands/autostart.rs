@@ -0,0 +1,46 @@
+use crate::autostart::{self, AutostartEntry};
+use crate::commands::settings::{persist_store, settings_store};
+use crate::error::AppError;
+use crate::settings::SettingsManager;
+use tauri::{AppHandle, State};
+
+/// Record the launch-at-login preference in the settings store
+fn store_start_at_login(
+    app: &AppHandle,
+    settings_manager: &SettingsManager,
+    enabled: bool,
+) -> Result<(), AppError> {
+    let store = settings_store(app)?;
+    store.set("start_at_login", enabled);
+    persist_store(app, settings_manager)
+}
+
+/// Register the app to launch at login
+#[tauri::command]
+pub async fn enable_autostart(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let entry = AutostartEntry::for_app(&app)?;
+    autostart::enable(&entry)?;
+    store_start_at_login(&app, &settings_manager, true)
+}
+
+/// Remove the app from launch at login
+#[tauri::command]
+pub async fn disable_autostart(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let entry = AutostartEntry::for_app(&app)?;
+    autostart::disable(&entry)?;
+    store_start_at_login(&app, &settings_manager, false)
+}
+
+/// Check whether the app is registered with the OS to launch at login.
+/// This reflects the OS state, which the user may have changed outside the app.
+#[tauri::command]
+pub async fn is_autostart_enabled(app: AppHandle) -> Result<bool, AppError> {
+    let entry = AutostartEntry::for_app(&app)?;
+    Ok(autostart::is_enabled(&entry))
+}
@@ -0,0 +1,28 @@
+use crate::commands::settings::{persist_store, settings_store};
+use crate::edge_indicator;
+use crate::error::AppError;
+use crate::settings::{EdgeIndicator, SettingsManager};
+use tauri::{AppHandle, State};
+
+/// Choose the screen edge for the progress bar (or turn it off) and persist the choice
+#[tauri::command]
+pub async fn update_edge_indicator(
+    app: AppHandle,
+    edge: EdgeIndicator,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let store = settings_store(&app)?;
+    store.set(
+        "edge_indicator",
+        serde_json::to_value(edge).map_err(|e| e.to_string())?,
+    );
+    persist_store(&app, &settings_manager)?;
+    edge_indicator::apply_edge_indicator(&app).map_err(AppError::Failed)
+}
+
+/// Move the progress bar to the configured edge of the current target monitor.
+/// Called after the overlay monitor setting or the display layout changes.
+#[tauri::command]
+pub async fn reposition_edge_indicator(app: AppHandle) -> Result<(), AppError> {
+    edge_indicator::apply_edge_indicator(&app).map_err(AppError::Failed)
+}
@@ -0,0 +1,109 @@
+use crate::error::AppError;
+use crate::failed_dictations::{
+    FailedDictation, FailedDictationAudio, FailedDictations, FailureDetails,
+};
+use crate::history_crypto;
+use crate::recording_recovery::RecordingRecovery;
+use crate::settings::read_setting;
+use tauri::{AppHandle, State};
+
+/// Keep a dictation whose pipeline run failed, with its recording, to retry later
+#[tauri::command]
+pub async fn record_failed_dictation(
+    app: AppHandle,
+    error: String,
+    raw_text: Option<String>,
+    bytes: Option<Vec<u8>>,
+    mime_type: Option<String>,
+    failed: State<'_, FailedDictations>,
+) -> Result<FailedDictation, AppError> {
+    if history_crypto::is_enabled(&app) {
+        return Err(AppError::Failed(
+            "Failed dictations aren't kept while history is encrypted".to_string(),
+        ));
+    }
+    let details = FailureDetails {
+        error,
+        stt_provider: read_setting(&app, "stt_provider"),
+        llm_provider: read_setting(&app, "llm_provider"),
+        raw_text: raw_text.filter(|text| !text.trim().is_empty()),
+    };
+    let audio = bytes.as_deref().zip(mime_type.as_deref());
+    let dictation = failed.record(details, audio)?;
+    log::warn!(
+        "Dictation failed ({}); kept to retry as {}",
+        dictation.error,
+        dictation.id
+    );
+    Ok(dictation)
+}
+
+/// Failed dictations waiting to be retried or discarded, newest first
+#[tauri::command]
+pub async fn list_failed_dictations(
+    failed: State<'_, FailedDictations>,
+) -> Result<Vec<FailedDictation>, AppError> {
+    failed.list().map_err(AppError::Storage)
+}
+
+/// The recording of a failed dictation, to replay through the pipeline
+#[tauri::command]
+pub async fn get_failed_dictation_audio(
+    id: String,
+    failed: State<'_, FailedDictations>,
+) -> Result<Option<FailedDictationAudio>, AppError> {
+    failed.audio(&id).map_err(AppError::Storage)
+}
+
+/// Start writing the recording in progress to disk, to recover it after a crash
+#[tauri::command]
+pub async fn begin_recording_recovery(
+    app: AppHandle,
+    mime_type: String,
+    recovery: State<'_, RecordingRecovery>,
+) -> Result<(), AppError> {
+    if history_crypto::is_enabled(&app) {
+        return Ok(());
+    }
+    recovery.begin(&mime_type).map_err(AppError::Storage)
+}
+
+/// Add the next chunk of the recording in progress
+#[tauri::command]
+pub async fn append_recording_recovery(
+    app: AppHandle,
+    bytes: Vec<u8>,
+    recovery: State<'_, RecordingRecovery>,
+) -> Result<(), AppError> {
+    if history_crypto::is_enabled(&app) {
+        return Ok(());
+    }
+    recovery.append(&bytes)
+}
+
+/// Drop the recording in progress from disk once recording ended
+#[tauri::command]
+pub async fn finish_recording_recovery(
+    recovery: State<'_, RecordingRecovery>,
+) -> Result<(), AppError> {
+    recovery.finish().map_err(AppError::Storage)
+}
+
+/// Note that retrying a failed dictation failed again
+#[tauri::command]
+pub async fn note_failed_dictation_retry(
+    id: String,
+    error: String,
+    failed: State<'_, FailedDictations>,
+) -> Result<bool, AppError> {
+    failed.retry_failed(&id, &error).map_err(AppError::Storage)
+}
+
+/// Drop a failed dictation and its recording
+#[tauri::command]
+pub async fn discard_failed_dictation(
+    id: String,
+    failed: State<'_, FailedDictations>,
+) -> Result<bool, AppError> {
+    failed.discard(&id).map_err(AppError::Storage)
+}
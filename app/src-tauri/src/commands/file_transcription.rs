@@ -0,0 +1,48 @@
+use crate::error::AppError;
+use crate::file_transcription::{self, FileJob, FileTranscriptionQueue, FILE_TRANSCRIPTION_EVENT};
+use tauri::{AppHandle, Emitter, State};
+
+/// Queue audio or video files to be transcribed, cleaned up unless `cleanup` is false
+#[tauri::command]
+pub async fn transcribe_files(
+    app: AppHandle,
+    paths: Vec<String>,
+    cleanup: Option<bool>,
+) -> Result<Vec<FileJob>, AppError> {
+    Ok(file_transcription::queue_files(
+        &app,
+        paths,
+        cleanup.unwrap_or(true),
+    )?)
+}
+
+/// Queued, running and finished file transcriptions, in the order they were added
+#[tauri::command]
+pub async fn list_file_transcriptions(
+    queue: State<'_, FileTranscriptionQueue>,
+) -> Result<Vec<FileJob>, AppError> {
+    Ok(queue.jobs())
+}
+
+/// Cancel a file transcription that hasn't started yet
+#[tauri::command]
+pub async fn cancel_file_transcription(
+    app: AppHandle,
+    id: String,
+    queue: State<'_, FileTranscriptionQueue>,
+) -> Result<bool, AppError> {
+    let Some(job) = queue.cancel(&id)? else {
+        return Ok(false);
+    };
+    let _ = app.emit(FILE_TRANSCRIPTION_EVENT, &job);
+    Ok(true)
+}
+
+/// Forget finished file transcriptions
+#[tauri::command]
+pub async fn clear_finished_file_transcriptions(
+    queue: State<'_, FileTranscriptionQueue>,
+) -> Result<(), AppError> {
+    queue.clear_finished();
+    Ok(())
+}
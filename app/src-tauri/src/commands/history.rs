@@ -1,35 +1,550 @@
-use crate::history::{HistoryEntry, HistoryStorage};
-use tauri::State;
+use crate::commands::settings::{persist_store, settings_store};
+use crate::error::AppError;
+use crate::failed_dictations::FailedDictations;
+use crate::history::{
+    self, AppCount, EntryDetails, HistoryEntry, HistoryLimits, HistoryRange, HistoryRevision,
+    HistoryStorage, Session, TagCount, TrashedEntry,
+};
+use crate::history_audio::{self, HistoryAudio};
+use crate::history_crypto::{self, HistoryEncryption, HistoryKey};
+use crate::history_duplicates::{self, CollapsedEntry, DEFAULT_DUPLICATE_THRESHOLD};
+use crate::history_export::{self, ExportFormat};
+use crate::history_sync::{self, SyncSummary};
+use crate::metrics::MetricEvent;
+use crate::quota::QuotaTracker;
+use crate::recording_recovery::RecordingRecovery;
+use crate::settings::SettingsManager;
+use tauri::{AppHandle, Emitter, Manager, State};
 
-/// Add a new entry to the dictation history
+/// Add a new entry to the dictation history. Providers, recording length and the
+/// target app are filled in from the app's own state.
 #[tauri::command]
 pub async fn add_history_entry(
+    app: AppHandle,
     text: String,
+    raw_text: Option<String>,
     history: State<'_, HistoryStorage>,
-) -> Result<HistoryEntry, String> {
-    history.add_entry(text)
+) -> Result<HistoryEntry, AppError> {
+    let words = text.split_whitespace().count();
+    let details = EntryDetails {
+        raw_text,
+        stt_provider: crate::settings::read_setting(&app, "stt_provider"),
+        llm_provider: crate::settings::read_setting(&app, "llm_provider"),
+        duration_seconds: app
+            .try_state::<QuotaTracker>()
+            .and_then(|tracker| tracker.last_recording_seconds()),
+        target_app: crate::paste_target::remembered(&app).and_then(|target| target.app),
+        latency_seconds: app
+            .try_state::<QuotaTracker>()
+            .and_then(|tracker| tracker.seconds_since_recording_stopped()),
+    };
+    let entry = history
+        .add_entry(text, details)
+        .map_err(history::command_error)?;
+    let _ = app.emit(history::HISTORY_ENTRY_ADDED_EVENT, &entry);
+    crate::webhooks::notify_all(&app, &entry);
+    history::apply_retention(&app, &history);
+    crate::record_metric(&app, MetricEvent::DictationCompleted { words });
+    Ok(entry)
 }
 
-/// Get dictation history entries
+/// Collapse repeated dictations as the `history_duplicate_threshold` setting says
+fn collapse_duplicates(app: &AppHandle, entries: Vec<HistoryEntry>) -> Vec<CollapsedEntry> {
+    let threshold = crate::settings::read_setting_or(
+        app,
+        "history_duplicate_threshold",
+        DEFAULT_DUPLICATE_THRESHOLD,
+    );
+    history_duplicates::collapse(entries, threshold)
+}
+
+/// Get dictation history entries, with near-duplicates listed under the newest
 #[tauri::command]
 pub async fn get_history(
+    app: AppHandle,
+    limit: Option<usize>,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<CollapsedEntry>, AppError> {
+    Ok(collapse_duplicates(
+        &app,
+        history.get_all(limit).map_err(history::command_error)?,
+    ))
+}
+
+/// Search dictation history for entries containing the query, with near-duplicates
+/// listed under the newest
+#[tauri::command]
+pub async fn search_history(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<CollapsedEntry>, AppError> {
+    Ok(collapse_duplicates(
+        &app,
+        history
+            .search(&query, limit)
+            .map_err(history::command_error)?,
+    ))
+}
+
+/// Replace a history entry's tags, returning them as stored (lowercase, without
+/// `#`), or `None` when there is no such entry
+#[tauri::command]
+pub async fn set_history_entry_tags(
+    app: AppHandle,
+    id: String,
+    tags: Vec<String>,
+    history: State<'_, HistoryStorage>,
+) -> Result<Option<Vec<String>>, AppError> {
+    let stored = history
+        .set_tags(&id, &tags)
+        .map_err(history::command_error)?;
+    history::emit_entry_updated(&app, &history, &id);
+    Ok(stored)
+}
+
+/// Set or clear the note on a history entry
+#[tauri::command]
+pub async fn set_history_entry_note(
+    app: AppHandle,
+    id: String,
+    note: Option<String>,
+    history: State<'_, HistoryStorage>,
+) -> Result<bool, AppError> {
+    let updated = history
+        .set_note(&id, note.as_deref())
+        .map_err(history::command_error)?;
+    history::emit_entry_updated(&app, &history, &id);
+    Ok(updated)
+}
+
+/// Every tag in use, most used first
+#[tauri::command]
+pub async fn list_history_tags(
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<TagCount>, AppError> {
+    history.list_tags().map_err(history::command_error)
+}
+
+/// History entries with a tag, with near-duplicates listed under the newest
+#[tauri::command]
+pub async fn get_history_by_tag(
+    app: AppHandle,
+    tag: String,
+    limit: Option<usize>,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<CollapsedEntry>, AppError> {
+    Ok(collapse_duplicates(
+        &app,
+        history
+            .get_by_tag(&tag, limit)
+            .map_err(history::command_error)?,
+    ))
+}
+
+/// Apps dictated into during the range, most used first
+#[tauri::command]
+pub async fn list_history_apps(
+    range: HistoryRange,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<AppCount>, AppError> {
+    history.list_apps(&range).map_err(history::command_error)
+}
+
+/// History entries dictated into an app during the range, with near-duplicates
+/// listed under the newest
+#[tauri::command]
+pub async fn get_history_by_app(
+    app: AppHandle,
+    target_app: String,
+    range: HistoryRange,
     limit: Option<usize>,
     history: State<'_, HistoryStorage>,
-) -> Result<Vec<HistoryEntry>, String> {
-    history.get_all(limit)
+) -> Result<Vec<CollapsedEntry>, AppError> {
+    Ok(collapse_duplicates(
+        &app,
+        history
+            .get_by_app(&target_app, &range, limit)
+            .map_err(history::command_error)?,
+    ))
 }
 
-/// Delete a history entry by ID
+/// Get the history entries pinned as favorites
+#[tauri::command]
+pub async fn get_pinned_history(
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    history.get_pinned().map_err(history::command_error)
+}
+
+/// Pin or unpin a history entry, keeping it past the retention period while pinned
+#[tauri::command]
+pub async fn set_history_entry_pinned(
+    app: AppHandle,
+    id: String,
+    pinned: bool,
+    history: State<'_, HistoryStorage>,
+) -> Result<bool, AppError> {
+    let updated = history
+        .set_pinned(&id, pinned)
+        .map_err(history::command_error)?;
+    history::emit_entry_updated(&app, &history, &id);
+    Ok(updated)
+}
+
+/// Store the result of re-cleaning an entry's transcript as a new revision
+#[tauri::command]
+pub async fn add_history_revision(
+    app: AppHandle,
+    entry_id: String,
+    text: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<HistoryRevision, AppError> {
+    let llm_provider = crate::settings::read_setting(&app, "llm_provider");
+    let revision = history
+        .add_revision(&entry_id, text, llm_provider)
+        .map_err(history::command_error)?;
+    history::emit_entry_updated(&app, &history, &entry_id);
+    Ok(revision)
+}
+
+/// Get the revisions of a history entry, newest first
+#[tauri::command]
+pub async fn get_history_revisions(
+    entry_id: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<HistoryRevision>, AppError> {
+    history
+        .get_revisions(&entry_id)
+        .map_err(history::command_error)
+}
+
+/// Move a history entry to the trash
 #[tauri::command]
 pub async fn delete_history_entry(
+    app: AppHandle,
+    id: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<bool, AppError> {
+    let deleted = history.delete(&id).map_err(history::command_error)?;
+    if deleted {
+        let _ = app.emit(history::HISTORY_ENTRY_DELETED_EVENT, &id);
+    }
+    history::empty_expired_trash(&app, &history);
+    history_audio::sweep(&app, &history);
+    Ok(deleted)
+}
+
+/// Move all history entries to the trash
+#[tauri::command]
+pub async fn clear_history(
+    app: AppHandle,
+    history: State<'_, HistoryStorage>,
+) -> Result<(), AppError> {
+    history.clear().map_err(history::command_error)?;
+    let _ = app.emit(history::HISTORY_CHANGED_EVENT, ());
+    history::empty_expired_trash(&app, &history);
+    history_audio::sweep(&app, &history);
+    Ok(())
+}
+
+/// Entries in the trash, most recently deleted first
+#[tauri::command]
+pub async fn list_trashed_history(
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<TrashedEntry>, AppError> {
+    history.trashed().map_err(history::command_error)
+}
+
+/// Bring a history entry back from the trash
+#[tauri::command]
+pub async fn restore_history_entry(
+    app: AppHandle,
     id: String,
     history: State<'_, HistoryStorage>,
-) -> Result<bool, String> {
-    history.delete(&id)
+) -> Result<bool, AppError> {
+    let restored = history.restore(&id).map_err(history::command_error)?;
+    if restored {
+        if let Some(entry) = history.get(&id).map_err(history::command_error)? {
+            let _ = app.emit(history::HISTORY_ENTRY_ADDED_EVENT, entry);
+        }
+    }
+    Ok(restored)
 }
 
-/// Clear all history entries
+/// Remove a history entry in the trash for good
 #[tauri::command]
-pub async fn clear_history(history: State<'_, HistoryStorage>) -> Result<(), String> {
-    history.clear()
+pub async fn purge_history_entry(
+    app: AppHandle,
+    id: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<bool, AppError> {
+    let purged = history.purge_entry(&id).map_err(history::command_error)?;
+    history_audio::sweep(&app, &history);
+    Ok(purged)
+}
+
+/// Remove everything in the trash for good
+#[tauri::command]
+pub async fn empty_history_trash(
+    app: AppHandle,
+    history: State<'_, HistoryStorage>,
+) -> Result<usize, AppError> {
+    let purged = history.empty_trash().map_err(history::command_error)?;
+    history_audio::sweep(&app, &history);
+    Ok(purged)
+}
+
+/// Keep the recording of a dictation with its history entry, when
+/// `history_keep_audio` is on. Returns whether it was saved.
+#[tauri::command]
+pub async fn save_history_audio(
+    app: AppHandle,
+    id: String,
+    bytes: Vec<u8>,
+    mime_type: String,
+    waveform: Vec<f32>,
+    history: State<'_, HistoryStorage>,
+) -> Result<bool, AppError> {
+    if !crate::settings::read_setting::<bool>(&app, "history_keep_audio")
+        || history_crypto::is_enabled(&app)
+    {
+        return Ok(false);
+    }
+    let audio_dir = history_audio::audio_dir(&app).map_err(history::command_error)?;
+    let saved = history_audio::save(&audio_dir, &history, &id, &bytes, &mime_type, &waveform)
+        .map_err(history::command_error)?;
+    if saved {
+        history::emit_entry_updated(&app, &history, &id);
+    }
+    Ok(saved)
+}
+
+/// The kept recording of a history entry, with a waveform preview
+#[tauri::command]
+pub async fn get_history_audio(
+    app: AppHandle,
+    id: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<Option<HistoryAudio>, AppError> {
+    let audio_dir = history_audio::audio_dir(&app).map_err(history::command_error)?;
+    history_audio::load(&audio_dir, &history, &id).map_err(history::command_error)
+}
+
+/// Write history entries in the range to `path`, returning how many were exported
+#[tauri::command]
+pub async fn export_history(
+    format: ExportFormat,
+    range: HistoryRange,
+    path: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<usize, AppError> {
+    let entries = history.get_range(&range).map_err(history::command_error)?;
+    let content = history_export::render(&entries, format, &chrono::Local)?;
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::Storage(format!("Failed to write {}: {}", path, e)))?;
+    Ok(entries.len())
+}
+
+/// Start a named session; dictations are grouped into it until it is ended
+#[tauri::command]
+pub async fn start_session(
+    name: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<Session, AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::InvalidInput("Session name is empty".to_string()));
+    }
+    history.start_session(&name).map_err(history::command_error)
+}
+
+/// Begin transcribing a meeting: a session named after it, the live transcript window
+/// and a recording
+#[tauri::command]
+pub async fn start_meeting(
+    app: AppHandle,
+    title: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<Session, AppError> {
+    let title = Some(title.trim())
+        .filter(|title| !title.is_empty())
+        .unwrap_or("Meeting");
+    let session = history
+        .start_session(title)
+        .map_err(history::command_error)?;
+    crate::live_transcript::open_transcript_window(&app)?;
+    #[cfg(desktop)]
+    crate::set_recording(&app, true, "Meeting");
+    Ok(session)
+}
+
+/// End the open session, if there is one
+#[tauri::command]
+pub async fn end_session(history: State<'_, HistoryStorage>) -> Result<Option<Session>, AppError> {
+    history.end_session().map_err(history::command_error)
+}
+
+/// Get the open session, if there is one
+#[tauri::command]
+pub async fn get_active_session(
+    history: State<'_, HistoryStorage>,
+) -> Result<Option<Session>, AppError> {
+    history.active_session().map_err(history::command_error)
+}
+
+/// Get all sessions, newest first
+#[tauri::command]
+pub async fn list_sessions(history: State<'_, HistoryStorage>) -> Result<Vec<Session>, AppError> {
+    history.list_sessions().map_err(history::command_error)
+}
+
+/// Write a session's dictations to `path` as one transcript with timestamps
+#[tauri::command]
+pub async fn export_session(
+    id: String,
+    path: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<(), AppError> {
+    let session = history
+        .get_session(&id)
+        .map_err(history::command_error)?
+        .ok_or_else(|| AppError::NotFound(format!("Session {} not found", id)))?;
+    let entries = history
+        .session_entries(&id)
+        .map_err(history::command_error)?;
+    let content = history_export::render_session(&session, &entries, &chrono::Local);
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::Storage(format!("Failed to write {}: {}", path, e)))
+}
+
+/// Whether history is encrypted and waiting for its passphrase
+#[tauri::command]
+pub async fn is_history_locked(history: State<'_, HistoryStorage>) -> Result<bool, AppError> {
+    Ok(history.is_locked())
+}
+
+/// Open passphrase-protected history for this session
+#[tauri::command]
+pub async fn unlock_history(
+    app: AppHandle,
+    passphrase: String,
+    history: State<'_, HistoryStorage>,
+) -> Result<(), AppError> {
+    history
+        .unlock(&HistoryKey::Passphrase(passphrase))
+        .map_err(history::command_error)?;
+    history::apply_retention(&app, &history);
+    let _ = app.emit(history::HISTORY_CHANGED_EVENT, ());
+    Ok(())
+}
+
+/// Encrypt history with a keychain key or a passphrase, or decrypt it, and save the
+/// choice
+#[tauri::command]
+pub async fn set_history_encryption(
+    app: AppHandle,
+    mode: HistoryEncryption,
+    passphrase: Option<String>,
+    history: State<'_, HistoryStorage>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let key = match mode {
+        HistoryEncryption::Off => None,
+        HistoryEncryption::Keychain => {
+            Some(history_crypto::keychain_key().map_err(AppError::PermissionDenied)?)
+        }
+        HistoryEncryption::Passphrase => Some(HistoryKey::Passphrase(
+            passphrase
+                .filter(|passphrase| !passphrase.is_empty())
+                .ok_or_else(|| AppError::InvalidInput("A passphrase is required".to_string()))?,
+        )),
+    };
+
+    // The choice is saved before the database is rekeyed, and put back if rekeying
+    // fails, so the setting never names a key the database isn't using
+    let store = settings_store(&app)?;
+    let previous = store.get("history_encryption");
+    store.set(
+        "history_encryption",
+        serde_json::to_value(mode).map_err(|e| e.to_string())?,
+    );
+    persist_store(&app, &settings_manager)?;
+
+    if let Err(e) = history.set_key(key.as_ref()) {
+        match previous {
+            Some(previous) => store.set("history_encryption", previous),
+            None => {
+                store.delete("history_encryption");
+            }
+        }
+        if let Err(e) = persist_store(&app, &settings_manager) {
+            log::error!("Failed to restore the history encryption setting: {}", e);
+        }
+        return Err(history::command_error(e));
+    }
+    // Only once nothing is encrypted with it any more
+    if mode != HistoryEncryption::Keychain {
+        history_crypto::delete_keychain_key();
+    }
+    if mode != HistoryEncryption::Off {
+        remove_unencrypted_copies(&app, &history);
+    }
+    Ok(())
+}
+
+/// Remove the recordings, failed dictations and synced history kept outside the
+/// database, which can't be encrypted along with it
+fn remove_unencrypted_copies(app: &AppHandle, history: &HistoryStorage) {
+    if let Err(e) = history.clear_audio() {
+        log::warn!("{}", e);
+    }
+    history_audio::sweep(app, history);
+    history_sync::remove_own_file(app);
+    if let Some(failed) = app.try_state::<FailedDictations>() {
+        match failed.clear() {
+            Ok(0) => {}
+            Ok(count) => {
+                log::info!("Removed {} failed dictations kept unencrypted", count);
+                let _ = app.emit("failed-dictations-changed", ());
+            }
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+    if let Some(recovery) = app.try_state::<RecordingRecovery>() {
+        if let Err(e) = recovery.finish() {
+            log::warn!("{}", e);
+        }
+    }
+    let _ = app.emit(history::HISTORY_CHANGED_EVENT, ());
+}
+
+/// Save the history limits and prune to them right away
+#[tauri::command]
+pub async fn update_history_limits(
+    app: AppHandle,
+    limits: HistoryLimits,
+    history: State<'_, HistoryStorage>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let store = settings_store(&app)?;
+    for (key, value) in [
+        ("history_retention_days", limits.max_days),
+        ("history_max_entries", limits.max_entries),
+        ("history_max_disk_mb", limits.max_disk_mb),
+    ] {
+        store.set(key, serde_json::to_value(value).map_err(|e| e.to_string())?);
+    }
+    persist_store(&app, &settings_manager)?;
+    history::apply_retention(&app, &history);
+    Ok(())
+}
+
+/// Merge history from the sync folder and publish this device's, right away
+#[tauri::command]
+pub async fn sync_history(
+    app: AppHandle,
+    history: State<'_, HistoryStorage>,
+) -> Result<SyncSummary, AppError> {
+    history_sync::sync(&app, &history).map_err(history::command_error)
 }
@@ -0,0 +1,44 @@
+use crate::commands::settings::{persist_store, settings_store};
+use crate::error::AppError;
+use crate::logging::{self, LogLevel, LOG_FILE_NAME, MAX_ROTATED_LOG_FILES};
+use crate::settings::SettingsManager;
+use tauri::{AppHandle, Manager, State};
+
+/// Number of lines returned by `get_recent_logs` when none is given
+const DEFAULT_RECENT_LOG_LINES: usize = 200;
+
+/// Get the most recent lines of the app log, e.g. to attach to a bug report
+#[tauri::command]
+pub async fn get_recent_logs(
+    app: AppHandle,
+    lines: Option<usize>,
+) -> Result<Vec<String>, AppError> {
+    let log_dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| AppError::Storage(format!("Failed to get log directory: {}", e)))?;
+    Ok(logging::recent_lines(
+        &log_dir.join(LOG_FILE_NAME),
+        MAX_ROTATED_LOG_FILES,
+        lines.unwrap_or(DEFAULT_RECENT_LOG_LINES),
+    ))
+}
+
+/// Change the log verbosity and persist it
+#[tauri::command]
+pub async fn update_log_level(
+    app: AppHandle,
+    level: LogLevel,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let store = settings_store(&app)?;
+    store.set(
+        "log_level",
+        serde_json::to_value(level).map_err(|e| e.to_string())?,
+    );
+    persist_store(&app, &settings_manager)?;
+
+    logging::set_level(level);
+    log::info!("Log level set to {:?}", level);
+    Ok(())
+}
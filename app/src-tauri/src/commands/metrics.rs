@@ -0,0 +1,36 @@
+use crate::commands::settings::{persist_store, settings_store};
+use crate::error::AppError;
+use crate::metrics::{AnalyticsMode, LocalMetrics, MetricsRecorder};
+use crate::settings::SettingsManager;
+use tauri::{AppHandle, State};
+
+/// Get usage metrics recorded on this machine
+#[tauri::command]
+pub async fn get_local_metrics(
+    metrics: State<'_, MetricsRecorder>,
+) -> Result<LocalMetrics, AppError> {
+    metrics.local_metrics().map_err(AppError::Storage)
+}
+
+/// Delete all locally recorded usage metrics
+#[tauri::command]
+pub async fn clear_local_metrics(metrics: State<'_, MetricsRecorder>) -> Result<(), AppError> {
+    metrics.clear().map_err(AppError::Storage)
+}
+
+/// Change the analytics mode and persist it
+#[tauri::command]
+pub async fn update_analytics_mode(
+    app: AppHandle,
+    mode: AnalyticsMode,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let store = settings_store(&app)?;
+    store.set(
+        "analytics_mode",
+        serde_json::to_value(mode).map_err(|e| e.to_string())?,
+    );
+    persist_store(&app, &settings_manager)?;
+    log::info!("Analytics mode set to {:?}", mode);
+    Ok(())
+}
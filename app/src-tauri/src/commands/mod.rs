@@ -1,4 +1,16 @@
+pub mod autostart;
+pub mod edge_indicator;
+pub mod failed_dictations;
+pub mod file_transcription;
 pub mod history;
+pub mod logging;
+pub mod metrics;
+pub mod onboarding;
 pub mod overlay;
+pub mod quota;
 pub mod settings;
+pub mod snippets;
+pub mod stats;
 pub mod text;
+pub mod transcript;
+pub mod tray;
@@ -0,0 +1,43 @@
+use crate::commands::settings::{persist_store, settings_store};
+use crate::error::AppError;
+use crate::onboarding::{
+    OnboardingProgress, OnboardingState, OnboardingStep, ONBOARDING_STORE_KEY,
+};
+use crate::settings::SettingsManager;
+use tauri::{AppHandle, State};
+
+/// Read onboarding progress from the settings store
+fn load_progress(app: &AppHandle) -> Result<OnboardingProgress, AppError> {
+    let store = settings_store(app)?;
+    Ok(store
+        .get(ONBOARDING_STORE_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Get the current onboarding state
+#[tauri::command]
+pub async fn get_onboarding_state(app: AppHandle) -> Result<OnboardingState, AppError> {
+    Ok(load_progress(&app)?.state())
+}
+
+/// Complete an onboarding step and persist the progress
+#[tauri::command]
+pub async fn complete_onboarding_step(
+    app: AppHandle,
+    step: OnboardingStep,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<OnboardingState, AppError> {
+    let mut progress = load_progress(&app)?;
+    progress.complete(step).map_err(AppError::InvalidInput)?;
+
+    let store = settings_store(&app)?;
+    store.set(
+        ONBOARDING_STORE_KEY,
+        serde_json::to_value(&progress).map_err(|e| e.to_string())?,
+    );
+    persist_store(&app, &settings_manager)?;
+
+    log::info!("Onboarding step completed: {:?}", step);
+    Ok(progress.state())
+}
@@ -1,39 +1,191 @@
-use tauri::{AppHandle, Manager};
-
-#[tauri::command]
-pub async fn resize_overlay(app: AppHandle, width: f64, height: f64) -> Result<(), String> {
-    // Enforce minimum dimensions to prevent invisible window
-    let min_size = 48.0;
-    let width = width.max(min_size);
-    let height = height.max(min_size);
-
-    if let Some(window) = app.get_webview_window("overlay") {
-        // Get current center point from current position and size
-        // This allows the overlay to be dragged and maintain its new position
-        let center = if let (Ok(pos), Ok(size)) = (window.outer_position(), window.outer_size()) {
-            let scale = window.scale_factor().unwrap_or(1.0);
-            let x = pos.x as f64 / scale;
-            let y = pos.y as f64 / scale;
-            let w = size.width as f64 / scale;
-            let h = size.height as f64 / scale;
-            Some((x + w / 2.0, y + h / 2.0))
-        } else {
-            None
-        };
+use crate::commands::settings::{persist_store, settings_store};
+use crate::edge_indicator::EDGE_INDICATOR_LABEL;
+use crate::error::AppError;
+use crate::overlay::{self, OverlayPlacement, OVERLAY_LABEL};
+use crate::overlay_auto_hide::OverlayAutoHide;
+use crate::overlay_state::{set_overlay_state, OverlayState};
+use crate::recording_state::RecordingState;
+use crate::settings::{OverlayAppearance, OverlayLayout, SettingsManager};
+use crate::state::AppState;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[tauri::command]
+pub async fn resize_overlay(app: AppHandle, width: f64, height: f64) -> Result<(), AppError> {
+    // Enforce the layout's minimum dimensions to prevent an invisible window
+    let layout: OverlayLayout = crate::settings::read_setting(&app, "overlay_layout");
+    let (min_width, min_height) = layout.min_size();
+    let width = width.max(min_width);
+    let height = height.max(min_height);
+
+    if let Some(window) = app.get_webview_window(OVERLAY_LABEL) {
+        // Keep the anchored point fixed (where the overlay was placed or dragged to),
+        // falling back to the center of its current geometry
+        let placement = app.state::<OverlayPlacement>();
+        let anchor = placement
+            .anchor()
+            .or_else(|| overlay::current_anchor(&window, (0.5, 0.5)));
 
         // Set the new size
         window
             .set_size(tauri::Size::Logical(tauri::LogicalSize { width, height }))
             .map_err(|e| e.to_string())?;
 
-        // Reposition to keep center fixed
-        if let Some((cx, cy)) = center {
-            let x = cx - width / 2.0;
-            let y = cy - height / 2.0;
-            window
-                .set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }))
-                .map_err(|e| e.to_string())?;
+        // Reposition to keep the anchor fixed
+        if let Some(anchor) = anchor {
+            overlay::move_to_anchor(&window, anchor, width, height)?;
+        }
+    }
+    Ok(())
+}
+
+/// Scale factor of the monitor the overlay is on
+#[tauri::command]
+pub async fn get_overlay_scale(app: AppHandle) -> Result<f64, AppError> {
+    let window = app
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or_else(|| AppError::NotFound("Overlay window not found".to_string()))?;
+    window
+        .scale_factor()
+        .map_err(|e| AppError::Failed(e.to_string()))
+}
+
+/// Names of the connected monitors
+#[tauri::command]
+pub async fn get_monitors(app: AppHandle) -> Result<Vec<String>, AppError> {
+    Ok(overlay::monitor_names(&app))
+}
+
+/// Move the overlay to its configured position.
+/// Called after the overlay monitor or anchor setting changes.
+#[tauri::command]
+pub async fn reposition_overlay(app: AppHandle) -> Result<(), AppError> {
+    overlay::position_overlay(&app, true).map_err(AppError::Failed)
+}
+
+/// Place the overlay again for the current displays and bring it back on screen if it
+/// drifted off (e.g. after a resolution change)
+#[tauri::command]
+pub async fn recalibrate_overlay(app: AppHandle) -> Result<(), AppError> {
+    overlay::recalibrate_overlay(&app).map_err(AppError::Failed)
+}
+
+/// Forget dragged overlay positions and move the overlay back to its default spot
+#[tauri::command]
+pub async fn reset_overlay_position(app: AppHandle) -> Result<(), AppError> {
+    overlay::reset_overlay_position(&app).map_err(AppError::Failed)
+}
+
+/// Make the overlay ignore mouse clicks (or accept them again) and persist the choice
+#[tauri::command]
+pub async fn update_overlay_click_through(
+    app: AppHandle,
+    enabled: bool,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let store = settings_store(&app)?;
+    store.set("overlay_click_through", serde_json::Value::Bool(enabled));
+    persist_store(&app, &settings_manager)?;
+
+    if let Some(window) = app.get_webview_window(OVERLAY_LABEL) {
+        overlay::apply_overlay_input(&window, enabled)?;
+    }
+    log::info!("Overlay click-through set to {}", enabled);
+    Ok(())
+}
+
+/// Hide the overlay and edge indicator from screen capture (or show them again) and
+/// persist the choice
+#[tauri::command]
+pub async fn update_overlay_capture_exclusion(
+    app: AppHandle,
+    enabled: bool,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let store = settings_store(&app)?;
+    store.set(
+        "exclude_overlay_from_capture",
+        serde_json::Value::Bool(enabled),
+    );
+    persist_store(&app, &settings_manager)?;
+
+    for label in [OVERLAY_LABEL, EDGE_INDICATOR_LABEL] {
+        if let Some(window) = app.get_webview_window(label) {
+            overlay::apply_capture_exclusion(&window, enabled)?;
         }
     }
+    log::info!("Overlay capture exclusion set to {}", enabled);
+    Ok(())
+}
+
+/// Report a pipeline step seen by the overlay webview (clicks on the overlay, server
+/// progress messages and connection errors) so it joins the `overlay-state` stream
+#[tauri::command]
+pub async fn report_overlay_state(app: AppHandle, state: OverlayState) -> Result<(), AppError> {
+    set_overlay_state(&app, state);
+    Ok(())
+}
+
+/// Where the app is in a dictation; changes are sent as `app-state-changed`
+#[tauri::command]
+pub async fn get_app_state(app: AppHandle) -> Result<RecordingState, AppError> {
+    Ok(app.state::<AppState>().lifecycle.current())
+}
+
+/// Event sent to all windows when the overlay appearance changes
+const OVERLAY_APPEARANCE_EVENT: &str = "overlay-appearance-changed";
+
+/// The saved overlay appearance
+#[tauri::command]
+pub async fn get_overlay_appearance(app: AppHandle) -> Result<OverlayAppearance, AppError> {
+    Ok(crate::settings::read_setting(&app, "overlay_appearance"))
+}
+
+/// Validate and persist a new overlay appearance, then push it to every window
+#[tauri::command]
+pub async fn update_overlay_appearance(
+    app: AppHandle,
+    appearance: OverlayAppearance,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    appearance.validate().map_err(AppError::InvalidInput)?;
+    let store = settings_store(&app)?;
+    store.set(
+        "overlay_appearance",
+        serde_json::to_value(&appearance).map_err(|e| e.to_string())?,
+    );
+    persist_store(&app, &settings_manager)?;
+
+    app.emit(OVERLAY_APPEARANCE_EVENT, appearance)
+        .map_err(|e| AppError::Failed(e.to_string()))
+}
+
+/// Event sent to all windows when the overlay layout changes
+const OVERLAY_LAYOUT_EVENT: &str = "overlay-layout-changed";
+
+/// Persist the overlay layout and switch the overlay to it; the overlay then
+/// resizes itself to its new content
+#[tauri::command]
+pub async fn update_overlay_layout(
+    app: AppHandle,
+    layout: OverlayLayout,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let store = settings_store(&app)?;
+    store.set(
+        "overlay_layout",
+        serde_json::to_value(layout).map_err(|e| e.to_string())?,
+    );
+    persist_store(&app, &settings_manager)?;
+
+    app.emit(OVERLAY_LAYOUT_EVENT, layout)
+        .map_err(|e| AppError::Failed(e.to_string()))
+}
+
+/// Pause (or resume) the auto-hide countdown while the pointer is over the overlay
+#[tauri::command]
+pub async fn set_overlay_hovered(app: AppHandle, hovered: bool) -> Result<(), AppError> {
+    if let Some(auto_hide) = app.try_state::<OverlayAutoHide>() {
+        auto_hide.set_hovered(hovered);
+    }
     Ok(())
 }
@@ -0,0 +1,14 @@
+use crate::error::AppError;
+use crate::quota::{QuotaLimits, QuotaStatus, QuotaTracker};
+use tauri::{AppHandle, State};
+
+/// Get today's dictation usage and how it compares to the configured limits
+#[tauri::command]
+pub async fn get_quota_status(
+    app: AppHandle,
+    tracker: State<'_, QuotaTracker>,
+) -> Result<QuotaStatus, AppError> {
+    tracker
+        .status(QuotaLimits::from_store(&app))
+        .map_err(AppError::Storage)
+}
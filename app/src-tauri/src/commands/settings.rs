@@ -1,17 +1,27 @@
-use crate::settings::HotkeyConfig;
-use tauri::AppHandle;
+use crate::error::AppError;
+use crate::history::{EntryDetails, HistoryEntry};
+use crate::importers::{self, ImportSource, ImportSummary};
+use crate::output_sinks::{Dictation, ScriptSink};
+use crate::remote_control::{RemoteControl, RemotePairingInfo};
+use crate::settings::{
+    read_setting_or, AppSettings, HotkeyConfig, SettingDescriptor, SettingsManager, SettingsPolicy,
+    SettingsScope, SETTINGS_FILE_NAME,
+};
+use crate::settings_window::{self, SettingsSection};
+use crate::state::AppState;
+use crate::webhooks::Webhook;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, Wry};
+use tauri_plugin_store::{Store, StoreExt};
 
 #[cfg(desktop)]
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 
-#[cfg(desktop)]
-use tauri_plugin_store::StoreExt;
-
 /// Temporarily unregister all global shortcuts.
 /// Call this before capturing a new hotkey to prevent the shortcuts from intercepting key presses.
 #[cfg(desktop)]
 #[tauri::command]
-pub async fn unregister_shortcuts(app: AppHandle) -> Result<(), String> {
+pub async fn unregister_shortcuts(app: AppHandle) -> Result<(), AppError> {
     log::info!("Temporarily unregistering all shortcuts for hotkey capture");
     let shortcut_manager = app.global_shortcut();
     shortcut_manager
@@ -23,52 +33,56 @@ pub async fn unregister_shortcuts(app: AppHandle) -> Result<(), String> {
 // Stub for non-desktop platforms
 #[cfg(not(desktop))]
 #[tauri::command]
-pub async fn unregister_shortcuts(_app: AppHandle) -> Result<(), String> {
+pub async fn unregister_shortcuts(_app: AppHandle) -> Result<(), AppError> {
     Ok(())
 }
 
-/// Helper to read a setting from the store with a default fallback
-#[cfg(desktop)]
-fn get_setting_from_store<T: serde::de::DeserializeOwned>(
-    app: &AppHandle,
-    key: &str,
-    default: T,
-) -> T {
-    app.store("settings.json")
-        .ok()
-        .and_then(|store| store.get(key))
-        .and_then(|v| serde_json::from_value(v).ok())
-        .unwrap_or(default)
-}
-
 /// Re-register global shortcuts with the current settings from the store.
 /// Called from frontend after hotkey settings are changed.
 /// Falls back to defaults if stored values are invalid.
 #[cfg(desktop)]
 #[tauri::command]
-pub async fn register_shortcuts(app: AppHandle) -> Result<(), String> {
+pub async fn register_shortcuts(app: AppHandle) -> Result<(), AppError> {
     // Read hotkeys from store with defaults
     let toggle_hotkey: HotkeyConfig =
-        get_setting_from_store(&app, "toggle_hotkey", HotkeyConfig::default_toggle());
+        read_setting_or(&app, "toggle_hotkey", HotkeyConfig::default_toggle());
     let hold_hotkey: HotkeyConfig =
-        get_setting_from_store(&app, "hold_hotkey", HotkeyConfig::default_hold());
-    let paste_last_hotkey: HotkeyConfig = get_setting_from_store(
+        read_setting_or(&app, "hold_hotkey", HotkeyConfig::default_hold());
+    let paste_last_hotkey: HotkeyConfig = read_setting_or(
         &app,
         "paste_last_hotkey",
         HotkeyConfig::default_paste_last(),
     );
+    let undo_paste_hotkey: HotkeyConfig = read_setting_or(
+        &app,
+        "undo_paste_hotkey",
+        HotkeyConfig::default_undo_paste(),
+    );
+    let clipboard_cleanup_hotkey: HotkeyConfig = read_setting_or(
+        &app,
+        "clipboard_cleanup_hotkey",
+        HotkeyConfig::default_clipboard_cleanup(),
+    );
+    let cancel_hotkey: HotkeyConfig =
+        read_setting_or(&app, "cancel_hotkey", HotkeyConfig::default_cancel());
+    let clipboard_cleanup_enabled: bool = read_setting_or(&app, "clipboard_cleanup_enabled", false);
 
     // Convert to shortcuts with validation (fall back to defaults if invalid)
     let toggle_shortcut = toggle_hotkey.to_shortcut_or_default(HotkeyConfig::default_toggle);
     let hold_shortcut = hold_hotkey.to_shortcut_or_default(HotkeyConfig::default_hold);
     let paste_last_shortcut =
         paste_last_hotkey.to_shortcut_or_default(HotkeyConfig::default_paste_last);
+    let undo_paste_shortcut =
+        undo_paste_hotkey.to_shortcut_or_default(HotkeyConfig::default_undo_paste);
+    let cancel_shortcut = cancel_hotkey.to_shortcut_or_default(HotkeyConfig::default_cancel);
 
     log::info!(
-        "Re-registering shortcuts - Toggle: {}, Hold: {}, PasteLast: {}",
+        "Re-registering shortcuts - Toggle: {}, Hold: {}, PasteLast: {}, UndoPaste: {}, Cancel: {}",
         toggle_hotkey.to_shortcut_string(),
         hold_hotkey.to_shortcut_string(),
-        paste_last_hotkey.to_shortcut_string()
+        paste_last_hotkey.to_shortcut_string(),
+        undo_paste_hotkey.to_shortcut_string(),
+        cancel_hotkey.to_shortcut_string()
     );
 
     // Get the global shortcut manager
@@ -80,7 +94,24 @@ pub async fn register_shortcuts(app: AppHandle) -> Result<(), String> {
         .map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
 
     // Collect shortcuts to register
-    let shortcuts: Vec<Shortcut> = vec![toggle_shortcut, hold_shortcut, paste_last_shortcut];
+    let mut shortcuts: Vec<Shortcut> = vec![
+        toggle_shortcut,
+        hold_shortcut,
+        paste_last_shortcut,
+        undo_paste_shortcut,
+        cancel_shortcut,
+    ];
+    // Only taken while the mode is on, so the key combination stays free otherwise
+    if clipboard_cleanup_enabled {
+        log::info!(
+            "Re-registering clipboard cleanup shortcut: {}",
+            clipboard_cleanup_hotkey.to_shortcut_string()
+        );
+        shortcuts.push(
+            clipboard_cleanup_hotkey
+                .to_shortcut_or_default(HotkeyConfig::default_clipboard_cleanup),
+        );
+    }
 
     // Register new shortcuts with handler
     shortcut_manager
@@ -96,6 +127,272 @@ pub async fn register_shortcuts(app: AppHandle) -> Result<(), String> {
 // Stub for non-desktop platforms
 #[cfg(not(desktop))]
 #[tauri::command]
-pub async fn register_shortcuts(_app: AppHandle) -> Result<(), String> {
+pub async fn register_shortcuts(_app: AppHandle) -> Result<(), AppError> {
+    Ok(())
+}
+
+/// Force locked settings in the store back to their policy values.
+/// Returns the keys that had to be reverted.
+pub(crate) fn apply_locked_settings<R: Runtime>(
+    store: &Store<R>,
+    policy: &SettingsPolicy,
+) -> Vec<String> {
+    let entries: serde_json::Map<String, serde_json::Value> = store.entries().into_iter().collect();
+    let violations = policy.violations(&entries);
+    for key in &violations {
+        store.set(key.clone(), policy.locked[key].clone());
+    }
+    violations
+}
+
+/// Open the settings store to change it
+pub(crate) fn settings_store(app: &AppHandle) -> Result<Arc<Store<Wry>>, AppError> {
+    app.store(SETTINGS_FILE_NAME)
+        .map_err(|e| AppError::Storage(format!("Failed to open settings store: {}", e)))
+}
+
+/// Write the current contents of the settings store to disk via the SettingsManager.
+/// Changes to settings locked by the admin policy are reverted and rejected.
+pub(crate) fn persist_store(
+    app: &AppHandle,
+    settings_manager: &SettingsManager,
+) -> Result<(), AppError> {
+    let store = settings_store(app)?;
+
+    let reverted = apply_locked_settings(&store, settings_manager.policy());
+    if !reverted.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "Cannot change settings locked by your administrator: {}",
+            reverted.join(", ")
+        )));
+    }
+
+    let entries: serde_json::Map<String, serde_json::Value> = store.entries().into_iter().collect();
+    settings_manager
+        .save_entries(&entries)
+        .map_err(AppError::Storage)?;
+
+    let pending = pending_restart_changes(app, &entries);
+    if !pending.is_empty() {
+        log::info!("Restart required to apply: {}", pending.join(", "));
+    }
+    let _ = app.emit("pending-restart-changed", pending);
+    crate::tray_menu::refresh(app);
     Ok(())
 }
+
+/// Restart-required settings changed since launch, compared against `entries`
+fn pending_restart_changes(
+    app: &AppHandle,
+    entries: &serde_json::Map<String, serde_json::Value>,
+) -> Vec<String> {
+    let state = app.state::<AppState>();
+    let baseline = match state.restart_baseline.lock() {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            log::warn!("Failed to read restart baseline: {}", e);
+            return Vec::new();
+        }
+    };
+    AppSettings::pending_restart_changes(&baseline, entries)
+}
+
+/// Persist the settings store to disk atomically, keeping rolling backups.
+/// Called from the frontend instead of the store's own save.
+#[tauri::command]
+pub async fn save_settings(
+    app: AppHandle,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    persist_store(&app, &settings_manager)
+}
+
+/// Restore defaults for one section of the settings (or all of them).
+/// Hotkey shortcuts must be re-registered by the caller afterwards.
+#[tauri::command]
+pub async fn reset_settings(
+    app: AppHandle,
+    scope: SettingsScope,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let store = settings_store(&app)?;
+
+    for (key, value) in scope.default_entries() {
+        store.set(key, value);
+    }
+    // Locked settings keep their policy values rather than the defaults
+    apply_locked_settings(&store, settings_manager.policy());
+    log::info!("Reset settings to defaults: {:?}", scope);
+
+    persist_store(&app, &settings_manager)
+}
+
+/// List settings keys locked by the admin policy, so the UI can disable their controls
+#[tauri::command]
+pub async fn get_locked_settings(
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<Vec<String>, AppError> {
+    Ok(settings_manager.policy().locked.keys().cloned().collect())
+}
+
+/// Describe every setting (type, default, description, restart and lock flags)
+#[tauri::command]
+pub async fn describe_settings(
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<Vec<SettingDescriptor>, AppError> {
+    let policy = settings_manager.policy();
+    Ok(AppSettings::describe()
+        .into_iter()
+        .map(|mut descriptor| {
+            descriptor.locked = policy.locked.contains_key(descriptor.key);
+            descriptor
+        })
+        .collect())
+}
+
+/// List settings changed since launch that only take effect after a restart
+#[tauri::command]
+pub async fn get_pending_restart_changes(app: AppHandle) -> Result<Vec<String>, AppError> {
+    let store = settings_store(&app)?;
+    let entries: serde_json::Map<String, serde_json::Value> = store.entries().into_iter().collect();
+    Ok(pending_restart_changes(&app, &entries))
+}
+
+/// The backup corrupted settings were restored from at launch, reported only once
+#[tauri::command]
+pub fn take_settings_recovery(app: AppHandle) -> Option<String> {
+    app.state::<AppState>()
+        .settings_recovered_from
+        .lock()
+        .ok()
+        .and_then(|mut recovered| recovered.take())
+}
+
+/// Relaunch the app once the dictation in flight is finished or kept to retry
+#[tauri::command]
+pub fn restart_app(app: AppHandle) {
+    crate::shutdown::restart(&app);
+}
+
+/// Show and focus the settings window, scrolled to `section` when given
+#[tauri::command]
+pub async fn open_settings(
+    app: AppHandle,
+    section: Option<SettingsSection>,
+) -> Result<(), AppError> {
+    settings_window::open_settings(&app, section).map_err(AppError::Failed)
+}
+
+/// Replace the automation API token, returning the new one
+#[tauri::command]
+pub async fn regenerate_automation_api_token(app: AppHandle) -> Result<String, AppError> {
+    crate::automation_api::regenerate_token(&app).map_err(AppError::Failed)
+}
+
+/// Post a sample dictation to a webhook, returning the HTTP status it answered with
+#[tauri::command]
+pub async fn test_webhook(webhook: Webhook) -> Result<u16, AppError> {
+    let entry = HistoryEntry::new(
+        "This is a test from Tambourine.".to_string(),
+        EntryDetails::default(),
+    );
+    crate::webhooks::send(&webhook, &entry).map_err(AppError::Failed)
+}
+
+/// Run an output sink command with a sample dictation, waiting for it to finish
+#[tauri::command]
+pub async fn test_output_sink(sink: ScriptSink) -> Result<(), AppError> {
+    let dictation = Dictation {
+        text: "This is a test from Tambourine.".to_string(),
+        ..Default::default()
+    };
+    sink.run(&dictation).map_err(AppError::Failed)
+}
+
+/// Register the app as the native messaging host of the browser extensions in
+/// `browser_extension_ids`, returning where it was registered
+#[tauri::command]
+pub async fn install_browser_extension_host(app: AppHandle) -> Result<Vec<String>, AppError> {
+    crate::browser_extension::install_host(&app).map_err(AppError::Failed)
+}
+
+/// Show a sample caption in OBS with the saved OBS captions settings
+#[tauri::command]
+pub async fn send_test_obs_caption(app: AppHandle) -> Result<(), AppError> {
+    crate::obs_captions::send_test_caption(&crate::obs_captions::ObsConfig::from_store(&app))
+        .map_err(AppError::Failed)
+}
+
+/// Addresses and pairing code for the phone remote, if it is running
+#[tauri::command]
+pub async fn get_remote_control_pairing(app: AppHandle) -> Result<RemotePairingInfo, AppError> {
+    app.try_state::<RemoteControl>()
+        .map(|remote| remote.info())
+        .ok_or_else(|| AppError::NotFound("The phone remote isn't running".to_string()))
+}
+
+/// Replace the phone remote's pairing code, returning the new addresses and code
+#[tauri::command]
+pub async fn regenerate_remote_pairing_code(app: AppHandle) -> Result<RemotePairingInfo, AppError> {
+    let remote = app
+        .try_state::<RemoteControl>()
+        .ok_or_else(|| AppError::NotFound("The phone remote isn't running".to_string()))?;
+    remote.pairing.regenerate()?;
+    Ok(remote.info())
+}
+
+/// Import vocabulary and settings from a file exported by another dictation tool,
+/// detecting the tool from the file when `source` isn't given
+#[tauri::command]
+pub async fn import_from_tool(
+    app: AppHandle,
+    file_name: String,
+    content: String,
+    source: Option<ImportSource>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<ImportSummary, AppError> {
+    let source = source
+        .or_else(|| importers::detect_source(&file_name, &content))
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!("Unrecognized vocabulary file {}", file_name))
+        })?;
+    let imported = importers::parse(source, &content)?;
+    if imported.vocabulary.is_empty() && imported.instructions.is_none() {
+        return Err(AppError::InvalidInput(format!(
+            "Nothing to import from {}",
+            file_name
+        )));
+    }
+
+    let store = settings_store(&app)?;
+    let existing: Vec<String> = store
+        .get("stt_vocabulary")
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    let (vocabulary, added) = importers::merge_terms(&existing, &imported.vocabulary);
+    store.set("stt_vocabulary", vocabulary);
+
+    let sections = imported.instructions.as_deref().and_then(|instructions| {
+        importers::with_instructions(store.get("cleanup_prompt_sections").as_ref(), instructions)
+    });
+    let instructions_imported = sections.is_some();
+    if let Some(sections) = sections {
+        store.set("cleanup_prompt_sections", sections);
+    }
+    persist_store(&app, &settings_manager)?;
+    log::info!(
+        "Imported {} vocabulary terms from {:?} file {}",
+        added,
+        source,
+        file_name
+    );
+    app.emit("settings-changed", ())
+        .map_err(|e| e.to_string())?;
+
+    Ok(ImportSummary {
+        source,
+        added,
+        duplicates: imported.vocabulary.len() - added,
+        instructions_imported,
+    })
+}
@@ -0,0 +1,71 @@
+use crate::error::AppError;
+use crate::snippets::{Snippet, SnippetLibrary};
+use crate::tray_menu;
+use tauri::{AppHandle, State};
+
+/// List snippets, sorted by name
+#[tauri::command]
+pub async fn list_snippets(library: State<'_, SnippetLibrary>) -> Result<Vec<Snippet>, AppError> {
+    library.list()
+}
+
+/// Save text (usually a cleaned transcript from history) as a named snippet
+#[tauri::command]
+pub async fn add_snippet(
+    app: AppHandle,
+    name: String,
+    text: String,
+    source_entry_id: Option<String>,
+    library: State<'_, SnippetLibrary>,
+) -> Result<Snippet, AppError> {
+    let snippet = library.add(&name, &text, source_entry_id)?;
+    tray_menu::refresh(&app);
+    Ok(snippet)
+}
+
+/// Rename a snippet or change its text
+#[tauri::command]
+pub async fn update_snippet(
+    app: AppHandle,
+    id: String,
+    name: String,
+    text: String,
+    library: State<'_, SnippetLibrary>,
+) -> Result<bool, AppError> {
+    let updated = library.edit(&id, &name, &text)?;
+    tray_menu::refresh(&app);
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn delete_snippet(
+    app: AppHandle,
+    id: String,
+    library: State<'_, SnippetLibrary>,
+) -> Result<bool, AppError> {
+    let deleted = library.delete(&id)?;
+    tray_menu::refresh(&app);
+    Ok(deleted)
+}
+
+/// Paste a snippet into the app that was focused last
+#[tauri::command]
+pub async fn paste_snippet(
+    app: AppHandle,
+    id: String,
+    library: State<'_, SnippetLibrary>,
+) -> Result<(), AppError> {
+    let snippet = library
+        .get(&id)?
+        .ok_or_else(|| AppError::NotFound("No such snippet".to_string()))?;
+    crate::commands::text::paste_picked(app, snippet.text).await
+}
+
+/// The snippet a dictation such as "insert my email signature" asks for, if any
+#[tauri::command]
+pub async fn resolve_snippet_command(
+    text: String,
+    library: State<'_, SnippetLibrary>,
+) -> Result<Option<Snippet>, AppError> {
+    library.match_voice_command(&text)
+}
@@ -0,0 +1,30 @@
+use crate::error::AppError;
+use crate::history::{self, HistoryRange, HistoryStorage};
+use crate::stats::{self, UsageStats};
+use crate::vocabulary::{self, VocabularyInsights};
+use tauri::{AppHandle, State};
+
+/// Get usage statistics for dictations in the range
+#[tauri::command]
+pub async fn get_stats(
+    app: AppHandle,
+    range: HistoryRange,
+    history: State<'_, HistoryStorage>,
+) -> Result<UsageStats, AppError> {
+    let entries = history.get_range(&range).map_err(history::command_error)?;
+    let words_per_minute = crate::settings::read_setting::<Option<u32>>(&app, "typing_speed_wpm")
+        .unwrap_or(stats::DEFAULT_TYPING_SPEED_WPM);
+    Ok(stats::compute(&entries, words_per_minute, &chrono::Local))
+}
+
+/// Suggest vocabulary terms from dictations in the range
+#[tauri::command]
+pub async fn get_vocabulary_insights(
+    app: AppHandle,
+    range: HistoryRange,
+    history: State<'_, HistoryStorage>,
+) -> Result<VocabularyInsights, AppError> {
+    let entries = history.get_range(&range).map_err(history::command_error)?;
+    let vocabulary = crate::settings::read_setting::<Vec<String>>(&app, "stt_vocabulary");
+    Ok(vocabulary::insights(&entries, &vocabulary))
+}
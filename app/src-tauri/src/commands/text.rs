@@ -1,9 +1,32 @@
+use crate::browser_extension;
+use crate::clipboard_snapshot::ClipboardSnapshot;
+use crate::commands::settings::{persist_store, settings_store};
+use crate::error::AppError;
+use crate::in_app_insert::{self, InAppField};
+use crate::input_backend::{self, KeyboardLayout};
+use crate::notifications::{self, NotificationKind, SNIPPET_MAX_CHARS};
+use crate::output_sinks::{self, Dictation};
+use crate::overlay_state::{set_overlay_state, OverlayState};
+use crate::paste_ring;
+use crate::paste_target::{self, PasteTarget};
+use crate::paste_undo;
+use crate::pipeline::{self, CancellationToken, DictationPipeline};
+use crate::review;
+use crate::rich_text;
+use crate::settings::{
+    find_app_profile, read_setting_or, AppOutputProfile, InsertMode, PasteFormat, PasteKeystroke,
+    PasteMethod, SettingsManager,
+};
+use crate::smart_insert;
+use crate::state::AppState;
 use arboard::Clipboard;
-use enigo::{Direction, Enigo, Key, Keyboard, Settings};
-use std::sync::mpsc;
+use enigo::{Button, Direction, Key, Keyboard, Mouse};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::Ordering;
 use std::thread;
-use std::time::Duration;
-use tauri::AppHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 /// Delay after clipboard operations to ensure system stability
 const CLIPBOARD_STABILIZATION_DELAY_MS: u64 = 50;
@@ -14,65 +37,880 @@ const KEY_EVENT_DELAY_MS: u64 = 50;
 /// Delay before restoring previous clipboard content
 const CLIPBOARD_RESTORE_DELAY_MS: u64 = 100;
 
-const SERVER_URL: &str = "http://127.0.0.1:8765";
+/// Characters before the cursor compared to tell whether a paste arrived
+const PASTE_CHECK_CHARS: usize = 32;
+
+/// How often the field is read while waiting for a paste to show up
+const PASTE_CHECK_INTERVAL_MS: u64 = 100;
+
+/// How long a paste may take to show up before it counts as missed. Apps that
+/// update the field late (web apps, editors with their own input handling) need
+/// well over a moment, and a paste wrongly counted as missed is pasted twice.
+const PASTE_CHECK_TIMEOUT_MS: u64 = 1500;
+
+/// Why text couldn't be delivered
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeliveryError {
+    /// The paste didn't arrive, even after refocusing and retrying
+    Missed,
+    /// The paste may or may not have arrived, as the field stopped being readable
+    /// while waiting for it
+    Unconfirmed,
+    /// A password field is focused, so the text was kept out of it
+    SecureField,
+    /// Sending the text failed
+    Failed(String),
+}
+
+impl DeliveryError {
+    /// Whether the text is held for the user to paste later rather than dropped
+    pub fn holds_text(&self) -> bool {
+        !matches!(self, Self::Failed(_))
+    }
+}
+
+impl fmt::Display for DeliveryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missed => write!(f, "The paste didn't reach the target app"),
+            Self::Unconfirmed => write!(f, "The paste may not have reached the target app"),
+            Self::SecureField => {
+                write!(f, "A password field is focused, so the text wasn't pasted")
+            }
+            Self::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for DeliveryError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+impl From<DeliveryError> for String {
+    fn from(error: DeliveryError) -> Self {
+        error.to_string()
+    }
+}
+
+pub(crate) const SERVER_URL: &str = "http://127.0.0.1:8765";
 
 #[tauri::command]
 pub async fn get_server_url() -> String {
     SERVER_URL.to_string()
 }
 
+/// Options controlling how text is delivered, read from settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PasteOptions {
+    pub method: PasteMethod,
+    /// Restore the previous clipboard text after pasting
+    pub restore_clipboard: bool,
+    /// Insert after a selection or replace it
+    pub insert_mode: InsertMode,
+    /// Fit spacing and capitalization to the text before the cursor
+    pub smart_insertion: bool,
+    /// Plain text or Markdown rendered as rich text, for clipboard methods
+    pub format: PasteFormat,
+    /// Keystroke that makes the target app paste
+    pub keystroke: PasteKeystroke,
+    /// Pace of simulated typing; `None` types as fast as possible
+    pub typing_chars_per_second: Option<u32>,
+    /// Random variation of the typing pace, in percent
+    pub typing_jitter_percent: u32,
+    /// Refuse to deliver text into password fields
+    pub refuse_secure_fields: bool,
+}
+
+impl Default for PasteOptions {
+    fn default() -> Self {
+        Self {
+            method: PasteMethod::default(),
+            restore_clipboard: true,
+            insert_mode: InsertMode::default(),
+            smart_insertion: false,
+            format: PasteFormat::default(),
+            keystroke: PasteKeystroke::default(),
+            typing_chars_per_second: None,
+            typing_jitter_percent: 0,
+            refuse_secure_fields: true,
+        }
+    }
+}
+
+impl PasteOptions {
+    /// Apply the overrides of a target app's output profile
+    pub fn with_profile(self, profile: &AppOutputProfile) -> Self {
+        Self {
+            method: profile.paste_method.unwrap_or(self.method),
+            format: profile.paste_format.unwrap_or(self.format),
+            keystroke: profile.paste_keystroke.unwrap_or(self.keystroke),
+            ..self
+        }
+    }
+
+    /// Read paste options from the settings store, with the overrides for the app that
+    /// currently has focus
+    #[cfg(desktop)]
+    pub fn from_store(app: &AppHandle) -> Self {
+        let defaults = Self::default();
+        let options = Self {
+            method: read_setting_or(app, "paste_method", defaults.method),
+            restore_clipboard: read_setting_or(
+                app,
+                "restore_clipboard",
+                defaults.restore_clipboard,
+            ),
+            insert_mode: read_setting_or(app, "insert_mode", defaults.insert_mode),
+            smart_insertion: read_setting_or(app, "smart_insertion", defaults.smart_insertion),
+            format: read_setting_or(app, "paste_format", defaults.format),
+            // Only set per app, in output profiles
+            keystroke: defaults.keystroke,
+            typing_chars_per_second: read_setting_or(
+                app,
+                "typing_chars_per_second",
+                defaults.typing_chars_per_second,
+            ),
+            typing_jitter_percent: read_setting_or(
+                app,
+                "typing_jitter_percent",
+                defaults.typing_jitter_percent,
+            ),
+            refuse_secure_fields: read_setting_or(
+                app,
+                "refuse_secure_fields",
+                defaults.refuse_secure_fields,
+            ),
+        };
+
+        let options = match foreground_profile(app) {
+            Some(profile) => options.with_profile(&profile),
+            None => options,
+        };
+        // Copy-only mode wins over per-app methods
+        let copy_only_dictation = app
+            .try_state::<AppState>()
+            .is_some_and(|state| state.copy_only_dictation.load(Ordering::SeqCst));
+        if copy_only_dictation || read_setting_or(app, "copy_only_mode", false) {
+            Self {
+                method: PasteMethod::CopyOnly,
+                ..options
+            }
+        } else {
+            options
+        }
+    }
+
+    #[cfg(not(desktop))]
+    pub fn from_store(_app: &AppHandle) -> Self {
+        Self::default()
+    }
+}
+
+/// Output profile of the app that currently has focus, if one is configured, or the
+/// one a `tambourine://` link asked the dictation to use
+#[cfg(desktop)]
+pub fn foreground_profile(app: &AppHandle) -> Option<AppOutputProfile> {
+    let profiles: HashMap<String, AppOutputProfile> =
+        read_setting_or(app, "app_output_profiles", HashMap::new());
+    let requested = app
+        .try_state::<AppState>()
+        .and_then(|state| state.dictation_profile.lock().ok()?.clone());
+    let target = match requested {
+        Some(name) => name,
+        None => crate::foreground_app::foreground_app()?,
+    };
+    let profile = find_app_profile(&profiles, &target)?;
+    log::debug!("Using output profile for {}", target);
+    Some(profile.clone())
+}
+
+#[cfg(not(desktop))]
+pub fn foreground_profile(_app: &AppHandle) -> Option<AppOutputProfile> {
+    None
+}
+
+/// Wrap text in the output template of the app that currently has focus
+pub fn apply_app_template(app: &AppHandle, text: &str) -> String {
+    match foreground_profile(app) {
+        Some(profile) => profile.apply_template(text),
+        None => text.to_string(),
+    }
+}
+
+/// Start a dictation run as the overlay starts recording; its ID comes back with
+/// the text in [`type_text`]
 #[tauri::command]
-pub async fn type_text(app: AppHandle, text: String) -> Result<(), String> {
-    // macOS HIToolbox APIs (used by enigo) must run on the main thread
-    // Use a channel to get the result back from the main thread
-    let (tx, rx) = mpsc::channel::<Result<(), String>>();
-
-    app.run_on_main_thread(move || {
-        let result = type_text_blocking(&text);
-        let _ = tx.send(result);
+pub async fn begin_dictation(pipeline: State<'_, DictationPipeline>) -> Result<u64, AppError> {
+    Ok(pipeline.begin())
+}
+
+/// Paste the text of dictation `dictation` (text without one, like a snippet
+/// picked by hand, is only given up at shutdown)
+#[tauri::command]
+pub async fn type_text(
+    app: AppHandle,
+    text: String,
+    dictation: Option<u64>,
+) -> Result<(), AppError> {
+    // Text the server still sends for a cancelled dictation is dropped
+    let cancel = pipeline::dictation_token(&app, dictation);
+    cancel.check()?;
+    let dictation = Dictation {
+        text: text.clone(),
+        target_app: paste_target::remembered(&app).and_then(|target| target.app),
+        profile: foreground_profile(&app),
+    };
+    match output_sinks::dispatch(&app, &dictation) {
+        Ok(true) => {
+            set_overlay_state(&app, OverlayState::Idle);
+            notifications::notify(
+                &app,
+                NotificationKind::Saved,
+                &notifications::snippet(&text, SNIPPET_MAX_CHARS),
+            );
+            return Ok(());
+        }
+        Ok(false) => {}
+        Err(e) => {
+            set_overlay_state(&app, OverlayState::Error { message: e.clone() });
+            return Err(AppError::Failed(e));
+        }
+    }
+    if let Some(reason) = paste_target::hold_reason(&app, &text, true) {
+        return paste_target::hold(&app, &text, &reason).map_err(AppError::Failed);
+    }
+    deliver_text(app, text, cancel).await
+}
+
+/// Paste text that was held back, now that the user confirmed it
+#[tauri::command]
+pub async fn paste_held(app: AppHandle) -> Result<(), AppError> {
+    let text = paste_target::take_held(&app)
+        .ok_or_else(|| AppError::NotFound("No text is waiting to be pasted".to_string()))?;
+    deliver_text(app, text, CancellationToken::default()).await
+}
+
+/// Paste text with the configured method and report the outcome on the overlay,
+/// unless `cancel` is cancelled before the text goes out
+async fn deliver_text(
+    app: AppHandle,
+    text: String,
+    cancel: CancellationToken,
+) -> Result<(), AppError> {
+    let options = PasteOptions::from_store(&app);
+    let text = apply_app_template(&app, &text);
+    cancel.check()?;
+    set_overlay_state(&app, OverlayState::Pasting);
+
+    // The app's own window takes the text over IPC
+    if options.method != PasteMethod::CopyOnly && in_app_insert::try_insert(&app, &text) {
+        set_overlay_state(&app, OverlayState::Idle);
+        notifications::notify(
+            &app,
+            NotificationKind::TranscriptionComplete,
+            &notifications::snippet(&text, SNIPPET_MAX_CHARS),
+        );
+        return Ok(());
+    }
+
+    // A browser extension inserts the text into the focused web page field itself
+    let target = paste_target::remembered(&app);
+    let target_app = target.as_ref().and_then(|target| target.app.as_deref());
+    if options.method != PasteMethod::CopyOnly
+        && browser_extension::try_insert(&app, &text, target_app)
+    {
+        set_overlay_state(&app, OverlayState::Idle);
+        notifications::notify(
+            &app,
+            NotificationKind::TranscriptionComplete,
+            &notifications::snippet(&text, SNIPPET_MAX_CHARS),
+        );
+        return Ok(());
+    }
+
+    // Delivery waits between keys and for the field to update, so it runs on a
+    // worker; each key is still sent from the main thread
+    cancel.check()?;
+    let pasted = text.clone();
+    let handle = app.clone();
+    let token = cancel.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        deliver_with_retry(&handle, &text, options, target.as_ref(), &token)
     })
-    .map_err(|e| e.to_string())?;
+    .await
+    .map_err(|e| DeliveryError::Failed(e.to_string()))
+    .and_then(|result| result);
+    match &result {
+        Ok(inserted) => {
+            set_overlay_state(&app, OverlayState::Idle);
+            let kind = if options.method == PasteMethod::CopyOnly {
+                NotificationKind::Copied
+            } else {
+                paste_undo::record(&app, inserted, options);
+                NotificationKind::TranscriptionComplete
+            };
+            notifications::notify(
+                &app,
+                kind,
+                &notifications::snippet(&pasted, SNIPPET_MAX_CHARS),
+            );
+        }
+        // Keep the text for the overlay and paste-last hotkey rather than lose it
+        Err(error) if error.holds_text() => {
+            return paste_target::hold(&app, &pasted, &error.to_string()).map_err(AppError::Failed);
+        }
+        // Typing stopped part way because the dictation was cancelled
+        Err(_) if cancel.is_cancelled() => return Err(AppError::Cancelled),
+        Err(error) => set_overlay_state(
+            &app,
+            OverlayState::Error {
+                message: format!("Failed to paste text: {}", error),
+            },
+        ),
+    }
+    result
+        .map(|_| ())
+        .map_err(|error| AppError::Failed(error.to_string()))
+}
 
-    // Wait for result from main thread
-    rx.recv().map_err(|e| e.to_string())?
+/// Paste the `n`th last transcript from history, 1 being the newest
+#[tauri::command]
+pub async fn paste_nth_last(app: AppHandle, n: usize) -> Result<(), AppError> {
+    let text = paste_ring::nth_last(&app, n)?;
+    paste_picked(app, text).await
 }
 
-/// Type text using clipboard and paste. Used internally by shortcut handlers.
-pub fn type_text_blocking(text: &str) -> Result<(), String> {
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+/// Paste text the user picked (a past transcript or a snippet)
+pub(crate) async fn paste_picked(app: AppHandle, text: String) -> Result<(), AppError> {
+    // Picked explicitly, so long text needs no further confirmation
+    if let Some(reason) = paste_target::hold_reason(&app, &text, false) {
+        return paste_target::hold(&app, &text, &reason).map_err(AppError::Failed);
+    }
+    deliver_text(app, text, CancellationToken::default()).await
+}
 
-    // Save previous clipboard content
-    let previous = clipboard.get_text().unwrap_or_default();
+/// Report whether a text field of the main window has focus, so dictation can be
+/// inserted there directly
+#[tauri::command]
+pub fn set_in_app_field_focused(focused: bool, field: State<'_, InAppField>) {
+    field.set_focused(focused);
+}
 
-    // Set new text
-    clipboard.set_text(text).map_err(|e| e.to_string())?;
+/// Remove the text of the last paste from the field it went into
+#[tauri::command]
+pub async fn undo_last_paste(app: AppHandle) -> Result<(), AppError> {
+    tauri::async_runtime::spawn_blocking(move || paste_undo::undo(&app))
+        .await
+        .map_err(|e| AppError::Failed(e.to_string()))?
+}
 
-    // Small delay for clipboard to stabilize
-    thread::sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
+/// Turn copy-only mode on or off (also in the tray menu)
+#[tauri::command]
+pub async fn set_copy_only_mode(
+    app: AppHandle,
+    enabled: bool,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), AppError> {
+    let store = settings_store(&app)?;
+    store.set("copy_only_mode", enabled);
+    persist_store(&app, &settings_manager)?;
+    crate::tray_menu::refresh(&app);
+    app.emit("settings-changed", ())
+        .map_err(|e| AppError::Failed(e.to_string()))
+}
+
+/// Hold cleaned text for review instead of pasting it, and open the review window
+#[tauri::command]
+pub async fn review_transcript(
+    app: AppHandle,
+    text: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    *state
+        .pending_paste
+        .lock()
+        .map_err(|e| format!("Failed to store pending paste: {}", e))? = Some(text.clone());
+    review::show_review_window(&app, &text).map_err(AppError::Failed)
+}
 
-    // Simulate Ctrl+V / Cmd+V
-    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+/// Text waiting in the review window, if any
+#[tauri::command]
+pub async fn get_pending_paste(state: State<'_, AppState>) -> Result<Option<String>, AppError> {
+    let pending = state
+        .pending_paste
+        .lock()
+        .map_err(|e| format!("Failed to read pending paste: {}", e))?;
+    Ok(pending.clone())
+}
+
+/// Paste the reviewed (possibly edited) text and close the review window
+#[tauri::command]
+pub async fn confirm_paste(
+    app: AppHandle,
+    text: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let pending = state
+        .pending_paste
+        .lock()
+        .map_err(|e| format!("Failed to read pending paste: {}", e))?
+        .take();
+    if pending.is_none() {
+        return Err(AppError::NotFound(
+            "No text is waiting to be pasted".to_string(),
+        ));
+    }
+
+    review::hide_review_window(&app);
+    tokio::time::sleep(review::FOCUS_RETURN_DELAY).await;
+    // Reviewing already confirmed the text, whatever its length
+    if let Some(reason) = paste_target::hold_reason(&app, &text, false) {
+        return paste_target::hold(&app, &text, &reason).map_err(AppError::Failed);
+    }
+    deliver_text(app, text, CancellationToken::default()).await
+}
+
+/// Drop the text waiting for review and close the review window
+#[tauri::command]
+pub async fn discard_pending(app: AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    state
+        .pending_paste
+        .lock()
+        .map_err(|e| format!("Failed to clear pending paste: {}", e))?
+        .take();
+    review::hide_review_window(&app);
+    set_overlay_state(&app, OverlayState::Idle);
+    Ok(())
+}
+
+/// Whether the field can show if a paste arrived: methods that write into it at the
+/// cursor, with the text as-is
+fn can_check_delivery(options: PasteOptions) -> bool {
+    matches!(
+        options.method,
+        PasteMethod::ClipboardPaste | PasteMethod::SimulateTyping
+    ) && options.format == PasteFormat::Plain
+}
+
+/// What the field showed after a paste
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PasteCheck {
+    /// The text before the cursor changed
+    Arrived,
+    /// Every read succeeded and matched the text from before the paste
+    Missed,
+    /// The text looked unchanged, but a read failed
+    Unconfirmed,
+}
+
+/// Judge a paste from the text before the cursor ahead of it and the reads taken
+/// since. Any change means it arrived; it only counts as missed when no read failed.
+pub fn check_paste(before: &str, reads: &[Option<String>]) -> PasteCheck {
+    if reads.iter().flatten().any(|after| after != before) {
+        PasteCheck::Arrived
+    } else if !reads.is_empty() && reads.iter().all(Option::is_some) {
+        PasteCheck::Missed
+    } else {
+        PasteCheck::Unconfirmed
+    }
+}
+
+/// Text before the cursor, when two reads in a row agree. A field whose value
+/// can't be read, or changes by itself, can't show whether a paste arrived.
+fn read_stable_context() -> Option<String> {
+    let first = crate::caret::text_before_caret(PASTE_CHECK_CHARS)?;
+    thread::sleep(Duration::from_millis(PASTE_CHECK_INTERVAL_MS));
+    let second = crate::caret::text_before_caret(PASTE_CHECK_CHARS)?;
+    (first == second).then_some(first)
+}
 
+/// Read the field until the paste shows up or the wait is over
+fn await_paste(before: &str) -> PasteCheck {
+    let deadline = Instant::now() + Duration::from_millis(PASTE_CHECK_TIMEOUT_MS);
+    let mut reads = Vec::new();
+    loop {
+        thread::sleep(Duration::from_millis(PASTE_CHECK_INTERVAL_MS));
+        reads.push(crate::caret::text_before_caret(PASTE_CHECK_CHARS));
+        let check = check_paste(before, &reads);
+        if check == PasteCheck::Arrived || Instant::now() >= deadline {
+            return check;
+        }
+    }
+}
+
+/// Deliver text and, when the field confirms it didn't arrive, bring back the
+/// window the recording started in and try once more. A paste the field can't
+/// confirm either way is reported as unconfirmed rather than pasted twice, and a
+/// failed attempt isn't retried, as typing may have stopped part way. Returns the
+/// text as it was inserted.
+pub fn deliver_with_retry(
+    app: &AppHandle,
+    text: &str,
+    options: PasteOptions,
+    target: Option<&PasteTarget>,
+    cancel: &CancellationToken,
+) -> Result<String, DeliveryError> {
+    let checkable = can_check_delivery(options);
+    let attempt = || -> Result<String, DeliveryError> {
+        prepare_field(app, options)?;
+        // Read once the field is prepared, as moving past a selection changes the
+        // text before the cursor
+        let before = checkable.then(read_stable_context).flatten();
+        let inserted = send_text(app, text, options, cancel)?;
+        match before.map(|before| await_paste(&before)) {
+            None | Some(PasteCheck::Arrived) => Ok(inserted),
+            Some(PasteCheck::Missed) => Err(DeliveryError::Missed),
+            Some(PasteCheck::Unconfirmed) => Err(DeliveryError::Unconfirmed),
+        }
+    };
+
+    let first = attempt();
+    let retry = matches!(first, Err(DeliveryError::Missed)) && !cancel.is_cancelled();
+    let Some(target) = target.filter(|_| retry) else {
+        return first;
+    };
+    log::warn!("Paste didn't arrive, refocusing the target window and retrying");
+    if !crate::foreground_app::focus_window(target.window) {
+        return Err(DeliveryError::Missed);
+    }
+    thread::sleep(review::FOCUS_RETURN_DELAY);
+    attempt()
+}
+
+/// Deliver text using the configured paste method and return it as inserted, after
+/// fitting it to the text around the cursor. Used internally by shortcut handlers.
+/// Paced typing stops between characters once `cancel` is cancelled.
+pub fn type_text_blocking(
+    app: &AppHandle,
+    text: &str,
+    options: PasteOptions,
+    cancel: &CancellationToken,
+) -> Result<String, DeliveryError> {
+    prepare_field(app, options)?;
+    Ok(send_text(app, text, options, cancel)?)
+}
+
+/// Refuse a password field, and move past a selection the text goes after
+fn prepare_field(app: &AppHandle, options: PasteOptions) -> Result<(), DeliveryError> {
+    if refuses_field(
+        options.method,
+        options.refuse_secure_fields,
+        crate::caret::secure_field_focused(),
+    ) {
+        return Err(DeliveryError::SecureField);
+    }
+    if should_collapse_selection(
+        options.method,
+        options.insert_mode,
+        crate::caret::text_selected(),
+    ) {
+        collapse_selection(app)?;
+    }
+    Ok(())
+}
+
+/// Fit text to the field and send it with the configured paste method
+fn send_text(
+    app: &AppHandle,
+    text: &str,
+    options: PasteOptions,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    let text = match fit_context(options) {
+        Some(before) => smart_insert::fit_to_context(text, &before),
+        None => text.to_string(),
+    };
+
+    match options.method {
+        PasteMethod::ClipboardPaste => paste_via_clipboard(app, &text, &options),
+        PasteMethod::SimulateTyping => simulate_typing(app, &text, &options, cancel),
+        PasteMethod::CopyOnly => copy_to_clipboard(&text, options.format),
+        PasteMethod::Accessibility => crate::caret::insert_text(&text).or_else(|e| {
+            log::warn!("Accessibility insertion failed, pasting instead: {}", e);
+            paste_via_clipboard(app, &text, &options)
+        }),
+    }?;
+    Ok(text)
+}
+
+/// Whether to move the cursor past the selection before delivering text, so it is
+/// inserted instead of typed or pasted over the selection
+pub fn should_collapse_selection(
+    method: PasteMethod,
+    mode: InsertMode,
+    text_selected: Option<bool>,
+) -> bool {
+    method != PasteMethod::CopyOnly && mode == InsertMode::Insert && text_selected == Some(true)
+}
+
+/// Whether delivery must stop because the focused field is a password field. Copying
+/// doesn't touch the field, and an unknown field is not refused.
+pub fn refuses_field(
+    method: PasteMethod,
+    refuse_secure_fields: bool,
+    secure: Option<bool>,
+) -> bool {
+    refuse_secure_fields && method != PasteMethod::CopyOnly && secure == Some(true)
+}
+
+/// Text before the cursor to fit inserted text to, when smart insertion applies
+fn fit_context(options: PasteOptions) -> Option<String> {
+    if !options.smart_insertion || options.method == PasteMethod::CopyOnly {
+        return None;
+    }
+    crate::caret::text_before_caret(smart_insert::CONTEXT_CHARS)
+}
+
+/// Select the `count` characters before the cursor, e.g. to replace the last paste
+pub fn select_previous_chars(app: &AppHandle, count: usize) -> Result<(), String> {
+    input_backend::with_enigo(app, move |enigo| {
+        enigo
+            .key(Key::Shift, Direction::Press)
+            .map_err(|e| e.to_string())?;
+        let result = (0..count).try_for_each(|_| {
+            enigo
+                .key(Key::LeftArrow, Direction::Click)
+                .map_err(|e| e.to_string())
+        });
+        enigo
+            .key(Key::Shift, Direction::Release)
+            .map_err(|e| e.to_string())?;
+        result
+    })?;
+    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+    Ok(())
+}
+
+/// Delete the selection made by `select_previous_chars`
+pub fn delete_selection(app: &AppHandle) -> Result<(), String> {
+    input_backend::with_enigo(app, |enigo| {
+        enigo
+            .key(Key::Backspace, Direction::Click)
+            .map_err(|e| e.to_string())
+    })?;
+    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+    Ok(())
+}
+
+/// Send the focused app's undo shortcut (Ctrl/Cmd+Z)
+pub fn send_undo_keystroke(app: &AppHandle) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     let modifier = Key::Meta;
     #[cfg(not(target_os = "macos"))]
     let modifier = Key::Control;
+    #[cfg(target_os = "windows")]
+    let key = Key::Z;
+    #[cfg(not(target_os = "windows"))]
+    let key = Key::Unicode('z');
+    press_chord(app, &[modifier], key)
+}
 
-    enigo
-        .key(modifier, Direction::Press)
-        .map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-    enigo
-        .key(Key::Unicode('v'), Direction::Click)
-        .map_err(|e| e.to_string())?;
+/// Move the cursor to the end of the selection
+fn collapse_selection(app: &AppHandle) -> Result<(), String> {
+    input_backend::with_enigo(app, |enigo| {
+        enigo
+            .key(Key::RightArrow, Direction::Click)
+            .map_err(|e| e.to_string())
+    })?;
     thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-    enigo
-        .key(modifier, Direction::Release)
-        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Press a chord: hold the modifiers, click the key, release the modifiers in reverse.
+/// Each key goes to the main thread on its own, so the pauses between them are
+/// spent on this thread instead of holding up the UI.
+fn press_chord(app: &AppHandle, modifiers: &[Key], key: Key) -> Result<(), String> {
+    let send = |key: Key, direction: Direction| {
+        input_backend::with_enigo(app, move |enigo| {
+            enigo.key(key, direction).map_err(|e| e.to_string())
+        })
+    };
+    for modifier in modifiers {
+        send(*modifier, Direction::Press)?;
+        thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+    }
+    send(key, Direction::Click)?;
+    for modifier in modifiers.iter().rev() {
+        thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+        send(*modifier, Direction::Release)?;
+    }
+    Ok(())
+}
+
+/// Key that pastes together with Ctrl/Cmd. Windows apps match the V virtual key
+/// whatever the layout; elsewhere the key producing "v" on the current layout is used.
+fn paste_key() -> Key {
+    #[cfg(target_os = "windows")]
+    {
+        Key::V
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Key::Unicode('v')
+    }
+}
+
+/// Trigger a paste in the focused app with the given keystroke
+fn send_paste_keystroke(app: &AppHandle, keystroke: PasteKeystroke) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let modifier = Key::Control;
+
+    match keystroke {
+        PasteKeystroke::CtrlV => press_chord(app, &[modifier], paste_key()),
+        PasteKeystroke::CtrlShiftV => press_chord(app, &[modifier, Key::Shift], paste_key()),
+        #[cfg(not(target_os = "macos"))]
+        PasteKeystroke::ShiftInsert => press_chord(app, &[Key::Shift], Key::Insert),
+        // macOS has no Insert key; fall back to Cmd+V
+        #[cfg(target_os = "macos")]
+        PasteKeystroke::ShiftInsert => press_chord(app, &[modifier], paste_key()),
+        PasteKeystroke::MiddleClick => input_backend::with_enigo(app, |enigo| {
+            enigo
+                .button(Button::Middle, Direction::Click)
+                .map_err(|e| e.to_string())
+        }),
+    }
+}
+
+/// Put text on the clipboard, as HTML with a plain-text fallback for the rich format
+fn set_clipboard_text(
+    clipboard: &mut Clipboard,
+    text: &str,
+    format: PasteFormat,
+) -> Result<(), String> {
+    match format {
+        PasteFormat::Plain => clipboard.set_text(text),
+        PasteFormat::Rich => {
+            let html = rich_text::markdown_to_html(text);
+            clipboard.set_html(html.as_str(), Some(text))
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Copy text to the clipboard without pasting
+fn copy_to_clipboard(text: &str, format: PasteFormat) -> Result<(), String> {
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    set_clipboard_text(&mut clipboard, text, format)?;
+    // Small delay for clipboard to stabilize
+    thread::sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
+    Ok(())
+}
+
+/// Type text directly with synthetic key events. Characters without a key on the
+/// target app's keyboard layout are pasted instead; otherwise the clipboard is untouched.
+fn simulate_typing(
+    app: &AppHandle,
+    text: &str,
+    options: &PasteOptions,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    let layout = KeyboardLayout::of_foreground();
+    let runs =
+        input_backend::split_typeable(text, |c| layout.as_ref().is_none_or(|l| l.has_key(c)));
+    if runs.iter().any(|(typeable, _)| !typeable) {
+        log::info!("Pasting characters the keyboard layout can't type");
+    }
+
+    for (typeable, run) in runs {
+        cancel.check()?;
+        if typeable {
+            type_run(app, run, options, cancel)?;
+        } else {
+            let paste_options = PasteOptions {
+                format: PasteFormat::Plain,
+                ..*options
+            };
+            paste_via_clipboard(app, run, &paste_options)?;
+        }
+    }
+    Ok(())
+}
+
+/// Type a run of characters, one at a time with a rate set, for apps that drop fast
+/// input. Only the keys go to the main thread; the pauses between them stay on this
+/// one, which checks `cancel` before each character.
+fn type_run(
+    app: &AppHandle,
+    text: &str,
+    options: &PasteOptions,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    let Some(delay) = typing_delay(options.typing_chars_per_second) else {
+        let text = text.to_string();
+        return input_backend::with_enigo(app, move |enigo| {
+            enigo.text(&text).map_err(|e| e.to_string())
+        });
+    };
+
+    for character in text.chars() {
+        cancel.check()?;
+        input_backend::with_enigo(app, move |enigo| {
+            let mut buffer = [0u8; 4];
+            enigo
+                .text(character.encode_utf8(&mut buffer))
+                .map_err(|e| e.to_string())
+        })?;
+        thread::sleep(jittered_delay(
+            delay,
+            options.typing_jitter_percent,
+            fastrand::f64(),
+        ));
+    }
+    Ok(())
+}
+
+/// Pause between typed characters for a rate, or `None` to type all at once
+pub fn typing_delay(chars_per_second: Option<u32>) -> Option<Duration> {
+    chars_per_second
+        .filter(|rate| *rate > 0)
+        .map(|rate| Duration::from_secs_f64(1.0 / f64::from(rate)))
+}
+
+/// Vary a typing pause by up to `jitter_percent` of it either way. `sample` is a
+/// random number in `[0, 1)`; 0.5 keeps the pause as it is.
+pub fn jittered_delay(delay: Duration, jitter_percent: u32, sample: f64) -> Duration {
+    let jitter = f64::from(jitter_percent.min(100)) / 100.0;
+    delay.mul_f64(1.0 + jitter * (2.0 * sample - 1.0))
+}
+
+/// Type text using clipboard and paste
+fn paste_via_clipboard(app: &AppHandle, text: &str, options: &PasteOptions) -> Result<(), String> {
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+
+    // Save previous clipboard content (text, rich text or image)
+    let previous = options
+        .restore_clipboard
+        .then(|| ClipboardSnapshot::capture(&mut clipboard));
+
+    // Set new text
+    set_clipboard_text(&mut clipboard, text, options.format)?;
+    // Middle-click pastes the primary selection rather than the clipboard
+    #[cfg(target_os = "linux")]
+    if options.keystroke == PasteKeystroke::MiddleClick {
+        use arboard::{LinuxClipboardKind, SetExtLinux};
+        clipboard
+            .set()
+            .clipboard(LinuxClipboardKind::Primary)
+            .text(text)
+            .map_err(|e| e.to_string())?;
+    }
+
+    // Small delay for clipboard to stabilize
+    thread::sleep(Duration::from_millis(CLIPBOARD_STABILIZATION_DELAY_MS));
+
+    send_paste_keystroke(app, options.keystroke)?;
 
-    // Restore previous clipboard after a delay
-    thread::sleep(Duration::from_millis(CLIPBOARD_RESTORE_DELAY_MS));
-    let _ = clipboard.set_text(&previous);
+    // Restore previous clipboard after a delay, once the target app has read it
+    if let Some(previous) = previous {
+        thread::sleep(Duration::from_millis(CLIPBOARD_RESTORE_DELAY_MS));
+        if let Err(e) = previous.restore(&mut clipboard) {
+            log::warn!("{}", e);
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,44 @@
+use crate::error::AppError;
+use crate::live_transcript::{self, LiveTranscript};
+use arboard::Clipboard;
+use tauri::{AppHandle, State};
+
+/// Open the always-on-top live transcript window
+#[tauri::command]
+pub async fn open_transcript_window(app: AppHandle) -> Result<(), AppError> {
+    live_transcript::open_transcript_window(&app).map_err(AppError::Failed)
+}
+
+/// Close the live transcript window
+#[tauri::command]
+pub async fn close_transcript_window(app: AppHandle) -> Result<(), AppError> {
+    live_transcript::close_transcript_window(&app).map_err(AppError::Failed)
+}
+
+/// Report the transcript received so far (sent by the overlay while recording)
+#[tauri::command]
+pub async fn update_live_transcript(app: AppHandle, text: String) -> Result<(), AppError> {
+    live_transcript::update(&app, text);
+    Ok(())
+}
+
+/// Transcript of the current or last recording
+#[tauri::command]
+pub async fn get_live_transcript(
+    transcript: State<'_, LiveTranscript>,
+) -> Result<String, AppError> {
+    Ok(transcript.text())
+}
+
+/// Copy the live transcript to the clipboard
+#[tauri::command]
+pub async fn copy_live_transcript(transcript: State<'_, LiveTranscript>) -> Result<(), AppError> {
+    let text = transcript.text();
+    if text.trim().is_empty() {
+        return Err(AppError::NotFound("Nothing to copy yet".to_string()));
+    }
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard
+        .set_text(text)
+        .map_err(|e| AppError::Failed(e.to_string()))
+}
@@ -0,0 +1,16 @@
+use crate::error::AppError;
+use crate::tray_menu::{self, ProviderOption, TrayMenuState};
+use tauri::{AppHandle, State};
+
+/// Set the providers listed in the tray menu (the server's available providers)
+#[tauri::command]
+pub async fn set_tray_providers(
+    app: AppHandle,
+    stt: Vec<ProviderOption>,
+    llm: Vec<ProviderOption>,
+    tray_menu_state: State<'_, TrayMenuState>,
+) -> Result<(), AppError> {
+    tray_menu_state.set_providers(stt, llm);
+    tray_menu::refresh(&app);
+    Ok(())
+}
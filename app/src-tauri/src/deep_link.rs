@@ -0,0 +1,131 @@
+//! `tambourine://` links that control dictation from browser bookmarks and other apps.
+//!
+//! - `tambourine://record`: start recording
+//! - `tambourine://stop`: stop recording
+//! - `tambourine://toggle`: start or stop recording
+//!
+//! `record` and `toggle` take `?profile=<name>` to deliver the dictation with the
+//! output profile of that name from `app_output_profiles`, instead of the profile of
+//! the app in focus, e.g. `tambourine://record?profile=email`.
+
+use crate::overlay_state::{set_overlay_state, OverlayState};
+use crate::settings::{find_app_profile, AppOutputProfile};
+use crate::state::AppState;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+/// URI scheme the app registers
+pub const DEEP_LINK_SCHEME: &str = "tambourine";
+
+/// Source named in logs for recordings started by a link
+const SOURCE: &str = "Deep link";
+
+/// What a link asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLinkAction {
+    Record { profile: Option<String> },
+    Stop,
+    Toggle { profile: Option<String> },
+}
+
+/// The action of a `tambourine://` link
+pub fn parse(url: &Url) -> Result<DeepLinkAction, String> {
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(format!("Not a {}:// link: {}", DEEP_LINK_SCHEME, url));
+    }
+    // `tambourine://record` has the action as host, `tambourine:record` as path
+    let action = url
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| url.path().trim_matches('/'));
+    let profile = url
+        .query_pairs()
+        .find(|(key, _)| key == "profile")
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    match action.to_ascii_lowercase().as_str() {
+        "record" | "start" => Ok(DeepLinkAction::Record { profile }),
+        "stop" => Ok(DeepLinkAction::Stop),
+        "toggle" => Ok(DeepLinkAction::Toggle { profile }),
+        _ => Err(format!("Unknown link action: {}", action)),
+    }
+}
+
+/// Start recording with the named output profile, refusing unknown profiles (also used
+/// by the Stream Deck socket)
+#[cfg(desktop)]
+pub(crate) fn record(app: &AppHandle, profile: Option<String>, source: &str) -> Result<(), String> {
+    if let Some(name) = &profile {
+        let profiles: HashMap<String, AppOutputProfile> =
+            crate::settings::read_setting(app, "app_output_profiles");
+        if find_app_profile(&profiles, name).is_none() {
+            return Err(format!("No output profile is named {}", name));
+        }
+    }
+    let state = app.state::<AppState>();
+    if state.lifecycle.is_recording() {
+        return Ok(());
+    }
+    crate::set_recording(app, true, source);
+    if let Ok(mut dictation_profile) = state.dictation_profile.lock() {
+        *dictation_profile = profile;
+    }
+    Ok(())
+}
+
+/// Carry out a link, showing what went wrong in the overlay
+pub fn handle(app: &AppHandle, url: &Url) {
+    log::info!("Opening link {}", url);
+    let result = parse(url).and_then(|action| {
+        #[cfg(desktop)]
+        match action {
+            DeepLinkAction::Record { profile } => record(app, profile, SOURCE)?,
+            DeepLinkAction::Stop => crate::set_recording(app, false, SOURCE),
+            DeepLinkAction::Toggle { profile } => {
+                if crate::recording_state::is_recording(app) {
+                    crate::set_recording(app, false, SOURCE);
+                } else {
+                    record(app, profile, SOURCE)?;
+                }
+            }
+        }
+        #[cfg(not(desktop))]
+        let _ = action;
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("{}", e);
+        set_overlay_state(app, OverlayState::Error { message: e });
+    }
+}
+
+/// Handle links opened while the app runs, and the one it was launched with
+pub fn setup(app: &AppHandle) {
+    // Installed builds register the scheme from the bundle; development builds do it here
+    #[cfg(all(debug_assertions, any(target_os = "linux", target_os = "windows")))]
+    if let Err(e) = app.deep_link().register_all() {
+        log::warn!(
+            "Failed to register the {}:// scheme: {}",
+            DEEP_LINK_SCHEME,
+            e
+        );
+    }
+
+    let app_handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle(&app_handle, &url);
+        }
+    });
+
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => {
+            for url in urls {
+                handle(app, &url);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to read the launch link: {}", e),
+    }
+}
@@ -0,0 +1,61 @@
+//! Detection of display configuration changes.
+//!
+//! Tauri has no event for monitors being added, removed or changing resolution,
+//! so the monitor layout is polled. On a change the overlay is recalibrated and
+//! the edge indicator re-placed, so neither ends up off-screen.
+
+use crate::{edge_indicator, overlay};
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// How often the monitor layout is checked
+const DISPLAY_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Name, physical position, physical size and scale of a monitor
+type MonitorLayout = (Option<String>, (i32, i32), (u32, u32), u64);
+
+fn monitor_layout(app: &AppHandle) -> Option<Vec<MonitorLayout>> {
+    let monitors = app.available_monitors().ok()?;
+    Some(
+        monitors
+            .iter()
+            .map(|monitor| {
+                let position = monitor.position();
+                let size = monitor.size();
+                (
+                    monitor.name().cloned(),
+                    (position.x, position.y),
+                    (size.width, size.height),
+                    monitor.scale_factor().to_bits(),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Start watching the monitor layout on a background thread
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || {
+        let mut last_layout = monitor_layout(&app);
+
+        loop {
+            thread::sleep(DISPLAY_WATCH_INTERVAL);
+
+            let layout = monitor_layout(&app);
+            // Skip failed reads and empty lists (e.g. while displays are reconnecting)
+            if layout.as_ref().is_none_or(Vec::is_empty) || layout == last_layout {
+                continue;
+            }
+            last_layout = layout;
+
+            log::info!("Display configuration changed, recalibrating overlay");
+            if let Err(e) = overlay::recalibrate_overlay(&app) {
+                log::warn!("Failed to recalibrate overlay: {}", e);
+            }
+            if let Err(e) = edge_indicator::apply_edge_indicator(&app) {
+                log::warn!("Failed to re-place edge indicator: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,97 @@
+//! Minimal progress indicator: a thin bar along a screen edge.
+//!
+//! An alternative to the overlay pill that never covers content. The bar is a
+//! separate frameless, click-through window on the overlay's monitor; its page
+//! colors itself from `overlay-state` events and is transparent while idle.
+//! The `edge_indicator` setting picks the edge or turns the bar off.
+
+use crate::overlay::{self, MonitorBounds};
+use crate::settings::{EdgeIndicator, OverlayMonitor};
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, Position, Size, WebviewUrl,
+    WebviewWindowBuilder,
+};
+
+/// Window label of the edge indicator
+pub const EDGE_INDICATOR_LABEL: &str = "edge-indicator";
+
+/// Thickness of the bar (logical px)
+pub const EDGE_THICKNESS: f64 = 4.0;
+
+/// Logical (x, y, width, height) of the bar along `edge` of a monitor
+pub fn edge_rect(bounds: &MonitorBounds, edge: EdgeIndicator) -> Option<(f64, f64, f64, f64)> {
+    let MonitorBounds {
+        x,
+        y,
+        width,
+        height,
+    } = *bounds;
+    match edge {
+        EdgeIndicator::Off => None,
+        EdgeIndicator::Top => Some((x, y, width, EDGE_THICKNESS)),
+        EdgeIndicator::Bottom => Some((x, y + height - EDGE_THICKNESS, width, EDGE_THICKNESS)),
+        EdgeIndicator::Left => Some((x, y, EDGE_THICKNESS, height)),
+        EdgeIndicator::Right => Some((x + width - EDGE_THICKNESS, y, EDGE_THICKNESS, height)),
+    }
+}
+
+/// Show the bar on the configured edge of the overlay's monitor, creating the window
+/// on first use, or hide it when the indicator is off
+pub fn apply_edge_indicator(app: &AppHandle) -> Result<(), String> {
+    let edge: EdgeIndicator = crate::settings::read_setting(app, "edge_indicator");
+    let existing = app.get_webview_window(EDGE_INDICATOR_LABEL);
+
+    let setting: OverlayMonitor = crate::settings::read_setting(app, "overlay_monitor");
+    let placement = overlay::target_monitor(app, &setting).and_then(|monitor| {
+        edge_rect(&MonitorBounds::from_monitor(&monitor), edge)
+            .map(|rect| (rect, monitor.scale_factor()))
+    });
+    let Some(((x, y, width, height), scale)) = placement else {
+        if let Some(window) = existing {
+            window.hide().map_err(|e| e.to_string())?;
+        }
+        return Ok(());
+    };
+
+    let window = match existing {
+        Some(window) => window,
+        None => WebviewWindowBuilder::new(
+            app,
+            EDGE_INDICATOR_LABEL,
+            WebviewUrl::App("edge-indicator.html".into()),
+        )
+        .title("Dictation Indicator")
+        .decorations(false)
+        .transparent(true)
+        .shadow(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .focused(false)
+        .focusable(false)
+        .visible_on_all_workspaces(true)
+        .build()
+        .map_err(|e| format!("Failed to create edge indicator: {}", e))?,
+    };
+
+    // Convert with the target monitor's scale; move first so the size is applied there
+    window
+        .set_position(Position::Physical(PhysicalPosition::new(
+            (x * scale).round() as i32,
+            (y * scale).round() as i32,
+        )))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_size(Size::Physical(PhysicalSize::new(
+            (width * scale).round() as u32,
+            (height * scale).round() as u32,
+        )))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_ignore_cursor_events(true)
+        .map_err(|e| e.to_string())?;
+    let exclude_from_capture: bool =
+        crate::settings::read_setting(app, "exclude_overlay_from_capture");
+    overlay::apply_capture_exclusion(&window, exclude_from_capture)?;
+    window.show().map_err(|e| e.to_string())
+}
@@ -0,0 +1,156 @@
+//! Starting email drafts from dictations.
+//!
+//! An output profile with `email_draft` set opens a new draft holding the dictation
+//! instead of pasting it, e.g. for a profile used while writing emails. The draft is
+//! created by the default mail app through a `mailto:` link, by Outlook through its
+//! COM interface (Windows), or by Apple Mail through AppleScript (macOS). The first
+//! line of a dictation that starts with "Subject:" becomes the subject.
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+/// Program creating the draft
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailClient {
+    /// The system's default mail app, through a `mailto:` link
+    #[default]
+    Mailto,
+    /// Microsoft Outlook (Windows)
+    Outlook,
+    /// Apple Mail (macOS)
+    AppleMail,
+}
+
+/// A draft's subject and body
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailDraft {
+    pub subject: String,
+    pub body: String,
+}
+
+/// Split a dictation into subject and body
+pub fn draft_from(text: &str) -> EmailDraft {
+    let text = text.trim();
+    let (first, rest) = text.split_once('\n').unwrap_or((text, ""));
+    let first = first.trim();
+    let subject = first
+        .get(..8)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("subject:"))
+        .map(|_| first[8..].trim().to_string());
+    match subject {
+        Some(subject) => EmailDraft {
+            subject,
+            body: rest.trim().to_string(),
+        },
+        None => EmailDraft {
+            subject: String::new(),
+            body: text.to_string(),
+        },
+    }
+}
+
+/// Percent-encode text for a URL query, with line breaks as CRLF as mail apps expect
+fn encode_query_value(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.replace("\r\n", "\n").replace('\n', "\r\n").bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// The `mailto:` link opening a draft
+pub fn mailto_url(draft: &EmailDraft) -> String {
+    format!(
+        "mailto:?subject={}&body={}",
+        encode_query_value(&draft.subject),
+        encode_query_value(&draft.body)
+    )
+}
+
+/// Run a scripting command that reads the draft from its environment or arguments
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn run_script(mut command: std::process::Command, client: &str) -> Result<(), String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to start {}: {}", client, e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(format!(
+        "Failed to create a {} draft: {}",
+        client,
+        stderr.trim()
+    ))
+}
+
+#[cfg(target_os = "windows")]
+fn create_outlook_draft(draft: &EmailDraft) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+
+    /// Keeps PowerShell from flashing a console window
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    // The text is passed in the environment, so it needs no quoting
+    const SCRIPT: &str = "$outlook = New-Object -ComObject Outlook.Application; \
+        $mail = $outlook.CreateItem(0); \
+        $mail.Subject = $env:TAMBOURINE_SUBJECT; \
+        $mail.Body = $env:TAMBOURINE_BODY; \
+        $mail.Display()";
+    let mut command = std::process::Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+        .env("TAMBOURINE_SUBJECT", &draft.subject)
+        .env("TAMBOURINE_BODY", &draft.body)
+        .creation_flags(CREATE_NO_WINDOW);
+    run_script(command, "Outlook")
+}
+
+#[cfg(not(target_os = "windows"))]
+fn create_outlook_draft(_draft: &EmailDraft) -> Result<(), String> {
+    Err("Outlook drafts are only supported on Windows".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn create_apple_mail_draft(draft: &EmailDraft) -> Result<(), String> {
+    // The text is passed as arguments, so it needs no quoting
+    const SCRIPT: &[&str] = &[
+        "on run argv",
+        "tell application \"Mail\"",
+        "make new outgoing message with properties \
+         {subject:item 1 of argv, content:item 2 of argv, visible:true}",
+        "activate",
+        "end tell",
+        "end run",
+    ];
+    let mut command = std::process::Command::new("osascript");
+    for line in SCRIPT {
+        command.args(["-e", line]);
+    }
+    command.args(["--", &draft.subject, &draft.body]);
+    run_script(command, "Apple Mail")
+}
+
+#[cfg(not(target_os = "macos"))]
+fn create_apple_mail_draft(_draft: &EmailDraft) -> Result<(), String> {
+    Err("Apple Mail drafts are only supported on macOS".to_string())
+}
+
+/// Open a new email draft with the dictated text
+pub fn create(app: &AppHandle, client: EmailClient, text: &str) -> Result<(), String> {
+    let draft = draft_from(text);
+    match client {
+        EmailClient::Mailto => app
+            .opener()
+            .open_url(mailto_url(&draft), None::<&str>)
+            .map_err(|e| format!("Failed to open an email draft: {}", e)),
+        EmailClient::Outlook => create_outlook_draft(&draft),
+        EmailClient::AppleMail => create_apple_mail_draft(&draft),
+    }
+}
@@ -0,0 +1,127 @@
+//! The error Tauri commands return to the frontend.
+//!
+//! Commands fail with an [`AppError`], serialized as
+//! `{ kind, message, recoverable, suggested_action }` so the frontend can say
+//! what went wrong and what to do about it instead of showing a raw string.
+//! The kind is chosen where the error happens: commands (and the modules whose
+//! errors are of more than one kind) build the variant themselves. Internals that
+//! return `Result<_, String>` and reach a command through `?` are [`AppError::Failed`].
+
+use crate::pipeline::CANCELLED;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// Why a command failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// The request can't be carried out as given (a taken hotkey, an empty name)
+    InvalidInput(String),
+    /// What the command works on doesn't exist (anymore)
+    NotFound(String),
+    /// The OS refused access (microphone, accessibility, keychain)
+    PermissionDenied(String),
+    /// The dictation server couldn't be reached
+    ServerUnavailable(String),
+    /// A speech-to-text or LLM provider failed
+    Provider(String),
+    /// App data couldn't be read or written
+    Storage(String),
+    /// History is encrypted and waits for its passphrase
+    Locked(String),
+    /// The dictation was cancelled before the command finished
+    Cancelled,
+    /// Anything else
+    Failed(String),
+}
+
+impl AppError {
+    /// Name of the variant, as the frontend sees it
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::InvalidInput(_) => "invalid_input",
+            Self::NotFound(_) => "not_found",
+            Self::PermissionDenied(_) => "permission_denied",
+            Self::ServerUnavailable(_) => "server_unavailable",
+            Self::Provider(_) => "provider",
+            Self::Storage(_) => "storage",
+            Self::Locked(_) => "locked",
+            Self::Cancelled => "cancelled",
+            Self::Failed(_) => "failed",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::InvalidInput(message)
+            | Self::NotFound(message)
+            | Self::PermissionDenied(message)
+            | Self::ServerUnavailable(message)
+            | Self::Provider(message)
+            | Self::Storage(message)
+            | Self::Locked(message)
+            | Self::Failed(message) => message,
+            Self::Cancelled => CANCELLED,
+        }
+    }
+
+    /// Whether trying again (possibly after the suggested action) can succeed
+    pub fn recoverable(&self) -> bool {
+        !matches!(self, Self::NotFound(_) | Self::Cancelled)
+    }
+
+    /// What the user can do about it, when there is something
+    pub fn suggested_action(&self) -> Option<&'static str> {
+        match self {
+            Self::InvalidInput(_) => Some("Check the value and try again"),
+            Self::PermissionDenied(_) => {
+                Some("Grant Tambourine access in the system privacy settings")
+            }
+            Self::ServerUnavailable(_) => Some("Make sure the Tambourine server is running"),
+            Self::Provider(_) => {
+                Some("Check the provider's API key and status, or switch providers")
+            }
+            Self::Storage(_) => Some("Check there is free disk space and try again"),
+            Self::Locked(_) => Some("Enter the history passphrase"),
+            Self::NotFound(_) | Self::Cancelled | Self::Failed(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::Failed(message.to_string())
+    }
+}
+
+/// Lets internals that return `String` errors call commands with `?`
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.message().to_string()
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("AppError", 4)?;
+        error.serialize_field("kind", self.kind())?;
+        error.serialize_field("message", self.message())?;
+        error.serialize_field("recoverable", &self.recoverable())?;
+        error.serialize_field("suggested_action", &self.suggested_action())?;
+        error.end()
+    }
+}
@@ -0,0 +1,230 @@
+//! Local WebSocket streaming dictation events to external displays.
+//!
+//! When `event_stream_enabled` is on, a read-only socket on
+//! `ws://127.0.0.1:<event_stream_port>` accepts connections that pass the
+//! automation API token as `?token=<token>`, so dashboards and OBS browser sources
+//! can show what the dictation is doing. Each message is `{"event": "...",
+//! "data": ...}`:
+//!
+//! - `state`: the dictation moved to another step, as sent to the overlay
+//!   (`{"state": "recording"}`, `{"state": "error", "message": "..."}`, ...)
+//! - `progress`: elapsed time and limit of the running recording, twice a second
+//! - `partial_transcript`: `{"text": "..."}` with the transcript so far
+//! - `dictation`: the history entry of a finished dictation
+//!
+//! The current state and partial transcript are sent on connecting. Clients only
+//! interested in some events list them as `?events=state,partial_transcript`.
+
+use crate::history::HISTORY_ENTRY_ADDED_EVENT;
+use crate::live_transcript::LiveTranscript;
+use crate::overlay_state::{OverlayStateMachine, OVERLAY_STATE_EVENT};
+use crate::recording_progress::RECORDING_PROGRESS_EVENT;
+use serde_json::{json, Value};
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+use tungstenite::handshake::server::{Request, Response};
+use tungstenite::Message;
+
+/// Port the socket listens on when the setting is missing
+pub const DEFAULT_EVENT_STREAM_PORT: u16 = 8768;
+
+/// Names of the events clients can receive
+pub const STREAM_EVENTS: &[&str] = &["state", "progress", "partial_transcript", "dictation"];
+
+/// How long a connection waits for a client message before sending queued events
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A connected client and the events it wants
+struct Client {
+    sender: mpsc::Sender<String>,
+    events: Vec<String>,
+}
+
+/// Connected clients of the event stream
+#[derive(Default)]
+pub struct EventStreamClients {
+    clients: Mutex<Vec<Client>>,
+}
+
+impl EventStreamClients {
+    fn add(&self, sender: mpsc::Sender<String>, events: Vec<String>) {
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.push(Client { sender, events });
+        }
+    }
+
+    /// Queue an event for the clients that want it, forgetting the ones that disconnected
+    fn broadcast(&self, event: &str, data: &Value) {
+        let message = event_message(event, data);
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.retain(|client| {
+                !wants(&client.events, event) || client.sender.send(message.clone()).is_ok()
+            });
+        }
+    }
+}
+
+/// A message for clients
+pub fn event_message(event: &str, data: &Value) -> String {
+    json!({ "event": event, "data": data }).to_string()
+}
+
+/// The value of a query parameter of a connection URL
+pub fn query_param<'a>(query: Option<&'a str>, name: &str) -> Option<&'a str> {
+    query?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then_some(value)
+    })
+}
+
+/// The events a client asked for with `?events=`, all of them when it didn't
+pub fn requested_events(query: Option<&str>) -> Vec<String> {
+    let Some(list) = query_param(query, "events") else {
+        return Vec::new();
+    };
+    list.split(',')
+        .map(str::trim)
+        .filter(|event| STREAM_EVENTS.contains(event))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether a client listing `events` gets `event`; an empty list means all
+pub fn wants(events: &[String], event: &str) -> bool {
+    events.is_empty() || events.iter().any(|wanted| wanted == event)
+}
+
+/// Send an event to every connected client, if the stream is running
+pub fn publish(app: &AppHandle, event: &str, data: Value) {
+    if let Some(clients) = app.try_state::<EventStreamClients>() {
+        clients.broadcast(event, &data);
+    }
+}
+
+/// Messages bringing a new client up to date
+fn current_messages(app: &AppHandle, events: &[String]) -> Vec<String> {
+    let mut messages = Vec::new();
+    if wants(events, "state") {
+        let state = app
+            .try_state::<OverlayStateMachine>()
+            .map(|machine| machine.current())
+            .unwrap_or_default();
+        messages.push(event_message("state", &json!(state)));
+    }
+    if wants(events, "partial_transcript") {
+        let text = app
+            .try_state::<LiveTranscript>()
+            .map(|transcript| transcript.text())
+            .unwrap_or_default();
+        messages.push(event_message(
+            "partial_transcript",
+            &json!({ "text": text }),
+        ));
+    }
+    messages
+}
+
+/// Stream events to one client until it disconnects
+fn serve(app: &AppHandle, stream: TcpStream) {
+    let token = crate::automation_api::stored_token(app).unwrap_or_default();
+    let mut events = Vec::new();
+    let check_token = |request: &Request, response: Response| {
+        let query = request.uri().query();
+        let given = crate::stream_deck::query_token(query).map(|token| format!("Bearer {}", token));
+        if !crate::automation_api::is_authorized(given.as_deref(), &token) {
+            return Err(crate::stream_deck::unauthorized());
+        }
+        events = requested_events(query);
+        Ok(response)
+    };
+    let mut socket = match tungstenite::accept_hdr(stream, check_token) {
+        Ok(socket) => socket,
+        Err(e) => {
+            log::debug!("Event stream connection refused: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)) {
+        log::warn!("Failed to set up event stream connection: {}", e);
+        return;
+    }
+    log::info!("Event stream client connected");
+
+    let (sender, receiver) = mpsc::channel();
+    for message in current_messages(app, &events) {
+        let _ = sender.send(message);
+    }
+    app.state::<EventStreamClients>().add(sender, events);
+
+    loop {
+        while let Ok(message) = receiver.try_recv() {
+            if socket.send(Message::text(message)).is_err() {
+                return;
+            }
+        }
+        // The stream is read-only; reading only notices pings and closing
+        match socket.read() {
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => {
+                log::debug!("Event stream connection closed: {}", e);
+                break;
+            }
+        }
+    }
+    log::info!("Event stream client disconnected");
+}
+
+/// Forward an app event to the stream under another name
+fn forward(app: &AppHandle, app_event: &'static str, stream_event: &'static str) {
+    let handle = app.clone();
+    app.listen(app_event, move |event| {
+        if let Ok(data) = serde_json::from_str::<Value>(event.payload()) {
+            publish(&handle, stream_event, data);
+        }
+    });
+}
+
+/// Serve the event stream on a background thread, when it is enabled
+pub fn spawn(app: AppHandle) {
+    if !crate::settings::read_setting::<bool>(&app, "event_stream_enabled") {
+        return;
+    }
+    if let Err(e) = crate::automation_api::ensure_token(&app) {
+        log::error!("Event stream not started: {}", e);
+        return;
+    }
+    let port = crate::settings::read_setting::<Option<u16>>(&app, "event_stream_port")
+        .unwrap_or(DEFAULT_EVENT_STREAM_PORT);
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("Failed to start event stream on port {}: {}", port, e);
+            return;
+        }
+    };
+    log::info!("Event stream listening on 127.0.0.1:{}", port);
+
+    app.manage(EventStreamClients::default());
+    forward(&app, OVERLAY_STATE_EVENT, "state");
+    forward(&app, RECORDING_PROGRESS_EVENT, "progress");
+    forward(&app, HISTORY_ENTRY_ADDED_EVENT, "dictation");
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let app = app.clone();
+                    thread::spawn(move || serve(&app, stream));
+                }
+                Err(e) => log::debug!("Failed to accept event stream connection: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,237 @@
+//! Dictations whose pipeline run failed, kept to retry.
+//!
+//! When the server reports an error, stops responding or the connection drops
+//! while a dictation is being recorded or processed, the overlay files it here
+//! with its recording, the error and the providers in use, so an outage doesn't
+//! lose what was said. A recording cut short by a crash is filed here at the
+//! next launch (see `recording_recovery`). Each failed dictation is listed until
+//! it is retried successfully (it then becomes a normal history entry) or
+//! discarded.
+//!
+//! The list is stored in `failed_dictations/index.json` in the app data directory
+//! and each recording next to it, named after the dictation. Neither can be
+//! encrypted, so nothing is filed while history is. Only the newest
+//! `MAX_FAILED_DICTATIONS` are kept.
+
+use crate::error::AppError;
+use crate::history_audio::file_extension;
+use crate::settings::write_file_atomic;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Folder in the app data directory holding failed dictations
+pub const FAILED_DIR: &str = "failed_dictations";
+
+/// How many failed dictations are kept before the oldest are dropped
+pub const MAX_FAILED_DICTATIONS: usize = 50;
+
+/// A dictation that couldn't be transcribed or cleaned up
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FailedDictation {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// What went wrong, from the latest attempt
+    pub error: String,
+    pub stt_provider: Option<String>,
+    pub llm_provider: Option<String>,
+    /// Transcript before cleanup, when the failure came after transcription
+    pub raw_text: Option<String>,
+    /// MIME type of the kept recording, when there is one
+    pub mime_type: Option<String>,
+    /// Retries that failed too
+    #[serde(default)]
+    pub retries: u32,
+}
+
+/// A failed dictation's recording, ready to replay
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedDictationAudio {
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// What is recorded about a failure, besides its recording
+#[derive(Debug, Clone, Default)]
+pub struct FailureDetails {
+    pub error: String,
+    pub stt_provider: Option<String>,
+    pub llm_provider: Option<String>,
+    pub raw_text: Option<String>,
+}
+
+pub struct FailedDictations {
+    failed: Mutex<Vec<FailedDictation>>,
+    /// Folder with the index and recordings, none for tests
+    dir: Option<PathBuf>,
+}
+
+fn audio_file(dir: &Path, dictation: &FailedDictation) -> Option<PathBuf> {
+    let mime_type = dictation.mime_type.as_deref()?;
+    Some(dir.join(format!("{}.{}", dictation.id, file_extension(mime_type))))
+}
+
+impl FailedDictations {
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        let dir = app_data_dir.join(FAILED_DIR);
+        let failed = fs::read_to_string(dir.join("index.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            failed: Mutex::new(failed),
+            dir: Some(dir),
+        }
+    }
+
+    /// A list that isn't saved anywhere, for tests
+    pub fn in_memory() -> Self {
+        Self {
+            failed: Mutex::new(Vec::new()),
+            dir: None,
+        }
+    }
+
+    fn update<T>(
+        &self,
+        change: impl FnOnce(&mut Vec<FailedDictation>) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut failed = self
+            .failed
+            .lock()
+            .map_err(|e| format!("Failed to lock failed dictations: {}", e))?;
+        let result = change(&mut failed)?;
+        if let Some(dir) = &self.dir {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            let content = serde_json::to_string_pretty(&*failed)
+                .map_err(|e| format!("Failed to serialize failed dictations: {}", e))?;
+            write_file_atomic(&dir.join("index.json"), content.as_bytes())?;
+        }
+        Ok(result)
+    }
+
+    fn remove_audio(&self, dictation: &FailedDictation) {
+        if let Some(path) = self
+            .dir
+            .as_deref()
+            .and_then(|dir| audio_file(dir, dictation))
+        {
+            if let Err(e) = fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("Failed to remove {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    /// Failed dictations, newest first
+    pub fn list(&self) -> Result<Vec<FailedDictation>, String> {
+        let mut failed = self
+            .failed
+            .lock()
+            .map_err(|e| format!("Failed to lock failed dictations: {}", e))?
+            .clone();
+        failed.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(failed)
+    }
+
+    /// File a failed dictation with its recording, if one was captured
+    pub fn record(
+        &self,
+        details: FailureDetails,
+        audio: Option<(&[u8], &str)>,
+    ) -> Result<FailedDictation, AppError> {
+        let audio = audio.filter(|(bytes, _)| !bytes.is_empty());
+        if audio.is_none() && details.raw_text.is_none() {
+            return Err(AppError::InvalidInput(
+                "Nothing of the dictation was captured to retry".to_string(),
+            ));
+        }
+
+        let dictation = FailedDictation {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            error: details.error,
+            stt_provider: details.stt_provider,
+            llm_provider: details.llm_provider,
+            raw_text: details.raw_text,
+            mime_type: audio.map(|(_, mime_type)| mime_type.to_string()),
+            retries: 0,
+        };
+
+        if let (Some(dir), Some((bytes, _))) = (&self.dir, audio) {
+            fs::create_dir_all(dir).map_err(|e| {
+                AppError::Storage(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+            if let Some(path) = audio_file(dir, &dictation) {
+                write_file_atomic(&path, bytes).map_err(AppError::Storage)?;
+            }
+        }
+
+        let dropped = self
+            .update(|failed| {
+                failed.insert(0, dictation.clone());
+                Ok(failed.split_off(failed.len().min(MAX_FAILED_DICTATIONS)))
+            })
+            .map_err(AppError::Storage)?;
+        for old in &dropped {
+            self.remove_audio(old);
+        }
+        Ok(dictation)
+    }
+
+    /// The recording of a failed dictation, if one was kept
+    pub fn audio(&self, id: &str) -> Result<Option<FailedDictationAudio>, String> {
+        let Some(dictation) = self.list()?.into_iter().find(|d| d.id == id) else {
+            return Ok(None);
+        };
+        let (Some(dir), Some(mime_type)) = (&self.dir, dictation.mime_type.clone()) else {
+            return Ok(None);
+        };
+        let Some(path) = audio_file(dir, &dictation) else {
+            return Ok(None);
+        };
+        match fs::read(&path) {
+            Ok(bytes) => Ok(Some(FailedDictationAudio { mime_type, bytes })),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Note that a retry failed too, keeping the dictation listed
+    pub fn retry_failed(&self, id: &str, error: &str) -> Result<bool, String> {
+        self.update(|failed| {
+            let Some(dictation) = failed.iter_mut().find(|d| d.id == id) else {
+                return Ok(false);
+            };
+            dictation.error = error.to_string();
+            dictation.retries += 1;
+            Ok(true)
+        })
+    }
+
+    /// Remove every failed dictation and its recording, returning how many there were
+    pub fn clear(&self) -> Result<usize, String> {
+        let removed = self.update(|failed| Ok(std::mem::take(failed)))?;
+        for dictation in &removed {
+            self.remove_audio(dictation);
+        }
+        Ok(removed.len())
+    }
+
+    /// Remove a failed dictation and its recording
+    pub fn discard(&self, id: &str) -> Result<bool, String> {
+        let removed = self.update(|failed| {
+            let index = failed.iter().position(|d| d.id == id);
+            Ok(index.map(|index| failed.remove(index)))
+        })?;
+        if let Some(dictation) = &removed {
+            self.remove_audio(dictation);
+        }
+        Ok(removed.is_some())
+    }
+}
@@ -0,0 +1,446 @@
+//! Transcribing audio and video files dropped on the app.
+//!
+//! Queued files are handled one at a time by a background worker: the audio track is
+//! decoded with symphonia (or, for formats it can't read, extracted with `ffmpeg` when
+//! that is installed), mixed down to 16 kHz mono and sent through the server's
+//! speech-to-text and cleanup like a dictation. Each finished transcript is added to
+//! history. Every change of a job is published as a `file-transcription` event.
+
+use crate::error::AppError;
+use crate::history::{self, EntryDetails, HistoryStorage};
+use crate::pipeline::REQUEST_ID_HEADER;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+/// Event carrying each changed [`FileJob`]
+pub const FILE_TRANSCRIPTION_EVENT: &str = "file-transcription";
+
+/// Sample rate of the audio sent to the server
+pub const TRANSCRIPTION_SAMPLE_RATE: u32 = 16_000;
+
+/// Longest file accepted, in seconds
+const MAX_FILE_SECONDS: f64 = 2.0 * 60.0 * 60.0;
+
+/// Time the server gets to transcribe and clean up one file
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Where a file is in the queue
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum FileJobStatus {
+    Queued,
+    /// Decoding the audio track
+    Extracting,
+    /// Waiting on the server's speech-to-text and cleanup
+    Transcribing,
+    Done {
+        entry_id: String,
+        text: String,
+    },
+    Failed {
+        message: String,
+    },
+    Cancelled,
+}
+
+/// A file to transcribe
+#[derive(Debug, Clone, Serialize)]
+pub struct FileJob {
+    pub id: String,
+    pub path: String,
+    /// File name shown in the queue
+    pub name: String,
+    /// Whether the transcript is cleaned up by the LLM
+    pub cleanup: bool,
+    #[serde(flatten)]
+    pub status: FileJobStatus,
+}
+
+impl FileJob {
+    fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            FileJobStatus::Done { .. } | FileJobStatus::Failed { .. } | FileJobStatus::Cancelled
+        )
+    }
+}
+
+#[derive(Serialize)]
+struct TranscribeRequest {
+    audio: String,
+    stt_provider: Option<String>,
+    cleanup: bool,
+    provider: Option<String>,
+    sections: Option<Value>,
+    locale: Option<Value>,
+    vocabulary: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TranscribeResponse {
+    raw_text: String,
+    text: String,
+}
+
+/// Files waiting for and handled by the worker, in the order they were added
+pub struct FileTranscriptionQueue {
+    jobs: Mutex<Vec<FileJob>>,
+    sender: mpsc::Sender<String>,
+}
+
+impl FileTranscriptionQueue {
+    pub fn jobs(&self) -> Vec<FileJob> {
+        self.jobs
+            .lock()
+            .map(|jobs| jobs.clone())
+            .unwrap_or_default()
+    }
+
+    fn job(&self, id: &str) -> Option<FileJob> {
+        self.jobs
+            .lock()
+            .ok()?
+            .iter()
+            .find(|job| job.id == id)
+            .cloned()
+    }
+
+    /// Add files to the end of the queue
+    pub fn enqueue(&self, paths: Vec<String>, cleanup: bool) -> Result<Vec<FileJob>, String> {
+        let added: Vec<FileJob> = paths
+            .into_iter()
+            .map(|path| FileJob {
+                id: Uuid::new_v4().to_string(),
+                name: Path::new(&path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.clone()),
+                path,
+                cleanup,
+                status: FileJobStatus::Queued,
+            })
+            .collect();
+        self.jobs
+            .lock()
+            .map_err(|e| format!("Failed to queue files: {}", e))?
+            .extend(added.iter().cloned());
+        for job in &added {
+            self.sender
+                .send(job.id.clone())
+                .map_err(|e| format!("Failed to queue files: {}", e))?;
+        }
+        Ok(added)
+    }
+
+    /// Set a job's status, returning the updated job
+    fn update(&self, id: &str, status: FileJobStatus) -> Option<FileJob> {
+        let mut jobs = self.jobs.lock().ok()?;
+        let job = jobs.iter_mut().find(|job| job.id == id)?;
+        job.status = status;
+        Some(job.clone())
+    }
+
+    /// Cancel a job that hasn't started yet
+    pub fn cancel(&self, id: &str) -> Result<Option<FileJob>, AppError> {
+        let mut jobs = self
+            .jobs
+            .lock()
+            .map_err(|e| AppError::Failed(format!("Failed to cancel file: {}", e)))?;
+        let Some(job) = jobs.iter_mut().find(|job| job.id == id) else {
+            return Ok(None);
+        };
+        if job.status != FileJobStatus::Queued {
+            return Err(AppError::InvalidInput(format!(
+                "{} is already being transcribed",
+                job.name
+            )));
+        }
+        job.status = FileJobStatus::Cancelled;
+        Ok(Some(job.clone()))
+    }
+
+    /// Forget finished jobs
+    pub fn clear_finished(&self) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.retain(|job| !job.is_finished());
+        }
+    }
+}
+
+/// Average interleaved samples into one channel
+pub fn downmix(samples: &[f32], channels: usize) -> Vec<f32> {
+    let channels = channels.max(1);
+    samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Linearly resample mono audio
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() || from_rate == 0 {
+        return samples.to_vec();
+    }
+    let ratio = from_rate as f64 / to_rate as f64;
+    let len = (samples.len() as f64 / ratio).round() as usize;
+    (0..len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = position.floor() as usize;
+            let next = samples
+                .get(index + 1)
+                .unwrap_or(&samples[samples.len() - 1]);
+            let current = samples.get(index).unwrap_or(next);
+            let fraction = (position - index as f64) as f32;
+            current + (next - current) * fraction
+        })
+        .collect()
+}
+
+/// Mono audio as a 16-bit PCM WAV file
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        wav.extend_from_slice(&value.to_le_bytes());
+    }
+    wav
+}
+
+/// Decode the first audio track of a file to mono samples and their sample rate
+pub fn decode_audio(path: &Path) -> Result<(Vec<f32>, u32), String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Unsupported file format: {}", e))?
+        .format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("The file has no audio track")?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or("The audio track has no sample rate")?;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported audio codec: {}", e))?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(format!("Failed to read audio: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A damaged packet is skipped rather than failing the whole file
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        };
+        let spec = *decoded.spec();
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend(downmix(buffer.samples(), spec.channels.count()));
+    }
+    Ok((samples, sample_rate))
+}
+
+/// Extract the audio of a file as a 16 kHz mono WAV with `ffmpeg`
+fn extract_with_ffmpeg(path: &Path) -> Result<Vec<u8>, String> {
+    let output = Command::new("ffmpeg")
+        .arg("-nostdin")
+        .args(["-loglevel", "error", "-i"])
+        .arg(path)
+        .args(["-vn", "-ac", "1", "-ar"])
+        .arg(TRANSCRIPTION_SAMPLE_RATE.to_string())
+        .args(["-f", "wav", "-"])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("ffmpeg isn't available: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// The audio of a file as a WAV ready for the server
+pub fn load_audio(path: &Path) -> Result<Vec<u8>, String> {
+    match decode_audio(path) {
+        Ok((samples, sample_rate)) => {
+            if samples.len() as f64 / sample_rate as f64 > MAX_FILE_SECONDS {
+                return Err("Files longer than two hours can't be transcribed".to_string());
+            }
+            let samples = resample(&samples, sample_rate, TRANSCRIPTION_SAMPLE_RATE);
+            Ok(encode_wav(&samples, TRANSCRIPTION_SAMPLE_RATE))
+        }
+        Err(decode_error) => extract_with_ffmpeg(path)
+            .map_err(|ffmpeg_error| format!("{} (and {})", decode_error, ffmpeg_error)),
+    }
+}
+
+/// Send a WAV through the server's speech-to-text and, with `cleanup`, the LLM
+fn transcribe(app: &AppHandle, wav: &[u8], cleanup: bool) -> Result<TranscribeResponse, String> {
+    let request = TranscribeRequest {
+        audio: base64::engine::general_purpose::STANDARD.encode(wav),
+        stt_provider: crate::settings::read_setting(app, "stt_provider"),
+        cleanup,
+        provider: crate::settings::read_setting(app, "llm_provider"),
+        sections: crate::settings::read_setting(app, "cleanup_prompt_sections"),
+        locale: crate::settings::read_setting(app, "locale_preferences"),
+        vocabulary: crate::settings::read_setting(app, "stt_vocabulary"),
+    };
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(TRANSCRIBE_TIMEOUT))
+        .build()
+        .into();
+    // Abort the server's work when the app shuts down
+    crate::pipeline::shutdown_token(app).run_request(move |id| {
+        agent
+            .post(format!(
+                "{}/api/transcribe",
+                crate::commands::text::SERVER_URL
+            ))
+            .header(REQUEST_ID_HEADER, id)
+            .send_json(&request)
+            .map_err(|e| format!("Failed to transcribe: {}", e))?
+            .body_mut()
+            .read_json()
+            .map_err(|e| format!("Failed to read transcript: {}", e))
+    })
+}
+
+fn set_status(app: &AppHandle, id: &str, status: FileJobStatus) {
+    if let Some(job) = app.state::<FileTranscriptionQueue>().update(id, status) {
+        let _ = app.emit(FILE_TRANSCRIPTION_EVENT, job);
+    }
+}
+
+/// Queue files and publish their jobs
+pub fn queue_files(
+    app: &AppHandle,
+    paths: Vec<String>,
+    cleanup: bool,
+) -> Result<Vec<FileJob>, String> {
+    let jobs = app
+        .state::<FileTranscriptionQueue>()
+        .enqueue(paths, cleanup)?;
+    for job in &jobs {
+        let _ = app.emit(FILE_TRANSCRIPTION_EVENT, job);
+    }
+    Ok(jobs)
+}
+
+/// Transcribe one file and add the transcript to history
+fn process(app: &AppHandle, job: &FileJob) -> Result<FileJobStatus, String> {
+    set_status(app, &job.id, FileJobStatus::Extracting);
+    let wav = load_audio(Path::new(&job.path))?;
+    let duration_seconds =
+        wav.len().saturating_sub(44) as f64 / 2.0 / TRANSCRIPTION_SAMPLE_RATE as f64;
+
+    set_status(app, &job.id, FileJobStatus::Transcribing);
+    let response = transcribe(app, &wav, job.cleanup)?;
+    if response.text.trim().is_empty() {
+        return Err("No speech was found in the file".to_string());
+    }
+
+    let history = app
+        .try_state::<HistoryStorage>()
+        .ok_or("History isn't available")?;
+    let details = EntryDetails {
+        raw_text: Some(response.raw_text).filter(|raw| *raw != response.text),
+        stt_provider: crate::settings::read_setting(app, "stt_provider"),
+        llm_provider: if job.cleanup {
+            crate::settings::read_setting(app, "llm_provider")
+        } else {
+            None
+        },
+        duration_seconds: Some(duration_seconds),
+        target_app: None,
+        latency_seconds: None,
+    };
+    let entry = history.add_entry(response.text, details)?;
+    let _ = app.emit(history::HISTORY_ENTRY_ADDED_EVENT, &entry);
+    Ok(FileJobStatus::Done {
+        entry_id: entry.id,
+        text: entry.text,
+    })
+}
+
+/// Start the worker and manage the queue it takes files from
+pub fn spawn(app: &AppHandle) {
+    let (sender, receiver) = mpsc::channel::<String>();
+    app.manage(FileTranscriptionQueue {
+        jobs: Mutex::new(Vec::new()),
+        sender,
+    });
+
+    let app = app.clone();
+    thread::spawn(move || {
+        for id in receiver {
+            let queue = app.state::<FileTranscriptionQueue>();
+            let Some(job) = queue
+                .job(&id)
+                .filter(|job| job.status == FileJobStatus::Queued)
+            else {
+                continue;
+            };
+            log::info!("Transcribing file {}", job.path);
+            let status = process(&app, &job).unwrap_or_else(|message| {
+                log::warn!("Failed to transcribe {}: {}", job.path, message);
+                FileJobStatus::Failed { message }
+            });
+            set_status(&app, &id, status);
+        }
+    });
+}
@@ -0,0 +1,189 @@
+//! Foreground app via the owner of the frontmost window in the Core Graphics
+//! window list. Owner names are readable without extra permissions. Bringing an
+//! app back to the front goes through the Accessibility API.
+
+use std::ffi::{c_char, c_void, CStr};
+
+type CFTypeRef = *const c_void;
+type CFArrayRef = *const c_void;
+type CFStringRef = *const c_void;
+
+const WINDOW_LIST_ON_SCREEN_ONLY: u32 = 1 << 0;
+const WINDOW_LIST_EXCLUDE_DESKTOP: u32 = 1 << 4;
+const NULL_WINDOW_ID: u32 = 0;
+const CF_NUMBER_SINT32_TYPE: i32 = 3;
+const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+const NORMAL_WINDOW_LAYER: i32 = 0;
+const WINDOW_LIST_INCLUDING_WINDOW: u32 = 1 << 3;
+const AX_ERROR_SUCCESS: i32 = 0;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    static kCGWindowLayer: CFStringRef;
+    static kCGWindowOwnerName: CFStringRef;
+    static kCGWindowNumber: CFStringRef;
+    static kCGWindowOwnerPID: CFStringRef;
+    fn CGWindowListCopyWindowInfo(option: u32, relative_to_window: u32) -> CFArrayRef;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFArrayGetCount(array: CFArrayRef) -> isize;
+    fn CFArrayGetValueAtIndex(array: CFArrayRef, index: isize) -> CFTypeRef;
+    fn CFDictionaryGetValue(dict: CFTypeRef, key: CFTypeRef) -> CFTypeRef;
+    fn CFNumberGetValue(number: CFTypeRef, number_type: i32, value: *mut c_void) -> bool;
+    fn CFStringGetCString(
+        string: CFStringRef,
+        buffer: *mut c_char,
+        size: isize,
+        encoding: u32,
+    ) -> bool;
+    fn CFRelease(cf: CFTypeRef);
+    fn CFStringCreateWithCString(
+        allocator: CFTypeRef,
+        c_str: *const c_char,
+        encoding: u32,
+    ) -> CFStringRef;
+    static kCFBooleanTrue: CFTypeRef;
+}
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXUIElementCreateApplication(pid: i32) -> CFTypeRef;
+    fn AXUIElementSetAttributeValue(
+        element: CFTypeRef,
+        attribute: CFStringRef,
+        value: CFTypeRef,
+    ) -> i32;
+}
+
+fn cf_string_to_string(string: CFStringRef) -> Option<String> {
+    let mut buffer = [0 as c_char; 512];
+    // SAFETY: string is a live CFString and buffer has the size passed in
+    let ok = unsafe {
+        CFStringGetCString(
+            string,
+            buffer.as_mut_ptr(),
+            buffer.len() as isize,
+            CF_STRING_ENCODING_UTF8,
+        )
+    };
+    if !ok {
+        return None;
+    }
+    // SAFETY: CFStringGetCString wrote a NUL-terminated string into buffer
+    let name = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Look up a value in the info dictionary of the frontmost normal window
+fn with_frontmost_window<T>(read: impl FnOnce(CFTypeRef) -> Option<T>) -> Option<T> {
+    // SAFETY: the returned array is owned here and released below; dictionary values
+    // are borrowed from it and only used before the release
+    unsafe {
+        let windows = CGWindowListCopyWindowInfo(
+            WINDOW_LIST_ON_SCREEN_ONLY | WINDOW_LIST_EXCLUDE_DESKTOP,
+            NULL_WINDOW_ID,
+        );
+        if windows.is_null() {
+            return None;
+        }
+
+        let mut result = None;
+        for index in 0..CFArrayGetCount(windows) {
+            let info = CFArrayGetValueAtIndex(windows, index);
+            if cf_number_i32(CFDictionaryGetValue(info, kCGWindowLayer))
+                != Some(NORMAL_WINDOW_LAYER)
+            {
+                continue;
+            }
+            result = read(info);
+            break;
+        }
+
+        CFRelease(windows);
+        result
+    }
+}
+
+fn cf_number_i32(number: CFTypeRef) -> Option<i32> {
+    if number.is_null() {
+        return None;
+    }
+    let mut value: i32 = 0;
+    // SAFETY: number is a live CFNumber and value matches the requested type
+    let ok = unsafe {
+        CFNumberGetValue(
+            number,
+            CF_NUMBER_SINT32_TYPE,
+            &mut value as *mut i32 as *mut c_void,
+        )
+    };
+    ok.then_some(value)
+}
+
+pub fn foreground_app() -> Option<String> {
+    with_frontmost_window(|info| {
+        // SAFETY: info is a live window info dictionary
+        let name = unsafe { CFDictionaryGetValue(info, kCGWindowOwnerName) };
+        (!name.is_null())
+            .then(|| cf_string_to_string(name))
+            .flatten()
+    })
+}
+
+pub fn foreground_window() -> Option<u64> {
+    with_frontmost_window(|info| {
+        // SAFETY: info is a live window info dictionary
+        let number = unsafe { CFDictionaryGetValue(info, kCGWindowNumber) };
+        cf_number_i32(number).map(|number| number as u32 as u64)
+    })
+}
+
+/// Process owning a window, looked up by window number
+fn window_owner_pid(window: u64) -> Option<i32> {
+    // SAFETY: the returned array is owned here and released below; the PID is read
+    // before the release
+    unsafe {
+        let windows = CGWindowListCopyWindowInfo(WINDOW_LIST_INCLUDING_WINDOW, window as u32);
+        if windows.is_null() {
+            return None;
+        }
+        let pid = (CFArrayGetCount(windows) > 0)
+            .then(|| {
+                let info = CFArrayGetValueAtIndex(windows, 0);
+                cf_number_i32(CFDictionaryGetValue(info, kCGWindowOwnerPID))
+            })
+            .flatten();
+        CFRelease(windows);
+        pid
+    }
+}
+
+pub fn focus_window(window: u64) -> bool {
+    let Some(pid) = window_owner_pid(window) else {
+        return false;
+    };
+    // SAFETY: the element and attribute name are created here, used for one call and
+    // released; kCFBooleanTrue is a constant
+    unsafe {
+        let application = AXUIElementCreateApplication(pid);
+        if application.is_null() {
+            return false;
+        }
+        let attribute = CFStringCreateWithCString(
+            std::ptr::null(),
+            c"AXFrontmost".as_ptr(),
+            CF_STRING_ENCODING_UTF8,
+        );
+        let result = if attribute.is_null() {
+            -1
+        } else {
+            let result = AXUIElementSetAttributeValue(application, attribute, kCFBooleanTrue);
+            CFRelease(attribute);
+            result
+        };
+        CFRelease(application);
+        result == AX_ERROR_SUCCESS
+    }
+}
@@ -0,0 +1,66 @@
+//! Identity of the app that has keyboard focus.
+//!
+//! Used to apply per-app output settings (see `AppOutputProfile`). The app is
+//! identified by its executable file name on Windows (e.g. `WindowsTerminal.exe`)
+//! and by its application name on macOS (e.g. `Terminal`). Linux has no
+//! implementation, so per-app settings never apply there.
+//!
+//! The foreground window is identified too, so a paste can check it still goes
+//! where the dictation started (see `paste_target`), and brought back to the
+//! front when a paste into it was missed.
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+/// Name of the app that currently has keyboard focus, if it can be determined
+pub fn foreground_app() -> Option<String> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::foreground_app()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::foreground_app()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Opaque identifier of the window that currently has keyboard focus, if it can be
+/// determined. Only meaningful for comparing against another call.
+pub fn foreground_window() -> Option<u64> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::foreground_window()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::foreground_window()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Bring a window from `foreground_window` back to the front. Returns false when it
+/// can't be focused (or on platforms without an implementation).
+pub fn focus_window(window: u64) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        windows::focus_window(window)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::focus_window(window)
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = window;
+        false
+    }
+}
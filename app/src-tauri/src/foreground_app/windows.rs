@@ -0,0 +1,56 @@
+//! Foreground app via the process owning the Win32 foreground window.
+
+use std::path::Path;
+use windows::core::PWSTR;
+use windows::Win32::Foundation::{CloseHandle, HWND};
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetForegroundWindow, GetWindowThreadProcessId, IsWindow, SetForegroundWindow,
+};
+
+pub fn foreground_window() -> Option<u64> {
+    // SAFETY: GetForegroundWindow has no preconditions
+    let foreground = unsafe { GetForegroundWindow() };
+    (!foreground.is_invalid()).then_some(foreground.0 as usize as u64)
+}
+
+pub fn focus_window(window: u64) -> bool {
+    let hwnd = HWND(window as usize as *mut std::ffi::c_void);
+    // SAFETY: IsWindow validates the handle before it is used
+    unsafe { IsWindow(Some(hwnd)).as_bool() && SetForegroundWindow(hwnd).as_bool() }
+}
+
+pub fn foreground_app() -> Option<String> {
+    // SAFETY: Win32 queries on the foreground window's process; the process handle is
+    // closed before returning and the path buffer outlives the call that fills it
+    unsafe {
+        let foreground = GetForegroundWindow();
+        if foreground.is_invalid() {
+            return None;
+        }
+        let mut process_id = 0u32;
+        GetWindowThreadProcessId(foreground, Some(&mut process_id));
+        if process_id == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut length = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut length,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..length as usize]);
+        Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+}
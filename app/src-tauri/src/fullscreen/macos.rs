@@ -0,0 +1,122 @@
+//! Fullscreen check via the Core Graphics window list.
+//!
+//! The window list is ordered front to back; the first window on the normal
+//! layer belongs to the frontmost app. It is fullscreen when it covers a whole
+//! display, which a regular (even zoomed) window never does because of the
+//! menu bar. Reading window bounds needs no extra permission.
+
+use super::ScreenRect;
+use std::ffi::c_void;
+
+type CFTypeRef = *const c_void;
+type CFArrayRef = *const c_void;
+type CFDictionaryRef = *const c_void;
+type CFStringRef = *const c_void;
+
+const WINDOW_LIST_ON_SCREEN_ONLY: u32 = 1 << 0;
+const WINDOW_LIST_EXCLUDE_DESKTOP: u32 = 1 << 4;
+const NULL_WINDOW_ID: u32 = 0;
+const CF_NUMBER_SINT32_TYPE: i32 = 3;
+const NORMAL_WINDOW_LAYER: i32 = 0;
+const MAX_DISPLAYS: u32 = 16;
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct CGRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl From<CGRect> for ScreenRect {
+    fn from(rect: CGRect) -> Self {
+        Self {
+            left: rect.x,
+            top: rect.y,
+            right: rect.x + rect.width,
+            bottom: rect.y + rect.height,
+        }
+    }
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    static kCGWindowLayer: CFStringRef;
+    static kCGWindowBounds: CFStringRef;
+    fn CGWindowListCopyWindowInfo(option: u32, relative_to_window: u32) -> CFArrayRef;
+    fn CGRectMakeWithDictionaryRepresentation(dict: CFDictionaryRef, rect: *mut CGRect) -> bool;
+    fn CGGetActiveDisplayList(max: u32, displays: *mut u32, count: *mut u32) -> i32;
+    fn CGDisplayBounds(display: u32) -> CGRect;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFArrayGetCount(array: CFArrayRef) -> isize;
+    fn CFArrayGetValueAtIndex(array: CFArrayRef, index: isize) -> CFTypeRef;
+    fn CFDictionaryGetValue(dict: CFDictionaryRef, key: CFTypeRef) -> CFTypeRef;
+    fn CFNumberGetValue(number: CFTypeRef, number_type: i32, value: *mut c_void) -> bool;
+    fn CFRelease(cf: CFTypeRef);
+}
+
+/// Bounds of the frontmost window on the normal layer, in global display points
+fn frontmost_window_bounds() -> Option<ScreenRect> {
+    // SAFETY: the returned array is owned here and released below; dictionary values
+    // are borrowed from it and only used before the release
+    unsafe {
+        let windows = CGWindowListCopyWindowInfo(
+            WINDOW_LIST_ON_SCREEN_ONLY | WINDOW_LIST_EXCLUDE_DESKTOP,
+            NULL_WINDOW_ID,
+        );
+        if windows.is_null() {
+            return None;
+        }
+
+        let mut bounds = None;
+        for index in 0..CFArrayGetCount(windows) {
+            let info = CFArrayGetValueAtIndex(windows, index);
+            let layer_value = CFDictionaryGetValue(info, kCGWindowLayer);
+            let mut layer: i32 = -1;
+            if layer_value.is_null()
+                || !CFNumberGetValue(
+                    layer_value,
+                    CF_NUMBER_SINT32_TYPE,
+                    &mut layer as *mut i32 as *mut c_void,
+                )
+                || layer != NORMAL_WINDOW_LAYER
+            {
+                continue;
+            }
+
+            let bounds_value = CFDictionaryGetValue(info, kCGWindowBounds);
+            let mut rect = CGRect::default();
+            if !bounds_value.is_null()
+                && CGRectMakeWithDictionaryRepresentation(bounds_value, &mut rect)
+            {
+                bounds = Some(rect.into());
+            }
+            break;
+        }
+
+        CFRelease(windows);
+        bounds
+    }
+}
+
+pub fn foreground_is_fullscreen() -> bool {
+    let Some(window) = frontmost_window_bounds() else {
+        return false;
+    };
+
+    let mut displays = [0u32; MAX_DISPLAYS as usize];
+    let mut count = 0u32;
+    // SAFETY: displays has room for MAX_DISPLAYS ids and count is a valid out-pointer
+    if unsafe { CGGetActiveDisplayList(MAX_DISPLAYS, displays.as_mut_ptr(), &mut count) } != 0 {
+        return false;
+    }
+
+    displays[..count as usize]
+        .iter()
+        // SAFETY: display ids come straight from CGGetActiveDisplayList
+        .any(|&display| window.covers(&unsafe { CGDisplayBounds(display) }.into()))
+}
@@ -0,0 +1,117 @@
+//! Detection of a fullscreen app in the foreground (games, presentations).
+//!
+//! Checked when a recording starts. With the `suppress_over_fullscreen` setting
+//! the overlay, edge indicator and start/stop sounds stay out of the way while
+//! the recording itself runs as usual. Each check is published as a
+//! `foreground-fullscreen` event so the UI can show what was detected.
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+use crate::state::AppState;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event carrying the [`FullscreenStatus`] of each check
+pub const FOREGROUND_FULLSCREEN_EVENT: &str = "foreground-fullscreen";
+
+/// Screen rectangle in one coordinate space (edges, not origin and size)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenRect {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl ScreenRect {
+    /// Whether this rectangle covers all of `monitor`, allowing a pixel of rounding
+    pub fn covers(&self, monitor: &ScreenRect) -> bool {
+        const SLACK: f64 = 1.0;
+        self.left <= monitor.left + SLACK
+            && self.top <= monitor.top + SLACK
+            && self.right >= monitor.right - SLACK
+            && self.bottom >= monitor.bottom - SLACK
+    }
+}
+
+/// Result of a foreground check
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct FullscreenStatus {
+    /// The foreground window covers its whole monitor
+    pub fullscreen: bool,
+    /// Overlay and sounds are held back for this recording
+    pub suppressed: bool,
+}
+
+/// Whether the foreground window covers its whole monitor. Always false on
+/// platforms without an implementation.
+pub fn foreground_is_fullscreen() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        windows::foreground_is_fullscreen()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::foreground_is_fullscreen()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        false
+    }
+}
+
+/// Check the foreground app as a recording starts, hide or restore the overlay
+/// and edge indicator accordingly, and publish the result
+pub fn check_foreground(app: &AppHandle) -> FullscreenStatus {
+    let enabled: bool = crate::settings::read_setting(app, "suppress_over_fullscreen");
+    let fullscreen = foreground_is_fullscreen();
+    let status = FullscreenStatus {
+        fullscreen,
+        suppressed: enabled && fullscreen,
+    };
+
+    let was_suppressed = app.try_state::<AppState>().is_some_and(|state| {
+        state
+            .feedback_suppressed
+            .swap(status.suppressed, Ordering::SeqCst)
+    });
+    if status.suppressed != was_suppressed {
+        set_overlay_hidden(app, status.suppressed);
+    }
+
+    let _ = app.emit(FOREGROUND_FULLSCREEN_EVENT, status);
+    status
+}
+
+/// Whether the current recording started over a fullscreen app with suppression on
+pub fn is_suppressed(app: &AppHandle) -> bool {
+    app.try_state::<AppState>()
+        .is_some_and(|state| state.feedback_suppressed.load(Ordering::SeqCst))
+}
+
+fn set_overlay_hidden(app: &AppHandle, hidden: bool) {
+    let tray_only = app
+        .try_state::<crate::tray_status::TrayStatus>()
+        .is_some_and(|status| status.enabled());
+    if let Some(window) = app.get_webview_window(crate::overlay::OVERLAY_LABEL) {
+        let result = if hidden {
+            window.hide()
+        } else if !tray_only {
+            window.show()
+        } else {
+            Ok(())
+        };
+        if let Err(e) = result {
+            log::warn!("Failed to change overlay visibility: {}", e);
+        }
+    }
+    if hidden {
+        if let Some(window) = app.get_webview_window(crate::edge_indicator::EDGE_INDICATOR_LABEL) {
+            let _ = window.hide();
+        }
+    }
+}
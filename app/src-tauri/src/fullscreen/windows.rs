@@ -0,0 +1,50 @@
+//! Fullscreen check via the Win32 foreground window and its monitor.
+
+use super::ScreenRect;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONULL,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetDesktopWindow, GetForegroundWindow, GetShellWindow, GetWindowRect,
+};
+
+fn screen_rect(rect: RECT) -> ScreenRect {
+    ScreenRect {
+        left: f64::from(rect.left),
+        top: f64::from(rect.top),
+        right: f64::from(rect.right),
+        bottom: f64::from(rect.bottom),
+    }
+}
+
+pub fn foreground_is_fullscreen() -> bool {
+    // SAFETY: Win32 queries on the foreground window; all out-params are initialized locals
+    unsafe {
+        let foreground = GetForegroundWindow();
+        // The desktop and shell windows cover the screen but aren't fullscreen apps
+        if foreground.is_invalid()
+            || foreground == GetDesktopWindow()
+            || foreground == GetShellWindow()
+        {
+            return false;
+        }
+
+        let mut window = RECT::default();
+        if GetWindowRect(foreground, &mut window).is_err() {
+            return false;
+        }
+        let monitor = MonitorFromWindow(foreground, MONITOR_DEFAULTTONULL);
+        if monitor.is_invalid() {
+            return false;
+        }
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return false;
+        }
+        screen_rect(window).covers(&screen_rect(info.rcMonitor))
+    }
+}
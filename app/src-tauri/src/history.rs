@@ -1,146 +1,1424 @@
-use chrono::{DateTime, Utc};
+//! Dictation history, kept in a SQLite database in the app data directory.
+//!
+//! Each entry records the cleaned text along with what produced it: the raw
+//! transcript, the providers, how long the recording was and which app it was
+//! dictated into. History from the earlier `history.json` file is imported the
+//! first time the database is opened.
+//!
+//! History is pruned to the `history_retention_days`, `history_max_entries` and
+//! `history_max_disk_mb` settings at startup, whenever an entry is added and
+//! periodically in the background. Pinned favorites are never pruned; when the
+//! size limit removes entries the file is vacuumed so the space is given back.
+//! Each pass that removes something emits a summary to the frontend.
+//!
+//! An entry can be cleaned up again with the current provider and prompt; each
+//! result is kept as a revision next to the original text. Audio isn't stored,
+//! so it is the raw transcript that gets re-cleaned.
+//!
+//! Dictations can also be grouped into a named session, such as a meeting: every
+//! entry added while a session is open belongs to it, and the session's entries
+//! can be exported as one transcript.
+//!
+//! Entries can be tagged (`email`, `blog`, `journal`, ...) and given a free-form
+//! note. Tags are stored lowercase without the leading `#`; search matches notes
+//! and tags as well as the text.
+//!
+//! With `history_keep_audio` on, each dictation's recording is kept as a file
+//! beside the database (see `history_audio`); the table here only notes which
+//! entries have one, with its type and waveform preview.
+//!
+//! Deleting an entry (or clearing history) moves it to the trash, from which it
+//! can be restored for `history_trash_days`; after that, or when the trash is
+//! emptied, it is gone for good. Trashed entries are left out of every listing,
+//! search and export.
+//!
+//! Entries removed for good by the user are remembered by ID, so history sync
+//! (see `history_sync`) deletes them on other devices instead of bringing them
+//! back. Pruned entries are remembered too, so sync doesn't import them again,
+//! but only on this device, since each device has its own limits. Both are
+//! forgotten after `DELETION_MEMORY_DAYS`.
+
+use crate::error::AppError;
+use crate::history_crypto::{self, HistoryKey};
+use chrono::{DateTime, Duration, SecondsFormat, SubsecRound, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::RwLock;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
+/// Event emitted with a `PruneSummary` when pruning removed entries
+pub const HISTORY_PRUNED_EVENT: &str = "history-pruned";
+
+/// Event emitted with the `HistoryEntry` of a new or restored dictation
+pub const HISTORY_ENTRY_ADDED_EVENT: &str = "history-entry-added";
+
+/// Event emitted with a `HistoryEntry` as stored after it changed
+pub const HISTORY_ENTRY_UPDATED_EVENT: &str = "history-entry-updated";
+
+/// Event emitted with the ID of an entry moved to the trash
+pub const HISTORY_ENTRY_DELETED_EVENT: &str = "history-entry-deleted";
+
+/// Event emitted when many entries changed at once, so windows reload history
+pub const HISTORY_CHANGED_EVENT: &str = "history-changed";
+
+/// Entries removed at a time while pruning down to the size limit
+const SIZE_PRUNE_BATCH: usize = 50;
+
+/// Days a removed entry is remembered so sync doesn't bring it back
+pub const DELETION_MEMORY_DAYS: i64 = 180;
+
+/// Days a deleted entry stays restorable when the setting is missing
+pub const DEFAULT_TRASH_DAYS: u32 = 30;
+
+/// Limits history is pruned to; `None` or zero means no limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryLimits {
+    pub max_days: Option<u32>,
+    pub max_entries: Option<u32>,
+    pub max_disk_mb: Option<u32>,
+}
+
+/// A tag and how many entries have it
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TagCount {
+    pub tag: String,
+    pub entries: u64,
+}
+
+/// An app dictated into and how many entries went to it
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AppCount {
+    pub app: String,
+    pub entries: u64,
+}
+
+/// A deleted entry waiting in the trash
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TrashedEntry {
+    #[serde(flatten)]
+    pub entry: HistoryEntry,
+    pub trashed_at: DateTime<Utc>,
+}
+
+/// What is known about a kept recording besides the file itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredAudio {
+    pub mime_type: String,
+    /// Peak levels from 0 to 1, evenly spaced over the recording
+    pub waveform: Vec<f32>,
+}
+
+/// What a pruning pass removed, by the limit that removed it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PruneSummary {
+    pub by_age: usize,
+    pub by_count: usize,
+    pub by_size: usize,
+    /// Database space no longer used by history
+    pub freed_bytes: u64,
+}
+
+impl PruneSummary {
+    pub fn total(&self) -> usize {
+        self.by_age + self.by_count + self.by_size
+    }
+}
+
+/// An entry deleted by the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeletedEntry {
+    pub id: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
 /// A single dictation history entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
     pub id: String,
     pub timestamp: DateTime<Utc>,
     pub text: String,
+    #[serde(flatten)]
+    pub details: EntryDetails,
+    /// Favorites are kept regardless of the retention setting
+    #[serde(default)]
+    pub pinned: bool,
+    /// Text of the newest revision, if the entry has been re-cleaned
+    #[serde(default)]
+    pub revised_text: Option<String>,
+    /// Session the entry was dictated in
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Whether the recording was kept with the entry
+    #[serde(default)]
+    pub has_audio: bool,
+    /// Sorted, without the leading `#`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// A named group of dictations with start and end times
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Session {
+    pub id: String,
+    pub name: String,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the session is still open
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl Session {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            started_at: parse_timestamp(row.get("started_at")?)?,
+            ended_at: row
+                .get::<_, Option<String>>("ended_at")?
+                .map(parse_timestamp)
+                .transpose()?,
+        })
+    }
+}
+
+/// A later cleanup of a history entry's transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRevision {
+    pub id: String,
+    pub entry_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub text: String,
+    pub llm_provider: Option<String>,
+}
+
+impl HistoryRevision {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            entry_id: row.get("entry_id")?,
+            timestamp: parse_timestamp(row.get("timestamp")?)?,
+            text: row.get("text")?,
+            llm_provider: row.get("llm_provider")?,
+        })
+    }
+}
+
+/// Where a dictation came from, as far as it is known
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EntryDetails {
+    /// Transcript before LLM cleanup
+    pub raw_text: Option<String>,
+    pub stt_provider: Option<String>,
+    pub llm_provider: Option<String>,
+    /// Length of the recording
+    pub duration_seconds: Option<f64>,
+    /// App that had focus when the recording started
+    pub target_app: Option<String>,
+    /// Time from the end of the recording until the text was delivered
+    pub latency_seconds: Option<f64>,
+}
+
+/// Period of history to read; either end may be left open
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryRange {
+    /// Earliest entry to include
+    pub from: Option<DateTime<Utc>>,
+    /// Entries at or after this time are left out
+    pub to: Option<DateTime<Utc>>,
 }
 
 impl HistoryEntry {
-    pub fn new(text: String) -> Self {
+    pub fn new(text: String, details: EntryDetails) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
-            timestamp: Utc::now(),
+            timestamp: now(),
             text,
+            details,
+            pinned: false,
+            revised_text: None,
+            session_id: None,
+            has_audio: false,
+            tags: Vec::new(),
+            note: None,
         }
     }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            timestamp: parse_timestamp(row.get("timestamp")?)?,
+            text: row.get("text")?,
+            details: EntryDetails {
+                raw_text: row.get("raw_text")?,
+                stt_provider: row.get("stt_provider")?,
+                llm_provider: row.get("llm_provider")?,
+                duration_seconds: row.get("duration_seconds")?,
+                target_app: row.get("target_app")?,
+                latency_seconds: row.get("latency_seconds")?,
+            },
+            pinned: row.get("pinned")?,
+            revised_text: row.get("revised_text")?,
+            session_id: row.get("session_id")?,
+            has_audio: row.get("has_audio")?,
+            tags: split_tags(row.get("tags")?),
+            note: row.get("note")?,
+        })
+    }
+}
+
+fn parse_timestamp(timestamp: String) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, e.into())
+        })
+}
+
+/// Tags as stored, lowercase without `#` and with spaces turned into dashes, or
+/// `None` for a tag with nothing left
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').trim().to_lowercase();
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join("-");
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Tags from the space-separated list the entry query returns
+fn split_tags(tags: Option<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .unwrap_or_default()
+        .split(' ')
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect();
+    tags.sort();
+    tags
+}
+
+/// The current time at the precision timestamps are stored with
+fn now() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(3)
+}
+
+/// Fixed-width UTC timestamps, so they sort as text
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
 }
 
-/// Storage for dictation history entries
-#[derive(Debug, Serialize, Deserialize, Default)]
-struct HistoryData {
+/// Oldest time an entry may have to be kept, or `None` to keep everything
+pub fn retention_cutoff(days: Option<u32>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    days.filter(|days| *days > 0)
+        .map(|days| now - Duration::days(i64::from(days)))
+}
+
+/// `query` as a LIKE pattern matching it anywhere, with wildcards escaped
+pub fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Legacy JSON history file
+#[derive(Deserialize)]
+struct LegacyHistory {
     entries: Vec<HistoryEntry>,
 }
 
-/// Manages loading and saving of dictation history
-pub struct HistoryStorage {
-    data: RwLock<HistoryData>,
-    file_path: PathBuf,
+const SELECT_ENTRIES: &str = "SELECT id, timestamp, text, raw_text, stt_provider, llm_provider, \
+     duration_seconds, target_app, latency_seconds, pinned, session_id, (SELECT r.text FROM revisions r \
+     WHERE r.entry_id = history.id ORDER BY r.timestamp DESC LIMIT 1) AS revised_text, \
+     EXISTS (SELECT 1 FROM audio a WHERE a.entry_id = history.id) AS has_audio, \
+     (SELECT group_concat(t.tag, ' ') FROM tags t WHERE t.entry_id = history.id) AS tags, \
+     note, trashed_at FROM history";
+
+/// Schema changes after the first version, applied in order and tracked with
+/// SQLite's `user_version`
+const MIGRATIONS: &[&str] = &[
+    "ALTER TABLE history ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0",
+    "CREATE TABLE revisions (
+        id TEXT PRIMARY KEY,
+        entry_id TEXT NOT NULL REFERENCES history (id) ON DELETE CASCADE,
+        timestamp TEXT NOT NULL,
+        text TEXT NOT NULL,
+        llm_provider TEXT
+    );
+    CREATE INDEX revisions_entry ON revisions (entry_id)",
+    "ALTER TABLE history ADD COLUMN latency_seconds REAL",
+    "CREATE TABLE sessions (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        started_at TEXT NOT NULL,
+        ended_at TEXT
+    );
+    ALTER TABLE history ADD COLUMN session_id TEXT REFERENCES sessions (id) ON DELETE SET NULL",
+    "CREATE TABLE audio (
+        entry_id TEXT PRIMARY KEY REFERENCES history (id) ON DELETE CASCADE,
+        mime_type TEXT NOT NULL,
+        waveform TEXT NOT NULL
+    )",
+    "ALTER TABLE history ADD COLUMN note TEXT;
+    CREATE TABLE tags (
+        entry_id TEXT NOT NULL REFERENCES history (id) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (entry_id, tag)
+    );
+    CREATE INDEX tags_tag ON tags (tag)",
+    "CREATE TABLE deleted_entries (
+        id TEXT PRIMARY KEY,
+        deleted_at TEXT NOT NULL
+    )",
+    "CREATE INDEX history_target_app ON history (target_app COLLATE NOCASE)",
+    "ALTER TABLE history ADD COLUMN trashed_at TEXT;
+    CREATE INDEX history_trashed_at ON history (trashed_at)",
+    "ALTER TABLE history ADD COLUMN synced INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE deleted_entries ADD COLUMN pruned INTEGER NOT NULL DEFAULT 0",
+];
+
+const SELECT_SESSIONS: &str = "SELECT id, name, started_at, ended_at FROM sessions";
+
+/// Error returned while encrypted history waits for its passphrase
+pub const LOCKED_ERROR: &str = "History is locked; enter the passphrase to open it";
+
+/// The command error for a failed history operation: locked history and a wrong
+/// passphrase are told apart from the database failing
+pub fn command_error(message: String) -> AppError {
+    match message.as_str() {
+        LOCKED_ERROR => AppError::Locked(message),
+        history_crypto::WRONG_PASSPHRASE_ERROR => AppError::InvalidInput(message),
+        _ => AppError::Storage(message),
+    }
 }
 
-impl HistoryStorage {
-    /// Create a new history storage with the given app data directory
-    pub fn new(app_data_dir: PathBuf) -> Self {
-        let file_path = app_data_dir.join("history.json");
+const HISTORY_DB_FILE: &str = "history.db";
+
+/// Create the tables and bring an older database up to date
+fn prepare_schema(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "PRAGMA foreign_keys = ON;
+        CREATE TABLE IF NOT EXISTS history (
+            id TEXT PRIMARY KEY,
+            timestamp TEXT NOT NULL,
+            text TEXT NOT NULL,
+            raw_text TEXT,
+            stt_provider TEXT,
+            llm_provider TEXT,
+            duration_seconds REAL,
+            target_app TEXT
+        );
+        CREATE INDEX IF NOT EXISTS history_timestamp ON history (timestamp);",
+    )
+    .map_err(|e| format!("Failed to create history table: {}", e))?;
+
+    let version: u32 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read history schema version: {}", e))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        conn.execute_batch(&format!(
+            "{}; PRAGMA user_version = {};",
+            migration,
+            index + 1
+        ))
+        .map_err(|e| format!("Failed to update history schema: {}", e))?;
+    }
+    Ok(())
+}
 
-        // Ensure the directory exists
-        if let Some(parent) = file_path.parent() {
-            let _ = fs::create_dir_all(parent);
+/// Open the database file with `key`, encrypting a plaintext file first
+fn open_connection(db_path: &Path, key: Option<&HistoryKey>) -> Result<Connection, String> {
+    if let Some(key) = key {
+        if history_crypto::is_plaintext(db_path) {
+            let plaintext =
+                Connection::open(db_path).map_err(|e| format!("Failed to open history: {}", e))?;
+            let encrypted = history_crypto::export_rekeyed(&plaintext, db_path, Some(key))?;
+            drop(plaintext);
+            fs::rename(&encrypted, db_path)
+                .map_err(|e| format!("Failed to replace history database: {}", e))?;
+            log::info!("Encrypted the history database");
         }
+    }
+
+    let conn = Connection::open(db_path).map_err(|e| format!("Failed to open history: {}", e))?;
+    history_crypto::apply_key(&conn, key)?;
+    prepare_schema(&conn)?;
+    Ok(conn)
+}
+
+/// Manages the dictation history database
+pub struct HistoryStorage {
+    /// `None` while an encrypted database waits to be unlocked
+    conn: Mutex<Option<Connection>>,
+    /// Database file, or `None` for in-memory history
+    db_path: Option<PathBuf>,
+}
+
+/// Access to the open database connection
+pub struct ConnectionGuard<'a>(MutexGuard<'a, Option<Connection>>);
 
-        // Load existing history or use empty
-        let data = Self::load_from_file(&file_path).unwrap_or_default();
+impl Deref for ConnectionGuard<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        // Only constructed around an open connection
+        self.0.as_ref().expect("history connection is open")
+    }
+}
+
+impl HistoryStorage {
+    /// Open (or create) the history database in the given app data directory,
+    /// decrypting it with `key` when history is encrypted. A plaintext database is
+    /// encrypted in place the first time it is opened with a key.
+    pub fn open(app_data_dir: PathBuf, key: Option<&HistoryKey>) -> Result<Self, String> {
+        let _ = fs::create_dir_all(&app_data_dir);
+        let db_path = app_data_dir.join(HISTORY_DB_FILE);
+        let conn = open_connection(&db_path, key)?;
+
+        let storage = Self {
+            conn: Mutex::new(Some(conn)),
+            db_path: Some(db_path),
+        };
+        storage.import_legacy(&app_data_dir.join("history.json"));
+        Ok(storage)
+    }
 
+    /// History that stays unavailable until [`HistoryStorage::unlock`] is called
+    pub fn locked(app_data_dir: PathBuf) -> Self {
         Self {
-            data: RwLock::new(data),
-            file_path,
+            conn: Mutex::new(None),
+            db_path: Some(app_data_dir.join(HISTORY_DB_FILE)),
         }
     }
 
-    /// Load history from the JSON file
-    fn load_from_file(file_path: &PathBuf) -> Option<HistoryData> {
-        let content = fs::read_to_string(file_path).ok()?;
-        serde_json::from_str(&content).ok()
+    /// History in memory only, for tests
+    pub fn in_memory() -> Result<Self, String> {
+        let conn =
+            Connection::open_in_memory().map_err(|e| format!("Failed to open history: {}", e))?;
+        prepare_schema(&conn)?;
+        Ok(Self {
+            conn: Mutex::new(Some(conn)),
+            db_path: None,
+        })
     }
 
-    /// Save current history to disk
-    fn save(&self) -> Result<(), String> {
-        let data = self
-            .data
-            .read()
-            .map_err(|e| format!("Failed to read history: {}", e))?;
+    /// Whether history is waiting to be unlocked
+    pub fn is_locked(&self) -> bool {
+        self.conn.lock().map(|conn| conn.is_none()).unwrap_or(true)
+    }
 
-        let content = serde_json::to_string_pretty(&*data)
-            .map_err(|e| format!("Failed to serialize history: {}", e))?;
+    /// Open locked history with its key
+    pub fn unlock(&self, key: &HistoryKey) -> Result<(), String> {
+        let Some(db_path) = &self.db_path else {
+            return Ok(());
+        };
+        let conn = open_connection(db_path, Some(key))?;
+        *self
+            .conn
+            .lock()
+            .map_err(|e| format!("Failed to lock history: {}", e))? = Some(conn);
+        if let Some(app_data_dir) = db_path.parent() {
+            self.import_legacy(&app_data_dir.join("history.json"));
+        }
+        Ok(())
+    }
 
-        fs::write(&self.file_path, content)
-            .map_err(|e| format!("Failed to write history file: {}", e))?;
+    /// Rewrite the database encrypted with `key`, or as plaintext for `None`
+    pub fn set_key(&self, key: Option<&HistoryKey>) -> Result<(), String> {
+        let Some(db_path) = &self.db_path else {
+            return Err("In-memory history can't be encrypted".to_string());
+        };
+        let mut slot = self
+            .conn
+            .lock()
+            .map_err(|e| format!("Failed to lock history: {}", e))?;
+        let conn = slot.as_ref().ok_or_else(|| LOCKED_ERROR.to_string())?;
+        let rekeyed = history_crypto::export_rekeyed(conn, db_path, key)?;
 
+        // Close the old file before replacing it
+        *slot = None;
+        if let Err(e) = fs::rename(&rekeyed, db_path) {
+            let _ = fs::remove_file(&rekeyed);
+            return Err(format!(
+                "Failed to replace history database, restart to open it again: {}",
+                e
+            ));
+        }
+        *slot = Some(open_connection(db_path, key)?);
         Ok(())
     }
 
-    /// Add a new entry to the history
-    pub fn add_entry(&self, text: String) -> Result<HistoryEntry, String> {
-        let entry = HistoryEntry::new(text);
-        {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
-
-            // Add to the beginning (newest first)
-            data.entries.insert(0, entry.clone());
+    fn conn(&self) -> Result<ConnectionGuard<'_>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| format!("Failed to lock history: {}", e))?;
+        if conn.is_none() {
+            return Err(LOCKED_ERROR.to_string());
+        }
+        Ok(ConnectionGuard(conn))
+    }
 
-            // Limit to 500 entries
-            if data.entries.len() > 500 {
-                data.entries.truncate(500);
+    /// Move entries from the old JSON history into the database, then delete the file
+    /// so it is only imported once and no plaintext copy is left next to the database
+    fn import_legacy(&self, json_path: &Path) {
+        // Earlier versions kept the imported file under a new name
+        let _ = fs::remove_file(json_path.with_extension("json.imported"));
+        let Ok(content) = fs::read_to_string(json_path) else {
+            return;
+        };
+        let imported = serde_json::from_str::<LegacyHistory>(&content)
+            .map_err(|e| e.to_string())
+            .and_then(|legacy| {
+                legacy
+                    .entries
+                    .iter()
+                    .try_for_each(|entry| self.insert(entry))
+                    .map(|_| legacy.entries.len())
+            });
+        match imported {
+            Ok(count) => {
+                log::info!("Imported {} history entries from {:?}", count, json_path);
+                if let Err(e) = fs::remove_file(json_path) {
+                    log::warn!("Failed to delete {:?}: {}", json_path, e);
+                }
             }
+            Err(e) => log::error!("Failed to import {:?}: {}", json_path, e),
         }
-        self.save()?;
+    }
+
+    fn insert(&self, entry: &HistoryEntry) -> Result<(), String> {
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO history (id, timestamp, text, raw_text, stt_provider, \
+                 llm_provider, duration_seconds, target_app, latency_seconds, pinned, \
+                 session_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    entry.id,
+                    format_timestamp(entry.timestamp),
+                    entry.text,
+                    entry.details.raw_text,
+                    entry.details.stt_provider,
+                    entry.details.llm_provider,
+                    entry.details.duration_seconds,
+                    entry.details.target_app,
+                    entry.details.latency_seconds,
+                    entry.pinned,
+                    entry.session_id,
+                ],
+            )
+            .map_err(|e| format!("Failed to save history entry: {}", e))?;
+        Ok(())
+    }
+
+    /// Add a new entry to the history, in the open session if there is one
+    pub fn add_entry(&self, text: String, details: EntryDetails) -> Result<HistoryEntry, String> {
+        let mut entry = HistoryEntry::new(text, details);
+        entry.session_id = self.active_session()?.map(|session| session.id);
+        self.insert(&entry)?;
         Ok(entry)
     }
 
+    fn query_sessions(
+        &self,
+        filter: &str,
+        args: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<Session>, String> {
+        let sql = format!("{} {} ORDER BY started_at DESC", SELECT_SESSIONS, filter);
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(&sql)
+            .map_err(|e| format!("Failed to read sessions: {}", e))?;
+        let sessions: Vec<Session> = statement
+            .query_map(args, Session::from_row)
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("Failed to read sessions: {}", e))?;
+        Ok(sessions)
+    }
+
+    /// The session new entries are added to, if one is open
+    pub fn active_session(&self) -> Result<Option<Session>, String> {
+        Ok(self
+            .query_sessions("WHERE ended_at IS NULL", &[])?
+            .into_iter()
+            .next())
+    }
+
+    /// All sessions, newest first
+    pub fn list_sessions(&self) -> Result<Vec<Session>, String> {
+        self.query_sessions("", &[])
+    }
+
+    /// Get a single session by ID
+    pub fn get_session(&self, id: &str) -> Result<Option<Session>, String> {
+        Ok(self
+            .query_sessions("WHERE id = ?1", &[&id])?
+            .into_iter()
+            .next())
+    }
+
+    /// Open a new session, ending the one that is open
+    pub fn start_session(&self, name: &str) -> Result<Session, String> {
+        self.end_session()?;
+        let session = Session {
+            id: Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            started_at: now(),
+            ended_at: None,
+        };
+        self.conn()?
+            .execute(
+                "INSERT INTO sessions (id, name, started_at) VALUES (?1, ?2, ?3)",
+                params![
+                    session.id,
+                    session.name,
+                    format_timestamp(session.started_at)
+                ],
+            )
+            .map_err(|e| format!("Failed to start session: {}", e))?;
+        Ok(session)
+    }
+
+    /// End the open session, returning it, or `None` if no session was open
+    pub fn end_session(&self) -> Result<Option<Session>, String> {
+        let Some(mut session) = self.active_session()? else {
+            return Ok(None);
+        };
+        let ended_at = now();
+        self.conn()?
+            .execute(
+                "UPDATE sessions SET ended_at = ?1 WHERE id = ?2",
+                params![format_timestamp(ended_at), session.id],
+            )
+            .map_err(|e| format!("Failed to end session: {}", e))?;
+        session.ended_at = Some(ended_at);
+        Ok(Some(session))
+    }
+
+    /// Entries dictated in a session, newest first
+    pub fn session_entries(&self, session_id: &str) -> Result<Vec<HistoryEntry>, String> {
+        self.query("session_id = ?1", &[&session_id], None)
+    }
+
+    /// Entries outside the trash matching `condition` (all of them when empty),
+    /// newest first
+    fn query(
+        &self,
+        condition: &str,
+        args: &[&dyn rusqlite::ToSql],
+        limit: Option<usize>,
+    ) -> Result<Vec<HistoryEntry>, String> {
+        let limit = limit.map_or(-1, |n| n as i64);
+        let condition = if condition.is_empty() {
+            String::new()
+        } else {
+            format!("AND ({})", condition)
+        };
+        let sql = format!(
+            "{} WHERE trashed_at IS NULL {} ORDER BY timestamp DESC LIMIT {}",
+            SELECT_ENTRIES, condition, limit
+        );
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(&sql)
+            .map_err(|e| format!("Failed to read history: {}", e))?;
+        let entries: Vec<HistoryEntry> = statement
+            .query_map(args, HistoryEntry::from_row)
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("Failed to read history: {}", e))?;
+        Ok(entries)
+    }
+
     /// Get all history entries (newest first), optionally limited
     pub fn get_all(&self, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
-        let data = self
-            .data
-            .read()
-            .map_err(|e| format!("Failed to read history: {}", e))?;
+        self.query("", &[], limit)
+    }
+
+    /// Entries dictated on this device rather than synced from another, newest first
+    pub fn get_dictated(&self, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
+        self.query("synced = 0", &[], limit)
+    }
+
+    /// Entries whose cleaned or raw text contains `query`, ignoring ASCII case
+    pub fn search(&self, query: &str, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
+        let pattern = like_pattern(query);
+        self.query(
+            "text LIKE ?1 ESCAPE '\\' OR raw_text LIKE ?1 ESCAPE '\\' \
+             OR note LIKE ?1 ESCAPE '\\' \
+             OR id IN (SELECT entry_id FROM tags WHERE tag LIKE ?1 ESCAPE '\\')",
+            &[&pattern],
+            limit,
+        )
+    }
+
+    /// Entries in the range, newest first
+    pub fn get_range(&self, range: &HistoryRange) -> Result<Vec<HistoryEntry>, String> {
+        let from = range.from.map(format_timestamp);
+        let to = range.to.map(format_timestamp);
+        self.query(
+            "(?1 IS NULL OR timestamp >= ?1) AND (?2 IS NULL OR timestamp < ?2)",
+            &[&from, &to],
+            None,
+        )
+    }
+
+    /// Pinned entries, newest first
+    pub fn get_pinned(&self) -> Result<Vec<HistoryEntry>, String> {
+        self.query("pinned = 1", &[], None)
+    }
+
+    /// Pin or unpin an entry, returning whether it exists
+    pub fn set_pinned(&self, id: &str, pinned: bool) -> Result<bool, String> {
+        let updated = self
+            .conn()?
+            .execute(
+                "UPDATE history SET pinned = ?1 WHERE id = ?2",
+                params![pinned, id],
+            )
+            .map_err(|e| format!("Failed to update history entry: {}", e))?;
+        Ok(updated > 0)
+    }
+
+    /// Replace an entry's tags, returning the tags as stored, or `None` when there
+    /// is no such entry
+    pub fn set_tags(&self, id: &str, tags: &[String]) -> Result<Option<Vec<String>>, String> {
+        let mut tags: Vec<String> = tags.iter().filter_map(|tag| normalize_tag(tag)).collect();
+        tags.sort();
+        tags.dedup();
+
+        let conn = self.conn()?;
+        let transaction = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to update tags: {}", e))?;
+        let exists: bool = transaction
+            .query_row(
+                "SELECT EXISTS (SELECT 1 FROM history WHERE id = ?1)",
+                params![id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to update tags: {}", e))?;
+        if !exists {
+            return Ok(None);
+        }
+        transaction
+            .execute("DELETE FROM tags WHERE entry_id = ?1", params![id])
+            .map_err(|e| format!("Failed to update tags: {}", e))?;
+        for tag in &tags {
+            transaction
+                .execute(
+                    "INSERT INTO tags (entry_id, tag) VALUES (?1, ?2)",
+                    params![id, tag],
+                )
+                .map_err(|e| format!("Failed to update tags: {}", e))?;
+        }
+        transaction
+            .commit()
+            .map_err(|e| format!("Failed to update tags: {}", e))?;
+        Ok(Some(tags))
+    }
 
-        let entries = match limit {
-            Some(n) => data.entries.iter().take(n).cloned().collect(),
-            None => data.entries.clone(),
+    /// Set or clear an entry's note; blank notes are cleared
+    pub fn set_note(&self, id: &str, note: Option<&str>) -> Result<bool, String> {
+        let note = note.map(str::trim).filter(|note| !note.is_empty());
+        let updated = self
+            .conn()?
+            .execute(
+                "UPDATE history SET note = ?1 WHERE id = ?2",
+                params![note, id],
+            )
+            .map_err(|e| format!("Failed to update history entry: {}", e))?;
+        Ok(updated > 0)
+    }
+
+    /// Every tag in use with how many entries have it, most used first
+    pub fn list_tags(&self) -> Result<Vec<TagCount>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT tag, count(*) FROM tags \
+                 WHERE entry_id IN (SELECT id FROM history WHERE trashed_at IS NULL) \
+                 GROUP BY tag ORDER BY count(*) DESC, tag",
+            )
+            .map_err(|e| format!("Failed to read tags: {}", e))?;
+        let tags: Vec<TagCount> = statement
+            .query_map([], |row| {
+                Ok(TagCount {
+                    tag: row.get(0)?,
+                    entries: row.get(1)?,
+                })
+            })
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("Failed to read tags: {}", e))?;
+        Ok(tags)
+    }
+
+    /// Entries with a tag (given with or without `#`), newest first
+    pub fn get_by_tag(&self, tag: &str, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
+        let Some(tag) = normalize_tag(tag) else {
+            return Ok(Vec::new());
         };
+        self.query(
+            "id IN (SELECT entry_id FROM tags WHERE tag = ?1)",
+            &[&tag],
+            limit,
+        )
+    }
 
-        Ok(entries)
+    /// Apps dictated into during the range, with how many entries went to each,
+    /// most used first
+    pub fn list_apps(&self, range: &HistoryRange) -> Result<Vec<AppCount>, String> {
+        let from = range.from.map(format_timestamp);
+        let to = range.to.map(format_timestamp);
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT target_app, count(*) FROM history \
+                 WHERE trashed_at IS NULL AND target_app IS NOT NULL AND target_app != '' \
+                 AND (?1 IS NULL OR timestamp >= ?1) AND (?2 IS NULL OR timestamp < ?2) \
+                 GROUP BY target_app COLLATE NOCASE ORDER BY count(*) DESC, target_app",
+            )
+            .map_err(|e| format!("Failed to read history apps: {}", e))?;
+        let apps: Vec<AppCount> = statement
+            .query_map(params![from, to], |row| {
+                Ok(AppCount {
+                    app: row.get(0)?,
+                    entries: row.get(1)?,
+                })
+            })
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("Failed to read history apps: {}", e))?;
+        Ok(apps)
     }
 
-    /// Delete an entry by ID
-    pub fn delete(&self, id: &str) -> Result<bool, String> {
-        let deleted = {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
-
-            let initial_len = data.entries.len();
-            data.entries.retain(|e| e.id != id);
-            data.entries.len() < initial_len
+    /// Entries dictated into an app (ignoring case) during the range, newest first
+    pub fn get_by_app(
+        &self,
+        app: &str,
+        range: &HistoryRange,
+        limit: Option<usize>,
+    ) -> Result<Vec<HistoryEntry>, String> {
+        let from = range.from.map(format_timestamp);
+        let to = range.to.map(format_timestamp);
+        self.query(
+            "target_app = ?1 COLLATE NOCASE \
+             AND (?2 IS NULL OR timestamp >= ?2) AND (?3 IS NULL OR timestamp < ?3)",
+            &[&app.trim(), &from, &to],
+            limit,
+        )
+    }
+
+    /// Get a single entry by ID
+    pub fn get(&self, id: &str) -> Result<Option<HistoryEntry>, String> {
+        Ok(self.query("id = ?1", &[&id], Some(1))?.pop())
+    }
+
+    /// Store a re-cleaned version of an entry's transcript
+    pub fn add_revision(
+        &self,
+        entry_id: &str,
+        text: String,
+        llm_provider: Option<String>,
+    ) -> Result<HistoryRevision, String> {
+        if self.get(entry_id)?.is_none() {
+            return Err(format!("History entry {} not found", entry_id));
+        }
+        let revision = HistoryRevision {
+            id: Uuid::new_v4().to_string(),
+            entry_id: entry_id.to_string(),
+            timestamp: now(),
+            text,
+            llm_provider,
         };
+        self.conn()?
+            .execute(
+                "INSERT INTO revisions (id, entry_id, timestamp, text, llm_provider) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    revision.id,
+                    revision.entry_id,
+                    format_timestamp(revision.timestamp),
+                    revision.text,
+                    revision.llm_provider,
+                ],
+            )
+            .map_err(|e| format!("Failed to save history revision: {}", e))?;
+        Ok(revision)
+    }
 
-        if deleted {
-            self.save()?;
+    /// Revisions of an entry, newest first
+    pub fn get_revisions(&self, entry_id: &str) -> Result<Vec<HistoryRevision>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT id, entry_id, timestamp, text, llm_provider FROM revisions \
+                 WHERE entry_id = ?1 ORDER BY timestamp DESC",
+            )
+            .map_err(|e| format!("Failed to read history revisions: {}", e))?;
+        let revisions: Vec<HistoryRevision> = statement
+            .query_map(params![entry_id], HistoryRevision::from_row)
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("Failed to read history revisions: {}", e))?;
+        Ok(revisions)
+    }
+
+    /// Note that an entry's recording was kept, returning false when there is no
+    /// such entry
+    pub fn set_audio(
+        &self,
+        entry_id: &str,
+        mime_type: &str,
+        waveform: &[f32],
+    ) -> Result<bool, String> {
+        if self.get(entry_id)?.is_none() {
+            return Ok(false);
         }
+        let waveform = serde_json::to_string(waveform)
+            .map_err(|e| format!("Failed to serialize waveform: {}", e))?;
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO audio (entry_id, mime_type, waveform) VALUES (?1, ?2, ?3)",
+                params![entry_id, mime_type, waveform],
+            )
+            .map_err(|e| format!("Failed to save history audio: {}", e))?;
+        Ok(true)
+    }
+
+    /// Type and waveform of an entry's recording, if it was kept
+    pub fn get_audio(&self, entry_id: &str) -> Result<Option<StoredAudio>, String> {
+        let stored = self
+            .conn()?
+            .query_row(
+                "SELECT mime_type, waveform FROM audio WHERE entry_id = ?1",
+                params![entry_id],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to read history audio: {}", e))?;
+        stored
+            .map(|(mime_type, waveform)| {
+                serde_json::from_str(&waveform)
+                    .map(|waveform| StoredAudio {
+                        mime_type,
+                        waveform,
+                    })
+                    .map_err(|e| format!("Failed to read waveform: {}", e))
+            })
+            .transpose()
+    }
+
+    /// Entry ID and MIME type of each kept recording
+    pub fn kept_audio(&self) -> Result<Vec<(String, String)>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare("SELECT entry_id, mime_type FROM audio")
+            .map_err(|e| format!("Failed to read history audio: {}", e))?;
+        let kept: Vec<(String, String)> = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("Failed to read history audio: {}", e))?;
+        Ok(kept)
+    }
+
+    /// Forget every kept recording; the files go with the next sweep
+    pub fn clear_audio(&self) -> Result<(), String> {
+        self.conn()?
+            .execute("DELETE FROM audio", [])
+            .map_err(|e| format!("Failed to clear history audio: {}", e))?;
+        Ok(())
+    }
+
+    /// Move an entry to the trash, returning whether it was in history
+    pub fn delete(&self, id: &str) -> Result<bool, String> {
+        let trashed = self
+            .conn()?
+            .execute(
+                "UPDATE history SET trashed_at = ?1 WHERE id = ?2 AND trashed_at IS NULL",
+                params![format_timestamp(now()), id],
+            )
+            .map_err(|e| format!("Failed to delete history entry: {}", e))?;
+        Ok(trashed > 0)
+    }
+
+    /// Entries in the trash, most recently deleted first
+    pub fn trashed(&self) -> Result<Vec<TrashedEntry>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(&format!(
+                "{} WHERE trashed_at IS NOT NULL ORDER BY trashed_at DESC",
+                SELECT_ENTRIES
+            ))
+            .map_err(|e| format!("Failed to read the trash: {}", e))?;
+        let trashed: Vec<TrashedEntry> = statement
+            .query_map([], |row| {
+                Ok(TrashedEntry {
+                    entry: HistoryEntry::from_row(row)?,
+                    trashed_at: parse_timestamp(row.get("trashed_at")?)?,
+                })
+            })
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("Failed to read the trash: {}", e))?;
+        Ok(trashed)
+    }
+
+    /// Take an entry out of the trash, returning whether it was there
+    pub fn restore(&self, id: &str) -> Result<bool, String> {
+        let restored = self
+            .conn()?
+            .execute(
+                "UPDATE history SET trashed_at = NULL WHERE id = ?1 AND trashed_at IS NOT NULL",
+                params![id],
+            )
+            .map_err(|e| format!("Failed to restore history entry: {}", e))?;
+        Ok(restored > 0)
+    }
+
+    /// Remove trashed entries matching `condition` for good and remember them for
+    /// sync, returning how many were removed
+    fn purge(&self, condition: &str, args: &[&dyn rusqlite::ToSql]) -> Result<usize, String> {
+        let conn = self.conn()?;
+        let transaction = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to empty the trash: {}", e))?;
+        // Stamped by SQLite in the same fixed-width form as `format_timestamp`
+        transaction
+            .execute(
+                &format!(
+                    "INSERT OR REPLACE INTO deleted_entries (id, deleted_at) \
+                     SELECT id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now') FROM history \
+                     WHERE trashed_at IS NOT NULL AND ({})",
+                    condition
+                ),
+                args,
+            )
+            .map_err(|e| format!("Failed to empty the trash: {}", e))?;
+        let removed = transaction
+            .execute(
+                &format!(
+                    "DELETE FROM history WHERE trashed_at IS NOT NULL AND ({})",
+                    condition
+                ),
+                args,
+            )
+            .map_err(|e| format!("Failed to empty the trash: {}", e))?;
+        transaction
+            .commit()
+            .map_err(|e| format!("Failed to empty the trash: {}", e))?;
+        Ok(removed)
+    }
+
+    /// Remove an entry in the trash for good, returning whether it was there
+    pub fn purge_entry(&self, id: &str) -> Result<bool, String> {
+        Ok(self.purge("id = ?1", &[&id])? > 0)
+    }
 
+    /// Remove entries trashed before `cutoff` for good
+    pub fn purge_trashed_before(&self, cutoff: DateTime<Utc>) -> Result<usize, String> {
+        self.purge("trashed_at < ?1", &[&format_timestamp(cutoff)])
+    }
+
+    /// Remove everything in the trash for good
+    pub fn empty_trash(&self) -> Result<usize, String> {
+        self.purge("1", &[])
+    }
+
+    /// Entries deleted by the user, oldest first
+    pub fn deleted_entries(&self) -> Result<Vec<DeletedEntry>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT id, deleted_at FROM deleted_entries WHERE pruned = 0 \
+                 ORDER BY deleted_at",
+            )
+            .map_err(|e| format!("Failed to read deleted history: {}", e))?;
+        let deleted: Vec<DeletedEntry> = statement
+            .query_map([], |row| {
+                Ok(DeletedEntry {
+                    id: row.get(0)?,
+                    deleted_at: parse_timestamp(row.get(1)?)?,
+                })
+            })
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("Failed to read deleted history: {}", e))?;
         Ok(deleted)
     }
 
-    /// Clear all history
+    /// Add an entry from another device, with its tags, note and newest revision,
+    /// unless it is already here or was deleted or pruned. Returns whether it was
+    /// added; the session it was dictated in isn't carried over.
+    pub fn import_entry(&self, entry: &HistoryEntry) -> Result<bool, String> {
+        let conn = self.conn()?;
+        let transaction = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to import history entry: {}", e))?;
+        let known: bool = transaction
+            .query_row(
+                "SELECT EXISTS (SELECT 1 FROM history WHERE id = ?1) \
+                 OR EXISTS (SELECT 1 FROM deleted_entries WHERE id = ?1)",
+                params![entry.id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to import history entry: {}", e))?;
+        if known {
+            return Ok(false);
+        }
+
+        transaction
+            .execute(
+                "INSERT INTO history (id, timestamp, text, raw_text, stt_provider, \
+                 llm_provider, duration_seconds, target_app, latency_seconds, pinned, note, \
+                 synced) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, 1)",
+                params![
+                    entry.id,
+                    format_timestamp(entry.timestamp),
+                    entry.text,
+                    entry.details.raw_text,
+                    entry.details.stt_provider,
+                    entry.details.llm_provider,
+                    entry.details.duration_seconds,
+                    entry.details.target_app,
+                    entry.details.latency_seconds,
+                    entry.pinned,
+                    entry.note,
+                ],
+            )
+            .map_err(|e| format!("Failed to import history entry: {}", e))?;
+        for tag in entry.tags.iter().filter_map(|tag| normalize_tag(tag)) {
+            transaction
+                .execute(
+                    "INSERT OR IGNORE INTO tags (entry_id, tag) VALUES (?1, ?2)",
+                    params![entry.id, tag],
+                )
+                .map_err(|e| format!("Failed to import history entry: {}", e))?;
+        }
+        if let Some(revised_text) = &entry.revised_text {
+            transaction
+                .execute(
+                    "INSERT INTO revisions (id, entry_id, timestamp, text) \
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        Uuid::new_v4().to_string(),
+                        entry.id,
+                        format_timestamp(now()),
+                        revised_text,
+                    ],
+                )
+                .map_err(|e| format!("Failed to import history entry: {}", e))?;
+        }
+        transaction
+            .commit()
+            .map_err(|e| format!("Failed to import history entry: {}", e))?;
+        Ok(true)
+    }
+
+    /// Delete entries that were deleted on another device and remember them, so
+    /// they aren't imported again. Returns how many were here.
+    pub fn apply_deletions(&self, deleted: &[DeletedEntry]) -> Result<usize, String> {
+        let conn = self.conn()?;
+        let transaction = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to delete history entries: {}", e))?;
+        let mut removed = 0;
+        for entry in deleted {
+            removed += transaction
+                .execute("DELETE FROM history WHERE id = ?1", params![entry.id])
+                .map_err(|e| format!("Failed to delete history entries: {}", e))?;
+            transaction
+                .execute(
+                    "INSERT OR IGNORE INTO deleted_entries (id, deleted_at) VALUES (?1, ?2)",
+                    params![entry.id, format_timestamp(entry.deleted_at)],
+                )
+                .map_err(|e| format!("Failed to delete history entries: {}", e))?;
+        }
+        transaction
+            .commit()
+            .map_err(|e| format!("Failed to delete history entries: {}", e))?;
+        Ok(removed)
+    }
+
+    /// Delete entries matching `condition` to stay within the limits, remembering them
+    /// on this device only so sync doesn't import them again. Returns how many were
+    /// removed.
+    fn delete_pruned(
+        &self,
+        condition: &str,
+        args: &[&dyn rusqlite::ToSql],
+    ) -> Result<usize, String> {
+        let conn = self.conn()?;
+        let transaction = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to prune history: {}", e))?;
+        transaction
+            .execute(
+                &format!(
+                    "INSERT OR IGNORE INTO deleted_entries (id, deleted_at, pruned) \
+                     SELECT id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), 1 FROM history \
+                     WHERE {}",
+                    condition
+                ),
+                args,
+            )
+            .map_err(|e| format!("Failed to prune history: {}", e))?;
+        let removed = transaction
+            .execute(&format!("DELETE FROM history WHERE {}", condition), args)
+            .map_err(|e| format!("Failed to prune history: {}", e))?;
+        transaction
+            .commit()
+            .map_err(|e| format!("Failed to prune history: {}", e))?;
+        Ok(removed)
+    }
+
+    /// Delete unpinned entries older than `cutoff`, returning how many were removed
+    pub fn delete_before(&self, cutoff: DateTime<Utc>) -> Result<usize, String> {
+        self.delete_pruned(
+            "timestamp < ?1 AND pinned = 0",
+            &[&format_timestamp(cutoff)],
+        )
+    }
+
+    /// Delete unpinned entries beyond the newest `max`, returning how many were removed
+    pub fn delete_beyond_count(&self, max: usize) -> Result<usize, String> {
+        self.delete_pruned(
+            "pinned = 0 AND trashed_at IS NULL AND id NOT IN \
+             (SELECT id FROM history WHERE trashed_at IS NULL \
+             ORDER BY timestamp DESC LIMIT ?1)",
+            &[&(max as i64)],
+        )
+    }
+
+    /// Delete the `count` oldest unpinned entries, returning how many were removed
+    fn delete_oldest(&self, count: usize) -> Result<usize, String> {
+        self.delete_pruned(
+            "id IN (SELECT id FROM history WHERE pinned = 0 ORDER BY timestamp ASC LIMIT ?1)",
+            &[&(count as i64)],
+        )
+    }
+
+    /// Forget entries removed before `cutoff`, returning how many were forgotten
+    pub fn forget_deletions_before(&self, cutoff: DateTime<Utc>) -> Result<usize, String> {
+        self.conn()?
+            .execute(
+                "DELETE FROM deleted_entries WHERE deleted_at < ?1",
+                params![format_timestamp(cutoff)],
+            )
+            .map_err(|e| format!("Failed to forget deleted history: {}", e))
+    }
+
+    /// Bytes of the database in use, not counting free pages
+    pub fn used_bytes(&self) -> Result<u64, String> {
+        self.conn()?
+            .query_row(
+                "SELECT (page_count - freelist_count) * page_size \
+                 FROM pragma_page_count(), pragma_freelist_count(), pragma_page_size()",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to read history size: {}", e))
+    }
+
+    /// Delete unpinned entries until history is within `limits`, oldest first
+    pub fn prune(
+        &self,
+        limits: &HistoryLimits,
+        now: DateTime<Utc>,
+    ) -> Result<PruneSummary, String> {
+        let mut summary = PruneSummary::default();
+        let used_before = self.used_bytes()?;
+
+        if let Some(cutoff) = retention_cutoff(limits.max_days, now) {
+            summary.by_age = self.delete_before(cutoff)?;
+        }
+        if let Some(max) = limits.max_entries.filter(|max| *max > 0) {
+            summary.by_count = self.delete_beyond_count(max as usize)?;
+        }
+        if let Some(max_mb) = limits.max_disk_mb.filter(|max| *max > 0) {
+            let max_bytes = u64::from(max_mb) * 1024 * 1024;
+            while self.used_bytes()? > max_bytes {
+                let deleted = self.delete_oldest(SIZE_PRUNE_BATCH)?;
+                if deleted == 0 {
+                    break;
+                }
+                summary.by_size += deleted;
+            }
+            if summary.by_size > 0 {
+                self.conn()?
+                    .execute_batch("VACUUM;")
+                    .map_err(|e| format!("Failed to compact history: {}", e))?;
+            }
+        }
+
+        self.forget_deletions_before(now - Duration::days(DELETION_MEMORY_DAYS))?;
+
+        summary.freed_bytes = used_before.saturating_sub(self.used_bytes()?);
+        Ok(summary)
+    }
+
+    /// Move all of history to the trash
     pub fn clear(&self) -> Result<(), String> {
-        {
-            let mut data = self
-                .data
-                .write()
-                .map_err(|e| format!("Failed to write history: {}", e))?;
-            data.entries.clear();
-        }
-        self.save()
+        self.conn()?
+            .execute(
+                "UPDATE history SET trashed_at = ?1 WHERE trashed_at IS NULL",
+                params![format_timestamp(now())],
+            )
+            .map_err(|e| format!("Failed to clear history: {}", e))?;
+        Ok(())
+    }
+}
+
+/// The pruning limits from settings
+pub fn stored_limits(app: &AppHandle) -> HistoryLimits {
+    HistoryLimits {
+        max_days: crate::settings::read_setting(app, "history_retention_days"),
+        max_entries: crate::settings::read_setting(app, "history_max_entries"),
+        max_disk_mb: crate::settings::read_setting(app, "history_max_disk_mb"),
+    }
+}
+
+/// Remove entries that have been in the trash longer than `history_trash_days`
+pub fn empty_expired_trash(app: &AppHandle, history: &HistoryStorage) {
+    let days = crate::settings::read_setting::<Option<u32>>(app, "history_trash_days")
+        .unwrap_or(DEFAULT_TRASH_DAYS);
+    match history.purge_trashed_before(now() - Duration::days(i64::from(days))) {
+        Ok(0) => {}
+        Ok(count) => log::info!("Removed {} history entries from the trash", count),
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+/// Prune history to the limits in settings and tell the frontend what was removed
+pub fn apply_retention(app: &AppHandle, history: &HistoryStorage) {
+    if history.is_locked() {
+        return;
+    }
+    empty_expired_trash(app, history);
+    match history.prune(&stored_limits(app), now()) {
+        Ok(summary) if summary.total() > 0 => {
+            log::info!(
+                "Pruned {} history entries ({} by age, {} by count, {} by size)",
+                summary.total(),
+                summary.by_age,
+                summary.by_count,
+                summary.by_size
+            );
+            let _ = app.emit(HISTORY_PRUNED_EVENT, summary);
+        }
+        Ok(_) => {}
+        Err(e) => log::error!("{}", e),
+    }
+    crate::history_audio::sweep(app, history);
+}
+
+/// Tell windows an entry changed, sending it as now stored
+pub fn emit_entry_updated(app: &AppHandle, history: &HistoryStorage, id: &str) {
+    match history.get(id) {
+        Ok(Some(entry)) => {
+            let _ = app.emit(HISTORY_ENTRY_UPDATED_EVENT, entry);
+        }
+        Ok(None) => {}
+        Err(e) => log::error!("{}", e),
     }
 }
@@ -0,0 +1,174 @@
+//! Recordings kept with history entries.
+//!
+//! With `history_keep_audio` on, the overlay records each dictation alongside
+//! streaming it, and saves the recording once its history entry exists. Files are
+//! named after the entry in a `history_audio` folder next to the database. They
+//! can't be encrypted, so none are kept while history is. The overlay also sends a
+//! waveform preview, since it can decode the audio and the backend can't.
+//!
+//! Recordings of entries that no longer exist, and files left by a recording saved
+//! again in another format, are removed whenever history is pruned, deleted or
+//! cleared.
+
+use crate::history::HistoryStorage;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Folder in the app data directory holding recordings
+pub const AUDIO_DIR: &str = "history_audio";
+
+/// Points in a stored waveform preview
+pub const WAVEFORM_POINTS: usize = 100;
+
+/// A kept recording, ready to play
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryAudio {
+    pub path: PathBuf,
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+    /// Peak levels from 0 to 1, evenly spaced over the recording
+    pub waveform: Vec<f32>,
+}
+
+/// File extension for a recording's MIME type
+pub fn file_extension(mime_type: &str) -> &'static str {
+    let essence = mime_type.split(';').next().unwrap_or_default().trim();
+    match essence {
+        "audio/webm" => "webm",
+        "audio/ogg" => "ogg",
+        "audio/mp4" => "m4a",
+        "audio/wav" | "audio/x-wav" => "wav",
+        _ => "audio",
+    }
+}
+
+/// Entry IDs are used as file names, so only plain ones are accepted
+fn is_safe_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Peak levels clamped to 0..1 and reduced to at most `points` values
+pub fn resample_waveform(values: &[f32], points: usize) -> Vec<f32> {
+    let clamped: Vec<f32> = values
+        .iter()
+        .map(|value| {
+            if value.is_finite() {
+                value.clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    if clamped.len() <= points || points == 0 {
+        return clamped;
+    }
+    (0..points)
+        .map(|i| {
+            let start = i * clamped.len() / points;
+            let end = ((i + 1) * clamped.len() / points).max(start + 1);
+            clamped[start..end].iter().copied().fold(0.0, f32::max)
+        })
+        .collect()
+}
+
+fn audio_file(audio_dir: &Path, entry_id: &str, mime_type: &str) -> PathBuf {
+    audio_dir.join(format!("{}.{}", entry_id, file_extension(mime_type)))
+}
+
+/// Save an entry's recording, returning false when there is no such entry
+pub fn save(
+    audio_dir: &Path,
+    history: &HistoryStorage,
+    entry_id: &str,
+    bytes: &[u8],
+    mime_type: &str,
+    waveform: &[f32],
+) -> Result<bool, String> {
+    if !is_safe_id(entry_id) || history.get(entry_id)?.is_none() {
+        return Ok(false);
+    }
+    fs::create_dir_all(audio_dir)
+        .map_err(|e| format!("Failed to create audio directory: {}", e))?;
+    let path = audio_file(audio_dir, entry_id, mime_type);
+    fs::write(&path, bytes).map_err(|e| format!("Failed to save recording: {}", e))?;
+    // A recording saved before in another format is replaced, not kept beside it
+    if let Some(previous) = history.get_audio(entry_id)? {
+        let previous = audio_file(audio_dir, entry_id, &previous.mime_type);
+        if previous != path {
+            let _ = fs::remove_file(previous);
+        }
+    }
+    history.set_audio(
+        entry_id,
+        mime_type,
+        &resample_waveform(waveform, WAVEFORM_POINTS),
+    )
+}
+
+/// An entry's recording, if it was kept and the file is still there
+pub fn load(
+    audio_dir: &Path,
+    history: &HistoryStorage,
+    entry_id: &str,
+) -> Result<Option<HistoryAudio>, String> {
+    if !is_safe_id(entry_id) {
+        return Ok(None);
+    }
+    let Some(stored) = history.get_audio(entry_id)? else {
+        return Ok(None);
+    };
+    let path = audio_file(audio_dir, entry_id, &stored.mime_type);
+    match fs::read(&path) {
+        Ok(bytes) => Ok(Some(HistoryAudio {
+            path,
+            mime_type: stored.mime_type,
+            bytes,
+            waveform: stored.waveform,
+        })),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read recording: {}", e)),
+    }
+}
+
+/// Delete recordings whose entry is gone or that were replaced, returning how many
+/// were removed
+pub fn remove_orphans(audio_dir: &Path, history: &HistoryStorage) -> Result<usize, String> {
+    let Ok(files) = fs::read_dir(audio_dir) else {
+        return Ok(0);
+    };
+    let kept: HashSet<PathBuf> = history
+        .kept_audio()?
+        .iter()
+        .map(|(entry_id, mime_type)| audio_file(audio_dir, entry_id, mime_type))
+        .collect();
+    let mut removed = 0;
+    for path in files.flatten().map(|file| file.path()) {
+        if !kept.contains(&path) && fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// The recordings folder for this install
+pub fn audio_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(AUDIO_DIR))
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+/// Remove recordings left behind by deleted entries
+pub fn sweep(app: &AppHandle, history: &HistoryStorage) {
+    if history.is_locked() {
+        return;
+    }
+    match audio_dir(app).and_then(|dir| remove_orphans(&dir, history)) {
+        Ok(0) => {}
+        Ok(count) => log::info!("Removed {} recordings of deleted history entries", count),
+        Err(e) => log::warn!("{}", e),
+    }
+}
@@ -0,0 +1,188 @@
+//! Encryption of the history database at rest.
+//!
+//! History is stored with SQLCipher. With `history_encryption` set to keychain, a
+//! random key is created on first use and kept in the OS keychain (Keychain on
+//! macOS, Credential Manager on Windows, Secret Service on Linux), so history
+//! opens without any prompt. In passphrase mode the key is derived from a
+//! passphrase that is entered after each start; history stays locked until then.
+//! Changing the mode rewrites the database with the new key.
+//!
+//! Nothing outside the database can be encrypted with its key, so while history
+//! is encrypted no recordings, failed dictations or recovery audio are written
+//! next to it, and turning encryption on removes the ones already there.
+
+use crate::history::HistoryStorage;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const KEYRING_SERVICE: &str = "tambourine-voice";
+const KEYRING_USER: &str = "history-key";
+
+/// Error returned when a passphrase doesn't open history
+pub const WRONG_PASSPHRASE_ERROR: &str = "Wrong passphrase for history";
+
+/// How the history database is protected
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryEncryption {
+    /// Plain SQLite file
+    #[default]
+    Off,
+    /// Encrypted with a random key kept in the OS keychain
+    Keychain,
+    /// Encrypted with a key derived from a passphrase entered after each start
+    Passphrase,
+}
+
+/// Key for an encrypted history database
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoryKey {
+    /// 256-bit key as hex, used as-is
+    Raw(String),
+    /// Passphrase SQLCipher derives the key from
+    Passphrase(String),
+}
+
+impl HistoryKey {
+    /// The key as an SQL literal for `PRAGMA key` and `ATTACH ... KEY`
+    pub fn sql_literal(&self) -> String {
+        match self {
+            Self::Raw(hex) => format!("\"x'{}'\"", hex),
+            Self::Passphrase(passphrase) => format!("'{}'", passphrase.replace('\'', "''")),
+        }
+    }
+}
+
+/// Whether the file is an unencrypted SQLite database
+pub fn is_plaintext(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|_| &header == b"SQLite format 3\0")
+}
+
+/// Set the key on a freshly opened connection and check that it fits
+pub fn apply_key(conn: &Connection, key: Option<&HistoryKey>) -> Result<(), String> {
+    if let Some(key) = key {
+        conn.execute_batch(&format!("PRAGMA key = {};", key.sql_literal()))
+            .map_err(|e| format!("Failed to set history key: {}", e))?;
+    }
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|_| match key {
+            Some(HistoryKey::Passphrase(_)) => WRONG_PASSPHRASE_ERROR.to_string(),
+            _ => "History can't be read with this key".to_string(),
+        })
+}
+
+/// Copy the open database next to `db_path`, encrypted with `key` (or as
+/// plaintext for `None`), and return the copy's path
+pub fn export_rekeyed(
+    conn: &Connection,
+    db_path: &Path,
+    key: Option<&HistoryKey>,
+) -> Result<PathBuf, String> {
+    let target = db_path.with_extension("db.rekey");
+    let _ = fs::remove_file(&target);
+    let version: u32 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read history schema version: {}", e))?;
+
+    let key_literal = key.map_or_else(|| "''".to_string(), HistoryKey::sql_literal);
+    let target_literal = target.to_string_lossy().replace('\'', "''");
+    conn.execute_batch(&format!(
+        "ATTACH DATABASE '{}' AS rekeyed KEY {};",
+        target_literal, key_literal
+    ))
+    .map_err(|e| format!("Failed to create history copy: {}", e))?;
+    let exported = conn
+        .query_row("SELECT sqlcipher_export('rekeyed')", [], |_| Ok(()))
+        .and_then(|_| conn.execute_batch(&format!("PRAGMA rekeyed.user_version = {};", version)))
+        .map_err(|e| format!("Failed to copy history: {}", e));
+    let _ = conn.execute_batch("DETACH DATABASE rekeyed;");
+    if exported.is_err() {
+        let _ = fs::remove_file(&target);
+    }
+    exported.map(|_| target)
+}
+
+fn random_key_hex() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate history key: {}", e))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+fn keyring_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| format!("Failed to open the keychain: {}", e))
+}
+
+/// The history key from the OS keychain, created on first use
+pub fn keychain_key() -> Result<HistoryKey, String> {
+    let entry = keyring_entry()?;
+    match entry.get_password() {
+        Ok(hex) => Ok(HistoryKey::Raw(hex)),
+        Err(keyring::Error::NoEntry) => {
+            let hex = random_key_hex()?;
+            entry
+                .set_password(&hex)
+                .map_err(|e| format!("Failed to store history key in the keychain: {}", e))?;
+            Ok(HistoryKey::Raw(hex))
+        }
+        Err(e) => Err(format!(
+            "Failed to read history key from the keychain: {}",
+            e
+        )),
+    }
+}
+
+/// Remove the history key from the OS keychain once it is no longer used
+pub fn delete_keychain_key() {
+    let deleted = keyring_entry().and_then(|entry| {
+        entry
+            .delete_credential()
+            .map_err(|e| format!("Failed to delete history key: {}", e))
+    });
+    if let Err(e) = deleted {
+        log::debug!("{}", e);
+    }
+}
+
+/// The `history_encryption` setting. A value that can't be read is taken as
+/// passphrase mode, so history is never opened or shared unencrypted by mistake.
+fn encryption_mode(app: &AppHandle) -> HistoryEncryption {
+    crate::settings::try_read_setting(app, "history_encryption")
+        .unwrap_or_else(|e| {
+            log::error!("{}, treating history as locked", e);
+            Some(HistoryEncryption::Passphrase)
+        })
+        .unwrap_or_default()
+}
+
+/// Whether history is set to be encrypted
+pub fn is_enabled(app: &AppHandle) -> bool {
+    encryption_mode(app) != HistoryEncryption::Off
+}
+
+/// Open history as the `history_encryption` setting says. History that can't be
+/// opened (including passphrase mode, until unlocked) starts locked.
+pub fn open_history(app: &AppHandle, app_data_dir: PathBuf) -> HistoryStorage {
+    let key = match encryption_mode(app) {
+        HistoryEncryption::Off => Ok(None),
+        HistoryEncryption::Keychain => keychain_key().map(Some),
+        HistoryEncryption::Passphrase => {
+            log::info!("History is locked until the passphrase is entered");
+            return HistoryStorage::locked(app_data_dir);
+        }
+    };
+    match key.and_then(|key| HistoryStorage::open(app_data_dir.clone(), key.as_ref())) {
+        Ok(history) => history,
+        Err(e) => {
+            log::error!("Failed to open history, leaving it locked: {}", e);
+            HistoryStorage::locked(app_data_dir)
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Collapsing repeated dictations in the history listing.
+//!
+//! Retries tend to produce the same text again, or nearly so. Consecutive
+//! entries whose text is at least `history_duplicate_threshold` similar are
+//! listed as one: the newest entry, with the older attempts under it. Nothing is
+//! deleted; every attempt and its revisions stay reachable.
+//!
+//! Similarity is one minus the edit distance between the texts, relative to the
+//! longer one, after ignoring case, punctuation and spacing.
+
+use crate::history::HistoryEntry;
+use serde::Serialize;
+
+/// Similarity at which consecutive dictations are collapsed by default
+pub const DEFAULT_DUPLICATE_THRESHOLD: f64 = 0.9;
+
+/// A history entry with the older near-duplicates listed under it
+#[derive(Debug, Clone, Serialize)]
+pub struct CollapsedEntry {
+    #[serde(flatten)]
+    pub entry: HistoryEntry,
+    /// Newest first
+    pub duplicates: Vec<HistoryEntry>,
+}
+
+/// Lowercase words without punctuation, as characters for comparison
+fn normalize(text: &str) -> Vec<char> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect()
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// How alike two texts are, from 0 (nothing in common) to 1 (the same words)
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize(a), normalize(b));
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(&a, &b) as f64 / longest as f64
+}
+
+/// The text an entry is shown with
+fn shown_text(entry: &HistoryEntry) -> &str {
+    entry.revised_text.as_deref().unwrap_or(&entry.text)
+}
+
+/// Group consecutive entries (newest first, as stored) that are at least
+/// `threshold` similar. A threshold of 0 or less, or above 1, collapses nothing.
+pub fn collapse(entries: Vec<HistoryEntry>, threshold: f64) -> Vec<CollapsedEntry> {
+    let enabled = threshold > 0.0 && threshold <= 1.0;
+    let mut collapsed: Vec<CollapsedEntry> = Vec::new();
+    for entry in entries {
+        if let Some(group) = collapsed.last_mut().filter(|_| enabled) {
+            let previous = group.duplicates.last().unwrap_or(&group.entry);
+            if similarity(shown_text(previous), shown_text(&entry)) >= threshold {
+                group.duplicates.push(entry);
+                continue;
+            }
+        }
+        collapsed.push(CollapsedEntry {
+            entry,
+            duplicates: Vec::new(),
+        });
+    }
+    collapsed
+}
@@ -0,0 +1,120 @@
+//! Exporting dictation history to Markdown, JSON or CSV.
+//!
+//! Entries are written oldest first. Markdown groups them under a heading per
+//! day, in the same `- HH:MM text` form used for output files; JSON keeps every
+//! field; CSV has one row per entry for spreadsheets and other tools.
+//!
+//! A session is exported as a single transcript: its name and times, then each
+//! dictation with the time it was made.
+
+use crate::history::{HistoryEntry, Session};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// File format for an export
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Csv,
+}
+
+const CSV_HEADER: &str =
+    "timestamp,text,raw_text,stt_provider,llm_provider,duration_seconds,target_app";
+
+/// Render entries (newest first, as stored) in the given format, with times for
+/// Markdown shown in `tz`
+pub fn render<Tz: TimeZone>(
+    entries: &[HistoryEntry],
+    format: ExportFormat,
+    tz: &Tz,
+) -> Result<String, String>
+where
+    Tz::Offset: Display,
+{
+    let chronological = entries.iter().rev();
+    match format {
+        ExportFormat::Markdown => {
+            let mut out = String::from("# Dictation history\n");
+            let mut current_day = None;
+            for entry in chronological {
+                let local = entry.timestamp.with_timezone(tz);
+                let day = local.date_naive();
+                if current_day != Some(day) {
+                    out.push_str(&format!("\n## {}\n\n", day.format("%Y-%m-%d")));
+                    current_day = Some(day);
+                }
+                out.push_str(&format!(
+                    "- {} {}\n",
+                    local.format("%H:%M"),
+                    entry.text.trim().replace('\n', "\n  ")
+                ));
+            }
+            Ok(out)
+        }
+        ExportFormat::Json => {
+            let entries: Vec<&HistoryEntry> = chronological.collect();
+            serde_json::to_string_pretty(&entries)
+                .map_err(|e| format!("Failed to serialize history: {}", e))
+        }
+        ExportFormat::Csv => {
+            let mut out = format!("{}\n", CSV_HEADER);
+            for entry in chronological {
+                let details = &entry.details;
+                let fields = [
+                    entry.timestamp.to_rfc3339(),
+                    entry.text.clone(),
+                    details.raw_text.clone().unwrap_or_default(),
+                    details.stt_provider.clone().unwrap_or_default(),
+                    details.llm_provider.clone().unwrap_or_default(),
+                    details
+                        .duration_seconds
+                        .map(|seconds| format!("{:.1}", seconds))
+                        .unwrap_or_default(),
+                    details.target_app.clone().unwrap_or_default(),
+                ];
+                let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+                out.push_str(&row.join(","));
+                out.push('\n');
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Stitch a session's entries (newest first, as stored) into one transcript, with
+/// times shown in `tz`
+pub fn render_session<Tz: TimeZone>(session: &Session, entries: &[HistoryEntry], tz: &Tz) -> String
+where
+    Tz::Offset: Display,
+{
+    let time = |at: &DateTime<Utc>| at.with_timezone(tz).format("%Y-%m-%d %H:%M").to_string();
+    let mut out = format!(
+        "# {}\n\nStarted {}",
+        session.name,
+        time(&session.started_at)
+    );
+    match &session.ended_at {
+        Some(ended_at) => out.push_str(&format!(", ended {}\n\n", time(ended_at))),
+        None => out.push_str(", still in progress\n\n"),
+    }
+    for entry in entries.iter().rev() {
+        out.push_str(&format!(
+            "[{}] {}\n",
+            entry.timestamp.with_timezone(tz).format("%H:%M:%S"),
+            entry.revised_text.as_deref().unwrap_or(&entry.text).trim()
+        ));
+    }
+    out
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,24 @@
+//! Periodic history pruning.
+//!
+//! History is also pruned when entries are added, but an install that is left
+//! running without dictating still has to drop entries as they age past
+//! `history_retention_days`, so the limits are re-applied on a timer.
+
+use crate::history::{self, HistoryStorage};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How often history is pruned in the background
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Start pruning history on a background thread
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(PRUNE_INTERVAL);
+
+        if let Some(history) = app.try_state::<HistoryStorage>() {
+            history::apply_retention(&app, &history);
+        }
+    });
+}
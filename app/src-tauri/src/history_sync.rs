@@ -0,0 +1,231 @@
+//! Sharing history between devices through a synced folder.
+//!
+//! With `history_sync_folder` set to a folder kept in sync by Dropbox, Syncthing
+//! or similar, each device writes its history to its own file there,
+//! `tambourine-history/<device id>.json`, and merges the other devices' files
+//! into its database. A file only ever has one writer, so the sync service never
+//! sees conflicting edits to it.
+//!
+//! Entries are merged by their UUID: ones this device hasn't seen are added, with
+//! the pin, tags, note and newest revision they had on the other device, and
+//! entries the user deleted on any device are deleted everywhere. Later edits to
+//! an entry that both devices have stay on the device that made them. Entries
+//! older than this device's retention, or that it pruned, aren't imported, and
+//! recordings aren't synced. Imported entries are listed and searched with the
+//! rest but never pasted by the paste-last hotkey.
+//!
+//! The files aren't encrypted, so history isn't synced while history encryption
+//! is on, and this device's file is removed from the folder.
+
+use crate::history::{self, DeletedEntry, HistoryEntry, HistoryStorage};
+use crate::settings::write_file_atomic;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+/// Folder inside the sync folder holding one file per device
+pub const SYNC_DIR: &str = "tambourine-history";
+
+/// File in the app data directory with this device's ID
+const DEVICE_ID_FILE: &str = "history_sync_device";
+
+/// How often history is synced in the background
+const SYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// One device's history, as written to the sync folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFile {
+    pub device_id: String,
+    pub updated_at: DateTime<Utc>,
+    pub entries: Vec<HistoryEntry>,
+    #[serde(default)]
+    pub deleted: Vec<DeletedEntry>,
+}
+
+/// What a sync changed in this device's history
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct SyncSummary {
+    /// Entries added from other devices
+    pub imported: usize,
+    /// Entries deleted because they were deleted on another device
+    pub deleted: usize,
+    /// Other devices whose history was read
+    pub devices: usize,
+}
+
+/// This device's ID, created the first time it is needed
+pub fn device_id(app_data_dir: &Path) -> Result<String, String> {
+    let path = app_data_dir.join(DEVICE_ID_FILE);
+    if let Ok(id) = fs::read_to_string(&path) {
+        let id = id.trim();
+        if !id.is_empty() {
+            return Ok(id.to_string());
+        }
+    }
+    let id = Uuid::new_v4().to_string();
+    write_file_atomic(&path, id.as_bytes())?;
+    Ok(id)
+}
+
+/// Merge other devices' history into this one; entries older than `cutoff` are
+/// left out
+pub fn merge(
+    history: &HistoryStorage,
+    remote: &[SyncFile],
+    cutoff: Option<DateTime<Utc>>,
+) -> Result<SyncSummary, String> {
+    let mut summary = SyncSummary {
+        devices: remote.len(),
+        ..Default::default()
+    };
+    for file in remote {
+        summary.deleted += history.apply_deletions(&file.deleted)?;
+    }
+    for entry in remote.iter().flat_map(|file| &file.entries) {
+        if cutoff.is_some_and(|cutoff| entry.timestamp < cutoff && !entry.pinned) {
+            continue;
+        }
+        if history.import_entry(entry)? {
+            summary.imported += 1;
+        }
+    }
+    Ok(summary)
+}
+
+/// The other devices' files in the sync directory; unreadable ones are skipped
+pub fn read_remote_files(sync_dir: &Path, device_id: &str) -> Vec<SyncFile> {
+    let Ok(dir) = fs::read_dir(sync_dir) else {
+        return Vec::new();
+    };
+    dir.filter_map(Result::ok)
+        .map(|item| item.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let file = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    serde_json::from_str::<SyncFile>(&content).map_err(|e| e.to_string())
+                });
+            match file {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    log::warn!("Skipping synced history {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .filter(|file| file.device_id != device_id)
+        .collect()
+}
+
+/// Write this device's history to its file in the sync directory
+pub fn write_own_file(
+    sync_dir: &Path,
+    device_id: &str,
+    history: &HistoryStorage,
+) -> Result<(), String> {
+    let file = SyncFile {
+        device_id: device_id.to_string(),
+        updated_at: Utc::now(),
+        entries: history.get_all(None)?,
+        deleted: history.deleted_entries()?,
+    };
+    let content = serde_json::to_string(&file)
+        .map_err(|e| format!("Failed to serialize history for sync: {}", e))?;
+    fs::create_dir_all(sync_dir)
+        .map_err(|e| format!("Failed to create {}: {}", sync_dir.display(), e))?;
+    write_file_atomic(
+        &sync_dir.join(format!("{}.json", device_id)),
+        content.as_bytes(),
+    )
+}
+
+/// The sync directory inside the configured folder, or none when sync is off
+fn stored_sync_dir(app: &AppHandle) -> Option<PathBuf> {
+    let folder = crate::settings::read_setting::<Option<String>>(app, "history_sync_folder")
+        .filter(|folder| !folder.trim().is_empty())?;
+    let home = app.path().home_dir().ok();
+    let folder = crate::output_file::expand_path(&folder, chrono::Local::now(), home.as_deref());
+    Some(folder.join(SYNC_DIR))
+}
+
+/// Remove this device's file from the sync folder, if it wrote one
+pub fn remove_own_file(app: &AppHandle) {
+    let Some(sync_dir) = stored_sync_dir(app) else {
+        return;
+    };
+    let Ok(app_data_dir) = app.path().app_data_dir() else {
+        return;
+    };
+    let Ok(device_id) = device_id(&app_data_dir) else {
+        return;
+    };
+    let path = sync_dir.join(format!("{}.json", device_id));
+    match fs::remove_file(&path) {
+        Ok(()) => log::info!("Removed unencrypted synced history {}", path.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("Failed to remove {}: {}", path.display(), e),
+    }
+}
+
+/// Merge the other devices' history, then publish this device's
+pub fn sync(app: &AppHandle, history: &HistoryStorage) -> Result<SyncSummary, String> {
+    let Some(sync_dir) = stored_sync_dir(app) else {
+        return Err("No history sync folder is set".to_string());
+    };
+    if crate::history_crypto::is_enabled(app) {
+        remove_own_file(app);
+        return Err(
+            "History isn't synced while it is encrypted, since the sync folder would hold it \
+             unencrypted"
+                .to_string(),
+        );
+    }
+    if history.is_locked() {
+        return Err(history::LOCKED_ERROR.to_string());
+    }
+
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let device_id = device_id(&app_data_dir)?;
+    let cutoff = history::retention_cutoff(history::stored_limits(app).max_days, Utc::now());
+
+    let summary = merge(history, &read_remote_files(&sync_dir, &device_id), cutoff)?;
+    if summary.imported > 0 {
+        history::apply_retention(app, history);
+    }
+    write_own_file(&sync_dir, &device_id, history)?;
+
+    if summary.imported > 0 || summary.deleted > 0 {
+        log::info!(
+            "Synced history: {} entries imported and {} deleted from {} other devices",
+            summary.imported,
+            summary.deleted,
+            summary.devices
+        );
+        crate::history_audio::sweep(app, history);
+        let _ = app.emit(history::HISTORY_CHANGED_EVENT, ());
+    }
+    Ok(summary)
+}
+
+/// Start syncing history on a background thread, when a sync folder is set
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || loop {
+        if stored_sync_dir(&app).is_some() {
+            if let Some(history) = app.try_state::<HistoryStorage>() {
+                if let Err(e) = sync(&app, &history) {
+                    log::warn!("History sync skipped: {}", e);
+                }
+            }
+        }
+        thread::sleep(SYNC_INTERVAL);
+    });
+}
@@ -0,0 +1,251 @@
+//! Importing vocabulary and settings exported from other dictation tools.
+//!
+//! Supported files:
+//!
+//! - Talon lists (`vocabulary.talon-list`, with `spoken: written` lines after the
+//!   `-` separator) and the community `additional_words.csv` (`Word,Spoken Form`)
+//! - Dragon word lists exported as text, one word per line, with an optional
+//!   spoken form after a backslash (`written\spoken`)
+//! - superwhisper mode and settings files (JSON), whose vocabulary words and
+//!   instructions are picked up wherever they are nested
+//!
+//! Written forms become STT vocabulary terms; spoken forms are left out, as the
+//! vocabulary is about spelling. superwhisper instructions become the advanced
+//! cleanup prompt section, unless a custom one is already set.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Tool an imported file comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    Talon,
+    Dragon,
+    Superwhisper,
+}
+
+/// What an imported file holds, in this app's terms
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedSettings {
+    /// Terms for the STT vocabulary, as they should be spelled
+    pub vocabulary: Vec<String>,
+    /// Instructions for the cleanup
+    pub instructions: Option<String>,
+}
+
+/// Result of an import, shown to the user
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ImportSummary {
+    pub source: ImportSource,
+    /// Terms added to the vocabulary
+    pub added: usize,
+    /// Terms the vocabulary already had
+    pub duplicates: usize,
+    /// Whether the instructions became the advanced prompt section
+    pub instructions_imported: bool,
+}
+
+/// Guess the tool a file comes from by its name and content
+pub fn detect_source(file_name: &str, content: &str) -> Option<ImportSource> {
+    let name = file_name.to_lowercase();
+    if name.ends_with(".talon-list") || name.ends_with(".csv") {
+        return Some(ImportSource::Talon);
+    }
+    if name.ends_with(".json") {
+        return Some(ImportSource::Superwhisper);
+    }
+    let content = content.trim_start();
+    if content.starts_with('{') || content.starts_with('[') {
+        Some(ImportSource::Superwhisper)
+    } else if content.starts_with("list:") {
+        Some(ImportSource::Talon)
+    } else if name.ends_with(".txt") {
+        Some(ImportSource::Dragon)
+    } else {
+        None
+    }
+}
+
+/// Read a file from `source`
+pub fn parse(source: ImportSource, content: &str) -> Result<ImportedSettings, String> {
+    let content = content.trim_start_matches('\u{feff}');
+    match source {
+        ImportSource::Talon => Ok(parse_talon(content)),
+        ImportSource::Dragon => Ok(parse_dragon(content)),
+        ImportSource::Superwhisper => parse_superwhisper(content),
+    }
+}
+
+/// Strip the quotes Talon allows around list keys and values
+fn unquote(text: &str) -> &str {
+    let text = text.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = text
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    text
+}
+
+/// Written forms from a Talon list or vocabulary CSV
+pub fn parse_talon(content: &str) -> ImportedSettings {
+    let is_list = content
+        .lines()
+        .any(|line| line.trim() == "-" || line.trim_start().starts_with("list:"));
+    let mut vocabulary = Vec::new();
+    if is_list {
+        // Headers like `list: user.vocabulary` come before the `-` separator
+        let has_separator = content.lines().any(|line| line.trim() == "-");
+        let mut in_body = !has_separator;
+        for line in content.lines() {
+            let line = line.trim();
+            if !in_body {
+                in_body = line == "-";
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let written = match line.split_once(':') {
+                Some((_, written)) if !written.trim().is_empty() => unquote(written),
+                Some((spoken, _)) => unquote(spoken),
+                None => unquote(line),
+            };
+            vocabulary.push(written.to_string());
+        }
+    } else {
+        for (index, line) in content.lines().enumerate() {
+            let mut columns = line.split(',').map(unquote);
+            let written = columns.next().unwrap_or_default();
+            if index == 0 && written.eq_ignore_ascii_case("word") {
+                continue;
+            }
+            vocabulary.push(written.to_string());
+        }
+    }
+    ImportedSettings {
+        vocabulary: clean_terms(vocabulary),
+        instructions: None,
+    }
+}
+
+/// Written forms from a Dragon word list
+pub fn parse_dragon(content: &str) -> ImportedSettings {
+    let vocabulary = content
+        .lines()
+        .map(|line| line.split('\\').next().unwrap_or_default().to_string())
+        .collect();
+    ImportedSettings {
+        vocabulary: clean_terms(vocabulary),
+        instructions: None,
+    }
+}
+
+/// Vocabulary words and instructions from a superwhisper file
+pub fn parse_superwhisper(content: &str) -> Result<ImportedSettings, String> {
+    let value: Value = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to read superwhisper settings: {}", e))?;
+    let mut vocabulary = Vec::new();
+    let mut instructions = None;
+    collect_superwhisper(&value, &mut vocabulary, &mut instructions);
+    Ok(ImportedSettings {
+        vocabulary: clean_terms(vocabulary),
+        instructions,
+    })
+}
+
+fn collect_superwhisper(
+    value: &Value,
+    vocabulary: &mut Vec<String>,
+    instructions: &mut Option<String>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("vocabulary" | "words" | "customVocabulary", Value::Array(words)) => {
+                        vocabulary.extend(words.iter().filter_map(|word| {
+                            match word {
+                                Value::String(word) => Some(word.clone()),
+                                Value::Object(word) => ["word", "text", "value"]
+                                    .iter()
+                                    .find_map(|key| word.get(*key)?.as_str())
+                                    .map(str::to_string),
+                                _ => None,
+                            }
+                        }));
+                    }
+                    ("vocabulary" | "words" | "customVocabulary", Value::String(words)) => {
+                        vocabulary.extend(words.split([',', '\n']).map(str::to_string));
+                    }
+                    ("prompt" | "instructions", Value::String(text))
+                        if instructions.is_none() && !text.trim().is_empty() =>
+                    {
+                        *instructions = Some(text.trim().to_string());
+                    }
+                    _ => collect_superwhisper(value, vocabulary, instructions),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_superwhisper(item, vocabulary, instructions);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Trim terms and drop empty ones and repeats, keeping the first spelling
+fn clean_terms(terms: Vec<String>) -> Vec<String> {
+    merge_terms(&[], &terms).0
+}
+
+/// Add new terms after the existing ones, skipping any the list already has
+/// (ignoring case). Returns the merged list and how many terms were added.
+pub fn merge_terms(existing: &[String], new: &[String]) -> (Vec<String>, usize) {
+    let mut merged = existing.to_vec();
+    let mut seen: std::collections::HashSet<String> = existing
+        .iter()
+        .map(|term| term.trim().to_lowercase())
+        .collect();
+    let mut added = 0;
+    for term in new {
+        let term = term.trim();
+        if term.is_empty() || !seen.insert(term.to_lowercase()) {
+            continue;
+        }
+        merged.push(term.to_string());
+        added += 1;
+    }
+    (merged, added)
+}
+
+/// Prompt sections with `instructions` as the advanced section, or `None` when a
+/// custom advanced section is already set
+pub fn with_instructions(sections: Option<&Value>, instructions: &str) -> Option<Value> {
+    let mut sections = sections.cloned().unwrap_or_else(|| {
+        serde_json::json!({
+            "main": { "enabled": true, "content": null },
+            "advanced": { "enabled": true, "content": null },
+            "dictionary": { "enabled": false, "content": null },
+        })
+    });
+    let advanced = sections.get("advanced");
+    if advanced
+        .and_then(|advanced| advanced.get("content"))
+        .and_then(Value::as_str)
+        .is_some_and(|content| !content.trim().is_empty())
+    {
+        return None;
+    }
+    sections.as_object_mut()?.insert(
+        "advanced".to_string(),
+        serde_json::json!({ "enabled": true, "content": instructions }),
+    );
+    Some(sections)
+}
@@ -0,0 +1,54 @@
+//! Dictating into the app's own windows.
+//!
+//! When the main (settings and history) window has focus, the global paste
+//! path would have the app send keystrokes to itself, which paste restrictions
+//! on some systems block. Instead the text is sent to the window over IPC and
+//! inserted into its focused field by the frontend. The frontend reports when
+//! one of its text fields gains or loses focus, so other focus (a button, no
+//! field) still goes through the regular paste.
+
+use crate::settings_window::MAIN_WINDOW_LABEL;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event asking the main window to insert text into its focused field
+pub const INSERT_TEXT_EVENT: &str = "insert-text";
+
+/// Whether a text field of the main window has keyboard focus
+#[derive(Default)]
+pub struct InAppField {
+    focused: AtomicBool,
+}
+
+impl InAppField {
+    pub fn set_focused(&self, focused: bool) {
+        self.focused.store(focused, Ordering::Relaxed);
+    }
+
+    pub fn focused(&self) -> bool {
+        self.focused.load(Ordering::Relaxed)
+    }
+}
+
+/// Send text to the main window's focused field, if that is where the user is typing.
+/// Returns false when the text must be pasted the regular way.
+pub fn try_insert(app: &AppHandle, text: &str) -> bool {
+    let field_focused = app
+        .try_state::<InAppField>()
+        .is_some_and(|field| field.focused());
+    let window_focused = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false);
+    if !field_focused || !window_focused {
+        return false;
+    }
+
+    match app.emit_to(MAIN_WINDOW_LABEL, INSERT_TEXT_EVENT, text) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("Failed to send text to the main window: {}", e);
+            false
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Keyboard and mouse injection backend.
+//!
+//! Windows and macOS have a single way to send input. Linux has one per display
+//! server, so the session is detected at runtime: X11 sessions send input through
+//! XTEST, and Wayland sessions through the virtual keyboard protocol (wlroots
+//! compositors and KDE) or, where the compositor lacks it (GNOME), through the
+//! RemoteDesktop portal, which asks the user for permission once. XWayland is
+//! the last resort on Wayland, reaching only X11 apps.
+//!
+//! Typed characters are sent for the keyboard layout of the app receiving them.
+//! On Windows that layout is read from the foreground window, so characters it
+//! has no key for can be pasted instead of coming out as the wrong ones.
+//!
+//! Input is sent from the main thread, which macOS's text input APIs (used by
+//! enigo) require, while the waits around it run on the caller's thread. The main
+//! thread keeps one connection to the backend for all input: on Wayland each new
+//! connection is a new RemoteDesktop portal session, so connecting per keystroke
+//! would be slow and could ask for permission again. The connection is only
+//! replaced after sending through it failed.
+
+use enigo::{Enigo, Settings};
+use std::cell::RefCell;
+use std::sync::{mpsc, OnceLock};
+use std::thread::{self, ThreadId};
+use tauri::AppHandle;
+
+/// Thread the app's event loop runs on
+static MAIN_THREAD: OnceLock<ThreadId> = OnceLock::new();
+
+thread_local! {
+    /// Connection to the input backend, made on first use (only the main thread
+    /// sends input, so there is one)
+    static ENIGO: RefCell<Option<Enigo>> = const { RefCell::new(None) };
+}
+
+/// Display server of the desktop session
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayServer {
+    X11,
+    Wayland,
+}
+
+/// Work out the display server from the session environment: `XDG_SESSION_TYPE`,
+/// then whichever of `WAYLAND_DISPLAY` and `DISPLAY` is set (Wayland first, as
+/// XWayland also sets `DISPLAY`)
+pub fn detect_display_server(
+    session_type: Option<&str>,
+    wayland_display: Option<&str>,
+    x11_display: Option<&str>,
+) -> Option<DisplayServer> {
+    let is_set = |value: Option<&str>| value.is_some_and(|v| !v.is_empty());
+    match session_type.map(str::to_ascii_lowercase).as_deref() {
+        Some("wayland") => Some(DisplayServer::Wayland),
+        Some("x11") => Some(DisplayServer::X11),
+        _ if is_set(wayland_display) => Some(DisplayServer::Wayland),
+        _ if is_set(x11_display) => Some(DisplayServer::X11),
+        _ => None,
+    }
+}
+
+/// Display server of the running session
+pub fn display_server() -> Option<DisplayServer> {
+    let var = |name| std::env::var(name).ok();
+    detect_display_server(
+        var("XDG_SESSION_TYPE").as_deref(),
+        var("WAYLAND_DISPLAY").as_deref(),
+        var("DISPLAY").as_deref(),
+    )
+}
+
+/// Keyboard layout of the app with focus
+pub struct KeyboardLayout {
+    #[cfg(target_os = "windows")]
+    layout: windows::Win32::UI::Input::KeyboardAndMouse::HKL,
+}
+
+impl KeyboardLayout {
+    /// Layout of the foreground window, or `None` where it can't be read
+    #[cfg(target_os = "windows")]
+    pub fn of_foreground() -> Option<Self> {
+        use windows::Win32::UI::Input::KeyboardAndMouse::GetKeyboardLayout;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetForegroundWindow, GetWindowThreadProcessId,
+        };
+
+        // SAFETY: queries on the foreground window's thread with no out-params
+        let layout = unsafe {
+            let foreground = GetForegroundWindow();
+            if foreground.is_invalid() {
+                return None;
+            }
+            GetKeyboardLayout(GetWindowThreadProcessId(foreground, None))
+        };
+        (!layout.is_invalid()).then_some(Self { layout })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn of_foreground() -> Option<Self> {
+        None
+    }
+
+    /// Whether a key (with modifiers) on this layout produces the character. Control
+    /// characters such as newlines are always typeable.
+    #[cfg(target_os = "windows")]
+    pub fn has_key(&self, character: char) -> bool {
+        use windows::Win32::UI::Input::KeyboardAndMouse::VkKeyScanExW;
+
+        if character.is_control() {
+            return true;
+        }
+        let mut units = [0u16; 2];
+        let &mut [unit] = character.encode_utf16(&mut units) else {
+            // Characters outside the Basic Multilingual Plane have no key
+            return false;
+        };
+        // SAFETY: VkKeyScanExW only reads its arguments
+        unsafe { VkKeyScanExW(unit, self.layout) != -1 }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn has_key(&self, _character: char) -> bool {
+        true
+    }
+}
+
+/// Split text into runs of characters that can be typed and runs that can't, in order
+pub fn split_typeable(text: &str, has_key: impl Fn(char) -> bool) -> Vec<(bool, &str)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut current = None;
+    for (index, character) in text.char_indices() {
+        let typeable = has_key(character);
+        if let Some(kind) = current.filter(|kind| *kind != typeable) {
+            runs.push((kind, &text[start..index]));
+            start = index;
+        }
+        current = Some(typeable);
+    }
+    if let Some(kind) = current {
+        runs.push((kind, &text[start..]));
+    }
+    runs
+}
+
+/// Note the calling thread as the main thread; called first thing at startup
+pub fn remember_main_thread() {
+    let _ = MAIN_THREAD.set(thread::current().id());
+}
+
+/// Send input through the backend on the main thread and wait for the outcome.
+/// Runs right away when called on the main thread.
+pub fn with_enigo<T, F>(app: &AppHandle, send: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Enigo) -> Result<T, String> + Send + 'static,
+{
+    let run = move || ENIGO.with(|cached| with_connection(cached, connect, send));
+    if MAIN_THREAD.get() == Some(&thread::current().id()) {
+        return run();
+    }
+    let (tx, rx) = mpsc::channel();
+    app.run_on_main_thread(move || {
+        let _ = tx.send(run());
+    })
+    .map_err(|e| e.to_string())?;
+    rx.recv().map_err(|e| e.to_string())?
+}
+
+/// Send through the cached connection, connecting first when there is none. A
+/// failed send drops the connection, as the failure may be the connection's own
+/// (a closed portal session, a restarted compositor), so the next send reconnects.
+pub fn with_connection<C, T>(
+    cached: &RefCell<Option<C>>,
+    connect: impl FnOnce() -> Result<C, String>,
+    send: impl FnOnce(&mut C) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut cached = cached.borrow_mut();
+    let connection = match cached.as_mut() {
+        Some(connection) => connection,
+        None => cached.insert(connect()?),
+    };
+    let result = send(connection);
+    if result.is_err() {
+        *cached = None;
+    }
+    result
+}
+
+/// Connect to the input backend for this session
+fn connect() -> Result<Enigo, String> {
+    Enigo::new(&settings()).map_err(|e| match display_server() {
+        Some(DisplayServer::Wayland) => format!(
+            "Failed to connect to the Wayland input backend ({}). The compositor needs the \
+             virtual keyboard protocol or the RemoteDesktop portal.",
+            e
+        ),
+        _ => format!("Failed to connect to the input backend: {}", e),
+    })
+}
+
+fn settings() -> Settings {
+    // Backends are tried in the order Wayland virtual keyboard, RemoteDesktop portal,
+    // X11; the ones whose display isn't set are skipped
+    #[cfg(target_os = "linux")]
+    log::debug!("Input backend for display server {:?}", display_server());
+    Settings::default()
+}
@@ -0,0 +1,201 @@
+//! Daily journal compiled from the day's dictations.
+//!
+//! With `journal_folder` set, a background job writes each finished day's
+//! dictations to `<folder>/YYYY-MM-DD.md` as one Markdown journal entry, in the
+//! same `- HH:MM text` form used for output files. The folder may start with `~`
+//! for the home directory. With `journal_summarize`, the server's LLM writes a
+//! short summary that heads the entry; when the server can't be reached the
+//! journal is written without one.
+//!
+//! A day that already has a file is left alone, so an edited journal is never
+//! overwritten. Days missed while the app wasn't running are caught up for up to
+//! a week, and days without dictations get no file.
+
+use crate::history::{HistoryEntry, HistoryRange, HistoryStorage};
+use chrono::{DateTime, Days, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How often the job looks for days to compile
+const CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// How many past days are compiled if their journal is missing
+const CATCH_UP_DAYS: u64 = 7;
+
+/// How long the server may take to summarize a day
+const SUMMARY_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Serialize)]
+struct SummarizeRequest<'a> {
+    text: &'a str,
+    provider: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SummarizeResponse {
+    summary: String,
+}
+
+/// The time range of a calendar day in `tz`
+pub fn day_range<Tz: TimeZone>(day: NaiveDate, tz: &Tz) -> HistoryRange {
+    let midnight = |date: NaiveDate| {
+        let naive = date.and_time(NaiveTime::MIN);
+        tz.from_local_datetime(&naive)
+            .earliest()
+            .map(|local| local.with_timezone(&Utc))
+            .unwrap_or_else(|| naive.and_utc())
+    };
+    HistoryRange {
+        from: Some(midnight(day)),
+        to: day.checked_add_days(Days::new(1)).map(midnight),
+    }
+}
+
+/// Past days, oldest first, whose journal is still missing
+pub fn pending_days(today: NaiveDate, exists: impl Fn(NaiveDate) -> bool) -> Vec<NaiveDate> {
+    (1..=CATCH_UP_DAYS)
+        .rev()
+        .filter_map(|back| today.checked_sub_days(Days::new(back)))
+        .filter(|day| !exists(*day))
+        .collect()
+}
+
+/// The dictations of a day (newest first, as stored) as a list, oldest first, with
+/// times shown in `tz`
+pub fn render_dictations<Tz: TimeZone>(entries: &[HistoryEntry], tz: &Tz) -> String
+where
+    Tz::Offset: Display,
+{
+    entries
+        .iter()
+        .rev()
+        .map(|entry| {
+            format!(
+                "- {} {}\n",
+                entry.timestamp.with_timezone(tz).format("%H:%M"),
+                entry
+                    .revised_text
+                    .as_deref()
+                    .unwrap_or(&entry.text)
+                    .trim()
+                    .replace('\n', "\n  ")
+            )
+        })
+        .collect()
+}
+
+/// A day's journal entry, headed by the summary when there is one
+pub fn render(day: NaiveDate, dictations: &str, summary: Option<&str>) -> String {
+    let mut out = format!("# Journal {}\n\n", day.format("%Y-%m-%d"));
+    if let Some(summary) = summary.map(str::trim).filter(|summary| !summary.is_empty()) {
+        out.push_str(&format!("## Summary\n\n{}\n\n## Dictations\n\n", summary));
+    }
+    out.push_str(dictations);
+    out
+}
+
+/// The journal file of a day in the configured folder
+pub fn journal_path(folder: &Path, day: NaiveDate) -> PathBuf {
+    folder.join(format!("{}.md", day.format("%Y-%m-%d")))
+}
+
+/// The configured journal folder, or none when journals are off
+fn stored_folder(app: &AppHandle) -> Option<PathBuf> {
+    let folder = crate::settings::read_setting::<Option<String>>(app, "journal_folder")
+        .filter(|folder| !folder.trim().is_empty())?;
+    let home = app.path().home_dir().ok();
+    Some(crate::output_file::expand_path(
+        &folder,
+        Local::now(),
+        home.as_deref(),
+    ))
+}
+
+/// Ask the server's LLM for a summary of the day's dictations
+fn summarize(app: &AppHandle, dictations: &str) -> Result<String, String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(SUMMARY_TIMEOUT))
+        .build()
+        .into();
+    let request = SummarizeRequest {
+        text: dictations,
+        provider: crate::settings::read_setting(app, "llm_provider"),
+    };
+    let response: SummarizeResponse = agent
+        .post(format!(
+            "{}/api/summarize",
+            crate::commands::text::SERVER_URL
+        ))
+        .send_json(&request)
+        .map_err(|e| format!("Failed to summarize journal: {}", e))?
+        .body_mut()
+        .read_json()
+        .map_err(|e| format!("Failed to read journal summary: {}", e))?;
+    Ok(response.summary)
+}
+
+/// Write the journal of one day, returning its path, or none without dictations
+fn compile_day(
+    app: &AppHandle,
+    history: &HistoryStorage,
+    folder: &Path,
+    day: NaiveDate,
+) -> Result<Option<PathBuf>, String> {
+    let entries = history.get_range(&day_range(day, &Local))?;
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    let dictations = render_dictations(&entries, &Local);
+    let summary = if crate::settings::read_setting::<bool>(app, "journal_summarize") {
+        summarize(app, &dictations)
+            .inspect_err(|e| log::warn!("{}; writing the journal without it", e))
+            .ok()
+    } else {
+        None
+    };
+
+    fs::create_dir_all(folder)
+        .map_err(|e| format!("Failed to create {}: {}", folder.display(), e))?;
+    let path = journal_path(folder, day);
+    crate::settings::write_file_atomic(
+        &path,
+        render(day, &dictations, summary.as_deref()).as_bytes(),
+    )?;
+    Ok(Some(path))
+}
+
+/// Compile the journals of past days that don't have one yet
+pub fn compile_pending(app: &AppHandle, history: &HistoryStorage, today: DateTime<Local>) {
+    let Some(folder) = stored_folder(app) else {
+        return;
+    };
+    if history.is_locked() {
+        return;
+    }
+
+    for day in pending_days(today.date_naive(), |day| {
+        journal_path(&folder, day).exists()
+    }) {
+        match compile_day(app, history, &folder, day) {
+            Ok(Some(path)) => log::info!("Journal written to {}", path.display()),
+            Ok(None) => {}
+            Err(e) => log::error!("{}", e),
+        }
+    }
+}
+
+/// Start compiling journals on a background thread
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || loop {
+        if let Some(history) = app.try_state::<HistoryStorage>() {
+            compile_pending(&app, &history, Local::now());
+        }
+        thread::sleep(CHECK_INTERVAL);
+    });
+}
@@ -1,6 +1,5 @@
 use std::sync::atomic::Ordering;
 use tauri::{
-    menu::{Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager,
 };
@@ -8,20 +7,93 @@ use tauri_utils::config::BackgroundThrottlingPolicy;
 
 mod audio;
 mod audio_mute;
+mod automation_api;
+mod autostart;
+mod browser_extension;
+mod calendar;
+mod caret;
+pub mod cli;
+mod clipboard_cleanup;
+mod clipboard_snapshot;
 mod commands;
+mod deep_link;
+mod display_watcher;
+mod edge_indicator;
+mod email_draft;
+mod error;
+mod event_stream;
+mod failed_dictations;
+mod file_transcription;
+mod foreground_app;
+mod fullscreen;
 mod history;
+mod history_audio;
+mod history_crypto;
+mod history_duplicates;
+mod history_export;
+mod history_pruner;
+mod history_sync;
+mod importers;
+mod in_app_insert;
+mod input_backend;
+mod journal;
+mod live_transcript;
+mod logging;
+mod metrics;
+mod mqtt;
+mod notifications;
+mod obs_captions;
+mod obsidian;
+mod onboarding;
+mod output_file;
+mod output_sinks;
+mod overlay;
+mod overlay_auto_hide;
+mod overlay_state;
+mod paste_ring;
+mod paste_target;
+mod paste_undo;
+mod pipeline;
+mod quota;
+mod recording_progress;
+mod recording_recovery;
+mod recording_state;
+mod remote_control;
+mod review;
+mod rich_text;
 mod settings;
+mod settings_watcher;
+mod settings_window;
+mod shutdown;
+mod single_instance;
+mod smart_insert;
+mod snippets;
 mod state;
+mod stats;
+mod stream_deck;
+mod tray_menu;
+mod tray_status;
+mod vocabulary;
+mod webhooks;
 
 #[cfg(test)]
 mod tests;
 
 use audio_mute::AudioMuteManager;
-use history::HistoryStorage;
-use settings::HotkeyConfig;
+use failed_dictations::FailedDictations;
+use logging::LogLevel;
+use metrics::{AnalyticsMode, MetricEvent, MetricsRecorder};
+use notifications::{notify, NotificationKind};
+use overlay_state::{set_overlay_state, OverlayState};
+use pipeline::DictationPipeline;
+use quota::{CloudUse, QuotaLevel, QuotaLimits, QuotaTracker};
+use recording_state::{RecordingMode, RecordingState};
+use settings::{
+    read_setting_or, HotkeyConfig, SettingsManager, SettingsPolicy, SETTINGS_FILE_NAME,
+};
+use snippets::SnippetLibrary;
 use state::AppState;
 
-#[cfg(desktop)]
 use tauri_plugin_store::StoreExt;
 
 #[cfg(desktop)]
@@ -48,18 +120,26 @@ pub(crate) fn normalize_shortcut_string(s: &str) -> String {
         .replace("win", "super")
 }
 
-/// Helper to read a setting from the store with a default fallback
-#[cfg(desktop)]
-fn get_setting_from_store<T: serde::de::DeserializeOwned>(
-    app: &AppHandle,
-    key: &str,
-    default: T,
-) -> T {
-    app.store("settings.json")
-        .ok()
-        .and_then(|store| store.get(key))
-        .and_then(|v| serde_json::from_value(v).ok())
-        .unwrap_or(default)
+/// Record a usage metric (no-op unless analytics are enabled). The mode is read from
+/// the store each time, so a change from a reset, an external edit or the admin
+/// policy applies to the next event.
+pub(crate) fn record_metric(app: &AppHandle, event: MetricEvent) {
+    if let Some(metrics) = app.try_state::<MetricsRecorder>() {
+        let mode: AnalyticsMode = crate::settings::read_setting(app, "analytics_mode");
+        if let Err(e) = metrics.record(mode, event) {
+            log::warn!("Failed to record metric: {}", e);
+        }
+    }
+}
+
+/// Ask the overlay to disconnect from the server and give it time to do so.
+/// Called last when the app exits or restarts (see [`shutdown`]).
+pub(crate) fn disconnect_overlay(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(overlay::OVERLAY_LABEL) {
+        let _ = window.emit("request-disconnect", ());
+    }
+    // Give frontend time to disconnect gracefully
+    std::thread::sleep(std::time::Duration::from_millis(500));
 }
 
 /// Start recording with sound and audio mute handling
@@ -70,12 +150,63 @@ fn start_recording(
     sound_enabled: bool,
     audio_mute_manager: &Option<tauri::State<'_, AudioMuteManager>>,
     auto_mute_audio: bool,
+    mode: RecordingMode,
     source: &str,
 ) {
-    state.is_recording.store(true, Ordering::SeqCst);
+    if state.lifecycle.is_recording() {
+        return;
+    }
+    // Refuse to record once a daily limit the dictation counts towards is reached
+    let limits = QuotaLimits::from_store(app).applying_to(CloudUse::from_store(app));
+    if let Some(tracker) = app.try_state::<QuotaTracker>() {
+        match tracker.status(limits) {
+            Ok(status) if status.level == QuotaLevel::Exceeded => {
+                log::warn!("{}: daily dictation limit reached, not recording", source);
+                set_overlay_state(
+                    app,
+                    OverlayState::Error {
+                        message: "Daily dictation limit reached".to_string(),
+                    },
+                );
+                let _ = app.emit("quota-exceeded", status);
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to check dictation quota: {}", e),
+        }
+    }
+
+    if let Err(e) = recording_state::transition(app, RecordingState::Recording { mode }) {
+        log::warn!("{}: not starting recording: {}", source, e);
+        return;
+    }
+    if let Some(tracker) = app.try_state::<QuotaTracker>() {
+        tracker.recording_started();
+    }
+    state.copy_only_dictation.store(false, Ordering::SeqCst);
+    if let Ok(mut profile) = state.dictation_profile.lock() {
+        *profile = None;
+    }
     log::info!("{}: starting recording", source);
+    paste_target::remember(app);
+    let suppressed = fullscreen::check_foreground(app).suppressed;
+    if suppressed {
+        log::info!(
+            "{}: fullscreen app in front, hiding overlay and sounds",
+            source
+        );
+    } else {
+        // Bring the overlay to the monitor being worked on (or next to the cursor or caret)
+        if let Err(e) = overlay::position_overlay(app, false) {
+            log::warn!("Failed to position overlay: {}", e);
+        }
+        if let Err(e) = edge_indicator::apply_edge_indicator(app) {
+            log::warn!("Failed to position edge indicator: {}", e);
+        }
+    }
+    record_metric(app, MetricEvent::RecordingStarted);
     // Play sound BEFORE muting so it's audible
-    if sound_enabled {
+    if sound_enabled && !suppressed {
         audio::play_sound(audio::SoundType::RecordingStart);
         // Brief delay to let sound play before muting
         std::thread::sleep(std::time::Duration::from_millis(150));
@@ -88,7 +219,10 @@ fn start_recording(
             }
         }
     }
+    set_overlay_state(app, OverlayState::Recording);
     let _ = app.emit("recording-start", ());
+    recording_progress::start_ticker(app);
+    tray_menu::refresh(app);
 }
 
 /// Stop recording with sound and audio unmute handling
@@ -101,7 +235,10 @@ fn stop_recording(
     auto_mute_audio: bool,
     source: &str,
 ) {
-    state.is_recording.store(false, Ordering::SeqCst);
+    // Nothing to stop if the recording was refused (e.g. hold released after a quota block)
+    if recording_state::transition(app, RecordingState::Transcribing).is_err() {
+        return;
+    }
     log::info!("{}: stopping recording", source);
     // Unmute system audio if it was muted
     if auto_mute_audio {
@@ -111,10 +248,106 @@ fn stop_recording(
             }
         }
     }
-    if sound_enabled {
+    if sound_enabled && !fullscreen::is_suppressed(app) {
         audio::play_sound(audio::SoundType::RecordingStop);
     }
+    set_overlay_state(app, OverlayState::Transcribing);
     let _ = app.emit("recording-stop", ());
+    tray_menu::refresh(app);
+
+    // Count the dictation towards today's usage and warn when nearing a limit
+    if let Some(tracker) = app.try_state::<QuotaTracker>() {
+        let cloud = CloudUse::from_store(app);
+        let status = tracker
+            .recording_stopped(cloud)
+            .and_then(|_| tracker.status(QuotaLimits::from_store(app).applying_to(cloud)));
+        match status {
+            Ok(status) if status.level == QuotaLevel::Warning => {
+                notify(
+                    app,
+                    NotificationKind::QuotaWarning,
+                    "You are close to today's dictation limit",
+                );
+                let _ = app.emit("quota-warning", status);
+            }
+            Ok(status) if status.level == QuotaLevel::Exceeded => {
+                notify(
+                    app,
+                    NotificationKind::QuotaWarning,
+                    "Today's dictation limit has been reached",
+                );
+                let _ = app.emit("quota-exceeded", status);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to record dictation usage: {}", e),
+        }
+    }
+}
+
+/// Start or stop recording depending on the current state (used by the tray menu)
+#[cfg(desktop)]
+pub(crate) fn toggle_recording(app: &AppHandle, source: &str) {
+    set_recording(app, !recording_state::is_recording(app), source);
+}
+
+/// Start or stop recording, doing nothing when it already is in that state (used by
+/// the automation API)
+#[cfg(desktop)]
+pub(crate) fn set_recording(app: &AppHandle, recording: bool, source: &str) {
+    let state = app.state::<AppState>();
+    let sound_enabled: bool = read_setting_or(app, "sound_enabled", true);
+    let auto_mute_audio: bool = read_setting_or(app, "auto_mute_audio", false);
+    let audio_mute_manager = app.try_state::<AudioMuteManager>();
+
+    if recording == state.lifecycle.is_recording() {
+        return;
+    }
+    if recording {
+        start_recording(
+            app,
+            &state,
+            sound_enabled,
+            &audio_mute_manager,
+            auto_mute_audio,
+            RecordingMode::Toggle,
+            source,
+        );
+    } else {
+        stop_recording(
+            app,
+            &state,
+            sound_enabled,
+            &audio_mute_manager,
+            auto_mute_audio,
+            source,
+        );
+    }
+}
+
+/// Stop recording and drop the audio, or abort the transcription and cleanup of a
+/// dictation already on its way, so nothing is pasted (from the cancel hotkey and the
+/// Stream Deck socket)
+#[cfg(desktop)]
+pub(crate) fn cancel_recording(app: &AppHandle, source: &str) {
+    let was_recording = recording_state::transition(app, RecordingState::Idle)
+        .is_ok_and(|previous| previous.is_recording());
+    let stage = app
+        .try_state::<DictationPipeline>()
+        .and_then(|pipeline| pipeline.cancel());
+    if !was_recording && stage.is_none() {
+        return;
+    }
+    log::info!("{}: cancelling dictation ({:?})", source, stage);
+    if was_recording && read_setting_or(app, "auto_mute_audio", false) {
+        if let Some(manager) = app.try_state::<AudioMuteManager>() {
+            if let Err(e) = manager.unmute() {
+                log::warn!("Failed to unmute audio: {}", e);
+            }
+        }
+    }
+    set_overlay_state(app, OverlayState::Idle);
+    let _ = app.emit("recording-cancel", ());
+    tray_menu::refresh(app);
 }
 
 /// Handle a shortcut event - public so it can be called from commands/settings.rs
@@ -123,19 +356,28 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
     let state = app.state::<AppState>();
 
     // Get current settings from store
-    let sound_enabled: bool = get_setting_from_store(app, "sound_enabled", true);
-    let auto_mute_audio: bool = get_setting_from_store(app, "auto_mute_audio", false);
+    let sound_enabled: bool = read_setting_or(app, "sound_enabled", true);
+    let auto_mute_audio: bool = read_setting_or(app, "auto_mute_audio", false);
 
     // Get shortcut string for comparison (normalized to handle "ctrl" vs "control" differences)
     let shortcut_str = normalize_shortcut_string(&shortcut.to_string());
 
     // Get configured shortcut strings from store (normalized), with validation fallback
     let toggle_hotkey: HotkeyConfig =
-        get_setting_from_store(app, "toggle_hotkey", HotkeyConfig::default_toggle());
+        read_setting_or(app, "toggle_hotkey", HotkeyConfig::default_toggle());
     let hold_hotkey: HotkeyConfig =
-        get_setting_from_store(app, "hold_hotkey", HotkeyConfig::default_hold());
+        read_setting_or(app, "hold_hotkey", HotkeyConfig::default_hold());
     let paste_last_hotkey: HotkeyConfig =
-        get_setting_from_store(app, "paste_last_hotkey", HotkeyConfig::default_paste_last());
+        read_setting_or(app, "paste_last_hotkey", HotkeyConfig::default_paste_last());
+    let undo_paste_hotkey: HotkeyConfig =
+        read_setting_or(app, "undo_paste_hotkey", HotkeyConfig::default_undo_paste());
+    let clipboard_cleanup_hotkey: HotkeyConfig = read_setting_or(
+        app,
+        "clipboard_cleanup_hotkey",
+        HotkeyConfig::default_clipboard_cleanup(),
+    );
+    let cancel_hotkey: HotkeyConfig =
+        read_setting_or(app, "cancel_hotkey", HotkeyConfig::default_cancel());
 
     // Validate hotkeys - if they can't be parsed as shortcuts, use defaults
     let toggle_shortcut_str = normalize_shortcut_string(
@@ -156,6 +398,24 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
             .map(|_| paste_last_hotkey.to_shortcut_string())
             .unwrap_or_else(|_| HotkeyConfig::default_paste_last().to_shortcut_string()),
     );
+    let undo_paste_shortcut_str = normalize_shortcut_string(
+        &undo_paste_hotkey
+            .to_shortcut()
+            .map(|_| undo_paste_hotkey.to_shortcut_string())
+            .unwrap_or_else(|_| HotkeyConfig::default_undo_paste().to_shortcut_string()),
+    );
+    let clipboard_cleanup_shortcut_str = normalize_shortcut_string(
+        &clipboard_cleanup_hotkey
+            .to_shortcut()
+            .map(|_| clipboard_cleanup_hotkey.to_shortcut_string())
+            .unwrap_or_else(|_| HotkeyConfig::default_clipboard_cleanup().to_shortcut_string()),
+    );
+    let cancel_shortcut_str = normalize_shortcut_string(
+        &cancel_hotkey
+            .to_shortcut()
+            .map(|_| cancel_hotkey.to_shortcut_string())
+            .unwrap_or_else(|_| HotkeyConfig::default_cancel().to_shortcut_string()),
+    );
 
     // Get audio mute manager if available
     let audio_mute_manager = app.try_state::<AudioMuteManager>();
@@ -164,54 +424,57 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
     let is_toggle = shortcut_str == toggle_shortcut_str;
     let is_hold = shortcut_str == hold_shortcut_str;
     let is_paste_last = shortcut_str == paste_last_shortcut_str;
+    let is_undo_paste = shortcut_str == undo_paste_shortcut_str;
+    let is_clipboard_cleanup = shortcut_str == clipboard_cleanup_shortcut_str;
+    let is_cancel = shortcut_str == cancel_shortcut_str;
 
     if is_toggle {
-        // Toggle mode: action happens on key release (debounced)
+        // Toggle mode: action happens on key release, once however long the key is held
         match event.state {
-            ShortcutState::Pressed => {
-                state.toggle_key_held.swap(true, Ordering::SeqCst);
-            }
+            ShortcutState::Pressed => {}
             ShortcutState::Released => {
-                if state.toggle_key_held.swap(false, Ordering::SeqCst) {
-                    if state.is_recording.load(Ordering::SeqCst) {
-                        stop_recording(
-                            app,
-                            &state,
-                            sound_enabled,
-                            &audio_mute_manager,
-                            auto_mute_audio,
-                            "Toggle",
-                        );
-                    } else {
-                        start_recording(
-                            app,
-                            &state,
-                            sound_enabled,
-                            &audio_mute_manager,
-                            auto_mute_audio,
-                            "Toggle",
-                        );
-                    }
-                }
-            }
-        }
-    } else if is_hold {
-        // Hold-to-Record: start on press, stop on release
-        match event.state {
-            ShortcutState::Pressed => {
-                if !state.ptt_key_held.swap(true, Ordering::SeqCst) {
+                if state.lifecycle.is_recording() {
+                    stop_recording(
+                        app,
+                        &state,
+                        sound_enabled,
+                        &audio_mute_manager,
+                        auto_mute_audio,
+                        "Toggle",
+                    );
+                } else {
                     start_recording(
                         app,
                         &state,
                         sound_enabled,
                         &audio_mute_manager,
                         auto_mute_audio,
-                        "Hold",
+                        RecordingMode::Toggle,
+                        "Toggle",
                     );
                 }
             }
+        }
+    } else if is_hold {
+        // Hold-to-Record: start on press (key repeat finds it recording), stop on release
+        // of a recording the hold started
+        let hold_recording = RecordingState::Recording {
+            mode: RecordingMode::Hold,
+        };
+        match event.state {
+            ShortcutState::Pressed => {
+                start_recording(
+                    app,
+                    &state,
+                    sound_enabled,
+                    &audio_mute_manager,
+                    auto_mute_audio,
+                    RecordingMode::Hold,
+                    "Hold",
+                );
+            }
             ShortcutState::Released => {
-                if state.ptt_key_held.swap(false, Ordering::SeqCst) {
+                if state.lifecycle.current() == hold_recording {
                     stop_recording(
                         app,
                         &state,
@@ -232,22 +495,62 @@ pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: &Short
             }
             ShortcutState::Released => {
                 if state.paste_key_held.swap(false, Ordering::SeqCst) {
-                    // Key released - do the paste
+                    // Key released - do the paste, off the main thread as it waits
+                    // between keys
                     log::info!("PasteLast: pasting last transcription");
-                    let history_storage = app.state::<HistoryStorage>();
-
-                    if let Ok(entries) = history_storage.get_all(Some(1)) {
-                        if let Some(entry) = entries.first() {
-                            if let Err(e) = commands::text::type_text_blocking(&entry.text) {
-                                log::error!("Failed to paste last transcription: {}", e);
-                            }
-                        } else {
-                            log::info!("PasteLast: no history entries available");
+                    record_metric(app, MetricEvent::PasteLastUsed);
+                    let app = app.clone();
+                    std::thread::spawn(move || {
+                        if let Err(e) = paste_ring::paste_from_hotkey(&app) {
+                            log::error!("Failed to paste last transcription: {}", e);
                         }
-                    }
+                    });
                 }
             }
         }
+    } else if is_undo_paste {
+        // Undo last paste: on release, so the hotkey's modifiers are up
+        match event.state {
+            ShortcutState::Pressed => {
+                state.undo_key_held.swap(true, Ordering::SeqCst);
+            }
+            ShortcutState::Released => {
+                if state.undo_key_held.swap(false, Ordering::SeqCst) {
+                    log::info!("UndoPaste: removing last paste");
+                    let app = app.clone();
+                    std::thread::spawn(move || {
+                        if let Err(e) = paste_undo::undo(&app) {
+                            log::warn!("Failed to undo last paste: {}", e);
+                            notify(&app, NotificationKind::ProviderError, e.message());
+                        }
+                    });
+                }
+            }
+        }
+    } else if is_clipboard_cleanup {
+        // Clean up the copied text: on release, like undo
+        match event.state {
+            ShortcutState::Pressed => {
+                state
+                    .clipboard_cleanup_key_held
+                    .swap(true, Ordering::SeqCst);
+            }
+            ShortcutState::Released => {
+                if state
+                    .clipboard_cleanup_key_held
+                    .swap(false, Ordering::SeqCst)
+                {
+                    log::info!("ClipboardCleanup: cleaning up copied text");
+                    clipboard_cleanup::run(app);
+                }
+            }
+        }
+    } else if is_cancel {
+        // Cancel on press: the sooner the requests stop, the less they cost
+        match event.state {
+            ShortcutState::Pressed => cancel_recording(app, "CancelHotkey"),
+            ShortcutState::Released => {}
+        }
     } else {
         log::warn!("Unknown shortcut: {}", shortcut_str);
     }
@@ -261,13 +564,19 @@ fn is_audio_mute_supported() -> bool {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logger
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    // Initialize logger (file output and configured level are applied during setup)
+    logging::init();
+    input_backend::remember_main_thread();
 
     let mut builder = tauri::Builder::default();
 
     #[cfg(desktop)]
     {
+        // Registered first: a second launch, such as from a tambourine:// link, hands
+        // its arguments over to the running app and exits
+        builder = builder.plugin(tauri_plugin_single_instance::init(
+            single_instance::on_second_instance,
+        ));
         builder = builder.plugin(build_global_shortcut_plugin());
     }
 
@@ -279,18 +588,138 @@ pub fn run() {
     builder
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_deep_link::init())
         .manage(AppState::default())
+        .manage(overlay::OverlayPlacement::default())
+        .manage(overlay_state::OverlayStateMachine::default())
+        .manage(DictationPipeline::default())
+        .manage(shutdown::Shutdown::default())
+        .manage(overlay_auto_hide::OverlayAutoHide::default())
+        .manage(paste_ring::PasteCycle::default())
+        .manage(output_sinks::OutputSinks::default())
+        .manage(in_app_insert::InAppField::default())
+        .manage(live_transcript::LiveTranscript::default())
+        .manage(tray_menu::TrayMenuState::default())
+        .manage(recording_progress::RecordingProgressTicker::default())
         .invoke_handler(tauri::generate_handler![
+            commands::text::begin_dictation,
             commands::text::type_text,
             commands::text::get_server_url,
+            commands::text::review_transcript,
+            commands::text::get_pending_paste,
+            commands::text::paste_nth_last,
+            commands::text::paste_held,
+            commands::text::undo_last_paste,
+            commands::text::set_copy_only_mode,
+            commands::text::set_in_app_field_focused,
+            commands::text::confirm_paste,
+            commands::text::discard_pending,
             commands::settings::register_shortcuts,
             commands::settings::unregister_shortcuts,
+            commands::settings::save_settings,
+            commands::settings::reset_settings,
+            commands::settings::get_locked_settings,
+            commands::settings::describe_settings,
+            commands::settings::get_pending_restart_changes,
+            commands::settings::take_settings_recovery,
+            commands::settings::restart_app,
+            commands::settings::open_settings,
+            commands::settings::regenerate_automation_api_token,
+            commands::settings::test_webhook,
+            commands::settings::test_output_sink,
+            commands::settings::import_from_tool,
+            commands::settings::install_browser_extension_host,
+            commands::settings::send_test_obs_caption,
+            commands::settings::get_remote_control_pairing,
+            commands::settings::regenerate_remote_pairing_code,
             is_audio_mute_supported,
             commands::history::add_history_entry,
+            commands::snippets::list_snippets,
+            commands::snippets::add_snippet,
+            commands::snippets::update_snippet,
+            commands::snippets::delete_snippet,
+            commands::snippets::paste_snippet,
+            commands::snippets::resolve_snippet_command,
+            commands::failed_dictations::record_failed_dictation,
+            commands::failed_dictations::list_failed_dictations,
+            commands::failed_dictations::get_failed_dictation_audio,
+            commands::failed_dictations::note_failed_dictation_retry,
+            commands::failed_dictations::begin_recording_recovery,
+            commands::failed_dictations::append_recording_recovery,
+            commands::failed_dictations::finish_recording_recovery,
+            commands::failed_dictations::discard_failed_dictation,
+            commands::file_transcription::transcribe_files,
+            commands::file_transcription::list_file_transcriptions,
+            commands::file_transcription::cancel_file_transcription,
+            commands::file_transcription::clear_finished_file_transcriptions,
             commands::history::get_history,
+            commands::history::save_history_audio,
+            commands::history::get_history_audio,
+            commands::history::set_history_entry_tags,
+            commands::history::set_history_entry_note,
+            commands::history::list_history_tags,
+            commands::history::get_history_by_tag,
+            commands::history::list_history_apps,
+            commands::history::get_history_by_app,
+            commands::history::search_history,
+            commands::history::get_pinned_history,
+            commands::history::set_history_entry_pinned,
+            commands::history::add_history_revision,
+            commands::history::get_history_revisions,
             commands::history::delete_history_entry,
             commands::history::clear_history,
+            commands::history::list_trashed_history,
+            commands::history::restore_history_entry,
+            commands::history::purge_history_entry,
+            commands::history::empty_history_trash,
+            commands::history::export_history,
+            commands::history::start_session,
+            commands::history::start_meeting,
+            commands::history::end_session,
+            commands::history::get_active_session,
+            commands::history::list_sessions,
+            commands::history::export_session,
+            commands::history::update_history_limits,
+            commands::history::is_history_locked,
+            commands::history::unlock_history,
+            commands::history::set_history_encryption,
+            commands::history::sync_history,
             commands::overlay::resize_overlay,
+            commands::overlay::get_overlay_scale,
+            commands::overlay::get_monitors,
+            commands::overlay::reposition_overlay,
+            commands::overlay::reset_overlay_position,
+            commands::overlay::update_overlay_click_through,
+            commands::overlay::update_overlay_capture_exclusion,
+            commands::overlay::report_overlay_state,
+            commands::overlay::get_app_state,
+            commands::overlay::get_overlay_appearance,
+            commands::overlay::update_overlay_appearance,
+            commands::overlay::update_overlay_layout,
+            commands::overlay::set_overlay_hovered,
+            commands::overlay::recalibrate_overlay,
+            commands::tray::set_tray_providers,
+            commands::transcript::open_transcript_window,
+            commands::transcript::close_transcript_window,
+            commands::transcript::update_live_transcript,
+            commands::transcript::get_live_transcript,
+            commands::transcript::copy_live_transcript,
+            commands::edge_indicator::update_edge_indicator,
+            commands::edge_indicator::reposition_edge_indicator,
+            commands::onboarding::get_onboarding_state,
+            commands::onboarding::complete_onboarding_step,
+            commands::autostart::enable_autostart,
+            commands::autostart::disable_autostart,
+            commands::autostart::is_autostart_enabled,
+            commands::metrics::get_local_metrics,
+            commands::metrics::clear_local_metrics,
+            commands::metrics::update_analytics_mode,
+            commands::quota::get_quota_status,
+            commands::stats::get_stats,
+            commands::stats::get_vocabulary_insights,
+            commands::logging::get_recent_logs,
+            commands::logging::update_log_level,
         ])
         .setup(|app| {
             // Initialize history storage
@@ -299,7 +728,84 @@ pub fn run() {
                 .app_data_dir()
                 .expect("Failed to get app data directory");
 
-            let history_storage = HistoryStorage::new(app_data_dir);
+            // Machine-local settings go to the cache directory, as the local data directory
+            // is the same as the data directory on macOS and Linux
+            let app_cache_dir = app
+                .path()
+                .app_cache_dir()
+                .expect("Failed to get app cache directory");
+            // Validate settings before the store first loads them, recovering from backup
+            let settings_manager = SettingsManager::new(app_data_dir.clone(), app_cache_dir)
+                .with_policy(SettingsPolicy::load(&settings::default_policy_path()));
+            match settings_manager.recover_if_corrupted() {
+                Ok(Some(backup)) => {
+                    log::warn!("Settings were corrupted and restored from {:?}", backup);
+                    // No window is listening yet, so the main window asks for it once loaded
+                    let state = app.state::<AppState>();
+                    if let Ok(mut recovered) = state.settings_recovered_from.lock() {
+                        *recovered = Some(backup.to_string_lossy().into_owned());
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => log::error!("Failed to recover settings: {}", e),
+            }
+
+            // Load the settings store without auto-save; writes go through SettingsManager
+            let settings_store = app
+                .store_builder(SETTINGS_FILE_NAME)
+                .disable_auto_save()
+                .build()?;
+
+            // Merge machine-local settings (mic, window placement) into the runtime store
+            for (key, value) in settings_manager.load_machine_entries() {
+                settings_store.set(key, value);
+            }
+
+            // Enforce admin-locked settings over whatever was stored
+            let reverted = commands::settings::apply_locked_settings(
+                &settings_store,
+                settings_manager.policy(),
+            );
+            if !reverted.is_empty() {
+                log::info!("Applied admin-locked settings: {}", reverted.join(", "));
+            }
+            app.manage(settings_manager);
+
+            // Apply the configured log level and start writing the rotating log file
+            let log_level: LogLevel = settings_store
+                .get("log_level")
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or_default();
+            logging::set_level(log_level);
+            match app.path().app_log_dir() {
+                Ok(log_dir) => match logging::attach_log_file(&log_dir) {
+                    Ok(path) => log::info!("Writing logs to {:?}", path),
+                    Err(e) => log::warn!("{}", e),
+                },
+                Err(e) => log::warn!("Failed to get log directory: {}", e),
+            }
+
+            // Remember restart-required values so later changes can be reported as pending
+            let launch_entries: serde_json::Map<String, serde_json::Value> =
+                settings_store.entries().into_iter().collect();
+            if let Ok(mut baseline) = app.state::<AppState>().restart_baseline.lock() {
+                *baseline = launch_entries;
+            }
+
+            // Usage metrics, gated by the analytics setting
+            app.manage(MetricsRecorder::new(app_data_dir.clone()));
+            app.manage(QuotaTracker::new(app_data_dir.clone()));
+            app.manage(SnippetLibrary::new(app_data_dir.clone()));
+            app.manage(FailedDictations::new(app_data_dir.clone()));
+            app.manage(recording_recovery::RecordingRecovery::new(
+                app_data_dir.clone(),
+            ));
+            // Keep a recording the last run was cut off in, to retry
+            recording_recovery::recover(app.handle());
+            file_transcription::spawn(app.handle());
+
+            let history_storage = history_crypto::open_history(app.handle(), app_data_dir);
+            history::apply_retention(app.handle(), &history_storage);
             app.manage(history_storage);
 
             // Initialize audio mute manager (may be None on unsupported platforms)
@@ -311,12 +817,25 @@ pub fn run() {
             #[cfg(desktop)]
             {
                 register_initial_shortcuts(app.handle())?;
+
+                // Start hidden to tray if configured
+                if read_setting_or(app.handle(), "start_minimized", false) {
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.hide();
+                        log::info!("Starting minimized to tray");
+                    }
+                }
             }
 
+            // In tray-only mode the overlay stays hidden; its webview still records and
+            // talks to the server, so it is always created
+            let tray_only: bool = crate::settings::read_setting(app.handle(), "tray_only_mode");
+            app.manage(tray_status::TrayStatus::new(tray_only));
+
             // Create overlay window
             let overlay = tauri::WebviewWindowBuilder::new(
                 app,
-                "overlay",
+                overlay::OVERLAY_LABEL,
                 tauri::WebviewUrl::App("overlay.html".into()),
             )
             .title("Voice Overlay")
@@ -330,7 +849,7 @@ pub fn run() {
             .focused(false)
             .focusable(false)
             .accept_first_mouse(true)
-            .visible(true)
+            .visible(!tray_only)
             .visible_on_all_workspaces(true)
             .background_throttling(BackgroundThrottlingPolicy::Disabled)
             .build()?;
@@ -363,60 +882,112 @@ pub fn run() {
                 }
             }
 
-            // Position bottom-right
-            if let Ok(Some(monitor)) = overlay.current_monitor() {
-                let size = monitor.size();
-                let scale = monitor.scale_factor();
-                let x = (size.width as f64 / scale) as i32 - 150;
-                let y = (size.height as f64 / scale) as i32 - 100;
-                let _ = overlay.set_position(tauri::Position::Logical(tauri::LogicalPosition {
-                    x: x as f64,
-                    y: y as f64,
-                }));
+            // Keep the overlay from taking focus and apply click-through
+            let click_through: bool =
+                crate::settings::read_setting(app.handle(), "overlay_click_through");
+            if let Err(e) = overlay::apply_overlay_input(&overlay, click_through) {
+                log::warn!("Failed to configure overlay input: {}", e);
+            }
+
+            // Leave the overlay out of screen shares if requested
+            let exclude_from_capture: bool =
+                crate::settings::read_setting(app.handle(), "exclude_overlay_from_capture");
+            if let Err(e) = overlay::apply_capture_exclusion(&overlay, exclude_from_capture) {
+                log::warn!("Failed to set overlay capture exclusion: {}", e);
+            }
+
+            // Place the overlay on the configured monitor
+            if let Err(e) = overlay::position_overlay(app.handle(), true) {
+                log::warn!("Failed to position overlay: {}", e);
             }
 
+            // Show the screen-edge indicator if enabled
+            if let Err(e) = edge_indicator::apply_edge_indicator(app.handle()) {
+                log::warn!("Failed to show edge indicator: {}", e);
+            }
+
+            // Remember where the user drags the overlay to, and keep it anchored when it
+            // lands on a monitor with a different scale factor
+            let app_handle = app.handle().clone();
+            overlay.on_window_event(move |event| match event {
+                tauri::WindowEvent::Moved(_) => overlay::handle_overlay_moved(&app_handle),
+                tauri::WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                    overlay::handle_scale_changed(&app_handle, *scale_factor)
+                }
+                _ => {}
+            });
+
             // Setup system tray
             setup_tray(app.handle())?;
 
+            // Reload settings when the file is edited by hand or synced from elsewhere
+            settings_watcher::spawn(app.handle().clone());
+
+            // Keep the overlay on screen when monitors or resolutions change
+            display_watcher::spawn(app.handle().clone());
+
+            // Keep history within its limits while the app stays open
+            history_pruner::spawn(app.handle().clone());
+
+            // Share history with other devices through the sync folder, when configured
+            history_sync::spawn(app.handle().clone());
+
+            // Compile each finished day's dictations into a journal, when configured
+            journal::spawn(app.handle().clone());
+
+            // Offer meeting transcription when a calendar meeting starts, when configured
+            calendar::spawn(app.handle().clone());
+
+            // Let automation tools control dictation over local HTTP, when enabled
+            automation_api::spawn(app.handle().clone());
+
+            // Let Stream Deck buttons control dictation and show its state, when enabled
+            stream_deck::spawn(app.handle().clone());
+
+            // Stream dictation events to external displays, when enabled
+            event_stream::spawn(app.handle().clone());
+
+            // Publish recording state to home automation, when a broker is set
+            mqtt::spawn(app.handle().clone());
+
+            // Let the browser extension insert dictations into web pages, when enabled
+            browser_extension::spawn(app.handle().clone());
+
+            // Caption dictation live in OBS, when enabled
+            obs_captions::spawn(app.handle().clone());
+
+            // Let a phone on the local network act as a push-to-talk remote, when enabled
+            remote_control::spawn(app.handle().clone());
+
+            // Control dictation from tambourine:// links
+            deep_link::setup(app.handle());
+            // Transcribe files the app was launched with
+            single_instance::on_launch(app.handle());
+
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::ExitRequested { code, api, .. } = event {
+                shutdown::on_exit_requested(app, code, &api);
+            }
+        });
 }
 
 fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let show_item = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+    let menu = tray_menu::build_menu(app)?;
 
     // Load the template icon for macOS menu bar
     // The @2x version is automatically used for retina displays
-    let icon_bytes = include_bytes!("../icons/tray-iconTemplate@2x.png");
-    let icon = tauri::image::Image::from_bytes(icon_bytes)?;
+    let icon = tray_status::base_icon()?;
 
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(tray_status::TRAY_ID)
         .icon(icon)
         .icon_as_template(true)
         .menu(&menu)
         .show_menu_on_left_click(false)
-        .on_menu_event(|app, event| match event.id.as_ref() {
-            "show" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
-            "quit" => {
-                // Emit disconnect request to frontend before exiting
-                if let Some(window) = app.get_webview_window("overlay") {
-                    let _ = window.emit("request-disconnect", ());
-                }
-                // Give frontend time to disconnect gracefully
-                std::thread::sleep(std::time::Duration::from_millis(500));
-                app.exit(0);
-            }
-            _ => {}
-        })
+        .on_menu_event(|app, event| tray_menu::handle_menu_event(app, event.id.as_ref()))
         .on_tray_icon_event(|tray, event| {
             if let TrayIconEvent::Click {
                 button: MouseButton::Left,
@@ -453,26 +1024,58 @@ fn register_initial_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error:
 
     // Read hotkeys from store with defaults
     let toggle_hotkey: HotkeyConfig =
-        get_setting_from_store(app, "toggle_hotkey", HotkeyConfig::default_toggle());
+        read_setting_or(app, "toggle_hotkey", HotkeyConfig::default_toggle());
     let hold_hotkey: HotkeyConfig =
-        get_setting_from_store(app, "hold_hotkey", HotkeyConfig::default_hold());
+        read_setting_or(app, "hold_hotkey", HotkeyConfig::default_hold());
     let paste_last_hotkey: HotkeyConfig =
-        get_setting_from_store(app, "paste_last_hotkey", HotkeyConfig::default_paste_last());
+        read_setting_or(app, "paste_last_hotkey", HotkeyConfig::default_paste_last());
+    let undo_paste_hotkey: HotkeyConfig =
+        read_setting_or(app, "undo_paste_hotkey", HotkeyConfig::default_undo_paste());
+    let clipboard_cleanup_hotkey: HotkeyConfig = read_setting_or(
+        app,
+        "clipboard_cleanup_hotkey",
+        HotkeyConfig::default_clipboard_cleanup(),
+    );
+    let cancel_hotkey: HotkeyConfig =
+        read_setting_or(app, "cancel_hotkey", HotkeyConfig::default_cancel());
+    let clipboard_cleanup_enabled: bool = read_setting_or(app, "clipboard_cleanup_enabled", false);
 
     // Convert to shortcuts with validation (fall back to defaults if invalid)
     let toggle_shortcut = toggle_hotkey.to_shortcut_or_default(HotkeyConfig::default_toggle);
     let hold_shortcut = hold_hotkey.to_shortcut_or_default(HotkeyConfig::default_hold);
     let paste_last_shortcut =
         paste_last_hotkey.to_shortcut_or_default(HotkeyConfig::default_paste_last);
+    let undo_paste_shortcut =
+        undo_paste_hotkey.to_shortcut_or_default(HotkeyConfig::default_undo_paste);
+    let cancel_shortcut = cancel_hotkey.to_shortcut_or_default(HotkeyConfig::default_cancel);
 
     log::info!(
-        "Registering shortcuts - Toggle: {}, Hold: {}, PasteLast: {}",
+        "Registering shortcuts - Toggle: {}, Hold: {}, PasteLast: {}, UndoPaste: {}, Cancel: {}",
         toggle_hotkey.to_shortcut_string(),
         hold_hotkey.to_shortcut_string(),
-        paste_last_hotkey.to_shortcut_string()
+        paste_last_hotkey.to_shortcut_string(),
+        undo_paste_hotkey.to_shortcut_string(),
+        cancel_hotkey.to_shortcut_string()
     );
 
-    let shortcuts: Vec<Shortcut> = vec![toggle_shortcut, hold_shortcut, paste_last_shortcut];
+    let mut shortcuts: Vec<Shortcut> = vec![
+        toggle_shortcut,
+        hold_shortcut,
+        paste_last_shortcut,
+        undo_paste_shortcut,
+        cancel_shortcut,
+    ];
+    // Only taken while the mode is on, so the key combination stays free otherwise
+    if clipboard_cleanup_enabled {
+        log::info!(
+            "Registering clipboard cleanup shortcut: {}",
+            clipboard_cleanup_hotkey.to_shortcut_string()
+        );
+        shortcuts.push(
+            clipboard_cleanup_hotkey
+                .to_shortcut_or_default(HotkeyConfig::default_clipboard_cleanup),
+        );
+    }
 
     app.global_shortcut()
         .on_shortcuts(shortcuts, |app, shortcut, event| {
@@ -0,0 +1,85 @@
+//! Picture-in-picture window with the running transcript (meeting mode).
+//!
+//! During long dictations the overlay reports the transcript received so far,
+//! which is kept here and pushed to a small always-on-top window when it is
+//! open. The text stays after the recording ends so it can be copied; the next
+//! recording starts it over.
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+/// Window label of the live transcript window
+pub const TRANSCRIPT_LABEL: &str = "transcript";
+
+/// Event sent to the transcript window with the full text so far
+pub const LIVE_TRANSCRIPT_EVENT: &str = "live-transcript";
+
+/// Transcript of the current (or last) recording
+#[derive(Default)]
+pub struct LiveTranscript {
+    text: Mutex<String>,
+}
+
+impl LiveTranscript {
+    pub fn text(&self) -> String {
+        self.text
+            .lock()
+            .map(|text| text.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set(&self, text: String) {
+        if let Ok(mut current) = self.text.lock() {
+            *current = text;
+        }
+    }
+}
+
+/// Show the transcript window, creating it on first use. It doesn't take focus, so
+/// dictation keeps going to the app being typed in.
+pub fn open_transcript_window(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(TRANSCRIPT_LABEL) {
+        return window.show().map_err(|e| e.to_string());
+    }
+
+    // A fresh window reads the current text itself once it has loaded
+    WebviewWindowBuilder::new(
+        app,
+        TRANSCRIPT_LABEL,
+        WebviewUrl::App("transcript.html".into()),
+    )
+    .title("Live Transcript")
+    .inner_size(360.0, 200.0)
+    .min_inner_size(240.0, 120.0)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible_on_all_workspaces(true)
+    .focused(false)
+    .build()
+    .map_err(|e| format!("Failed to open transcript window: {}", e))?;
+    Ok(())
+}
+
+/// Close the transcript window if it is open
+pub fn close_transcript_window(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(TRANSCRIPT_LABEL) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Store the latest transcript and pass it to the window if it is open
+pub fn update(app: &AppHandle, text: String) {
+    if let Some(transcript) = app.try_state::<LiveTranscript>() {
+        transcript.set(text.clone());
+    }
+    crate::event_stream::publish(
+        app,
+        "partial_transcript",
+        serde_json::json!({ "text": text }),
+    );
+    crate::obs_captions::publish(app, &text);
+    if app.get_webview_window(TRANSCRIPT_LABEL).is_some() {
+        let _ = app.emit_to(TRANSCRIPT_LABEL, LIVE_TRANSCRIPT_EVENT, text);
+    }
+}
@@ -0,0 +1,227 @@
+//! Application logging: stderr (via env_logger) plus a size-rotated log file.
+//!
+//! The logger is installed at the very start of `run()`, before the app log
+//! directory is known; the file sink is attached during setup. Verbosity comes
+//! from the `log_level` setting and can be changed at runtime. When `RUST_LOG`
+//! is set it takes precedence, as it did before logs were configurable.
+
+use chrono::Utc;
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Name of the current log file in the app log directory
+pub const LOG_FILE_NAME: &str = "tambourine.log";
+
+/// Size at which the log file is rotated
+pub const MAX_LOG_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Number of rotated log files kept (`tambourine.log.1` is the newest)
+pub const MAX_ROTATED_LOG_FILES: usize = 3;
+
+/// Log verbosity setting
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn to_level_filter(self) -> LevelFilter {
+        match self {
+            Self::Error => LevelFilter::Error,
+            Self::Warn => LevelFilter::Warn,
+            Self::Info => LevelFilter::Info,
+            Self::Debug => LevelFilter::Debug,
+            Self::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+/// Append-only log file that rotates once it grows past a size limit
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    pub fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    /// Append a line, rotating first if it would push the file past the limit
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += len;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+
+    /// Shift `log.N` to `log.N+1` (dropping the oldest) and start a fresh file
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            self.file = File::create(&self.path)?;
+            self.size = 0;
+            return Ok(());
+        }
+
+        let _ = fs::remove_file(rotated_path(&self.path, self.max_files));
+        for index in (1..self.max_files).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))?;
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+/// The last `lines` log lines, reading back into rotated files when needed
+pub fn recent_lines(path: &Path, max_files: usize, lines: usize) -> Vec<String> {
+    let mut collected: Vec<String> = Vec::new();
+    let files = std::iter::once(path.to_path_buf())
+        .chain((1..=max_files).map(|index| rotated_path(path, index)));
+
+    for file_path in files {
+        if collected.len() >= lines {
+            break;
+        }
+        let Ok(file) = File::open(&file_path) else {
+            continue;
+        };
+        let mut file_lines: Vec<String> =
+            BufReader::new(file).lines().map_while(Result::ok).collect();
+        // Older files go in front of what has been collected so far
+        file_lines.append(&mut collected);
+        collected = file_lines;
+    }
+
+    let skip = collected.len().saturating_sub(lines);
+    collected.split_off(skip)
+}
+
+static LOG_FILE: Mutex<Option<RotatingFile>> = Mutex::new(None);
+
+struct AppLogger {
+    stderr: env_logger::Logger,
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if self.stderr.matches(record) {
+            self.stderr.log(record);
+        }
+
+        if let Ok(mut file) = LOG_FILE.lock() {
+            if let Some(file) = file.as_mut() {
+                let line = format!(
+                    "{} {:<5} {}: {}",
+                    Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+                    record.level(),
+                    record.target(),
+                    record.args()
+                );
+                let _ = file.write_line(&line);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+        if let Ok(mut file) = LOG_FILE.lock() {
+            if let Some(file) = file.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+fn env_override() -> bool {
+    std::env::var_os("RUST_LOG").is_some()
+}
+
+/// Install the logger. Logs at info until the configured level is applied.
+pub fn init() {
+    let stderr = env_logger::Builder::new()
+        .filter_level(LevelFilter::Trace)
+        .parse_default_env()
+        .build();
+    let max_level = if env_override() {
+        stderr.filter()
+    } else {
+        LevelFilter::Info
+    };
+
+    if log::set_boxed_logger(Box::new(AppLogger { stderr })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Apply the configured log level (ignored when `RUST_LOG` is set)
+pub fn set_level(level: LogLevel) {
+    if !env_override() {
+        log::set_max_level(level.to_level_filter());
+    }
+}
+
+/// Start writing logs to the rotating file in `log_dir`
+pub fn attach_log_file(log_dir: &Path) -> Result<PathBuf, String> {
+    let path = log_dir.join(LOG_FILE_NAME);
+    let file = RotatingFile::open(path.clone(), MAX_LOG_FILE_BYTES, MAX_ROTATED_LOG_FILES)
+        .map_err(|e| format!("Failed to open log file: {}", e))?;
+    let mut current = LOG_FILE
+        .lock()
+        .map_err(|e| format!("Failed to lock log file: {}", e))?;
+    *current = Some(file);
+    Ok(path)
+}
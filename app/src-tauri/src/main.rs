@@ -1,6 +1,16 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use tambourine_voice_lib::cli;
+
 fn main() {
-    tambourine_voice_lib::run();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::parse_args(&args) {
+        Ok(Some(command)) => std::process::exit(cli::run(command)),
+        Ok(None) => tambourine_voice_lib::run(),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
 }
@@ -0,0 +1,120 @@
+//! Usage metrics gated by the user's analytics setting.
+//!
+//! Every usage event goes through [`MetricsRecorder::record`], which enforces the
+//! analytics mode in one place: nothing is recorded when analytics are off.
+//! Metrics never leave this machine; they are kept in `metrics.json` in the app
+//! data directory so users can inspect them.
+
+use crate::settings::write_file_atomic;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// How usage metrics may be collected
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsMode {
+    /// Nothing is recorded
+    #[default]
+    Off,
+    /// Metrics are recorded on this machine only and never reported
+    LocalOnly,
+}
+
+/// A usage event
+#[derive(Debug, Clone, Copy)]
+pub enum MetricEvent {
+    RecordingStarted,
+    DictationCompleted { words: usize },
+    PasteLastUsed,
+}
+
+/// Aggregated usage counters
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LocalMetrics {
+    pub recordings_started: u64,
+    pub dictations_completed: u64,
+    pub words_dictated: u64,
+    pub paste_last_used: u64,
+    /// When the first metric was recorded
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Records usage metrics according to the analytics mode
+pub struct MetricsRecorder {
+    metrics: RwLock<LocalMetrics>,
+    file_path: PathBuf,
+}
+
+impl MetricsRecorder {
+    /// Create a recorder with the given app data directory
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        let file_path = app_data_dir.join("metrics.json");
+        let metrics = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            metrics: RwLock::new(metrics),
+            file_path,
+        }
+    }
+
+    /// Record a usage event under the current analytics mode. Does nothing when
+    /// analytics are off.
+    pub fn record(&self, mode: AnalyticsMode, event: MetricEvent) -> Result<(), String> {
+        if mode == AnalyticsMode::Off {
+            return Ok(());
+        }
+
+        {
+            let mut metrics = self
+                .metrics
+                .write()
+                .map_err(|e| format!("Failed to write metrics: {}", e))?;
+            metrics.since.get_or_insert_with(Utc::now);
+            match event {
+                MetricEvent::RecordingStarted => metrics.recordings_started += 1,
+                MetricEvent::DictationCompleted { words } => {
+                    metrics.dictations_completed += 1;
+                    metrics.words_dictated += words as u64;
+                }
+                MetricEvent::PasteLastUsed => metrics.paste_last_used += 1,
+            }
+        }
+        self.save()
+    }
+
+    /// Metrics recorded on this machine
+    pub fn local_metrics(&self) -> Result<LocalMetrics, String> {
+        self.metrics
+            .read()
+            .map(|metrics| metrics.clone())
+            .map_err(|e| format!("Failed to read metrics: {}", e))
+    }
+
+    /// Delete all locally recorded metrics
+    pub fn clear(&self) -> Result<(), String> {
+        {
+            let mut metrics = self
+                .metrics
+                .write()
+                .map_err(|e| format!("Failed to write metrics: {}", e))?;
+            *metrics = LocalMetrics::default();
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let metrics = self
+            .metrics
+            .read()
+            .map_err(|e| format!("Failed to read metrics: {}", e))?;
+        let content = serde_json::to_string_pretty(&*metrics)
+            .map_err(|e| format!("Failed to serialize metrics: {}", e))?;
+        write_file_atomic(&self.file_path, content.as_bytes())
+    }
+}
@@ -0,0 +1,292 @@
+//! Publishing dictation status to an MQTT broker, for Home Assistant and other home
+//! automation.
+//!
+//! With `mqtt_broker_url` set (`mqtt://host:1883`, or `mqtts://` for TLS), the app
+//! keeps retained topics under `mqtt_topic_prefix` (`tambourine` when unset) up to
+//! date, e.g. to switch on an "on air" light while the microphone is live:
+//!
+//! - `<prefix>/availability`: `online`, or `offline` once the app is gone
+//! - `<prefix>/recording`: `ON` or `OFF`
+//! - `<prefix>/state`: `idle`, `recording`, `transcribing`, `cleaning`, `pasting` or
+//!   `error`
+//! - `<prefix>/dictations_today` and `<prefix>/words_today`: today's counts
+//!
+//! With `mqtt_home_assistant_discovery` on, matching sensors appear in Home
+//! Assistant by themselves through its MQTT discovery.
+
+use crate::history::{HistoryStorage, HISTORY_ENTRY_ADDED_EVENT};
+use crate::overlay_state::{OverlayState, OverlayStateMachine, OVERLAY_STATE_EVENT};
+use chrono::Local;
+use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS, Transport};
+use serde_json::json;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+
+/// Topic prefix when the setting is missing
+pub const DEFAULT_TOPIC_PREFIX: &str = "tambourine";
+
+/// Prefix Home Assistant watches for discovery messages
+const DISCOVERY_PREFIX: &str = "homeassistant";
+
+/// Time between keep-alive pings to the broker
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// Wait before reconnecting after the broker dropped the connection
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Messages queued while the broker is unreachable
+const QUEUE_CAPACITY: usize = 32;
+
+/// Where the broker is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Broker {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+}
+
+/// Read a broker URL such as `mqtt://homeassistant.local:1883`; a bare host works too
+pub fn parse_broker(url: &str) -> Result<Broker, String> {
+    let url = url.trim();
+    let (rest, tls) = if let Some(rest) = url.strip_prefix("mqtts://") {
+        (rest, true)
+    } else if let Some(rest) = url.strip_prefix("mqtt://") {
+        (rest, false)
+    } else if url.contains("://") {
+        return Err(format!("Unsupported MQTT broker URL {}", url));
+    } else {
+        (url, false)
+    };
+    let rest = rest.trim_end_matches('/');
+    let default_port = if tls { 8883 } else { 1883 };
+    let (host, port) = match rest.rsplit_once(':') {
+        Some((host, port)) => (
+            host,
+            port.parse()
+                .map_err(|_| format!("Invalid MQTT broker port in {}", url))?,
+        ),
+        None => (rest, default_port),
+    };
+    if host.is_empty() {
+        return Err(format!("Missing MQTT broker host in {}", url));
+    }
+    Ok(Broker {
+        host: host.to_string(),
+        port,
+        tls,
+    })
+}
+
+/// How a state is published
+pub fn state_name(state: &OverlayState) -> &'static str {
+    match state {
+        OverlayState::Idle => "idle",
+        OverlayState::Recording => "recording",
+        OverlayState::Transcribing => "transcribing",
+        OverlayState::Cleaning => "cleaning",
+        OverlayState::Pasting => "pasting",
+        OverlayState::Error { .. } => "error",
+    }
+}
+
+/// Identifier of the app in Home Assistant, from the topic prefix
+fn node_id(prefix: &str) -> String {
+    prefix
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Home Assistant discovery topics and their configs for the published topics
+pub fn discovery_messages(prefix: &str) -> Vec<(String, String)> {
+    let node = node_id(prefix);
+    let device = json!({
+        "identifiers": [node],
+        "name": "Tambourine",
+        "manufacturer": "Tambourine",
+    });
+    let availability = format!("{}/availability", prefix);
+    let config = |component: &str, key: &str, name: &str, extra: serde_json::Value| {
+        let mut config = json!({
+            "name": name,
+            "unique_id": format!("{}_{}", node, key),
+            "state_topic": format!("{}/{}", prefix, key),
+            "availability_topic": availability,
+            "device": device,
+        });
+        if let (Some(config), Some(extra)) = (config.as_object_mut(), extra.as_object()) {
+            config.extend(extra.clone());
+        }
+        (
+            format!("{}/{}/{}/{}/config", DISCOVERY_PREFIX, component, node, key),
+            config.to_string(),
+        )
+    };
+    vec![
+        config(
+            "binary_sensor",
+            "recording",
+            "Recording",
+            json!({ "icon": "mdi:microphone" }),
+        ),
+        config("sensor", "state", "Dictation state", json!({})),
+        config(
+            "sensor",
+            "dictations_today",
+            "Dictations today",
+            json!({ "state_class": "total", "icon": "mdi:counter" }),
+        ),
+        config(
+            "sensor",
+            "words_today",
+            "Words dictated today",
+            json!({ "state_class": "total", "icon": "mdi:text" }),
+        ),
+    ]
+}
+
+/// Connection to the broker with the topic prefix
+pub struct MqttPublisher {
+    client: Client,
+    prefix: String,
+}
+
+impl MqttPublisher {
+    /// Queue a retained message under the prefix, dropping it when the queue is full
+    fn publish(&self, name: &str, payload: impl Into<Vec<u8>>) {
+        let topic = format!("{}/{}", self.prefix, name);
+        if let Err(e) = self
+            .client
+            .try_publish(&topic, QoS::AtLeastOnce, true, payload)
+        {
+            log::debug!("Failed to publish {}: {}", topic, e);
+        }
+    }
+
+    fn publish_state(&self, state: &OverlayState) {
+        let recording = matches!(state, OverlayState::Recording);
+        self.publish("recording", if recording { "ON" } else { "OFF" });
+        self.publish("state", state_name(state));
+    }
+
+    fn publish_counts(&self, app: &AppHandle) {
+        let Some(history) = app.try_state::<HistoryStorage>() else {
+            return;
+        };
+        let range = crate::journal::day_range(Local::now().date_naive(), &Local);
+        match history.get_range(&range) {
+            Ok(entries) => {
+                let words: usize = entries
+                    .iter()
+                    .map(|entry| entry.text.split_whitespace().count())
+                    .sum();
+                self.publish("dictations_today", entries.len().to_string());
+                self.publish("words_today", words.to_string());
+            }
+            Err(e) => log::debug!("Failed to count today's dictations: {}", e),
+        }
+    }
+
+    /// Publish everything, after (re)connecting
+    fn publish_all(&self, app: &AppHandle, discovery: bool) {
+        self.publish("availability", "online");
+        if discovery {
+            for (topic, config) in discovery_messages(&self.prefix) {
+                if let Err(e) = self
+                    .client
+                    .try_publish(&topic, QoS::AtLeastOnce, true, config)
+                {
+                    log::debug!("Failed to publish {}: {}", topic, e);
+                }
+            }
+        }
+        let state = app
+            .try_state::<OverlayStateMachine>()
+            .map(|machine| machine.current())
+            .unwrap_or_default();
+        self.publish_state(&state);
+        self.publish_counts(app);
+    }
+}
+
+/// Connect to the configured broker and keep it up to date in the background
+pub fn spawn(app: AppHandle) {
+    let Some(url) = crate::settings::read_setting::<Option<String>>(&app, "mqtt_broker_url")
+        .filter(|url| !url.trim().is_empty())
+    else {
+        return;
+    };
+    let broker = match parse_broker(&url) {
+        Ok(broker) => broker,
+        Err(e) => {
+            log::error!("MQTT publishing not started: {}", e);
+            return;
+        }
+    };
+    let prefix = crate::settings::read_setting::<Option<String>>(&app, "mqtt_topic_prefix")
+        .map(|prefix| prefix.trim().trim_matches('/').to_string())
+        .filter(|prefix| !prefix.is_empty())
+        .unwrap_or_else(|| DEFAULT_TOPIC_PREFIX.to_string());
+    let discovery =
+        crate::settings::read_setting::<Option<bool>>(&app, "mqtt_home_assistant_discovery")
+            .unwrap_or(true);
+
+    let client_id = format!("{}-{}", node_id(&prefix), uuid::Uuid::new_v4().simple());
+    let mut options = MqttOptions::new(client_id, broker.host.clone(), broker.port);
+    options.set_keep_alive(KEEP_ALIVE);
+    options.set_last_will(LastWill::new(
+        format!("{}/availability", prefix),
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if let Some(username) = crate::settings::read_setting::<Option<String>>(&app, "mqtt_username")
+        .filter(|username| !username.is_empty())
+    {
+        let password = crate::settings::read_setting::<Option<String>>(&app, "mqtt_password")
+            .unwrap_or_default();
+        options.set_credentials(username, password);
+    }
+    if broker.tls {
+        options.set_transport(Transport::tls_with_default_config());
+    }
+
+    let (client, mut connection) = Client::new(options, QUEUE_CAPACITY);
+    app.manage(MqttPublisher { client, prefix });
+    log::info!(
+        "Publishing status to MQTT broker {}:{}",
+        broker.host,
+        broker.port
+    );
+
+    let state_app = app.clone();
+    app.listen(OVERLAY_STATE_EVENT, move |event| {
+        if let Ok(state) = serde_json::from_str::<OverlayState>(event.payload()) {
+            state_app.state::<MqttPublisher>().publish_state(&state);
+        }
+    });
+    let counts_app = app.clone();
+    app.listen(HISTORY_ENTRY_ADDED_EVENT, move |_| {
+        counts_app
+            .state::<MqttPublisher>()
+            .publish_counts(&counts_app);
+    });
+
+    thread::spawn(move || {
+        // Driving the connection sends queued messages and reconnects after errors
+        for notification in connection.iter() {
+            match notification {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    log::info!("Connected to MQTT broker");
+                    app.state::<MqttPublisher>().publish_all(&app, discovery);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    log::warn!("MQTT connection failed: {}", e);
+                    thread::sleep(RECONNECT_DELAY);
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,114 @@
+//! Optional OS notifications for dictation outcomes.
+//!
+//! Each kind of notification has its own toggle in the `notifications`
+//! setting, all off by default. Errors are always posted in tray-only mode,
+//! where there is no overlay to show them. Held pastes are always posted, since
+//! the text would otherwise seem lost, and so are copied results in copy-only
+//! mode, where nothing is pasted to show the dictation finished. The same goes
+//! for dictations saved only to a file, for meetings starting in the calendar
+//! the user set up for meeting prompts, and for copied text cleaned up with the
+//! clipboard cleanup hotkey.
+
+use crate::settings::NotificationSettings;
+use crate::tray_status::TrayStatus;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// Longest transcript snippet shown in a notification, in characters
+pub const SNIPPET_MAX_CHARS: usize = 80;
+
+/// Kind of event being notified, matched against the per-event toggles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotificationKind {
+    TranscriptionComplete,
+    ProviderError,
+    QuotaWarning,
+    PasteHeld,
+    Copied,
+    Saved,
+    MeetingStarting,
+    ClipboardCleaned,
+    RecordingRecovered,
+}
+
+impl NotificationKind {
+    fn title(self) -> &'static str {
+        match self {
+            Self::TranscriptionComplete => "Dictation pasted",
+            Self::ProviderError => "Dictation failed",
+            Self::QuotaWarning => "Dictation limit",
+            Self::PasteHeld => "Dictation held",
+            Self::Copied => "Dictation copied",
+            Self::Saved => "Dictation saved",
+            Self::MeetingStarting => "Meeting starting",
+            Self::ClipboardCleaned => "Clipboard cleaned up",
+            Self::RecordingRecovered => "Recording recovered",
+        }
+    }
+}
+
+impl NotificationSettings {
+    /// Read the notification toggles from the settings store
+    #[cfg(desktop)]
+    pub fn from_store(app: &AppHandle) -> Self {
+        crate::settings::read_setting_or(app, "notifications", Self::default())
+    }
+
+    #[cfg(not(desktop))]
+    pub fn from_store(_app: &AppHandle) -> Self {
+        Self::default()
+    }
+
+    pub fn allows(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::TranscriptionComplete => self.transcription_complete,
+            NotificationKind::ProviderError => self.provider_errors,
+            NotificationKind::QuotaWarning => self.quota_warnings,
+            NotificationKind::PasteHeld
+            | NotificationKind::MeetingStarting
+            | NotificationKind::ClipboardCleaned
+            | NotificationKind::RecordingRecovered => true,
+            NotificationKind::Copied | NotificationKind::Saved => self.transcription_complete,
+        }
+    }
+}
+
+/// First `max_chars` characters of the text on one line, with an ellipsis when cut
+pub fn snippet(text: &str, max_chars: usize) -> String {
+    let single_line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if single_line.chars().count() <= max_chars {
+        return single_line;
+    }
+    let cut: String = single_line.chars().take(max_chars).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Post a notification if its kind is enabled
+pub fn notify(app: &AppHandle, kind: NotificationKind, body: &str) {
+    let tray_only = app
+        .try_state::<TrayStatus>()
+        .is_some_and(|status| status.enabled());
+    let copy_only_mode = kind == NotificationKind::Copied
+        && crate::settings::read_setting::<bool>(app, "copy_only_mode");
+    // Saved is only sent when dictations go to a file instead of being pasted
+    let forced = kind == NotificationKind::PasteHeld
+        || kind == NotificationKind::MeetingStarting
+        || kind == NotificationKind::ClipboardCleaned
+        || kind == NotificationKind::RecordingRecovered
+        || kind == NotificationKind::Saved
+        || copy_only_mode
+        || (tray_only && kind == NotificationKind::ProviderError);
+    if !forced && !NotificationSettings::from_store(app).allows(kind) {
+        return;
+    }
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(kind.title())
+        .body(body)
+        .show()
+    {
+        log::warn!("Failed to show notification: {}", e);
+    }
+}
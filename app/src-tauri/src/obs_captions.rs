@@ -0,0 +1,307 @@
+//! Live captions in OBS from the partial transcripts of a dictation.
+//!
+//! With `obs_captions_enabled` on, the transcript so far is written to a text
+//! source in OBS (`obs_caption_source`, "Tambourine Captions" when unset) through
+//! obs-websocket 5 (built into OBS 28 and later), so streamers get captions from
+//! the speech-to-text provider they already dictate with. Only the end of a long
+//! transcript is shown, and the caption is cleared once nothing was said for
+//! `obs_caption_clear_seconds`. With `obs_stream_captions` on, the text is also sent
+//! as the stream's closed captions.
+//!
+//! Updates are coalesced on a background thread, so a slow or unreachable OBS never
+//! holds up dictation; the connection is retried on the next caption.
+
+use base64::Engine;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::net::TcpStream;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+/// obs-websocket address when the setting is missing
+pub const DEFAULT_OBS_WEBSOCKET_URL: &str = "ws://127.0.0.1:4455";
+
+/// Text source captions are written to when the setting is missing
+pub const DEFAULT_CAPTION_SOURCE: &str = "Tambourine Captions";
+
+/// Longest caption shown, in characters, before older words scroll off
+pub const CAPTION_MAX_CHARS: usize = 160;
+
+/// Seconds without new words before the caption is cleared, when the setting is missing
+const DEFAULT_CLEAR_SECONDS: u64 = 5;
+
+/// Time OBS gets to answer a request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Wait before trying an unreachable OBS again
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/// obs-websocket message types (`op`)
+const OP_HELLO: u64 = 0;
+const OP_IDENTIFY: u64 = 1;
+const OP_IDENTIFIED: u64 = 2;
+const OP_REQUEST: u64 = 6;
+const OP_REQUEST_RESPONSE: u64 = 7;
+
+/// The end of a transcript that fits in a caption, cut at a word boundary
+pub fn caption_tail(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text;
+    }
+    let tail: String = text.chars().skip(count - max_chars).collect();
+    match tail.split_once(' ') {
+        Some((_, words)) if !words.is_empty() => words.to_string(),
+        _ => tail,
+    }
+}
+
+/// The answer to obs-websocket's authentication challenge
+pub fn auth_response(password: &str, salt: &str, challenge: &str) -> String {
+    let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+    let secret = encode(&Sha256::digest(format!("{}{}", password, salt)));
+    encode(&Sha256::digest(format!("{}{}", secret, challenge)))
+}
+
+/// The Identify message answering OBS's Hello
+pub fn identify_message(hello: &Value, password: Option<&str>) -> Result<Value, String> {
+    let mut data = json!({ "rpcVersion": 1, "eventSubscriptions": 0 });
+    if let Some(auth) = hello["d"].get("authentication") {
+        let password = password
+            .filter(|password| !password.is_empty())
+            .ok_or("OBS asks for a password; set it in the OBS captions settings")?;
+        let (Some(salt), Some(challenge)) = (auth["salt"].as_str(), auth["challenge"].as_str())
+        else {
+            return Err("OBS sent an invalid authentication challenge".to_string());
+        };
+        data["authentication"] = json!(auth_response(password, salt, challenge));
+    }
+    Ok(json!({ "op": OP_IDENTIFY, "d": data }))
+}
+
+/// A request message
+pub fn request_message(id: &str, request_type: &str, data: Value) -> Value {
+    json!({
+        "op": OP_REQUEST,
+        "d": { "requestType": request_type, "requestId": id, "requestData": data },
+    })
+}
+
+/// Where captions go and how
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObsConfig {
+    pub url: String,
+    pub password: Option<String>,
+    pub source: String,
+    pub stream_captions: bool,
+}
+
+impl ObsConfig {
+    /// The configuration in the settings, with defaults for missing values
+    pub fn from_store(app: &AppHandle) -> Self {
+        let text = |key: &str| {
+            crate::settings::read_setting::<Option<String>>(app, key)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        Self {
+            url: text("obs_websocket_url").unwrap_or_else(|| DEFAULT_OBS_WEBSOCKET_URL.to_string()),
+            password: text("obs_websocket_password"),
+            source: text("obs_caption_source")
+                .unwrap_or_else(|| DEFAULT_CAPTION_SOURCE.to_string()),
+            stream_captions: crate::settings::read_setting::<bool>(app, "obs_stream_captions"),
+        }
+    }
+}
+
+/// An identified obs-websocket session
+pub struct ObsConnection {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+}
+
+impl ObsConnection {
+    /// Connect and identify, with the password when OBS asks for one
+    pub fn connect(url: &str, password: Option<&str>) -> Result<Self, String> {
+        let (socket, _) = tungstenite::connect(url).map_err(|e| {
+            format!(
+                "Failed to connect to OBS at {} (is it running?): {}",
+                url, e
+            )
+        })?;
+        if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+            stream
+                .set_read_timeout(Some(REQUEST_TIMEOUT))
+                .map_err(|e| format!("Failed to set up the OBS connection: {}", e))?;
+        }
+        let mut connection = Self { socket };
+        let hello = connection.read_op(OP_HELLO)?;
+        connection.send(&identify_message(&hello, password)?)?;
+        connection.read_op(OP_IDENTIFIED)?;
+        Ok(connection)
+    }
+
+    fn send(&mut self, message: &Value) -> Result<(), String> {
+        self.socket
+            .send(Message::text(message.to_string()))
+            .map_err(|e| format!("Failed to send to OBS: {}", e))
+    }
+
+    /// Read messages until one of type `op`, skipping others
+    fn read_op(&mut self, op: u64) -> Result<Value, String> {
+        loop {
+            match self.socket.read() {
+                Ok(Message::Text(text)) => {
+                    let message: Value = serde_json::from_str(&text)
+                        .map_err(|e| format!("Invalid message from OBS: {}", e))?;
+                    if message["op"].as_u64() == Some(op) {
+                        return Ok(message);
+                    }
+                }
+                Ok(Message::Close(frame)) => {
+                    // obs-websocket closes with code 4009 when the password is wrong
+                    let reason = match frame {
+                        Some(frame) => {
+                            format!(": {} ({})", frame.reason.as_str(), u16::from(frame.code))
+                        }
+                        None => String::new(),
+                    };
+                    return Err(format!("OBS closed the connection{}", reason));
+                }
+                Ok(_) => {}
+                Err(e) => return Err(format!("Lost the connection to OBS: {}", e)),
+            }
+        }
+    }
+
+    /// Send a request and wait for OBS to carry it out
+    pub fn request(&mut self, request_type: &str, data: Value) -> Result<Value, String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.send(&request_message(&id, request_type, data))?;
+        loop {
+            let response = self.read_op(OP_REQUEST_RESPONSE)?;
+            let response = &response["d"];
+            if response["requestId"].as_str() != Some(id.as_str()) {
+                continue;
+            }
+            let status = &response["requestStatus"];
+            if status["result"].as_bool() == Some(true) {
+                return Ok(response["responseData"].clone());
+            }
+            return Err(format!(
+                "OBS couldn't {}: {}",
+                request_type,
+                status["comment"].as_str().unwrap_or("unknown error")
+            ));
+        }
+    }
+
+    /// Show a caption in the text source, and in the stream's captions when asked to
+    pub fn show(&mut self, config: &ObsConfig, caption: &str) -> Result<(), String> {
+        self.request(
+            "SetInputSettings",
+            json!({ "inputName": config.source, "inputSettings": { "text": caption } }),
+        )?;
+        if config.stream_captions && !caption.is_empty() {
+            // Fails while not streaming, which is fine
+            if let Err(e) = self.request("SendStreamCaption", json!({ "captionText": caption })) {
+                log::debug!("{}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Queue of captions for the OBS thread
+pub struct ObsCaptions {
+    sender: Mutex<mpsc::Sender<String>>,
+}
+
+/// Send the transcript so far to OBS, if captions are on
+pub fn publish(app: &AppHandle, text: &str) {
+    if let Some(captions) = app.try_state::<ObsCaptions>() {
+        if let Ok(sender) = captions.sender.lock() {
+            let _ = sender.send(caption_tail(text, CAPTION_MAX_CHARS));
+        }
+    }
+}
+
+/// Keep the caption in OBS up to date on a background thread
+fn run(config: ObsConfig, clear_after: Duration, receiver: mpsc::Receiver<String>) {
+    let mut connection: Option<ObsConnection> = None;
+    let mut retry_at: Option<Instant> = None;
+    let mut shown = String::new();
+    loop {
+        let mut caption = match receiver.recv_timeout(clear_after) {
+            Ok(caption) => caption,
+            Err(mpsc::RecvTimeoutError::Timeout) if !shown.is_empty() => String::new(),
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        };
+        // Only the newest of the captions that queued up meanwhile matters
+        while let Ok(newer) = receiver.try_recv() {
+            caption = newer;
+        }
+        if caption == shown || retry_at.is_some_and(|retry_at| Instant::now() < retry_at) {
+            continue;
+        }
+
+        if connection.is_none() {
+            match ObsConnection::connect(&config.url, config.password.as_deref()) {
+                Ok(connected) => {
+                    log::info!("Connected to OBS for captions");
+                    connection = Some(connected);
+                    retry_at = None;
+                }
+                Err(e) => {
+                    log::warn!("{}", e);
+                    retry_at = Some(Instant::now() + RECONNECT_DELAY);
+                    continue;
+                }
+            }
+        }
+        if let Some(obs) = connection.as_mut() {
+            match obs.show(&config, &caption) {
+                Ok(()) => shown = caption,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    connection = None;
+                }
+            }
+        }
+    }
+}
+
+/// Start sending captions to OBS, when enabled
+pub fn spawn(app: AppHandle) {
+    if !crate::settings::read_setting::<bool>(&app, "obs_captions_enabled") {
+        return;
+    }
+    let config = ObsConfig::from_store(&app);
+    let clear_after = Duration::from_secs(
+        crate::settings::read_setting::<Option<u64>>(&app, "obs_caption_clear_seconds")
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(DEFAULT_CLEAR_SECONDS),
+    );
+    log::info!(
+        "Sending captions to OBS text source \"{}\" at {}",
+        config.source,
+        config.url
+    );
+
+    let (sender, receiver) = mpsc::channel();
+    app.manage(ObsCaptions {
+        sender: Mutex::new(sender),
+    });
+    thread::spawn(move || run(config, clear_after, receiver));
+}
+
+/// Connect to OBS and show a sample caption, checking the connection and text source
+pub fn send_test_caption(config: &ObsConfig) -> Result<(), String> {
+    let mut obs = ObsConnection::connect(&config.url, config.password.as_deref())?;
+    obs.show(config, "Captions from Tambourine are working.")
+}
@@ -0,0 +1,101 @@
+//! Appending dictations to notes in an Obsidian vault.
+//!
+//! An output profile with `obsidian_note` set sends its dictations to that note in
+//! the `obsidian_vault` folder instead of pasting them. The note path is relative
+//! to the vault, may use the `{date}`, `{year}`, `{month}` and `{day}` placeholders
+//! of output files, and gets `.md` added when it has no extension.
+//!
+//! A new note starts with YAML frontmatter holding its creation time and the
+//! `obsidian_tags`. Each dictation is added as a timestamped list item linking to
+//! that day's daily note (`[[YYYY-MM-DD]]`), so dictations show up in its backlinks.
+
+use chrono::{DateTime, Local};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Tags of new notes when the setting is missing
+pub fn default_tags() -> Vec<String> {
+    vec!["dictation".to_string()]
+}
+
+/// The file of a note inside a vault, refusing paths that leave the vault
+pub fn note_path(vault: &Path, note: &str, now: DateTime<Local>) -> Result<PathBuf, String> {
+    let relative = crate::output_file::expand_path(note, now, None);
+    let inside_vault = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if note.trim().is_empty() || !inside_vault {
+        return Err(format!("Not a note path inside the vault: {}", note));
+    }
+    let mut path = vault.join(relative);
+    if path.extension().is_none() {
+        path.set_extension("md");
+    }
+    Ok(path)
+}
+
+/// Frontmatter of a new note
+pub fn frontmatter(tags: &[String], now: DateTime<Local>) -> String {
+    let mut out = format!("---\ncreated: {}\n", now.format("%Y-%m-%dT%H:%M"));
+    let tags: Vec<&str> = tags
+        .iter()
+        .map(|tag| tag.trim().trim_start_matches('#'))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    if !tags.is_empty() {
+        out.push_str("tags:\n");
+        for tag in tags {
+            out.push_str(&format!("  - {}\n", tag));
+        }
+    }
+    out.push_str("---\n\n");
+    out
+}
+
+/// One dictation as a list item with its time and a link to the day's daily note
+pub fn format_entry(text: &str, now: DateTime<Local>) -> String {
+    format!(
+        "- {} {} [[{}]]\n",
+        now.format("%H:%M"),
+        text.trim().replace('\n', "\n  "),
+        now.format("%Y-%m-%d")
+    )
+}
+
+/// Append a dictation to a note in the configured vault
+pub fn append(app: &AppHandle, note: &str, text: &str) -> Result<PathBuf, String> {
+    let vault = crate::settings::read_setting::<Option<String>>(app, "obsidian_vault")
+        .filter(|vault| !vault.trim().is_empty())
+        .ok_or("Choose an Obsidian vault to send dictations to")?;
+    let now = Local::now();
+    let home = app.path().home_dir().ok();
+    let vault = crate::output_file::expand_path(&vault, now, home.as_deref());
+    if !vault.is_dir() {
+        return Err(format!("Obsidian vault not found: {}", vault.display()));
+    }
+
+    let path = note_path(&vault, note, now)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut content = String::new();
+    if is_new {
+        let tags = crate::settings::read_setting::<Option<Vec<String>>>(app, "obsidian_tags")
+            .unwrap_or_else(default_tags);
+        content.push_str(&frontmatter(&tags, now));
+    }
+    content.push_str(&format_entry(text, now));
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write to {}: {}", path.display(), e))?;
+    log::debug!("Dictation added to note {}", path.display());
+    Ok(path)
+}
@@ -0,0 +1,79 @@
+//! First-run onboarding progress.
+//!
+//! Onboarding is a fixed sequence of steps completed in order. Progress is persisted
+//! in the settings store so the frontend wizard resumes where it left off after a restart.
+
+use serde::{Deserialize, Serialize};
+
+/// Settings store key holding onboarding progress
+pub const ONBOARDING_STORE_KEY: &str = "onboarding";
+
+/// A single onboarding step
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    /// Microphone access granted by the OS
+    MicPermission,
+    /// STT and LLM providers selected
+    ProviderSetup,
+    /// A dictation triggered successfully with the configured hotkey
+    HotkeyTest,
+}
+
+impl OnboardingStep {
+    /// All steps, in the order they must be completed
+    pub const ALL: [Self; 3] = [Self::MicPermission, Self::ProviderSetup, Self::HotkeyTest];
+}
+
+/// Persisted onboarding progress
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct OnboardingProgress {
+    pub completed_steps: Vec<OnboardingStep>,
+}
+
+/// Onboarding state reported to the frontend
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct OnboardingState {
+    pub completed_steps: Vec<OnboardingStep>,
+    /// Next step to complete, or None when onboarding is finished
+    pub current_step: Option<OnboardingStep>,
+    pub is_complete: bool,
+}
+
+impl OnboardingProgress {
+    /// The first step that has not been completed yet
+    pub fn current_step(&self) -> Option<OnboardingStep> {
+        OnboardingStep::ALL
+            .into_iter()
+            .find(|step| !self.completed_steps.contains(step))
+    }
+
+    /// Mark a step as completed. Completing an already-completed step is a no-op;
+    /// completing a step out of order is rejected.
+    pub fn complete(&mut self, step: OnboardingStep) -> Result<(), String> {
+        if self.completed_steps.contains(&step) {
+            return Ok(());
+        }
+        match self.current_step() {
+            Some(current) if current == step => {
+                self.completed_steps.push(step);
+                Ok(())
+            }
+            Some(current) => Err(format!(
+                "Cannot complete onboarding step {:?} before {:?}",
+                step, current
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Snapshot of the progress for the frontend
+    pub fn state(&self) -> OnboardingState {
+        let current_step = self.current_step();
+        OnboardingState {
+            completed_steps: self.completed_steps.clone(),
+            current_step,
+            is_complete: current_step.is_none(),
+        }
+    }
+}
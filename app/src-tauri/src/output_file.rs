@@ -0,0 +1,73 @@
+//! Appending dictations to a file.
+//!
+//! With the `output_file_path` setting, every dictation is appended to that file
+//! as a timestamped Markdown list item, next to being pasted or, with
+//! `output_file_only`, instead of it. The path can contain `{date}` (YYYY-MM-DD),
+//! `{year}`, `{month}` and `{day}` for one file per day, and a leading `~` for
+//! the home directory. Missing folders are created.
+
+use chrono::{DateTime, Local};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Fill in the date placeholders and home directory of a file path template
+pub fn expand_path(template: &str, now: DateTime<Local>, home: Option<&Path>) -> PathBuf {
+    let path = template
+        .trim()
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{year}", &now.format("%Y").to_string())
+        .replace("{month}", &now.format("%m").to_string())
+        .replace("{day}", &now.format("%d").to_string());
+
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            home.join(rest.trim_start_matches(['/', '\\']))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// One dictation as a list item with its time; later lines are indented to stay in it
+pub fn format_entry(text: &str, now: DateTime<Local>) -> String {
+    format!(
+        "- {} {}\n",
+        now.format("%H:%M"),
+        text.trim().replace('\n', "\n  ")
+    )
+}
+
+/// Whether dictations should only go to the file, without being pasted
+pub fn replaces_paste(app: &AppHandle) -> bool {
+    crate::settings::read_setting::<Option<String>>(app, "output_file_path")
+        .is_some_and(|path| !path.trim().is_empty())
+        && crate::settings::read_setting::<bool>(app, "output_file_only")
+}
+
+/// Append a dictation to the configured file, if there is one
+pub fn append(app: &AppHandle, text: &str) -> Result<(), String> {
+    let Some(template) = crate::settings::read_setting::<Option<String>>(app, "output_file_path")
+        .filter(|path| !path.trim().is_empty())
+    else {
+        return Ok(());
+    };
+
+    let now = Local::now();
+    let home = app.path().home_dir().ok();
+    let path = expand_path(&template, now, home.as_deref());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    file.write_all(format_entry(text, now).as_bytes())
+        .map_err(|e| format!("Failed to write to {}: {}", path.display(), e))?;
+    log::debug!("Dictation appended to {}", path.display());
+    Ok(())
+}
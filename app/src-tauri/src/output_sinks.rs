@@ -0,0 +1,359 @@
+//! Destinations a finished dictation is handed to before it is pasted.
+//!
+//! Every sink in the registry gets each dictation in turn. A sink either works
+//! alongside pasting (like appending to the output file) or takes the dictation
+//! instead of it (like an Obsidian note profile); pasting is what happens when no
+//! sink took the dictation. Built-in sinks are the output file, Obsidian notes and
+//! email drafts, and more can be added with [`OutputSinks::register`].
+//!
+//! Sinks can also be added without touching the app through the `output_sinks`
+//! setting: each is a command that gets the dictation as JSON on stdin
+//! (`{"text", "target_app", "timestamp"}`) and as the `TAMBOURINE_TEXT`
+//! environment variable, e.g. a script that posts it as a Jira comment. Commands
+//! can be limited to dictations into some apps, and run in the background unless
+//! they replace pasting, when the dictation waits for them to finish.
+
+use crate::settings::AppOutputProfile;
+use crate::{email_draft, obsidian, output_file};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::{ErrorKind, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// Time a sink command gets to finish before it is stopped
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a running sink command is checked on
+const SCRIPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Longest part of a failed command's error output shown in its error
+const SCRIPT_ERROR_MAX_CHARS: usize = 200;
+
+/// Error output kept from a command, in bytes; the rest is read and dropped
+const SCRIPT_ERROR_MAX_BYTES: usize = SCRIPT_ERROR_MAX_CHARS * 4;
+
+/// Time a failed command's error output gets to finish after it exits
+const SCRIPT_STDERR_GRACE: Duration = Duration::from_secs(1);
+
+/// A finished dictation on its way out
+#[derive(Debug, Clone, Default)]
+pub struct Dictation {
+    pub text: String,
+    /// App that had focus when recording started
+    pub target_app: Option<String>,
+    /// Output profile of the app the dictation goes to
+    pub profile: Option<AppOutputProfile>,
+}
+
+/// What a sink did with a dictation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Delivery {
+    /// The dictation is still pasted
+    Alongside,
+    /// The sink took the dictation, so it isn't pasted
+    InsteadOfPaste,
+}
+
+/// A destination for finished dictations
+pub trait OutputSink: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &str;
+
+    /// Hand a dictation over. An error stops the dictation and shows on the overlay.
+    fn deliver(&self, app: &AppHandle, dictation: &Dictation) -> Result<Delivery, String>;
+}
+
+/// Appends dictations to the `output_file_path` file
+struct FileSink;
+
+impl OutputSink for FileSink {
+    fn name(&self) -> &str {
+        "output file"
+    }
+
+    fn deliver(&self, app: &AppHandle, dictation: &Dictation) -> Result<Delivery, String> {
+        output_file::append(app, &dictation.text)?;
+        Ok(if output_file::replaces_paste(app) {
+            Delivery::InsteadOfPaste
+        } else {
+            Delivery::Alongside
+        })
+    }
+}
+
+/// Adds dictations to the Obsidian note of the target app's output profile
+struct ObsidianSink;
+
+impl OutputSink for ObsidianSink {
+    fn name(&self) -> &str {
+        "Obsidian"
+    }
+
+    fn deliver(&self, app: &AppHandle, dictation: &Dictation) -> Result<Delivery, String> {
+        let Some(profile) = &dictation.profile else {
+            return Ok(Delivery::Alongside);
+        };
+        let Some(note) = profile
+            .obsidian_note
+            .as_deref()
+            .filter(|note| !note.trim().is_empty())
+        else {
+            return Ok(Delivery::Alongside);
+        };
+        obsidian::append(app, note, &profile.apply_template(&dictation.text))?;
+        Ok(Delivery::InsteadOfPaste)
+    }
+}
+
+/// Opens an email draft for the target app's output profile
+struct EmailDraftSink;
+
+impl OutputSink for EmailDraftSink {
+    fn name(&self) -> &str {
+        "email draft"
+    }
+
+    fn deliver(&self, app: &AppHandle, dictation: &Dictation) -> Result<Delivery, String> {
+        let Some(profile) = &dictation.profile else {
+            return Ok(Delivery::Alongside);
+        };
+        let Some(client) = profile.email_draft else {
+            return Ok(Delivery::Alongside);
+        };
+        email_draft::create(app, client, &profile.apply_template(&dictation.text))?;
+        Ok(Delivery::InsteadOfPaste)
+    }
+}
+
+/// A command from the `output_sinks` setting
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ScriptSink {
+    pub name: String,
+    /// Program to run, found on the PATH or as a full path
+    pub command: String,
+    pub args: Vec<String>,
+    pub enabled: bool,
+    /// Deliver dictations only through this command, without pasting them
+    pub replaces_paste: bool,
+    /// Apps whose dictations the command gets, matched case-insensitively; all when empty
+    pub apps: Vec<String>,
+}
+
+impl Default for ScriptSink {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            command: String::new(),
+            args: Vec::new(),
+            enabled: true,
+            replaces_paste: false,
+            apps: Vec::new(),
+        }
+    }
+}
+
+impl ScriptSink {
+    /// Whether the command gets dictations into this app
+    pub fn applies_to(&self, target_app: Option<&str>) -> bool {
+        if !self.enabled || self.command.trim().is_empty() {
+            return false;
+        }
+        self.apps.is_empty()
+            || target_app.is_some_and(|target| {
+                self.apps
+                    .iter()
+                    .any(|app| app.trim().eq_ignore_ascii_case(target))
+            })
+    }
+
+    fn label(&self) -> &str {
+        if self.name.trim().is_empty() {
+            &self.command
+        } else {
+            &self.name
+        }
+    }
+
+    /// Run the command for a dictation and wait for it to succeed
+    pub fn run(&self, dictation: &Dictation) -> Result<(), String> {
+        let mut child = Command::new(self.command.trim())
+            .args(&self.args)
+            .env("TAMBOURINE_TEXT", &dictation.text)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run {}: {}", self.label(), e))?;
+
+        // Writing the payload and draining error output happen on their own
+        // threads, so a command that ignores stdin or writes a lot of errors
+        // can't block the dictation past the timeout
+        if let Some(mut stdin) = child.stdin.take() {
+            let payload = script_payload(dictation, &chrono::Utc::now().to_rfc3339());
+            thread::spawn(move || {
+                // Commands that don't read stdin close it early, which is fine
+                let _ = stdin.write_all(payload.as_bytes());
+            });
+        }
+        let (stderr_tx, stderr_rx) = mpsc::channel();
+        if let Some(output) = child.stderr.take() {
+            thread::spawn(move || {
+                let _ = stderr_tx.send(drain_error_output(output));
+            });
+        }
+
+        let started = Instant::now();
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if started.elapsed() < SCRIPT_TIMEOUT => {
+                    thread::sleep(SCRIPT_POLL_INTERVAL)
+                }
+                Ok(None) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!(
+                        "{} took longer than {} seconds",
+                        self.label(),
+                        SCRIPT_TIMEOUT.as_secs()
+                    ));
+                }
+                Err(e) => return Err(format!("Failed to wait for {}: {}", self.label(), e)),
+            }
+        };
+        if status.success() {
+            return Ok(());
+        }
+
+        // Processes the command started may still hold its error output open
+        let stderr = stderr_rx
+            .recv_timeout(SCRIPT_STDERR_GRACE)
+            .unwrap_or_default();
+        let stderr: String = stderr.trim().chars().take(SCRIPT_ERROR_MAX_CHARS).collect();
+        if stderr.is_empty() {
+            Err(format!("{} failed ({})", self.label(), status))
+        } else {
+            Err(format!("{} failed ({}): {}", self.label(), status, stderr))
+        }
+    }
+}
+
+impl OutputSink for ScriptSink {
+    fn name(&self) -> &str {
+        self.label()
+    }
+
+    fn deliver(&self, _app: &AppHandle, dictation: &Dictation) -> Result<Delivery, String> {
+        if !self.applies_to(dictation.target_app.as_deref()) {
+            return Ok(Delivery::Alongside);
+        }
+        if self.replaces_paste {
+            self.run(dictation)?;
+            return Ok(Delivery::InsteadOfPaste);
+        }
+        // Pasting doesn't wait on commands that only work alongside it
+        let sink = self.clone();
+        let dictation = dictation.clone();
+        thread::spawn(move || {
+            if let Err(e) = sink.run(&dictation) {
+                log::warn!("{}", e);
+            }
+        });
+        Ok(Delivery::Alongside)
+    }
+}
+
+/// Read a command's error output to the end, keeping only its start
+fn drain_error_output(mut output: impl Read) -> String {
+    let mut kept = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        match output.read(&mut buffer) {
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Ok(0) | Err(_) => break,
+            Ok(read) => {
+                let room = SCRIPT_ERROR_MAX_BYTES.saturating_sub(kept.len());
+                kept.extend_from_slice(&buffer[..read.min(room)]);
+            }
+        }
+    }
+    String::from_utf8_lossy(&kept).into_owned()
+}
+
+/// The JSON a sink command gets on stdin
+pub fn script_payload(dictation: &Dictation, timestamp: &str) -> String {
+    json!({
+        "text": dictation.text,
+        "target_app": dictation.target_app,
+        "timestamp": timestamp,
+    })
+    .to_string()
+}
+
+/// Registered output sinks, in the order they get dictations
+pub struct OutputSinks {
+    sinks: RwLock<Vec<Arc<dyn OutputSink>>>,
+}
+
+impl Default for OutputSinks {
+    fn default() -> Self {
+        Self {
+            sinks: RwLock::new(vec![
+                Arc::new(FileSink),
+                Arc::new(ObsidianSink),
+                Arc::new(EmailDraftSink),
+            ]),
+        }
+    }
+}
+
+impl OutputSinks {
+    /// Add a sink after the ones already registered
+    pub fn register(&self, sink: Arc<dyn OutputSink>) {
+        if let Ok(mut sinks) = self.sinks.write() {
+            sinks.push(sink);
+        }
+    }
+
+    fn registered(&self) -> Vec<Arc<dyn OutputSink>> {
+        self.sinks
+            .read()
+            .map(|sinks| sinks.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Hand a dictation to every registered sink, then to the configured commands.
+/// Returns whether a sink took it, so it shouldn't be pasted.
+pub fn dispatch(app: &AppHandle, dictation: &Dictation) -> Result<bool, String> {
+    let mut sinks = app
+        .try_state::<OutputSinks>()
+        .map(|sinks| sinks.registered())
+        .unwrap_or_default();
+    sinks.extend(
+        crate::settings::read_setting::<Vec<ScriptSink>>(app, "output_sinks")
+            .into_iter()
+            .map(|sink| Arc::new(sink) as Arc<dyn OutputSink>),
+    );
+
+    let mut taken = false;
+    for sink in sinks {
+        match sink.deliver(app, dictation) {
+            Ok(Delivery::InsteadOfPaste) => {
+                log::debug!("Dictation delivered by {}", sink.name());
+                taken = true;
+            }
+            Ok(Delivery::Alongside) => {}
+            Err(e) => {
+                log::warn!("Output sink {} failed: {}", sink.name(), e);
+                return Err(e);
+            }
+        }
+    }
+    Ok(taken)
+}
@@ -0,0 +1,653 @@
+//! Overlay window placement across monitors.
+//!
+//! The overlay is placed on the monitor chosen by the `overlay_monitor` setting.
+//! In follow-focus mode it moves to the monitor containing the focused window
+//! each time recording starts; if it is already on that monitor it stays where
+//! it is, so a dragged overlay keeps its position.
+//!
+//! Positions the user drags the overlay to are saved per monitor (machine-local
+//! `overlay_positions` setting) and used instead of the default spot whenever
+//! the overlay is placed on that monitor.
+//!
+//! The `overlay_anchor` setting can instead pin the overlay to a screen edge or
+//! corner, or next to the mouse cursor or text caret. Whatever the anchor,
+//! resizing keeps the anchored point of the window fixed (the center for free
+//! placement, the bottom edge for bottom-center, and so on).
+//!
+//! Coordinates are logical per monitor: a monitor's physical bounds divided by
+//! its own scale factor. Positions are converted back to physical pixels with
+//! the scale of the monitor they land on, so the overlay keeps its size and
+//! anchor when moved between monitors with different DPI. When the overlay's
+//! scale factor changes it is re-placed on its anchor and the new scale is
+//! sent to the frontend.
+//!
+//! When the display configuration changes (resolution, monitors added or
+//! removed) the overlay is recalibrated: placed again from the settings and
+//! clamped so the whole window stays on a monitor.
+//!
+//! The overlay never takes keyboard focus, so dictated text always lands in the
+//! app the user is typing in. With `overlay_click_through` it also ignores the
+//! mouse entirely.
+
+use crate::caret;
+use crate::commands::settings::persist_store;
+use crate::settings::{
+    read_setting, OverlayAnchor, OverlayMonitor, OverlayPoint, SettingsManager, SETTINGS_FILE_NAME,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Monitor, PhysicalPosition, Position, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+
+/// Window label of the recording overlay
+pub const OVERLAY_LABEL: &str = "overlay";
+
+/// Event carrying the overlay's new scale factor after it changes monitors
+pub const OVERLAY_SCALE_EVENT: &str = "overlay-scale-changed";
+
+/// Offset of the default overlay center from the monitor's bottom-right corner (logical px)
+const DEFAULT_CENTER_INSET: (f64, f64) = (126.0, 76.0);
+
+/// Gap between an edge-anchored overlay and the monitor edge (logical px)
+const EDGE_MARGIN: f64 = 24.0;
+
+/// Gap between the cursor and the overlay in near-cursor mode (logical px)
+const CURSOR_OFFSET: f64 = 16.0;
+
+/// How long the overlay must stay put after moving before a drag is saved
+const DRAG_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Moves smaller than this (logical px) are rounding noise, not drags
+const DRAG_TOLERANCE: f64 = 2.0;
+
+/// A screen point and which point of the overlay window sits on it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnchorPoint {
+    /// Screen point in logical coordinates
+    pub point: (f64, f64),
+    /// Point of the window as fractions of its size: (0.5, 0.5) is the center,
+    /// (1.0, 1.0) the bottom-right corner
+    pub alignment: (f64, f64),
+}
+
+impl AnchorPoint {
+    pub fn centered(point: (f64, f64)) -> Self {
+        Self {
+            point,
+            alignment: (0.5, 0.5),
+        }
+    }
+
+    /// Top-left corner of a window of the given logical size placed at this anchor
+    pub fn window_origin(&self, width: f64, height: f64) -> (f64, f64) {
+        (
+            self.point.0 - self.alignment.0 * width,
+            self.point.1 - self.alignment.1 * height,
+        )
+    }
+
+    /// Top-left corner in physical pixels on a monitor with the given scale factor
+    pub fn physical_origin(&self, width: f64, height: f64, scale: f64) -> PhysicalPosition<i32> {
+        let (x, y) = self.window_origin(width, height);
+        PhysicalPosition::new((x * scale).round() as i32, (y * scale).round() as i32)
+    }
+}
+
+/// Where the overlay is anchored, shared between placement, resizing and drag tracking
+#[derive(Default)]
+pub struct OverlayPlacement {
+    /// Anchor the overlay was last placed at by the app or the user
+    anchor: Mutex<Option<AnchorPoint>>,
+    /// Bumped on every move event so only the last one in a burst is handled
+    move_generation: AtomicU64,
+}
+
+impl OverlayPlacement {
+    pub fn anchor(&self) -> Option<AnchorPoint> {
+        self.anchor.lock().ok().and_then(|anchor| *anchor)
+    }
+
+    pub fn set_anchor(&self, anchor_point: AnchorPoint) {
+        if let Ok(mut anchor) = self.anchor.lock() {
+            *anchor = Some(anchor_point);
+        }
+    }
+}
+
+/// Monitor bounds in logical coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl MonitorBounds {
+    pub fn from_monitor(monitor: &Monitor) -> Self {
+        let scale = monitor.scale_factor();
+        let position = monitor.position();
+        let size = monitor.size();
+        Self {
+            x: position.x as f64 / scale,
+            y: position.y as f64 / scale,
+            width: size.width as f64 / scale,
+            height: size.height as f64 / scale,
+        }
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Where the overlay center goes by default (near the bottom-right corner)
+    pub fn default_overlay_center(&self) -> (f64, f64) {
+        (
+            self.x + self.width - DEFAULT_CENTER_INSET.0,
+            self.y + self.height - DEFAULT_CENTER_INSET.1,
+        )
+    }
+
+    /// Anchor for an edge or corner preset; `None` for free, cursor and caret placement
+    pub fn preset_anchor(&self, anchor: OverlayAnchor) -> Option<AnchorPoint> {
+        let left = self.x + EDGE_MARGIN;
+        let center = self.x + self.width / 2.0;
+        let right = self.x + self.width - EDGE_MARGIN;
+        let top = self.y + EDGE_MARGIN;
+        let bottom = self.y + self.height - EDGE_MARGIN;
+
+        let (point, alignment) = match anchor {
+            OverlayAnchor::TopLeft => ((left, top), (0.0, 0.0)),
+            OverlayAnchor::TopCenter => ((center, top), (0.5, 0.0)),
+            OverlayAnchor::TopRight => ((right, top), (1.0, 0.0)),
+            OverlayAnchor::BottomLeft => ((left, bottom), (0.0, 1.0)),
+            OverlayAnchor::BottomCenter => ((center, bottom), (0.5, 1.0)),
+            OverlayAnchor::BottomRight => ((right, bottom), (1.0, 1.0)),
+            OverlayAnchor::Free | OverlayAnchor::NearCursor | OverlayAnchor::NearCaret => {
+                return None
+            }
+        };
+        Some(AnchorPoint { point, alignment })
+    }
+
+    /// Origin of a window of the given size moved the least distance needed to fit
+    /// entirely inside these bounds (pinned to the top-left if it is larger)
+    pub fn clamp_origin(&self, origin: (f64, f64), width: f64, height: f64) -> (f64, f64) {
+        let max_x = (self.x + self.width - width).max(self.x);
+        let max_y = (self.y + self.height - height).max(self.y);
+        (origin.0.clamp(self.x, max_x), origin.1.clamp(self.y, max_y))
+    }
+
+    /// Anchor just below and right of the cursor, flipped to the other side of the
+    /// cursor near the right or bottom edge so a window of the given size stays visible
+    pub fn near_cursor_anchor(&self, cursor: (f64, f64), width: f64, height: f64) -> AnchorPoint {
+        let (x, align_x) = if cursor.0 + CURSOR_OFFSET + width > self.x + self.width {
+            (cursor.0 - CURSOR_OFFSET, 1.0)
+        } else {
+            (cursor.0 + CURSOR_OFFSET, 0.0)
+        };
+        let (y, align_y) = if cursor.1 + CURSOR_OFFSET + height > self.y + self.height {
+            (cursor.1 - CURSOR_OFFSET, 1.0)
+        } else {
+            (cursor.1 + CURSOR_OFFSET, 0.0)
+        };
+        AnchorPoint {
+            point: (x, y),
+            alignment: (align_x, align_y),
+        }
+    }
+}
+
+/// Configure how the overlay handles input: never take keyboard focus, and optionally let
+/// mouse events pass through to the window underneath
+pub fn apply_overlay_input(window: &WebviewWindow, click_through: bool) -> Result<(), String> {
+    window
+        .set_focusable(false)
+        .map_err(|e| format!("Failed to make overlay non-focusable: {}", e))?;
+    window
+        .set_ignore_cursor_events(click_through)
+        .map_err(|e| format!("Failed to set overlay click-through: {}", e))?;
+
+    #[cfg(target_os = "windows")]
+    prevent_activation(window)?;
+
+    Ok(())
+}
+
+/// Add `WS_EX_NOACTIVATE` so clicking the overlay doesn't activate it and pull
+/// focus away from the app being dictated into
+#[cfg(target_os = "windows")]
+fn prevent_activation(window: &WebviewWindow) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetWindowLongPtrW, GWL_EXSTYLE, WS_EX_NOACTIVATE,
+    };
+
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("Failed to get overlay window handle: {}", e))?;
+    let hwnd = HWND(hwnd.0);
+
+    // SAFETY: hwnd is the overlay's live window handle; only the extended style changes
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_NOACTIVATE.0 as isize);
+    }
+    Ok(())
+}
+
+/// Keep a status window (overlay or edge indicator) out of screenshots and screen
+/// shares, or let it be captured again
+pub fn apply_capture_exclusion(window: &WebviewWindow, excluded: bool) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        exclude_from_capture(window, excluded)
+    }
+    // Sets the NSWindow sharingType to none on macOS
+    #[cfg(not(target_os = "windows"))]
+    {
+        window
+            .set_content_protected(excluded)
+            .map_err(|e| format!("Failed to set capture exclusion: {}", e))
+    }
+}
+
+/// Set the display affinity directly: `WDA_EXCLUDEFROMCAPTURE` leaves the window out
+/// of captures entirely, where plain content protection draws a black box
+#[cfg(target_os = "windows")]
+fn exclude_from_capture(window: &WebviewWindow, excluded: bool) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE, WDA_NONE,
+    };
+
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("Failed to get window handle: {}", e))?;
+    let affinity = if excluded {
+        WDA_EXCLUDEFROMCAPTURE
+    } else {
+        WDA_NONE
+    };
+    // SAFETY: hwnd is a live top-level window owned by this process
+    unsafe { SetWindowDisplayAffinity(HWND(hwnd.0), affinity) }
+        .map_err(|e| format!("Failed to set capture exclusion: {}", e))
+}
+
+/// Center of the foreground window in physical coordinates
+#[cfg(target_os = "windows")]
+fn foreground_window_center() -> Option<PhysicalPosition<f64>> {
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowRect};
+
+    // SAFETY: plain Win32 queries on the current foreground window handle
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() {
+            return None;
+        }
+        let mut rect = RECT::default();
+        GetWindowRect(hwnd, &mut rect).ok()?;
+        Some(PhysicalPosition::new(
+            f64::from(rect.left + rect.right) / 2.0,
+            f64::from(rect.top + rect.bottom) / 2.0,
+        ))
+    }
+}
+
+/// A point on the monitor the user is working on. Uses the foreground window where the
+/// platform exposes it and the mouse cursor otherwise.
+fn focus_point(app: &AppHandle) -> Option<PhysicalPosition<f64>> {
+    #[cfg(target_os = "windows")]
+    if let Some(point) = foreground_window_center() {
+        return Some(point);
+    }
+    app.cursor_position().ok()
+}
+
+fn primary_monitor(app: &AppHandle) -> Option<Monitor> {
+    app.primary_monitor().ok().flatten()
+}
+
+/// Resolve the monitor the overlay should be on for the given setting
+pub fn target_monitor(app: &AppHandle, setting: &OverlayMonitor) -> Option<Monitor> {
+    match setting {
+        OverlayMonitor::FollowFocus => focus_point(app)
+            .and_then(|point| app.monitor_from_point(point.x, point.y).ok().flatten())
+            .or_else(|| primary_monitor(app)),
+        OverlayMonitor::Primary => primary_monitor(app),
+        OverlayMonitor::Fixed(name) => app
+            .available_monitors()
+            .ok()
+            .and_then(|monitors| {
+                monitors
+                    .into_iter()
+                    .find(|monitor| monitor.name() == Some(name))
+            })
+            .or_else(|| primary_monitor(app)),
+    }
+}
+
+/// Names of the connected monitors, for choosing a fixed overlay monitor
+pub fn monitor_names(app: &AppHandle) -> Vec<String> {
+    app.available_monitors()
+        .map(|monitors| {
+            monitors
+                .iter()
+                .filter_map(|monitor| monitor.name().cloned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Find the monitor containing a screen position and convert the position to logical
+/// coordinates
+fn locate_point(app: &AppHandle, position: Position) -> Option<(MonitorBounds, (f64, f64))> {
+    match position {
+        Position::Physical(point) => {
+            let monitor = app
+                .monitor_from_point(f64::from(point.x), f64::from(point.y))
+                .ok()
+                .flatten()?;
+            let scale = monitor.scale_factor();
+            Some((
+                MonitorBounds::from_monitor(&monitor),
+                (f64::from(point.x) / scale, f64::from(point.y) / scale),
+            ))
+        }
+        Position::Logical(point) => app
+            .available_monitors()
+            .ok()?
+            .iter()
+            .map(MonitorBounds::from_monitor)
+            .find(|bounds| bounds.contains(point.x, point.y))
+            .map(|bounds| (bounds, (point.x, point.y))),
+    }
+}
+
+/// Monitor whose logical bounds contain a logical point
+fn monitor_at(app: &AppHandle, point: (f64, f64)) -> Option<Monitor> {
+    app.available_monitors()
+        .ok()?
+        .into_iter()
+        .find(|monitor| MonitorBounds::from_monitor(monitor).contains(point.0, point.1))
+}
+
+/// Convert a logical point to physical pixels using the scale of the monitor it is on,
+/// falling back to the overlay's current scale when it is off-screen
+fn to_physical(window: &WebviewWindow, point: (f64, f64)) -> PhysicalPosition<i32> {
+    let scale = monitor_at(window.app_handle(), point)
+        .map(|monitor| monitor.scale_factor())
+        .or_else(|| window.scale_factor().ok())
+        .unwrap_or(1.0);
+    PhysicalPosition::new(
+        (point.0 * scale).round() as i32,
+        (point.1 * scale).round() as i32,
+    )
+}
+
+/// Current overlay geometry as a logical (x, y, width, height) rectangle
+fn overlay_rect(window: &WebviewWindow) -> Option<(f64, f64, f64, f64)> {
+    let (pos, size) = (window.outer_position().ok()?, window.outer_size().ok()?);
+    let scale = window.scale_factor().unwrap_or(1.0);
+    Some((
+        pos.x as f64 / scale,
+        pos.y as f64 / scale,
+        size.width as f64 / scale,
+        size.height as f64 / scale,
+    ))
+}
+
+/// The overlay's current position expressed as an anchor with the given alignment
+pub fn current_anchor(window: &WebviewWindow, alignment: (f64, f64)) -> Option<AnchorPoint> {
+    let (x, y, width, height) = overlay_rect(window)?;
+    Some(AnchorPoint {
+        point: (x + alignment.0 * width, y + alignment.1 * height),
+        alignment,
+    })
+}
+
+/// Move the overlay onto the given anchor and remember it for later resizes
+pub fn place_overlay(window: &WebviewWindow, anchor: AnchorPoint) -> Result<(), String> {
+    let (_, _, width, height) = overlay_rect(window).ok_or("Failed to read overlay size")?;
+    move_to_anchor(window, anchor, width, height)
+}
+
+/// Position a window of the given logical size on an anchor and remember the anchor.
+/// The origin is converted with the scale of the anchor's monitor, not the monitor the
+/// overlay is leaving.
+pub fn move_to_anchor(
+    window: &WebviewWindow,
+    anchor: AnchorPoint,
+    width: f64,
+    height: f64,
+) -> Result<(), String> {
+    if let Some(placement) = window.try_state::<OverlayPlacement>() {
+        placement.set_anchor(anchor);
+    }
+    let position = match monitor_at(window.app_handle(), anchor.point) {
+        Some(monitor) => anchor.physical_origin(width, height, monitor.scale_factor()),
+        None => to_physical(window, anchor.window_origin(width, height)),
+    };
+    window
+        .set_position(Position::Physical(position))
+        .map_err(|e| e.to_string())
+}
+
+/// Called when the overlay's scale factor changes (it moved to a monitor with a
+/// different DPI). Keeps the overlay on its anchor if the anchor is on the new monitor,
+/// otherwise recomputes the anchor from where the window now is.
+pub fn handle_scale_changed(app: &AppHandle, scale: f64) {
+    let Some(window) = app.get_webview_window(OVERLAY_LABEL) else {
+        return;
+    };
+    let placement = app.state::<OverlayPlacement>();
+    let stored = placement.anchor();
+    let alignment = stored.map(|anchor| anchor.alignment).unwrap_or((0.5, 0.5));
+    let anchor = stored
+        .filter(|anchor| {
+            monitor_at(app, anchor.point)
+                .is_some_and(|monitor| (monitor.scale_factor() - scale).abs() < f64::EPSILON)
+        })
+        .or_else(|| current_anchor(&window, alignment));
+
+    if let Some(anchor) = anchor {
+        if let Err(e) = place_overlay(&window, anchor) {
+            log::warn!("Failed to re-place overlay after scale change: {}", e);
+        }
+    }
+    log::debug!("Overlay scale factor changed to {}", scale);
+    let _ = app.emit(OVERLAY_SCALE_EVENT, scale);
+}
+
+/// Place the overlay according to the anchor and monitor settings. For free placement,
+/// unless `force` is set, an overlay already on the target monitor is left where it is.
+pub fn position_overlay(app: &AppHandle, force: bool) -> Result<(), String> {
+    let Some(window) = app.get_webview_window(OVERLAY_LABEL) else {
+        return Ok(());
+    };
+    let anchor: OverlayAnchor = read_setting(app, "overlay_anchor");
+
+    if matches!(anchor, OverlayAnchor::NearCursor | OverlayAnchor::NearCaret) {
+        let caret = if anchor == OverlayAnchor::NearCaret {
+            caret::caret_position()
+        } else {
+            None
+        };
+        let cursor = || {
+            app.cursor_position()
+                .ok()
+                .map(|point| Position::Physical(point.cast()))
+        };
+        let Some(target) = caret.or_else(cursor) else {
+            return Ok(());
+        };
+        let Some((bounds, point)) = locate_point(app, target) else {
+            return Ok(());
+        };
+        let (_, _, width, height) = overlay_rect(&window).ok_or("Failed to read overlay size")?;
+        return place_overlay(&window, bounds.near_cursor_anchor(point, width, height));
+    }
+
+    let setting: OverlayMonitor = read_setting(app, "overlay_monitor");
+    let Some(monitor) = target_monitor(app, &setting) else {
+        return Ok(());
+    };
+    let bounds = MonitorBounds::from_monitor(&monitor);
+
+    if let Some(anchor_point) = bounds.preset_anchor(anchor) {
+        return place_overlay(&window, anchor_point);
+    }
+
+    if !force {
+        if let Some(AnchorPoint {
+            point: (cx, cy), ..
+        }) = current_anchor(&window, (0.5, 0.5))
+        {
+            if bounds.contains(cx, cy) {
+                return Ok(());
+            }
+        }
+    }
+
+    let saved: HashMap<String, OverlayPoint> = read_setting(app, "overlay_positions");
+    let center = monitor
+        .name()
+        .and_then(|name| saved.get(name))
+        .map(|point| (point.x, point.y))
+        .filter(|(x, y)| bounds.contains(*x, *y))
+        .unwrap_or_else(|| bounds.default_overlay_center());
+    place_overlay(&window, AnchorPoint::centered(center))
+}
+
+/// Move the overlay the least distance needed to be fully visible on the monitor its
+/// center is on, or on the primary monitor when it is off-screen
+pub fn clamp_overlay_to_screen(app: &AppHandle) -> Result<(), String> {
+    let Some(window) = app.get_webview_window(OVERLAY_LABEL) else {
+        return Ok(());
+    };
+    let (x, y, width, height) = overlay_rect(&window).ok_or("Failed to read overlay size")?;
+    let center = (x + width / 2.0, y + height / 2.0);
+    let Some(monitor) = monitor_at(app, center).or_else(|| primary_monitor(app)) else {
+        return Ok(());
+    };
+    let (clamped_x, clamped_y) =
+        MonitorBounds::from_monitor(&monitor).clamp_origin((x, y), width, height);
+    if (clamped_x - x).abs() < DRAG_TOLERANCE && (clamped_y - y).abs() < DRAG_TOLERANCE {
+        return Ok(());
+    }
+
+    let alignment = app
+        .state::<OverlayPlacement>()
+        .anchor()
+        .map(|anchor| anchor.alignment)
+        .unwrap_or((0.5, 0.5));
+    let anchor = AnchorPoint {
+        point: (
+            clamped_x + alignment.0 * width,
+            clamped_y + alignment.1 * height,
+        ),
+        alignment,
+    };
+    move_to_anchor(&window, anchor, width, height)
+}
+
+/// Place the overlay again for the current display configuration and make sure it is
+/// fully on screen. Called when monitors change and from the `recalibrate_overlay`
+/// command.
+pub fn recalibrate_overlay(app: &AppHandle) -> Result<(), String> {
+    position_overlay(app, true)?;
+    clamp_overlay_to_screen(app)
+}
+
+/// Called for every overlay move event. Once the overlay has stopped moving, a
+/// position that differs from where the app put it is saved as a user drag.
+pub fn handle_overlay_moved(app: &AppHandle) {
+    let Some(placement) = app.try_state::<OverlayPlacement>() else {
+        return;
+    };
+    let generation = placement.move_generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(DRAG_SAVE_DELAY);
+        let placement = app.state::<OverlayPlacement>();
+        if placement.move_generation.load(Ordering::SeqCst) != generation {
+            return; // Still moving
+        }
+        if let Err(e) = save_dragged_position(&app, &placement) {
+            log::warn!("Failed to save overlay position: {}", e);
+        }
+    });
+}
+
+fn save_dragged_position(app: &AppHandle, placement: &OverlayPlacement) -> Result<(), String> {
+    let Some(window) = app.get_webview_window(OVERLAY_LABEL) else {
+        return Ok(());
+    };
+    let alignment = placement
+        .anchor()
+        .map(|anchor| anchor.alignment)
+        .unwrap_or((0.5, 0.5));
+    let Some(current) = current_anchor(&window, alignment) else {
+        return Ok(());
+    };
+    if let Some(anchor) = placement.anchor() {
+        if (current.point.0 - anchor.point.0).abs() < DRAG_TOLERANCE
+            && (current.point.1 - anchor.point.1).abs() < DRAG_TOLERANCE
+        {
+            return Ok(());
+        }
+    }
+    // Follow the drag for the rest of the session; only free placement is saved
+    placement.set_anchor(current);
+    if read_setting::<OverlayAnchor>(app, "overlay_anchor") != OverlayAnchor::Free {
+        return Ok(());
+    }
+    let Some(AnchorPoint { point: center, .. }) = current_anchor(&window, (0.5, 0.5)) else {
+        return Ok(());
+    };
+
+    let Some(monitor_name) = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned())
+    else {
+        return Ok(());
+    };
+
+    let mut saved: HashMap<String, OverlayPoint> = read_setting(app, "overlay_positions");
+    saved.insert(
+        monitor_name.clone(),
+        OverlayPoint {
+            x: center.0,
+            y: center.1,
+        },
+    );
+    write_positions(app, &saved)?;
+    log::info!("Saved overlay position on {}", monitor_name);
+    Ok(())
+}
+
+/// Forget all dragged positions and move the overlay back to its default spot
+pub fn reset_overlay_position(app: &AppHandle) -> Result<(), String> {
+    write_positions(app, &HashMap::new())?;
+    position_overlay(app, true)
+}
+
+fn write_positions(
+    app: &AppHandle,
+    positions: &HashMap<String, OverlayPoint>,
+) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_FILE_NAME)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.set(
+        "overlay_positions",
+        serde_json::to_value(positions).map_err(|e| e.to_string())?,
+    );
+    persist_store(app, &app.state::<SettingsManager>()).map_err(String::from)
+}
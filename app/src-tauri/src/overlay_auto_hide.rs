@@ -0,0 +1,103 @@
+//! Hiding the overlay a while after a dictation finishes.
+//!
+//! With `overlay_auto_hide_seconds` set, the overlay stays up showing the
+//! result (done or error) for that long once the pipeline returns to idle or
+//! fails, then hides until the next recording. Hovering the overlay pauses the
+//! countdown. The timer runs here rather than in the overlay webview so every
+//! window sees the same visibility.
+
+use crate::overlay::OVERLAY_LABEL;
+use crate::settings::read_setting;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// Resolution of the countdown
+const TICK: Duration = Duration::from_millis(100);
+
+/// Current countdown generation and hover state of the overlay
+#[derive(Default)]
+pub struct OverlayAutoHide {
+    generation: AtomicU64,
+    hovered: AtomicBool,
+}
+
+impl OverlayAutoHide {
+    /// Start a new countdown, stopping any running one; returns its generation
+    pub fn restart(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Stop any running countdown
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Whether the countdown started as `generation` is still the running one
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+
+    pub fn set_hovered(&self, hovered: bool) {
+        self.hovered.store(hovered, Ordering::SeqCst);
+    }
+
+    pub fn hovered(&self) -> bool {
+        self.hovered.load(Ordering::SeqCst)
+    }
+}
+
+/// Start the countdown after a dictation finished, if auto-hide is enabled
+pub fn schedule(app: &AppHandle) {
+    let Some(auto_hide) = app.try_state::<OverlayAutoHide>() else {
+        return;
+    };
+    let seconds: Option<u32> = read_setting(app, "overlay_auto_hide_seconds");
+    let Some(seconds) = seconds else {
+        auto_hide.cancel();
+        return;
+    };
+    let generation = auto_hide.restart();
+
+    let app = app.clone();
+    thread::spawn(move || {
+        let auto_hide = app.state::<OverlayAutoHide>();
+        let mut remaining = Duration::from_secs(u64::from(seconds));
+        while !remaining.is_zero() {
+            thread::sleep(TICK);
+            if !auto_hide.is_current(generation) {
+                return;
+            }
+            // Hovering pauses the countdown; it resumes where it left off
+            if !auto_hide.hovered() {
+                remaining = remaining.saturating_sub(TICK);
+            }
+        }
+        if let Some(window) = app.get_webview_window(OVERLAY_LABEL) {
+            log::debug!("Hiding overlay after {}s", seconds);
+            let _ = window.hide();
+        }
+    });
+}
+
+/// Stop the countdown and bring the overlay back for a new recording, unless it is
+/// kept hidden by tray-only mode or a fullscreen app
+pub fn reveal(app: &AppHandle) {
+    let Some(auto_hide) = app.try_state::<OverlayAutoHide>() else {
+        return;
+    };
+    auto_hide.cancel();
+
+    let tray_only = app
+        .try_state::<crate::tray_status::TrayStatus>()
+        .is_some_and(|status| status.enabled());
+    if tray_only || crate::fullscreen::is_suppressed(app) {
+        return;
+    }
+    if let Some(window) = app.get_webview_window(OVERLAY_LABEL) {
+        if !window.is_visible().unwrap_or(true) {
+            let _ = window.show();
+        }
+    }
+}
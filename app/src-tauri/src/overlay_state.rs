@@ -0,0 +1,98 @@
+//! Dictation pipeline state shown by the overlay.
+//!
+//! Every step of a dictation is published as one `overlay-state` event:
+//! idle → recording → transcribing → cleaning → pasting → idle, or error from
+//! any step. Recording and pasting are driven by the Rust side; the cleanup
+//! step runs on the server and is reported by the overlay webview. Transitions
+//! that arrive out of order (e.g. a late server message after the text was
+//! already pasted) are dropped so the stream only ever moves forward.
+
+use crate::notifications::{notify, NotificationKind};
+use crate::overlay_auto_hide;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event carrying each new [`OverlayState`]
+pub const OVERLAY_STATE_EVENT: &str = "overlay-state";
+
+/// Step of the dictation pipeline
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum OverlayState {
+    #[default]
+    Idle,
+    Recording,
+    /// Audio is done; waiting for speech-to-text
+    Transcribing,
+    /// The LLM is cleaning up the transcript
+    Cleaning,
+    /// The cleaned text is being pasted or typed
+    Pasting,
+    Error {
+        message: String,
+    },
+}
+
+impl OverlayState {
+    /// Whether the pipeline can move from `self` to `next`
+    pub fn can_transition_to(&self, next: &OverlayState) -> bool {
+        match next {
+            Self::Idle | Self::Recording | Self::Error { .. } => true,
+            Self::Transcribing => matches!(self, Self::Recording),
+            Self::Cleaning => matches!(self, Self::Transcribing),
+            Self::Pasting => matches!(self, Self::Transcribing | Self::Cleaning),
+        }
+    }
+}
+
+/// Current pipeline state, shared between the shortcut handlers and commands
+#[derive(Default)]
+pub struct OverlayStateMachine {
+    current: Mutex<OverlayState>,
+}
+
+impl OverlayStateMachine {
+    pub fn current(&self) -> OverlayState {
+        self.current
+            .lock()
+            .map(|state| state.clone())
+            .unwrap_or_default()
+    }
+
+    /// Move to `next` if allowed; returns false for repeated or out-of-order transitions
+    pub fn transition(&self, next: OverlayState) -> bool {
+        let Ok(mut current) = self.current.lock() else {
+            return false;
+        };
+        if *current == next || !current.can_transition_to(&next) {
+            return false;
+        }
+        *current = next;
+        true
+    }
+}
+
+/// Move the pipeline to `next` and notify all windows
+pub fn set_overlay_state(app: &AppHandle, next: OverlayState) {
+    let Some(machine) = app.try_state::<OverlayStateMachine>() else {
+        return;
+    };
+    let previous = machine.current();
+    if !machine.transition(next.clone()) {
+        log::debug!("Ignoring overlay state {:?} after {:?}", next, previous);
+        return;
+    }
+    log::debug!("Overlay state: {:?} -> {:?}", previous, next);
+    crate::pipeline::on_overlay_state(app, &next);
+    crate::recording_state::on_overlay_state(app, &next);
+    crate::tray_status::show_state(app, &next);
+    match &next {
+        OverlayState::Idle | OverlayState::Error { .. } => overlay_auto_hide::schedule(app),
+        _ => overlay_auto_hide::reveal(app),
+    }
+    if let OverlayState::Error { message } = &next {
+        notify(app, NotificationKind::ProviderError, message);
+    }
+    let _ = app.emit(OVERLAY_STATE_EVENT, next);
+}
@@ -0,0 +1,148 @@
+//! Pasting earlier transcripts from history.
+//!
+//! The newest `paste_ring_size` history entries dictated on this device (not
+//! synced from another) form a ring that the paste-last hotkey steps through. The first press pastes the newest
+//! transcript; pressing again within `CYCLE_WINDOW` selects what was just
+//! pasted and pastes the one before it over it, wrapping around after the
+//! oldest. `paste_nth_last` pastes a given slot directly.
+
+use crate::commands::text::{self, PasteOptions};
+use crate::error::AppError;
+use crate::history::{self, HistoryStorage};
+use crate::pipeline::CancellationToken;
+use crate::settings::{InsertMode, PasteMethod};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// Time after a paste-last press within which another press cycles further back
+pub const CYCLE_WINDOW: Duration = Duration::from_secs(3);
+
+/// Transcripts kept in the ring when the setting is missing or zero
+pub const DEFAULT_RING_SIZE: usize = 5;
+
+/// Where the paste-last hotkey is in the ring
+#[derive(Default)]
+pub struct PasteCycle {
+    last: Mutex<Option<CycleStep>>,
+}
+
+#[derive(Clone, Copy)]
+struct CycleStep {
+    index: usize,
+    at: Instant,
+    pasted_chars: usize,
+}
+
+/// What a paste-last press should do
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CycleAction {
+    /// Slot to paste, 0 being the newest transcript
+    pub index: usize,
+    /// Characters of the previous paste to select and replace, when continuing a cycle
+    pub replace_chars: Option<usize>,
+}
+
+impl PasteCycle {
+    /// Work out the slot for a press at `now` with `ring_len` transcripts available
+    pub fn next(&self, now: Instant, ring_len: usize) -> CycleAction {
+        let last = self.last.lock().ok().and_then(|last| *last);
+        match last {
+            Some(step) if ring_len > 1 && now.duration_since(step.at) <= CYCLE_WINDOW => {
+                CycleAction {
+                    index: (step.index + 1) % ring_len,
+                    replace_chars: Some(step.pasted_chars),
+                }
+            }
+            _ => CycleAction {
+                index: 0,
+                replace_chars: None,
+            },
+        }
+    }
+
+    /// Remember a completed paste so the next press can continue from it
+    pub fn record(&self, index: usize, now: Instant, pasted_chars: usize) {
+        if let Ok(mut last) = self.last.lock() {
+            *last = Some(CycleStep {
+                index,
+                at: now,
+                pasted_chars,
+            });
+        }
+    }
+}
+
+/// Number of transcripts in the ring, from settings
+fn ring_size(app: &AppHandle) -> usize {
+    match crate::settings::read_setting::<Option<u32>>(app, "paste_ring_size") {
+        Some(size) if size > 0 => size as usize,
+        _ => DEFAULT_RING_SIZE,
+    }
+}
+
+/// Newest transcripts, newest first, limited to the ring size
+fn ring(app: &AppHandle) -> Result<Vec<String>, String> {
+    let history = app.state::<HistoryStorage>();
+    Ok(history
+        .get_dictated(Some(ring_size(app)))?
+        .into_iter()
+        .map(|entry| entry.text)
+        .collect())
+}
+
+/// Text of the `n`th last transcript, 1 being the newest
+pub fn nth_last(app: &AppHandle, n: usize) -> Result<String, AppError> {
+    let ring = ring(app).map_err(history::command_error)?;
+    if n == 0 || n > ring.len() {
+        return Err(AppError::NotFound(format!(
+            "No transcript {} back; {} available",
+            n,
+            ring.len()
+        )));
+    }
+    Ok(ring[n - 1].clone())
+}
+
+/// Handle a paste-last hotkey press: paste the newest transcript, or cycle back
+/// through the ring when pressed again shortly after
+pub fn paste_from_hotkey(app: &AppHandle) -> Result<(), String> {
+    let ring = ring(app)?;
+    let cycle = app.state::<PasteCycle>();
+    let now = Instant::now();
+    let action = cycle.next(now, ring.len());
+
+    // A paste held back because focus moved goes first
+    let held = if action.replace_chars.is_none() {
+        crate::paste_target::take_held(app)
+    } else {
+        None
+    };
+    let Some(entry) = held.or_else(|| ring.get(action.index).cloned()) else {
+        log::info!("PasteLast: no history entries available");
+        return Ok(());
+    };
+    let text = text::apply_app_template(app, &entry);
+
+    let mut options = PasteOptions::from_store(app);
+    // Pasted as-is, so the next press knows how much to select
+    options.smart_insertion = false;
+    if let Some(chars) = action.replace_chars {
+        text::select_previous_chars(app, chars)?;
+        // Paste over the selection just made
+        options.insert_mode = InsertMode::ReplaceSelection;
+    }
+    let inserted =
+        match text::type_text_blocking(app, &text, options, &CancellationToken::default()) {
+            // Keep it for when a regular field is focused
+            Err(error @ text::DeliveryError::SecureField) => {
+                return crate::paste_target::hold(app, &entry, &error.to_string())
+            }
+            result => result?,
+        };
+    cycle.record(action.index, now, inserted.chars().count());
+    if options.method != PasteMethod::CopyOnly {
+        crate::paste_undo::record(app, &inserted, options);
+    }
+    Ok(())
+}
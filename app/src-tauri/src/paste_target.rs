@@ -0,0 +1,197 @@
+//! Checking that a paste still goes where the dictation started.
+//!
+//! The foreground window is remembered when recording starts. With the
+//! `verify_paste_target` setting, text about to be pasted into a different
+//! window (or, with `require_text_field_for_paste`, into something that isn't a
+//! text field) is held back instead, and a notification says so. The held text
+//! is pasted by a click on the overlay or by the paste-last hotkey, once the
+//! right field is focused.
+//!
+//! Long outputs can be held the same way: with `confirm_paste_over_chars` set,
+//! text longer than that waits for the same confirmation before it is pasted.
+//!
+//! When a window or text field can't be identified on this platform the paste
+//! goes ahead as before.
+
+use crate::notifications::{self, NotificationKind};
+use crate::overlay_state::{set_overlay_state, OverlayState};
+use crate::state::AppState;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event sent with the held paste (or `null` once it is pasted or dropped)
+pub const HELD_PASTE_EVENT: &str = "held-paste-changed";
+
+/// Text waiting to be pasted, as shown on the overlay
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HeldPaste {
+    pub chars: usize,
+    pub reason: String,
+}
+
+/// Window that had focus when a recording started
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasteTarget {
+    pub window: u64,
+    pub app: Option<String>,
+}
+
+impl PasteTarget {
+    /// The window that has focus right now, if it can be identified
+    pub fn current() -> Option<Self> {
+        crate::foreground_app::foreground_window().map(|window| Self {
+            window,
+            app: crate::foreground_app::foreground_app(),
+        })
+    }
+}
+
+/// Outcome of checking the focused window before a paste
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TargetCheck {
+    Ready,
+    FocusChanged,
+    NoTextField,
+}
+
+impl TargetCheck {
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::Ready => "Ready to paste",
+            Self::FocusChanged => "Focus moved to another window, so the text was held",
+            Self::NoTextField => "No text field is focused, so the text was held",
+        }
+    }
+}
+
+/// Whether text is long enough to need confirmation before it is pasted
+pub fn needs_confirmation(text: &str, limit: Option<u32>) -> bool {
+    limit.is_some_and(|limit| text.chars().count() > limit as usize)
+}
+
+/// Check a paste against the focus target and, if `confirm_length`, the length limit.
+/// Returns why the text should be held, or `None` to paste it now.
+pub fn hold_reason(app: &AppHandle, text: &str, confirm_length: bool) -> Option<String> {
+    let check = verify(app);
+    if check != TargetCheck::Ready {
+        return Some(check.message().to_string());
+    }
+    let limit = crate::settings::read_setting::<Option<u32>>(app, "confirm_paste_over_chars");
+    if confirm_length && needs_confirmation(text, limit) {
+        return Some(format!(
+            "{} characters are waiting for confirmation",
+            text.chars().count()
+        ));
+    }
+    None
+}
+
+/// Compare the window a recording started in with the one focused now. Unknown
+/// windows or text-field state never block a paste.
+pub fn check_target(
+    expected: Option<&PasteTarget>,
+    current: Option<&PasteTarget>,
+    text_field_focused: Option<bool>,
+    require_text_field: bool,
+) -> TargetCheck {
+    if let (Some(expected), Some(current)) = (expected, current) {
+        if expected.window != current.window {
+            return TargetCheck::FocusChanged;
+        }
+    }
+    if require_text_field && text_field_focused == Some(false) {
+        return TargetCheck::NoTextField;
+    }
+    TargetCheck::Ready
+}
+
+/// Remember the focused window as the target of the recording that is starting. A
+/// paste still held from an earlier recording is dropped; it stays in history.
+pub fn remember(app: &AppHandle) {
+    let target = PasteTarget::current();
+    log::debug!("Paste target: {:?}", target);
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(mut slot) = state.paste_target.lock() {
+            *slot = target;
+        }
+        if let Ok(mut held) = state.held_paste.lock() {
+            if held.take().is_some() {
+                let _ = app.emit(HELD_PASTE_EVENT, None::<HeldPaste>);
+            }
+        }
+    }
+}
+
+/// Window the current (or last) recording started in
+pub fn remembered(app: &AppHandle) -> Option<PasteTarget> {
+    app.try_state::<AppState>()
+        .and_then(|state| state.paste_target.lock().ok().and_then(|t| t.clone()))
+}
+
+/// Check the focused window against the remembered target, if verification is on
+pub fn verify(app: &AppHandle) -> TargetCheck {
+    if !crate::settings::read_setting::<bool>(app, "verify_paste_target") {
+        return TargetCheck::Ready;
+    }
+    let require_text_field =
+        crate::settings::read_setting::<bool>(app, "require_text_field_for_paste");
+
+    let expected = remembered(app);
+    let text_field_focused = if require_text_field {
+        crate::caret::text_field_focused()
+    } else {
+        None
+    };
+    check_target(
+        expected.as_ref(),
+        PasteTarget::current().as_ref(),
+        text_field_focused,
+        require_text_field,
+    )
+}
+
+/// Keep text that wasn't (or may not have been) pasted for the overlay and the
+/// paste-last hotkey, and tell the user why
+pub fn hold(app: &AppHandle, text: &str, reason: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    *state
+        .held_paste
+        .lock()
+        .map_err(|e| format!("Failed to hold paste: {}", e))? = Some(text.to_string());
+
+    log::info!("Paste held: {}", reason);
+    set_overlay_state(app, OverlayState::Idle);
+    // Keep the overlay up so the held text can be pasted from it
+    crate::overlay_auto_hide::reveal(app);
+    let _ = app.emit(
+        HELD_PASTE_EVENT,
+        Some(HeldPaste {
+            chars: text.chars().count(),
+            reason: reason.to_string(),
+        }),
+    );
+    notifications::notify(
+        app,
+        NotificationKind::PasteHeld,
+        &format!(
+            "{}. Click the overlay or use the paste-last hotkey to paste it.",
+            reason
+        ),
+    );
+    Ok(())
+}
+
+/// Take the held text, if a paste was held back
+pub fn take_held(app: &AppHandle) -> Option<String> {
+    let held = app.try_state::<AppState>().and_then(|state| {
+        state
+            .held_paste
+            .lock()
+            .ok()
+            .and_then(|mut held| held.take())
+    });
+    if held.is_some() {
+        let _ = app.emit(HELD_PASTE_EVENT, None::<HeldPaste>);
+    }
+    held
+}
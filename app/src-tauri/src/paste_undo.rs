@@ -0,0 +1,98 @@
+//! Undoing the last paste.
+//!
+//! Every paste remembers exactly what was inserted (after smart insertion and
+//! templates) and into which window. Undo either selects that many characters
+//! back from the cursor and deletes them, or sends the target app's own undo
+//! shortcut, per the `undo_paste_method` setting. Rich text always uses the
+//! app's undo, since its length in the field isn't known. Undo is refused when
+//! another window has focus or the text before the cursor no longer ends with
+//! the paste, so it never deletes something else.
+
+use crate::commands::text::{self, PasteOptions};
+use crate::error::AppError;
+use crate::settings::{PasteFormat, UndoPasteMethod};
+use crate::state::AppState;
+use tauri::{AppHandle, Manager};
+
+/// Text inserted by the last paste
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastPaste {
+    pub text: String,
+    /// Window it went into, if that can be identified
+    pub window: Option<u64>,
+    /// Whether it can be removed by selecting back over it
+    pub selectable: bool,
+}
+
+/// Remember a paste that just went into the focused window
+pub fn record(app: &AppHandle, inserted: &str, options: PasteOptions) {
+    let last = LastPaste {
+        text: inserted.to_string(),
+        window: crate::foreground_app::foreground_window(),
+        selectable: options.format == PasteFormat::Plain,
+    };
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(mut slot) = state.last_paste.lock() {
+            *slot = Some(last);
+        }
+    }
+}
+
+/// Check that the last paste is still where undo would remove it from
+pub fn check_undo(
+    last: &LastPaste,
+    current_window: Option<u64>,
+    before_caret: Option<&str>,
+) -> Result<(), String> {
+    if let (Some(pasted_into), Some(current)) = (last.window, current_window) {
+        if pasted_into != current {
+            return Err("The last paste went into another window".to_string());
+        }
+    }
+    if before_caret.is_some_and(|before| !before.ends_with(&last.text)) {
+        return Err("The cursor moved since the last paste".to_string());
+    }
+    Ok(())
+}
+
+/// Remove the last paste from the focused field
+pub fn undo(app: &AppHandle) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+    let last = state
+        .last_paste
+        .lock()
+        .map_err(|e| format!("Failed to read last paste: {}", e))?
+        .clone()
+        .ok_or_else(|| AppError::NotFound("Nothing has been pasted yet".to_string()))?;
+
+    let chars = last.text.chars().count();
+    let method = if last.selectable {
+        crate::settings::read_setting::<UndoPasteMethod>(app, "undo_paste_method")
+    } else {
+        UndoPasteMethod::AppUndo
+    };
+    let before = match method {
+        UndoPasteMethod::SelectBack => crate::caret::text_before_caret(chars),
+        UndoPasteMethod::AppUndo => None,
+    };
+    check_undo(
+        &last,
+        crate::foreground_app::foreground_window(),
+        before.as_deref(),
+    )
+    .map_err(AppError::InvalidInput)?;
+
+    match method {
+        UndoPasteMethod::SelectBack => {
+            text::select_previous_chars(app, chars)?;
+            text::delete_selection(app)?;
+        }
+        UndoPasteMethod::AppUndo => text::send_undo_keystroke(app)?,
+    }
+    log::info!("Undid the last paste ({} characters)", chars);
+
+    if let Ok(mut slot) = state.last_paste.lock() {
+        *slot = None;
+    }
+    Ok(())
+}
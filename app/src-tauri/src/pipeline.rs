@@ -0,0 +1,287 @@
+//! The record → transcribe → cleanup → paste flow of a dictation, as one run that
+//! can be cancelled at any stage.
+//!
+//! The overlay begins a run when it starts recording and gets the run's ID, which
+//! it hands back with the text to paste. Each run has its own
+//! [`CancellationToken`], looked up by that ID, so cancelling one dictation never
+//! touches another. The overlay state only moves the current run through its
+//! stages. Cancelling (the cancel hotkey or the Stream Deck's cancel action) or
+//! shutting down cancels the token: the server is told to drop the recording and
+//! any cleanup in flight, and text arriving afterwards is not pasted. Server
+//! requests made from the Rust side go through
+//! [`CancellationToken::run_request`], which returns as soon as the token is
+//! cancelled and has the server abort the request's provider work.
+
+use crate::error::AppError;
+use crate::overlay_state::OverlayState;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+/// Error for work that was given up because its dictation was cancelled
+pub const CANCELLED: &str = "The dictation was cancelled";
+
+/// How often a cancellable wait checks its token
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Header carrying the ID the server can abort a request by
+pub const REQUEST_ID_HEADER: &str = "X-Tambourine-Request";
+
+/// How long asking the server to abort a request may take
+const ABORT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How many runs are kept after they end, so late text for them can be checked
+const KEPT_RUNS: usize = 8;
+
+/// Stage of a running dictation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PipelineStage {
+    Recording,
+    Transcribing,
+    Cleaning,
+    Pasting,
+}
+
+impl PipelineStage {
+    /// The stage an overlay state belongs to, or `None` once the dictation is over
+    pub fn from_overlay_state(state: &OverlayState) -> Option<Self> {
+        match state {
+            OverlayState::Recording => Some(Self::Recording),
+            OverlayState::Transcribing => Some(Self::Transcribing),
+            OverlayState::Cleaning => Some(Self::Cleaning),
+            OverlayState::Pasting => Some(Self::Pasting),
+            OverlayState::Idle | OverlayState::Error { .. } => None,
+        }
+    }
+}
+
+/// Shared flag telling work to stop, cancelled along with its parent
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    parent: Option<Arc<AtomicBool>>,
+}
+
+impl CancellationToken {
+    /// A token that is also cancelled when this one is
+    pub fn child(&self) -> Self {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            parent: Some(self.cancelled.clone()),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.load(Ordering::SeqCst))
+    }
+
+    /// Fail with [`AppError::Cancelled`] once the token is cancelled
+    pub fn check(&self) -> Result<(), AppError> {
+        if self.is_cancelled() {
+            Err(AppError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Run blocking work (like a provider request) on its own thread and wait for
+    /// it, giving up with [`CANCELLED`] as soon as the token is cancelled. The
+    /// abandoned work ends on its own timeout and its result is dropped.
+    pub fn run<T, F>(&self, work: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T, String> + Send + 'static,
+    {
+        self.check()?;
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(work());
+        });
+        loop {
+            match receiver.recv_timeout(CANCEL_POLL_INTERVAL) {
+                Ok(result) => return result,
+                Err(mpsc::RecvTimeoutError::Timeout) => self.check()?,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err("The request stopped unexpectedly".to_string())
+                }
+            }
+        }
+    }
+
+    /// Send a server request through [`run`](Self::run). `request` is given an ID
+    /// to send in [`REQUEST_ID_HEADER`]; once the token is cancelled the server is
+    /// asked to abort the request by it, which stops the provider call instead of
+    /// leaving it to run (and be paid for) with nobody waiting.
+    pub fn run_request<T, F>(&self, request: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&str) -> Result<T, String> + Send + 'static,
+    {
+        let id = Uuid::new_v4().to_string();
+        let request_id = id.clone();
+        let result = self.run(move || request(&request_id));
+        if result.is_err() && self.is_cancelled() {
+            abort_request(id);
+        }
+        result
+    }
+}
+
+/// Ask the server to abort request `id`, without waiting for its answer
+fn abort_request(id: String) {
+    thread::spawn(move || {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(ABORT_TIMEOUT))
+            .build()
+            .into();
+        let url = format!(
+            "{}/api/requests/{}/abort",
+            crate::commands::text::SERVER_URL,
+            id
+        );
+        if let Err(e) = agent.post(url).send_empty() {
+            log::debug!("Failed to abort server request {}: {}", id, e);
+        }
+    });
+}
+
+/// A dictation, from the start of its recording until its text is pasted
+#[derive(Debug)]
+struct PipelineRun {
+    id: u64,
+    stage: PipelineStage,
+    token: CancellationToken,
+    finished: bool,
+}
+
+impl PipelineRun {
+    fn is_going(&self) -> bool {
+        !self.finished && !self.token.is_cancelled()
+    }
+}
+
+/// The dictation pipeline: the latest runs, newest last, and the token shutdown
+/// cancels
+#[derive(Default)]
+pub struct DictationPipeline {
+    runs: Mutex<VecDeque<PipelineRun>>,
+    next_id: AtomicU64,
+    shutdown: CancellationToken,
+}
+
+impl DictationPipeline {
+    /// Start a new run and return its ID. Text still on its way for an earlier run
+    /// is pasted as usual, so nothing already dictated is lost.
+    pub fn begin(&self) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Ok(mut runs) = self.runs.lock() {
+            if let Some(previous) = runs.back_mut() {
+                previous.finished = true;
+            }
+            runs.push_back(PipelineRun {
+                id,
+                stage: PipelineStage::Recording,
+                token: self.shutdown.child(),
+                finished: false,
+            });
+            while runs.len() > KEPT_RUNS {
+                runs.pop_front();
+            }
+        }
+        id
+    }
+
+    /// Move the current run to `stage`
+    pub fn advance(&self, stage: PipelineStage) {
+        if let Ok(mut runs) = self.runs.lock() {
+            if let Some(run) = runs.back_mut().filter(|run| run.is_going()) {
+                run.stage = stage;
+            }
+        }
+    }
+
+    /// End the current run. Its token stays available by ID, so text the server
+    /// still sends for it is pasted, or refused when the run was cancelled.
+    pub fn finish(&self) {
+        if let Ok(mut runs) = self.runs.lock() {
+            if let Some(run) = runs.back_mut() {
+                run.finished = true;
+            }
+        }
+    }
+
+    /// Stage of the current run, if one is going
+    pub fn stage(&self) -> Option<PipelineStage> {
+        let runs = self.runs.lock().ok()?;
+        runs.back()
+            .filter(|run| run.is_going())
+            .map(|run| run.stage)
+    }
+
+    /// Token of run `id`, or one only shutdown cancels for text that belongs to no
+    /// run still kept
+    pub fn token(&self, id: Option<u64>) -> CancellationToken {
+        id.and_then(|id| {
+            let runs = self.runs.lock().ok()?;
+            let run = runs.iter().find(|run| run.id == id)?;
+            Some(run.token.clone())
+        })
+        .unwrap_or_else(|| self.shutdown.child())
+    }
+
+    /// Cancel the current run, returning the stage it was cancelled in
+    pub fn cancel(&self) -> Option<PipelineStage> {
+        let runs = self.runs.lock().ok()?;
+        let run = runs.back().filter(|run| run.is_going())?;
+        run.token.cancel();
+        Some(run.stage)
+    }
+
+    /// Token cancelled at shutdown, for work outside dictations
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.child()
+    }
+
+    /// Cancel the current run and everything started afterwards
+    pub fn shut_down(&self) {
+        self.shutdown.cancel();
+        self.cancel();
+    }
+}
+
+/// Follow the overlay state with the current run's stage
+pub fn on_overlay_state(app: &AppHandle, state: &OverlayState) {
+    let Some(pipeline) = app.try_state::<DictationPipeline>() else {
+        return;
+    };
+    match PipelineStage::from_overlay_state(state) {
+        Some(stage) => pipeline.advance(stage),
+        None => pipeline.finish(),
+    }
+}
+
+/// Token of dictation `id` (see [`DictationPipeline::token`])
+pub fn dictation_token(app: &AppHandle, id: Option<u64>) -> CancellationToken {
+    app.try_state::<DictationPipeline>()
+        .map(|pipeline| pipeline.token(id))
+        .unwrap_or_default()
+}
+
+/// Token cancelled at shutdown
+pub fn shutdown_token(app: &AppHandle) -> CancellationToken {
+    app.try_state::<DictationPipeline>()
+        .map(|pipeline| pipeline.shutdown_token())
+        .unwrap_or_default()
+}
@@ -0,0 +1,296 @@
+//! Optional daily dictation limits.
+//!
+//! Usage is counted per local calendar day in `usage.json` in the app data
+//! directory. Only cloud providers count: each finished recording adds its audio
+//! duration when speech-to-text runs in the cloud, and one cloud request (the STT +
+//! cleanup round trip) when any part of it does. Limits come from the settings
+//! store, are checked before a recording starts and stop a recording that runs
+//! out of audio time.
+
+use crate::settings::write_file_atomic;
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::AppHandle;
+
+/// Fraction of a limit at which a warning is emitted
+pub const QUOTA_WARNING_THRESHOLD: f64 = 0.8;
+
+/// Providers that run on this machine, so using them costs nothing
+const LOCAL_PROVIDERS: &[&str] = &["whisper", "ollama"];
+
+/// Which parts of a dictation go to a cloud provider
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CloudUse {
+    /// Speech-to-text, which counts towards the audio limit
+    pub stt: bool,
+    /// Cleanup by an LLM
+    pub llm: bool,
+}
+
+impl CloudUse {
+    /// Judge the selected providers. An unset provider is left to the server, which
+    /// may pick a cloud one, so it counts as cloud.
+    pub fn from_providers(stt_provider: Option<&str>, llm_provider: Option<&str>) -> Self {
+        let cloud = |provider: Option<&str>| {
+            !provider.is_some_and(|provider| LOCAL_PROVIDERS.contains(&provider))
+        };
+        Self {
+            stt: cloud(stt_provider),
+            llm: cloud(llm_provider),
+        }
+    }
+
+    /// Judge the providers selected in the settings store
+    #[cfg(desktop)]
+    pub fn from_store(app: &AppHandle) -> Self {
+        let stt_provider: Option<String> = crate::settings::read_setting(app, "stt_provider");
+        let llm_provider: Option<String> = crate::settings::read_setting(app, "llm_provider");
+        Self::from_providers(stt_provider.as_deref(), llm_provider.as_deref())
+    }
+
+    #[cfg(not(desktop))]
+    pub fn from_store(_app: &AppHandle) -> Self {
+        Self::default()
+    }
+
+    /// Whether the dictation makes a cloud request at all
+    pub fn any(self) -> bool {
+        self.stt || self.llm
+    }
+}
+
+/// Configured daily limits; `None` means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QuotaLimits {
+    pub daily_audio_minutes: Option<f64>,
+    pub daily_cloud_requests: Option<u32>,
+}
+
+impl QuotaLimits {
+    /// Read the configured limits from the settings store
+    #[cfg(desktop)]
+    pub fn from_store(app: &AppHandle) -> Self {
+        Self {
+            daily_audio_minutes: crate::settings::read_setting_or(
+                app,
+                "daily_audio_minutes_limit",
+                None,
+            ),
+            daily_cloud_requests: crate::settings::read_setting_or(
+                app,
+                "daily_cloud_request_limit",
+                None,
+            ),
+        }
+    }
+
+    #[cfg(not(desktop))]
+    pub fn from_store(_app: &AppHandle) -> Self {
+        Self::default()
+    }
+
+    /// The limits a dictation with the given cloud use counts towards
+    pub fn applying_to(self, cloud: CloudUse) -> Self {
+        Self {
+            daily_audio_minutes: self.daily_audio_minutes.filter(|_| cloud.stt),
+            daily_cloud_requests: self.daily_cloud_requests.filter(|_| cloud.any()),
+        }
+    }
+}
+
+/// Usage counted for a single day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DailyUsage {
+    pub date: NaiveDate,
+    pub audio_seconds: f64,
+    pub cloud_requests: u32,
+}
+
+impl DailyUsage {
+    fn empty(date: NaiveDate) -> Self {
+        Self {
+            date,
+            audio_seconds: 0.0,
+            cloud_requests: 0,
+        }
+    }
+}
+
+/// How close today's usage is to the configured limits
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaLevel {
+    Ok,
+    Warning,
+    Exceeded,
+}
+
+/// Usage and limit state sent to the frontend with quota events
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct QuotaStatus {
+    pub level: QuotaLevel,
+    pub usage: DailyUsage,
+    pub audio_minutes_limit: Option<f64>,
+    pub cloud_requests_limit: Option<u32>,
+}
+
+impl QuotaStatus {
+    /// Evaluate usage against limits
+    pub fn evaluate(usage: DailyUsage, limits: QuotaLimits) -> Self {
+        let mut fractions = Vec::new();
+        if let Some(limit) = limits.daily_audio_minutes {
+            fractions.push(fraction_used(usage.audio_seconds / 60.0, limit));
+        }
+        if let Some(limit) = limits.daily_cloud_requests {
+            fractions.push(fraction_used(
+                f64::from(usage.cloud_requests),
+                f64::from(limit),
+            ));
+        }
+
+        let highest = fractions.into_iter().fold(0.0, f64::max);
+        let level = if highest >= 1.0 {
+            QuotaLevel::Exceeded
+        } else if highest >= QUOTA_WARNING_THRESHOLD {
+            QuotaLevel::Warning
+        } else {
+            QuotaLevel::Ok
+        };
+
+        Self {
+            level,
+            usage,
+            audio_minutes_limit: limits.daily_audio_minutes,
+            cloud_requests_limit: limits.daily_cloud_requests,
+        }
+    }
+}
+
+fn fraction_used(used: f64, limit: f64) -> f64 {
+    if limit <= 0.0 {
+        return f64::INFINITY;
+    }
+    used / limit
+}
+
+/// Tracks daily usage and the currently running recording
+pub struct QuotaTracker {
+    usage: Mutex<DailyUsage>,
+    recording_started_at: Mutex<Option<Instant>>,
+    /// Length of the last finished recording and when it stopped
+    last_recording: Mutex<Option<(f64, Instant)>>,
+    file_path: PathBuf,
+}
+
+impl QuotaTracker {
+    /// Create a tracker with the given app data directory
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        let file_path = app_data_dir.join("usage.json");
+        let usage = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_else(|| DailyUsage::empty(today()));
+
+        Self {
+            usage: Mutex::new(usage),
+            recording_started_at: Mutex::new(None),
+            last_recording: Mutex::new(None),
+            file_path,
+        }
+    }
+
+    /// Usage for today (zero if nothing has been recorded yet today)
+    pub fn usage(&self) -> Result<DailyUsage, String> {
+        self.usage_on(today())
+    }
+
+    fn usage_on(&self, date: NaiveDate) -> Result<DailyUsage, String> {
+        let usage = self
+            .usage
+            .lock()
+            .map_err(|e| format!("Failed to read usage: {}", e))?;
+        if usage.date == date {
+            Ok(usage.clone())
+        } else {
+            Ok(DailyUsage::empty(date))
+        }
+    }
+
+    /// Today's usage evaluated against the given limits
+    pub fn status(&self, limits: QuotaLimits) -> Result<QuotaStatus, String> {
+        Ok(QuotaStatus::evaluate(self.usage()?, limits))
+    }
+
+    /// Mark the start of a recording
+    pub fn recording_started(&self) {
+        if let Ok(mut started_at) = self.recording_started_at.lock() {
+            *started_at = Some(Instant::now());
+        }
+    }
+
+    /// Count the recording that just ended towards the limits it uses
+    pub fn recording_stopped(&self, cloud: CloudUse) -> Result<DailyUsage, String> {
+        let elapsed = self
+            .recording_started_at
+            .lock()
+            .map_err(|e| format!("Failed to read recording start: {}", e))?
+            .take()
+            .map(|started_at| started_at.elapsed().as_secs_f64())
+            .unwrap_or(0.0);
+        if let Ok(mut last) = self.last_recording.lock() {
+            *last = Some((elapsed, Instant::now()));
+        }
+        let audio_seconds = if cloud.stt { elapsed } else { 0.0 };
+        self.add(today(), audio_seconds, u32::from(cloud.any()))
+    }
+
+    /// Length of the last finished recording, if one has finished
+    pub fn last_recording_seconds(&self) -> Option<f64> {
+        self.last_recording
+            .lock()
+            .ok()
+            .and_then(|last| last.map(|(seconds, _)| seconds))
+    }
+
+    /// Seconds since the last recording stopped, if one has finished
+    pub fn seconds_since_recording_stopped(&self) -> Option<f64> {
+        self.last_recording
+            .lock()
+            .ok()
+            .and_then(|last| last.map(|(_, stopped_at)| stopped_at.elapsed().as_secs_f64()))
+    }
+
+    /// Add usage for the given day, starting a fresh count when the day changes
+    pub fn add(
+        &self,
+        date: NaiveDate,
+        audio_seconds: f64,
+        cloud_requests: u32,
+    ) -> Result<DailyUsage, String> {
+        let updated = {
+            let mut usage = self
+                .usage
+                .lock()
+                .map_err(|e| format!("Failed to write usage: {}", e))?;
+            if usage.date != date {
+                *usage = DailyUsage::empty(date);
+            }
+            usage.audio_seconds += audio_seconds;
+            usage.cloud_requests += cloud_requests;
+            usage.clone()
+        };
+
+        let content = serde_json::to_string_pretty(&updated)
+            .map_err(|e| format!("Failed to serialize usage: {}", e))?;
+        write_file_atomic(&self.file_path, content.as_bytes())?;
+        Ok(updated)
+    }
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
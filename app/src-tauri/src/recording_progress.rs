@@ -0,0 +1,112 @@
+//! Periodic progress of the running recording for the overlay.
+//!
+//! While recording, a `recording-progress` event is emitted twice a second with
+//! the elapsed time and the approximate size of the audio sent so far, so the
+//! overlay can show e.g. "1:45 / 2:00". The limit is the shorter of the
+//! `max_recording_seconds` setting (for providers that cap request length) and
+//! what is left of the daily audio quota. A recording that uses up the daily
+//! audio quota is stopped, keeping what was recorded.
+
+use crate::quota::{CloudUse, QuotaLimits, QuotaTracker, QUOTA_WARNING_THRESHOLD};
+use crate::state::AppState;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event carrying each [`RecordingProgress`]
+pub const RECORDING_PROGRESS_EVENT: &str = "recording-progress";
+
+/// Time between progress events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Audio is streamed to the server as 16 kHz, 16-bit mono PCM
+pub const AUDIO_BYTES_PER_SECOND: f64 = 32_000.0;
+
+/// Snapshot of the running recording
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct RecordingProgress {
+    pub elapsed_seconds: f64,
+    /// Approximate size of the audio sent so far
+    pub audio_bytes: u64,
+    /// Longest this recording can run, if anything limits it
+    pub limit_seconds: Option<f64>,
+    /// The recording has used most of its limit
+    pub near_limit: bool,
+}
+
+impl RecordingProgress {
+    pub fn new(elapsed: Duration, limit_seconds: Option<f64>) -> Self {
+        let elapsed_seconds = elapsed.as_secs_f64();
+        Self {
+            elapsed_seconds,
+            audio_bytes: (elapsed_seconds * AUDIO_BYTES_PER_SECOND) as u64,
+            limit_seconds,
+            near_limit: limit_seconds
+                .is_some_and(|limit| elapsed_seconds >= limit * QUOTA_WARNING_THRESHOLD),
+        }
+    }
+}
+
+/// The tighter of the per-recording limit and the daily audio left, if either is set
+pub fn recording_limit(
+    max_recording_seconds: Option<u32>,
+    daily_audio_seconds_left: Option<f64>,
+) -> Option<f64> {
+    let per_recording = max_recording_seconds.map(f64::from);
+    match (
+        per_recording,
+        daily_audio_seconds_left.map(|left| left.max(0.0)),
+    ) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Generation counter that stops the ticker of a previous recording
+#[derive(Default)]
+pub struct RecordingProgressTicker {
+    generation: AtomicU64,
+}
+
+fn daily_audio_seconds_left(app: &AppHandle) -> Option<f64> {
+    let limit_minutes = QuotaLimits::from_store(app)
+        .applying_to(CloudUse::from_store(app))
+        .daily_audio_minutes?;
+    let used = app.try_state::<QuotaTracker>()?.usage().ok()?.audio_seconds;
+    Some(limit_minutes * 60.0 - used)
+}
+
+/// Emit progress events until the recording that is starting now stops
+pub fn start_ticker(app: &AppHandle) {
+    let Some(ticker) = app.try_state::<RecordingProgressTicker>() else {
+        return;
+    };
+    let generation = ticker.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let max_recording_seconds: Option<u32> =
+        crate::settings::read_setting(app, "max_recording_seconds");
+    let audio_left = daily_audio_seconds_left(app);
+    let limit = recording_limit(max_recording_seconds, audio_left);
+    let started_at = Instant::now();
+
+    let app = app.clone();
+    thread::spawn(move || {
+        let ticker = app.state::<RecordingProgressTicker>();
+        let state = app.state::<AppState>();
+        while ticker.generation.load(Ordering::SeqCst) == generation
+            && state.lifecycle.is_recording()
+        {
+            let elapsed = started_at.elapsed();
+            let progress = RecordingProgress::new(elapsed, limit);
+            let _ = app.emit(RECORDING_PROGRESS_EVENT, progress);
+            if audio_left.is_some_and(|left| elapsed.as_secs_f64() >= left) {
+                log::warn!("Daily audio limit reached, stopping the recording");
+                #[cfg(desktop)]
+                crate::set_recording(&app, false, "Daily limit");
+                break;
+            }
+            thread::sleep(PROGRESS_INTERVAL);
+        }
+    });
+}
@@ -0,0 +1,174 @@
+//! Recovering a recording cut short by a crash.
+//!
+//! While recording, the overlay hands the audio captured so far to this module
+//! every second and it is appended to `recording_recovery/` in the app data
+//! directory, synced to disk each time. The recorder's chunks form a streamable
+//! container (WebM, Ogg or fragmented MP4), so the file stays decodable up to
+//! the last chunk however it ends. The files are removed when recording ends.
+//! Nothing is written while history is encrypted, since the files can't be.
+//!
+//! Ones found at launch belong to a recording the app didn't get to finish: the
+//! audio is moved to the failed dictations, where retrying it transcribes it,
+//! and a notification points there.
+
+use crate::error::AppError;
+use crate::failed_dictations::{FailedDictations, FailureDetails};
+use crate::notifications::{notify, NotificationKind};
+use crate::settings::{read_setting, write_file_atomic};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Folder in the app data directory holding the recording in progress
+pub const RECOVERY_DIR: &str = "recording_recovery";
+
+/// Error the recovered recording is kept with
+pub const INTERRUPTED_ERROR: &str = "The app closed while recording";
+
+const AUDIO_FILE: &str = "recording.audio";
+const INFO_FILE: &str = "recording.json";
+
+/// What is known about the recording in progress, written when it starts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecordingInfo {
+    pub mime_type: String,
+    pub started_at: DateTime<Utc>,
+}
+
+/// A recording found at launch, left behind by a crash
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterruptedRecording {
+    pub info: RecordingInfo,
+    pub bytes: Vec<u8>,
+}
+
+/// The recording in progress, as written so far
+pub struct RecordingRecovery {
+    dir: PathBuf,
+    /// Serializes writes from overlapping commands
+    lock: Mutex<()>,
+}
+
+fn remove_if_present(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            Err(format!("Failed to remove {}: {}", path.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl RecordingRecovery {
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        Self {
+            dir: app_data_dir.join(RECOVERY_DIR),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Start writing a new recording, replacing any previous one
+    pub fn begin(&self, mime_type: &str) -> Result<(), String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+        let info = RecordingInfo {
+            mime_type: mime_type.to_string(),
+            started_at: Utc::now(),
+        };
+        let json = serde_json::to_vec(&info).map_err(|e| e.to_string())?;
+        fs::File::create(self.dir.join(AUDIO_FILE))
+            .map_err(|e| format!("Failed to start the recovery file: {}", e))?;
+        write_file_atomic(&self.dir.join(INFO_FILE), &json)
+    }
+
+    /// Add the next chunk of the recording and sync it to disk
+    pub fn append(&self, bytes: &[u8]) -> Result<(), AppError> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|e| AppError::Failed(e.to_string()))?;
+        if !self.dir.join(INFO_FILE).exists() {
+            return Err(AppError::NotFound(
+                "No recording is being written".to_string(),
+            ));
+        }
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(self.dir.join(AUDIO_FILE))
+            .map_err(|e| AppError::Storage(format!("Failed to open the recovery file: {}", e)))?;
+        file.write_all(bytes)
+            .and_then(|_| file.sync_data())
+            .map_err(|e| AppError::Storage(format!("Failed to write the recovery file: {}", e)))
+    }
+
+    /// Drop the recording once it ended normally
+    pub fn finish(&self) -> Result<(), String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        remove_if_present(&self.dir.join(INFO_FILE))?;
+        remove_if_present(&self.dir.join(AUDIO_FILE))
+    }
+
+    /// Take the recording left behind by the last run, if it captured anything
+    pub fn take_interrupted(&self) -> Result<Option<InterruptedRecording>, String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        let info_path = self.dir.join(INFO_FILE);
+        let audio_path = self.dir.join(AUDIO_FILE);
+        let info = fs::read(&info_path)
+            .ok()
+            .and_then(|json| serde_json::from_slice::<RecordingInfo>(&json).ok());
+        let bytes = fs::read(&audio_path).unwrap_or_default();
+        remove_if_present(&info_path)?;
+        remove_if_present(&audio_path)?;
+        Ok(info
+            .filter(|_| !bytes.is_empty())
+            .map(|info| InterruptedRecording { info, bytes }))
+    }
+}
+
+/// Keep a recording the last run didn't finish as a failed dictation to retry
+pub fn recover(app: &AppHandle) {
+    let Some(recovery) = app.try_state::<RecordingRecovery>() else {
+        return;
+    };
+    let recording = match recovery.take_interrupted() {
+        Ok(Some(recording)) => recording,
+        Ok(None) => return,
+        Err(e) => {
+            log::warn!("Failed to read the interrupted recording: {}", e);
+            return;
+        }
+    };
+    let Some(failed) = app.try_state::<FailedDictations>() else {
+        return;
+    };
+    let details = FailureDetails {
+        error: INTERRUPTED_ERROR.to_string(),
+        stt_provider: read_setting(app, "stt_provider"),
+        llm_provider: read_setting(app, "llm_provider"),
+        raw_text: None,
+    };
+    let audio = Some((
+        recording.bytes.as_slice(),
+        recording.info.mime_type.as_str(),
+    ));
+    match failed.record(details, audio) {
+        Ok(dictation) => {
+            log::info!(
+                "Recovered a recording from {} as {}",
+                recording.info.started_at,
+                dictation.id
+            );
+            notify(
+                app,
+                NotificationKind::RecordingRecovered,
+                "The recording cut short when the app closed was kept; retry it to transcribe it",
+            );
+            let _ = app.emit("failed-dictations-changed", ());
+        }
+        Err(e) => log::warn!("Failed to keep the interrupted recording: {}", e),
+    }
+}
@@ -0,0 +1,147 @@
+//! Where the app is in a dictation, kept in [`AppState`](crate::state::AppState).
+//!
+//! idle → recording → transcribing → pasting → idle. Recording remembers how it
+//! was started, so releasing the hold hotkey only stops a recording the hold
+//! hotkey started. The state follows the overlay state (see
+//! [`crate::overlay_state`]), so a recording started by clicking the overlay or
+//! ended by an error is reflected here too. Transitions that make no sense from
+//! the current state are refused, and every change is published as an
+//! `app-state-changed` event.
+
+use crate::overlay_state::OverlayState;
+use crate::state::AppState;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event carrying each new [`RecordingState`]
+pub const APP_STATE_EVENT: &str = "app-state-changed";
+
+/// What started a recording
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingMode {
+    /// Toggle hotkey, tray menu, links and remotes
+    Toggle,
+    /// Held hotkey, stopped when it is released
+    Hold,
+    /// A click on the overlay
+    Overlay,
+}
+
+/// Step of the dictation lifecycle
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum RecordingState {
+    #[default]
+    Idle,
+    Recording {
+        mode: RecordingMode,
+    },
+    /// Audio is done; the server is transcribing and cleaning it up
+    Transcribing,
+    /// The text is being pasted
+    Pasting,
+}
+
+impl RecordingState {
+    pub fn is_recording(&self) -> bool {
+        matches!(self, Self::Recording { .. })
+    }
+
+    /// Whether the lifecycle can move from `self` to `next`. A new recording may
+    /// start while the previous dictation is still on its way, and any step can
+    /// end in idle (cancelled, failed or done).
+    pub fn can_transition_to(&self, next: &RecordingState) -> bool {
+        match next {
+            Self::Idle => *self != Self::Idle,
+            Self::Recording { .. } => !self.is_recording(),
+            Self::Transcribing => self.is_recording(),
+            Self::Pasting => *self == Self::Transcribing,
+        }
+    }
+
+    /// The state an overlay state belongs to. Recording started elsewhere keeps
+    /// its mode.
+    fn from_overlay_state(state: &OverlayState) -> Self {
+        match state {
+            OverlayState::Recording => Self::Recording {
+                mode: RecordingMode::Overlay,
+            },
+            OverlayState::Transcribing | OverlayState::Cleaning => Self::Transcribing,
+            OverlayState::Pasting => Self::Pasting,
+            OverlayState::Idle | OverlayState::Error { .. } => Self::Idle,
+        }
+    }
+}
+
+/// The current [`RecordingState`], guarded against invalid transitions
+#[derive(Default)]
+pub struct RecordingLifecycle {
+    current: Mutex<RecordingState>,
+}
+
+impl RecordingLifecycle {
+    pub fn current(&self) -> RecordingState {
+        self.current.lock().map(|state| *state).unwrap_or_default()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.current().is_recording()
+    }
+
+    /// Move to `next`, returning the state it left
+    pub fn transition(&self, next: RecordingState) -> Result<RecordingState, String> {
+        let mut current = self
+            .current
+            .lock()
+            .map_err(|e| format!("Failed to read the recording state: {}", e))?;
+        if !current.can_transition_to(&next) {
+            return Err(format!("Can't go from {:?} to {:?}", *current, next));
+        }
+        Ok(std::mem::replace(&mut *current, next))
+    }
+}
+
+/// Move the app to `next` and publish it; fails for a transition that isn't allowed
+pub fn transition(app: &AppHandle, next: RecordingState) -> Result<RecordingState, String> {
+    let lifecycle = &app.state::<AppState>().lifecycle;
+    let previous = lifecycle.transition(next)?;
+    log::debug!("App state: {:?} -> {:?}", previous, next);
+    let _ = app.emit(APP_STATE_EVENT, next);
+    Ok(previous)
+}
+
+/// Follow the overlay state, for steps the shortcut handlers don't drive
+pub fn on_overlay_state(app: &AppHandle, state: &OverlayState) {
+    let next = RecordingState::from_overlay_state(state);
+    let Some(app_state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let current = app_state.lifecycle.current();
+    // Already there, such as recording started by a hotkey
+    if current == next || (current.is_recording() && next.is_recording()) {
+        return;
+    }
+    // A hotkey recording is only ended by its hotkey, a cancel or an error, not by
+    // the previous dictation finishing while it goes on
+    if *state == OverlayState::Idle
+        && matches!(
+            current,
+            RecordingState::Recording {
+                mode: RecordingMode::Toggle | RecordingMode::Hold
+            }
+        )
+    {
+        return;
+    }
+    if let Err(e) = transition(app, next) {
+        log::debug!("Not following overlay state {:?}: {}", state, e);
+    }
+}
+
+/// Whether the app is recording
+pub fn is_recording(app: &AppHandle) -> bool {
+    app.try_state::<AppState>()
+        .is_some_and(|state| state.lifecycle.is_recording())
+}
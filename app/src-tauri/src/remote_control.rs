@@ -0,0 +1,542 @@
+//! Using a phone as a push-to-talk remote over the local network.
+//!
+//! When `remote_control_enabled` is on, a small web page is served on port
+//! `remote_control_port` of every network interface and announced over mDNS as
+//! `http://tambourine.local:<port>/`. Opened in a phone's browser, it shows a large
+//! button that records while held, the dictation's state and the last transcript,
+//! e.g. for dictating speaker notes from a podium.
+//!
+//! Phones pair by entering the six-digit code shown in the app's settings, which
+//! gives them a token that stays valid until the app quits. Each wrong code makes
+//! the next attempt from the same address wait twice as long, and after
+//! `MAX_PAIRING_FAILURES` wrong codes pairing is turned off until a new code is
+//! made in the app, so the code can't be guessed.
+//!
+//! Besides the page at `/`, the server answers:
+//!
+//! - `POST /pair` with `{"code": "123456"}`: `{"token": "..."}` for a correct code
+//! - `GET /state`: `{"recording": bool, "state": "...", "last_transcript": {"text",
+//!   "timestamp"} | null}`
+//! - `POST /recording/start` and `/recording/stop`: `{"recording": bool}`
+//!
+//! Every request but the page and pairing must send `Authorization: Bearer
+//! <token>`. Errors are returned as `{"error": "..."}`.
+//!
+//! Only the transcript of the remote's own last recording is shown, never one
+//! dictated at the computer. The page polls `/state` every second; a recording
+//! the remote started is stopped when it hasn't been heard from for
+//! `HOLD_TIMEOUT`, such as when the phone locks or drops off the network while
+//! the button is held.
+
+use crate::automation_api::{generate_token, is_authorized};
+use crate::history::{HistoryEntry, HistoryStorage};
+use crate::overlay_state::OverlayStateMachine;
+use chrono::{DateTime, Utc};
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::{IpAddr, UdpSocket};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tiny_http::{Header, Request, Response, Server};
+
+/// Port the remote listens on when the setting is missing
+pub const DEFAULT_REMOTE_CONTROL_PORT: u16 = 8770;
+
+/// Wrong pairing codes accepted before pairing is turned off
+pub const MAX_PAIRING_FAILURES: u32 = 5;
+
+/// Wait after a first wrong code from an address, doubled for each one after it
+pub const PAIRING_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest wait between pairing attempts from one address
+const MAX_PAIRING_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// Silence from the remote after which a recording it holds is stopped
+pub const HOLD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time after the remote's recording stops within which its transcript arrives
+pub const TRANSCRIPT_WINDOW: Duration = Duration::from_secs(120);
+
+/// Recent entries searched for the remote's transcript
+const TRANSCRIPT_CANDIDATES: usize = 20;
+
+/// Event telling the settings window the pairing code changed
+pub const PAIRING_CODE_CHANGED_EVENT: &str = "remote-pairing-code-changed";
+
+/// Name the remote is announced under on the local network
+const MDNS_HOST_NAME: &str = "tambourine";
+
+/// Service type the remote is announced as
+const MDNS_SERVICE_TYPE: &str = "_http._tcp.local.";
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: u64 = 4 * 1024;
+
+/// Source named in logs for recordings started from the remote
+const SOURCE: &str = "Phone remote";
+
+/// The page phones open
+const REMOTE_PAGE: &str = include_str!("remote_control.html");
+
+/// What a request asks for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemoteEndpoint {
+    Page,
+    Pair,
+    State,
+    StartRecording,
+    StopRecording,
+}
+
+/// The endpoint for a method and URL, ignoring any query string
+pub fn route(method: &str, url: &str) -> Option<RemoteEndpoint> {
+    let path = url.split('?').next().unwrap_or(url);
+    match (method.to_ascii_uppercase().as_str(), path) {
+        ("GET", "/" | "/index.html") => Some(RemoteEndpoint::Page),
+        ("POST", "/pair") => Some(RemoteEndpoint::Pair),
+        ("GET", "/state") => Some(RemoteEndpoint::State),
+        ("POST", "/recording/start") => Some(RemoteEndpoint::StartRecording),
+        ("POST", "/recording/stop") => Some(RemoteEndpoint::StopRecording),
+        _ => None,
+    }
+}
+
+/// A random six-digit pairing code
+pub fn generate_pairing_code() -> Result<String, String> {
+    let mut bytes = [0u8; 4];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate pairing code: {}", e))?;
+    Ok(format!("{:06}", u32::from_le_bytes(bytes) % 1_000_000))
+}
+
+struct PairingState {
+    code: String,
+    failures: u32,
+    /// Set after too many wrong codes, until a new code is made in the app
+    disabled: bool,
+    /// Wrong codes from each address and when the last one came
+    attempts: HashMap<IpAddr, (u32, Instant)>,
+    tokens: Vec<String>,
+}
+
+/// The current pairing code and the tokens of paired phones
+pub struct RemotePairing {
+    state: Mutex<PairingState>,
+}
+
+impl RemotePairing {
+    pub fn new(code: String) -> Self {
+        Self {
+            state: Mutex::new(PairingState {
+                code,
+                failures: 0,
+                disabled: false,
+                attempts: HashMap::new(),
+                tokens: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn code(&self) -> String {
+        self.state
+            .lock()
+            .map(|state| state.code.clone())
+            .unwrap_or_default()
+    }
+
+    /// Whether pairing is off after too many wrong codes
+    pub fn is_disabled(&self) -> bool {
+        self.state.lock().is_ok_and(|state| state.disabled)
+    }
+
+    /// Replace the pairing code and turn pairing back on; phones already paired stay
+    /// paired
+    pub fn regenerate(&self) -> Result<String, String> {
+        let code = generate_pairing_code()?;
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        state.code = code.clone();
+        state.failures = 0;
+        state.disabled = false;
+        state.attempts.clear();
+        Ok(code)
+    }
+
+    /// Pair a phone at `from` that entered `code`, returning its token. Returns
+    /// whether pairing was just turned off along with an error.
+    pub fn pair(&self, code: &str, from: IpAddr, now: Instant) -> Result<String, (String, bool)> {
+        let mut state = self.state.lock().map_err(|e| (e.to_string(), false))?;
+        if state.disabled {
+            return Err((
+                "Pairing is off after too many wrong codes; make a new code in Tambourine"
+                    .to_string(),
+                false,
+            ));
+        }
+        if let Some(&(failures, last)) = state.attempts.get(&from) {
+            let wait = pairing_backoff(failures);
+            if now.duration_since(last) < wait {
+                return Err((
+                    format!(
+                        "Too many wrong codes; try again in {} seconds",
+                        (wait - now.duration_since(last)).as_secs().max(1)
+                    ),
+                    false,
+                ));
+            }
+        }
+
+        let given = format!("Bearer {}", code.trim());
+        if is_authorized(Some(&given), &state.code) {
+            let token = generate_token().map_err(|e| (e, false))?;
+            state.tokens.push(token.clone());
+            state.attempts.remove(&from);
+            return Ok(token);
+        }
+        state.failures += 1;
+        let attempts = state.attempts.entry(from).or_insert((0, now));
+        *attempts = (attempts.0 + 1, now);
+        if state.failures < MAX_PAIRING_FAILURES {
+            return Err(("Wrong pairing code".to_string(), false));
+        }
+        state.disabled = true;
+        Err((
+            "Too many wrong codes; make a new code in Tambourine to pair".to_string(),
+            true,
+        ))
+    }
+
+    /// Whether an `Authorization` header carries the token of a paired phone
+    pub fn is_paired(&self, authorization: Option<&str>) -> bool {
+        self.state.lock().is_ok_and(|state| {
+            state
+                .tokens
+                .iter()
+                .any(|token| is_authorized(authorization, token))
+        })
+    }
+}
+
+/// Wait before the next pairing attempt from an address with `failures` wrong codes
+pub fn pairing_backoff(failures: u32) -> Duration {
+    PAIRING_BACKOFF
+        .saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+        .min(MAX_PAIRING_BACKOFF)
+}
+
+/// The recording the remote started, if it is still going, and its last one
+#[derive(Default)]
+pub struct RemoteSession {
+    state: Mutex<SessionState>,
+}
+
+#[derive(Default)]
+struct SessionState {
+    /// When the remote was last heard from, while a recording it started goes on
+    holding: Option<Instant>,
+    /// When the remote's last recording stopped
+    stopped_at: Option<DateTime<Utc>>,
+}
+
+impl RemoteSession {
+    /// Note that a recording the remote started is going
+    pub fn start(&self, now: Instant) {
+        if let Ok(mut state) = self.state.lock() {
+            state.holding = Some(now);
+        }
+    }
+
+    /// Note that the remote's recording stopped, returning whether it had one
+    pub fn stop(&self, at: DateTime<Utc>) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        let held = state.holding.take().is_some();
+        if held {
+            state.stopped_at = Some(at);
+        }
+        held
+    }
+
+    /// Note a request from the remote
+    pub fn seen(&self, now: Instant) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(holding) = state.holding.as_mut() {
+                *holding = now;
+            }
+        }
+    }
+
+    /// Whether the remote holds a recording but hasn't been heard from for
+    /// `HOLD_TIMEOUT`
+    pub fn hold_expired(&self, now: Instant) -> bool {
+        self.state.lock().is_ok_and(|state| {
+            state
+                .holding
+                .is_some_and(|seen| now.duration_since(seen) >= HOLD_TIMEOUT)
+        })
+    }
+
+    /// When the remote's last recording stopped
+    pub fn stopped_at(&self) -> Option<DateTime<Utc>> {
+        self.state.lock().ok().and_then(|state| state.stopped_at)
+    }
+}
+
+/// The transcript of the remote's recording that stopped at `stopped_at`: the first
+/// entry added after it, within `TRANSCRIPT_WINDOW`. `entries` are newest first.
+pub fn remote_transcript(
+    entries: &[HistoryEntry],
+    stopped_at: DateTime<Utc>,
+) -> Option<&HistoryEntry> {
+    let window = chrono::Duration::from_std(TRANSCRIPT_WINDOW).unwrap_or_default();
+    entries
+        .iter()
+        .rev()
+        .find(|entry| entry.timestamp >= stopped_at && entry.timestamp < stopped_at + window)
+}
+
+/// Where phones reach the remote and the code they pair with
+#[derive(Debug, Clone, Serialize)]
+pub struct RemotePairingInfo {
+    pub urls: Vec<String>,
+    pub code: String,
+    /// Pairing is off after too many wrong codes, until a new code is made
+    pub pairing_disabled: bool,
+}
+
+/// The running remote
+pub struct RemoteControl {
+    pub pairing: RemotePairing,
+    session: RemoteSession,
+    port: u16,
+    /// Keeps answering mDNS queries while the app runs
+    _mdns: Option<ServiceDaemon>,
+}
+
+impl RemoteControl {
+    /// Addresses to open on the phone: the mDNS name and the computer's LAN address
+    pub fn info(&self) -> RemotePairingInfo {
+        let mut urls = vec![format!("http://{}.local:{}/", MDNS_HOST_NAME, self.port)];
+        if let Some(address) = lan_address() {
+            urls.push(format!(
+                "http://{}/",
+                std::net::SocketAddr::new(address, self.port)
+            ));
+        }
+        RemotePairingInfo {
+            urls,
+            code: self.pairing.code(),
+            pairing_disabled: self.pairing.is_disabled(),
+        }
+    }
+}
+
+/// This computer's address on the local network, if it has one
+pub fn lan_address() -> Option<IpAddr> {
+    // Connecting a UDP socket sends nothing, but picks the interface for the route
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("192.0.2.1", 9)).ok()?;
+    let address = socket.local_addr().ok()?.ip();
+    (!address.is_unspecified() && !address.is_loopback()).then_some(address)
+}
+
+#[derive(Deserialize)]
+struct PairBody {
+    code: String,
+}
+
+fn error(message: impl Into<String>) -> Value {
+    json!({ "error": message.into() })
+}
+
+fn is_recording(app: &AppHandle) -> bool {
+    crate::recording_state::is_recording(app)
+}
+
+fn set_recording(app: &AppHandle, remote: &RemoteControl, recording: bool) -> (u16, Value) {
+    let was_recording = is_recording(app);
+    #[cfg(desktop)]
+    crate::set_recording(app, recording, SOURCE);
+    // Only a recording the remote started is the remote's
+    if recording && !was_recording && is_recording(app) {
+        remote.session.start(Instant::now());
+    } else if !recording {
+        remote.session.stop(Utc::now());
+    }
+    (200, json!({ "recording": is_recording(app) }))
+}
+
+fn state(app: &AppHandle, remote: &RemoteControl) -> (u16, Value) {
+    let state = app
+        .try_state::<OverlayStateMachine>()
+        .map(|machine| machine.current())
+        .unwrap_or_default();
+    let last_transcript = remote.session.stopped_at().and_then(|stopped_at| {
+        let history = app.try_state::<HistoryStorage>()?;
+        let entries = history.get_dictated(Some(TRANSCRIPT_CANDIDATES)).ok()?;
+        let entry = remote_transcript(&entries, stopped_at)?;
+        Some(json!({
+            "text": entry.revised_text.as_deref().unwrap_or(&entry.text),
+            "timestamp": entry.timestamp,
+        }))
+    });
+    (
+        200,
+        json!({
+            "recording": is_recording(app),
+            "state": crate::mqtt::state_name(&state),
+            "last_transcript": last_transcript,
+        }),
+    )
+}
+
+fn pair(app: &AppHandle, remote: &RemoteControl, request: &mut Request) -> (u16, Value) {
+    let mut body = String::new();
+    if let Err(e) = request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+    {
+        return (400, error(format!("Failed to read request: {}", e)));
+    }
+    let code = match serde_json::from_str::<PairBody>(&body) {
+        Ok(body) => body.code,
+        Err(e) => return (400, error(format!("Expected {{\"code\": ...}}: {}", e))),
+    };
+    let Some(from) = request.remote_addr().map(|address| address.ip()) else {
+        return (400, error("Unknown address"));
+    };
+    match remote.pairing.pair(&code, from, Instant::now()) {
+        Ok(token) => {
+            log::info!("Phone remote paired");
+            (200, json!({ "token": token }))
+        }
+        Err((message, disabled)) => {
+            if disabled {
+                log::warn!("Phone remote pairing turned off after too many wrong codes");
+                let _ = app.emit(PAIRING_CODE_CHANGED_EVENT, ());
+            }
+            (401, error(message))
+        }
+    }
+}
+
+fn handle(app: &AppHandle, request: &mut Request) -> (u16, Value) {
+    let remote = app.state::<RemoteControl>();
+    let endpoint = route(request.method().as_str(), request.url());
+    if endpoint == Some(RemoteEndpoint::Pair) {
+        return pair(app, &remote, request);
+    }
+    let authorization = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .map(|header| header.value.as_str());
+    if !remote.pairing.is_paired(authorization) {
+        return (401, error("Not paired"));
+    }
+    remote.session.seen(Instant::now());
+    match endpoint {
+        Some(RemoteEndpoint::State) => state(app, &remote),
+        Some(RemoteEndpoint::StartRecording) => set_recording(app, &remote, true),
+        Some(RemoteEndpoint::StopRecording) => set_recording(app, &remote, false),
+        Some(RemoteEndpoint::Page | RemoteEndpoint::Pair) | None => {
+            (404, error("Unknown endpoint"))
+        }
+    }
+}
+
+fn respond(app: &AppHandle, mut request: Request) {
+    let response = if route(request.method().as_str(), request.url()) == Some(RemoteEndpoint::Page)
+    {
+        let content_type = Header::from_bytes("Content-Type", "text/html; charset=utf-8")
+            .expect("Content type header is valid");
+        Response::from_string(REMOTE_PAGE).with_header(content_type)
+    } else {
+        let (status, body) = handle(app, &mut request);
+        let content_type = Header::from_bytes("Content-Type", "application/json")
+            .expect("Content type header is valid");
+        Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(content_type)
+    };
+    if let Err(e) = request.respond(response) {
+        log::debug!("Failed to answer remote request: {}", e);
+    }
+}
+
+/// Announce the remote on the local network
+fn announce(port: u16) -> Result<ServiceDaemon, String> {
+    let daemon = ServiceDaemon::new().map_err(|e| e.to_string())?;
+    let service = ServiceInfo::new(
+        MDNS_SERVICE_TYPE,
+        "Tambourine Remote",
+        &format!("{}.local.", MDNS_HOST_NAME),
+        "",
+        port,
+        &[("path", "/")][..],
+    )
+    .map_err(|e| e.to_string())?
+    .enable_addr_auto();
+    daemon.register(service).map_err(|e| e.to_string())?;
+    Ok(daemon)
+}
+
+/// Serve the remote on a background thread, when it is enabled
+pub fn spawn(app: AppHandle) {
+    if !crate::settings::read_setting::<bool>(&app, "remote_control_enabled") {
+        return;
+    }
+    let code = match generate_pairing_code() {
+        Ok(code) => code,
+        Err(e) => {
+            log::error!("Phone remote not started: {}", e);
+            return;
+        }
+    };
+    let port = crate::settings::read_setting::<Option<u16>>(&app, "remote_control_port")
+        .unwrap_or(DEFAULT_REMOTE_CONTROL_PORT);
+    let server = match Server::http(("0.0.0.0", port)) {
+        Ok(server) => server,
+        Err(e) => {
+            log::error!("Failed to start phone remote on port {}: {}", port, e);
+            return;
+        }
+    };
+    log::info!("Phone remote listening on port {}", port);
+
+    // Phones can still use the address shown in the app when mDNS isn't available
+    let mdns = announce(port)
+        .inspect_err(|e| log::warn!("Failed to announce phone remote over mDNS: {}", e))
+        .ok();
+    app.manage(RemoteControl {
+        pairing: RemotePairing::new(code),
+        session: RemoteSession::default(),
+        port,
+        _mdns: mdns,
+    });
+
+    let watchdog = app.clone();
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_secs(1));
+        let remote = watchdog.state::<RemoteControl>();
+        if !is_recording(&watchdog) {
+            // Stopped at the computer, so no longer the remote's to time out
+            remote.session.stop(Utc::now());
+        } else if remote.session.hold_expired(Instant::now()) {
+            log::warn!("Phone remote went quiet while recording; stopping");
+            set_recording(&watchdog, &remote, false);
+        }
+    });
+
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let app = app.clone();
+            thread::spawn(move || respond(&app, request));
+        }
+    });
+}
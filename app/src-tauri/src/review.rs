@@ -0,0 +1,49 @@
+//! Optional review step between cleanup and paste.
+//!
+//! With the `review_before_paste` setting, cleaned text is held as the pending
+//! paste and shown in a small editable window instead of being pasted right
+//! away. Confirming pastes the (possibly edited) text into the app that was
+//! focused before the window appeared; discarding drops it.
+
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+/// Window label of the review window
+pub const REVIEW_LABEL: &str = "review";
+
+/// Event sent to the review window when new text is waiting for review
+pub const PASTE_REVIEW_EVENT: &str = "paste-review";
+
+/// Time for focus to return to the previous app after the review window hides
+pub const FOCUS_RETURN_DELAY: Duration = Duration::from_millis(150);
+
+/// Show the review window (creating it on first use) and hand it the pending text
+pub fn show_review_window(app: &AppHandle, text: &str) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(REVIEW_LABEL) {
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        return window
+            .emit(PASTE_REVIEW_EVENT, text)
+            .map_err(|e| e.to_string());
+    }
+
+    // A fresh window reads the pending text itself once it has loaded
+    WebviewWindowBuilder::new(app, REVIEW_LABEL, WebviewUrl::App("review.html".into()))
+        .title("Review Dictation")
+        .inner_size(440.0, 180.0)
+        .min_inner_size(320.0, 120.0)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .center()
+        .focused(true)
+        .build()
+        .map_err(|e| format!("Failed to open review window: {}", e))?;
+    Ok(())
+}
+
+/// Hide the review window so focus goes back to the app being dictated into
+pub fn hide_review_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(REVIEW_LABEL) {
+        let _ = window.hide();
+    }
+}
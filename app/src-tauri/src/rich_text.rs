@@ -0,0 +1,17 @@
+//! Markdown to HTML for rich-text pastes.
+//!
+//! Cleanup can format its output as Markdown (lists, emphasis, headings). Apps
+//! like Gmail and Word don't render Markdown, so with the rich paste format the
+//! text is also put on the clipboard as HTML, with the original Markdown as the
+//! plain-text fallback for apps that only read plain text.
+
+use pulldown_cmark::{html, Options, Parser};
+
+/// Render Markdown as an HTML fragment
+pub fn markdown_to_html(markdown: &str) -> String {
+    let options =
+        Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS;
+    let mut output = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut output, Parser::new_ext(markdown, options));
+    output.trim_end().to_string()
+}
@@ -1,5 +1,22 @@
+use crate::email_draft::EmailClient;
+use crate::history_crypto::HistoryEncryption;
+use crate::history_duplicates::DEFAULT_DUPLICATE_THRESHOLD;
+use crate::logging::LogLevel;
+use crate::metrics::AnalyticsMode;
+use crate::output_sinks::ScriptSink;
+use crate::webhooks::Webhook;
+use chrono::Utc;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
 
 #[cfg(desktop)]
 use tauri_plugin_global_shortcut::Shortcut;
@@ -20,6 +37,15 @@ pub const DEFAULT_HOLD_KEY: &str = "Backquote";
 /// Default key for paste last transcription (Ctrl+Alt+.)
 pub const DEFAULT_PASTE_LAST_KEY: &str = "Period";
 
+/// Default key for undoing the last paste (Ctrl+Alt+Z)
+pub const DEFAULT_UNDO_PASTE_KEY: &str = "Z";
+
+/// Default key for cleaning up the copied text (Ctrl+Alt+C)
+pub const DEFAULT_CLIPBOARD_CLEANUP_KEY: &str = "C";
+
+/// Default key for cancelling the dictation in progress (Ctrl+Alt+X)
+pub const DEFAULT_CANCEL_KEY: &str = "X";
+
 // ============================================================================
 
 /// Configuration for a hotkey combination
@@ -77,6 +103,39 @@ impl HotkeyConfig {
         }
     }
 
+    /// Create default undo-paste hotkey config
+    pub fn default_undo_paste() -> Self {
+        Self {
+            modifiers: DEFAULT_HOTKEY_MODIFIERS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            key: DEFAULT_UNDO_PASTE_KEY.to_string(),
+        }
+    }
+
+    /// Create default clipboard-cleanup hotkey config
+    pub fn default_clipboard_cleanup() -> Self {
+        Self {
+            modifiers: DEFAULT_HOTKEY_MODIFIERS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            key: DEFAULT_CLIPBOARD_CLEANUP_KEY.to_string(),
+        }
+    }
+
+    /// Create default cancel hotkey config
+    pub fn default_cancel() -> Self {
+        Self {
+            modifiers: DEFAULT_HOTKEY_MODIFIERS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            key: DEFAULT_CANCEL_KEY.to_string(),
+        }
+    }
+
     /// Convert to shortcut string format like "ctrl+alt+Space"
     /// Note: modifiers must be lowercase for the parser to recognize them
     pub fn to_shortcut_string(&self) -> String {
@@ -103,3 +162,1204 @@ impl HotkeyConfig {
         })
     }
 }
+
+/// How dictated text is delivered to the focused application
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteMethod {
+    /// Put the text on the clipboard and simulate Ctrl+V / Cmd+V
+    #[default]
+    ClipboardPaste,
+    /// Type the text with synthetic key events
+    SimulateTyping,
+    /// Only copy the text to the clipboard
+    CopyOnly,
+    /// Write the text into the focused field through the Accessibility API (macOS) or the
+    /// field's own edit control (Windows), pasting via the clipboard where that isn't
+    /// supported
+    Accessibility,
+}
+
+/// What dictated text does to a selection in the target field
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InsertMode {
+    /// Insert at the cursor, after any selected text
+    #[default]
+    Insert,
+    /// Replace the selected text, e.g. to rewrite a sentence by voice
+    ReplaceSelection,
+}
+
+/// Clipboard flavor of pasted text
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteFormat {
+    /// The text as-is
+    #[default]
+    Plain,
+    /// Markdown rendered as HTML, with the text as the plain-text fallback
+    Rich,
+}
+
+/// How the last paste is undone
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum UndoPasteMethod {
+    /// Select the pasted characters back from the cursor and delete them
+    #[default]
+    SelectBack,
+    /// Send the target app's undo shortcut
+    AppUndo,
+}
+
+/// Keystroke sent to make the target app paste the clipboard
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteKeystroke {
+    /// Ctrl+V (Cmd+V on macOS)
+    #[default]
+    CtrlV,
+    /// Ctrl+Shift+V, used by most Linux terminals
+    CtrlShiftV,
+    /// Shift+Insert, for terminals and older Windows apps
+    ShiftInsert,
+    /// Middle mouse button at the pointer, pasting the primary selection (Linux)
+    MiddleClick,
+}
+
+/// Which monitor the overlay is shown on
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "mode", content = "monitor", rename_all = "snake_case")]
+pub enum OverlayMonitor {
+    /// The monitor containing the focused window
+    #[default]
+    FollowFocus,
+    /// The primary monitor
+    Primary,
+    /// A specific monitor by name, falling back to the primary one when disconnected
+    Fixed(String),
+}
+
+/// Screen position the overlay is anchored to
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayAnchor {
+    /// Near the bottom-right corner, or wherever the user drags it
+    #[default]
+    Free,
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    /// Next to the mouse cursor when recording starts
+    NearCursor,
+    /// Next to the text caret when recording starts, or the mouse cursor when the
+    /// focused app doesn't expose its caret
+    NearCaret,
+}
+
+/// Screen edge for the minimal progress bar, or off
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeIndicator {
+    #[default]
+    Off,
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// Overall size of the overlay pill
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlaySize {
+    Compact,
+    #[default]
+    Regular,
+    Large,
+}
+
+/// Arrangement of the overlay: the small pill, or a wider panel that also shows the
+/// transcript while recording
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayLayout {
+    #[default]
+    Pill,
+    Panel,
+}
+
+impl OverlayLayout {
+    /// Smallest logical size (width, height) of the overlay window in this layout
+    pub fn min_size(self) -> (f64, f64) {
+        match self {
+            Self::Pill => (48.0, 48.0),
+            Self::Panel => (240.0, 96.0),
+        }
+    }
+}
+
+/// Overlay look, applied by the overlay window and kept across restarts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OverlayAppearance {
+    /// Color of the waveform and logo, as `#rrggbb`
+    pub accent_color: String,
+    /// Opacity of the overlay background, from 0.2 to 1.0
+    pub opacity: f64,
+    pub size: OverlaySize,
+    /// Replace the live waveform and transitions with static indicators
+    pub reduced_motion: bool,
+}
+
+impl Default for OverlayAppearance {
+    fn default() -> Self {
+        Self {
+            accent_color: "#eeeeee".to_string(),
+            opacity: 0.9,
+            size: OverlaySize::default(),
+            reduced_motion: false,
+        }
+    }
+}
+
+impl OverlayAppearance {
+    /// Check the accent color is a `#rrggbb` hex color and the opacity is in range
+    pub fn validate(&self) -> Result<(), String> {
+        let hex = self.accent_color.strip_prefix('#').unwrap_or_default();
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "Accent color must be a #rrggbb hex color, got {}",
+                self.accent_color
+            ));
+        }
+        if !(0.2..=1.0).contains(&self.opacity) {
+            return Err(format!(
+                "Overlay opacity must be between 0.2 and 1.0, got {}",
+                self.opacity
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A point in logical screen coordinates
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct OverlayPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Output settings for one target app, overriding the global ones where set
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AppOutputProfile {
+    /// How text is delivered to this app (e.g. typing for terminals that block paste)
+    pub paste_method: Option<PasteMethod>,
+    /// Plain or rich text on the clipboard (e.g. rich for email and word processors)
+    pub paste_format: Option<PasteFormat>,
+    /// Keystroke that pastes in this app (e.g. Ctrl+Shift+V in terminals)
+    pub paste_keystroke: Option<PasteKeystroke>,
+    /// Put before every line of the output (e.g. "// " in an IDE, "> " to quote in Slack)
+    pub line_prefix: Option<String>,
+    /// Wrapper around the output, with `{text}` where it goes (e.g. "```\n{text}\n```")
+    pub template: Option<String>,
+    /// Note in the Obsidian vault the output is added to instead of being pasted
+    /// (e.g. "Inbox/{date}")
+    pub obsidian_note: Option<String>,
+    /// Mail app that opens a new draft with the output instead of it being pasted
+    pub email_draft: Option<EmailClient>,
+}
+
+/// Placeholder for the dictated text in an output template
+pub const TEMPLATE_TEXT_PLACEHOLDER: &str = "{text}";
+
+impl AppOutputProfile {
+    /// Apply the line prefix, then the template, to dictated text
+    pub fn apply_template(&self, text: &str) -> String {
+        let text = match self.line_prefix.as_deref().filter(|p| !p.is_empty()) {
+            Some(prefix) => text
+                .lines()
+                .map(|line| format!("{}{}", prefix, line))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => text.to_string(),
+        };
+        match self.template.as_deref() {
+            Some(template) if template.contains(TEMPLATE_TEXT_PLACEHOLDER) => {
+                template.replace(TEMPLATE_TEXT_PLACEHOLDER, &text)
+            }
+            _ => text,
+        }
+    }
+}
+
+/// Profile for an app name, matched case-insensitively
+pub fn find_app_profile<'a>(
+    profiles: &'a HashMap<String, AppOutputProfile>,
+    app_name: &str,
+) -> Option<&'a AppOutputProfile> {
+    profiles
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(app_name))
+        .map(|(_, profile)| profile)
+}
+
+/// Which pipeline outcomes are posted as OS notifications
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Dictated text was pasted (with a snippet of the text)
+    pub transcription_complete: bool,
+    /// A provider, connection or paste error stopped a dictation
+    pub provider_errors: bool,
+    /// A daily dictation limit is nearly or fully used
+    pub quota_warnings: bool,
+}
+
+/// Metadata describing a single setting, for the settings UI and docs
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingDescriptor {
+    /// Settings store key
+    pub key: &'static str,
+    /// Rust type of the value
+    pub value_type: &'static str,
+    pub default: serde_json::Value,
+    pub description: &'static str,
+    /// Whether changes only take effect after restarting the app
+    pub restart_required: bool,
+    /// Whether the setting is stored per machine rather than roaming
+    pub machine_local: bool,
+    /// Whether the admin policy locks this setting
+    pub locked: bool,
+}
+
+/// Defines [`AppSettings`] and its metadata from a single field list, so the metadata
+/// exposed by `describe_settings` can't drift from the Rust definitions.
+macro_rules! define_app_settings {
+    (
+        $(
+            $(#[doc = $doc:literal])+
+            #[restart_required = $restart:literal]
+            $field:ident: $ty:ty = $default:expr,
+        )+
+    ) => {
+        /// All persisted app settings with their defaults.
+        /// Each field is stored as its own key in the settings store.
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[serde(default)]
+        pub struct AppSettings {
+            $(
+                $(#[doc = $doc])+
+                pub $field: $ty,
+            )+
+        }
+
+        impl Default for AppSettings {
+            fn default() -> Self {
+                Self {
+                    $($field: $default,)+
+                }
+            }
+        }
+
+        impl AppSettings {
+            /// Settings store key of every setting, in declaration order
+            pub const KEYS: &'static [&'static str] = &[$(stringify!($field)),+];
+
+            /// Metadata for every setting, generated from the field definitions
+            pub fn describe() -> Vec<SettingDescriptor> {
+                let defaults = Self::default();
+                vec![
+                    $(
+                        SettingDescriptor {
+                            key: stringify!($field),
+                            value_type: stringify!($ty),
+                            default: json_value(&defaults.$field),
+                            description: concat!($($doc),+).trim(),
+                            restart_required: $restart,
+                            machine_local: SettingsManager::is_machine_local(stringify!($field)),
+                            locked: false,
+                        },
+                    )+
+                ]
+            }
+        }
+    };
+}
+
+define_app_settings! {
+    /// Shortcut that starts and stops recording
+    #[restart_required = false]
+    toggle_hotkey: HotkeyConfig = HotkeyConfig::default_toggle(),
+
+    /// Shortcut held down to record (push-to-talk)
+    #[restart_required = false]
+    hold_hotkey: HotkeyConfig = HotkeyConfig::default_hold(),
+
+    /// Shortcut that pastes the last transcription again
+    #[restart_required = false]
+    paste_last_hotkey: HotkeyConfig = HotkeyConfig::default_paste_last(),
+
+    /// Hotkey that removes the last paste from the field it went into
+    #[restart_required = false]
+    undo_paste_hotkey: HotkeyConfig = HotkeyConfig::default_undo_paste(),
+
+    /// Hotkey that cleans up the copied text and puts the result back on the clipboard
+    #[restart_required = false]
+    clipboard_cleanup_hotkey: HotkeyConfig = HotkeyConfig::default_clipboard_cleanup(),
+
+    /// Hotkey that cancels the dictation in progress, aborting its server requests
+    #[restart_required = false]
+    cancel_hotkey: HotkeyConfig = HotkeyConfig::default_cancel(),
+
+    /// Register the clipboard cleanup hotkey
+    #[restart_required = false]
+    clipboard_cleanup_enabled: bool = false,
+
+    /// Microphone device ID, or the system default when unset
+    #[restart_required = false]
+    selected_mic_id: Option<String> = None,
+
+    /// Play sounds when recording starts and stops
+    #[restart_required = false]
+    sound_enabled: bool = true,
+
+    /// Custom cleanup prompt sections, or the server defaults when unset
+    #[restart_required = false]
+    cleanup_prompt_sections: Option<serde_json::Value> = None,
+
+    /// Speech-to-text provider ID, or the server default when unset
+    #[restart_required = false]
+    stt_provider: Option<String> = None,
+
+    /// Cleanup LLM provider ID, or the server default when unset
+    #[restart_required = false]
+    llm_provider: Option<String> = None,
+
+    /// Mute system audio while recording
+    #[restart_required = false]
+    auto_mute_audio: bool = false,
+
+    /// Seconds to wait for a transcription before giving up
+    #[restart_required = false]
+    stt_timeout_seconds: Option<f64> = None,
+
+    /// Names and terms transcription should recognize, spelled as they should appear
+    #[restart_required = false]
+    stt_vocabulary: Vec<String> = Vec::new(),
+
+    /// How dictated text is delivered to the focused application
+    #[restart_required = false]
+    paste_method: PasteMethod = PasteMethod::default(),
+
+    /// Show cleaned text in an editable window before pasting it
+    #[restart_required = false]
+    review_before_paste: bool = false,
+
+    /// Restore the previous clipboard contents after pasting
+    #[restart_required = false]
+    restore_clipboard: bool = true,
+
+    /// Insert dictated text at the cursor or replace the selected text
+    #[restart_required = false]
+    insert_mode: InsertMode = InsertMode::default(),
+
+    /// Put Markdown output on the clipboard as rich text (HTML) or plain text
+    #[restart_required = false]
+    paste_format: PasteFormat = PasteFormat::default(),
+
+    /// Add a leading space and fix the first letter's case to fit the text before the
+    /// cursor
+    #[restart_required = false]
+    smart_insertion: bool = false,
+
+    /// Ask for confirmation before pasting text longer than this many characters
+    #[restart_required = false]
+    confirm_paste_over_chars: Option<u32> = None,
+
+    /// Number of recent transcripts the paste-last hotkey cycles through
+    #[restart_required = false]
+    paste_ring_size: Option<u32> = None,
+
+    /// How the undo-paste hotkey removes the last paste
+    #[restart_required = false]
+    undo_paste_method: UndoPasteMethod = UndoPasteMethod::SelectBack,
+
+    /// Only copy results to the clipboard (with a notification), whatever the paste
+    /// method, e.g. while working over remote desktop
+    #[restart_required = false]
+    copy_only_mode: bool = false,
+
+    /// Hold text instead of pasting it when focus moved away from the window the
+    /// recording started in
+    #[restart_required = false]
+    verify_paste_target: bool = false,
+
+    /// Hold text instead of pasting it into a password field
+    #[restart_required = false]
+    refuse_secure_fields: bool = true,
+
+    /// With `verify_paste_target`, also hold text when no text field is focused
+    #[restart_required = false]
+    require_text_field_for_paste: bool = false,
+
+    /// Characters per second when typing text with key events, or as fast as possible
+    /// when unset
+    #[restart_required = false]
+    typing_chars_per_second: Option<u32> = None,
+
+    /// Random variation of the pause between typed characters, as a percentage of the
+    /// pause, so paced typing looks like a person typing
+    #[restart_required = false]
+    typing_jitter_percent: u32 = 0,
+
+    /// File each dictation is appended to, e.g. `~/notes/{date}.md`, or none
+    #[restart_required = false]
+    output_file_path: Option<String> = None,
+
+    /// Only append dictations to `output_file_path`, without pasting them
+    #[restart_required = false]
+    output_file_only: bool = false,
+
+    /// Obsidian vault folder that output profiles with an `obsidian_note` write to
+    #[restart_required = false]
+    obsidian_vault: Option<String> = None,
+
+    /// Tags in the frontmatter of notes created in the Obsidian vault
+    #[restart_required = false]
+    obsidian_tags: Vec<String> = crate::obsidian::default_tags(),
+
+    /// Commands that get each dictation, next to or instead of pasting it
+    #[restart_required = false]
+    output_sinks: Vec<ScriptSink> = Vec::new(),
+
+    /// Output overrides per target app, keyed by executable name (Windows) or app name
+    /// (macOS)
+    #[restart_required = false]
+    app_output_profiles: HashMap<String, AppOutputProfile> = HashMap::new(),
+
+    /// Launch the app when logging in
+    #[restart_required = false]
+    start_at_login: bool = false,
+
+    /// Start hidden in the tray instead of showing the main window
+    #[restart_required = false]
+    start_minimized: bool = false,
+
+    /// Hide the overlay and show dictation status in the tray icon and notifications
+    #[restart_required = true]
+    tray_only_mode: bool = false,
+
+    /// Usage analytics: off or local-only
+    #[restart_required = false]
+    analytics_mode: AnalyticsMode = AnalyticsMode::default(),
+
+    /// Days a dictation is kept in history, or forever when unset
+    #[restart_required = false]
+    history_retention_days: Option<u32> = None,
+
+    /// Most dictations kept in history (pinned ones are never removed), or no limit
+    /// when unset
+    #[restart_required = false]
+    history_max_entries: Option<u32> = None,
+
+    /// Most disk space history may use, in megabytes, or no limit when unset
+    #[restart_required = false]
+    history_max_disk_mb: Option<u32> = None,
+
+    /// Days a deleted history entry can be restored from the trash (30 when unset);
+    /// 0 removes deleted entries right away
+    #[restart_required = false]
+    history_trash_days: Option<u32> = None,
+
+    /// How similar (0 to 1) consecutive dictations must be to be listed as one in
+    /// history; 0 lists every dictation separately
+    #[restart_required = false]
+    history_duplicate_threshold: f64 = DEFAULT_DUPLICATE_THRESHOLD,
+
+    /// Keep each dictation's recording with its history entry to listen back to.
+    /// Recordings are stored unencrypted, even with history encryption on
+    #[restart_required = false]
+    history_keep_audio: bool = false,
+
+    /// Whether history is encrypted at rest, with a keychain key or a passphrase
+    #[restart_required = false]
+    history_encryption: HistoryEncryption = HistoryEncryption::Off,
+
+    /// Folder kept in sync between devices (Dropbox, Syncthing, ...) that history
+    /// is shared through, or none. Not used while history is encrypted
+    #[restart_required = false]
+    history_sync_folder: Option<String> = None,
+
+    /// Folder that each day's dictations are compiled into as a Markdown journal,
+    /// e.g. `~/journal`, or none
+    #[restart_required = false]
+    journal_folder: Option<String> = None,
+
+    /// Head each daily journal with a summary written by the server's LLM
+    #[restart_required = false]
+    journal_summarize: bool = false,
+
+    /// Serve a local HTTP API that automation tools (Raycast, AutoHotkey, Stream Deck)
+    /// use to start and stop recording, read the last transcript and clean up text
+    #[restart_required = true]
+    automation_api_enabled: bool = false,
+
+    /// Port of the automation API on 127.0.0.1 (8766 when unset)
+    #[restart_required = true]
+    automation_api_port: Option<u16> = None,
+
+    /// Token automation requests send as `Authorization: Bearer <token>`, generated
+    /// when the API first starts
+    #[restart_required = false]
+    automation_api_token: Option<String> = None,
+
+    /// ICS calendar (`https://` or `webcal://` link, or file) whose meetings prompt to
+    /// begin meeting transcription, or none
+    #[restart_required = false]
+    calendar_ics_url: Option<String> = None,
+
+    /// Minutes before a calendar meeting starts that the prompt appears (1 when unset)
+    #[restart_required = false]
+    meeting_prompt_minutes: Option<u32> = None,
+
+    /// Serve a WebSocket on 127.0.0.1 that Stream Deck buttons use to control
+    /// dictation and show its state, authorized with `automation_api_token`
+    #[restart_required = true]
+    stream_deck_enabled: bool = false,
+
+    /// Port of the Stream Deck socket on 127.0.0.1 (8767 when unset)
+    #[restart_required = true]
+    stream_deck_port: Option<u16> = None,
+
+    /// Serve a read-only WebSocket on 127.0.0.1 streaming dictation state and partial
+    /// transcripts to dashboards and OBS overlays, authorized with `automation_api_token`
+    #[restart_required = true]
+    event_stream_enabled: bool = false,
+
+    /// Port of the event stream on 127.0.0.1 (8768 when unset)
+    #[restart_required = true]
+    event_stream_port: Option<u16> = None,
+
+    /// MQTT broker that recording state and dictation counts are published to, e.g.
+    /// "mqtt://homeassistant.local:1883"
+    #[restart_required = true]
+    mqtt_broker_url: Option<String> = None,
+
+    /// User name for the MQTT broker
+    #[restart_required = true]
+    mqtt_username: Option<String> = None,
+
+    /// Password for the MQTT broker
+    #[restart_required = true]
+    mqtt_password: Option<String> = None,
+
+    /// Prefix of the published MQTT topics ("tambourine" when unset)
+    #[restart_required = true]
+    mqtt_topic_prefix: Option<String> = None,
+
+    /// Announce the published topics as sensors to Home Assistant
+    #[restart_required = true]
+    mqtt_home_assistant_discovery: bool = true,
+
+    /// Let the companion browser extension insert dictations into web pages through
+    /// a socket on 127.0.0.1, authorized with `automation_api_token`
+    #[restart_required = true]
+    browser_extension_enabled: bool = false,
+
+    /// Port of the browser extension socket on 127.0.0.1 (8769 when unset)
+    #[restart_required = true]
+    browser_extension_port: Option<u16> = None,
+
+    /// IDs of the browser extensions allowed to use the native messaging host
+    #[restart_required = false]
+    browser_extension_ids: Vec<String> = Vec::new(),
+
+    /// Show the transcript of a running dictation as live captions in OBS
+    #[restart_required = true]
+    obs_captions_enabled: bool = false,
+
+    /// Address of OBS's WebSocket server ("ws://127.0.0.1:4455" when unset)
+    #[restart_required = true]
+    obs_websocket_url: Option<String> = None,
+
+    /// Password of OBS's WebSocket server, if it has one
+    #[restart_required = true]
+    obs_websocket_password: Option<String> = None,
+
+    /// Name of the OBS text source showing captions ("Tambourine Captions" when unset)
+    #[restart_required = true]
+    obs_caption_source: Option<String> = None,
+
+    /// Seconds without new words before the caption is cleared (5 when unset)
+    #[restart_required = true]
+    obs_caption_clear_seconds: Option<u64> = None,
+
+    /// Also send captions as the stream's closed captions
+    #[restart_required = true]
+    obs_stream_captions: bool = false,
+
+    /// Serve a push-to-talk page for phones on the local network, paired with a code
+    #[restart_required = true]
+    remote_control_enabled: bool = false,
+
+    /// Port of the phone remote on the local network (8770 when unset)
+    #[restart_required = true]
+    remote_control_port: Option<u16> = None,
+
+    /// URLs each finished dictation is posted to, optionally with a templated body
+    #[restart_required = false]
+    webhooks: Vec<Webhook> = Vec::new(),
+
+    /// Typing speed in words per minute that usage statistics compare dictation with
+    #[restart_required = false]
+    typing_speed_wpm: Option<u32> = None,
+
+    /// Minutes of audio that may be dictated per day, or unlimited when unset
+    #[restart_required = false]
+    daily_audio_minutes_limit: Option<f64> = None,
+
+    /// Cloud transcription requests allowed per day, or unlimited when unset
+    #[restart_required = false]
+    daily_cloud_request_limit: Option<u32> = None,
+
+    /// Longest single recording the speech-to-text provider accepts, shown as a countdown
+    /// in the overlay, or no limit when unset
+    #[restart_required = false]
+    max_recording_seconds: Option<u32> = None,
+
+    /// Number, date and unit conventions for the cleanup prompt, or unspecified when unset
+    #[restart_required = false]
+    locale_preferences: Option<serde_json::Value> = None,
+
+    /// Monitor the overlay appears on: follow focus, primary or a fixed monitor
+    #[restart_required = false]
+    overlay_monitor: OverlayMonitor = OverlayMonitor::default(),
+
+    /// Screen position the overlay is anchored to: free, an edge or corner, or near the
+    /// cursor or caret
+    #[restart_required = false]
+    overlay_anchor: OverlayAnchor = OverlayAnchor::default(),
+
+    /// Overlay centers dragged to by the user, keyed by monitor name
+    #[restart_required = false]
+    overlay_positions: HashMap<String, OverlayPoint> = HashMap::new(),
+
+    /// Let mouse clicks pass through the overlay to the window underneath
+    #[restart_required = false]
+    overlay_click_through: bool = false,
+
+    /// Seconds the overlay stays up after a dictation before hiding, or always visible
+    /// when unset
+    #[restart_required = false]
+    overlay_auto_hide_seconds: Option<u32> = None,
+
+    /// Leave the overlay and edge indicator out of screenshots and screen shares
+    #[restart_required = false]
+    exclude_overlay_from_capture: bool = false,
+
+    /// Overlay accent color, background opacity, size and reduced motion
+    #[restart_required = false]
+    overlay_appearance: OverlayAppearance = OverlayAppearance::default(),
+
+    /// Compact pill or expanded panel with the live transcript
+    #[restart_required = false]
+    overlay_layout: OverlayLayout = OverlayLayout::default(),
+
+    /// Thin colored bar along a screen edge showing the dictation state, or off
+    #[restart_required = false]
+    edge_indicator: EdgeIndicator = EdgeIndicator::default(),
+
+    /// Keep recording but hide the overlay and mute sounds while a fullscreen app is in front
+    #[restart_required = false]
+    suppress_over_fullscreen: bool = false,
+
+    /// OS notifications for transcription results, errors and quota warnings
+    #[restart_required = false]
+    notifications: NotificationSettings = NotificationSettings::default(),
+
+    /// Log verbosity: error, warn, info, debug or trace
+    #[restart_required = false]
+    log_level: LogLevel = LogLevel::default(),
+}
+
+impl AppSettings {
+    /// Keys of settings that only take effect after the app restarts
+    pub fn restart_required_keys() -> Vec<&'static str> {
+        Self::describe()
+            .into_iter()
+            .filter(|descriptor| descriptor.restart_required)
+            .map(|descriptor| descriptor.key)
+            .collect()
+    }
+
+    /// Restart-required settings whose value differs between `baseline` (the
+    /// values at launch) and `current`. Missing keys count as their default.
+    pub fn pending_restart_changes(
+        baseline: &serde_json::Map<String, serde_json::Value>,
+        current: &serde_json::Map<String, serde_json::Value>,
+    ) -> Vec<String> {
+        let defaults = Self::default_values();
+        let value_or_default = |entries: &serde_json::Map<String, serde_json::Value>, key: &str| {
+            entries
+                .get(key)
+                .or_else(|| defaults.get(key))
+                .cloned()
+                .unwrap_or_default()
+        };
+        Self::restart_required_keys()
+            .into_iter()
+            .filter(|key| value_or_default(baseline, key) != value_or_default(current, key))
+            .map(String::from)
+            .collect()
+    }
+
+    /// Default values keyed by settings store key
+    pub fn default_values() -> serde_json::Map<String, serde_json::Value> {
+        match json_value(Self::default()) {
+            serde_json::Value::Object(map) => map,
+            _ => serde_json::Map::new(),
+        }
+    }
+}
+
+/// Sections of the settings that can be reset to defaults independently
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsScope {
+    Hotkeys,
+    Audio,
+    Providers,
+    Prompts,
+    Output,
+    All,
+}
+
+impl SettingsScope {
+    /// Settings store keys belonging to this scope
+    pub fn keys(self) -> Vec<&'static str> {
+        match self {
+            Self::Hotkeys => vec![
+                "toggle_hotkey",
+                "hold_hotkey",
+                "paste_last_hotkey",
+                "undo_paste_hotkey",
+                "clipboard_cleanup_hotkey",
+                "cancel_hotkey",
+            ],
+            Self::Audio => vec!["selected_mic_id", "sound_enabled", "auto_mute_audio"],
+            Self::Providers => vec!["stt_provider", "llm_provider", "stt_timeout_seconds"],
+            Self::Prompts => vec!["cleanup_prompt_sections", "locale_preferences"],
+            Self::Output => vec![
+                "paste_method",
+                "copy_only_mode",
+                "restore_clipboard",
+                "insert_mode",
+                "smart_insertion",
+                "paste_format",
+                "review_before_paste",
+                "verify_paste_target",
+                "require_text_field_for_paste",
+                "refuse_secure_fields",
+                "confirm_paste_over_chars",
+                "typing_chars_per_second",
+                "typing_jitter_percent",
+                "output_file_path",
+                "output_file_only",
+                "obsidian_vault",
+                "obsidian_tags",
+                "output_sinks",
+                "app_output_profiles",
+                "paste_ring_size",
+                "undo_paste_method",
+            ],
+            Self::All => AppSettings::KEYS.to_vec(),
+        }
+    }
+
+    /// Store keys and their default values for this scope
+    pub fn default_entries(self) -> Vec<(&'static str, serde_json::Value)> {
+        let defaults = AppSettings::default_values();
+        self.keys()
+            .into_iter()
+            .map(|key| {
+                let value = defaults.get(key).cloned().unwrap_or_default();
+                (key, value)
+            })
+            .collect()
+    }
+}
+
+fn json_value<T: Serialize>(value: T) -> serde_json::Value {
+    serde_json::to_value(value).expect("Default settings must serialize")
+}
+
+// ============================================================================
+// SETTINGS PERSISTENCE
+// ============================================================================
+
+/// Name of the settings store file (relative to the app data directory)
+pub const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Name of the machine-local settings file (relative to the app cache directory, the
+/// one app directory that is never roamed or synced on any platform)
+pub const MACHINE_SETTINGS_FILE_NAME: &str = "machine-settings.json";
+
+/// Settings keys that describe this machine (devices, window placement) and must not
+/// roam with the rest of the settings when the config folder is synced between machines
+pub const MACHINE_LOCAL_KEYS: &[&str] = &["selected_mic_id", "onboarding", "overlay_positions"];
+
+/// Read a setting from the settings store. `Ok(None)` when it isn't set; an error
+/// when the store can't be opened or the stored value has the wrong shape.
+pub fn try_read_setting<T: DeserializeOwned>(
+    app: &AppHandle,
+    key: &str,
+) -> Result<Option<T>, String> {
+    let store = app
+        .store(SETTINGS_FILE_NAME)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store
+        .get(key)
+        .map(|value| {
+            serde_json::from_value(value)
+                .map_err(|e| format!("Stored setting {} is invalid: {}", key, e))
+        })
+        .transpose()
+}
+
+/// Read a setting, or `default` when it isn't set. Unreadable values are logged and
+/// read as `default`.
+pub fn read_setting_or<T: DeserializeOwned>(app: &AppHandle, key: &str, default: T) -> T {
+    match try_read_setting(app, key) {
+        Ok(value) => value.unwrap_or(default),
+        Err(e) => {
+            log::warn!("{}, using the default", e);
+            default
+        }
+    }
+}
+
+/// Read a setting, or its type's default when it isn't set or can't be read
+pub fn read_setting<T: DeserializeOwned + Default>(app: &AppHandle, key: &str) -> T {
+    read_setting_or(app, key, T::default())
+}
+
+/// Directory (inside the app data directory) holding settings backups
+const SETTINGS_BACKUP_DIR: &str = "settings-backups";
+
+/// Number of settings backups to keep
+const MAX_SETTINGS_BACKUPS: usize = 5;
+
+/// Write a file atomically by writing to a temporary sibling and renaming it into place
+pub fn write_file_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let mut file = fs::File::create(&tmp_path)
+        .map_err(|e| format!("Failed to create temp file {:?}: {}", tmp_path, e))?;
+    file.write_all(contents)
+        .map_err(|e| format!("Failed to write temp file {:?}: {}", tmp_path, e))?;
+    file.sync_all()
+        .map_err(|e| format!("Failed to sync temp file {:?}: {}", tmp_path, e))?;
+    drop(file);
+
+    fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("Failed to replace {:?}: {}", path, e)
+    })
+}
+
+/// Name of the environment variable that overrides the admin policy file location.
+/// Only honored in debug builds, so users can't swap the admin's policy for their own.
+#[cfg(debug_assertions)]
+pub const POLICY_PATH_ENV_VAR: &str = "TAMBOURINE_POLICY_PATH";
+
+/// Default system-wide location of the admin policy file
+pub fn default_policy_path() -> PathBuf {
+    #[cfg(debug_assertions)]
+    if let Ok(path) = std::env::var(POLICY_PATH_ENV_VAR) {
+        return PathBuf::from(path);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let program_data =
+            std::env::var("ProgramData").unwrap_or_else(|_| "C:\\ProgramData".to_string());
+        PathBuf::from(program_data)
+            .join("Tambourine")
+            .join("policy.json")
+    }
+    #[cfg(target_os = "macos")]
+    {
+        PathBuf::from("/Library/Application Support/Tambourine/policy.json")
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        PathBuf::from("/etc/tambourine/policy.json")
+    }
+}
+
+/// Admin-managed policy that pins settings to fixed values for enterprise deployments.
+///
+/// Example policy file forcing local-only providers:
+/// `{ "locked": { "stt_provider": "whisper", "llm_provider": "ollama" } }`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SettingsPolicy {
+    /// Settings keys and the values they are locked to
+    #[serde(default)]
+    pub locked: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SettingsPolicy {
+    /// Load a policy file. A missing file means no policy; an invalid one is ignored with a warning.
+    pub fn load(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<Self>(&content) {
+            Ok(policy) => {
+                log::info!(
+                    "Loaded settings policy from {:?} ({} locked settings)",
+                    path,
+                    policy.locked.len()
+                );
+                policy
+            }
+            Err(e) => {
+                log::warn!("Ignoring invalid settings policy {:?}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Locked keys whose value in `entries` differs from the policy (sorted)
+    pub fn violations(&self, entries: &serde_json::Map<String, serde_json::Value>) -> Vec<String> {
+        self.locked
+            .iter()
+            .filter(|(key, locked_value)| entries.get(*key) != Some(*locked_value))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+}
+
+/// Manages persistence of the settings file: atomic writes, rolling backups
+/// and recovery from corruption on startup.
+///
+/// Settings are held in a single store at runtime but split on disk: roaming settings
+/// live in the app data directory, machine-local ones in the app cache directory.
+pub struct SettingsManager {
+    file_path: PathBuf,
+    backup_dir: PathBuf,
+    machine_file_path: PathBuf,
+    policy: SettingsPolicy,
+    /// Last settings file contents written or loaded by the app, used to tell our own
+    /// writes apart from external edits
+    known_content: Mutex<Option<String>>,
+}
+
+impl SettingsManager {
+    /// Create a new settings manager with the given app data and cache directories
+    pub fn new(app_data_dir: PathBuf, app_cache_dir: PathBuf) -> Self {
+        let _ = fs::create_dir_all(&app_data_dir);
+        let _ = fs::create_dir_all(&app_cache_dir);
+        Self {
+            file_path: app_data_dir.join(SETTINGS_FILE_NAME),
+            backup_dir: app_data_dir.join(SETTINGS_BACKUP_DIR),
+            machine_file_path: app_cache_dir.join(MACHINE_SETTINGS_FILE_NAME),
+            policy: SettingsPolicy::default(),
+            known_content: Mutex::new(None),
+        }
+    }
+
+    /// Apply an admin policy whose locked settings cannot be changed
+    pub fn with_policy(mut self, policy: SettingsPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The admin policy in effect
+    pub fn policy(&self) -> &SettingsPolicy {
+        &self.policy
+    }
+
+    /// Whether a settings key is stored per machine rather than roaming
+    pub fn is_machine_local(key: &str) -> bool {
+        MACHINE_LOCAL_KEYS.contains(&key)
+    }
+
+    /// Load machine-local settings. Returns an empty map if the file is missing or invalid.
+    pub fn load_machine_entries(&self) -> serde_json::Map<String, serde_json::Value> {
+        let Ok(content) = fs::read_to_string(&self.machine_file_path) else {
+            return serde_json::Map::new();
+        };
+        Self::parse_settings(&content).unwrap_or_else(|| {
+            log::warn!("Machine settings file is invalid, using defaults");
+            serde_json::Map::new()
+        })
+    }
+
+    /// Parse settings file contents, accepting only a JSON object
+    pub(crate) fn parse_settings(
+        content: &str,
+    ) -> Option<serde_json::Map<String, serde_json::Value>> {
+        match serde_json::from_str(content) {
+            Ok(serde_json::Value::Object(map)) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Backup files sorted newest first (file names embed a sortable timestamp)
+    fn backups_newest_first(&self) -> Vec<PathBuf> {
+        let mut backups: Vec<PathBuf> = fs::read_dir(&self.backup_dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        backups.sort();
+        backups.reverse();
+        backups
+    }
+
+    /// Validate the settings file and restore the newest valid backup if it is corrupted.
+    ///
+    /// Must be called before the settings store is first loaded. Returns the path of the
+    /// backup that was restored, or None if no recovery was needed (or possible).
+    /// A corrupted file is moved aside rather than deleted so it can be inspected.
+    pub fn recover_if_corrupted(&self) -> Result<Option<PathBuf>, String> {
+        let content = match fs::read_to_string(&self.file_path) {
+            Ok(content) => content,
+            // Missing file is fine - the store starts with defaults
+            Err(_) => return Ok(None),
+        };
+
+        if Self::parse_settings(&content).is_some() {
+            return Ok(None);
+        }
+
+        let corrupt_path = self.file_path.with_extension(format!(
+            "corrupt-{}.json",
+            Utc::now().format("%Y%m%dT%H%M%S")
+        ));
+        fs::rename(&self.file_path, &corrupt_path)
+            .map_err(|e| format!("Failed to move corrupted settings aside: {}", e))?;
+        log::warn!(
+            "Settings file was corrupted, moved to {:?}",
+            corrupt_path.file_name().unwrap_or_default()
+        );
+
+        for backup in self.backups_newest_first() {
+            let Ok(backup_content) = fs::read_to_string(&backup) else {
+                continue;
+            };
+            if Self::parse_settings(&backup_content).is_none() {
+                log::warn!("Skipping invalid settings backup {:?}", backup);
+                continue;
+            }
+            write_file_atomic(&self.file_path, backup_content.as_bytes())?;
+            log::warn!("Recovered settings from backup {:?}", backup);
+            return Ok(Some(backup));
+        }
+
+        log::warn!("No valid settings backup found, settings reset to defaults");
+        Ok(None)
+    }
+
+    /// Modification time of the roaming settings file, if it exists
+    pub fn modified_time(&self) -> Option<SystemTime> {
+        fs::metadata(&self.file_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Check the settings file for edits made outside the app.
+    ///
+    /// Returns the new settings if the file changed since the app last wrote or read it,
+    /// None if it is unchanged (the first call only records a baseline), or an error if
+    /// the edited file is not a valid settings object. Invalid edits are not recorded as
+    /// known, so they are reported again until fixed or overwritten.
+    pub fn read_external_change(
+        &self,
+    ) -> Result<Option<serde_json::Map<String, serde_json::Value>>, String> {
+        let content = fs::read_to_string(&self.file_path)
+            .map_err(|e| format!("Failed to read settings file: {}", e))?;
+
+        let mut known = self
+            .known_content
+            .lock()
+            .map_err(|e| format!("Failed to lock settings state: {}", e))?;
+
+        match known.as_deref() {
+            None => {
+                *known = Some(content);
+                return Ok(None);
+            }
+            Some(previous) if previous == content => return Ok(None),
+            Some(_) => {}
+        }
+
+        let entries = Self::parse_settings(&content)
+            .ok_or_else(|| "Settings file is not a valid JSON object".to_string())?;
+        *known = Some(content);
+        Ok(Some(entries))
+    }
+
+    /// Persist settings entries atomically and record a rolling backup.
+    /// Machine-local keys are split out into the machine settings file.
+    pub fn save_entries(
+        &self,
+        entries: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), String> {
+        let (machine, roaming): (serde_json::Map<_, _>, serde_json::Map<_, _>) = entries
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .partition(|(key, _)| Self::is_machine_local(key));
+
+        let machine_content = serde_json::to_string_pretty(&machine)
+            .map_err(|e| format!("Failed to serialize machine settings: {}", e))?;
+        write_file_atomic(&self.machine_file_path, machine_content.as_bytes())?;
+
+        let content = serde_json::to_string_pretty(&roaming)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+        write_file_atomic(&self.file_path, content.as_bytes())?;
+        if let Ok(mut known) = self.known_content.lock() {
+            *known = Some(content.clone());
+        }
+
+        if let Err(e) = self.write_backup(content.as_bytes()) {
+            // Backups are best-effort; the settings themselves were saved
+            log::warn!("Failed to back up settings: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Write a timestamped backup and prune old ones beyond MAX_SETTINGS_BACKUPS
+    fn write_backup(&self, contents: &[u8]) -> Result<(), String> {
+        fs::create_dir_all(&self.backup_dir)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+
+        let backup_path = self.backup_dir.join(format!(
+            "settings-{}.json",
+            Utc::now().format("%Y%m%dT%H%M%S%.3f")
+        ));
+        write_file_atomic(&backup_path, contents)?;
+
+        for stale in self
+            .backups_newest_first()
+            .iter()
+            .skip(MAX_SETTINGS_BACKUPS)
+        {
+            let _ = fs::remove_file(stale);
+        }
+        Ok(())
+    }
+}
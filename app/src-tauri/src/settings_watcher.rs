@@ -0,0 +1,84 @@
+//! Hot-reload of the settings file when it is edited outside the app.
+//!
+//! Polls the settings file's modification time, validates changed contents and
+//! applies them to the runtime store, so hand edits and folder sync take effect
+//! without a restart.
+
+use crate::commands::settings::apply_locked_settings;
+use crate::settings::{SettingsManager, SETTINGS_FILE_NAME};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+/// How often the settings file is checked for external modifications
+const SETTINGS_WATCH_INTERVAL_MS: u64 = 1000;
+
+/// Start watching the settings file on a background thread
+pub fn spawn(app: AppHandle) {
+    thread::spawn(move || {
+        let settings_manager = app.state::<SettingsManager>();
+        let mut last_modified = settings_manager.modified_time();
+
+        // Record the current contents as the baseline for change detection
+        let _ = settings_manager.read_external_change();
+
+        loop {
+            thread::sleep(Duration::from_millis(SETTINGS_WATCH_INTERVAL_MS));
+
+            let modified = settings_manager.modified_time();
+            if modified.is_none() || modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            match settings_manager.read_external_change() {
+                Ok(Some(entries)) => {
+                    if let Err(e) = reload_settings(&app, &settings_manager, entries) {
+                        log::error!("Failed to reload settings: {}", e);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::warn!("Ignoring external settings edit: {}", e);
+                    let _ = app.emit("settings-reload-failed", e);
+                }
+            }
+        }
+    });
+}
+
+/// Replace roaming settings in the store with the externally edited ones
+fn reload_settings(
+    app: &AppHandle,
+    settings_manager: &SettingsManager,
+    entries: serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_FILE_NAME)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+
+    // Machine-local settings live in a separate file and are left untouched
+    for (key, _) in store.entries() {
+        if !SettingsManager::is_machine_local(&key) && !entries.contains_key(&key) {
+            store.delete(&key);
+        }
+    }
+    for (key, value) in entries {
+        if !SettingsManager::is_machine_local(&key) {
+            store.set(key, value);
+        }
+    }
+    apply_locked_settings(&store, settings_manager.policy());
+
+    log::info!("Settings reloaded after external edit");
+
+    #[cfg(desktop)]
+    {
+        tauri::async_runtime::block_on(crate::commands::settings::register_shortcuts(app.clone()))?;
+    }
+
+    let _ = app.emit("settings-changed", ());
+    crate::tray_menu::refresh(app);
+    Ok(())
+}
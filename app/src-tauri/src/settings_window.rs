@@ -0,0 +1,40 @@
+//! Opening the settings at a specific section.
+//!
+//! Settings live in the main window. Opening them shows and focuses that window
+//! and sends `open-settings` with the section, so the frontend switches to the
+//! settings view and scrolls to it. Used by error messages that point the user
+//! at the setting to fix (e.g. an invalid API key under providers).
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Label of the main (settings) window
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+/// Event asking the main window to show a settings section
+pub const OPEN_SETTINGS_EVENT: &str = "open-settings";
+
+/// Section of the settings view that can be linked to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsSection {
+    Providers,
+    Audio,
+    Hotkeys,
+    Prompts,
+}
+
+/// Show and focus the main window on the settings view, at `section` if given
+pub fn open_settings(app: &AppHandle, section: Option<SettingsSection>) -> Result<(), String> {
+    let window = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .ok_or("Main window not found")?;
+    window
+        .unminimize()
+        .and_then(|_| window.show())
+        .and_then(|_| window.set_focus())
+        .map_err(|e| format!("Failed to show settings window: {}", e))?;
+    window
+        .emit(OPEN_SETTINGS_EVENT, section)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,152 @@
+//! Quitting and restarting without dropping a dictation mid-flight.
+//!
+//! Before the app exits, a recording in progress is stopped so it goes through
+//! as usual, and the app waits (up to [`DRAIN_TIMEOUT`]) for the dictation in
+//! flight to be pasted. One still unfinished by then is handed to the overlay to
+//! keep in the failed dictations, to retry after the next launch. Then whatever
+//! is left is cancelled, shortcuts are unregistered, system audio is unmuted and
+//! the overlay disconnects from the server, releasing the microphone.
+//!
+//! Draining runs on its own thread: pasting needs the main thread, which the
+//! tray menu and exit requests arrive on.
+
+use crate::audio_mute::AudioMuteManager;
+use crate::pipeline::DictationPipeline;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Longest wait for the dictation in flight to finish
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest wait for the overlay to keep an unfinished dictation
+const PERSIST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often the pipeline is checked while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Event asking the overlay to keep the dictation in flight as a failed dictation
+pub const DICTATION_INTERRUPTED_EVENT: &str = "dictation-interrupted";
+
+/// Whether the app is on its way out
+#[derive(Default)]
+pub struct Shutdown {
+    started: AtomicBool,
+    drained: AtomicBool,
+}
+
+impl Shutdown {
+    /// Claim the shutdown; false when it already started
+    pub fn begin(&self) -> bool {
+        !self.started.swap(true, Ordering::SeqCst)
+    }
+
+    /// Whether in-flight work was dealt with, so the app can exit right away
+    pub fn is_drained(&self) -> bool {
+        self.drained.load(Ordering::SeqCst)
+    }
+
+    fn mark_drained(&self) {
+        self.drained.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Wait until `is_busy` turns false or `timeout` passes; true when it did
+pub fn wait_until_idle(timeout: Duration, is_busy: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    while is_busy() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    true
+}
+
+/// Let the dictation in flight finish, or have the overlay keep it to retry
+fn finish_dictation(app: &AppHandle, pipeline: &DictationPipeline) {
+    let busy = || pipeline.stage().is_some();
+
+    // Let a recording in progress go through instead of losing it
+    #[cfg(desktop)]
+    crate::set_recording(app, false, "Shutdown");
+
+    if !busy() {
+        return;
+    }
+    log::info!("Shutdown: waiting for the dictation in flight");
+    if wait_until_idle(DRAIN_TIMEOUT, busy) {
+        return;
+    }
+    log::warn!("Shutdown: dictation unfinished, keeping it to retry");
+    let _ = app.emit(DICTATION_INTERRUPTED_EVENT, ());
+    if !wait_until_idle(PERSIST_TIMEOUT, busy) {
+        log::warn!("Shutdown: the overlay didn't keep the dictation in time");
+    }
+}
+
+/// Finish or keep the dictation in flight, then release what the app holds
+fn drain(app: &AppHandle) {
+    if let Some(pipeline) = app.try_state::<DictationPipeline>() {
+        finish_dictation(app, &pipeline);
+        pipeline.shut_down();
+    }
+
+    #[cfg(desktop)]
+    {
+        use tauri_plugin_global_shortcut::GlobalShortcutExt;
+        if let Err(e) = app.global_shortcut().unregister_all() {
+            log::warn!("Failed to unregister shortcuts: {}", e);
+        }
+    }
+    if let Some(manager) = app.try_state::<AudioMuteManager>() {
+        if let Err(e) = manager.unmute() {
+            log::warn!("Failed to unmute audio: {}", e);
+        }
+    }
+    crate::disconnect_overlay(app);
+}
+
+/// Drain in-flight work on a background thread, then run `exit`. Does nothing
+/// when a shutdown is already under way.
+fn shut_down(app: &AppHandle, exit: impl FnOnce(&AppHandle) + Send + 'static) {
+    let Some(shutdown) = app.try_state::<Shutdown>() else {
+        exit(app);
+        return;
+    };
+    if !shutdown.begin() {
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || {
+        drain(&app);
+        app.state::<Shutdown>().mark_drained();
+        exit(&app);
+    });
+}
+
+/// Quit once in-flight work is finished or kept
+pub fn quit(app: &AppHandle, code: i32) {
+    log::info!("Quitting");
+    shut_down(app, move |app| app.exit(code));
+}
+
+/// Relaunch once in-flight work is finished or kept
+pub fn restart(app: &AppHandle) {
+    log::info!("Restarting app");
+    shut_down(app, |app| app.restart());
+}
+
+/// Hold back an exit requested by the OS (e.g. Cmd+Q or logging out) until
+/// in-flight work is dealt with
+pub fn on_exit_requested(app: &AppHandle, code: Option<i32>, api: &tauri::ExitRequestApi) {
+    if app
+        .try_state::<Shutdown>()
+        .is_some_and(|shutdown| shutdown.is_drained())
+    {
+        return;
+    }
+    api.prevent_exit();
+    quit(app, code.unwrap_or(0));
+}
@@ -0,0 +1,64 @@
+//! Keeping to one running app.
+//!
+//! A second launch hands its arguments to the app already running and exits
+//! (through `tauri-plugin-single-instance`, registered first in [`crate::run`]).
+//! `tambourine://` links among them go to [`crate::deep_link`]; files are queued
+//! for transcription, as when they're passed to the first launch ("Open with" or
+//! `tambourine recording.m4a`). Unless the launch only opened a link, the main
+//! window is brought forward so the user sees the app they launched.
+
+use crate::deep_link::DEEP_LINK_SCHEME;
+use crate::file_transcription;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Files among a launch's arguments, resolved against its working directory.
+/// Skips the executable, flags, links and paths that aren't files.
+pub fn files_in_args(args: &[String], cwd: &str) -> Vec<String> {
+    args.iter()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-') && !is_link(arg))
+        .map(|arg| Path::new(cwd).join(arg))
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Whether an argument is a `tambourine://` link
+pub fn is_link(arg: &str) -> bool {
+    arg.starts_with(&format!("{}:", DEEP_LINK_SCHEME))
+}
+
+/// Queue the files a launch was given for transcription
+fn transcribe_files(app: &AppHandle, files: Vec<String>) {
+    if files.is_empty() {
+        return;
+    }
+    log::info!("Transcribing {} file(s) from the command line", files.len());
+    if let Err(e) = file_transcription::queue_files(app, files, true) {
+        log::warn!("{}", e);
+    }
+}
+
+/// Take over what a second launch was asked to do
+pub fn on_second_instance(app: &AppHandle, args: Vec<String>, cwd: String) {
+    log::info!(
+        "Another launch forwarded {:?}",
+        args.get(1..).unwrap_or_default()
+    );
+    transcribe_files(app, files_in_args(&args, &cwd));
+    // Links are handed to the deep link handler by the plugin itself
+    let opens_link = args.iter().any(|arg| is_link(arg));
+    if !opens_link {
+        crate::tray_menu::show_main_window(app);
+    }
+}
+
+/// Queue the files the app itself was launched with
+pub fn on_launch(app: &AppHandle) {
+    let args: Vec<String> = std::env::args().collect();
+    let cwd = std::env::current_dir()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    transcribe_files(app, files_in_args(&args, &cwd));
+}
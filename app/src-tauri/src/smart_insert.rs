@@ -0,0 +1,78 @@
+//! Fitting dictated text to the text around the cursor.
+//!
+//! Cleaned transcripts read as standalone sentences: capitalized, without a
+//! leading space. With the `smart_insertion` setting, the characters before the
+//! cursor decide whether the text needs a separating space and whether its
+//! first word should be capitalized (start of a field or sentence) or lowered
+//! (middle of a sentence). Lowering can't tell a name from an ordinary word, so
+//! a name starting the text is lowered too; "I" and acronyms are kept. When the
+//! context can't be read the text is left as it is.
+
+/// Characters before the cursor read to decide spacing and capitalization
+pub const CONTEXT_CHARS: usize = 8;
+
+/// Adjust `text` for insertion after `before`, the text preceding the cursor
+pub fn fit_to_context(text: &str, before: &str) -> String {
+    let text = text.trim_start();
+    let Some(first) = text.chars().next() else {
+        return String::new();
+    };
+
+    let preceding = before.trim_end_matches([' ', '\t']);
+    let last = preceding.chars().last();
+    let sentence_start = match last {
+        None => true,
+        Some(c) => matches!(c, '.' | '!' | '?' | '\n' | '\r'),
+    };
+
+    let body = if sentence_start {
+        capitalize_first(text)
+    } else {
+        lower_first_word(text)
+    };
+
+    // A space is needed after a word or punctuation, unless one is already there
+    // or the text starts with closing punctuation
+    let needs_space = !before.is_empty()
+        && !before.ends_with(char::is_whitespace)
+        && !opens_group(last)
+        && !matches!(first, '.' | ',' | ';' | ':' | '!' | '?' | ')' | ']' | '}');
+    if needs_space {
+        format!(" {}", body)
+    } else {
+        body
+    }
+}
+
+/// Whether a character opens a bracket, quote or similar that text follows directly
+fn opens_group(c: Option<char>) -> bool {
+    matches!(
+        c,
+        Some('(' | '[' | '{' | '"' | '\'' | '“' | '‘' | '/' | '-' | '@' | '#')
+    )
+}
+
+fn capitalize_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Lowercase the first letter unless the word looks like a name, acronym or "I"
+fn lower_first_word(text: &str) -> String {
+    let word: String = text.chars().take_while(|c| c.is_alphanumeric()).collect();
+    let keep = word.is_empty()
+        || word == "I"
+        || text.starts_with("I'")
+        || word.chars().skip(1).any(char::is_uppercase);
+    if keep {
+        return text.to_string();
+    }
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
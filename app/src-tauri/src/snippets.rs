@@ -0,0 +1,204 @@
+//! Snippet library: named pieces of text to paste again later.
+//!
+//! A snippet is usually promoted from a cleaned transcript in history, but is
+//! stored apart from it in `snippets.json` in the app data directory, so pruning
+//! or clearing history never touches it. Snippets are pasted from the tray's
+//! Insert Snippet menu, from the snippet list, or by saying "insert <name>" (for
+//! example "insert my email signature") as a whole dictation.
+
+use crate::error::AppError;
+use crate::history_duplicates::similarity;
+use crate::settings::write_file_atomic;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// How alike a spoken name must be to a snippet's name to insert it
+pub const VOICE_MATCH_THRESHOLD: f64 = 0.85;
+
+/// Words that start a spoken snippet command
+const VOICE_VERBS: &[&str] = &["insert", "paste"];
+
+/// Words after the verb that aren't part of the name
+const VOICE_FILLERS: &[&str] = &["my", "the", "our"];
+
+/// A named piece of text
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Snippet {
+    pub id: String,
+    pub name: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+    /// History entry the snippet was promoted from
+    #[serde(default)]
+    pub source_entry_id: Option<String>,
+}
+
+/// Lowercase words without punctuation
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// The snippet name in a dictation like "Insert my email signature.", if the
+/// dictation is a snippet command
+pub fn spoken_snippet_name(text: &str) -> Option<String> {
+    let words = words(text);
+    let (verb, rest) = words.split_first()?;
+    if !VOICE_VERBS.contains(&verb.as_str()) {
+        return None;
+    }
+    let rest = match rest.split_first() {
+        Some((first, tail)) if VOICE_FILLERS.contains(&first.as_str()) => tail,
+        _ => rest,
+    };
+    let rest = rest.strip_suffix(&["snippet".to_string()]).unwrap_or(rest);
+    (!rest.is_empty()).then(|| rest.join(" "))
+}
+
+/// The snippet a dictation asks to insert, matching names loosely
+pub fn match_voice_command<'a>(text: &str, snippets: &'a [Snippet]) -> Option<&'a Snippet> {
+    let spoken = spoken_snippet_name(text)?;
+    snippets
+        .iter()
+        .map(|snippet| {
+            let name = words(&snippet.name).join(" ");
+            let name = name
+                .split_once(' ')
+                .filter(|(first, _)| VOICE_FILLERS.contains(first))
+                .map_or(name.as_str(), |(_, rest)| rest)
+                .to_string();
+            (snippet, similarity(&spoken, &name))
+        })
+        .filter(|(_, score)| *score >= VOICE_MATCH_THRESHOLD)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(snippet, _)| snippet)
+}
+
+fn validate(name: &str, text: &str) -> Result<(String, String), AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput("A snippet needs a name".to_string()));
+    }
+    if text.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "A snippet needs some text".to_string(),
+        ));
+    }
+    Ok((name.to_string(), text.to_string()))
+}
+
+/// Snippets kept in `snippets.json`
+pub struct SnippetLibrary {
+    snippets: Mutex<Vec<Snippet>>,
+    file_path: Option<PathBuf>,
+}
+
+impl SnippetLibrary {
+    /// Load the library from the given app data directory
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        let file_path = app_data_dir.join("snippets.json");
+        let snippets = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            snippets: Mutex::new(snippets),
+            file_path: Some(file_path),
+        }
+    }
+
+    /// A library that isn't saved anywhere, for tests
+    pub fn in_memory() -> Self {
+        Self {
+            snippets: Mutex::new(Vec::new()),
+            file_path: None,
+        }
+    }
+
+    fn update<T>(&self, change: impl FnOnce(&mut Vec<Snippet>) -> T) -> Result<T, AppError> {
+        let mut snippets = self
+            .snippets
+            .lock()
+            .map_err(|e| AppError::Failed(format!("Failed to lock snippets: {}", e)))?;
+        let result = change(&mut snippets);
+        if let Some(file_path) = &self.file_path {
+            let content = serde_json::to_string_pretty(&*snippets)
+                .map_err(|e| AppError::Storage(format!("Failed to serialize snippets: {}", e)))?;
+            write_file_atomic(file_path, content.as_bytes()).map_err(AppError::Storage)?;
+        }
+        Ok(result)
+    }
+
+    /// All snippets, sorted by name
+    pub fn list(&self) -> Result<Vec<Snippet>, AppError> {
+        let mut snippets = self
+            .snippets
+            .lock()
+            .map_err(|e| AppError::Failed(format!("Failed to lock snippets: {}", e)))?
+            .clone();
+        snippets.sort_by_key(|snippet| snippet.name.to_lowercase());
+        Ok(snippets)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<Snippet>, AppError> {
+        Ok(self.list()?.into_iter().find(|snippet| snippet.id == id))
+    }
+
+    /// Add a snippet, optionally noting the history entry it came from
+    pub fn add(
+        &self,
+        name: &str,
+        text: &str,
+        source_entry_id: Option<String>,
+    ) -> Result<Snippet, AppError> {
+        let (name, text) = validate(name, text)?;
+        let snippet = Snippet {
+            id: Uuid::new_v4().to_string(),
+            name,
+            text,
+            created_at: Utc::now(),
+            source_entry_id,
+        };
+        self.update(|snippets| snippets.push(snippet.clone()))?;
+        Ok(snippet)
+    }
+
+    /// Rename a snippet or change its text, returning false when there is no such
+    /// snippet
+    pub fn edit(&self, id: &str, name: &str, text: &str) -> Result<bool, AppError> {
+        let (name, text) = validate(name, text)?;
+        self.update(|snippets| {
+            let Some(snippet) = snippets.iter_mut().find(|snippet| snippet.id == id) else {
+                return false;
+            };
+            snippet.name = name;
+            snippet.text = text;
+            true
+        })
+    }
+
+    pub fn delete(&self, id: &str) -> Result<bool, AppError> {
+        self.update(|snippets| {
+            let before = snippets.len();
+            snippets.retain(|snippet| snippet.id != id);
+            snippets.len() < before
+        })
+    }
+
+    /// The snippet a dictation asks to insert, if it is a snippet command
+    pub fn match_voice_command(&self, text: &str) -> Result<Option<Snippet>, AppError> {
+        let snippets = self.list()?;
+        Ok(match_voice_command(text, &snippets).cloned())
+    }
+}
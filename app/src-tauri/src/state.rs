@@ -1,13 +1,40 @@
+use crate::paste_target::PasteTarget;
+use crate::paste_undo::LastPaste;
+use crate::recording_state::RecordingLifecycle;
 use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
 
 #[derive(Default)]
 pub struct AppState {
-    /// Tracks if currently recording (for both toggle and hold modes)
-    pub is_recording: AtomicBool,
-    /// Tracks if PTT key is currently held down (for hold-to-record mode)
-    pub ptt_key_held: AtomicBool,
+    /// Where the app is in a dictation (recording, transcribing, pasting)
+    pub lifecycle: RecordingLifecycle,
     /// Tracks if paste-last key is currently held down
     pub paste_key_held: AtomicBool,
-    /// Tracks if toggle key is currently held down (for debouncing - action happens on release)
-    pub toggle_key_held: AtomicBool,
+    /// Tracks if undo-paste key is currently held down
+    pub undo_key_held: AtomicBool,
+    /// Tracks if clipboard-cleanup key is currently held down
+    pub clipboard_cleanup_key_held: AtomicBool,
+    /// A clipboard cleanup is waiting on the LLM
+    pub clipboard_cleanup_running: AtomicBool,
+    /// What the last clipboard cleanup copied, so it isn't cleaned up twice
+    pub last_clipboard_cleanup: Mutex<Option<String>>,
+    /// Overlay and sounds are held back because the recording started over a fullscreen app
+    pub feedback_suppressed: AtomicBool,
+    /// The current dictation is only copied, not pasted (asked for by the automation API)
+    pub copy_only_dictation: AtomicBool,
+    /// Output profile the current dictation is delivered with instead of the focused
+    /// app's (asked for by a `tambourine://` link)
+    pub dictation_profile: Mutex<Option<String>>,
+    /// Cleaned text waiting in the review window to be pasted or discarded
+    pub pending_paste: Mutex<Option<String>>,
+    /// Window that had focus when the current recording started
+    pub paste_target: Mutex<Option<PasteTarget>>,
+    /// Text held back because focus moved before it could be pasted
+    pub held_paste: Mutex<Option<String>>,
+    /// What the last paste inserted, for undoing it
+    pub last_paste: Mutex<Option<LastPaste>>,
+    /// Values of restart-required settings as they were when the app launched
+    pub restart_baseline: Mutex<serde_json::Map<String, serde_json::Value>>,
+    /// Backup the settings were restored from at launch, until the main window shows it
+    pub settings_recovered_from: Mutex<Option<String>>,
 }
@@ -0,0 +1,142 @@
+//! Usage statistics computed from dictation history.
+//!
+//! Everything is derived from the history database, so the numbers cover what
+//! history still holds (see `history_retention_days`) and work whatever the
+//! analytics setting. Time saved compares each dictation's recording length with
+//! typing the same words at `typing_speed_wpm`.
+
+use crate::history::HistoryEntry;
+use chrono::{Datelike, Days, NaiveDate, TimeZone};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Typing speed assumed when the setting is missing or zero
+pub const DEFAULT_TYPING_SPEED_WPM: u32 = 40;
+
+/// Dictation counts for one day or week
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PeriodCount {
+    /// First day of the period (Monday for weeks)
+    pub start: NaiveDate,
+    pub dictations: u64,
+    pub words: u64,
+}
+
+/// Delivery latency for one provider combination
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ProviderLatency {
+    /// `None` when the server default was used
+    pub stt_provider: Option<String>,
+    pub llm_provider: Option<String>,
+    /// Dictations with a recorded latency
+    pub samples: usize,
+    pub p50_seconds: f64,
+    pub p90_seconds: f64,
+    pub p99_seconds: f64,
+}
+
+/// Aggregated usage for a range of history
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UsageStats {
+    pub dictations: u64,
+    pub words: u64,
+    pub audio_seconds: f64,
+    /// Estimated typing time minus speaking time, never negative per dictation
+    pub time_saved_seconds: f64,
+    /// Oldest first
+    pub daily: Vec<PeriodCount>,
+    /// Oldest first
+    pub weekly: Vec<PeriodCount>,
+    pub latency: Vec<ProviderLatency>,
+}
+
+/// Nearest-rank percentile of sorted values
+pub fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Seconds it would take to type `words` at `words_per_minute`
+pub fn typing_seconds(words: u64, words_per_minute: u32) -> f64 {
+    let wpm = if words_per_minute == 0 {
+        DEFAULT_TYPING_SPEED_WPM
+    } else {
+        words_per_minute
+    };
+    words as f64 * 60.0 / wpm as f64
+}
+
+fn add_to_period(periods: &mut BTreeMap<NaiveDate, PeriodCount>, start: NaiveDate, words: u64) {
+    let period = periods.entry(start).or_insert(PeriodCount {
+        start,
+        dictations: 0,
+        words: 0,
+    });
+    period.dictations += 1;
+    period.words += words;
+}
+
+/// Aggregate history entries, with days and weeks taken in `tz`
+pub fn compute<Tz: TimeZone>(
+    entries: &[HistoryEntry],
+    words_per_minute: u32,
+    tz: &Tz,
+) -> UsageStats {
+    let mut words = 0;
+    let mut audio_seconds = 0.0;
+    let mut time_saved_seconds = 0.0;
+    let mut daily = BTreeMap::new();
+    let mut weekly = BTreeMap::new();
+    let mut latencies: BTreeMap<(Option<String>, Option<String>), Vec<f64>> = BTreeMap::new();
+
+    for entry in entries {
+        let entry_words = entry.text.split_whitespace().count() as u64;
+        let spoken = entry.details.duration_seconds.unwrap_or(0.0);
+        words += entry_words;
+        audio_seconds += spoken;
+        time_saved_seconds += (typing_seconds(entry_words, words_per_minute) - spoken).max(0.0);
+
+        let day = entry.timestamp.with_timezone(tz).date_naive();
+        let week = day - Days::new(u64::from(day.weekday().num_days_from_monday()));
+        add_to_period(&mut daily, day, entry_words);
+        add_to_period(&mut weekly, week, entry_words);
+
+        if let Some(latency) = entry.details.latency_seconds {
+            latencies
+                .entry((
+                    entry.details.stt_provider.clone(),
+                    entry.details.llm_provider.clone(),
+                ))
+                .or_default()
+                .push(latency);
+        }
+    }
+
+    let latency = latencies
+        .into_iter()
+        .map(|((stt_provider, llm_provider), mut samples)| {
+            samples.sort_by(f64::total_cmp);
+            ProviderLatency {
+                stt_provider,
+                llm_provider,
+                samples: samples.len(),
+                p50_seconds: percentile(&samples, 50.0),
+                p90_seconds: percentile(&samples, 90.0),
+                p99_seconds: percentile(&samples, 99.0),
+            }
+        })
+        .collect();
+
+    UsageStats {
+        dictations: entries.len() as u64,
+        words,
+        audio_seconds,
+        time_saved_seconds,
+        daily: daily.into_values().collect(),
+        weekly: weekly.into_values().collect(),
+        latency,
+    }
+}
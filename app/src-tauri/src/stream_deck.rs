@@ -0,0 +1,261 @@
+//! Local WebSocket for Stream Deck buttons and similar control surfaces.
+//!
+//! When `stream_deck_enabled` is on, a socket on `ws://127.0.0.1:<stream_deck_port>`
+//! accepts connections that pass the automation API token as `?token=<token>`.
+//! A Stream Deck plugin connects once and keeps the socket open.
+//!
+//! The client sends actions as JSON text messages:
+//!
+//! - `{"action": "start"}`, `{"action": "stop"}`, `{"action": "toggle"}`: start or
+//!   stop recording. `start` and `toggle` take `"profile": "<name>"` to deliver the
+//!   dictation with that output profile, for per-profile buttons
+//! - `{"action": "cancel"}`: stop recording and drop it without pasting
+//! - `{"action": "state"}`: ask for the current state
+//!
+//! The app answers with `{"event": "state", "state": "...", "recording": bool,
+//! "profile": "..." | null}` on connecting, after every action and whenever the
+//! dictation moves to another step (`idle`, `recording`, `transcribing`, `cleaning`,
+//! `pasting` or `error`, which comes with a `message`), so buttons can show live
+//! feedback. Actions that fail are answered with `{"event": "error", "message": "..."}`.
+
+use crate::automation_api::is_authorized;
+use crate::overlay_state::{OverlayState, OverlayStateMachine, OVERLAY_STATE_EVENT};
+use crate::state::AppState;
+use serde::Deserialize;
+use serde_json::json;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tungstenite::Message;
+
+/// Port the socket listens on when the setting is missing
+pub const DEFAULT_STREAM_DECK_PORT: u16 = 8767;
+
+/// How long a connection waits for an action before sending queued state changes
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Source named in logs for recordings started from the socket
+const SOURCE: &str = "Stream Deck";
+
+/// What a client asks for
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum StreamDeckAction {
+    Start {
+        #[serde(default)]
+        profile: Option<String>,
+    },
+    Stop,
+    Toggle {
+        #[serde(default)]
+        profile: Option<String>,
+    },
+    Cancel,
+    State,
+}
+
+/// Senders of the connected clients' state messages
+#[derive(Default)]
+pub struct StreamDeckClients {
+    senders: Mutex<Vec<mpsc::Sender<String>>>,
+}
+
+impl StreamDeckClients {
+    fn add(&self, sender: mpsc::Sender<String>) {
+        if let Ok(mut senders) = self.senders.lock() {
+            senders.push(sender);
+        }
+    }
+
+    /// Queue a message for every client, forgetting the ones that disconnected
+    fn broadcast(&self, message: &str) {
+        if let Ok(mut senders) = self.senders.lock() {
+            senders.retain(|sender| sender.send(message.to_string()).is_ok());
+        }
+    }
+}
+
+/// The action of a client message
+pub fn parse_action(message: &str) -> Result<StreamDeckAction, String> {
+    serde_json::from_str(message).map_err(|e| format!("Unknown action {}: {}", message, e))
+}
+
+/// A state message for clients
+pub fn state_message(state: &OverlayState, recording: bool, profile: Option<&str>) -> String {
+    let mut message = json!({
+        "event": "state",
+        "recording": recording,
+        "profile": profile,
+    });
+    if let (Ok(serde_json::Value::Object(state)), Some(message)) =
+        (serde_json::to_value(state), message.as_object_mut())
+    {
+        message.extend(state);
+    }
+    message.to_string()
+}
+
+/// The token a connection URL carries as `?token=`
+pub fn query_token(query: Option<&str>) -> Option<&str> {
+    query?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+}
+
+fn current_state_message(app: &AppHandle, state: Option<&OverlayState>) -> String {
+    let current = match state {
+        Some(state) => state.clone(),
+        None => app
+            .try_state::<OverlayStateMachine>()
+            .map(|machine| machine.current())
+            .unwrap_or_default(),
+    };
+    let app_state = app.state::<AppState>();
+    let profile = app_state
+        .dictation_profile
+        .lock()
+        .ok()
+        .and_then(|profile| profile.clone());
+    state_message(
+        &current,
+        app_state.lifecycle.is_recording(),
+        profile.as_deref(),
+    )
+}
+
+fn broadcast_state(app: &AppHandle, state: Option<&OverlayState>) {
+    if let Some(clients) = app.try_state::<StreamDeckClients>() {
+        clients.broadcast(&current_state_message(app, state));
+    }
+}
+
+/// Carry out an action
+fn run(app: &AppHandle, action: StreamDeckAction) -> Result<(), String> {
+    #[cfg(desktop)]
+    match action {
+        StreamDeckAction::Start { profile } => crate::deep_link::record(app, profile, SOURCE)?,
+        StreamDeckAction::Stop => crate::set_recording(app, false, SOURCE),
+        StreamDeckAction::Toggle { profile } => {
+            if crate::recording_state::is_recording(app) {
+                crate::set_recording(app, false, SOURCE);
+            } else {
+                crate::deep_link::record(app, profile, SOURCE)?;
+            }
+        }
+        StreamDeckAction::Cancel => crate::cancel_recording(app, SOURCE),
+        StreamDeckAction::State => {}
+    }
+    #[cfg(not(desktop))]
+    let _ = (app, action);
+    Ok(())
+}
+
+pub(crate) fn unauthorized() -> ErrorResponse {
+    tungstenite::http::Response::builder()
+        .status(401)
+        .body(Some("Missing or wrong API token".to_string()))
+        .expect("Unauthorized response is valid")
+}
+
+/// Answer one client until it disconnects
+fn serve(app: &AppHandle, stream: TcpStream) {
+    let token = crate::automation_api::stored_token(app).unwrap_or_default();
+    let check_token = |request: &Request, response: Response| {
+        let given = query_token(request.uri().query()).map(|token| format!("Bearer {}", token));
+        if is_authorized(given.as_deref(), &token) {
+            Ok(response)
+        } else {
+            Err(unauthorized())
+        }
+    };
+    let mut socket = match tungstenite::accept_hdr(stream, check_token) {
+        Ok(socket) => socket,
+        Err(e) => {
+            log::debug!("Stream Deck connection refused: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)) {
+        log::warn!("Failed to set up Stream Deck connection: {}", e);
+        return;
+    }
+    log::info!("Stream Deck client connected");
+
+    let (sender, receiver) = mpsc::channel();
+    let _ = sender.send(current_state_message(app, None));
+    app.state::<StreamDeckClients>().add(sender);
+
+    loop {
+        while let Ok(message) = receiver.try_recv() {
+            if socket.send(Message::text(message)).is_err() {
+                return;
+            }
+        }
+        match socket.read() {
+            Ok(Message::Text(text)) => match parse_action(&text).and_then(|a| run(app, a)) {
+                Ok(()) => broadcast_state(app, None),
+                Err(e) => {
+                    log::warn!("{}", e);
+                    let error = json!({ "event": "error", "message": e }).to_string();
+                    if socket.send(Message::text(error)).is_err() {
+                        return;
+                    }
+                }
+            },
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => {
+                log::debug!("Stream Deck connection closed: {}", e);
+                break;
+            }
+        }
+    }
+    log::info!("Stream Deck client disconnected");
+}
+
+/// Serve the socket on a background thread, when it is enabled
+pub fn spawn(app: AppHandle) {
+    if !crate::settings::read_setting::<bool>(&app, "stream_deck_enabled") {
+        return;
+    }
+    if let Err(e) = crate::automation_api::ensure_token(&app) {
+        log::error!("Stream Deck socket not started: {}", e);
+        return;
+    }
+    let port = crate::settings::read_setting::<Option<u16>>(&app, "stream_deck_port")
+        .unwrap_or(DEFAULT_STREAM_DECK_PORT);
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("Failed to start Stream Deck socket on port {}: {}", port, e);
+            return;
+        }
+    };
+    log::info!("Stream Deck socket listening on 127.0.0.1:{}", port);
+
+    app.manage(StreamDeckClients::default());
+    let state_app = app.clone();
+    app.listen(OVERLAY_STATE_EVENT, move |event| {
+        if let Ok(state) = serde_json::from_str::<OverlayState>(event.payload()) {
+            broadcast_state(&state_app, Some(&state));
+        }
+    });
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let app = app.clone();
+                    thread::spawn(move || serve(&app, stream));
+                }
+                Err(e) => log::debug!("Failed to accept Stream Deck connection: {}", e),
+            }
+        }
+    });
+}
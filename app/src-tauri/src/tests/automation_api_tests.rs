@@ -0,0 +1,63 @@
+use crate::automation_api::{generate_token, is_authorized, query_flag, route, Endpoint};
+
+#[test]
+fn test_routes() {
+    assert_eq!(
+        route("POST", "/recording/start"),
+        Some(Endpoint::StartRecording)
+    );
+    assert_eq!(
+        route("POST", "/recording/stop/"),
+        Some(Endpoint::StopRecording)
+    );
+    assert_eq!(
+        route("post", "/recording/toggle?source=deck"),
+        Some(Endpoint::ToggleRecording)
+    );
+    assert_eq!(
+        route("GET", "/transcript/last"),
+        Some(Endpoint::LastTranscript)
+    );
+    assert_eq!(route("POST", "/cleanup"), Some(Endpoint::Cleanup));
+
+    assert_eq!(route("GET", "/recording/start"), None);
+    assert_eq!(route("POST", "/transcript/last"), None);
+    assert_eq!(route("GET", "/"), None);
+}
+
+#[test]
+fn test_requests_need_the_token() {
+    assert!(is_authorized(Some("Bearer secret"), "secret"));
+    assert!(is_authorized(Some(" Bearer secret "), "secret"));
+
+    assert!(!is_authorized(None, "secret"));
+    assert!(!is_authorized(Some("Bearer wrong!"), "secret"));
+    assert!(!is_authorized(Some("Bearer secre"), "secret"));
+    assert!(!is_authorized(Some("secret"), "secret"));
+    assert!(!is_authorized(Some("Basic secret"), "secret"));
+    // No token stored means nothing gets in
+    assert!(!is_authorized(Some("Bearer "), ""));
+}
+
+#[test]
+fn test_generated_tokens_differ() {
+    let token = generate_token().unwrap();
+    assert_eq!(token.len(), 48);
+    assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_ne!(generate_token().unwrap(), token);
+}
+
+#[test]
+fn test_query_flags() {
+    assert_eq!(
+        query_flag("/recording/start?paste=false", "paste"),
+        Some(false)
+    );
+    assert_eq!(
+        query_flag("/recording/toggle?source=deck&paste=true", "paste"),
+        Some(true)
+    );
+    assert_eq!(query_flag("/recording/start", "paste"), None);
+    assert_eq!(query_flag("/recording/start?paste=maybe", "paste"), None);
+    assert_eq!(query_flag("/recording/start?pasted=false", "paste"), None);
+}
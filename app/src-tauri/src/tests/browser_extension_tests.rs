@@ -0,0 +1,108 @@
+use crate::browser_extension::{
+    extension_family, host_manifest, insert_message, is_browser, read_frame, write_frame,
+    BrowserFamily, ExtensionMessage, MAX_FRAME_TO_BROWSER, NATIVE_HOST_NAME,
+};
+use serde_json::{json, Value};
+use std::io::Cursor;
+use std::path::Path;
+
+#[test]
+fn test_is_browser() {
+    assert!(is_browser("chrome.exe"));
+    assert!(is_browser("Google Chrome"));
+    assert!(is_browser("msedge.exe"));
+    assert!(is_browser("Firefox"));
+    assert!(is_browser("Brave Browser"));
+    assert!(!is_browser("Code.exe"));
+    assert!(!is_browser("Slack"));
+}
+
+#[test]
+fn test_frames_round_trip() {
+    let mut buffer = Vec::new();
+    write_frame(&mut buffer, r#"{"type":"hello"}"#).unwrap();
+    write_frame(&mut buffer, r#"{"type":"inserted","id":"1","ok":true}"#).unwrap();
+    assert_eq!(&buffer[..4], &16u32.to_ne_bytes());
+
+    let mut reader = Cursor::new(buffer);
+    assert_eq!(
+        read_frame(&mut reader).unwrap().as_deref(),
+        Some(r#"{"type":"hello"}"#)
+    );
+    assert_eq!(
+        read_frame(&mut reader).unwrap().as_deref(),
+        Some(r#"{"type":"inserted","id":"1","ok":true}"#)
+    );
+    assert_eq!(read_frame(&mut reader).unwrap(), None);
+}
+
+#[test]
+fn test_frame_errors() {
+    let too_long = "x".repeat(MAX_FRAME_TO_BROWSER + 1);
+    assert!(write_frame(&mut Vec::new(), &too_long).is_err());
+
+    // Length promises more than the browser sent
+    let mut truncated = 10u32.to_ne_bytes().to_vec();
+    truncated.extend_from_slice(b"{}");
+    assert!(read_frame(&mut Cursor::new(truncated)).is_err());
+}
+
+#[test]
+fn test_extension_messages() {
+    assert_eq!(
+        serde_json::from_str::<ExtensionMessage>(r#"{"type":"hello","browser":"firefox"}"#)
+            .unwrap(),
+        ExtensionMessage::Hello {
+            browser: Some("firefox".to_string())
+        }
+    );
+    assert_eq!(
+        serde_json::from_str::<ExtensionMessage>(
+            r#"{"type":"inserted","id":"a","ok":false,"error":"No field has focus"}"#
+        )
+        .unwrap(),
+        ExtensionMessage::Inserted {
+            id: "a".to_string(),
+            ok: false,
+            error: Some("No field has focus".to_string()),
+        }
+    );
+
+    let insert: Value = serde_json::from_str(&insert_message("a", "Hello")).unwrap();
+    assert_eq!(
+        insert,
+        json!({ "type": "insert", "id": "a", "text": "Hello" })
+    );
+}
+
+#[test]
+fn test_host_manifests_per_family() {
+    let ids = vec![
+        "abcdefghijklmnopabcdefghijklmnop".to_string(),
+        "tambourine@example.org".to_string(),
+        " ".to_string(),
+    ];
+    assert_eq!(extension_family(&ids[0]), BrowserFamily::Chromium);
+    assert_eq!(extension_family(&ids[1]), BrowserFamily::Firefox);
+    assert_eq!(
+        extension_family("{c2a8f5a1-0000-4000-8000-000000000000}"),
+        BrowserFamily::Firefox
+    );
+
+    let path = Path::new("/opt/tambourine/tambourine");
+    let chromium = host_manifest(BrowserFamily::Chromium, path, &ids);
+    assert_eq!(chromium["name"], json!(NATIVE_HOST_NAME));
+    assert_eq!(chromium["type"], json!("stdio"));
+    assert_eq!(chromium["path"], json!("/opt/tambourine/tambourine"));
+    assert_eq!(
+        chromium["allowed_origins"],
+        json!(["chrome-extension://abcdefghijklmnopabcdefghijklmnop/"])
+    );
+    assert!(chromium.get("allowed_extensions").is_none());
+
+    let firefox = host_manifest(BrowserFamily::Firefox, path, &ids);
+    assert_eq!(
+        firefox["allowed_extensions"],
+        json!(["tambourine@example.org"])
+    );
+}
@@ -0,0 +1,98 @@
+use crate::calendar::{parse_date_time, parse_ics, starting_soon, Frequency};
+use chrono::{DateTime, Duration, Utc};
+
+const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+SUMMARY:Team standup\\, daily\r
+DTSTART:20260601T090000Z\r
+DTEND:20260601T091500Z\r
+RRULE:FREQ=DAILY;COUNT=5\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:review@example.com\r
+SUMMARY:Design review with a very long title that the calendar\r
+  folded\r
+DTSTART;TZID=Europe/Berlin:20260610T140000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:holiday@example.com\r
+SUMMARY:Holiday\r
+DTSTART;VALUE=DATE:20260604\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:cancelled@example.com\r
+SUMMARY:Cancelled sync\r
+STATUS:CANCELLED\r
+DTSTART:20260602T100000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+fn utc(value: &str) -> DateTime<Utc> {
+    parse_date_time(value).unwrap()
+}
+
+#[test]
+fn test_parse_ics_keeps_timed_events() {
+    let events = parse_ics(CALENDAR);
+    assert_eq!(events.len(), 2);
+
+    assert_eq!(events[0].uid, "standup@example.com");
+    assert_eq!(events[0].title, "Team standup, daily");
+    assert_eq!(events[0].start, utc("20260601T090000Z"));
+    assert_eq!(events[0].end, Some(utc("20260601T091500Z")));
+    let rule = events[0].recurrence.as_ref().unwrap();
+    assert_eq!(rule.frequency, Frequency::Daily);
+    assert_eq!(rule.count, Some(5));
+
+    assert_eq!(
+        events[1].title,
+        "Design review with a very long title that the calendar folded"
+    );
+    assert_eq!(events[1].recurrence, None);
+}
+
+#[test]
+fn test_starting_soon_follows_repeats() {
+    let events = parse_ics(CALENDAR);
+
+    let soon = starting_soon(&events, utc("20260603T085930Z"), 1);
+    assert_eq!(soon.len(), 1);
+    assert_eq!(soon[0].title, "Team standup, daily");
+    assert_eq!(soon[0].start, utc("20260603T090000Z"));
+    assert_eq!(soon[0].end, Some(utc("20260603T091500Z")));
+
+    // Meetings that just started are still offered, later ones aren't
+    assert_eq!(starting_soon(&events, utc("20260603T090300Z"), 1).len(), 1);
+    assert!(starting_soon(&events, utc("20260603T091000Z"), 1).is_empty());
+    assert!(starting_soon(&events, utc("20260603T085000Z"), 1).is_empty());
+
+    // The fifth occurrence is the last
+    assert_eq!(starting_soon(&events, utc("20260605T085930Z"), 1).len(), 1);
+    assert!(starting_soon(&events, utc("20260606T085930Z"), 1).is_empty());
+}
+
+#[test]
+fn test_weekly_repeats_until() {
+    let ics = "BEGIN:VEVENT\nUID:1on1\nSUMMARY:1:1\nDTSTART:20260601T120000Z\n\
+               RRULE:FREQ=WEEKLY;INTERVAL=2;UNTIL=20260701T000000Z\nEND:VEVENT\n";
+    let events = parse_ics(ics);
+    let at = |start: &str| starting_soon(&events, utc(start) - Duration::seconds(30), 1);
+
+    assert_eq!(at("20260615T120000Z").len(), 1);
+    assert_eq!(at("20260629T120000Z").len(), 1);
+    assert!(at("20260608T120000Z").is_empty());
+    assert!(at("20260713T120000Z").is_empty());
+}
+
+#[test]
+fn test_parse_date_time_refuses_dates() {
+    assert_eq!(
+        parse_date_time("20260601T090000Z"),
+        Some("2026-06-01T09:00:00Z".parse().unwrap())
+    );
+    assert!(parse_date_time("20260601").is_none());
+    assert!(parse_date_time("tomorrow").is_none());
+}
@@ -0,0 +1,95 @@
+use crate::cli::{parse_args, settings_in, CliCommand};
+use crate::settings::{SettingsPolicy, SETTINGS_FILE_NAME};
+use crate::tests::TempDir;
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn test_parse_transcribe() {
+    assert_eq!(
+        parse_args(&args(&["--transcribe", "a.wav", "b.wav"])),
+        Ok(Some(CliCommand::Transcribe {
+            files: vec![PathBuf::from("a.wav"), PathBuf::from("b.wav")],
+            raw: false,
+        }))
+    );
+    assert_eq!(
+        parse_args(&args(&["--transcribe", "--raw", "memo.wav"])),
+        Ok(Some(CliCommand::Transcribe {
+            files: vec![PathBuf::from("memo.wav")],
+            raw: true,
+        }))
+    );
+    assert!(parse_args(&args(&["--transcribe"])).is_err());
+    assert!(parse_args(&args(&["--transcribe", "a.wav", "--fast"])).is_err());
+}
+
+#[test]
+fn test_parse_listen() {
+    assert_eq!(
+        parse_args(&args(&["--listen"])),
+        Ok(Some(CliCommand::Listen { paste: false }))
+    );
+    assert_eq!(
+        parse_args(&args(&["--listen", "--paste"])),
+        Ok(Some(CliCommand::Listen { paste: true }))
+    );
+    assert!(parse_args(&args(&["--listen", "now"])).is_err());
+}
+
+#[test]
+fn test_parse_native_messaging_host() {
+    assert_eq!(
+        parse_args(&args(&[
+            "chrome-extension://abcdefghijklmnop/",
+            "--parent-window=0"
+        ])),
+        Ok(Some(CliCommand::NativeMessagingHost))
+    );
+    assert_eq!(
+        parse_args(&args(&[
+            "/home/sam/.mozilla/native-messaging-hosts/com.tambourine_voice.host.json",
+            "tambourine@example.org",
+        ])),
+        Ok(Some(CliCommand::NativeMessagingHost))
+    );
+    assert_eq!(parse_args(&args(&["/tmp/other.json"])), Ok(None));
+}
+
+#[test]
+fn test_other_arguments_start_the_app() {
+    assert_eq!(parse_args(&[]), Ok(None));
+    assert_eq!(parse_args(&args(&["-psn_0_12345"])), Ok(None));
+    assert_eq!(parse_args(&args(&["-h"])), Ok(Some(CliCommand::Help)));
+}
+
+#[test]
+fn test_settings_apply_locked_values() {
+    let dir = TempDir::new();
+
+    // Missing settings are all defaults
+    assert!(settings_in(&dir, &SettingsPolicy::default())
+        .unwrap()
+        .is_empty());
+
+    fs::write(
+        dir.join(SETTINGS_FILE_NAME),
+        json!({ "stt_provider": "deepgram", "llm_provider": "openai" }).to_string(),
+    )
+    .unwrap();
+    let mut policy = SettingsPolicy::default();
+    policy
+        .locked
+        .insert("stt_provider".to_string(), json!("whisper"));
+    let settings = settings_in(&dir, &policy).unwrap();
+    assert_eq!(settings["stt_provider"], "whisper");
+    assert_eq!(settings["llm_provider"], "openai");
+
+    fs::write(dir.join(SETTINGS_FILE_NAME), "[]").unwrap();
+    assert!(settings_in(&dir, &policy).is_err());
+}
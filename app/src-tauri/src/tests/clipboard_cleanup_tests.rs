@@ -0,0 +1,26 @@
+use crate::clipboard_cleanup::{text_to_clean, MAX_CLIPBOARD_CLEANUP_CHARS};
+
+#[test]
+fn test_copied_text_is_cleaned() {
+    let text = text_to_clean(Some("um so the the meeting".to_string()), None).unwrap();
+    assert_eq!(text, "um so the the meeting");
+}
+
+#[test]
+fn test_empty_clipboard_refused() {
+    assert!(text_to_clean(None, None).is_err());
+    assert!(text_to_clean(Some("  \n".to_string()), None).is_err());
+}
+
+#[test]
+fn test_last_cleanup_not_cleaned_again() {
+    let cleaned = "So the meeting is at noon.";
+    assert!(text_to_clean(Some(cleaned.to_string()), Some(cleaned)).is_err());
+    assert!(text_to_clean(Some("Something new".to_string()), Some(cleaned)).is_ok());
+}
+
+#[test]
+fn test_long_text_refused() {
+    let long = "a".repeat(MAX_CLIPBOARD_CLEANUP_CHARS + 1);
+    assert!(text_to_clean(Some(long), None).is_err());
+}
@@ -0,0 +1,41 @@
+use crate::deep_link::{parse, DeepLinkAction};
+use tauri::Url;
+
+fn action(link: &str) -> Result<DeepLinkAction, String> {
+    parse(&Url::parse(link).unwrap())
+}
+
+#[test]
+fn test_parse_actions() {
+    assert_eq!(
+        action("tambourine://record"),
+        Ok(DeepLinkAction::Record { profile: None })
+    );
+    assert_eq!(action("tambourine://stop/"), Ok(DeepLinkAction::Stop));
+    assert_eq!(
+        action("tambourine:toggle"),
+        Ok(DeepLinkAction::Toggle { profile: None })
+    );
+    assert!(action("tambourine://delete-everything").is_err());
+    assert!(action("https://record").is_err());
+}
+
+#[test]
+fn test_parse_profile() {
+    assert_eq!(
+        action("tambourine://record?profile=email"),
+        Ok(DeepLinkAction::Record {
+            profile: Some("email".to_string())
+        })
+    );
+    assert_eq!(
+        action("tambourine://toggle?profile=Visual%20Studio%20Code"),
+        Ok(DeepLinkAction::Toggle {
+            profile: Some("Visual Studio Code".to_string())
+        })
+    );
+    assert_eq!(
+        action("tambourine://record?profile="),
+        Ok(DeepLinkAction::Record { profile: None })
+    );
+}
@@ -0,0 +1,40 @@
+use crate::email_draft::{draft_from, mailto_url, EmailClient, EmailDraft};
+use crate::settings::AppOutputProfile;
+
+#[test]
+fn test_draft_without_subject_keeps_all_text() {
+    let draft = draft_from("Hi Sam,\nsee you tomorrow.");
+    assert_eq!(draft.subject, "");
+    assert_eq!(draft.body, "Hi Sam,\nsee you tomorrow.");
+}
+
+#[test]
+fn test_draft_takes_subject_line() {
+    let draft = draft_from("Subject: Launch plan\n\nHi team,\nhere it is.");
+    assert_eq!(draft.subject, "Launch plan");
+    assert_eq!(draft.body, "Hi team,\nhere it is.");
+
+    let draft = draft_from("subject: Quick question");
+    assert_eq!(draft.subject, "Quick question");
+    assert_eq!(draft.body, "");
+}
+
+#[test]
+fn test_mailto_url_encodes_text_and_line_breaks() {
+    let draft = EmailDraft {
+        subject: "Q3 & Q4".to_string(),
+        body: "Hi,\nthanks!".to_string(),
+    };
+    assert_eq!(
+        mailto_url(&draft),
+        "mailto:?subject=Q3%20%26%20Q4&body=Hi%2C%0D%0Athanks%21"
+    );
+}
+
+#[test]
+fn test_profile_email_draft_from_json() {
+    let profile: AppOutputProfile =
+        serde_json::from_str(r#"{"email_draft": "apple_mail"}"#).unwrap();
+    assert_eq!(profile.email_draft, Some(EmailClient::AppleMail));
+    assert_eq!(AppOutputProfile::default().email_draft, None);
+}
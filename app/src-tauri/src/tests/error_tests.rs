@@ -0,0 +1,63 @@
+use crate::error::AppError;
+use crate::history::{self, LOCKED_ERROR};
+use crate::history_crypto::WRONG_PASSPHRASE_ERROR;
+use crate::pipeline::CANCELLED;
+
+#[test]
+fn test_untyped_messages_are_failures() {
+    // Messages aren't sorted by what they say; the kind comes from the error site
+    assert_eq!(
+        AppError::from("Accessibility insertion is not supported on this platform"),
+        AppError::Failed("Accessibility insertion is not supported on this platform".to_string())
+    );
+    assert_eq!(
+        AppError::from(CANCELLED.to_string()),
+        AppError::Failed(CANCELLED.to_string())
+    );
+}
+
+#[test]
+fn test_history_errors_keep_their_kind() {
+    assert_eq!(
+        history::command_error(LOCKED_ERROR.to_string()),
+        AppError::Locked(LOCKED_ERROR.to_string())
+    );
+    assert_eq!(
+        history::command_error(WRONG_PASSPHRASE_ERROR.to_string()),
+        AppError::InvalidInput(WRONG_PASSPHRASE_ERROR.to_string())
+    );
+    assert_eq!(
+        history::command_error("Failed to read history: disk I/O error".to_string()),
+        AppError::Storage("Failed to read history: disk I/O error".to_string())
+    );
+}
+
+#[test]
+fn test_serializes_for_the_frontend() {
+    let error = AppError::ServerUnavailable("Failed to connect".to_string());
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        serde_json::json!({
+            "kind": "server_unavailable",
+            "message": "Failed to connect",
+            "recoverable": true,
+            "suggested_action": "Make sure the Tambourine server is running",
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(AppError::Cancelled).unwrap(),
+        serde_json::json!({
+            "kind": "cancelled",
+            "message": CANCELLED,
+            "recoverable": false,
+            "suggested_action": null,
+        })
+    );
+}
+
+#[test]
+fn test_converts_back_to_message() {
+    let error = AppError::NotFound("Nothing to copy yet".to_string());
+    assert_eq!(error.to_string(), "Nothing to copy yet");
+    assert_eq!(String::from(error), "Nothing to copy yet");
+}
@@ -0,0 +1,39 @@
+use crate::event_stream::{event_message, query_param, requested_events, wants};
+use serde_json::json;
+
+#[test]
+fn test_event_message() {
+    let message: serde_json::Value = serde_json::from_str(&event_message(
+        "partial_transcript",
+        &json!({ "text": "Hi" }),
+    ))
+    .unwrap();
+    assert_eq!(message["event"], "partial_transcript");
+    assert_eq!(message["data"]["text"], "Hi");
+}
+
+#[test]
+fn test_query_param() {
+    let query = Some("token=abc&events=state");
+    assert_eq!(query_param(query, "token"), Some("abc"));
+    assert_eq!(query_param(query, "events"), Some("state"));
+    assert_eq!(query_param(query, "other"), None);
+    assert_eq!(query_param(None, "token"), None);
+}
+
+#[test]
+fn test_requested_events_keep_known_names() {
+    assert!(requested_events(Some("token=abc")).is_empty());
+    assert_eq!(
+        requested_events(Some("token=abc&events=state,bogus,partial_transcript")),
+        vec!["state", "partial_transcript"]
+    );
+}
+
+#[test]
+fn test_wants_everything_without_a_list() {
+    assert!(wants(&[], "progress"));
+    let events = vec!["state".to_string()];
+    assert!(wants(&events, "state"));
+    assert!(!wants(&events, "progress"));
+}
@@ -0,0 +1,103 @@
+use crate::failed_dictations::{FailedDictations, FailureDetails, MAX_FAILED_DICTATIONS};
+use crate::tests::TempDir;
+
+fn details(error: &str, raw_text: Option<&str>) -> FailureDetails {
+    FailureDetails {
+        error: error.to_string(),
+        stt_provider: Some("deepgram".to_string()),
+        llm_provider: None,
+        raw_text: raw_text.map(str::to_string),
+    }
+}
+
+#[test]
+fn test_record_needs_audio_or_transcript() {
+    let failed = FailedDictations::in_memory();
+    assert!(failed.record(details("timeout", None), None).is_err());
+    assert!(failed
+        .record(details("timeout", None), Some((&[], "audio/webm")))
+        .is_err());
+
+    let dictation = failed
+        .record(details("LLM error", Some("raw words")), None)
+        .unwrap();
+    assert_eq!(dictation.raw_text.as_deref(), Some("raw words"));
+    assert_eq!(dictation.mime_type, None);
+    assert_eq!(failed.list().unwrap(), vec![dictation]);
+}
+
+#[test]
+fn test_retry_failure_is_counted() {
+    let failed = FailedDictations::in_memory();
+    let dictation = failed.record(details("timeout", Some("hi")), None).unwrap();
+
+    assert!(failed.retry_failed(&dictation.id, "still down").unwrap());
+    assert!(!failed.retry_failed("missing", "still down").unwrap());
+    let listed = &failed.list().unwrap()[0];
+    assert_eq!(listed.error, "still down");
+    assert_eq!(listed.retries, 1);
+}
+
+#[test]
+fn test_recording_is_kept_until_discarded() {
+    let dir = TempDir::new();
+    let failed = FailedDictations::new(dir.to_path_buf());
+    let dictation = failed
+        .record(
+            details("timeout", None),
+            Some((b"opus", "audio/webm;codecs=opus")),
+        )
+        .unwrap();
+
+    let reloaded = FailedDictations::new(dir.to_path_buf());
+    assert_eq!(reloaded.list().unwrap(), vec![dictation.clone()]);
+    let audio = reloaded.audio(&dictation.id).unwrap().unwrap();
+    assert_eq!(audio.bytes, b"opus");
+    assert_eq!(audio.mime_type, "audio/webm;codecs=opus");
+
+    assert!(reloaded.discard(&dictation.id).unwrap());
+    assert!(!reloaded.discard(&dictation.id).unwrap());
+    assert!(reloaded.audio(&dictation.id).unwrap().is_none());
+    assert!(!dir
+        .join("failed_dictations")
+        .join(format!("{}.webm", dictation.id))
+        .exists());
+}
+
+#[test]
+fn test_only_newest_are_kept() {
+    let failed = FailedDictations::in_memory();
+    for i in 0..MAX_FAILED_DICTATIONS + 2 {
+        failed
+            .record(details("timeout", Some(&i.to_string())), None)
+            .unwrap();
+    }
+    let listed = failed.list().unwrap();
+    assert_eq!(listed.len(), MAX_FAILED_DICTATIONS);
+    assert!(listed.iter().all(|d| d.raw_text.as_deref() != Some("0")));
+}
+
+#[test]
+fn test_clear_removes_every_dictation_and_recording() {
+    let dir = TempDir::new();
+    let failed = FailedDictations::new(dir.to_path_buf());
+    failed
+        .record(details("timeout", None), Some((b"opus", "audio/webm")))
+        .unwrap();
+    failed
+        .record(details("timeout", Some("raw words")), None)
+        .unwrap();
+
+    assert_eq!(failed.clear().unwrap(), 2);
+    assert!(failed.list().unwrap().is_empty());
+    let files: Vec<_> = std::fs::read_dir(dir.join(crate::failed_dictations::FAILED_DIR))
+        .unwrap()
+        .flatten()
+        .map(|file| file.file_name())
+        .collect();
+    assert_eq!(files, ["index.json"]);
+    assert!(FailedDictations::new(dir.to_path_buf())
+        .list()
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,50 @@
+use crate::file_transcription::{decode_audio, downmix, encode_wav, resample};
+use crate::tests::TempDir;
+use std::fs;
+
+#[test]
+fn test_downmix_averages_channels() {
+    assert_eq!(downmix(&[0.5, -0.5, 1.0, 0.0], 2), vec![0.0, 0.5]);
+    assert_eq!(downmix(&[0.25, 0.5], 1), vec![0.25, 0.5]);
+}
+
+#[test]
+fn test_resample_changes_length_and_keeps_shape() {
+    let samples: Vec<f32> = (0..48).map(|i| i as f32 / 48.0).collect();
+    let resampled = resample(&samples, 48_000, 16_000);
+    assert_eq!(resampled.len(), 16);
+    assert_eq!(resampled[0], 0.0);
+    assert!((resampled[8] - 0.5).abs() < 1e-6);
+
+    assert_eq!(resample(&samples, 16_000, 16_000), samples);
+    assert!(resample(&[], 48_000, 16_000).is_empty());
+}
+
+#[test]
+fn test_encoded_wav_decodes_back() {
+    let samples: Vec<f32> = (0..1600).map(|i| (i as f32 / 10.0).sin() * 0.5).collect();
+    let wav = encode_wav(&samples, 16_000);
+    assert_eq!(&wav[..4], b"RIFF");
+    assert_eq!(&wav[8..12], b"WAVE");
+    assert_eq!(wav.len(), 44 + samples.len() * 2);
+
+    let dir = TempDir::new();
+    let path = dir.join("tone.wav");
+    fs::write(&path, &wav).unwrap();
+    let (decoded, sample_rate) = decode_audio(&path).unwrap();
+
+    assert_eq!(sample_rate, 16_000);
+    assert_eq!(decoded.len(), samples.len());
+    assert!(decoded
+        .iter()
+        .zip(&samples)
+        .all(|(a, b)| (a - b).abs() < 1e-3));
+}
+
+#[test]
+fn test_decode_audio_rejects_other_files() {
+    let dir = TempDir::new();
+    let path = dir.join("notes.txt");
+    fs::write(&path, "not audio").unwrap();
+    assert!(decode_audio(&path).is_err());
+}
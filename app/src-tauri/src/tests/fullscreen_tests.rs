@@ -0,0 +1,55 @@
+use crate::fullscreen::{FullscreenStatus, ScreenRect};
+
+const MONITOR: ScreenRect = ScreenRect {
+    left: 0.0,
+    top: 0.0,
+    right: 1920.0,
+    bottom: 1080.0,
+};
+
+#[test]
+fn test_window_covering_monitor_is_fullscreen() {
+    assert!(MONITOR.covers(&MONITOR));
+
+    // Borderless windows are often a pixel off, exclusive ones may overhang
+    let rounded = ScreenRect {
+        left: 0.5,
+        top: -1.0,
+        right: 1919.5,
+        bottom: 1081.0,
+    };
+    assert!(rounded.covers(&MONITOR));
+}
+
+#[test]
+fn test_maximized_window_is_not_fullscreen() {
+    // Taskbar or menu bar still visible
+    let maximized = ScreenRect {
+        left: 0.0,
+        top: 0.0,
+        right: 1920.0,
+        bottom: 1040.0,
+    };
+    assert!(!maximized.covers(&MONITOR));
+
+    // Fullscreen on a different monitor
+    let second_monitor = ScreenRect {
+        left: 1920.0,
+        top: 0.0,
+        right: 3840.0,
+        bottom: 1080.0,
+    };
+    assert!(!second_monitor.covers(&MONITOR));
+}
+
+#[test]
+fn test_fullscreen_status_serialization() {
+    let status = FullscreenStatus {
+        fullscreen: true,
+        suppressed: false,
+    };
+    assert_eq!(
+        serde_json::to_value(status).unwrap(),
+        serde_json::json!({ "fullscreen": true, "suppressed": false })
+    );
+}
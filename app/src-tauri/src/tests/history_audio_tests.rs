@@ -0,0 +1,92 @@
+use crate::history::{EntryDetails, HistoryStorage};
+use crate::history_audio::{file_extension, load, remove_orphans, resample_waveform, save};
+use crate::tests::TempDir;
+use std::fs;
+
+#[test]
+fn test_file_extension_from_mime_type() {
+    assert_eq!(file_extension("audio/webm;codecs=opus"), "webm");
+    assert_eq!(file_extension("audio/ogg"), "ogg");
+    assert_eq!(file_extension("audio/mp4"), "m4a");
+    assert_eq!(file_extension("application/octet-stream"), "audio");
+}
+
+#[test]
+fn test_resample_waveform_keeps_peaks_in_range() {
+    assert_eq!(
+        resample_waveform(&[0.5, 2.0, -1.0, f32::NAN], 10),
+        [0.5, 1.0, 0.0, 0.0]
+    );
+    assert_eq!(
+        resample_waveform(&[0.1, 0.9, 0.2, 0.3, 0.4, 0.8], 3),
+        [0.9, 0.3, 0.8]
+    );
+    assert_eq!(resample_waveform(&vec![0.5; 1000], 100).len(), 100);
+}
+
+#[test]
+fn test_recording_is_saved_loaded_and_swept() {
+    let dir = TempDir::new();
+    let audio_dir = dir.join("history_audio");
+    let history = HistoryStorage::in_memory().unwrap();
+    let entry = history
+        .add_entry("hello".to_string(), EntryDetails::default())
+        .unwrap();
+
+    assert!(!save(&audio_dir, &history, "missing", b"x", "audio/webm", &[]).unwrap());
+    assert!(!save(&audio_dir, &history, "../escape", b"x", "audio/webm", &[]).unwrap());
+    assert!(save(
+        &audio_dir,
+        &history,
+        &entry.id,
+        b"opus data",
+        "audio/webm;codecs=opus",
+        &[0.2, 0.7]
+    )
+    .unwrap());
+    assert!(history.get_all(None).unwrap()[0].has_audio);
+
+    let audio = load(&audio_dir, &history, &entry.id).unwrap().unwrap();
+    assert_eq!(audio.bytes, b"opus data");
+    assert_eq!(audio.mime_type, "audio/webm;codecs=opus");
+    assert_eq!(audio.waveform, [0.2, 0.7]);
+    assert!(audio.path.ends_with(format!("{}.webm", entry.id)));
+
+    assert_eq!(remove_orphans(&audio_dir, &history).unwrap(), 0);
+    // A trashed entry keeps its recording until it is removed for good
+    history.delete(&entry.id).unwrap();
+    assert_eq!(remove_orphans(&audio_dir, &history).unwrap(), 0);
+    history.purge_entry(&entry.id).unwrap();
+    assert_eq!(remove_orphans(&audio_dir, &history).unwrap(), 1);
+    assert!(load(&audio_dir, &history, &entry.id).unwrap().is_none());
+}
+
+#[test]
+fn test_recording_saved_in_another_format_replaces_the_old_file() {
+    let dir = TempDir::new();
+    let audio_dir = dir.join("history_audio");
+    let history = HistoryStorage::in_memory().unwrap();
+    let entry = history
+        .add_entry("hello".to_string(), EntryDetails::default())
+        .unwrap();
+
+    assert!(save(&audio_dir, &history, &entry.id, b"webm", "audio/webm", &[]).unwrap());
+    assert!(save(&audio_dir, &history, &entry.id, b"mp4", "audio/mp4", &[]).unwrap());
+    assert!(!audio_dir.join(format!("{}.webm", entry.id)).exists());
+    assert_eq!(
+        load(&audio_dir, &history, &entry.id)
+            .unwrap()
+            .unwrap()
+            .bytes,
+        b"mp4"
+    );
+
+    // A file left behind by an earlier version is swept
+    fs::write(audio_dir.join(format!("{}.ogg", entry.id)), b"ogg").unwrap();
+    assert_eq!(remove_orphans(&audio_dir, &history).unwrap(), 1);
+    assert!(audio_dir.join(format!("{}.m4a", entry.id)).exists());
+
+    history.clear_audio().unwrap();
+    assert_eq!(remove_orphans(&audio_dir, &history).unwrap(), 1);
+    assert!(load(&audio_dir, &history, &entry.id).unwrap().is_none());
+}
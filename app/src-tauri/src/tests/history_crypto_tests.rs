@@ -0,0 +1,83 @@
+use crate::history::{EntryDetails, HistoryStorage, LOCKED_ERROR};
+use crate::history_crypto::{is_plaintext, HistoryKey};
+use crate::tests::TempDir;
+
+fn raw_key(byte: &str) -> HistoryKey {
+    HistoryKey::Raw(byte.repeat(32))
+}
+
+#[test]
+fn test_key_literals() {
+    assert_eq!(
+        raw_key("ab").sql_literal(),
+        format!("\"x'{}'\"", "ab".repeat(32))
+    );
+    assert_eq!(
+        HistoryKey::Passphrase("it's secret".to_string()).sql_literal(),
+        "'it''s secret'"
+    );
+}
+
+#[test]
+fn test_encrypted_history_needs_its_key() {
+    let dir = TempDir::new();
+    let history = HistoryStorage::open(dir.to_path_buf(), Some(&raw_key("01"))).unwrap();
+    history
+        .add_entry("secret".to_string(), EntryDetails::default())
+        .unwrap();
+    drop(history);
+
+    assert!(!is_plaintext(&dir.join("history.db")));
+    assert!(HistoryStorage::open(dir.to_path_buf(), None).is_err());
+    assert!(HistoryStorage::open(dir.to_path_buf(), Some(&raw_key("02"))).is_err());
+
+    let reopened = HistoryStorage::open(dir.to_path_buf(), Some(&raw_key("01"))).unwrap();
+    assert_eq!(reopened.get_all(None).unwrap()[0].text, "secret");
+}
+
+#[test]
+fn test_plaintext_history_is_encrypted_on_open() {
+    let dir = TempDir::new();
+    let history = HistoryStorage::open(dir.to_path_buf(), None).unwrap();
+    history
+        .add_entry("kept".to_string(), EntryDetails::default())
+        .unwrap();
+    drop(history);
+    assert!(is_plaintext(&dir.join("history.db")));
+
+    let encrypted = HistoryStorage::open(dir.to_path_buf(), Some(&raw_key("01"))).unwrap();
+    assert!(!is_plaintext(&dir.join("history.db")));
+    assert_eq!(encrypted.get_all(None).unwrap()[0].text, "kept");
+
+    // And back to plaintext, keeping the schema version
+    encrypted.set_key(None).unwrap();
+    assert!(is_plaintext(&dir.join("history.db")));
+    assert_eq!(encrypted.get_all(None).unwrap()[0].text, "kept");
+    drop(encrypted);
+    let reopened = HistoryStorage::open(dir.to_path_buf(), None).unwrap();
+    assert_eq!(reopened.get_all(None).unwrap().len(), 1);
+}
+
+#[test]
+fn test_locked_history_opens_with_passphrase() {
+    let dir = TempDir::new();
+    let passphrase = HistoryKey::Passphrase("correct horse".to_string());
+    let history = HistoryStorage::open(dir.to_path_buf(), None).unwrap();
+    history.set_key(Some(&passphrase)).unwrap();
+    history
+        .add_entry("hello".to_string(), EntryDetails::default())
+        .unwrap();
+    drop(history);
+
+    let locked = HistoryStorage::locked(dir.to_path_buf());
+    assert!(locked.is_locked());
+    assert_eq!(locked.get_all(None).unwrap_err(), LOCKED_ERROR);
+    assert!(locked
+        .unlock(&HistoryKey::Passphrase("wrong".to_string()))
+        .is_err());
+    assert!(locked.is_locked());
+
+    locked.unlock(&passphrase).unwrap();
+    assert!(!locked.is_locked());
+    assert_eq!(locked.get_all(None).unwrap()[0].text, "hello");
+}
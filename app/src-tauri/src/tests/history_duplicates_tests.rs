@@ -0,0 +1,65 @@
+use crate::history::{EntryDetails, HistoryEntry};
+use crate::history_duplicates::{collapse, similarity, DEFAULT_DUPLICATE_THRESHOLD};
+
+fn entries(texts: &[&str]) -> Vec<HistoryEntry> {
+    texts
+        .iter()
+        .map(|text| HistoryEntry::new(text.to_string(), EntryDetails::default()))
+        .collect()
+}
+
+#[test]
+fn test_similarity_ignores_case_punctuation_and_spacing() {
+    assert_eq!(similarity("Send the report.", "send   the report"), 1.0);
+    assert_eq!(similarity("", "  "), 1.0);
+    assert_eq!(similarity("abc", "xyz"), 0.0);
+
+    let retry = similarity(
+        "Please send the quarterly report to Anna",
+        "Please send the quarterly report to Ana",
+    );
+    assert!(retry > DEFAULT_DUPLICATE_THRESHOLD && retry < 1.0);
+    assert!(similarity("Send the report", "Book a meeting room") < 0.5);
+}
+
+#[test]
+fn test_collapse_groups_consecutive_near_duplicates() {
+    let collapsed = collapse(
+        entries(&[
+            "Please send the quarterly report to Ana",
+            "Please send the quarterly report to Anna",
+            "please send the quarterly report to anna!",
+            "Book a meeting room",
+            "Please send the quarterly report to Ana",
+        ]),
+        DEFAULT_DUPLICATE_THRESHOLD,
+    );
+
+    assert_eq!(collapsed.len(), 3);
+    assert_eq!(collapsed[0].duplicates.len(), 2);
+    assert_eq!(
+        collapsed[0].duplicates[1].text,
+        "please send the quarterly report to anna!"
+    );
+    assert!(collapsed[1].duplicates.is_empty());
+    // Only consecutive entries are collapsed
+    assert!(collapsed[2].duplicates.is_empty());
+}
+
+#[test]
+fn test_collapse_uses_revised_text_and_can_be_turned_off() {
+    let mut list = entries(&["first attempt", "something else"]);
+    list[1].revised_text = Some("First attempt.".to_string());
+
+    assert_eq!(collapse(list.clone(), DEFAULT_DUPLICATE_THRESHOLD).len(), 1);
+    assert_eq!(collapse(list.clone(), 0.0).len(), 2);
+    assert_eq!(collapse(list, 1.5).len(), 2);
+}
+
+#[test]
+fn test_collapsed_entry_serializes_flat() {
+    let collapsed = collapse(entries(&["hello", "hello"]), 1.0);
+    let value = serde_json::to_value(&collapsed[0]).unwrap();
+    assert_eq!(value["text"], "hello");
+    assert_eq!(value["duplicates"].as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,127 @@
+use crate::history::{EntryDetails, HistoryEntry, HistoryRange, HistoryStorage, Session};
+use crate::history_export::{csv_field, render, render_session, ExportFormat};
+use chrono::{Duration, TimeZone, Utc};
+
+fn entry(text: &str, day: u32, hour: u32, details: EntryDetails) -> HistoryEntry {
+    HistoryEntry {
+        id: format!("{}-{}", day, hour),
+        timestamp: Utc.with_ymd_and_hms(2025, 3, day, hour, 5, 0).unwrap(),
+        text: text.to_string(),
+        details,
+        pinned: false,
+        revised_text: None,
+        session_id: None,
+        has_audio: false,
+        tags: Vec::new(),
+        note: None,
+    }
+}
+
+/// Newest first, as history is stored
+fn sample() -> Vec<HistoryEntry> {
+    vec![
+        entry(
+            "Second day, \"quoted\"",
+            2,
+            9,
+            EntryDetails {
+                raw_text: Some("second day quoted".to_string()),
+                duration_seconds: Some(2.5),
+                target_app: Some("Notes".to_string()),
+                ..Default::default()
+            },
+        ),
+        entry("Line one\nLine two", 1, 15, EntryDetails::default()),
+        entry("Morning", 1, 8, EntryDetails::default()),
+    ]
+}
+
+#[test]
+fn test_markdown_groups_by_day_oldest_first() {
+    let markdown = render(&sample(), ExportFormat::Markdown, &Utc).unwrap();
+    assert_eq!(
+        markdown,
+        "# Dictation history\n\
+         \n## 2025-03-01\n\n\
+         - 08:05 Morning\n\
+         - 15:05 Line one\n  Line two\n\
+         \n## 2025-03-02\n\n\
+         - 09:05 Second day, \"quoted\"\n"
+    );
+}
+
+#[test]
+fn test_json_keeps_all_fields() {
+    let json = render(&sample(), ExportFormat::Json, &Utc).unwrap();
+    let parsed: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.len(), 3);
+    assert_eq!(parsed[0]["text"], "Morning");
+    assert_eq!(parsed[2]["raw_text"], "second day quoted");
+    assert_eq!(parsed[2]["target_app"], "Notes");
+}
+
+#[test]
+fn test_csv_rows_are_escaped() {
+    let csv = render(&sample(), ExportFormat::Csv, &Utc).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "timestamp,text,raw_text,stt_provider,llm_provider,duration_seconds,target_app"
+    );
+    assert_eq!(lines[1], "2025-03-01T08:05:00+00:00,Morning,,,,,");
+    assert!(csv.ends_with(
+        "2025-03-02T09:05:00+00:00,\"Second day, \"\"quoted\"\"\",second day quoted,,,2.5,Notes\n"
+    ));
+}
+
+#[test]
+fn test_csv_field_quotes_only_when_needed() {
+    assert_eq!(csv_field("plain"), "plain");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+}
+
+#[test]
+fn test_session_transcript_is_stitched_with_times() {
+    let session = Session {
+        id: "s".to_string(),
+        name: "Standup".to_string(),
+        started_at: Utc.with_ymd_and_hms(2025, 3, 1, 8, 0, 0).unwrap(),
+        ended_at: Some(Utc.with_ymd_and_hms(2025, 3, 1, 16, 0, 0).unwrap()),
+    };
+    let mut revised = entry("Line one\nLine two", 1, 15, EntryDetails::default());
+    revised.revised_text = Some("Line one, line two".to_string());
+    let entries = vec![revised, entry("Morning", 1, 8, EntryDetails::default())];
+
+    assert_eq!(
+        render_session(&session, &entries, &Utc),
+        "# Standup\n\nStarted 2025-03-01 08:00, ended 2025-03-01 16:00\n\n\
+         [08:05:00] Morning\n\
+         [15:05:00] Line one, line two\n"
+    );
+}
+
+#[test]
+fn test_get_range_filters_by_time() {
+    let history = HistoryStorage::in_memory().unwrap();
+    history
+        .add_entry("now".to_string(), EntryDetails::default())
+        .unwrap();
+    let hour_ago = Utc::now() - Duration::hours(1);
+
+    let since_hour_ago = HistoryRange {
+        from: Some(hour_ago),
+        to: None,
+    };
+    let until_hour_ago = HistoryRange {
+        from: None,
+        to: Some(hour_ago),
+    };
+
+    assert_eq!(
+        history.get_range(&HistoryRange::default()).unwrap().len(),
+        1
+    );
+    assert_eq!(history.get_range(&since_hour_ago).unwrap().len(), 1);
+    assert!(history.get_range(&until_hour_ago).unwrap().is_empty());
+}
@@ -0,0 +1,196 @@
+use crate::history::{EntryDetails, HistoryStorage};
+use crate::history_sync::{device_id, merge, read_remote_files, write_own_file, SyncFile};
+use crate::tests::TempDir;
+use chrono::{Duration, Utc};
+use std::fs;
+
+/// The history of another device, as it would be read from the sync folder
+fn snapshot(device_id: &str, history: &HistoryStorage) -> SyncFile {
+    SyncFile {
+        device_id: device_id.to_string(),
+        updated_at: Utc::now(),
+        entries: history.get_all(None).unwrap(),
+        deleted: history.deleted_entries().unwrap(),
+    }
+}
+
+#[test]
+fn test_merge_imports_unseen_entries_with_labels() {
+    let laptop = HistoryStorage::in_memory().unwrap();
+    let entry = laptop
+        .add_entry("from the laptop".to_string(), EntryDetails::default())
+        .unwrap();
+    laptop.set_pinned(&entry.id, true).unwrap();
+    laptop.set_tags(&entry.id, &["email".to_string()]).unwrap();
+    laptop.set_note(&entry.id, Some("send later")).unwrap();
+    laptop
+        .add_revision(&entry.id, "From the laptop.".to_string(), None)
+        .unwrap();
+
+    let desktop = HistoryStorage::in_memory().unwrap();
+    desktop
+        .add_entry("from the desktop".to_string(), EntryDetails::default())
+        .unwrap();
+    let summary = merge(&desktop, &[snapshot("laptop", &laptop)], None).unwrap();
+    assert_eq!(summary.imported, 1);
+    assert_eq!(summary.devices, 1);
+
+    let imported = desktop.get(&entry.id).unwrap().unwrap();
+    assert_eq!(imported.text, "from the laptop");
+    assert!(imported.pinned);
+    assert_eq!(imported.tags, vec!["email"]);
+    assert_eq!(imported.note.as_deref(), Some("send later"));
+    assert_eq!(imported.revised_text.as_deref(), Some("From the laptop."));
+    assert_eq!(desktop.get_all(None).unwrap().len(), 2);
+
+    // Merging the same history again changes nothing
+    let again = merge(&desktop, &[snapshot("laptop", &laptop)], None).unwrap();
+    assert_eq!(again.imported, 0);
+}
+
+#[test]
+fn test_deletions_spread_and_are_not_undone() {
+    let laptop = HistoryStorage::in_memory().unwrap();
+    let kept = laptop
+        .add_entry("kept".to_string(), EntryDetails::default())
+        .unwrap();
+    let removed = laptop
+        .add_entry("removed".to_string(), EntryDetails::default())
+        .unwrap();
+    let desktop = HistoryStorage::in_memory().unwrap();
+    merge(&desktop, &[snapshot("laptop", &laptop)], None).unwrap();
+
+    // Deleted on the desktop, so the laptop's copy must not bring it back
+    assert!(desktop.delete(&removed.id).unwrap());
+    merge(&desktop, &[snapshot("laptop", &laptop)], None).unwrap();
+    assert!(desktop.get(&removed.id).unwrap().is_none());
+
+    // Other devices keep it while it can still be restored from the trash
+    let summary = merge(&laptop, &[snapshot("desktop", &desktop)], None).unwrap();
+    assert_eq!(summary.deleted, 0);
+
+    desktop.empty_trash().unwrap();
+    let summary = merge(&laptop, &[snapshot("desktop", &desktop)], None).unwrap();
+    assert_eq!(summary.deleted, 1);
+    assert!(laptop.get(&removed.id).unwrap().is_none());
+    assert!(laptop.get(&kept.id).unwrap().is_some());
+}
+
+#[test]
+fn test_emptied_trash_is_remembered_for_sync() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let entry = history
+        .add_entry("gone".to_string(), EntryDetails::default())
+        .unwrap();
+    history.clear().unwrap();
+    assert!(history.deleted_entries().unwrap().is_empty());
+
+    history.empty_trash().unwrap();
+    let deleted = history.deleted_entries().unwrap();
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].id, entry.id);
+}
+
+#[test]
+fn test_merge_skips_unpinned_entries_past_retention() {
+    let laptop = HistoryStorage::in_memory().unwrap();
+    laptop
+        .add_entry("recent".to_string(), EntryDetails::default())
+        .unwrap();
+    let desktop = HistoryStorage::in_memory().unwrap();
+
+    let cutoff = Some(Utc::now() + Duration::hours(1));
+    let summary = merge(&desktop, &[snapshot("laptop", &laptop)], cutoff).unwrap();
+    assert_eq!(summary.imported, 0);
+    assert!(desktop.get_all(None).unwrap().is_empty());
+}
+
+#[test]
+fn test_files_round_trip_and_skip_own() {
+    let sync_dir = TempDir::new();
+    let laptop = HistoryStorage::in_memory().unwrap();
+    laptop
+        .add_entry("hello".to_string(), EntryDetails::default())
+        .unwrap();
+    write_own_file(&sync_dir, "laptop", &laptop).unwrap();
+    write_own_file(&sync_dir, "desktop", &HistoryStorage::in_memory().unwrap()).unwrap();
+    fs::write(sync_dir.join("broken.json"), "not json").unwrap();
+
+    let remote = read_remote_files(&sync_dir, "desktop");
+    assert_eq!(remote.len(), 1);
+    assert_eq!(remote[0].device_id, "laptop");
+    assert_eq!(remote[0].entries[0].text, "hello");
+}
+
+#[test]
+fn test_device_id_is_kept() {
+    let dir = TempDir::new();
+    let id = device_id(&dir).unwrap();
+    assert!(!id.is_empty());
+    assert_eq!(device_id(&dir).unwrap(), id);
+}
+
+#[test]
+fn test_pruned_entries_are_not_imported_again_or_deleted_elsewhere() {
+    let laptop = HistoryStorage::in_memory().unwrap();
+    for text in ["first", "second", "third"] {
+        laptop
+            .add_entry(text.to_string(), EntryDetails::default())
+            .unwrap();
+    }
+    let desktop = HistoryStorage::in_memory().unwrap();
+    merge(&desktop, &[snapshot("laptop", &laptop)], None).unwrap();
+
+    assert_eq!(desktop.delete_beyond_count(1).unwrap(), 2);
+    let summary = merge(&desktop, &[snapshot("laptop", &laptop)], None).unwrap();
+    assert_eq!(summary.imported, 0);
+    assert_eq!(desktop.get_all(None).unwrap().len(), 1);
+
+    // Pruning follows this device's limits, so the laptop keeps its entries
+    assert!(desktop.deleted_entries().unwrap().is_empty());
+    let summary = merge(&laptop, &[snapshot("desktop", &desktop)], None).unwrap();
+    assert_eq!(summary.deleted, 0);
+    assert_eq!(laptop.get_all(None).unwrap().len(), 3);
+}
+
+#[test]
+fn test_imported_entries_are_not_dictated_here() {
+    let laptop = HistoryStorage::in_memory().unwrap();
+    laptop
+        .add_entry("from the laptop".to_string(), EntryDetails::default())
+        .unwrap();
+    let desktop = HistoryStorage::in_memory().unwrap();
+    let own = desktop
+        .add_entry("from the desktop".to_string(), EntryDetails::default())
+        .unwrap();
+    merge(&desktop, &[snapshot("laptop", &laptop)], None).unwrap();
+
+    assert_eq!(desktop.get_all(None).unwrap().len(), 2);
+    let dictated = desktop.get_dictated(None).unwrap();
+    assert_eq!(dictated.len(), 1);
+    assert_eq!(dictated[0].id, own.id);
+}
+
+#[test]
+fn test_old_deletions_are_forgotten() {
+    let history = HistoryStorage::in_memory().unwrap();
+    history
+        .add_entry("gone".to_string(), EntryDetails::default())
+        .unwrap();
+    history.clear().unwrap();
+    history.empty_trash().unwrap();
+
+    assert_eq!(
+        history
+            .forget_deletions_before(Utc::now() - Duration::days(1))
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        history
+            .forget_deletions_before(Utc::now() + Duration::days(1))
+            .unwrap(),
+        1
+    );
+    assert!(history.deleted_entries().unwrap().is_empty());
+}
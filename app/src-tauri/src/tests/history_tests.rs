@@ -0,0 +1,531 @@
+use crate::history::{
+    like_pattern, normalize_tag, retention_cutoff, AppCount, EntryDetails, HistoryLimits,
+    HistoryRange, HistoryStorage, PruneSummary, TagCount,
+};
+use crate::tests::TempDir;
+use chrono::{Duration, TimeZone, Utc};
+use std::fs;
+
+#[test]
+fn test_entries_are_listed_newest_first_with_details() {
+    let history = HistoryStorage::in_memory().unwrap();
+    history
+        .add_entry("first".to_string(), EntryDetails::default())
+        .unwrap();
+    let details = EntryDetails {
+        raw_text: Some("um second".to_string()),
+        stt_provider: Some("deepgram".to_string()),
+        llm_provider: Some("openai".to_string()),
+        duration_seconds: Some(2.5),
+        target_app: Some("Notes".to_string()),
+        latency_seconds: Some(0.8),
+    };
+    history
+        .add_entry("second".to_string(), details.clone())
+        .unwrap();
+
+    let entries = history.get_all(None).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].text, "second");
+    assert_eq!(entries[0].details, details);
+    assert_eq!(history.get_all(Some(1)).unwrap().len(), 1);
+}
+
+#[test]
+fn test_search_matches_cleaned_and_raw_text() {
+    let history = HistoryStorage::in_memory().unwrap();
+    history
+        .add_entry("Meeting at noon".to_string(), EntryDetails::default())
+        .unwrap();
+    history
+        .add_entry(
+            "Buy milk".to_string(),
+            EntryDetails {
+                raw_text: Some("uh buy some milk".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    assert_eq!(history.search("meeting", None).unwrap().len(), 1);
+    assert_eq!(
+        history.search("some milk", None).unwrap()[0].text,
+        "Buy milk"
+    );
+    assert!(history.search("_", None).unwrap().is_empty());
+}
+
+#[test]
+fn test_like_pattern_escapes_wildcards() {
+    assert_eq!(like_pattern("50%_off"), "%50\\%\\_off%");
+}
+
+#[test]
+fn test_delete_and_clear() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let entry = history
+        .add_entry("hello".to_string(), EntryDetails::default())
+        .unwrap();
+    history
+        .add_entry("world".to_string(), EntryDetails::default())
+        .unwrap();
+
+    assert!(history.delete(&entry.id).unwrap());
+    assert!(!history.delete(&entry.id).unwrap());
+    assert_eq!(history.get_all(None).unwrap().len(), 1);
+
+    history.clear().unwrap();
+    assert!(history.get_all(None).unwrap().is_empty());
+}
+
+#[test]
+fn test_deleted_entries_wait_in_the_trash() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let details = EntryDetails {
+        target_app: Some("Notes".to_string()),
+        ..Default::default()
+    };
+    let entry = history
+        .add_entry("draft".to_string(), details.clone())
+        .unwrap();
+    history.set_tags(&entry.id, &["work".to_string()]).unwrap();
+    let other = history
+        .add_entry("other".to_string(), EntryDetails::default())
+        .unwrap();
+
+    assert!(history.delete(&entry.id).unwrap());
+    // Trashed entries are left out of listings, search, tags and apps
+    assert!(history.get(&entry.id).unwrap().is_none());
+    assert!(history.search("draft", None).unwrap().is_empty());
+    assert!(history.list_tags().unwrap().is_empty());
+    assert!(history
+        .list_apps(&HistoryRange::default())
+        .unwrap()
+        .is_empty());
+
+    let trashed = history.trashed().unwrap();
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0].entry.id, entry.id);
+
+    // Restoring brings it back with its labels
+    assert!(history.restore(&entry.id).unwrap());
+    assert!(!history.restore(&entry.id).unwrap());
+    let restored = history.get(&entry.id).unwrap().unwrap();
+    assert_eq!(restored.tags, ["work"]);
+    assert!(history.trashed().unwrap().is_empty());
+
+    // Only entries in the trash can be removed for good
+    assert!(!history.purge_entry(&other.id).unwrap());
+    history.delete(&other.id).unwrap();
+    assert!(history.purge_entry(&other.id).unwrap());
+    assert!(history.trashed().unwrap().is_empty());
+    assert_eq!(history.get_all(None).unwrap().len(), 1);
+}
+
+#[test]
+fn test_trash_expires() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let entry = history
+        .add_entry("old".to_string(), EntryDetails::default())
+        .unwrap();
+    history
+        .add_entry("kept".to_string(), EntryDetails::default())
+        .unwrap();
+    history.clear().unwrap();
+    assert_eq!(history.trashed().unwrap().len(), 2);
+
+    let trashed_at = history.trashed().unwrap()[0].trashed_at;
+    assert_eq!(history.purge_trashed_before(trashed_at).unwrap(), 0);
+    assert_eq!(
+        history
+            .purge_trashed_before(trashed_at + Duration::seconds(1))
+            .unwrap(),
+        2
+    );
+    assert!(!history.restore(&entry.id).unwrap());
+
+    history
+        .add_entry("new".to_string(), EntryDetails::default())
+        .unwrap();
+    history.clear().unwrap();
+    assert_eq!(history.empty_trash().unwrap(), 1);
+    assert!(history.trashed().unwrap().is_empty());
+}
+
+#[test]
+fn test_retention_cutoff() {
+    let now = Utc.with_ymd_and_hms(2025, 6, 10, 12, 0, 0).unwrap();
+    assert_eq!(retention_cutoff(None, now), None);
+    assert_eq!(retention_cutoff(Some(0), now), None);
+    assert_eq!(
+        retention_cutoff(Some(7), now),
+        Some(now - Duration::days(7))
+    );
+}
+
+#[test]
+fn test_delete_before_removes_only_older_entries() {
+    let history = HistoryStorage::in_memory().unwrap();
+    history
+        .add_entry("recent".to_string(), EntryDetails::default())
+        .unwrap();
+
+    assert_eq!(
+        history
+            .delete_before(Utc::now() - Duration::days(1))
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        history
+            .delete_before(Utc::now() + Duration::days(1))
+            .unwrap(),
+        1
+    );
+}
+
+#[test]
+fn test_pinned_entries_survive_retention() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let kept = history
+        .add_entry("keep me".to_string(), EntryDetails::default())
+        .unwrap();
+    history
+        .add_entry("drop me".to_string(), EntryDetails::default())
+        .unwrap();
+
+    assert!(history.set_pinned(&kept.id, true).unwrap());
+    assert!(!history.set_pinned("missing", true).unwrap());
+    assert_eq!(
+        history
+            .delete_before(Utc::now() + Duration::days(1))
+            .unwrap(),
+        1
+    );
+
+    let pinned = history.get_pinned().unwrap();
+    assert_eq!(pinned.len(), 1);
+    assert_eq!(pinned[0].text, "keep me");
+    assert!(pinned[0].pinned);
+
+    history.set_pinned(&kept.id, false).unwrap();
+    assert!(history.get_pinned().unwrap().is_empty());
+}
+
+#[test]
+fn test_prune_by_count_keeps_newest_and_pinned() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let oldest = history
+        .add_entry("oldest".to_string(), EntryDetails::default())
+        .unwrap();
+    for text in ["older", "newer", "newest"] {
+        // Distinct millisecond timestamps keep the order well defined
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        history
+            .add_entry(text.to_string(), EntryDetails::default())
+            .unwrap();
+    }
+    history.set_pinned(&oldest.id, true).unwrap();
+
+    let limits = HistoryLimits {
+        max_entries: Some(2),
+        ..Default::default()
+    };
+    let summary = history.prune(&limits, Utc::now()).unwrap();
+    assert_eq!(summary.by_count, 1);
+    assert_eq!(summary.total(), 1);
+
+    let texts: Vec<String> = history
+        .get_all(None)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.text)
+        .collect();
+    assert_eq!(texts, ["newest", "newer", "oldest"]);
+}
+
+#[test]
+fn test_prune_by_age_and_without_limits() {
+    let history = HistoryStorage::in_memory().unwrap();
+    history
+        .add_entry("old".to_string(), EntryDetails::default())
+        .unwrap();
+
+    let unlimited = history
+        .prune(&HistoryLimits::default(), Utc::now())
+        .unwrap();
+    assert_eq!(unlimited, PruneSummary::default());
+
+    let limits = HistoryLimits {
+        max_days: Some(1),
+        max_entries: Some(0),
+        max_disk_mb: None,
+    };
+    let summary = history
+        .prune(&limits, Utc::now() + Duration::days(2))
+        .unwrap();
+    assert_eq!(summary.by_age, 1);
+    assert!(history.get_all(None).unwrap().is_empty());
+}
+
+#[test]
+fn test_prune_by_size_removes_oldest_until_under_limit() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let text = "word ".repeat(2000);
+    for _ in 0..300 {
+        history
+            .add_entry(text.clone(), EntryDetails::default())
+            .unwrap();
+    }
+    assert!(history.used_bytes().unwrap() > 2 * 1024 * 1024);
+
+    let limits = HistoryLimits {
+        max_disk_mb: Some(1),
+        ..Default::default()
+    };
+    let summary = history.prune(&limits, Utc::now()).unwrap();
+    assert!(summary.by_size > 0);
+    assert!(summary.freed_bytes > 1024 * 1024);
+    assert!(history.used_bytes().unwrap() <= 1024 * 1024);
+    assert_eq!(history.get_all(None).unwrap().len(), 300 - summary.by_size);
+}
+
+#[test]
+fn test_normalize_tag() {
+    assert_eq!(normalize_tag("#Email"), Some("email".to_string()));
+    assert_eq!(normalize_tag("  blog post "), Some("blog-post".to_string()));
+    assert_eq!(normalize_tag("# "), None);
+}
+
+#[test]
+fn test_tags_and_notes_organize_entries() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let email = history
+        .add_entry("Dear Sam".to_string(), EntryDetails::default())
+        .unwrap();
+    let journal = history
+        .add_entry("Slept well".to_string(), EntryDetails::default())
+        .unwrap();
+
+    let stored = history
+        .set_tags(
+            &email.id,
+            &[
+                "#Email".to_string(),
+                "work".to_string(),
+                "email".to_string(),
+            ],
+        )
+        .unwrap();
+    assert_eq!(stored, Some(vec!["email".to_string(), "work".to_string()]));
+    history
+        .set_tags(&journal.id, &["journal".to_string(), "work".to_string()])
+        .unwrap();
+    assert_eq!(history.set_tags("missing", &[]).unwrap(), None);
+
+    assert!(history
+        .set_note(&email.id, Some("  send on Monday "))
+        .unwrap());
+    assert!(!history.set_note("missing", Some("note")).unwrap());
+
+    let entry = history.get(&email.id).unwrap().unwrap();
+    assert_eq!(entry.tags, ["email", "work"]);
+    assert_eq!(entry.note.as_deref(), Some("send on Monday"));
+
+    let tagged = history.get_by_tag("#work", None).unwrap();
+    assert_eq!(tagged.len(), 2);
+    assert_eq!(
+        history.get_by_tag("journal", None).unwrap()[0].id,
+        journal.id
+    );
+    assert_eq!(
+        history.list_tags().unwrap()[0],
+        TagCount {
+            tag: "work".to_string(),
+            entries: 2
+        }
+    );
+
+    // Search looks at notes and tags too
+    assert_eq!(history.search("monday", None).unwrap()[0].id, email.id);
+    assert_eq!(history.search("journ", None).unwrap()[0].id, journal.id);
+
+    // Replacing tags drops the old ones; a blank note clears it
+    history.set_tags(&email.id, &[]).unwrap();
+    history.set_note(&email.id, Some("  ")).unwrap();
+    let entry = history.get(&email.id).unwrap().unwrap();
+    assert!(entry.tags.is_empty());
+    assert_eq!(entry.note, None);
+    assert_eq!(history.list_tags().unwrap().len(), 2);
+}
+
+#[test]
+fn test_entries_filtered_by_app() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let into = |app: Option<&str>| EntryDetails {
+        target_app: app.map(str::to_string),
+        ..Default::default()
+    };
+    let note = history
+        .add_entry("note".to_string(), into(Some("Obsidian")))
+        .unwrap();
+    history
+        .add_entry("mail".to_string(), into(Some("Mail")))
+        .unwrap();
+    history
+        .add_entry("unknown".to_string(), into(None))
+        .unwrap();
+    let later = history
+        .add_entry("later note".to_string(), into(Some("obsidian")))
+        .unwrap();
+
+    let everything = HistoryRange::default();
+    let apps = history.list_apps(&everything).unwrap();
+    assert_eq!(apps.len(), 2);
+    assert_eq!(apps[0].entries, 2);
+    assert_eq!(
+        apps[1],
+        AppCount {
+            app: "Mail".to_string(),
+            entries: 1
+        }
+    );
+
+    let notes = history.get_by_app("OBSIDIAN", &everything, None).unwrap();
+    assert_eq!(notes.len(), 2);
+    assert!(notes.iter().any(|entry| entry.id == note.id));
+    assert!(notes.iter().any(|entry| entry.id == later.id));
+    assert_eq!(
+        history
+            .get_by_app("Obsidian", &everything, Some(1))
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Only entries inside the range are listed
+    let since_later = HistoryRange {
+        from: Some(later.timestamp),
+        to: None,
+    };
+    assert!(history
+        .get_by_app("Obsidian", &since_later, None)
+        .unwrap()
+        .iter()
+        .any(|entry| entry.id == later.id));
+    let before = HistoryRange {
+        from: None,
+        to: Some(note.timestamp - Duration::seconds(1)),
+    };
+    assert!(history
+        .get_by_app("Obsidian", &before, None)
+        .unwrap()
+        .is_empty());
+    assert!(history.list_apps(&before).unwrap().is_empty());
+}
+
+#[test]
+fn test_revisions_are_kept_beside_the_original() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let entry = history
+        .add_entry("helo world".to_string(), EntryDetails::default())
+        .unwrap();
+
+    history
+        .add_revision(
+            &entry.id,
+            "Hello world".to_string(),
+            Some("openai".to_string()),
+        )
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    history
+        .add_revision(&entry.id, "Hello, world.".to_string(), None)
+        .unwrap();
+
+    let revisions = history.get_revisions(&entry.id).unwrap();
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0].text, "Hello, world.");
+    assert_eq!(revisions[1].llm_provider.as_deref(), Some("openai"));
+
+    let stored = history.get(&entry.id).unwrap().unwrap();
+    assert_eq!(stored.text, "helo world");
+    assert_eq!(stored.revised_text.as_deref(), Some("Hello, world."));
+
+    assert!(history
+        .add_revision("missing", "x".to_string(), None)
+        .is_err());
+
+    // Revisions go with their entry
+    history.delete(&entry.id).unwrap();
+    assert!(history.get_revisions(&entry.id).unwrap().is_empty());
+}
+
+#[test]
+fn test_entries_join_the_open_session() {
+    let history = HistoryStorage::in_memory().unwrap();
+    history
+        .add_entry("before".to_string(), EntryDetails::default())
+        .unwrap();
+
+    let session = history.start_session(" Standup ").unwrap();
+    assert_eq!(session.name, "Standup");
+    assert_eq!(history.active_session().unwrap(), Some(session.clone()));
+    let during = history
+        .add_entry("during".to_string(), EntryDetails::default())
+        .unwrap();
+    assert_eq!(during.session_id.as_deref(), Some(session.id.as_str()));
+
+    let ended = history.end_session().unwrap().unwrap();
+    assert!(ended.ended_at.is_some());
+    assert_eq!(history.active_session().unwrap(), None);
+    assert_eq!(history.end_session().unwrap(), None);
+    history
+        .add_entry("after".to_string(), EntryDetails::default())
+        .unwrap();
+
+    let entries = history.session_entries(&session.id).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].text, "during");
+    assert_eq!(history.get_session(&session.id).unwrap(), Some(ended));
+}
+
+#[test]
+fn test_starting_a_session_ends_the_open_one() {
+    let history = HistoryStorage::in_memory().unwrap();
+    let first = history.start_session("First").unwrap();
+    let second = history.start_session("Second").unwrap();
+
+    let sessions = history.list_sessions().unwrap();
+    assert_eq!(sessions.len(), 2);
+    assert!(history
+        .get_session(&first.id)
+        .unwrap()
+        .unwrap()
+        .ended_at
+        .is_some());
+    assert_eq!(history.active_session().unwrap(), Some(second));
+}
+
+#[test]
+fn test_legacy_json_history_is_imported_once() {
+    let dir = TempDir::new();
+    fs::write(
+        dir.join("history.json"),
+        r#"{"entries":[{"id":"a","timestamp":"2025-01-01T10:00:00Z","text":"old entry"}]}"#,
+    )
+    .unwrap();
+
+    let history = HistoryStorage::open(dir.to_path_buf(), None).unwrap();
+    let entries = history.get_all(None).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].text, "old entry");
+    assert_eq!(entries[0].details, EntryDetails::default());
+    assert!(!dir.join("history.json").exists());
+    assert!(!dir.join("history.json.imported").exists());
+    drop(history);
+
+    // Entries survive reopening the database
+    let reopened = HistoryStorage::open(dir.to_path_buf(), None).unwrap();
+    assert_eq!(reopened.get_all(None).unwrap().len(), 1);
+}
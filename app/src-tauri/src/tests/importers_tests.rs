@@ -0,0 +1,136 @@
+use crate::importers::{
+    detect_source, merge_terms, parse, parse_dragon, parse_superwhisper, parse_talon,
+    with_instructions, ImportSource,
+};
+use serde_json::json;
+
+fn terms(list: &[&str]) -> Vec<String> {
+    list.iter().map(|term| term.to_string()).collect()
+}
+
+#[test]
+fn test_detect_source() {
+    assert_eq!(
+        detect_source("vocabulary.talon-list", ""),
+        Some(ImportSource::Talon)
+    );
+    assert_eq!(
+        detect_source("additional_words.csv", ""),
+        Some(ImportSource::Talon)
+    );
+    assert_eq!(
+        detect_source("Mode.JSON", ""),
+        Some(ImportSource::Superwhisper)
+    );
+    assert_eq!(
+        detect_source("words.txt", "Kubernetes\n"),
+        Some(ImportSource::Dragon)
+    );
+    assert_eq!(
+        detect_source("words.txt", "list: user.vocabulary\n-\n"),
+        Some(ImportSource::Talon)
+    );
+    assert_eq!(
+        detect_source("export", "{\"vocabulary\": []}"),
+        Some(ImportSource::Superwhisper)
+    );
+    assert_eq!(detect_source("export", "Kubernetes"), None);
+}
+
+#[test]
+fn test_talon_list_takes_written_forms() {
+    let content = "list: user.vocabulary\n\
+                   -\n\
+                   # names\n\
+                   nmap: N map\n\
+                   under documented: \"under-documented\"\n\
+                   Tambourine\n\
+                   \n";
+    assert_eq!(
+        parse_talon(content).vocabulary,
+        terms(&["N map", "under-documented", "Tambourine"])
+    );
+}
+
+#[test]
+fn test_talon_csv_skips_header() {
+    let content =
+        "Word,Spoken Form (If Different)\nKubernetes,cube\n\"PostgreSQL\",post gres\nTambourine\n";
+    assert_eq!(
+        parse_talon(content).vocabulary,
+        terms(&["Kubernetes", "PostgreSQL", "Tambourine"])
+    );
+}
+
+#[test]
+fn test_dragon_word_list_drops_spoken_forms() {
+    let content = "\u{feff}Kubernetes\r\nk8s\\kates\r\n\r\nkubernetes\r\n";
+    let imported = parse(ImportSource::Dragon, content).unwrap();
+    assert_eq!(imported.vocabulary, terms(&["Kubernetes", "k8s"]));
+    assert_eq!(parse_dragon("").vocabulary, Vec::<String>::new());
+}
+
+#[test]
+fn test_superwhisper_collects_nested_vocabulary_and_prompt() {
+    let content = json!({
+        "name": "Email",
+        "prompt": "  Write in a friendly tone.  ",
+        "settings": {
+            "vocabulary": ["Tambourine", { "word": "Pipecat" }, 3],
+            "customVocabulary": "LLM, Groq\nDeepgram",
+        },
+    })
+    .to_string();
+    let imported = parse_superwhisper(&content).unwrap();
+    let mut vocabulary = imported.vocabulary.clone();
+    vocabulary.sort();
+    assert_eq!(
+        vocabulary,
+        terms(&["Deepgram", "Groq", "LLM", "Pipecat", "Tambourine"])
+    );
+    assert_eq!(
+        imported.instructions.as_deref(),
+        Some("Write in a friendly tone.")
+    );
+    assert!(parse_superwhisper("not json").is_err());
+}
+
+#[test]
+fn test_merge_terms_skips_existing_ignoring_case() {
+    let (merged, added) = merge_terms(
+        &terms(&["Tambourine", "LLM"]),
+        &terms(&["llm", " Pipecat ", "", "Tambourine", "Groq"]),
+    );
+    assert_eq!(merged, terms(&["Tambourine", "LLM", "Pipecat", "Groq"]));
+    assert_eq!(added, 2);
+}
+
+#[test]
+fn test_with_instructions_fills_empty_advanced_section() {
+    let sections = with_instructions(None, "Be brief.").unwrap();
+    assert_eq!(
+        sections["advanced"],
+        json!({ "enabled": true, "content": "Be brief." })
+    );
+    assert_eq!(sections["main"]["content"], json!(null));
+
+    let existing = json!({
+        "main": { "enabled": true, "content": "Custom main" },
+        "advanced": { "enabled": false, "content": null },
+        "dictionary": { "enabled": true, "content": null },
+    });
+    let sections = with_instructions(Some(&existing), "Be brief.").unwrap();
+    assert_eq!(sections["main"]["content"], json!("Custom main"));
+    assert_eq!(sections["advanced"]["content"], json!("Be brief."));
+    assert_eq!(sections["dictionary"]["enabled"], json!(true));
+}
+
+#[test]
+fn test_with_instructions_keeps_custom_advanced_section() {
+    let existing = json!({
+        "main": { "enabled": true, "content": null },
+        "advanced": { "enabled": true, "content": "My rules" },
+        "dictionary": { "enabled": false, "content": null },
+    });
+    assert_eq!(with_instructions(Some(&existing), "Be brief."), None);
+}
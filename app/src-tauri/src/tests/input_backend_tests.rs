@@ -0,0 +1,75 @@
+use crate::input_backend::{detect_display_server, split_typeable, with_connection, DisplayServer};
+use std::cell::RefCell;
+
+#[test]
+fn test_session_type_decides_display_server() {
+    assert_eq!(
+        detect_display_server(Some("wayland"), None, Some(":0")),
+        Some(DisplayServer::Wayland)
+    );
+    assert_eq!(
+        detect_display_server(Some("X11"), Some("wayland-0"), Some(":0")),
+        Some(DisplayServer::X11)
+    );
+}
+
+#[test]
+fn test_display_variables_used_without_session_type() {
+    // XWayland sets DISPLAY too, so a Wayland socket wins
+    assert_eq!(
+        detect_display_server(Some("tty"), Some("wayland-0"), Some(":0")),
+        Some(DisplayServer::Wayland)
+    );
+    assert_eq!(
+        detect_display_server(None, Some(""), Some(":0")),
+        Some(DisplayServer::X11)
+    );
+    assert_eq!(detect_display_server(None, None, None), None);
+}
+
+#[test]
+fn test_split_typeable_keeps_order_of_runs() {
+    let runs = split_typeable("café ☕ ok", |c| c.is_ascii());
+    assert_eq!(
+        runs,
+        vec![
+            (true, "caf"),
+            (false, "é"),
+            (true, " "),
+            (false, "☕"),
+            (true, " ok")
+        ]
+    );
+    assert_eq!(split_typeable("plain", |_| true), vec![(true, "plain")]);
+    assert!(split_typeable("", |_| true).is_empty());
+}
+
+#[test]
+fn test_connection_is_reused_until_a_send_fails() {
+    let cached = RefCell::new(None);
+    let mut connections = 0;
+    let mut connect = || {
+        connections += 1;
+        Ok(connections)
+    };
+
+    assert_eq!(with_connection(&cached, &mut connect, |c| Ok(*c)), Ok(1));
+    assert_eq!(with_connection(&cached, &mut connect, |c| Ok(*c)), Ok(1));
+    assert_eq!(
+        with_connection(&cached, &mut connect, |_| Err::<(), _>(
+            "Closed".to_string()
+        )),
+        Err("Closed".to_string())
+    );
+    assert_eq!(with_connection(&cached, &mut connect, |c| Ok(*c)), Ok(2));
+}
+
+#[test]
+fn test_failed_connect_is_retried() {
+    let cached: RefCell<Option<u32>> = RefCell::new(None);
+    assert_eq!(
+        with_connection(&cached, || Err("No backend".to_string()), |c| Ok(*c)),
+        Err("No backend".to_string())
+    );
+    assert_eq!(with_connection(&cached, || Ok(7), |c| Ok(*c)), Ok(7));
+}
@@ -0,0 +1,83 @@
+use crate::history::{EntryDetails, HistoryEntry};
+use crate::journal::{day_range, journal_path, pending_days, render, render_dictations};
+use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+use std::path::Path;
+
+fn entry(text: &str, hour: u32) -> HistoryEntry {
+    HistoryEntry {
+        id: hour.to_string(),
+        timestamp: Utc.with_ymd_and_hms(2025, 3, 7, hour, 5, 0).unwrap(),
+        text: text.to_string(),
+        details: EntryDetails::default(),
+        pinned: false,
+        revised_text: None,
+        session_id: None,
+        has_audio: false,
+        tags: Vec::new(),
+        note: None,
+    }
+}
+
+fn day(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+}
+
+#[test]
+fn test_day_range_spans_local_midnights() {
+    let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+    let range = day_range(day(7), &tz);
+    assert_eq!(
+        range.from,
+        Some(Utc.with_ymd_and_hms(2025, 3, 6, 22, 0, 0).unwrap())
+    );
+    assert_eq!(
+        range.to,
+        Some(Utc.with_ymd_and_hms(2025, 3, 7, 22, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_pending_days_skips_today_and_existing_journals() {
+    let pending = pending_days(day(10), |date| date == day(8));
+    assert_eq!(
+        pending,
+        vec![day(3), day(4), day(5), day(6), day(7), day(9)]
+    );
+}
+
+#[test]
+fn test_dictations_are_listed_oldest_first_with_revisions() {
+    let mut revised = entry("Call Sam\nabout lunch", 15);
+    revised.revised_text = Some("Call Sam\nabout dinner".to_string());
+    let entries = vec![revised, entry("Buy milk.", 8)];
+    assert_eq!(
+        render_dictations(&entries, &Utc),
+        "- 08:05 Buy milk.\n- 15:05 Call Sam\n  about dinner\n"
+    );
+}
+
+#[test]
+fn test_render_heads_journal_with_summary() {
+    let dictations = "- 08:05 Buy milk.\n";
+    assert_eq!(
+        render(day(7), dictations, None),
+        "# Journal 2025-03-07\n\n- 08:05 Buy milk.\n"
+    );
+    assert_eq!(
+        render(day(7), dictations, Some(" Errands. \n")),
+        "# Journal 2025-03-07\n\n## Summary\n\nErrands.\n\n## Dictations\n\n- 08:05 Buy milk.\n"
+    );
+    // A blank summary is left out
+    assert_eq!(
+        render(day(7), dictations, Some("  ")),
+        render(day(7), dictations, None)
+    );
+}
+
+#[test]
+fn test_journal_path_is_named_by_date() {
+    assert_eq!(
+        journal_path(Path::new("/notes"), day(7)),
+        Path::new("/notes/2025-03-07.md")
+    );
+}
@@ -0,0 +1,14 @@
+use crate::live_transcript::LiveTranscript;
+
+#[test]
+fn test_live_transcript_keeps_latest_text() {
+    let transcript = LiveTranscript::default();
+    assert_eq!(transcript.text(), "");
+
+    transcript.set("Hello".to_string());
+    transcript.set("Hello world".to_string());
+    assert_eq!(transcript.text(), "Hello world");
+
+    transcript.set(String::new());
+    assert_eq!(transcript.text(), "");
+}
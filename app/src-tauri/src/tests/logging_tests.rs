@@ -0,0 +1,54 @@
+use crate::logging::{recent_lines, LogLevel, RotatingFile};
+use crate::tests::TempDir;
+use log::LevelFilter;
+use std::fs;
+
+#[test]
+fn test_log_level_serialization() {
+    assert_eq!(
+        serde_json::to_string(&LogLevel::Debug).unwrap(),
+        "\"debug\""
+    );
+    assert_eq!(LogLevel::default().to_level_filter(), LevelFilter::Info);
+}
+
+#[test]
+fn test_rotation_keeps_limited_files() {
+    let dir = TempDir::new();
+    let path = dir.join("test.log");
+    let mut file = RotatingFile::open(path.clone(), 20, 2).unwrap();
+    for index in 0..5 {
+        file.write_line(&format!("line number {}", index)).unwrap();
+    }
+    file.flush().unwrap();
+
+    assert!(path.exists());
+    assert!(path.with_extension("log.1").exists());
+    assert!(path.with_extension("log.2").exists());
+    assert!(!path.with_extension("log.3").exists());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "line number 4\n");
+}
+
+#[test]
+fn test_recent_lines_reads_across_rotated_files() {
+    let dir = TempDir::new();
+    let path = dir.join("test.log");
+    let mut file = RotatingFile::open(path.clone(), 20, 3).unwrap();
+    for index in 0..4 {
+        file.write_line(&format!("line number {}", index)).unwrap();
+    }
+    file.flush().unwrap();
+
+    assert_eq!(
+        recent_lines(&path, 3, 2),
+        vec!["line number 2".to_string(), "line number 3".to_string()]
+    );
+    assert_eq!(recent_lines(&path, 3, 100).len(), 4);
+}
+
+#[test]
+fn test_recent_lines_missing_file() {
+    let dir = TempDir::new();
+    let path = dir.join("test.log");
+    assert!(recent_lines(&path, 3, 10).is_empty());
+}
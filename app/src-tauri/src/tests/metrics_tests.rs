@@ -0,0 +1,69 @@
+use crate::metrics::{AnalyticsMode, MetricEvent, MetricsRecorder};
+use crate::tests::TempDir;
+
+#[test]
+fn test_analytics_off_records_nothing() {
+    let dir = TempDir::new();
+    let recorder = MetricsRecorder::new(dir.to_path_buf());
+    recorder
+        .record(AnalyticsMode::Off, MetricEvent::RecordingStarted)
+        .unwrap();
+
+    assert_eq!(recorder.local_metrics().unwrap().recordings_started, 0);
+    assert!(!dir.join("metrics.json").exists());
+}
+
+#[test]
+fn test_local_only_records() {
+    let dir = TempDir::new();
+    let recorder = MetricsRecorder::new(dir.to_path_buf());
+    recorder
+        .record(
+            AnalyticsMode::LocalOnly,
+            MetricEvent::DictationCompleted { words: 12 },
+        )
+        .unwrap();
+
+    let metrics = recorder.local_metrics().unwrap();
+    assert_eq!(metrics.dictations_completed, 1);
+    assert_eq!(metrics.words_dictated, 12);
+    assert!(metrics.since.is_some());
+}
+
+#[test]
+fn test_metrics_persist_across_instances() {
+    let dir = TempDir::new();
+    let recorder = MetricsRecorder::new(dir.to_path_buf());
+    recorder
+        .record(AnalyticsMode::LocalOnly, MetricEvent::RecordingStarted)
+        .unwrap();
+
+    let reloaded = MetricsRecorder::new(dir.to_path_buf());
+    assert_eq!(reloaded.local_metrics().unwrap().recordings_started, 1);
+}
+
+#[test]
+fn test_clear_resets_metrics() {
+    let dir = TempDir::new();
+    let recorder = MetricsRecorder::new(dir.to_path_buf());
+    recorder
+        .record(AnalyticsMode::LocalOnly, MetricEvent::RecordingStarted)
+        .unwrap();
+    recorder.clear().unwrap();
+
+    assert_eq!(recorder.local_metrics().unwrap().recordings_started, 0);
+}
+
+#[test]
+fn test_switching_mode_off_stops_recording() {
+    let dir = TempDir::new();
+    let recorder = MetricsRecorder::new(dir.to_path_buf());
+    recorder
+        .record(AnalyticsMode::LocalOnly, MetricEvent::RecordingStarted)
+        .unwrap();
+    recorder
+        .record(AnalyticsMode::Off, MetricEvent::RecordingStarted)
+        .unwrap();
+
+    assert_eq!(recorder.local_metrics().unwrap().recordings_started, 1);
+}
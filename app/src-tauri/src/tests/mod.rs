@@ -1,3 +1,92 @@
+mod automation_api_tests;
+mod browser_extension_tests;
+mod calendar_tests;
+mod cli_tests;
+mod clipboard_cleanup_tests;
+mod deep_link_tests;
+mod email_draft_tests;
+mod error_tests;
+mod event_stream_tests;
+mod failed_dictations_tests;
+mod file_transcription_tests;
+mod fullscreen_tests;
+mod history_audio_tests;
+mod history_crypto_tests;
+mod history_duplicates_tests;
+mod history_export_tests;
+mod history_sync_tests;
+mod history_tests;
 mod hotkey_config_tests;
+mod importers_tests;
+mod input_backend_tests;
+mod journal_tests;
+mod live_transcript_tests;
+mod logging_tests;
+mod metrics_tests;
+mod mqtt_tests;
+mod notifications_tests;
+mod obs_captions_tests;
+mod obsidian_tests;
+mod onboarding_tests;
+mod output_file_tests;
+mod output_sinks_tests;
+mod overlay_auto_hide_tests;
+mod overlay_state_tests;
+mod overlay_tests;
+mod paste_options_tests;
+mod paste_ring_tests;
+mod paste_target_tests;
+mod paste_undo_tests;
+mod pipeline_tests;
+mod quota_tests;
+mod recording_progress_tests;
+mod recording_recovery_tests;
+mod recording_state_tests;
+mod remote_control_tests;
+mod rich_text_tests;
 mod settings_commands_tests;
+mod settings_manager_tests;
 mod shortcut_tests;
+mod shutdown_tests;
+mod single_instance_tests;
+mod smart_insert_tests;
+mod snippets_tests;
+mod stats_tests;
+mod stream_deck_tests;
+mod tray_menu_tests;
+mod tray_status_tests;
+mod vocabulary_tests;
+mod webhooks_tests;
+
+use std::path::{Path, PathBuf};
+
+/// A fresh directory under the system temp directory, deleted when dropped
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    pub(crate) fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("tambourine-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl std::ops::Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::mqtt::{discovery_messages, parse_broker, state_name, Broker};
+use crate::overlay_state::OverlayState;
+
+#[test]
+fn test_parse_broker_urls() {
+    assert_eq!(
+        parse_broker("mqtt://homeassistant.local:1884"),
+        Ok(Broker {
+            host: "homeassistant.local".to_string(),
+            port: 1884,
+            tls: false,
+        })
+    );
+    assert_eq!(parse_broker("192.168.1.5").unwrap().port, 1883);
+    let tls = parse_broker("mqtts://broker.example.com/").unwrap();
+    assert_eq!(
+        (tls.host.as_str(), tls.port, tls.tls),
+        ("broker.example.com", 8883, true)
+    );
+}
+
+#[test]
+fn test_parse_broker_rejects_bad_urls() {
+    assert!(parse_broker("http://broker:1883").is_err());
+    assert!(parse_broker("mqtt://broker:port").is_err());
+    assert!(parse_broker("mqtt://").is_err());
+}
+
+#[test]
+fn test_state_names() {
+    assert_eq!(state_name(&OverlayState::Recording), "recording");
+    assert_eq!(
+        state_name(&OverlayState::Error {
+            message: "Server unreachable".to_string()
+        }),
+        "error"
+    );
+}
+
+#[test]
+fn test_discovery_points_at_prefixed_topics() {
+    let messages = discovery_messages("home/office-mic");
+    let (topic, config) = &messages[0];
+    assert_eq!(
+        topic,
+        "homeassistant/binary_sensor/home_office_mic/recording/config"
+    );
+    let config: serde_json::Value = serde_json::from_str(config).unwrap();
+    assert_eq!(config["state_topic"], "home/office-mic/recording");
+    assert_eq!(config["availability_topic"], "home/office-mic/availability");
+    assert_eq!(config["unique_id"], "home_office_mic_recording");
+    assert_eq!(config["icon"], "mdi:microphone");
+}
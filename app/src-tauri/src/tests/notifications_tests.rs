@@ -0,0 +1,45 @@
+use crate::notifications::{snippet, NotificationKind};
+use crate::settings::NotificationSettings;
+
+#[test]
+fn test_snippet_keeps_short_text() {
+    assert_eq!(
+        snippet("Send the email to John.", 80),
+        "Send the email to John."
+    );
+}
+
+#[test]
+fn test_snippet_truncates_on_one_line() {
+    let text = "First line\nsecond line that goes on";
+    assert_eq!(snippet(text, 17), "First line second…");
+}
+
+#[test]
+fn test_notification_settings_default_off() {
+    let settings = NotificationSettings::default();
+    assert!(!settings.allows(NotificationKind::TranscriptionComplete));
+    assert!(!settings.allows(NotificationKind::ProviderError));
+    assert!(!settings.allows(NotificationKind::QuotaWarning));
+    // Held pastes are always announced
+    assert!(settings.allows(NotificationKind::PasteHeld));
+}
+
+#[test]
+fn test_copied_follows_transcription_complete_toggle() {
+    let settings = NotificationSettings {
+        transcription_complete: true,
+        ..Default::default()
+    };
+    assert!(settings.allows(NotificationKind::Copied));
+    assert!(!NotificationSettings::default().allows(NotificationKind::Copied));
+    assert!(settings.allows(NotificationKind::Saved));
+}
+
+#[test]
+fn test_notification_settings_partial_json() {
+    let settings: NotificationSettings =
+        serde_json::from_value(serde_json::json!({ "quota_warnings": true })).unwrap();
+    assert!(settings.allows(NotificationKind::QuotaWarning));
+    assert!(!settings.allows(NotificationKind::ProviderError));
+}
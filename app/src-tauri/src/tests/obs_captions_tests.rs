@@ -0,0 +1,78 @@
+use crate::obs_captions::{auth_response, caption_tail, identify_message, request_message};
+use serde_json::json;
+
+#[test]
+fn test_caption_tail_keeps_short_text() {
+    assert_eq!(
+        caption_tail("  Hello   there\nworld ", 40),
+        "Hello there world"
+    );
+    assert_eq!(caption_tail("", 40), "");
+}
+
+#[test]
+fn test_caption_tail_cuts_at_word_boundary() {
+    let text = "one two three four five six";
+    assert_eq!(caption_tail(text, 12), "five six");
+    assert_eq!(caption_tail(text, 9), "five six");
+    // A single word longer than the caption is cut rather than dropped
+    assert_eq!(caption_tail("supercalifragilistic", 5), "istic");
+}
+
+#[test]
+fn test_auth_response_matches_protocol_example() {
+    // Example from the obs-websocket 5 protocol documentation
+    assert_eq!(
+        auth_response(
+            "supersecretpassword",
+            "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+            "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY=",
+        ),
+        "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+    );
+}
+
+#[test]
+fn test_identify_without_authentication() {
+    let hello = json!({ "op": 0, "d": { "obsWebSocketVersion": "5.5.0", "rpcVersion": 1 } });
+    assert_eq!(
+        identify_message(&hello, None).unwrap(),
+        json!({ "op": 1, "d": { "rpcVersion": 1, "eventSubscriptions": 0 } })
+    );
+}
+
+#[test]
+fn test_identify_with_authentication() {
+    let hello = json!({
+        "op": 0,
+        "d": {
+            "rpcVersion": 1,
+            "authentication": {
+                "challenge": "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY=",
+                "salt": "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+            },
+        },
+    });
+    let identify = identify_message(&hello, Some("supersecretpassword")).unwrap();
+    assert_eq!(
+        identify["d"]["authentication"],
+        json!("1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4=")
+    );
+    assert!(identify_message(&hello, None).is_err());
+    assert!(identify_message(&hello, Some("")).is_err());
+}
+
+#[test]
+fn test_request_message() {
+    assert_eq!(
+        request_message("1", "SendStreamCaption", json!({ "captionText": "Hi" })),
+        json!({
+            "op": 6,
+            "d": {
+                "requestType": "SendStreamCaption",
+                "requestId": "1",
+                "requestData": { "captionText": "Hi" },
+            },
+        })
+    );
+}
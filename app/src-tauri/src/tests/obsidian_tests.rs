@@ -0,0 +1,43 @@
+use crate::obsidian::{format_entry, frontmatter, note_path};
+use chrono::{Local, TimeZone};
+use std::path::Path;
+
+#[test]
+fn test_note_path_stays_in_the_vault() {
+    let now = Local.with_ymd_and_hms(2025, 3, 7, 9, 5, 0).unwrap();
+    let vault = Path::new("/vault");
+    assert_eq!(
+        note_path(vault, "Inbox/{date}", now).unwrap(),
+        vault.join("Inbox/2025-03-07.md")
+    );
+    assert_eq!(
+        note_path(vault, "Dictations.markdown", now).unwrap(),
+        vault.join("Dictations.markdown")
+    );
+
+    assert!(note_path(vault, "../outside", now).is_err());
+    assert!(note_path(vault, "/etc/notes", now).is_err());
+    assert!(note_path(vault, "  ", now).is_err());
+}
+
+#[test]
+fn test_frontmatter_lists_tags() {
+    let now = Local.with_ymd_and_hms(2025, 3, 7, 9, 5, 0).unwrap();
+    assert_eq!(
+        frontmatter(&["#dictation".to_string(), " ".to_string()], now),
+        "---\ncreated: 2025-03-07T09:05\ntags:\n  - dictation\n---\n\n"
+    );
+    assert_eq!(
+        frontmatter(&[], now),
+        "---\ncreated: 2025-03-07T09:05\n---\n\n"
+    );
+}
+
+#[test]
+fn test_format_entry_links_the_daily_note() {
+    let now = Local.with_ymd_and_hms(2025, 3, 7, 14, 30, 0).unwrap();
+    assert_eq!(
+        format_entry("Buy milk.\nCall Sam.\n", now),
+        "- 14:30 Buy milk.\n  Call Sam. [[2025-03-07]]\n"
+    );
+}
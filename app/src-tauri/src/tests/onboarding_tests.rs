@@ -0,0 +1,42 @@
+use crate::onboarding::{OnboardingProgress, OnboardingStep};
+
+#[test]
+fn test_new_progress_starts_at_mic_permission() {
+    let state = OnboardingProgress::default().state();
+    assert_eq!(state.current_step, Some(OnboardingStep::MicPermission));
+    assert!(!state.is_complete);
+}
+
+#[test]
+fn test_complete_steps_in_order() {
+    let mut progress = OnboardingProgress::default();
+    for step in OnboardingStep::ALL {
+        progress.complete(step).unwrap();
+    }
+    let state = progress.state();
+    assert!(state.is_complete);
+    assert_eq!(state.current_step, None);
+    assert_eq!(state.completed_steps, OnboardingStep::ALL.to_vec());
+}
+
+#[test]
+fn test_complete_out_of_order_is_rejected() {
+    let mut progress = OnboardingProgress::default();
+    assert!(progress.complete(OnboardingStep::HotkeyTest).is_err());
+    assert!(progress.completed_steps.is_empty());
+}
+
+#[test]
+fn test_complete_same_step_twice_is_noop() {
+    let mut progress = OnboardingProgress::default();
+    progress.complete(OnboardingStep::MicPermission).unwrap();
+    progress.complete(OnboardingStep::MicPermission).unwrap();
+    assert_eq!(progress.completed_steps.len(), 1);
+    assert_eq!(progress.current_step(), Some(OnboardingStep::ProviderSetup));
+}
+
+#[test]
+fn test_step_serializes_snake_case() {
+    let json = serde_json::to_string(&OnboardingStep::ProviderSetup).unwrap();
+    assert_eq!(json, "\"provider_setup\"");
+}
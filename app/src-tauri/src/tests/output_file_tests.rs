@@ -0,0 +1,31 @@
+use crate::output_file::{expand_path, format_entry};
+use chrono::{Local, TimeZone};
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_expand_path_fills_date_and_home() {
+    let now = Local.with_ymd_and_hms(2025, 3, 7, 9, 5, 0).unwrap();
+    let home = Path::new("/home/ada");
+    assert_eq!(
+        expand_path("~/notes/{date}.md", now, Some(home)),
+        home.join("notes/2025-03-07.md")
+    );
+    assert_eq!(
+        expand_path("/journal/{year}/{month}-{day}.md", now, Some(home)),
+        PathBuf::from("/journal/2025/03-07.md")
+    );
+    // Only a leading "~/" means the home directory
+    assert_eq!(
+        expand_path("~notes.md", now, Some(home)),
+        PathBuf::from("~notes.md")
+    );
+}
+
+#[test]
+fn test_format_entry_keeps_lines_in_one_item() {
+    let now = Local.with_ymd_and_hms(2025, 3, 7, 14, 30, 0).unwrap();
+    assert_eq!(
+        format_entry("Buy milk.\nCall Sam.\n", now),
+        "- 14:30 Buy milk.\n  Call Sam.\n"
+    );
+}
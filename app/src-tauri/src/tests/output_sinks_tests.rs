@@ -0,0 +1,95 @@
+use crate::output_sinks::{script_payload, Dictation, ScriptSink};
+
+fn sink(apps: &[&str]) -> ScriptSink {
+    ScriptSink {
+        name: "Jira comment".to_string(),
+        command: "jira-comment".to_string(),
+        apps: apps.iter().map(|app| app.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_script_sink_defaults_from_partial_json() {
+    let sink: ScriptSink = serde_json::from_str(r#"{"command": "notify.sh"}"#).unwrap();
+    assert!(sink.enabled);
+    assert!(!sink.replaces_paste);
+    assert!(sink.args.is_empty());
+}
+
+#[test]
+fn test_script_sink_applies_to_listed_apps() {
+    assert!(sink(&[]).applies_to(None));
+    assert!(sink(&["Firefox"]).applies_to(Some("firefox")));
+    assert!(!sink(&["Firefox"]).applies_to(Some("Slack")));
+    assert!(!sink(&["Firefox"]).applies_to(None));
+}
+
+#[test]
+fn test_disabled_or_empty_script_sink_never_applies() {
+    let disabled = ScriptSink {
+        enabled: false,
+        ..sink(&[])
+    };
+    assert!(!disabled.applies_to(None));
+    assert!(!ScriptSink::default().applies_to(None));
+}
+
+#[test]
+fn test_script_payload() {
+    let dictation = Dictation {
+        text: "Looks good, \"ship it\"".to_string(),
+        target_app: Some("Firefox".to_string()),
+        profile: None,
+    };
+    let payload: serde_json::Value =
+        serde_json::from_str(&script_payload(&dictation, "2026-06-10T09:00:00Z")).unwrap();
+    assert_eq!(payload["text"], "Looks good, \"ship it\"");
+    assert_eq!(payload["target_app"], "Firefox");
+    assert_eq!(payload["timestamp"], "2026-06-10T09:00:00Z");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_script_sink_run_reports_failures() {
+    let dictation = Dictation {
+        text: "Hello".to_string(),
+        ..Default::default()
+    };
+    let script = |script: &str| ScriptSink {
+        command: "sh".to_string(),
+        args: vec!["-c".to_string(), script.to_string()],
+        ..Default::default()
+    };
+    assert!(
+        script(r#"test "$TAMBOURINE_TEXT" = Hello && grep -q Hello"#)
+            .run(&dictation)
+            .is_ok()
+    );
+    let error = script("echo broken >&2; exit 3")
+        .run(&dictation)
+        .unwrap_err();
+    assert!(error.contains("broken"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_script_sink_run_drains_large_output() {
+    // More than a pipe buffer in both directions, without reading stdin
+    let dictation = Dictation {
+        text: "word ".repeat(20_000),
+        ..Default::default()
+    };
+    let sink = ScriptSink {
+        command: "sh".to_string(),
+        args: vec![
+            "-c".to_string(),
+            "head -c 1000000 /dev/zero | tr '\\0' x >&2; exit 1".to_string(),
+        ],
+        ..Default::default()
+    };
+    let started = std::time::Instant::now();
+    let error = sink.run(&dictation).unwrap_err();
+    assert!(error.contains("xxx"));
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+}
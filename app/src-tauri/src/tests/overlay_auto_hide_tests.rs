@@ -0,0 +1,28 @@
+use crate::overlay_auto_hide::OverlayAutoHide;
+
+#[test]
+fn test_restart_supersedes_running_countdown() {
+    let auto_hide = OverlayAutoHide::default();
+    let first = auto_hide.restart();
+    assert!(auto_hide.is_current(first));
+
+    let second = auto_hide.restart();
+    assert!(!auto_hide.is_current(first));
+    assert!(auto_hide.is_current(second));
+}
+
+#[test]
+fn test_cancel_stops_countdown() {
+    let auto_hide = OverlayAutoHide::default();
+    let generation = auto_hide.restart();
+    auto_hide.cancel();
+    assert!(!auto_hide.is_current(generation));
+}
+
+#[test]
+fn test_hover_state() {
+    let auto_hide = OverlayAutoHide::default();
+    assert!(!auto_hide.hovered());
+    auto_hide.set_hovered(true);
+    assert!(auto_hide.hovered());
+}
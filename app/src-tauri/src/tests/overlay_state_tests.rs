@@ -0,0 +1,38 @@
+use crate::overlay_state::{OverlayState, OverlayStateMachine};
+
+#[test]
+fn test_overlay_state_follows_pipeline() {
+    let machine = OverlayStateMachine::default();
+    assert!(machine.transition(OverlayState::Recording));
+    assert!(machine.transition(OverlayState::Transcribing));
+    assert!(machine.transition(OverlayState::Cleaning));
+    assert!(machine.transition(OverlayState::Pasting));
+    assert!(machine.transition(OverlayState::Idle));
+    assert_eq!(machine.current(), OverlayState::Idle);
+}
+
+#[test]
+fn test_overlay_state_drops_out_of_order_transitions() {
+    let machine = OverlayStateMachine::default();
+    // A late cleanup message after the dictation already finished
+    assert!(!machine.transition(OverlayState::Cleaning));
+    assert!(machine.transition(OverlayState::Recording));
+    // Repeats (e.g. reported by both the hotkey handler and the overlay) are ignored
+    assert!(!machine.transition(OverlayState::Recording));
+    assert!(!machine.transition(OverlayState::Pasting));
+    assert_eq!(machine.current(), OverlayState::Recording);
+}
+
+#[test]
+fn test_overlay_state_error_is_always_allowed() {
+    let machine = OverlayStateMachine::default();
+    let error = OverlayState::Error {
+        message: "No response from server".to_string(),
+    };
+    assert!(machine.transition(error.clone()));
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        serde_json::json!({ "state": "error", "message": "No response from server" })
+    );
+    assert!(machine.transition(OverlayState::Recording));
+}
@@ -0,0 +1,188 @@
+use crate::edge_indicator::{edge_rect, EDGE_THICKNESS};
+use crate::overlay::{AnchorPoint, MonitorBounds};
+use crate::settings::{
+    EdgeIndicator, OverlayAnchor, OverlayAppearance, OverlayLayout, OverlayMonitor, OverlaySize,
+    SettingsManager,
+};
+
+fn secondary_monitor() -> MonitorBounds {
+    MonitorBounds {
+        x: 1920.0,
+        y: 0.0,
+        width: 1280.0,
+        height: 800.0,
+    }
+}
+
+#[test]
+fn test_default_overlay_center_is_on_its_monitor() {
+    let bounds = secondary_monitor();
+    let (x, y) = bounds.default_overlay_center();
+    assert!(bounds.contains(x, y));
+    assert_eq!((x, y), (1920.0 + 1280.0 - 126.0, 800.0 - 76.0));
+}
+
+#[test]
+fn test_contains_excludes_other_monitors() {
+    let bounds = secondary_monitor();
+    assert!(!bounds.contains(1919.0, 400.0));
+    assert!(!bounds.contains(3200.0, 400.0));
+    assert!(bounds.contains(1920.0, 0.0));
+}
+
+#[test]
+fn test_overlay_monitor_serialization() {
+    assert_eq!(
+        serde_json::to_value(OverlayMonitor::default()).unwrap(),
+        serde_json::json!({ "mode": "follow_focus" })
+    );
+    let fixed: OverlayMonitor =
+        serde_json::from_value(serde_json::json!({ "mode": "fixed", "monitor": "DELL U2720Q" }))
+            .unwrap();
+    assert_eq!(fixed, OverlayMonitor::Fixed("DELL U2720Q".to_string()));
+}
+
+#[test]
+fn test_overlay_positions_are_machine_local() {
+    assert!(SettingsManager::is_machine_local("overlay_positions"));
+    assert!(!SettingsManager::is_machine_local("overlay_monitor"));
+}
+
+#[test]
+fn test_anchor_window_origin_keeps_anchored_point_fixed() {
+    let anchor = AnchorPoint {
+        point: (1000.0, 700.0),
+        alignment: (0.5, 1.0),
+    };
+    // Bottom-center stays at the anchor whatever the size
+    assert_eq!(anchor.window_origin(48.0, 48.0), (976.0, 652.0));
+    assert_eq!(anchor.window_origin(200.0, 80.0), (900.0, 620.0));
+}
+
+#[test]
+fn test_preset_anchors() {
+    let bounds = secondary_monitor();
+    let top_right = bounds.preset_anchor(OverlayAnchor::TopRight).unwrap();
+    assert_eq!(top_right.point, (1920.0 + 1280.0 - 24.0, 24.0));
+    assert_eq!(top_right.alignment, (1.0, 0.0));
+
+    let bottom_center = bounds.preset_anchor(OverlayAnchor::BottomCenter).unwrap();
+    assert_eq!(bottom_center.point, (1920.0 + 640.0, 800.0 - 24.0));
+
+    assert!(bounds.preset_anchor(OverlayAnchor::Free).is_none());
+    assert!(bounds.preset_anchor(OverlayAnchor::NearCursor).is_none());
+}
+
+#[test]
+fn test_near_cursor_anchor_flips_at_edges() {
+    let bounds = secondary_monitor();
+
+    let middle = bounds.near_cursor_anchor((2500.0, 400.0), 100.0, 48.0);
+    assert_eq!(middle.alignment, (0.0, 0.0));
+    assert_eq!(middle.point, (2516.0, 416.0));
+
+    let corner = bounds.near_cursor_anchor((3190.0, 790.0), 100.0, 48.0);
+    assert_eq!(corner.alignment, (1.0, 1.0));
+    assert_eq!(corner.point, (3174.0, 774.0));
+}
+
+#[test]
+fn test_overlay_anchor_serialization() {
+    assert_eq!(
+        serde_json::to_value(OverlayAnchor::NearCaret).unwrap(),
+        serde_json::json!("near_caret")
+    );
+    assert!(secondary_monitor()
+        .preset_anchor(OverlayAnchor::NearCaret)
+        .is_none());
+}
+
+#[test]
+fn test_physical_origin_uses_target_monitor_scale() {
+    // Bottom-right anchor on a 4K monitor at 2x: logical (1896, 1056)
+    let anchor = AnchorPoint {
+        point: (1896.0, 1056.0),
+        alignment: (1.0, 1.0),
+    };
+    let origin = anchor.physical_origin(200.0, 48.0, 2.0);
+    assert_eq!((origin.x, origin.y), (3392, 2016));
+
+    // The same logical window size is half as many pixels at 1x
+    let origin = anchor.physical_origin(200.0, 48.0, 1.0);
+    assert_eq!((origin.x, origin.y), (1696, 1008));
+}
+
+#[test]
+fn test_edge_indicator_rect_hugs_chosen_edge() {
+    let bounds = secondary_monitor();
+    assert_eq!(edge_rect(&bounds, EdgeIndicator::Off), None);
+    assert_eq!(
+        edge_rect(&bounds, EdgeIndicator::Top),
+        Some((1920.0, 0.0, 1280.0, EDGE_THICKNESS))
+    );
+    assert_eq!(
+        edge_rect(&bounds, EdgeIndicator::Right),
+        Some((3200.0 - EDGE_THICKNESS, 0.0, EDGE_THICKNESS, 800.0))
+    );
+}
+
+#[test]
+fn test_overlay_appearance_validation() {
+    assert!(OverlayAppearance::default().validate().is_ok());
+
+    let bad_color = OverlayAppearance {
+        accent_color: "red".to_string(),
+        ..OverlayAppearance::default()
+    };
+    assert!(bad_color.validate().is_err());
+
+    let too_faint = OverlayAppearance {
+        opacity: 0.05,
+        ..OverlayAppearance::default()
+    };
+    assert!(too_faint.validate().is_err());
+}
+
+#[test]
+fn test_overlay_appearance_fills_missing_fields() {
+    let appearance: OverlayAppearance =
+        serde_json::from_value(serde_json::json!({ "size": "large" })).unwrap();
+    assert_eq!(appearance.size, OverlaySize::Large);
+    assert_eq!(appearance.accent_color, "#eeeeee");
+}
+
+#[test]
+fn test_overlay_layout_min_size() {
+    assert_eq!(OverlayLayout::default(), OverlayLayout::Pill);
+    assert_eq!(OverlayLayout::Pill.min_size(), (48.0, 48.0));
+
+    let (panel_width, panel_height) = OverlayLayout::Panel.min_size();
+    assert!(panel_width > 48.0 && panel_height > 48.0);
+    assert_eq!(
+        serde_json::to_value(OverlayLayout::Panel).unwrap(),
+        serde_json::json!("panel")
+    );
+}
+
+#[test]
+fn test_clamp_origin_keeps_window_on_monitor() {
+    let bounds = MonitorBounds {
+        x: 0.0,
+        y: 0.0,
+        width: 1280.0,
+        height: 720.0,
+    };
+    // Already inside: unchanged
+    assert_eq!(
+        bounds.clamp_origin((100.0, 100.0), 48.0, 48.0),
+        (100.0, 100.0)
+    );
+    // Left behind by a resolution drop from 1920x1080
+    assert_eq!(
+        bounds.clamp_origin((1800.0, 1000.0), 48.0, 48.0),
+        (1232.0, 672.0)
+    );
+    assert_eq!(bounds.clamp_origin((-30.0, -10.0), 48.0, 48.0), (0.0, 0.0));
+    // Larger than the monitor: pinned to the top-left
+    assert_eq!(bounds.clamp_origin((50.0, 50.0), 2000.0, 48.0), (0.0, 50.0));
+}
@@ -0,0 +1,185 @@
+use crate::commands::text::{
+    check_paste, jittered_delay, refuses_field, should_collapse_selection, typing_delay,
+    DeliveryError, PasteCheck, PasteOptions,
+};
+use crate::settings::{
+    find_app_profile, AppOutputProfile, InsertMode, PasteFormat, PasteKeystroke, PasteMethod,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[test]
+fn test_app_profile_overrides_paste_method() {
+    let profile = AppOutputProfile {
+        paste_method: Some(PasteMethod::SimulateTyping),
+        ..Default::default()
+    };
+    let options = PasteOptions::default().with_profile(&profile);
+    assert_eq!(options.method, PasteMethod::SimulateTyping);
+    assert!(options.restore_clipboard);
+    assert_eq!(options.format, PasteFormat::Plain);
+
+    // An empty profile keeps the global settings
+    let options = PasteOptions::default().with_profile(&AppOutputProfile::default());
+    assert_eq!(options, PasteOptions::default());
+}
+
+#[test]
+fn test_find_app_profile_ignores_case() {
+    let mut profiles = HashMap::new();
+    profiles.insert(
+        "WindowsTerminal.exe".to_string(),
+        AppOutputProfile {
+            paste_method: Some(PasteMethod::SimulateTyping),
+            ..Default::default()
+        },
+    );
+    assert!(find_app_profile(&profiles, "windowsterminal.exe").is_some());
+    assert!(find_app_profile(&profiles, "Code.exe").is_none());
+}
+
+#[test]
+fn test_app_profile_fills_missing_fields() {
+    let profile: AppOutputProfile = serde_json::from_str("{}").unwrap();
+    assert_eq!(profile.paste_method, None);
+    assert_eq!(profile.paste_format, None);
+}
+
+#[test]
+fn test_app_profile_overrides_paste_format() {
+    let profile: AppOutputProfile =
+        serde_json::from_value(serde_json::json!({ "paste_format": "rich" })).unwrap();
+    let options = PasteOptions::default().with_profile(&profile);
+    assert_eq!(options.format, PasteFormat::Rich);
+    assert_eq!(options.method, PasteMethod::ClipboardPaste);
+}
+
+#[test]
+fn test_typing_delay_from_rate() {
+    assert_eq!(typing_delay(None), None);
+    assert_eq!(typing_delay(Some(0)), None);
+    assert_eq!(typing_delay(Some(50)), Some(Duration::from_millis(20)));
+}
+
+#[test]
+fn test_insert_mode_collapses_known_selection() {
+    let paste = PasteMethod::ClipboardPaste;
+    assert!(should_collapse_selection(
+        paste,
+        InsertMode::Insert,
+        Some(true)
+    ));
+    assert!(!should_collapse_selection(
+        paste,
+        InsertMode::Insert,
+        Some(false)
+    ));
+    // Unknown selection is left alone
+    assert!(!should_collapse_selection(paste, InsertMode::Insert, None));
+}
+
+#[test]
+fn test_replace_mode_and_copy_only_keep_selection() {
+    assert!(!should_collapse_selection(
+        PasteMethod::ClipboardPaste,
+        InsertMode::ReplaceSelection,
+        Some(true)
+    ));
+    assert!(!should_collapse_selection(
+        PasteMethod::CopyOnly,
+        InsertMode::Insert,
+        Some(true)
+    ));
+}
+
+#[test]
+fn test_app_profile_overrides_paste_keystroke() {
+    let profile: AppOutputProfile =
+        serde_json::from_value(serde_json::json!({ "paste_keystroke": "ctrl_shift_v" })).unwrap();
+    let options = PasteOptions::default().with_profile(&profile);
+    assert_eq!(options.keystroke, PasteKeystroke::CtrlShiftV);
+    assert_eq!(PasteOptions::default().keystroke, PasteKeystroke::CtrlV);
+}
+
+#[test]
+fn test_app_template_prefixes_lines_then_wraps() {
+    let profile = AppOutputProfile {
+        line_prefix: Some("> ".to_string()),
+        template: Some("Quote:\n{text}".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        profile.apply_template("First line\nsecond line"),
+        "Quote:\n> First line\n> second line"
+    );
+}
+
+#[test]
+fn test_app_template_without_placeholder_is_ignored() {
+    let profile = AppOutputProfile {
+        template: Some("// ".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(profile.apply_template("note"), "note");
+    assert_eq!(AppOutputProfile::default().apply_template("note"), "note");
+}
+
+#[test]
+fn test_paste_missed_only_when_every_read_is_unchanged() {
+    let unchanged = Some("Dear team,".to_string());
+    assert_eq!(
+        check_paste("Dear team,", &[unchanged.clone(), unchanged.clone()]),
+        PasteCheck::Missed
+    );
+    // Apps that update the field late show the paste on a later read
+    assert_eq!(
+        check_paste(
+            "Dear team,",
+            &[unchanged.clone(), Some("team, hello".to_string())]
+        ),
+        PasteCheck::Arrived
+    );
+}
+
+#[test]
+fn test_paste_unconfirmed_when_a_read_fails() {
+    let unchanged = Some("Dear team,".to_string());
+    assert_eq!(
+        check_paste("Dear team,", &[unchanged, None]),
+        PasteCheck::Unconfirmed
+    );
+    assert_eq!(check_paste("Dear team,", &[None]), PasteCheck::Unconfirmed);
+    assert_eq!(check_paste("Dear team,", &[]), PasteCheck::Unconfirmed);
+}
+
+#[test]
+fn test_failed_delivery_drops_the_text() {
+    assert!(DeliveryError::Missed.holds_text());
+    assert!(DeliveryError::Unconfirmed.holds_text());
+    assert!(DeliveryError::SecureField.holds_text());
+    assert!(!DeliveryError::Failed("Clipboard unavailable".to_string()).holds_text());
+}
+
+#[test]
+fn test_jittered_delay_stays_within_range() {
+    let delay = Duration::from_millis(100);
+    assert_eq!(jittered_delay(delay, 0, 0.9), delay);
+    assert_eq!(jittered_delay(delay, 30, 0.5), delay);
+    assert_eq!(jittered_delay(delay, 30, 0.0), Duration::from_millis(70));
+    // Jitter over 100% is capped so a pause never goes negative
+    assert_eq!(jittered_delay(delay, 250, 0.0), Duration::ZERO);
+}
+
+#[test]
+fn test_password_fields_refused_unless_copying() {
+    assert!(refuses_field(PasteMethod::ClipboardPaste, true, Some(true)));
+    assert!(refuses_field(PasteMethod::SimulateTyping, true, Some(true)));
+    assert!(!refuses_field(PasteMethod::CopyOnly, true, Some(true)));
+    assert!(!refuses_field(
+        PasteMethod::ClipboardPaste,
+        false,
+        Some(true)
+    ));
+    // A field that can't be inspected is not refused
+    assert!(!refuses_field(PasteMethod::ClipboardPaste, true, None));
+}
@@ -0,0 +1,51 @@
+use crate::paste_ring::{CycleAction, PasteCycle, CYCLE_WINDOW};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_first_press_pastes_newest() {
+    let cycle = PasteCycle::default();
+    assert_eq!(
+        cycle.next(Instant::now(), 5),
+        CycleAction {
+            index: 0,
+            replace_chars: None
+        }
+    );
+}
+
+#[test]
+fn test_quick_presses_cycle_back_and_wrap() {
+    let cycle = PasteCycle::default();
+    let start = Instant::now();
+    cycle.record(0, start, 12);
+
+    let second = cycle.next(start + Duration::from_millis(500), 3);
+    assert_eq!(
+        second,
+        CycleAction {
+            index: 1,
+            replace_chars: Some(12)
+        }
+    );
+
+    cycle.record(2, start, 4);
+    assert_eq!(cycle.next(start, 3).index, 0);
+}
+
+#[test]
+fn test_slow_press_starts_over() {
+    let cycle = PasteCycle::default();
+    let start = Instant::now();
+    cycle.record(1, start, 12);
+    let later = start + CYCLE_WINDOW + Duration::from_millis(1);
+    assert_eq!(cycle.next(later, 5).index, 0);
+    assert_eq!(cycle.next(later, 5).replace_chars, None);
+}
+
+#[test]
+fn test_single_transcript_never_cycles() {
+    let cycle = PasteCycle::default();
+    let start = Instant::now();
+    cycle.record(0, start, 12);
+    assert_eq!(cycle.next(start, 1).replace_chars, None);
+}
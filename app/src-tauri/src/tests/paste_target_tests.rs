@@ -0,0 +1,55 @@
+use crate::paste_target::{check_target, needs_confirmation, PasteTarget, TargetCheck};
+
+fn target(window: u64) -> PasteTarget {
+    PasteTarget {
+        window,
+        app: Some("Notes".to_string()),
+    }
+}
+
+#[test]
+fn test_same_window_is_ready() {
+    let check = check_target(Some(&target(7)), Some(&target(7)), None, false);
+    assert_eq!(check, TargetCheck::Ready);
+}
+
+#[test]
+fn test_other_window_is_held() {
+    let check = check_target(Some(&target(7)), Some(&target(8)), Some(true), false);
+    assert_eq!(check, TargetCheck::FocusChanged);
+}
+
+#[test]
+fn test_unknown_window_does_not_block() {
+    assert_eq!(
+        check_target(None, Some(&target(8)), None, false),
+        TargetCheck::Ready
+    );
+    assert_eq!(
+        check_target(Some(&target(7)), None, None, false),
+        TargetCheck::Ready
+    );
+}
+
+#[test]
+fn test_text_field_required_only_when_known() {
+    let same = Some(&target(7));
+    assert_eq!(
+        check_target(same, same, Some(false), true),
+        TargetCheck::NoTextField
+    );
+    assert_eq!(check_target(same, same, None, true), TargetCheck::Ready);
+    assert_eq!(
+        check_target(same, same, Some(false), false),
+        TargetCheck::Ready
+    );
+}
+
+#[test]
+fn test_confirmation_only_above_limit() {
+    assert!(!needs_confirmation("short", None));
+    assert!(!needs_confirmation("exactly ten", Some(11)));
+    assert!(needs_confirmation("exactly ten!", Some(11)));
+    // Counted in characters, not bytes
+    assert!(!needs_confirmation("ééé", Some(3)));
+}
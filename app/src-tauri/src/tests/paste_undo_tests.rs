@@ -0,0 +1,24 @@
+use crate::paste_undo::{check_undo, LastPaste};
+
+fn last_paste(text: &str) -> LastPaste {
+    LastPaste {
+        text: text.to_string(),
+        window: Some(7),
+        selectable: true,
+    }
+}
+
+#[test]
+fn test_undo_allowed_right_after_paste() {
+    let last = last_paste("Hello there.");
+    assert!(check_undo(&last, Some(7), Some("Dear Sam, Hello there.")).is_ok());
+    // Unknown window or field doesn't block undo
+    assert!(check_undo(&last, None, None).is_ok());
+}
+
+#[test]
+fn test_undo_refused_after_focus_or_cursor_moved() {
+    let last = last_paste("Hello there.");
+    assert!(check_undo(&last, Some(8), None).is_err());
+    assert!(check_undo(&last, Some(7), Some("Hello there. More")).is_err());
+}
@@ -0,0 +1,162 @@
+use crate::error::AppError;
+use crate::overlay_state::OverlayState;
+use crate::pipeline::{CancellationToken, DictationPipeline, PipelineStage, CANCELLED};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_stage_from_overlay_state() {
+    assert_eq!(
+        PipelineStage::from_overlay_state(&OverlayState::Recording),
+        Some(PipelineStage::Recording)
+    );
+    assert_eq!(
+        PipelineStage::from_overlay_state(&OverlayState::Cleaning),
+        Some(PipelineStage::Cleaning)
+    );
+    assert_eq!(PipelineStage::from_overlay_state(&OverlayState::Idle), None);
+    assert_eq!(
+        PipelineStage::from_overlay_state(&OverlayState::Error {
+            message: "Oops".to_string()
+        }),
+        None
+    );
+}
+
+#[test]
+fn test_child_token_follows_parent() {
+    let parent = CancellationToken::default();
+    let child = parent.child();
+    child.cancel();
+    assert!(!parent.is_cancelled());
+
+    let child = parent.child();
+    parent.cancel();
+    assert!(child.is_cancelled());
+    assert_eq!(child.check(), Err(AppError::Cancelled));
+}
+
+#[test]
+fn test_run_returns_work_result() {
+    let token = CancellationToken::default();
+    assert_eq!(token.run(|| Ok(42)), Ok(42));
+    assert_eq!(
+        token.run(|| Err::<(), _>("Provider failed".to_string())),
+        Err("Provider failed".to_string())
+    );
+}
+
+#[test]
+fn test_run_gives_up_when_cancelled() {
+    let token = CancellationToken::default();
+    let canceller = token.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        canceller.cancel();
+    });
+    let started = Instant::now();
+    let result = token.run(|| {
+        std::thread::sleep(Duration::from_secs(5));
+        Ok(())
+    });
+    assert_eq!(result, Err(CANCELLED.to_string()));
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_run_request_gets_an_id() {
+    let token = CancellationToken::default();
+    let id = token.run_request(|id| Ok(id.to_string())).unwrap();
+    assert_eq!(id.len(), 36);
+    assert_ne!(token.run_request(|id| Ok(id.to_string())), Ok(id));
+}
+
+#[test]
+fn test_cancel_reports_stage_once() {
+    let pipeline = DictationPipeline::default();
+    assert_eq!(pipeline.cancel(), None);
+
+    let id = pipeline.begin();
+    pipeline.advance(PipelineStage::Transcribing);
+    pipeline.advance(PipelineStage::Cleaning);
+    assert_eq!(pipeline.cancel(), Some(PipelineStage::Cleaning));
+    assert!(pipeline.token(Some(id)).is_cancelled());
+    assert_eq!(pipeline.cancel(), None);
+}
+
+#[test]
+fn test_cancelled_run_refuses_late_text() {
+    let pipeline = DictationPipeline::default();
+    let id = pipeline.begin();
+    pipeline.advance(PipelineStage::Transcribing);
+    pipeline.cancel();
+    // The overlay goes idle, but the server's answer may still arrive
+    pipeline.finish();
+    assert!(pipeline.token(Some(id)).is_cancelled());
+
+    // The next dictation has its own token
+    let next = pipeline.begin();
+    assert!(!pipeline.token(Some(next)).is_cancelled());
+    assert!(pipeline.token(Some(id)).is_cancelled());
+}
+
+#[test]
+fn test_finished_run_still_pastes() {
+    let pipeline = DictationPipeline::default();
+    let id = pipeline.begin();
+    pipeline.finish();
+    assert_eq!(pipeline.stage(), None);
+    assert_eq!(pipeline.cancel(), None);
+    assert!(!pipeline.token(Some(id)).is_cancelled());
+}
+
+#[test]
+fn test_new_recording_leaves_earlier_run_alone() {
+    let pipeline = DictationPipeline::default();
+    let first = pipeline.begin();
+    pipeline.advance(PipelineStage::Cleaning);
+    let second = pipeline.begin();
+    assert_ne!(first, second);
+    assert_eq!(pipeline.cancel(), Some(PipelineStage::Recording));
+    assert!(!pipeline.token(Some(first)).is_cancelled());
+    assert!(pipeline.token(Some(second)).is_cancelled());
+}
+
+#[test]
+fn test_text_without_a_run_gets_a_fresh_token() {
+    let pipeline = DictationPipeline::default();
+    pipeline.begin();
+    pipeline.cancel();
+    assert!(!pipeline.token(None).is_cancelled());
+    assert!(!pipeline.token(Some(12345)).is_cancelled());
+}
+
+#[test]
+fn test_overlay_state_does_not_start_runs() {
+    let pipeline = DictationPipeline::default();
+    pipeline.advance(PipelineStage::Recording);
+    assert_eq!(pipeline.stage(), None);
+    assert_eq!(pipeline.cancel(), None);
+}
+
+#[test]
+fn test_shut_down_cancels_everything() {
+    let pipeline = DictationPipeline::default();
+    let run = pipeline.begin();
+    let other = pipeline.shutdown_token();
+    pipeline.shut_down();
+    assert!(pipeline.token(Some(run)).is_cancelled());
+    assert!(other.is_cancelled());
+    let next = pipeline.begin();
+    assert!(pipeline.token(Some(next)).is_cancelled());
+}
+
+#[test]
+fn test_stage_follows_the_current_run() {
+    let pipeline = DictationPipeline::default();
+    assert_eq!(pipeline.stage(), None);
+    pipeline.begin();
+    pipeline.advance(PipelineStage::Pasting);
+    assert_eq!(pipeline.stage(), Some(PipelineStage::Pasting));
+    pipeline.cancel();
+    assert_eq!(pipeline.stage(), None);
+}
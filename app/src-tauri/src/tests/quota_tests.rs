@@ -0,0 +1,110 @@
+use crate::quota::{CloudUse, DailyUsage, QuotaLevel, QuotaLimits, QuotaStatus, QuotaTracker};
+use crate::tests::TempDir;
+use chrono::{Days, Local, NaiveDate};
+
+fn usage(audio_seconds: f64, cloud_requests: u32) -> DailyUsage {
+    DailyUsage {
+        date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        audio_seconds,
+        cloud_requests,
+    }
+}
+
+#[test]
+fn test_no_limits_is_always_ok() {
+    let status = QuotaStatus::evaluate(usage(100_000.0, 10_000), QuotaLimits::default());
+    assert_eq!(status.level, QuotaLevel::Ok);
+}
+
+#[test]
+fn test_warning_when_approaching_audio_limit() {
+    let limits = QuotaLimits {
+        daily_audio_minutes: Some(10.0),
+        daily_cloud_requests: None,
+    };
+    assert_eq!(
+        QuotaStatus::evaluate(usage(7.0 * 60.0, 0), limits).level,
+        QuotaLevel::Ok
+    );
+    assert_eq!(
+        QuotaStatus::evaluate(usage(8.5 * 60.0, 0), limits).level,
+        QuotaLevel::Warning
+    );
+    assert_eq!(
+        QuotaStatus::evaluate(usage(10.0 * 60.0, 0), limits).level,
+        QuotaLevel::Exceeded
+    );
+}
+
+#[test]
+fn test_highest_limit_usage_wins() {
+    let limits = QuotaLimits {
+        daily_audio_minutes: Some(60.0),
+        daily_cloud_requests: Some(5),
+    };
+    let status = QuotaStatus::evaluate(usage(60.0, 5), limits);
+    assert_eq!(status.level, QuotaLevel::Exceeded);
+}
+
+#[test]
+fn test_zero_limit_blocks_everything() {
+    let limits = QuotaLimits {
+        daily_audio_minutes: None,
+        daily_cloud_requests: Some(0),
+    };
+    assert_eq!(
+        QuotaStatus::evaluate(usage(0.0, 0), limits).level,
+        QuotaLevel::Exceeded
+    );
+}
+
+#[test]
+fn test_usage_persists_and_resets_on_new_day() {
+    let dir = TempDir::new();
+    let today = Local::now().date_naive();
+    let yesterday = today.checked_sub_days(Days::new(1)).unwrap();
+
+    let tracker = QuotaTracker::new(dir.to_path_buf());
+    tracker.add(yesterday, 120.0, 3).unwrap();
+    assert_eq!(tracker.usage().unwrap().cloud_requests, 0);
+
+    tracker.add(today, 30.0, 1).unwrap();
+    let reloaded = QuotaTracker::new(dir.to_path_buf());
+    let usage = reloaded.usage().unwrap();
+    assert_eq!(usage.cloud_requests, 1);
+    assert_eq!(usage.audio_seconds, 30.0);
+}
+
+#[test]
+fn test_local_providers_are_not_cloud() {
+    let local = CloudUse::from_providers(Some("whisper"), Some("ollama"));
+    assert!(!local.any());
+    let cloud_cleanup = CloudUse::from_providers(Some("whisper"), Some("openai"));
+    assert!(!cloud_cleanup.stt && cloud_cleanup.llm);
+    // Left to the server, which may pick a cloud provider
+    assert!(CloudUse::from_providers(None, None).stt);
+}
+
+#[test]
+fn test_limits_apply_only_to_cloud_use() {
+    let limits = QuotaLimits {
+        daily_audio_minutes: Some(10.0),
+        daily_cloud_requests: Some(5),
+    };
+    let cloud_cleanup = limits.applying_to(CloudUse::from_providers(Some("whisper"), None));
+    assert_eq!(cloud_cleanup.daily_audio_minutes, None);
+    assert_eq!(cloud_cleanup.daily_cloud_requests, Some(5));
+    let local = limits.applying_to(CloudUse::from_providers(Some("whisper"), Some("ollama")));
+    assert_eq!(local, QuotaLimits::default());
+}
+
+#[test]
+fn test_local_recording_adds_no_usage() {
+    let dir = TempDir::new();
+    let tracker = QuotaTracker::new(dir.to_path_buf());
+    tracker.recording_started();
+    let usage = tracker.recording_stopped(CloudUse::default()).unwrap();
+    assert_eq!(usage.cloud_requests, 0);
+    assert_eq!(usage.audio_seconds, 0.0);
+    assert!(tracker.last_recording_seconds().is_some());
+}
@@ -0,0 +1,41 @@
+use crate::recording_progress::{recording_limit, RecordingProgress};
+use std::time::Duration;
+
+#[test]
+fn test_progress_estimates_audio_size() {
+    let progress = RecordingProgress::new(Duration::from_secs(10), None);
+    assert_eq!(progress.elapsed_seconds, 10.0);
+    assert_eq!(progress.audio_bytes, 320_000);
+    assert!(!progress.near_limit);
+}
+
+#[test]
+fn test_progress_warns_near_limit() {
+    assert!(!RecordingProgress::new(Duration::from_secs(90), Some(120.0)).near_limit);
+    assert!(RecordingProgress::new(Duration::from_secs(105), Some(120.0)).near_limit);
+    assert!(RecordingProgress::new(Duration::from_secs(130), Some(120.0)).near_limit);
+}
+
+#[test]
+fn test_recording_limit_uses_tighter_bound() {
+    assert_eq!(recording_limit(None, None), None);
+    assert_eq!(recording_limit(Some(120), None), Some(120.0));
+    assert_eq!(recording_limit(None, Some(300.0)), Some(300.0));
+    assert_eq!(recording_limit(Some(120), Some(45.0)), Some(45.0));
+    // Quota already used up
+    assert_eq!(recording_limit(Some(120), Some(-30.0)), Some(0.0));
+}
+
+#[test]
+fn test_progress_serialization() {
+    let progress = RecordingProgress::new(Duration::from_secs(1), Some(2.0));
+    assert_eq!(
+        serde_json::to_value(progress).unwrap(),
+        serde_json::json!({
+            "elapsed_seconds": 1.0,
+            "audio_bytes": 32000,
+            "limit_seconds": 2.0,
+            "near_limit": false,
+        })
+    );
+}
@@ -0,0 +1,63 @@
+use crate::recording_recovery::{RecordingRecovery, RECOVERY_DIR};
+use crate::tests::TempDir;
+use std::fs;
+
+#[test]
+fn test_interrupted_recording_is_recovered_once() {
+    let dir = TempDir::new();
+    let recovery = RecordingRecovery::new(dir.to_path_buf());
+    recovery.begin("audio/webm;codecs=opus").unwrap();
+    recovery.append(b"header").unwrap();
+    recovery.append(b"cluster").unwrap();
+
+    // A new run finds what the crashed one wrote
+    let recovery = RecordingRecovery::new(dir.to_path_buf());
+    let recording = recovery.take_interrupted().unwrap().unwrap();
+    assert_eq!(recording.info.mime_type, "audio/webm;codecs=opus");
+    assert_eq!(recording.bytes, b"headercluster");
+    assert_eq!(recovery.take_interrupted().unwrap(), None);
+}
+
+#[test]
+fn test_finished_recording_is_not_recovered() {
+    let dir = TempDir::new();
+    let recovery = RecordingRecovery::new(dir.to_path_buf());
+    recovery.begin("audio/webm").unwrap();
+    recovery.append(b"audio").unwrap();
+    recovery.finish().unwrap();
+    assert_eq!(recovery.take_interrupted().unwrap(), None);
+    // Finishing twice is fine
+    recovery.finish().unwrap();
+}
+
+#[test]
+fn test_recording_without_audio_is_dropped() {
+    let dir = TempDir::new();
+    let recovery = RecordingRecovery::new(dir.to_path_buf());
+    recovery.begin("audio/webm").unwrap();
+    assert_eq!(recovery.take_interrupted().unwrap(), None);
+    assert!(fs::read_dir(dir.join(RECOVERY_DIR))
+        .unwrap()
+        .next()
+        .is_none());
+}
+
+#[test]
+fn test_begin_replaces_the_previous_recording() {
+    let dir = TempDir::new();
+    let recovery = RecordingRecovery::new(dir.to_path_buf());
+    recovery.begin("audio/webm").unwrap();
+    recovery.append(b"old").unwrap();
+    recovery.begin("audio/mp4").unwrap();
+    recovery.append(b"new").unwrap();
+    let recording = recovery.take_interrupted().unwrap().unwrap();
+    assert_eq!(recording.info.mime_type, "audio/mp4");
+    assert_eq!(recording.bytes, b"new");
+}
+
+#[test]
+fn test_append_needs_a_recording() {
+    let dir = TempDir::new();
+    let recovery = RecordingRecovery::new(dir.to_path_buf());
+    assert!(recovery.append(b"audio").is_err());
+}
@@ -0,0 +1,63 @@
+use crate::recording_state::{RecordingLifecycle, RecordingMode, RecordingState};
+use serde_json::json;
+
+const HOLD: RecordingState = RecordingState::Recording {
+    mode: RecordingMode::Hold,
+};
+
+#[test]
+fn test_dictation_moves_through_the_lifecycle() {
+    let lifecycle = RecordingLifecycle::default();
+    assert_eq!(lifecycle.current(), RecordingState::Idle);
+    assert_eq!(lifecycle.transition(HOLD), Ok(RecordingState::Idle));
+    assert!(lifecycle.is_recording());
+    assert_eq!(lifecycle.transition(RecordingState::Transcribing), Ok(HOLD));
+    assert!(!lifecycle.is_recording());
+    assert!(lifecycle.transition(RecordingState::Pasting).is_ok());
+    assert!(lifecycle.transition(RecordingState::Idle).is_ok());
+    assert_eq!(lifecycle.current(), RecordingState::Idle);
+}
+
+#[test]
+fn test_invalid_transitions_are_refused() {
+    let lifecycle = RecordingLifecycle::default();
+    assert!(lifecycle.transition(RecordingState::Transcribing).is_err());
+    assert!(lifecycle.transition(RecordingState::Pasting).is_err());
+    assert!(lifecycle.transition(RecordingState::Idle).is_err());
+
+    lifecycle.transition(HOLD).unwrap();
+    // Starting twice keeps the first recording and its mode
+    assert!(lifecycle
+        .transition(RecordingState::Recording {
+            mode: RecordingMode::Toggle
+        })
+        .is_err());
+    assert_eq!(lifecycle.current(), HOLD);
+    assert!(lifecycle.transition(RecordingState::Pasting).is_err());
+}
+
+#[test]
+fn test_new_recording_can_start_while_the_last_is_on_its_way() {
+    assert!(RecordingState::Transcribing.can_transition_to(&HOLD));
+    assert!(RecordingState::Pasting.can_transition_to(&HOLD));
+    assert!(!RecordingState::Pasting.can_transition_to(&RecordingState::Transcribing));
+}
+
+#[test]
+fn test_any_step_can_end_in_idle() {
+    for state in [HOLD, RecordingState::Transcribing, RecordingState::Pasting] {
+        assert!(state.can_transition_to(&RecordingState::Idle));
+    }
+}
+
+#[test]
+fn test_state_serialization() {
+    assert_eq!(
+        serde_json::to_value(HOLD).unwrap(),
+        json!({ "state": "recording", "mode": "hold" })
+    );
+    assert_eq!(
+        serde_json::to_value(RecordingState::Transcribing).unwrap(),
+        json!({ "state": "transcribing" })
+    );
+}
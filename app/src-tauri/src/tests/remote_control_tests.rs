@@ -0,0 +1,148 @@
+use crate::history::{EntryDetails, HistoryEntry};
+use crate::remote_control::{
+    generate_pairing_code, pairing_backoff, remote_transcript, route, RemoteEndpoint,
+    RemotePairing, RemoteSession, HOLD_TIMEOUT, MAX_PAIRING_FAILURES, PAIRING_BACKOFF,
+};
+use chrono::Utc;
+use std::net::IpAddr;
+use std::time::Instant;
+
+#[test]
+fn test_route() {
+    assert_eq!(route("GET", "/"), Some(RemoteEndpoint::Page));
+    assert_eq!(route("get", "/index.html"), Some(RemoteEndpoint::Page));
+    assert_eq!(route("POST", "/pair"), Some(RemoteEndpoint::Pair));
+    assert_eq!(route("GET", "/state?t=1"), Some(RemoteEndpoint::State));
+    assert_eq!(
+        route("POST", "/recording/start"),
+        Some(RemoteEndpoint::StartRecording)
+    );
+    assert_eq!(
+        route("POST", "/recording/stop"),
+        Some(RemoteEndpoint::StopRecording)
+    );
+    assert_eq!(route("GET", "/recording/start"), None);
+    assert_eq!(route("POST", "/cleanup"), None);
+}
+
+#[test]
+fn test_pairing_codes_have_six_digits() {
+    for _ in 0..20 {
+        let code = generate_pairing_code().unwrap();
+        assert_eq!(code.len(), 6);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+    }
+}
+
+fn phone(last: u8) -> IpAddr {
+    IpAddr::from([192, 168, 1, last])
+}
+
+#[test]
+fn test_pairing_with_the_code_gives_a_token() {
+    let pairing = RemotePairing::new("123456".to_string());
+    assert!(!pairing.is_paired(None));
+
+    let now = Instant::now();
+    let token = pairing.pair(" 123456 ", phone(1), now).unwrap();
+    assert!(pairing.is_paired(Some(&format!("Bearer {}", token))));
+    assert!(!pairing.is_paired(Some("Bearer 123456")));
+
+    // A second phone gets its own token, and the first stays paired
+    let second = pairing.pair("123456", phone(2), now).unwrap();
+    assert_ne!(token, second);
+    assert!(pairing.is_paired(Some(&format!("Bearer {}", token))));
+}
+
+#[test]
+fn test_wrong_codes_turn_pairing_off_until_regenerated() {
+    let pairing = RemotePairing::new("123456".to_string());
+    let now = Instant::now();
+    for attempt in 1..MAX_PAIRING_FAILURES {
+        assert_eq!(
+            pairing.pair("000000", phone(attempt as u8), now),
+            Err(("Wrong pairing code".to_string(), false))
+        );
+    }
+    let (_, disabled) = pairing
+        .pair("000000", phone(MAX_PAIRING_FAILURES as u8), now)
+        .unwrap_err();
+    assert!(disabled);
+    assert!(pairing.is_disabled());
+    // Not even the right code pairs now
+    assert!(pairing.pair("123456", phone(100), now).is_err());
+
+    let code = pairing.regenerate().unwrap();
+    assert!(!pairing.is_disabled());
+    assert!(pairing.pair(&code, phone(1), now).is_ok());
+}
+
+#[test]
+fn test_wrong_codes_back_off_per_address() {
+    assert_eq!(pairing_backoff(1), PAIRING_BACKOFF);
+    assert_eq!(pairing_backoff(3), PAIRING_BACKOFF * 4);
+    assert_eq!(pairing_backoff(40), pairing_backoff(60));
+
+    let pairing = RemotePairing::new("123456".to_string());
+    let now = Instant::now();
+    assert!(pairing.pair("000000", phone(1), now).is_err());
+    // Even the right code waits out the backoff, but other addresses don't
+    let (message, _) = pairing.pair("123456", phone(1), now).unwrap_err();
+    assert!(message.starts_with("Too many wrong codes"));
+    assert!(pairing.pair("123456", phone(2), now).is_ok());
+    assert!(pairing
+        .pair("123456", phone(1), now + PAIRING_BACKOFF)
+        .is_ok());
+}
+
+#[test]
+fn test_regenerate_keeps_paired_phones() {
+    let pairing = RemotePairing::new("123456".to_string());
+    let token = pairing.pair("123456", phone(1), Instant::now()).unwrap();
+    let code = pairing.regenerate().unwrap();
+    assert_eq!(pairing.code(), code);
+    assert!(pairing.is_paired(Some(&format!("Bearer {}", token))));
+}
+
+#[test]
+fn test_hold_expires_without_word_from_the_remote() {
+    let session = RemoteSession::default();
+    let start = Instant::now();
+    assert!(!session.hold_expired(start + HOLD_TIMEOUT));
+
+    session.start(start);
+    assert!(!session.hold_expired(start + HOLD_TIMEOUT / 2));
+    session.seen(start + HOLD_TIMEOUT / 2);
+    assert!(!session.hold_expired(start + HOLD_TIMEOUT));
+    assert!(session.hold_expired(start + HOLD_TIMEOUT * 2));
+
+    let stopped_at = Utc::now();
+    assert!(session.stop(stopped_at));
+    assert!(!session.hold_expired(start + HOLD_TIMEOUT * 2));
+    assert_eq!(session.stopped_at(), Some(stopped_at));
+    // Stopping without a recording of its own keeps the last one
+    assert!(!session.stop(stopped_at + chrono::Duration::seconds(1)));
+    assert_eq!(session.stopped_at(), Some(stopped_at));
+}
+
+#[test]
+fn test_remote_transcript_is_the_first_entry_after_its_recording() {
+    let stopped_at = Utc::now();
+    let entry = |text: &str, seconds: i64| {
+        let mut entry = HistoryEntry::new(text.to_string(), EntryDetails::default());
+        entry.timestamp = stopped_at + chrono::Duration::seconds(seconds);
+        entry
+    };
+    // Newest first, as history lists them
+    let entries = [
+        entry("much later", 600),
+        entry("dictated at the computer", 30),
+        entry("from the phone", 3),
+        entry("before", -10),
+    ];
+    assert_eq!(
+        remote_transcript(&entries, stopped_at).unwrap().text,
+        "from the phone"
+    );
+    assert!(remote_transcript(&entries[..1], stopped_at).is_none());
+}
@@ -0,0 +1,18 @@
+use crate::rich_text::markdown_to_html;
+
+#[test]
+fn test_markdown_list_and_emphasis() {
+    let html = markdown_to_html("Groceries:\n\n- **milk**\n- eggs");
+    assert_eq!(
+        html,
+        "<p>Groceries:</p>\n<ul>\n<li><strong>milk</strong></li>\n<li>eggs</li>\n</ul>"
+    );
+}
+
+#[test]
+fn test_plain_sentence_is_one_paragraph() {
+    assert_eq!(
+        markdown_to_html("See you at 5 & bring snacks."),
+        "<p>See you at 5 &amp; bring snacks.</p>"
+    );
+}
@@ -1,4 +1,5 @@
-use crate::settings::HotkeyConfig;
+use crate::settings::{AppSettings, HotkeyConfig, PasteMethod, SettingsScope};
+use crate::settings_window::SettingsSection;
 
 // Tests for HotkeyConfig
 #[test]
@@ -25,6 +26,24 @@ fn test_default_paste_last_hotkey() {
     assert!(hotkey.modifiers.contains(&"alt".to_string()));
 }
 
+#[test]
+fn test_default_undo_paste_hotkey() {
+    let hotkey = HotkeyConfig::default_undo_paste();
+    assert_eq!(hotkey.to_shortcut_string(), "ctrl+alt+Z");
+}
+
+#[test]
+fn test_default_clipboard_cleanup_hotkey() {
+    let hotkey = HotkeyConfig::default_clipboard_cleanup();
+    assert_eq!(hotkey.to_shortcut_string(), "ctrl+alt+C");
+}
+
+#[test]
+fn test_default_cancel_hotkey() {
+    let hotkey = HotkeyConfig::default_cancel();
+    assert_eq!(hotkey.to_shortcut_string(), "ctrl+alt+X");
+}
+
 #[test]
 fn test_to_shortcut_string() {
     let hotkey = HotkeyConfig {
@@ -37,3 +56,210 @@ fn test_to_shortcut_string() {
     assert!(result.contains("alt"));
     assert!(result.contains("Space"));
 }
+
+// Tests for SettingsScope::default_entries
+#[test]
+fn test_reset_hotkeys_scope_restores_default_hotkeys() {
+    let entries = SettingsScope::Hotkeys.default_entries();
+    let keys: Vec<&str> = entries.iter().map(|(key, _)| *key).collect();
+    assert_eq!(
+        keys,
+        vec![
+            "toggle_hotkey",
+            "hold_hotkey",
+            "paste_last_hotkey",
+            "undo_paste_hotkey",
+            "clipboard_cleanup_hotkey",
+            "cancel_hotkey"
+        ]
+    );
+
+    let toggle: HotkeyConfig = serde_json::from_value(entries[0].1.clone()).unwrap();
+    assert_eq!(toggle, HotkeyConfig::default_toggle());
+}
+
+#[test]
+fn test_reset_audio_scope_does_not_touch_hotkeys() {
+    let entries = SettingsScope::Audio.default_entries();
+    assert!(entries.iter().all(|(key, _)| !key.ends_with("_hotkey")));
+    assert!(entries.iter().any(|(key, _)| *key == "sound_enabled"));
+}
+
+#[test]
+fn test_reset_all_scope_covers_every_section() {
+    let all = SettingsScope::All.default_entries();
+    let sections = [
+        SettingsScope::Hotkeys,
+        SettingsScope::Audio,
+        SettingsScope::Providers,
+        SettingsScope::Prompts,
+        SettingsScope::Output,
+    ];
+    for section in sections {
+        for (key, _) in section.default_entries() {
+            assert!(all.iter().any(|(all_key, _)| *all_key == key), "{key}");
+        }
+    }
+}
+
+#[test]
+fn test_reset_all_scope_covers_every_setting() {
+    let all = SettingsScope::All.keys();
+    let defaults = AppSettings::default_values();
+    assert_eq!(all.len(), defaults.len());
+    for key in defaults.keys() {
+        assert!(all.contains(&key.as_str()), "{key}");
+    }
+}
+
+#[test]
+fn test_settings_scope_deserializes_lowercase() {
+    let scope: SettingsScope = serde_json::from_str("\"providers\"").unwrap();
+    assert_eq!(scope, SettingsScope::Providers);
+}
+
+#[test]
+fn test_paste_method_defaults_to_clipboard_paste() {
+    assert_eq!(PasteMethod::default(), PasteMethod::ClipboardPaste);
+    let json = serde_json::to_string(&PasteMethod::CopyOnly).unwrap();
+    assert_eq!(json, "\"copy_only\"");
+    let method: PasteMethod = serde_json::from_str("\"accessibility\"").unwrap();
+    assert_eq!(method, PasteMethod::Accessibility);
+}
+
+#[test]
+fn test_reset_output_scope_restores_clipboard_defaults() {
+    let entries = SettingsScope::Output.default_entries();
+    assert!(entries
+        .iter()
+        .any(|(key, value)| *key == "restore_clipboard" && *value == true));
+    assert!(entries
+        .iter()
+        .any(|(key, value)| *key == "paste_method" && *value == "clipboard_paste"));
+}
+
+// Tests for settings metadata
+#[test]
+fn test_describe_settings_covers_every_scope_key() {
+    let descriptors = AppSettings::describe();
+    for key in SettingsScope::All.keys() {
+        assert!(
+            descriptors.iter().any(|descriptor| descriptor.key == key),
+            "missing metadata for {key}"
+        );
+    }
+}
+
+#[test]
+fn test_describe_settings_has_descriptions_and_types() {
+    for descriptor in AppSettings::describe() {
+        assert!(!descriptor.description.is_empty(), "{}", descriptor.key);
+        assert!(!descriptor.value_type.is_empty(), "{}", descriptor.key);
+    }
+
+    let descriptors = AppSettings::describe();
+    let mic = descriptors
+        .iter()
+        .find(|descriptor| descriptor.key == "selected_mic_id")
+        .unwrap();
+    assert_eq!(mic.value_type, "Option<String>");
+    assert!(mic.machine_local);
+}
+
+#[test]
+fn test_describe_settings_restart_required() {
+    let descriptors = AppSettings::describe();
+    let restart_keys: Vec<&str> = descriptors
+        .iter()
+        .filter(|descriptor| descriptor.restart_required)
+        .map(|descriptor| descriptor.key)
+        .collect();
+    assert!(restart_keys.contains(&"tray_only_mode"));
+    // Read again at every launch, so changing it doesn't need one
+    assert!(!restart_keys.contains(&"start_minimized"));
+}
+
+#[test]
+fn test_integration_servers_need_restart() {
+    let restart_keys: Vec<&str> = AppSettings::describe()
+        .iter()
+        .filter(|descriptor| descriptor.restart_required)
+        .map(|descriptor| descriptor.key)
+        .collect();
+    assert_eq!(
+        restart_keys,
+        vec![
+            "tray_only_mode",
+            "automation_api_enabled",
+            "automation_api_port",
+            "stream_deck_enabled",
+            "stream_deck_port",
+            "event_stream_enabled",
+            "event_stream_port",
+            "mqtt_broker_url",
+            "mqtt_username",
+            "mqtt_password",
+            "mqtt_topic_prefix",
+            "mqtt_home_assistant_discovery",
+            "browser_extension_enabled",
+            "browser_extension_port",
+            "obs_captions_enabled",
+            "obs_websocket_url",
+            "obs_websocket_password",
+            "obs_caption_source",
+            "obs_caption_clear_seconds",
+            "obs_stream_captions",
+            "remote_control_enabled",
+            "remote_control_port",
+        ]
+    );
+}
+
+#[test]
+fn test_describe_settings_defaults_match_reset_defaults() {
+    let descriptors = AppSettings::describe();
+    for (key, value) in SettingsScope::All.default_entries() {
+        let descriptor = descriptors
+            .iter()
+            .find(|descriptor| descriptor.key == key)
+            .unwrap();
+        assert_eq!(descriptor.default, value, "{key}");
+    }
+}
+
+// Tests for pending restart changes
+#[test]
+fn test_no_pending_restart_changes_when_unchanged() {
+    let baseline = serde_json::Map::new();
+    let mut current = serde_json::Map::new();
+    current.insert("tray_only_mode".to_string(), serde_json::json!(false));
+    current.insert("sound_enabled".to_string(), serde_json::json!(false));
+
+    // Explicit default equals a missing key; non-restart settings are ignored
+    assert!(AppSettings::pending_restart_changes(&baseline, &current).is_empty());
+}
+
+#[test]
+fn test_pending_restart_change_detected_and_cleared_on_revert() {
+    let mut baseline = serde_json::Map::new();
+    baseline.insert("tray_only_mode".to_string(), serde_json::json!(false));
+
+    let mut current = baseline.clone();
+    current.insert("tray_only_mode".to_string(), serde_json::json!(true));
+    assert_eq!(
+        AppSettings::pending_restart_changes(&baseline, &current),
+        vec!["tray_only_mode".to_string()]
+    );
+
+    current.insert("tray_only_mode".to_string(), serde_json::json!(false));
+    assert!(AppSettings::pending_restart_changes(&baseline, &current).is_empty());
+}
+
+#[test]
+fn test_settings_section_deserialization() {
+    let section: SettingsSection = serde_json::from_str("\"providers\"").unwrap();
+    assert_eq!(section, SettingsSection::Providers);
+    let section: Option<SettingsSection> = serde_json::from_str("null").unwrap();
+    assert_eq!(section, None);
+    assert!(serde_json::from_str::<SettingsSection>("\"advanced\"").is_err());
+}
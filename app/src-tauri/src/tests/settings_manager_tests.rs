@@ -0,0 +1,192 @@
+use crate::settings::{SettingsManager, SettingsPolicy};
+use crate::tests::TempDir;
+use std::fs;
+
+fn entries(sound_enabled: bool) -> serde_json::Map<String, serde_json::Value> {
+    let mut map = serde_json::Map::new();
+    map.insert("sound_enabled".to_string(), sound_enabled.into());
+    map
+}
+
+#[test]
+fn test_save_entries_writes_settings_file() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    manager.save_entries(&entries(false)).unwrap();
+
+    let content = fs::read_to_string(dir.join("settings.json")).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(value["sound_enabled"], false);
+    assert!(!dir.join("settings.json.tmp").exists());
+}
+
+#[test]
+fn test_recover_valid_settings_is_noop() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    manager.save_entries(&entries(true)).unwrap();
+
+    assert_eq!(manager.recover_if_corrupted().unwrap(), None);
+}
+
+#[test]
+fn test_recover_missing_settings_is_noop() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    assert_eq!(manager.recover_if_corrupted().unwrap(), None);
+}
+
+#[test]
+fn test_recover_corrupted_settings_from_backup() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    manager.save_entries(&entries(false)).unwrap();
+
+    fs::write(dir.join("settings.json"), "{ \"sound_enabled\": fa").unwrap();
+
+    let restored = manager.recover_if_corrupted().unwrap();
+    assert!(restored.is_some());
+
+    let content = fs::read_to_string(dir.join("settings.json")).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(value["sound_enabled"], false);
+}
+
+#[test]
+fn test_recover_without_backup_moves_corrupted_file_aside() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    fs::write(dir.join("settings.json"), "not json").unwrap();
+
+    assert_eq!(manager.recover_if_corrupted().unwrap(), None);
+    assert!(!dir.join("settings.json").exists());
+}
+
+#[test]
+fn test_backups_are_pruned() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    for i in 0..10 {
+        manager.save_entries(&entries(i % 2 == 0)).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+
+    let backups = fs::read_dir(dir.join("settings-backups")).unwrap().count();
+    assert_eq!(backups, 5);
+}
+
+#[test]
+fn test_machine_local_keys_are_split_from_roaming_settings() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    let mut map = entries(true);
+    map.insert("selected_mic_id".to_string(), "usb-mic".into());
+    manager.save_entries(&map).unwrap();
+
+    let roaming: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("settings.json")).unwrap()).unwrap();
+    assert!(roaming.get("selected_mic_id").is_none());
+    assert_eq!(roaming["sound_enabled"], true);
+
+    let machine = manager.load_machine_entries();
+    assert_eq!(machine["selected_mic_id"], "usb-mic");
+    assert!(machine.get("sound_enabled").is_none());
+}
+
+#[test]
+fn test_load_machine_entries_missing_file_is_empty() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    assert!(manager.load_machine_entries().is_empty());
+}
+
+// Tests for SettingsPolicy
+#[test]
+fn test_policy_missing_file_locks_nothing() {
+    let dir = TempDir::new();
+    let policy = SettingsPolicy::load(&dir.join("policy.json"));
+    assert!(policy.locked.is_empty());
+}
+
+#[test]
+fn test_policy_invalid_file_is_ignored() {
+    let dir = TempDir::new();
+    let path = dir.join("policy.json");
+    fs::write(&path, "{ locked: ").unwrap();
+    assert_eq!(SettingsPolicy::load(&path), SettingsPolicy::default());
+}
+
+#[test]
+fn test_policy_violations_reports_changed_locked_keys() {
+    let dir = TempDir::new();
+    let path = dir.join("policy.json");
+    fs::write(
+        &path,
+        r#"{ "locked": { "stt_provider": "whisper", "llm_provider": "ollama" } }"#,
+    )
+    .unwrap();
+    let policy = SettingsPolicy::load(&path);
+
+    let mut map = entries(true);
+    map.insert("stt_provider".to_string(), "deepgram".into());
+    map.insert("llm_provider".to_string(), "ollama".into());
+
+    assert_eq!(policy.violations(&map), vec!["stt_provider".to_string()]);
+}
+
+#[test]
+fn test_policy_violations_includes_missing_locked_keys() {
+    let policy = SettingsPolicy {
+        locked: serde_json::from_str(r#"{ "stt_provider": "whisper" }"#).unwrap(),
+    };
+    assert_eq!(
+        policy.violations(&entries(true)),
+        vec!["stt_provider".to_string()]
+    );
+}
+
+// Tests for external edit detection
+#[test]
+fn test_external_change_ignores_own_writes() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    manager.save_entries(&entries(true)).unwrap();
+
+    assert_eq!(manager.read_external_change().unwrap(), None);
+}
+
+#[test]
+fn test_external_change_detects_hand_edit() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    manager.save_entries(&entries(true)).unwrap();
+
+    fs::write(dir.join("settings.json"), r#"{ "sound_enabled": false }"#).unwrap();
+
+    let changed = manager.read_external_change().unwrap().unwrap();
+    assert_eq!(changed["sound_enabled"], false);
+    // Subsequent checks see no further change
+    assert_eq!(manager.read_external_change().unwrap(), None);
+}
+
+#[test]
+fn test_external_change_rejects_invalid_json() {
+    let dir = TempDir::new();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+    manager.save_entries(&entries(true)).unwrap();
+
+    fs::write(dir.join("settings.json"), "{ \"sound_enabled\": ").unwrap();
+
+    assert!(manager.read_external_change().is_err());
+    // Still reported until the file is fixed
+    assert!(manager.read_external_change().is_err());
+}
+
+#[test]
+fn test_external_change_first_read_is_baseline() {
+    let dir = TempDir::new();
+    fs::write(dir.join("settings.json"), r#"{ "sound_enabled": true }"#).unwrap();
+    let manager = SettingsManager::new(dir.to_path_buf(), dir.join("local"));
+
+    assert_eq!(manager.read_external_change().unwrap(), None);
+}
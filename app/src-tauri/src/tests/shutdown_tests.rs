@@ -0,0 +1,29 @@
+use crate::shutdown::{wait_until_idle, Shutdown};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_shutdown_begins_once() {
+    let shutdown = Shutdown::default();
+    assert!(shutdown.begin());
+    assert!(!shutdown.begin());
+    assert!(!shutdown.is_drained());
+}
+
+#[test]
+fn test_wait_until_idle_returns_once_idle() {
+    let checks = AtomicUsize::new(0);
+    let idle = wait_until_idle(Duration::from_secs(5), || {
+        checks.fetch_add(1, Ordering::SeqCst) < 2
+    });
+    assert!(idle);
+    assert_eq!(checks.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_wait_until_idle_gives_up_after_timeout() {
+    let started = Instant::now();
+    assert!(!wait_until_idle(Duration::from_millis(120), || true));
+    assert!(started.elapsed() >= Duration::from_millis(120));
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
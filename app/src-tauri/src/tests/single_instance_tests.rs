@@ -0,0 +1,44 @@
+use crate::single_instance::{files_in_args, is_link};
+use crate::tests::TempDir;
+use std::fs;
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn test_is_link() {
+    assert!(is_link("tambourine://record"));
+    assert!(!is_link("https://example.com"));
+    assert!(!is_link("memo.m4a"));
+}
+
+#[test]
+fn test_files_in_args() {
+    let dir = TempDir::new();
+    fs::write(dir.join("memo.m4a"), b"audio").unwrap();
+    let absolute = dir.join("memo.m4a").to_string_lossy().into_owned();
+    let cwd = dir.to_string_lossy().into_owned();
+
+    // Relative paths are resolved against the launch's directory
+    assert_eq!(
+        files_in_args(&args(&["tambourine", "memo.m4a"]), &cwd),
+        vec![absolute.clone()]
+    );
+    assert_eq!(
+        files_in_args(&args(&["tambourine", &absolute]), "/"),
+        vec![absolute.clone()]
+    );
+    // The executable, flags, links, folders and missing files are skipped
+    assert!(files_in_args(
+        &args(&[
+            &absolute,
+            "--minimized",
+            "tambourine://toggle",
+            &cwd,
+            "missing.wav"
+        ]),
+        &cwd
+    )
+    .is_empty());
+}
@@ -0,0 +1,30 @@
+use crate::smart_insert::fit_to_context;
+
+#[test]
+fn test_start_of_field_is_capitalized_without_space() {
+    assert_eq!(fit_to_context("hello there.", ""), "Hello there.");
+}
+
+#[test]
+fn test_mid_sentence_adds_space_and_lowers() {
+    assert_eq!(fit_to_context("Buy milk.", "I need to"), " buy milk.");
+    assert_eq!(fit_to_context("Buy milk.", "I need to "), "buy milk.");
+}
+
+#[test]
+fn test_after_sentence_end_capitalizes() {
+    assert_eq!(fit_to_context("then leave.", "Done."), " Then leave.");
+    assert_eq!(fit_to_context("then leave.", "Done.\n"), "Then leave.");
+}
+
+#[test]
+fn test_keeps_i_and_acronyms() {
+    assert_eq!(fit_to_context("I think so.", "and"), " I think so.");
+    assert_eq!(fit_to_context("NASA called.", "then"), " NASA called.");
+}
+
+#[test]
+fn test_no_space_after_opening_bracket_or_before_punctuation() {
+    assert_eq!(fit_to_context("See below", "("), "see below");
+    assert_eq!(fit_to_context(", right?", "okay"), ", right?");
+}
@@ -0,0 +1,87 @@
+use crate::error::AppError;
+use crate::snippets::{match_voice_command, spoken_snippet_name, SnippetLibrary};
+use crate::tests::TempDir;
+
+#[test]
+fn test_spoken_snippet_name() {
+    assert_eq!(
+        spoken_snippet_name("Insert my email signature."),
+        Some("email signature".to_string())
+    );
+    assert_eq!(
+        spoken_snippet_name("paste the address snippet"),
+        Some("address".to_string())
+    );
+    assert_eq!(spoken_snippet_name("Insert"), None);
+    assert_eq!(spoken_snippet_name("Please insert my signature"), None);
+}
+
+#[test]
+fn test_voice_command_matches_names_loosely() {
+    let library = SnippetLibrary::in_memory();
+    let signature = library
+        .add("My Email Signature", "Best,\nSam", None)
+        .unwrap();
+    library.add("Home address", "1 Main St", None).unwrap();
+    let snippets = library.list().unwrap();
+
+    assert_eq!(
+        match_voice_command("Insert my email signature.", &snippets).map(|s| &s.id),
+        Some(&signature.id)
+    );
+    // Small transcription slips still match
+    assert_eq!(
+        match_voice_command("insert email signatures", &snippets).map(|s| &s.id),
+        Some(&signature.id)
+    );
+    assert!(match_voice_command("insert my phone number", &snippets).is_none());
+    assert!(match_voice_command("email signature", &snippets).is_none());
+}
+
+#[test]
+fn test_library_add_edit_delete() {
+    let library = SnippetLibrary::in_memory();
+    assert!(matches!(
+        library.add("  ", "text", None),
+        Err(AppError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        library.add("Name", " ", None),
+        Err(AppError::InvalidInput(_))
+    ));
+
+    let snippet = library
+        .add(" Greeting ", "Hello there", Some("entry-1".to_string()))
+        .unwrap();
+    assert_eq!(snippet.name, "Greeting");
+    assert_eq!(snippet.source_entry_id.as_deref(), Some("entry-1"));
+    library.add("apology", "Sorry for the delay", None).unwrap();
+
+    let names: Vec<String> = library
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(names, ["apology", "Greeting"]);
+
+    assert!(library.edit(&snippet.id, "Hello", "Hi!").unwrap());
+    assert!(!library.edit("missing", "Hello", "Hi!").unwrap());
+    assert_eq!(library.get(&snippet.id).unwrap().unwrap().text, "Hi!");
+
+    assert!(library.delete(&snippet.id).unwrap());
+    assert!(!library.delete(&snippet.id).unwrap());
+    assert_eq!(library.list().unwrap().len(), 1);
+}
+
+#[test]
+fn test_library_is_saved_to_disk() {
+    let dir = TempDir::new();
+
+    SnippetLibrary::new(dir.to_path_buf())
+        .add("Sign-off", "Cheers", None)
+        .unwrap();
+    let reloaded = SnippetLibrary::new(dir.to_path_buf()).list().unwrap();
+    assert_eq!(reloaded.len(), 1);
+    assert_eq!(reloaded[0].text, "Cheers");
+}
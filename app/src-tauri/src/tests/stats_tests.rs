@@ -0,0 +1,90 @@
+use crate::history::{EntryDetails, HistoryEntry};
+use crate::stats::{compute, percentile, typing_seconds};
+use chrono::{Datelike, NaiveDate, TimeZone, Utc};
+
+fn entry(text: &str, day: u32, details: EntryDetails) -> HistoryEntry {
+    HistoryEntry {
+        id: format!("{}-{}", day, text),
+        timestamp: Utc.with_ymd_and_hms(2025, 6, day, 12, 0, 0).unwrap(),
+        text: text.to_string(),
+        details,
+        pinned: false,
+        revised_text: None,
+        session_id: None,
+        has_audio: false,
+        tags: Vec::new(),
+        note: None,
+    }
+}
+
+fn timed(stt: &str, duration: f64, latency: f64) -> EntryDetails {
+    EntryDetails {
+        stt_provider: Some(stt.to_string()),
+        duration_seconds: Some(duration),
+        latency_seconds: Some(latency),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_percentile_uses_nearest_rank() {
+    let sorted = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+    assert_eq!(percentile(&sorted, 50.0), 5.0);
+    assert_eq!(percentile(&sorted, 90.0), 9.0);
+    assert_eq!(percentile(&sorted, 99.0), 10.0);
+    assert_eq!(percentile(&[3.0], 50.0), 3.0);
+    assert_eq!(percentile(&[], 50.0), 0.0);
+}
+
+#[test]
+fn test_typing_seconds_falls_back_to_default_speed() {
+    assert_eq!(typing_seconds(40, 40), 60.0);
+    assert_eq!(typing_seconds(40, 0), 60.0);
+    assert_eq!(typing_seconds(30, 60), 30.0);
+}
+
+#[test]
+fn test_totals_and_time_saved() {
+    // 2025-06-02 is a Monday, 2025-06-09 the Monday after
+    let entries = vec![
+        entry("one two three four", 9, timed("deepgram", 1.0, 0.5)),
+        entry("five six", 3, timed("deepgram", 10.0, 1.5)),
+        entry("seven", 2, EntryDetails::default()),
+    ];
+    let stats = compute(&entries, 60, &Utc);
+
+    assert_eq!(stats.dictations, 3);
+    assert_eq!(stats.words, 7);
+    assert_eq!(stats.audio_seconds, 11.0);
+    // 4 words take 4s to type but 1s to say; speaking 2 words for 10s saves nothing
+    assert_eq!(stats.time_saved_seconds, 3.0 + 0.0 + 1.0);
+
+    let days: Vec<u32> = stats.daily.iter().map(|d| d.start.day()).collect();
+    assert_eq!(days, vec![2, 3, 9]);
+    assert_eq!(stats.weekly.len(), 2);
+    assert_eq!(
+        stats.weekly[0].start,
+        NaiveDate::from_ymd_opt(2025, 6, 2).unwrap()
+    );
+    assert_eq!(stats.weekly[0].dictations, 2);
+    assert_eq!(stats.weekly[0].words, 3);
+}
+
+#[test]
+fn test_latency_grouped_by_provider() {
+    let entries = vec![
+        entry("a", 2, timed("deepgram", 1.0, 0.5)),
+        entry("b", 2, timed("deepgram", 1.0, 1.5)),
+        entry("c", 2, timed("whisper", 1.0, 3.0)),
+        entry("d", 2, EntryDetails::default()),
+    ];
+    let stats = compute(&entries, 40, &Utc);
+
+    assert_eq!(stats.latency.len(), 2);
+    let deepgram = &stats.latency[0];
+    assert_eq!(deepgram.stt_provider.as_deref(), Some("deepgram"));
+    assert_eq!(deepgram.samples, 2);
+    assert_eq!(deepgram.p50_seconds, 0.5);
+    assert_eq!(deepgram.p90_seconds, 1.5);
+    assert_eq!(stats.latency[1].p99_seconds, 3.0);
+}
@@ -0,0 +1,58 @@
+use crate::overlay_state::OverlayState;
+use crate::stream_deck::{parse_action, query_token, state_message, StreamDeckAction};
+
+#[test]
+fn test_parse_actions() {
+    assert_eq!(
+        parse_action(r#"{"action": "toggle"}"#),
+        Ok(StreamDeckAction::Toggle { profile: None })
+    );
+    assert_eq!(
+        parse_action(r#"{"action": "start", "profile": "email"}"#),
+        Ok(StreamDeckAction::Start {
+            profile: Some("email".to_string())
+        })
+    );
+    assert_eq!(
+        parse_action(r#"{"action": "cancel"}"#),
+        Ok(StreamDeckAction::Cancel)
+    );
+    assert!(parse_action(r#"{"action": "explode"}"#).is_err());
+    assert!(parse_action("toggle").is_err());
+}
+
+#[test]
+fn test_state_message_flattens_the_state() {
+    let message: serde_json::Value = serde_json::from_str(&state_message(
+        &OverlayState::Recording,
+        true,
+        Some("email"),
+    ))
+    .unwrap();
+    assert_eq!(
+        message,
+        serde_json::json!({
+            "event": "state",
+            "state": "recording",
+            "recording": true,
+            "profile": "email",
+        })
+    );
+
+    let error = OverlayState::Error {
+        message: "No microphone".to_string(),
+    };
+    let message: serde_json::Value =
+        serde_json::from_str(&state_message(&error, false, None)).unwrap();
+    assert_eq!(message["state"], "error");
+    assert_eq!(message["message"], "No microphone");
+    assert_eq!(message["profile"], serde_json::Value::Null);
+}
+
+#[test]
+fn test_query_token() {
+    assert_eq!(query_token(Some("token=abc")), Some("abc"));
+    assert_eq!(query_token(Some("plugin=deck&token=abc")), Some("abc"));
+    assert_eq!(query_token(Some("plugin=deck")), None);
+    assert_eq!(query_token(None), None);
+}
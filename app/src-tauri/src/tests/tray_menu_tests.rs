@@ -0,0 +1,28 @@
+use crate::tray_menu::{cleanup_section_enabled, toggle_cleanup_section};
+use serde_json::{json, Value};
+
+#[test]
+fn test_cleanup_sections_default_to_server_defaults() {
+    assert!(cleanup_section_enabled(&Value::Null, "advanced"));
+    assert!(!cleanup_section_enabled(&Value::Null, "dictionary"));
+}
+
+#[test]
+fn test_toggle_cleanup_section_fills_missing_sections() {
+    let sections = toggle_cleanup_section(None, "dictionary");
+    assert_eq!(sections["dictionary"]["enabled"], json!(true));
+    assert_eq!(sections["advanced"]["enabled"], json!(true));
+    assert_eq!(sections["main"]["enabled"], json!(true));
+}
+
+#[test]
+fn test_toggle_cleanup_section_keeps_custom_content() {
+    let sections = json!({
+        "main": { "enabled": true, "content": null },
+        "advanced": { "enabled": true, "content": "Custom rules" },
+        "dictionary": { "enabled": false, "content": null },
+    });
+    let toggled = toggle_cleanup_section(Some(sections), "advanced");
+    assert_eq!(toggled["advanced"]["enabled"], json!(false));
+    assert_eq!(toggled["advanced"]["content"], json!("Custom rules"));
+}
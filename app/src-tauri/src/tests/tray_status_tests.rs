@@ -0,0 +1,24 @@
+use crate::tray_status::draw_status_dot;
+
+fn pixel(rgba: &[u8], width: u32, x: u32, y: u32) -> &[u8] {
+    let offset = ((y * width + x) * 4) as usize;
+    &rgba[offset..offset + 4]
+}
+
+#[test]
+fn test_status_dot_fills_bottom_right_corner() {
+    let (width, height) = (16, 16);
+    let mut rgba = vec![0u8; (width * height * 4) as usize];
+    draw_status_dot(&mut rgba, width, height, [0xef, 0x44, 0x44]);
+
+    assert_eq!(pixel(&rgba, width, 12, 12), &[0xef, 0x44, 0x44, 0xff]);
+    assert_eq!(pixel(&rgba, width, 0, 0), &[0, 0, 0, 0]);
+    assert_eq!(pixel(&rgba, width, 4, 12), &[0, 0, 0, 0]);
+}
+
+#[test]
+fn test_status_dot_ignores_short_buffer() {
+    let mut rgba = vec![0u8; 8];
+    draw_status_dot(&mut rgba, 16, 16, [0xff, 0xff, 0xff]);
+    assert_eq!(rgba, vec![0u8; 8]);
+}
@@ -0,0 +1,106 @@
+use crate::history::{EntryDetails, HistoryEntry};
+use crate::vocabulary::{corrections, insights, terms, words, CorrectedWord, TermCount};
+use chrono::Utc;
+
+fn entry(raw: Option<&str>, text: &str) -> HistoryEntry {
+    HistoryEntry {
+        id: text.to_string(),
+        timestamp: Utc::now(),
+        text: text.to_string(),
+        details: EntryDetails {
+            raw_text: raw.map(str::to_string),
+            ..Default::default()
+        },
+        pinned: false,
+        revised_text: None,
+        session_id: None,
+        has_audio: false,
+        tags: Vec::new(),
+        note: None,
+    }
+}
+
+#[test]
+fn test_words_trim_punctuation() {
+    assert_eq!(
+        words("Hello, world! (it's) - ok."),
+        vec!["Hello", "world", "it's", "ok"]
+    );
+}
+
+#[test]
+fn test_corrections_pair_replaced_words() {
+    assert_eq!(
+        corrections(
+            "i pushed the fix to tamborine voice today",
+            "I pushed the fix to Tambourine Voice today."
+        ),
+        vec![("tamborine".to_string(), "Tambourine".to_string())]
+    );
+    assert_eq!(
+        corrections("ask cloud about it", "Ask Claude about it."),
+        vec![("cloud".to_string(), "Claude".to_string())]
+    );
+}
+
+#[test]
+fn test_corrections_ignore_fillers_numbers_and_rewrites() {
+    // Dropped fillers and case changes aren't corrections
+    assert!(corrections("um so we ship it", "So we ship it.").is_empty());
+    // Numbers written as digits aren't vocabulary
+    assert!(corrections("we need five", "We need 5.").is_empty());
+    // A reworded sentence is a rewrite, not a correction
+    assert!(corrections(
+        "the meeting is moved",
+        "We rescheduled our weekly sync to next Thursday afternoon."
+    )
+    .is_empty());
+}
+
+#[test]
+fn test_terms_skip_sentence_starts() {
+    assert_eq!(
+        terms("Today I met Alice about the GPT4 rollout. Then we tried PostgreSQL."),
+        vec!["Alice", "GPT4", "PostgreSQL"]
+    );
+    assert_eq!(terms("I'm fine. \"Great.\" Sure"), Vec::<&str>::new());
+}
+
+#[test]
+fn test_insights_rank_and_skip_known_terms() {
+    let entries = vec![
+        entry(Some("ping cloud later"), "Ping Claude later."),
+        entry(Some("ask claud again"), "Ask Claude again."),
+        entry(Some("deploy to cubernetes"), "Deploy to Kubernetes."),
+        entry(None, "Send it to Alice and Bob."),
+        entry(None, "Call Alice."),
+    ];
+    let result = insights(&entries, &[]);
+    assert_eq!(
+        result.corrected[0],
+        CorrectedWord {
+            word: "Claude".to_string(),
+            heard_as: vec!["claud".to_string(), "cloud".to_string()],
+            count: 2,
+        }
+    );
+    assert_eq!(result.corrected[1].word, "Kubernetes");
+    // Claude appears twice too; Bob and Kubernetes only once
+    assert_eq!(
+        result.frequent,
+        vec![
+            TermCount {
+                term: "Alice".to_string(),
+                count: 2
+            },
+            TermCount {
+                term: "Claude".to_string(),
+                count: 2
+            },
+        ]
+    );
+
+    let result = insights(&entries, &["claude".to_string(), "Alice".to_string()]);
+    assert_eq!(result.corrected.len(), 1);
+    assert!(result.frequent.is_empty());
+}
@@ -0,0 +1,77 @@
+use crate::history::{EntryDetails, HistoryEntry};
+use crate::webhooks::{render_payload, Webhook};
+
+fn entry() -> HistoryEntry {
+    HistoryEntry::new(
+        "Buy \"oat\" milk\nand bread".to_string(),
+        EntryDetails {
+            raw_text: Some("buy oat milk and bread".to_string()),
+            target_app: Some("Notes".to_string()),
+            ..Default::default()
+        },
+    )
+}
+
+fn webhook(template: Option<&str>) -> Webhook {
+    Webhook {
+        url: "http://localhost:5678/webhook/dictation".to_string(),
+        template: template.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_default_payload_is_the_entry() {
+    let entry = entry();
+    let payload: serde_json::Value =
+        serde_json::from_str(&render_payload(&webhook(None), &entry)).unwrap();
+    assert_eq!(payload["id"], entry.id);
+    assert_eq!(payload["text"], entry.text);
+    assert_eq!(payload["target_app"], "Notes");
+
+    // A blank template counts as none
+    assert_eq!(
+        render_payload(&webhook(Some("  ")), &entry),
+        render_payload(&webhook(None), &entry)
+    );
+}
+
+#[test]
+fn test_template_placeholders_are_json_escaped() {
+    let entry = entry();
+    let rendered = render_payload(
+        &webhook(Some(
+            r#"{"message": "{{text}}", "app": "{{ target_app }}", "words": {{words}}}"#,
+        )),
+        &entry,
+    );
+    let payload: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+    assert_eq!(payload["message"], "Buy \"oat\" milk\nand bread");
+    assert_eq!(payload["app"], "Notes");
+    assert_eq!(payload["words"], 5);
+}
+
+#[test]
+fn test_template_missing_and_unknown_placeholders() {
+    let entry = entry();
+    assert_eq!(
+        render_payload(
+            &webhook(Some("[{{llm_provider}}] {{nope}} {{raw_text")),
+            &entry
+        ),
+        "[] {{nope}} {{raw_text"
+    );
+    assert_eq!(
+        render_payload(&webhook(Some("{{tags}} {{pinned}}")), &entry),
+        "[] false"
+    );
+}
+
+#[test]
+fn test_webhook_defaults() {
+    let webhook: Webhook =
+        serde_json::from_str(r#"{"url": "https://hooks.zapier.com/x"}"#).unwrap();
+    assert!(webhook.enabled);
+    assert_eq!(webhook.template, None);
+    assert_eq!(webhook.content_type, "application/json");
+}
@@ -0,0 +1,334 @@
+//! Tray menu built from the current app state and settings.
+//!
+//! The menu is rebuilt whenever something it shows changes: recording starts or
+//! stops, settings are saved or reloaded, or the frontend reports the providers
+//! the server offers. Choices made in the menu are written to the settings store
+//! and announced with `settings-changed`, so the overlay syncs them to the server
+//! exactly as if they were changed in the settings window.
+//!
+//! Snippets are listed under Insert Snippet; picking one pastes it into the app
+//! that was focused last.
+
+use crate::commands::settings::persist_store;
+use crate::settings::{SettingsManager, SETTINGS_FILE_NAME};
+use crate::snippets::{Snippet, SnippetLibrary};
+use crate::state::AppState;
+use crate::tray_status::TRAY_ID;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tauri_plugin_store::StoreExt;
+
+const TOGGLE_RECORDING_ID: &str = "toggle_recording";
+const SOUNDS_ID: &str = "sound_enabled";
+const COPY_ONLY_ID: &str = "copy_only_mode";
+const SHOW_ID: &str = "show";
+const QUIT_ID: &str = "quit";
+const STT_PREFIX: &str = "stt:";
+const LLM_PREFIX: &str = "llm:";
+const CLEANUP_PREFIX: &str = "cleanup:";
+const SNIPPET_PREFIX: &str = "snippet:";
+
+/// Optional cleanup prompt sections that can be toggled from the tray
+const CLEANUP_SECTIONS: &[(&str, &str)] = &[
+    ("advanced", "Corrections and Lists"),
+    ("dictionary", "Personal Dictionary"),
+];
+
+/// A provider offered by the server
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct ProviderOption {
+    pub value: String,
+    pub label: String,
+}
+
+/// Provider lists reported by the frontend (the server's available providers)
+#[derive(Default)]
+pub struct TrayMenuState {
+    providers: Mutex<(Vec<ProviderOption>, Vec<ProviderOption>)>,
+}
+
+impl TrayMenuState {
+    pub fn set_providers(&self, stt: Vec<ProviderOption>, llm: Vec<ProviderOption>) {
+        if let Ok(mut providers) = self.providers.lock() {
+            *providers = (stt, llm);
+        }
+    }
+
+    fn providers(&self) -> (Vec<ProviderOption>, Vec<ProviderOption>) {
+        self.providers
+            .lock()
+            .map(|providers| providers.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Whether a cleanup section is enabled, using the server defaults when the
+/// sections were never customized
+pub fn cleanup_section_enabled(sections: &Value, section: &str) -> bool {
+    sections
+        .get(section)
+        .and_then(|s| s.get("enabled"))
+        .and_then(Value::as_bool)
+        .unwrap_or(section != "dictionary")
+}
+
+/// Flip one cleanup section on or off, filling in defaults for missing sections
+pub fn toggle_cleanup_section(sections: Option<Value>, section: &str) -> Value {
+    let mut sections = match sections {
+        Some(Value::Object(map)) => Value::Object(map),
+        _ => json!({}),
+    };
+    for name in ["main", "advanced", "dictionary"] {
+        if sections.get(name).is_none() {
+            sections[name] = json!({
+                "enabled": cleanup_section_enabled(&Value::Null, name),
+                "content": null,
+            });
+        }
+    }
+    let enabled = cleanup_section_enabled(&sections, section);
+    sections[section]["enabled"] = Value::Bool(!enabled);
+    sections
+}
+
+fn stored(app: &AppHandle, key: &str) -> Option<Value> {
+    app.store(SETTINGS_FILE_NAME)
+        .ok()
+        .and_then(|store| store.get(key))
+        .filter(|value| !value.is_null())
+}
+
+fn provider_submenu(
+    app: &AppHandle,
+    title: &str,
+    prefix: &str,
+    providers: &[ProviderOption],
+    selected: Option<&str>,
+) -> tauri::Result<Submenu<Wry>> {
+    let submenu = Submenu::new(app, title, !providers.is_empty())?;
+    for provider in providers {
+        let item = CheckMenuItem::with_id(
+            app,
+            format!("{}{}", prefix, provider.value),
+            &provider.label,
+            true,
+            selected == Some(provider.value.as_str()),
+            None::<&str>,
+        )?;
+        submenu.append(&item)?;
+    }
+    Ok(submenu)
+}
+
+fn snippet_submenu(app: &AppHandle, snippets: &[Snippet]) -> tauri::Result<Submenu<Wry>> {
+    let submenu = Submenu::new(app, "Insert Snippet", !snippets.is_empty())?;
+    for snippet in snippets {
+        let item = MenuItem::with_id(
+            app,
+            format!("{}{}", SNIPPET_PREFIX, snippet.id),
+            &snippet.name,
+            true,
+            None::<&str>,
+        )?;
+        submenu.append(&item)?;
+    }
+    Ok(submenu)
+}
+
+/// Build the tray menu for the current state and settings
+pub fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let recording = app
+        .try_state::<AppState>()
+        .is_some_and(|state| state.lifecycle.is_recording());
+    let (stt_providers, llm_providers) = app
+        .try_state::<TrayMenuState>()
+        .map(|state| state.providers())
+        .unwrap_or_default();
+    let stt_selected = stored(app, "stt_provider");
+    let llm_selected = stored(app, "llm_provider");
+    let sections = stored(app, "cleanup_prompt_sections").unwrap_or(Value::Null);
+    let sound_enabled = stored(app, "sound_enabled")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(true);
+    let copy_only = stored(app, COPY_ONLY_ID)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+
+    let toggle = MenuItem::with_id(
+        app,
+        TOGGLE_RECORDING_ID,
+        if recording {
+            "Stop Dictation"
+        } else {
+            "Start Dictation"
+        },
+        true,
+        None::<&str>,
+    )?;
+    let stt = provider_submenu(
+        app,
+        "Speech-to-Text",
+        STT_PREFIX,
+        &stt_providers,
+        stt_selected.as_ref().and_then(Value::as_str),
+    )?;
+    let llm = provider_submenu(
+        app,
+        "Cleanup Model",
+        LLM_PREFIX,
+        &llm_providers,
+        llm_selected.as_ref().and_then(Value::as_str),
+    )?;
+    let cleanup = Submenu::new(app, "Cleanup", true)?;
+    for (section, label) in CLEANUP_SECTIONS {
+        let item = CheckMenuItem::with_id(
+            app,
+            format!("{}{}", CLEANUP_PREFIX, section),
+            *label,
+            true,
+            cleanup_section_enabled(&sections, section),
+            None::<&str>,
+        )?;
+        cleanup.append(&item)?;
+    }
+    let sounds =
+        CheckMenuItem::with_id(app, SOUNDS_ID, "Sounds", true, sound_enabled, None::<&str>)?;
+    let copy_only_item = CheckMenuItem::with_id(
+        app,
+        COPY_ONLY_ID,
+        "Copy Only (Don't Paste)",
+        true,
+        copy_only,
+        None::<&str>,
+    )?;
+    let snippets = app
+        .try_state::<SnippetLibrary>()
+        .and_then(|library| library.list().ok())
+        .unwrap_or_default();
+    let snippet_menu = snippet_submenu(app, &snippets)?;
+    let show = MenuItem::with_id(app, SHOW_ID, "Settings...", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, QUIT_ID, "Quit", true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &toggle,
+            &snippet_menu,
+            &PredefinedMenuItem::separator(app)?,
+            &stt,
+            &llm,
+            &cleanup,
+            &sounds,
+            &copy_only_item,
+            &PredefinedMenuItem::separator(app)?,
+            &show,
+            &quit,
+        ],
+    )
+}
+
+/// Rebuild the tray menu so it reflects the latest state and settings
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                log::warn!("Failed to update tray menu: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to build tray menu: {}", e),
+    }
+}
+
+/// Save a setting chosen in the tray and let the windows pick it up
+fn update_setting(app: &AppHandle, key: &str, value: Value) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_FILE_NAME)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.set(key, value);
+    persist_store(app, &app.state::<SettingsManager>())?;
+    let _ = app.emit("settings-changed", ());
+    Ok(())
+}
+
+pub(crate) fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn paste_snippet(app: &AppHandle, id: &str) {
+    let Some(text) = app
+        .try_state::<SnippetLibrary>()
+        .and_then(|library| library.get(id).ok().flatten())
+        .map(|snippet| snippet.text)
+    else {
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::commands::text::paste_picked(app, text).await {
+            log::warn!("Failed to paste snippet: {}", e);
+        }
+    });
+}
+
+/// Handle a click on a tray menu item
+pub fn handle_menu_event(app: &AppHandle, id: &str) {
+    let result = match id {
+        TOGGLE_RECORDING_ID => {
+            #[cfg(desktop)]
+            crate::toggle_recording(app, "Tray");
+            Ok(())
+        }
+        SOUNDS_ID => {
+            let enabled = stored(app, SOUNDS_ID)
+                .and_then(|value| value.as_bool())
+                .unwrap_or(true);
+            update_setting(app, SOUNDS_ID, Value::Bool(!enabled))
+        }
+        COPY_ONLY_ID => {
+            let enabled = stored(app, COPY_ONLY_ID)
+                .and_then(|value| value.as_bool())
+                .unwrap_or(false);
+            update_setting(app, COPY_ONLY_ID, Value::Bool(!enabled))
+        }
+        SHOW_ID => {
+            show_main_window(app);
+            Ok(())
+        }
+        QUIT_ID => {
+            crate::shutdown::quit(app, 0);
+            Ok(())
+        }
+        _ => {
+            if let Some(provider) = id.strip_prefix(STT_PREFIX) {
+                update_setting(app, "stt_provider", Value::String(provider.to_string()))
+            } else if let Some(provider) = id.strip_prefix(LLM_PREFIX) {
+                update_setting(app, "llm_provider", Value::String(provider.to_string()))
+            } else if let Some(snippet_id) = id.strip_prefix(SNIPPET_PREFIX) {
+                paste_snippet(app, snippet_id);
+                Ok(())
+            } else if let Some(section) = id.strip_prefix(CLEANUP_PREFIX) {
+                let sections =
+                    toggle_cleanup_section(stored(app, "cleanup_prompt_sections"), section);
+                update_setting(app, "cleanup_prompt_sections", sections)
+            } else {
+                Ok(())
+            }
+        }
+    };
+
+    if let Err(e) = result {
+        log::warn!("Tray action {} failed: {}", id, e);
+    }
+    // Check items toggle themselves on click; rebuild so they match the saved settings
+    refresh(app);
+}
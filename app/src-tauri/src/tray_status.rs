@@ -0,0 +1,139 @@
+//! Tray-only mode: dictation status in the tray icon instead of the overlay.
+//!
+//! With the `tray_only_mode` setting the overlay pill is never shown (its
+//! webview still runs hidden, since it owns the microphone and the server
+//! connection). The tray icon shows a blinking red dot while recording and a
+//! steady amber dot while the dictation is processed. Pipeline errors are
+//! always posted as notifications in this mode (see `notifications`).
+
+use crate::overlay_state::OverlayState;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::{AppHandle, Manager};
+
+/// Id of the app's tray icon
+pub const TRAY_ID: &str = "main";
+
+/// Base tray icon (template image on macOS)
+const TRAY_ICON: &[u8] = include_bytes!("../icons/tray-iconTemplate@2x.png");
+
+/// Time between frames of the recording blink
+const BLINK_INTERVAL: Duration = Duration::from_millis(500);
+
+const RECORDING_COLOR: [u8; 3] = [0xef, 0x44, 0x44];
+const PROCESSING_COLOR: [u8; 3] = [0xf5, 0x9e, 0x0b];
+
+/// Whether tray-only mode is active, plus a generation counter that stops
+/// a running blink once the state changes
+pub struct TrayStatus {
+    enabled: bool,
+    generation: AtomicU64,
+}
+
+impl TrayStatus {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Load the base tray icon
+pub fn base_icon() -> tauri::Result<Image<'static>> {
+    Image::from_bytes(TRAY_ICON).map(|icon| icon.to_owned())
+}
+
+/// Fill a circle in the bottom-right quarter of an RGBA image
+pub fn draw_status_dot(rgba: &mut [u8], width: u32, height: u32, color: [u8; 3]) {
+    let radius = f64::from(width.min(height)) / 4.0;
+    let center = (f64::from(width) - radius, f64::from(height) - radius);
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = f64::from(x) + 0.5 - center.0;
+            let dy = f64::from(y) + 0.5 - center.1;
+            if dx * dx + dy * dy > radius * radius {
+                continue;
+            }
+            let offset = ((y * width + x) * 4) as usize;
+            if let Some(pixel) = rgba.get_mut(offset..offset + 4) {
+                pixel.copy_from_slice(&[color[0], color[1], color[2], 0xff]);
+            }
+        }
+    }
+}
+
+fn icon_with_dot(color: [u8; 3]) -> Option<Image<'static>> {
+    let icon = base_icon().ok()?;
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+    draw_status_dot(&mut rgba, width, height, color);
+    Some(Image::new_owned(rgba, width, height))
+}
+
+fn tooltip(state: &OverlayState) -> &'static str {
+    match state {
+        OverlayState::Idle => "Tambourine",
+        OverlayState::Recording => "Tambourine: recording",
+        OverlayState::Transcribing => "Tambourine: transcribing",
+        OverlayState::Cleaning => "Tambourine: cleaning up",
+        OverlayState::Pasting => "Tambourine: pasting",
+        OverlayState::Error { .. } => "Tambourine: error",
+    }
+}
+
+/// Set the tray icon; colored frames are not templates so the dot keeps its color
+fn set_icon(app: &AppHandle, dot: Option<[u8; 3]>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let icon = match dot {
+        Some(color) => icon_with_dot(color),
+        None => base_icon().ok(),
+    };
+    if let Some(icon) = icon {
+        let _ = tray.set_icon(Some(icon));
+        let _ = tray.set_icon_as_template(dot.is_none());
+    }
+}
+
+/// Reflect a pipeline state in the tray when tray-only mode is on
+pub fn show_state(app: &AppHandle, state: &OverlayState) {
+    let Some(status) = app.try_state::<TrayStatus>() else {
+        return;
+    };
+    if !status.enabled {
+        return;
+    }
+    let generation = status.generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(tooltip(state)));
+    }
+
+    match state {
+        OverlayState::Recording => {
+            let app = app.clone();
+            thread::spawn(move || {
+                let status = app.state::<TrayStatus>();
+                let mut lit = true;
+                while status.generation.load(Ordering::SeqCst) == generation {
+                    set_icon(&app, lit.then_some(RECORDING_COLOR));
+                    lit = !lit;
+                    thread::sleep(BLINK_INTERVAL);
+                }
+            });
+        }
+        OverlayState::Transcribing | OverlayState::Cleaning | OverlayState::Pasting => {
+            set_icon(app, Some(PROCESSING_COLOR));
+        }
+        OverlayState::Idle | OverlayState::Error { .. } => set_icon(app, None),
+    }
+}
@@ -0,0 +1,198 @@
+//! Vocabulary insights computed from dictation history.
+//!
+//! Two lists suggest terms for `stt_vocabulary`. Corrected words come from
+//! aligning each entry's raw transcript with its final text: a short run of
+//! words that speech-to-text heard one way and the cleanup (or a re-clean) wrote
+//! another is a correction. Frequent terms are words used in several dictations
+//! that look like names, acronyms or product names: capitalized mid-sentence, or
+//! mixing letters with digits or inner capitals. Terms already in the vocabulary
+//! are left out of both.
+
+use crate::history::HistoryEntry;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// How many suggestions each list holds
+pub const INSIGHT_LIMIT: usize = 20;
+
+/// Times a term must be used before it is suggested
+const MIN_TERM_USES: usize = 2;
+
+/// Longest run of words still treated as a correction rather than a rewrite
+const MAX_CORRECTION_WORDS: usize = 3;
+
+/// Entries longer than this many words (raw times final) aren't aligned
+const MAX_ALIGNMENT_CELLS: usize = 1_000_000;
+
+/// A word the cleanup kept correcting
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CorrectedWord {
+    /// The word as it was written in the end
+    pub word: String,
+    /// What speech-to-text heard instead, most frequent first
+    pub heard_as: Vec<String>,
+    pub count: usize,
+}
+
+/// A term and how often it was used
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TermCount {
+    pub term: String,
+    pub count: usize,
+}
+
+/// Suggestions for the vocabulary, most frequent first
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct VocabularyInsights {
+    pub corrected: Vec<CorrectedWord>,
+    pub frequent: Vec<TermCount>,
+}
+
+/// Words of a text, with surrounding punctuation trimmed
+pub fn words(text: &str) -> Vec<&str> {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Runs of words in `text` that replaced a different run in `raw`, as
+/// `(heard, written)` pairs; case changes, added and dropped words don't count
+pub fn corrections(raw: &str, text: &str) -> Vec<(String, String)> {
+    let heard = words(raw);
+    let written = words(text);
+    if heard.len() * written.len() > MAX_ALIGNMENT_CELLS {
+        return Vec::new();
+    }
+    let heard_lower: Vec<String> = heard.iter().map(|word| word.to_lowercase()).collect();
+    let written_lower: Vec<String> = written.iter().map(|word| word.to_lowercase()).collect();
+
+    // Longest common subsequence lengths of the suffixes
+    let (n, m) = (heard.len(), written.len());
+    let mut lengths = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if heard_lower[i] == written_lower[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let mut push_gap = |heard_gap: &[&str], written_gap: &[&str]| {
+        let is_correction = !heard_gap.is_empty()
+            && !written_gap.is_empty()
+            && heard_gap.len() <= MAX_CORRECTION_WORDS
+            && written_gap.len() <= MAX_CORRECTION_WORDS
+            && written_gap
+                .iter()
+                .any(|word| word.chars().any(char::is_alphabetic));
+        if is_correction {
+            pairs.push((heard_gap.join(" "), written_gap.join(" ")));
+        }
+    };
+    let (mut i, mut j) = (0, 0);
+    let (mut gap_i, mut gap_j) = (0, 0);
+    while i < n && j < m {
+        if heard_lower[i] == written_lower[j] {
+            push_gap(&heard[gap_i..i], &written[gap_j..j]);
+            i += 1;
+            j += 1;
+            gap_i = i;
+            gap_j = j;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    push_gap(&heard[gap_i..], &written[gap_j..]);
+    pairs
+}
+
+/// Whether a word looks like a name, acronym or product name
+fn looks_like_term(word: &str, sentence_start: bool) -> bool {
+    if word.chars().count() < 2 || word.contains('\'') || word.contains('’') {
+        return false;
+    }
+    let has_letter = word.chars().any(char::is_alphabetic);
+    let has_digit = word.chars().any(|c| c.is_ascii_digit());
+    let inner_capital = word.chars().skip(1).any(char::is_uppercase);
+    let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+    has_letter && (has_digit || inner_capital || (capitalized && !sentence_start))
+}
+
+/// Terms in a text that look like names, acronyms or product names
+pub fn terms(text: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut sentence_start = true;
+    for token in text.split_whitespace() {
+        let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+        if !word.is_empty() && looks_like_term(word, sentence_start) {
+            found.push(word);
+        }
+        sentence_start = token
+            .trim_end_matches(['"', '\'', ')', '”', '’'])
+            .ends_with(['.', '!', '?', ':']);
+    }
+    found
+}
+
+/// Suggest vocabulary from history, leaving out terms already in `vocabulary`
+pub fn insights(entries: &[HistoryEntry], vocabulary: &[String]) -> VocabularyInsights {
+    let known: HashSet<String> = vocabulary.iter().map(|term| term.to_lowercase()).collect();
+    let mut corrected: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    let mut frequent: BTreeMap<String, usize> = BTreeMap::new();
+
+    for entry in entries {
+        let text = entry.revised_text.as_deref().unwrap_or(&entry.text);
+        if let Some(raw) = entry.details.raw_text.as_deref() {
+            for (heard, written) in corrections(raw, text) {
+                if !known.contains(&written.to_lowercase()) {
+                    *corrected
+                        .entry(written)
+                        .or_default()
+                        .entry(heard)
+                        .or_default() += 1;
+                }
+            }
+        }
+        // Each entry counts once per term
+        let entry_terms: HashSet<&str> = terms(text).into_iter().collect();
+        for term in entry_terms {
+            if !known.contains(&term.to_lowercase()) {
+                *frequent.entry(term.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    let mut corrected: Vec<CorrectedWord> = corrected
+        .into_iter()
+        .map(|(word, heard)| {
+            let mut heard: Vec<(String, usize)> = heard.into_iter().collect();
+            heard.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            CorrectedWord {
+                word,
+                count: heard.iter().map(|(_, count)| count).sum(),
+                heard_as: heard.into_iter().map(|(heard, _)| heard).collect(),
+            }
+        })
+        .collect();
+    corrected.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+    corrected.truncate(INSIGHT_LIMIT);
+
+    let mut frequent: Vec<TermCount> = frequent
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_TERM_USES)
+        .map(|(term, count)| TermCount { term, count })
+        .collect();
+    frequent.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.term.cmp(&b.term)));
+    frequent.truncate(INSIGHT_LIMIT);
+
+    VocabularyInsights {
+        corrected,
+        frequent,
+    }
+}
@@ -0,0 +1,130 @@
+//! Webhooks posting each finished dictation to user-defined URLs, for integrations
+//! with n8n, Zapier, Home Assistant and the like.
+//!
+//! Without a template the payload is the history entry as JSON. A template is any
+//! text with `{{field}}` placeholders for the entry's fields (`text`, `raw_text`,
+//! `timestamp`, `target_app`, `tags`, ...) and `{{words}}`. Strings are inserted
+//! JSON-escaped without quotes, so `{"message": "{{text}}"}` stays valid JSON;
+//! missing values are inserted as nothing.
+
+use crate::history::HistoryEntry;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Time a webhook gets to answer
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A URL that finished dictations are posted to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Webhook {
+    pub url: String,
+    pub enabled: bool,
+    /// Request body with `{{field}}` placeholders, or the entry as JSON when unset
+    pub template: Option<String>,
+    /// Content type of the request body
+    pub content_type: String,
+}
+
+impl Default for Webhook {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            enabled: true,
+            template: None,
+            content_type: "application/json".to_string(),
+        }
+    }
+}
+
+/// A value as inserted into a template
+fn placeholder_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        // The JSON string without its quotes
+        Value::String(text) => {
+            let quoted = Value::String(text.clone()).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        }
+        other => other.to_string(),
+    }
+}
+
+/// The request body a webhook sends for an entry
+pub fn render_payload(webhook: &Webhook, entry: &HistoryEntry) -> String {
+    let Some(template) = webhook
+        .template
+        .as_deref()
+        .filter(|template| !template.trim().is_empty())
+    else {
+        return serde_json::to_string(entry).unwrap_or_default();
+    };
+
+    let mut fields = match serde_json::to_value(entry) {
+        Ok(Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    };
+    let text = entry.revised_text.as_deref().unwrap_or(&entry.text);
+    fields.insert(
+        "words".to_string(),
+        Value::from(text.split_whitespace().count()),
+    );
+
+    let mut payload = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        payload.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        match fields.get(after[..end].trim()) {
+            Some(value) => payload.push_str(&placeholder_value(value)),
+            // Unknown placeholders are kept, so typos are easy to spot
+            None => payload.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    payload.push_str(rest);
+    payload
+}
+
+/// Post an entry to one webhook, returning the HTTP status it answered with
+pub fn send(webhook: &Webhook, entry: &HistoryEntry) -> Result<u16, String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(WEBHOOK_TIMEOUT))
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let response = agent
+        .post(&webhook.url)
+        .content_type(&webhook.content_type)
+        .send(render_payload(webhook, entry))
+        .map_err(|e| format!("Failed to call webhook {}: {}", webhook.url, e))?;
+    Ok(response.status().as_u16())
+}
+
+/// Post a finished dictation to every enabled webhook, in the background
+pub fn notify_all(app: &AppHandle, entry: &HistoryEntry) {
+    let webhooks: Vec<Webhook> = crate::settings::read_setting::<Vec<Webhook>>(app, "webhooks")
+        .into_iter()
+        .filter(|webhook| webhook.enabled && !webhook.url.trim().is_empty())
+        .collect();
+    if webhooks.is_empty() {
+        return;
+    }
+    let entry = entry.clone();
+    thread::spawn(move || {
+        for webhook in &webhooks {
+            match send(webhook, &entry) {
+                Ok(status) if (200..300).contains(&status) => {}
+                Ok(status) => log::warn!("Webhook {} answered {}", webhook.url, status),
+                Err(e) => log::warn!("{}", e),
+            }
+        }
+    });
+}
@@ -1,22 +1,45 @@
-import { Kbd, Loader, NavLink, Text, Title, Tooltip } from "@mantine/core";
+import {
+	Anchor,
+	Kbd,
+	Loader,
+	NavLink,
+	Text,
+	Title,
+	Tooltip,
+} from "@mantine/core";
 import { notifications } from "@mantine/notifications";
 import { Home, Settings } from "lucide-react";
 import { useEffect, useState } from "react";
+import { FailedDictations } from "./components/FailedDictations";
+import { FileTranscriptions } from "./components/FileTranscriptions";
 import { HistoryFeed } from "./components/HistoryFeed";
 import { Logo } from "./components/Logo";
+import { MeetingPrompt } from "./components/MeetingPrompt";
+import { SnippetLibrary } from "./components/SnippetLibrary";
 import {
 	AudioSettings,
 	HotkeySettings,
 	PromptSettings,
 	ProvidersSettings,
+	VocabularySettings,
 } from "./components/settings";
 import {
 	DEFAULT_HOLD_HOTKEY,
 	DEFAULT_PASTE_LAST_HOTKEY,
 	DEFAULT_TOGGLE_HOTKEY,
 } from "./lib/hotkeyDefaults";
-import { useRefreshServerQueriesOnConnect, useSettings } from "./lib/queries";
-import { type ConfigResponse, type HotkeyConfig, tauriAPI } from "./lib/tauri";
+import { insertIntoFocusedField, isTextField } from "./lib/insertText";
+import {
+	useRefreshServerQueriesOnConnect,
+	useSettings,
+	useTrayMenuSync,
+} from "./lib/queries";
+import {
+	type ConfigResponse,
+	type HotkeyConfig,
+	type SettingsSection,
+	tauriAPI,
+} from "./lib/tauri";
 import { useRecordingStore } from "./stores/recordingStore";
 import "./styles.css";
 
@@ -180,6 +203,12 @@ function HomeView() {
 
 			<InstructionsCard />
 
+			<FailedDictations />
+
+			<FileTranscriptions />
+
+			<SnippetLibrary />
+
 			<HistoryFeed />
 		</div>
 	);
@@ -198,6 +227,7 @@ function SettingsView() {
 			</header>
 
 			<ProvidersSettings />
+			<VocabularySettings />
 			<AudioSettings />
 			<HotkeySettings />
 			<PromptSettings />
@@ -205,23 +235,136 @@ function SettingsView() {
 	);
 }
 
+// Settings section where each server setting can be fixed
+const SETTING_SECTIONS: Record<string, SettingsSection> = {
+	"stt-provider": "providers",
+	"llm-provider": "providers",
+	"stt-timeout": "providers",
+	"stt-vocabulary": "providers",
+	"prompt-sections": "prompts",
+};
+
 function formatSettingName(setting: string): string {
 	const names: Record<string, string> = {
 		"stt-provider": "STT provider",
 		"llm-provider": "LLM provider",
 		"prompt-sections": "Formatting prompt",
 		"stt-timeout": "STT timeout",
+		"stt-vocabulary": "STT vocabulary",
 	};
 	return names[setting] ?? setting;
 }
 
 export default function App() {
 	const [activeView, setActiveView] = useState<View>("home");
+	const [pendingSection, setPendingSection] = useState<SettingsSection | null>(
+		null,
+	);
 	const connectionState = useRecordingStore((s) => s.state);
 
 	// Refresh server-side queries when connection is established
 	useRefreshServerQueriesOnConnect(connectionState);
 
+	// Keep the tray menu's providers and the settings shown here in sync
+	useTrayMenuSync();
+
+	// Open the settings view (at a section) when asked by the backend
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onOpenSettings((section) => {
+				setActiveView("settings");
+				setPendingSection(section);
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
+
+	// Dictate into this window's own fields: report which has focus, and insert
+	// the text the backend sends while one does
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const reportFocus = () => {
+			tauriAPI.setInAppFieldFocused(isTextField(document.activeElement));
+		};
+		document.addEventListener("focusin", reportFocus);
+		document.addEventListener("focusout", reportFocus);
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onInsertText((text) => {
+				if (!insertIntoFocusedField(text)) {
+					console.warn("No focused field to insert dictated text into");
+				}
+			});
+		};
+
+		setup();
+
+		return () => {
+			document.removeEventListener("focusin", reportFocus);
+			document.removeEventListener("focusout", reportFocus);
+			unlisten?.();
+		};
+	}, []);
+
+	// Say if settings were restored from a backup at launch
+	useEffect(() => {
+		tauriAPI.takeSettingsRecovery().then((backup) => {
+			if (!backup) return;
+			notifications.show({
+				title: "Settings Restored",
+				message: `Your settings file was damaged and has been restored from ${backup}`,
+				color: "yellow",
+				autoClose: false,
+			});
+		});
+	}, []);
+
+	// Say what automatic pruning removed from history
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onHistoryPruned((summary) => {
+				const removed = summary.by_age + summary.by_count + summary.by_size;
+				// Dropping the oldest entry to make room for a new dictation is routine
+				if (removed === 1 && summary.by_count === 1) return;
+				const noun = removed === 1 ? "dictation" : "dictations";
+				const freedMb = summary.freed_bytes / (1024 * 1024);
+				notifications.show({
+					title: "History Pruned",
+					message: `Removed ${removed} old ${noun}, freeing ${freedMb.toFixed(1)} MB`,
+					color: "blue",
+					autoClose: 4000,
+				});
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
+
+	// Scroll to the requested section once the settings view is rendered
+	useEffect(() => {
+		if (activeView !== "settings" || !pendingSection) {
+			return;
+		}
+		document
+			.getElementById(`settings-${pendingSection}`)
+			?.scrollIntoView({ behavior: "smooth", block: "start" });
+		setPendingSection(null);
+	}, [activeView, pendingSection]);
+
 	// Listen for config response events from overlay window and show notifications
 	useEffect(() => {
 		let isMounted = true;
@@ -236,9 +379,25 @@ export default function App() {
 					autoClose: 2000,
 				});
 			} else if (response.type === "config-error") {
+				const section = SETTING_SECTIONS[response.setting];
 				notifications.show({
 					title: "Settings Error",
-					message: `Failed to update ${formatSettingName(response.setting)}: ${response.error}`,
+					message: (
+						<>
+							Failed to update {formatSettingName(response.setting)}:{" "}
+							{response.error}
+							{section && (
+								<Anchor
+									component="button"
+									size="sm"
+									ml={6}
+									onClick={() => tauriAPI.openSettings(section)}
+								>
+									Click to fix
+								</Anchor>
+							)}
+						</>
+					),
 					color: "red",
 					autoClose: 5000,
 				});
@@ -264,6 +423,7 @@ export default function App() {
 		<div className="app-layout">
 			<Sidebar activeView={activeView} onViewChange={setActiveView} />
 			{activeView === "home" ? <HomeView /> : <SettingsView />}
+			<MeetingPrompt />
 		</div>
 	);
 }
@@ -4,6 +4,7 @@ import { PipecatClient, RTVIEvent } from "@pipecat-ai/client-js";
 import {
 	PipecatClientProvider,
 	usePipecatClient,
+	usePipecatClientMediaTrack,
 	useRTVIClientEvent,
 } from "@pipecat-ai/client-react";
 import { SmallWebRTCTransport } from "@pipecat-ai/small-webrtc-transport";
@@ -11,18 +12,37 @@ import { ThemeProvider, UserAudioComponent } from "@pipecat-ai/voice-ui-kit";
 import { useQueryClient } from "@tanstack/react-query";
 import { listen } from "@tauri-apps/api/event";
 import { useDrag } from "@use-gesture/react";
+import { Check } from "lucide-react";
 import { useCallback, useEffect, useRef, useState } from "react";
 import { z } from "zod";
 import Logo from "./assets/logo.svg?react";
+import {
+	type CapturedAudio,
+	decodeForReplay,
+	pcmChunksBase64,
+	type RecordingRecovery,
+	REPLAY_SAMPLE_RATE,
+	startAudioCapture,
+} from "./lib/audioCapture";
+import { useAudioLevels } from "./lib/audioLevels";
+import { errorMessage, isCancelled } from "./lib/errors";
 import {
 	useAddHistoryEntry,
 	useServerUrl,
 	useSettings,
 	useTypeText,
 } from "./lib/queries";
+import { formatRecordingTime } from "./lib/recordingTime";
 import {
 	type CleanupPromptSections,
 	type ConnectionState,
+	DEFAULT_OVERLAY_APPEARANCE,
+	type HeldPaste,
+	type LocalePreferences,
+	type OverlayAppearance,
+	type OverlayLayout,
+	type OverlaySize,
+	type RecordingProgress,
 	tauriAPI,
 } from "./lib/tauri";
 import { useRecordingStore } from "./stores/recordingStore";
@@ -34,6 +54,22 @@ const TranscriptMessageSchema = z.object({
 	text: z.string(),
 });
 
+const CleanupStartedMessageSchema = z.object({
+	type: z.literal("cleanup-started"),
+	raw: z.string().optional(),
+});
+
+const RecleanResultMessageSchema = z.object({
+	type: z.literal("reclean-result"),
+	id: z.string(),
+	text: z.string(),
+});
+
+const PartialTranscriptMessageSchema = z.object({
+	type: z.literal("partial-transcript"),
+	text: z.string(),
+});
+
 const RecordingCompleteMessageSchema = z.object({
 	type: z.literal("recording-complete"),
 	hasContent: z.boolean().optional(),
@@ -61,7 +97,9 @@ type ConfigMessage =
 	| { type: "set-prompt-sections"; data: { sections: CleanupPromptSections } }
 	| { type: "set-stt-provider"; data: { provider: string } }
 	| { type: "set-llm-provider"; data: { provider: string } }
-	| { type: "set-stt-timeout"; data: { timeout_seconds: number } };
+	| { type: "set-stt-timeout"; data: { timeout_seconds: number } }
+	| { type: "set-locale"; data: { locale: LocalePreferences | null } }
+	| { type: "set-stt-vocabulary"; data: { terms: string[] } };
 
 // Helper to send multiple config messages - only callable with non-empty list
 function sendConfigMessages(
@@ -73,6 +111,79 @@ function sendConfigMessages(
 	}
 }
 
+// Recordings are kept on disk as they go, and recovered at launch after a crash
+const recordingRecovery: RecordingRecovery = {
+	begin: tauriAPI.beginRecordingRecovery,
+	append: tauriAPI.appendRecordingRecovery,
+	finish: tauriAPI.finishRecordingRecovery,
+};
+
+// Zoom applied to the whole overlay for each size preset
+const OVERLAY_SIZE_ZOOM: Record<OverlaySize, number> = {
+	compact: 0.8,
+	regular: 1,
+	large: 1.3,
+};
+
+/** Overlay appearance from settings, updated live when changed from another window */
+function useOverlayAppearance(): OverlayAppearance {
+	const { data: settings } = useSettings();
+	const [appearance, setAppearance] = useState(DEFAULT_OVERLAY_APPEARANCE);
+
+	useEffect(() => {
+		if (settings) {
+			setAppearance(settings.overlay_appearance);
+		}
+	}, [settings]);
+
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onOverlayAppearanceChanged(setAppearance);
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
+
+	return appearance;
+}
+
+/** Overlay layout from settings, updated live when changed from another window */
+function useOverlayLayout(): OverlayLayout {
+	const { data: settings } = useSettings();
+	const [layout, setLayout] = useState<OverlayLayout>("pill");
+
+	useEffect(() => {
+		if (settings) {
+			setLayout(settings.overlay_layout);
+		}
+	}, [settings]);
+
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onOverlayLayoutChanged(setLayout);
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
+
+	return layout;
+}
+
+// Characters of the live transcript shown in the panel layout (the latest ones)
+const PANEL_TRANSCRIPT_CHARS = 120;
+
 function RecordingControl() {
 	const client = usePipecatClient();
 	const queryClient = useQueryClient();
@@ -81,10 +192,13 @@ function RecordingControl() {
 		setClient,
 		startRecording,
 		stopRecording,
+		cancelRecording,
 		handleResponse,
 		handleConnected,
 		handleDisconnected,
 	} = useRecordingStore();
+	const appearance = useOverlayAppearance();
+	const layout = useOverlayLayout();
 
 	// Use Mantine's useResizeObserver hook
 	const [containerRef, rect] = useResizeObserver();
@@ -116,12 +230,79 @@ function RecordingControl() {
 	// TanStack Query hooks
 	const typeTextMutation = useTypeText();
 	const addHistoryEntry = useAddHistoryEntry();
+	// Transcript before cleanup, kept for the history entry
+	const rawTranscriptRef = useRef<string | null>(null);
+	// Recording of the current dictation, kept with its history entry when
+	// history keeps audio, and kept to retry it if the dictation fails
+	const audioCaptureRef = useRef<ReturnType<typeof startAudioCapture> | null>(
+		null,
+	);
+	const capturedAudioRef = useRef<Promise<CapturedAudio | null> | null>(null);
+	// Failed dictation being retried, whose result goes to history unpasted
+	const retryRef = useRef<{ id: string; audio: CapturedAudio } | null>(null);
+	// The dictation was cancelled after recording, so whatever the server still
+	// sends for it is dropped
+	const cancelledRef = useRef(false);
+	// Run of the current dictation in the backend, whose token cancelling it
+	// cancels; text is pasted under it so a cancelled dictation's text is refused
+	const dictationRef = useRef<number | null>(null);
+
+	// Keep the current dictation to retry, or note that its retry failed again
+	const handleFailure = useCallback(async (error: string) => {
+		const retry = retryRef.current;
+		retryRef.current = null;
+		const capturedAudio = capturedAudioRef.current;
+		capturedAudioRef.current = null;
+		const rawText = rawTranscriptRef.current;
+		rawTranscriptRef.current = null;
+		try {
+			if (retry) {
+				await tauriAPI.noteFailedDictationRetry(retry.id, error);
+			} else {
+				const audio = await capturedAudio;
+				if (!audio && !rawText) return;
+				await tauriAPI.recordFailedDictation(
+					error,
+					rawText,
+					audio?.bytes ?? null,
+					audio?.mimeType ?? null,
+				);
+			}
+			tauriAPI.emitFailedDictationsChanged();
+		} catch (failure) {
+			console.error("[Pipecat] Failed to keep failed dictation:", failure);
+		}
+	}, []);
+
+	// Save a dictation's recording with its history entry, when history keeps audio
+	const keepHistoryAudio = useCallback(
+		async (entryId: string, audio: CapturedAudio | null) => {
+			if (!audio || !settings?.history_keep_audio) return;
+			try {
+				await tauriAPI.saveHistoryAudio(
+					entryId,
+					audio.bytes,
+					audio.mimeType,
+					audio.waveform,
+				);
+				queryClient.invalidateQueries({ queryKey: ["history"] });
+			} catch (error) {
+				console.error("[Audio] Failed to keep recording:", error);
+			}
+		},
+		[queryClient, settings?.history_keep_audio],
+	);
 
 	// Response timeout (10s)
 	const { start: startResponseTimeout, clear: clearResponseTimeout } =
 		useTimeout(() => {
 			const currentState = useRecordingStore.getState().state;
 			if (currentState === "processing") {
+				tauriAPI.reportOverlayState({
+					state: "error",
+					message: "No response from server",
+				});
+				handleFailure("No response from server");
 				handleResponse(); // Reset to idle
 			}
 		}, 10000);
@@ -151,25 +332,168 @@ function RecordingControl() {
 		}
 	}, [rect.width, rect.height]);
 
+	// Re-apply the size after moving to a monitor with a different scale factor
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onOverlayScaleChanged(() => {
+				if (rect.width > 0 && rect.height > 0) {
+					tauriAPI.resizeOverlay(Math.ceil(rect.width), Math.ceil(rect.height));
+				}
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, [rect.width, rect.height]);
+
 	// Handle start/stop recording from hotkeys
+	// Report transitions to the backend too, since overlay clicks bypass the hotkey handlers
 	const onStartRecording = useCallback(async () => {
-		await startRecording();
-	}, [startRecording]);
+		if (await startRecording()) {
+			cancelledRef.current = false;
+			dictationRef.current = await tauriAPI.beginDictation().catch(() => null);
+			tauriAPI.reportOverlayState({ state: "recording" });
+			const track = client?.tracks()?.local?.audio;
+			if (track) {
+				try {
+					audioCaptureRef.current = startAudioCapture(
+						track,
+						recordingRecovery,
+					);
+				} catch (error) {
+					console.warn("[Audio] Failed to start keeping audio:", error);
+				}
+			}
+		}
+	}, [client, startRecording]);
 
 	const onStopRecording = useCallback(() => {
+		// Finish the recording before the track is stopped
+		capturedAudioRef.current = audioCaptureRef.current?.stop() ?? null;
+		audioCaptureRef.current = null;
 		if (stopRecording()) {
+			tauriAPI.reportOverlayState({ state: "transcribing" });
 			startResponseTimeout();
 		}
 	}, [stopRecording, startResponseTimeout]);
 
+	// The backend already reported idle, so only the audio and the server's work
+	// on it have to go
+	const onCancelRecording = useCallback(() => {
+		audioCaptureRef.current?.stop();
+		audioCaptureRef.current = null;
+		const processing = useRecordingStore.getState().state === "processing";
+		if (cancelRecording() && processing) {
+			cancelledRef.current = true;
+			clearResponseTimeout();
+			capturedAudioRef.current = null;
+			rawTranscriptRef.current = null;
+			retryRef.current = null;
+		}
+	}, [cancelRecording, clearResponseTimeout]);
+
+	// The app is quitting before the dictation finished: keep it to retry after
+	// the next launch, then let the backend know it can go on
+	const onDictationInterrupted = useCallback(async () => {
+		if (audioCaptureRef.current) {
+			capturedAudioRef.current = audioCaptureRef.current.stop();
+			audioCaptureRef.current = null;
+		}
+		clearResponseTimeout();
+		cancelledRef.current = true;
+		await handleFailure("The app quit before the dictation finished");
+		handleResponse();
+		tauriAPI.reportOverlayState({ state: "idle" });
+	}, [clearResponseTimeout, handleFailure, handleResponse]);
+
+	// Show pipeline errors reported by the backend until the next state change
+	const [pipelineError, setPipelineError] = useState<string | null>(null);
+	// Whether the last dictation was pasted, shown until the overlay auto-hides
+	const [pasted, setPasted] = useState(false);
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onOverlayState((overlayState) => {
+				setPipelineError(
+					overlayState.state === "error" ? overlayState.message : null,
+				);
+				if (overlayState.state === "pasting") {
+					setPasted(true);
+				} else if (overlayState.state !== "idle") {
+					setPasted(false);
+				}
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
+
+	// Text held back from pasting, pasted with a click on the overlay
+	const [heldPaste, setHeldPaste] = useState<HeldPaste | null>(null);
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onHeldPasteChanged(setHeldPaste);
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
+
+	// Elapsed time and limit of the running recording
+	const [progress, setProgress] = useState<RecordingProgress | null>(null);
+	const [partialTranscript, setPartialTranscript] = useState("");
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onRecordingProgress(setProgress);
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
+	useEffect(() => {
+		if (state !== "recording") {
+			setProgress(null);
+			setPartialTranscript("");
+		} else {
+			// Start the live transcript window over for the new recording
+			tauriAPI.updateLiveTranscript("");
+		}
+	}, [state]);
+
 	// Hotkey event listeners
 	useEffect(() => {
 		let unlistenStart: (() => void) | undefined;
 		let unlistenStop: (() => void) | undefined;
+		let unlistenCancel: (() => void) | undefined;
+		let unlistenInterrupted: (() => void) | undefined;
 
 		const setup = async () => {
 			unlistenStart = await tauriAPI.onStartRecording(onStartRecording);
 			unlistenStop = await tauriAPI.onStopRecording(onStopRecording);
+			unlistenCancel = await tauriAPI.onCancelRecording(onCancelRecording);
+			unlistenInterrupted = await tauriAPI.onDictationInterrupted(
+				onDictationInterrupted,
+			);
 		};
 
 		setup();
@@ -177,8 +501,15 @@ function RecordingControl() {
 		return () => {
 			unlistenStart?.();
 			unlistenStop?.();
+			unlistenCancel?.();
+			unlistenInterrupted?.();
 		};
-	}, [onStartRecording, onStopRecording]);
+	}, [
+		onStartRecording,
+		onStopRecording,
+		onCancelRecording,
+		onDictationInterrupted,
+	]);
 
 	// Listen for settings changes from main window and invalidate cache to trigger sync
 	useEffect(() => {
@@ -199,6 +530,82 @@ function RecordingControl() {
 		};
 	}, [queryClient]);
 
+	// Send history entries to be cleaned up again when asked from the main window
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onRecleanRequest(({ id, text }) => {
+				try {
+					client?.sendClientMessage("reclean-text", { id, text });
+				} catch (error) {
+					console.error("[Pipecat] Failed to request re-clean:", error);
+				}
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, [client]);
+
+	// Replay failed dictations through the pipeline when asked from the main window
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onRetryFailedDictation(async (id) => {
+				const { state: currentState, setState } = useRecordingStore.getState();
+				try {
+					if (currentState !== "idle" || !client) {
+						throw new Error("Not connected, or busy with another dictation");
+					}
+					const recording = await tauriAPI.getFailedDictationAudio(id);
+					if (!recording) {
+						throw new Error("The recording of this dictation is missing");
+					}
+					const bytes = new Uint8Array(recording.bytes);
+					const { pcm, waveform } = await decodeForReplay(bytes);
+					retryRef.current = {
+						id,
+						audio: { bytes, mimeType: recording.mime_type, waveform },
+					};
+					cancelledRef.current = false;
+					setState("processing");
+					client.sendClientMessage("start-recording", {});
+					for (const chunk of pcmChunksBase64(pcm)) {
+						client.sendClientMessage("replay-audio", {
+							audio: chunk,
+							sample_rate: REPLAY_SAMPLE_RATE,
+						});
+					}
+					client.sendClientMessage("stop-recording", {});
+					client.sendClientMessage("replay-audio-end", {});
+					startResponseTimeout();
+				} catch (error) {
+					console.error("[Pipecat] Failed to retry dictation:", error);
+					retryRef.current = null;
+					if (useRecordingStore.getState().state === "processing") {
+						handleResponse();
+					}
+					const message = errorMessage(error);
+					await tauriAPI
+						.noteFailedDictationRetry(id, message)
+						.catch(() => false);
+					tauriAPI.emitFailedDictationsChanged();
+				}
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, [client, startResponseTimeout, handleResponse]);
+
 	// Listen for disconnect request from Rust (triggered on app quit)
 	useEffect(() => {
 		let unlisten: (() => void) | undefined;
@@ -206,8 +613,12 @@ function RecordingControl() {
 		const setup = async () => {
 			unlisten = await listen("request-disconnect", async () => {
 				console.log("[Pipecat] Received disconnect request from Rust");
+				// Whatever was still in flight was finished or kept by now
+				audioCaptureRef.current?.stop();
+				audioCaptureRef.current = null;
 				if (client) {
 					try {
+						client.tracks()?.local?.audio?.stop();
 						await client.disconnect();
 						console.log("[Pipecat] Disconnected gracefully");
 					} catch (error) {
@@ -258,6 +669,16 @@ function RecordingControl() {
 						provider: settings.llm_provider,
 					});
 				}
+				if (settings?.locale_preferences) {
+					client?.sendClientMessage("set-locale", {
+						locale: settings.locale_preferences,
+					});
+				}
+				if (settings?.stt_vocabulary.length) {
+					client?.sendClientMessage("set-stt-vocabulary", {
+						terms: settings.stt_vocabulary,
+					});
+				}
 			}, 1000);
 		}, [client, settings, handleConnected]),
 	);
@@ -312,6 +733,26 @@ function RecordingControl() {
 				data: { timeout_seconds: settings.stt_timeout_seconds },
 			});
 		}
+		if (
+			settings &&
+			JSON.stringify(settings.locale_preferences) !==
+				JSON.stringify(prevSettings?.locale_preferences)
+		) {
+			messages.push({
+				type: "set-locale",
+				data: { locale: settings.locale_preferences },
+			});
+		}
+		if (
+			settings &&
+			JSON.stringify(settings.stt_vocabulary) !==
+				JSON.stringify(prevSettings?.stt_vocabulary ?? [])
+		) {
+			messages.push({
+				type: "set-stt-vocabulary",
+				data: { terms: settings.stt_vocabulary },
+			});
+		}
 
 		// Only send if there are messages (type guard ensures non-empty)
 		if (messages.length > 0) {
@@ -330,6 +771,11 @@ function RecordingControl() {
 			const currentState = useRecordingStore.getState().state;
 			if (currentState === "recording" || currentState === "processing") {
 				console.warn("[Pipecat] Disconnected during recording/processing");
+				if (audioCaptureRef.current) {
+					capturedAudioRef.current = audioCaptureRef.current.stop();
+					audioCaptureRef.current = null;
+				}
+				handleFailure("Disconnected from the server");
 				try {
 					client?.enableMic(false);
 					// Also stop the track to release the mic (removes OS mic indicator)
@@ -358,7 +804,7 @@ function RecordingControl() {
 					}
 				}, 3000);
 			}
-		}, [client, serverUrl, handleDisconnected]),
+		}, [client, serverUrl, handleDisconnected, handleFailure]),
 	);
 
 	// Server message handler
@@ -367,24 +813,118 @@ function RecordingControl() {
 		useCallback(
 			async (message: unknown) => {
 				const transcriptResult = TranscriptMessageSchema.safeParse(message);
+				if (transcriptResult.success && cancelledRef.current) {
+					console.debug("[Pipecat] Dropping transcript of cancelled dictation");
+					return;
+				}
 				if (transcriptResult.success) {
 					clearResponseTimeout();
 					const { text } = transcriptResult.data;
 					console.debug("[Pipecat] Transcript:", text);
+
+					// A retried failed dictation goes to history without being pasted
+					const retry = retryRef.current;
+					retryRef.current = null;
+					if (retry) {
+						addHistoryEntry.mutate(
+							{ text, rawText: rawTranscriptRef.current },
+							{
+								onSuccess: async (entry) => {
+									await keepHistoryAudio(entry.id, retry.audio);
+									await tauriAPI.discardFailedDictation(retry.id);
+									tauriAPI.emitFailedDictationsChanged();
+								},
+							},
+						);
+						rawTranscriptRef.current = null;
+						handleResponse();
+						return;
+					}
+
+					// "Insert my email signature" pastes that snippet instead
+					const snippet = await tauriAPI
+						.resolveSnippetCommand(text)
+						.catch(() => null);
+					if (snippet) {
+						try {
+							await typeTextMutation.mutateAsync({
+								text: snippet.text,
+								dictation: dictationRef.current,
+							});
+						} catch (error) {
+							console.error("[Pipecat] Failed to insert snippet:", error);
+						}
+						rawTranscriptRef.current = null;
+						capturedAudioRef.current = null;
+						handleResponse();
+						return;
+					}
+
 					try {
-						await typeTextMutation.mutateAsync(text);
+						if (settings?.review_before_paste) {
+							await tauriAPI.reviewTranscript(text);
+						} else {
+							await typeTextMutation.mutateAsync({
+								text,
+								dictation: dictationRef.current,
+							});
+						}
 					} catch (error) {
-						console.error("[Pipecat] Failed to type text:", error);
+						if (!isCancelled(error)) {
+							console.error("[Pipecat] Failed to type text:", error);
+						}
 					}
-					addHistoryEntry.mutate(text);
+					const capturedAudio = capturedAudioRef.current;
+					capturedAudioRef.current = null;
+					addHistoryEntry.mutate(
+						{ text, rawText: rawTranscriptRef.current },
+						{
+							onSuccess: async (entry) =>
+								keepHistoryAudio(entry.id, await capturedAudio),
+						},
+					);
+					rawTranscriptRef.current = null;
 					handleResponse();
 					return;
 				}
 
+				const recleanResult = RecleanResultMessageSchema.safeParse(message);
+				if (recleanResult.success) {
+					const { id, text } = recleanResult.data;
+					try {
+						await tauriAPI.addHistoryRevision(id, text);
+						queryClient.invalidateQueries({ queryKey: ["history"] });
+					} catch (error) {
+						console.error("[Pipecat] Failed to save revision:", error);
+					}
+					return;
+				}
+
+				const partialResult = PartialTranscriptMessageSchema.safeParse(message);
+				if (partialResult.success) {
+					setPartialTranscript(partialResult.data.text);
+					tauriAPI.updateLiveTranscript(partialResult.data.text);
+					return;
+				}
+
+				const cleanupStartedResult =
+					CleanupStartedMessageSchema.safeParse(message);
+				if (cleanupStartedResult.success) {
+					rawTranscriptRef.current = cleanupStartedResult.data.raw ?? null;
+					tauriAPI.reportOverlayState({ state: "cleaning" });
+					return;
+				}
+
 				const recordingCompleteResult =
 					RecordingCompleteMessageSchema.safeParse(message);
 				if (recordingCompleteResult.success) {
 					clearResponseTimeout();
+					// Nothing was transcribed, so there is no entry to keep audio for
+					capturedAudioRef.current = null;
+					if (retryRef.current) {
+						handleFailure("Nothing was transcribed");
+					}
+					tauriAPI.reportOverlayState({ state: "idle" });
 					handleResponse();
 					return;
 				}
@@ -411,16 +951,33 @@ function RecordingControl() {
 					return;
 				}
 			},
-			[clearResponseTimeout, typeTextMutation, addHistoryEntry, handleResponse],
+			[
+				clearResponseTimeout,
+				queryClient,
+				typeTextMutation,
+				addHistoryEntry,
+				keepHistoryAudio,
+				handleFailure,
+				handleResponse,
+				settings?.review_before_paste,
+			],
 		),
 	);
 
 	// Error handlers
 	useRTVIClientEvent(
 		RTVIEvent.Error,
-		useCallback((error: unknown) => {
-			console.error("[Pipecat] Error:", error);
-		}, []),
+		useCallback(
+			(error: unknown) => {
+				console.error("[Pipecat] Error:", error);
+				const message = errorMessage(error);
+				tauriAPI.reportOverlayState({ state: "error", message });
+				if (useRecordingStore.getState().state === "processing") {
+					handleFailure(message);
+				}
+			},
+			[handleFailure],
+		),
 	);
 
 	useRTVIClientEvent(
@@ -467,17 +1024,31 @@ function RecordingControl() {
 		{ filterTaps: true },
 	);
 
+	const showPasted =
+		pasted && state === "idle" && settings?.overlay_auto_hide_seconds != null;
+
+	const panelText =
+		partialTranscript.length > PANEL_TRANSCRIPT_CHARS
+			? `…${partialTranscript.slice(-PANEL_TRANSCRIPT_CHARS)}`
+			: partialTranscript;
+
 	return (
 		<div
 			ref={containerRef}
 			role="application"
+			title={pipelineError ?? undefined}
 			{...bindDrag()}
+			// Hovering pauses the auto-hide countdown
+			onMouseEnter={() => tauriAPI.setOverlayHovered(true)}
+			onMouseLeave={() => tauriAPI.setOverlayHovered(false)}
 			style={{
 				width: "fit-content",
 				height: "fit-content",
-				backgroundColor: "rgba(0, 0, 0, 0.9)",
+				backgroundColor: `rgba(0, 0, 0, ${appearance.opacity})`,
 				borderRadius: 12,
 				padding: 2,
+				zoom: OVERLAY_SIZE_ZOOM[appearance.size],
+				outline: pipelineError ? "2px solid #ef4444" : undefined,
 				cursor: "grab",
 				userSelect: "none",
 			}}
@@ -497,27 +1068,137 @@ function RecordingControl() {
 					<Loader size="sm" color="white" />
 				</div>
 			) : (
-				<UserAudioComponent
-					onClick={handleClick}
-					isMicEnabled={state === "recording"}
-					noIcon={true}
-					noDevicePicker={true}
-					noVisualizer={state !== "recording"}
-					visualizerProps={{
-						barColor: "#eeeeee",
-						backgroundColor: "#000000",
-					}}
-					classNames={{
-						button: "bg-black text-white hover:bg-gray-900",
+				<div style={{ display: "flex", alignItems: "center" }}>
+					<UserAudioComponent
+						onClick={handleClick}
+						isMicEnabled={state === "recording"}
+						noIcon={true}
+						noDevicePicker={true}
+						noVisualizer={true}
+						classNames={{
+							button: "bg-black text-white hover:bg-gray-900",
+						}}
+					>
+						{state === "recording" ? (
+							<LevelBars
+								color={appearance.accent_color}
+								reducedMotion={appearance.reduced_motion}
+							/>
+						) : showPasted ? (
+							<Check size={20} color="#22c55e" />
+						) : (
+							<Logo
+								className="size-5"
+								style={{ color: appearance.accent_color }}
+							/>
+						)}
+					</UserAudioComponent>
+					{state === "idle" && heldPaste && (
+						<button
+							type="button"
+							title={heldPaste.reason}
+							onClick={() => tauriAPI.pasteHeld()}
+							style={{
+								padding: "0 8px 0 4px",
+								fontSize: 12,
+								whiteSpace: "nowrap",
+								background: "none",
+								border: "none",
+								cursor: "pointer",
+								color: "#f59e0b",
+							}}
+						>
+							Paste {heldPaste.chars} chars
+						</button>
+					)}
+					{state === "recording" && progress && (
+						<span
+							style={{
+								padding: "0 8px 0 4px",
+								fontSize: 12,
+								fontVariantNumeric: "tabular-nums",
+								whiteSpace: "nowrap",
+								color: progress.near_limit
+									? "#f59e0b"
+									: appearance.accent_color,
+							}}
+						>
+							{formatRecordingTime(progress)}
+						</span>
+					)}
+				</div>
+			)}
+			{layout === "panel" && (
+				<div
+					style={{
+						width: 232,
+						height: 44,
+						overflow: "hidden",
+						padding: "2px 8px 6px",
+						fontSize: 12,
+						lineHeight: 1.3,
+						color: appearance.accent_color,
 					}}
 				>
-					{state !== "recording" && <Logo className="size-5" />}
-				</UserAudioComponent>
+					{panelText || (state === "recording" ? "Listening…" : null)}
+				</div>
 			)}
 		</div>
 	);
 }
 
+/**
+ * Live spectrum of the local microphone, drawn as bars. With reduced motion a
+ * static dot is shown instead.
+ */
+function LevelBars({
+	color,
+	reducedMotion,
+}: {
+	color: string;
+	reducedMotion: boolean;
+}) {
+	const micTrack = usePipecatClientMediaTrack("audio", "local");
+	const { bands } = useAudioLevels(reducedMotion ? null : micTrack);
+
+	if (reducedMotion) {
+		return (
+			<div
+				style={{
+					width: 10,
+					height: 10,
+					borderRadius: "50%",
+					backgroundColor: color,
+				}}
+			/>
+		);
+	}
+
+	return (
+		<div
+			style={{
+				display: "flex",
+				alignItems: "center",
+				gap: 1,
+				height: 20,
+			}}
+		>
+			{bands.map((value, index) => (
+				<div
+					// biome-ignore lint/suspicious/noArrayIndexKey: bands are fixed positions
+					key={index}
+					style={{
+						width: 2,
+						height: `${Math.max(10, value * 100)}%`,
+						borderRadius: 1,
+						backgroundColor: color,
+					}}
+				/>
+			))}
+		</div>
+	);
+}
+
 export default function OverlayApp() {
 	const [client, setClient] = useState<PipecatClient | null>(null);
 	const [devicesReady, setDevicesReady] = useState(false);
@@ -0,0 +1,93 @@
+import { Button, Group, Stack, Text, Textarea } from "@mantine/core";
+import { type KeyboardEvent, useCallback, useEffect, useState } from "react";
+import { describeError } from "./lib/errors";
+import { tauriAPI } from "./lib/tauri";
+import "./app.css";
+
+/**
+ * Review window: shows the cleaned dictation for a last edit.
+ * Enter pastes, Shift+Enter inserts a new line, Esc discards.
+ */
+export default function ReviewApp() {
+	const [text, setText] = useState("");
+	const [error, setError] = useState<string | null>(null);
+
+	// Load the pending text on open, and pick up new text when the window is reused
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			const pending = await tauriAPI.getPendingPaste();
+			setText(pending ?? "");
+			unlisten = await tauriAPI.onPasteReview((reviewText) => {
+				setText(reviewText);
+				setError(null);
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
+
+	const confirm = useCallback(async () => {
+		try {
+			await tauriAPI.confirmPaste(text);
+			setError(null);
+		} catch (err) {
+			setError(describeError(err));
+		}
+	}, [text]);
+
+	const discard = useCallback(async () => {
+		await tauriAPI.discardPending();
+		setError(null);
+	}, []);
+
+	const handleKeyDown = useCallback(
+		(event: KeyboardEvent<HTMLTextAreaElement>) => {
+			if (event.key === "Enter" && !event.shiftKey) {
+				event.preventDefault();
+				confirm();
+			} else if (event.key === "Escape") {
+				event.preventDefault();
+				discard();
+			}
+		},
+		[confirm, discard],
+	);
+
+	return (
+		<Stack gap="xs" p="sm">
+			<Textarea
+				value={text}
+				onChange={(event) => setText(event.currentTarget.value)}
+				onKeyDown={handleKeyDown}
+				autosize
+				minRows={2}
+				maxRows={6}
+				autoFocus
+			/>
+			{error && (
+				<Text size="xs" c="red">
+					{error}
+				</Text>
+			)}
+			<Group justify="space-between">
+				<Text size="xs" c="dimmed">
+					Enter to paste · Shift+Enter for a new line · Esc to discard
+				</Text>
+				<Group gap="xs">
+					<Button size="xs" variant="default" onClick={discard}>
+						Discard
+					</Button>
+					<Button size="xs" onClick={confirm}>
+						Paste
+					</Button>
+				</Group>
+			</Group>
+		</Stack>
+	);
+}
@@ -0,0 +1,81 @@
+import { Button, Group, ScrollArea, Stack, Text } from "@mantine/core";
+import { useCallback, useEffect, useRef, useState } from "react";
+import { errorMessage } from "./lib/errors";
+import { tauriAPI } from "./lib/tauri";
+import "./app.css";
+
+/**
+ * Live transcript window: the text of the running dictation, kept after it
+ * ends so it can be copied.
+ */
+export default function TranscriptApp() {
+	const [text, setText] = useState("");
+	const [status, setStatus] = useState<string | null>(null);
+	const viewportRef = useRef<HTMLDivElement>(null);
+
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			setText(await tauriAPI.getLiveTranscript());
+			unlisten = await tauriAPI.onLiveTranscript((liveText) => {
+				setText(liveText);
+				setStatus(null);
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
+
+	// Keep the newest words in view
+	useEffect(() => {
+		const viewport = viewportRef.current;
+		if (viewport && text) {
+			viewport.scrollTo({ top: viewport.scrollHeight });
+		}
+	}, [text]);
+
+	const copy = useCallback(async () => {
+		try {
+			await tauriAPI.copyLiveTranscript();
+			setStatus("Copied");
+		} catch (err) {
+			setStatus(errorMessage(err));
+		}
+	}, []);
+
+	return (
+		<Stack gap="xs" p="sm" h="100vh">
+			<ScrollArea flex={1} viewportRef={viewportRef}>
+				<Text size="sm" style={{ whiteSpace: "pre-wrap" }}>
+					{text || (
+						<Text span c="dimmed" size="sm">
+							The transcript appears here while you dictate.
+						</Text>
+					)}
+				</Text>
+			</ScrollArea>
+			<Group justify="space-between">
+				<Text size="xs" c="dimmed">
+					{status}
+				</Text>
+				<Group gap="xs">
+					<Button
+						size="xs"
+						variant="default"
+						onClick={() => tauriAPI.closeTranscriptWindow()}
+					>
+						Close
+					</Button>
+					<Button size="xs" onClick={copy} disabled={!text}>
+						Copy
+					</Button>
+				</Group>
+			</Group>
+		</Stack>
+	);
+}
@@ -0,0 +1,111 @@
+import { ActionIcon } from "@mantine/core";
+import { useClipboard } from "@mantine/hooks";
+import { useQueryClient } from "@tanstack/react-query";
+import { format } from "date-fns";
+import { Copy, RotateCcw, Trash2 } from "lucide-react";
+import { useEffect } from "react";
+import { useDiscardFailedDictation, useFailedDictations } from "../lib/queries";
+import { type FailedDictation, tauriAPI } from "../lib/tauri";
+
+/** Providers in use when a dictation failed, e.g. "deepgram / openai" */
+function providers(dictation: FailedDictation): string | null {
+	const names = [dictation.stt_provider, dictation.llm_provider].filter(
+		(name): name is string => Boolean(name),
+	);
+	return names.length > 0 ? names.join(" / ") : null;
+}
+
+/**
+ * Dictations whose pipeline run failed, kept with their recording so they can
+ * be retried once the provider is back, or discarded. Hidden when empty.
+ */
+export function FailedDictations() {
+	const queryClient = useQueryClient();
+	const { data: failed } = useFailedDictations();
+	const discard = useDiscardFailedDictation();
+	const clipboard = useClipboard();
+
+	// Refresh when the overlay files a failure or finishes a retry
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onFailedDictationsChanged(() => {
+				queryClient.invalidateQueries({ queryKey: ["failedDictations"] });
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, [queryClient]);
+
+	if (!failed || failed.length === 0) return null;
+
+	return (
+		<div className="animate-in animate-in-delay-2" style={{ marginBottom: 32 }}>
+			<div className="section-header">
+				<span className="section-title">Failed Dictations</span>
+			</div>
+
+			<div className="history-feed">
+				{failed.map((dictation) => (
+					<div key={dictation.id} className="history-item">
+						<span className="history-time">
+							{format(new Date(dictation.timestamp), "MMM d, h:mm a")}
+						</span>
+						<div className="history-text">
+							<p style={{ color: "var(--mantine-color-red-5)", margin: 0 }}>
+								{dictation.error}
+								{providers(dictation) && ` (${providers(dictation)})`}
+								{dictation.retries > 0 &&
+									`, ${dictation.retries} failed ${
+										dictation.retries === 1 ? "retry" : "retries"
+									}`}
+							</p>
+							{dictation.raw_text && <p>{dictation.raw_text}</p>}
+						</div>
+						<div className="history-actions">
+							{dictation.mime_type && (
+								<ActionIcon
+									variant="subtle"
+									size="sm"
+									color="gray"
+									onClick={() =>
+										tauriAPI.emitRetryFailedDictation(dictation.id)
+									}
+									title="Retry"
+								>
+									<RotateCcw size={14} />
+								</ActionIcon>
+							)}
+							{dictation.raw_text && (
+								<ActionIcon
+									variant="subtle"
+									size="sm"
+									color="gray"
+									onClick={() => clipboard.copy(dictation.raw_text)}
+									title="Copy the transcript before cleanup"
+								>
+									<Copy size={14} />
+								</ActionIcon>
+							)}
+							<ActionIcon
+								variant="subtle"
+								size="sm"
+								color="red"
+								onClick={() => discard.mutate(dictation.id)}
+								title="Discard"
+								disabled={discard.isPending}
+							>
+								<Trash2 size={14} />
+							</ActionIcon>
+						</div>
+					</div>
+				))}
+			</div>
+		</div>
+	);
+}
@@ -0,0 +1,171 @@
+import { ActionIcon, Button, Loader } from "@mantine/core";
+import { useClipboard } from "@mantine/hooks";
+import { useQueryClient } from "@tanstack/react-query";
+import { getCurrentWebview } from "@tauri-apps/api/webview";
+import { Copy, FileAudio, X } from "lucide-react";
+import { useEffect, useState } from "react";
+import { useFileTranscriptions, useTranscribeFiles } from "../lib/queries";
+import { type FileJob, tauriAPI } from "../lib/tauri";
+
+function statusText(job: FileJob): string {
+	switch (job.status) {
+		case "queued":
+			return "Waiting…";
+		case "extracting":
+			return "Reading audio…";
+		case "transcribing":
+			return job.cleanup ? "Transcribing and cleaning up…" : "Transcribing…";
+		case "done":
+			return job.text;
+		case "failed":
+			return job.message;
+		case "cancelled":
+			return "Cancelled";
+	}
+}
+
+/**
+ * Audio and video files dropped on the window, transcribed one after another
+ * into history. Hidden while no files are queued or dragged over the window.
+ */
+export function FileTranscriptions() {
+	const queryClient = useQueryClient();
+	const { data: jobs } = useFileTranscriptions();
+	const transcribe = useTranscribeFiles();
+	const clipboard = useClipboard();
+	const [dragging, setDragging] = useState(false);
+
+	// Queue files dropped anywhere on the window
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await getCurrentWebview().onDragDropEvent((event) => {
+				if (event.payload.type === "over") {
+					setDragging(true);
+				} else if (event.payload.type === "drop") {
+					setDragging(false);
+					if (event.payload.paths.length > 0) {
+						transcribe.mutate(event.payload.paths);
+					}
+				} else if (event.payload.type === "leave") {
+					setDragging(false);
+				}
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, [transcribe.mutate]);
+
+	// Follow the progress of each file
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onFileTranscription(() => {
+				queryClient.invalidateQueries({ queryKey: ["fileTranscriptions"] });
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, [queryClient]);
+
+	const hasFinished = jobs?.some((job) =>
+		["done", "failed", "cancelled"].includes(job.status),
+	);
+
+	if (!dragging && (!jobs || jobs.length === 0)) return null;
+
+	return (
+		<div className="animate-in" style={{ marginBottom: 32 }}>
+			<div className="section-header">
+				<span className="section-title">File Transcriptions</span>
+				{hasFinished && (
+					<Button
+						variant="subtle"
+						size="compact-xs"
+						color="gray"
+						onClick={async () => {
+							await tauriAPI.clearFinishedFileTranscriptions();
+							queryClient.invalidateQueries({
+								queryKey: ["fileTranscriptions"],
+							});
+						}}
+					>
+						Clear finished
+					</Button>
+				)}
+			</div>
+
+			{dragging && (
+				<div className="empty-state">
+					<FileAudio className="empty-state-icon" />
+					<p className="empty-state-text">
+						Drop audio or video files to transcribe them into your history
+					</p>
+				</div>
+			)}
+
+			<div className="history-feed">
+				{jobs?.map((job) => (
+					<div key={job.id} className="history-item">
+						<span className="history-time" title={job.path}>
+							<FileAudio size={12} /> {job.name}
+						</span>
+						<div className="history-text">
+							<p
+								style={{
+									margin: 0,
+									color:
+										job.status === "failed"
+											? "var(--mantine-color-red-5)"
+											: job.status === "done"
+												? undefined
+												: "var(--mantine-color-dimmed)",
+								}}
+							>
+								{(job.status === "extracting" ||
+									job.status === "transcribing") && (
+									<Loader size={10} mr={6} />
+								)}
+								{statusText(job)}
+							</p>
+						</div>
+						<div className="history-actions">
+							{job.status === "done" && (
+								<ActionIcon
+									variant="subtle"
+									size="sm"
+									color="gray"
+									onClick={() => clipboard.copy(job.text)}
+									title="Copy"
+								>
+									<Copy size={14} />
+								</ActionIcon>
+							)}
+							{job.status === "queued" && (
+								<ActionIcon
+									variant="subtle"
+									size="sm"
+									color="gray"
+									onClick={() => tauriAPI.cancelFileTranscription(job.id)}
+									title="Cancel"
+								>
+									<X size={14} />
+								</ActionIcon>
+							)}
+						</div>
+					</div>
+				))}
+			</div>
+		</div>
+	);
+}
@@ -1,15 +1,62 @@
-import { ActionIcon, Button, Group, Modal, Text } from "@mantine/core";
-import { useClipboard, useDisclosure } from "@mantine/hooks";
+import {
+	ActionIcon,
+	Badge,
+	Button,
+	Group,
+	Modal,
+	PasswordInput,
+	Select,
+	TagsInput,
+	Text,
+	Textarea,
+	TextInput,
+} from "@mantine/core";
+import {
+	useClipboard,
+	useDebouncedValue,
+	useDisclosure,
+} from "@mantine/hooks";
 import { useQueryClient } from "@tanstack/react-query";
-import { format, isToday, isYesterday } from "date-fns";
-import { Copy, MessageSquare, Trash2 } from "lucide-react";
-import { useEffect } from "react";
+import {
+	format,
+	isToday,
+	isYesterday,
+	startOfDay,
+	startOfMonth,
+	startOfWeek,
+} from "date-fns";
+import {
+	BookmarkPlus,
+	Copy,
+	Lock,
+	MessageSquare,
+	Pin,
+	Play,
+	RefreshCw,
+	Search,
+	Tag,
+	Trash2,
+} from "lucide-react";
+import { useEffect, useState } from "react";
+import { errorMessage } from "../lib/errors";
 import {
 	useClearHistory,
 	useDeleteHistoryEntry,
 	useHistory,
+	useHistoryApps,
+	useHistoryLocked,
+	useHistoryTags,
+	useSetHistoryEntryPinned,
+	useUnlockHistory,
+	useUpdateHistoryEntryLabels,
 } from "../lib/queries";
-import { tauriAPI } from "../lib/tauri";
+import {
+	type CollapsedHistoryEntry,
+	type HistoryRange,
+	tauriAPI,
+} from "../lib/tauri";
+import { HistoryTrash } from "./HistoryTrash";
+import { type SnippetDraft, SnippetEditorModal } from "./SnippetLibrary";
 
 function formatTime(timestamp: string): string {
 	return format(new Date(timestamp), "h:mm a");
@@ -22,17 +69,170 @@ function formatDate(timestamp: string): string {
 	return format(date, "MMM d");
 }
 
+type Period = "all" | "today" | "week" | "month";
+
+const PERIOD_OPTIONS: { value: Period; label: string }[] = [
+	{ value: "all", label: "All time" },
+	{ value: "today", label: "Today" },
+	{ value: "week", label: "This week" },
+	{ value: "month", label: "This month" },
+];
+
+/** The range from the start of the period until now */
+function periodRange(period: Period): HistoryRange {
+	const now = new Date();
+	const starts: Record<Period, Date | null> = {
+		all: null,
+		today: startOfDay(now),
+		week: startOfWeek(now, { weekStartsOn: 1 }),
+		month: startOfMonth(now),
+	};
+	const from = starts[period];
+	return from ? { from: from.toISOString() } : {};
+}
+
+/** Waveform preview and player for an entry's kept recording */
+function HistoryAudioPlayer({ entryId }: { entryId: string }) {
+	const [audio, setAudio] = useState<{
+		url: string;
+		waveform: number[];
+	} | null>(null);
+	const [failed, setFailed] = useState(false);
+
+	useEffect(() => {
+		let url: string | null = null;
+		let cancelled = false;
+		tauriAPI
+			.getHistoryAudio(entryId)
+			.then((recording) => {
+				if (cancelled) return;
+				if (!recording) {
+					setFailed(true);
+					return;
+				}
+				const blob = new Blob([new Uint8Array(recording.bytes)], {
+					type: recording.mime_type,
+				});
+				url = URL.createObjectURL(blob);
+				setAudio({ url, waveform: recording.waveform });
+			})
+			.catch(() => {
+				if (!cancelled) setFailed(true);
+			});
+
+		return () => {
+			cancelled = true;
+			if (url) URL.revokeObjectURL(url);
+		};
+	}, [entryId]);
+
+	if (failed) {
+		return (
+			<Text size="xs" c="dimmed" ml={24}>
+				The recording is no longer available
+			</Text>
+		);
+	}
+	if (!audio) return null;
+
+	return (
+		<div style={{ marginLeft: 24, marginBottom: 8 }}>
+			<svg
+				width="100%"
+				height={24}
+				viewBox={`0 0 ${audio.waveform.length} 1`}
+				preserveAspectRatio="none"
+				aria-hidden
+			>
+				{audio.waveform.map((peak, index) => (
+					<rect
+						// biome-ignore lint/suspicious/noArrayIndexKey: fixed-length preview
+						key={index}
+						x={index + 0.15}
+						y={(1 - peak) / 2}
+						width={0.7}
+						height={Math.max(peak, 0.02)}
+						fill="currentColor"
+						opacity={0.5}
+					/>
+				))}
+			</svg>
+			{/* biome-ignore lint/a11y/useMediaCaption: dictation audio has its transcript beside it */}
+			<audio src={audio.url} controls autoPlay style={{ width: "100%" }} />
+		</div>
+	);
+}
+
+/** Editor for an entry's tags and note */
+function EntryLabelsModal({
+	entry,
+	onClose,
+}: {
+	entry: CollapsedHistoryEntry | null;
+	onClose: () => void;
+}) {
+	const { data: knownTags } = useHistoryTags();
+	const updateLabels = useUpdateHistoryEntryLabels();
+	const [tags, setTags] = useState<string[]>([]);
+	const [note, setNote] = useState("");
+
+	useEffect(() => {
+		setTags(entry?.tags ?? []);
+		setNote(entry?.note ?? "");
+	}, [entry]);
+
+	const handleSave = () => {
+		if (!entry) return;
+		updateLabels.mutate(
+			{ id: entry.id, tags, note: note.trim() || null },
+			{ onSuccess: onClose },
+		);
+	};
+
+	return (
+		<Modal
+			opened={entry !== null}
+			onClose={onClose}
+			title="Tags and note"
+			centered
+			size="sm"
+		>
+			<TagsInput
+				label="Tags"
+				placeholder="email, blog, journal..."
+				data={knownTags?.map((known) => known.tag) ?? []}
+				value={tags}
+				onChange={setTags}
+				clearable
+				mb="sm"
+			/>
+			<Textarea
+				label="Note"
+				value={note}
+				onChange={(event) => setNote(event.currentTarget.value)}
+				autosize
+				minRows={2}
+				mb="lg"
+			/>
+			<Group justify="flex-end">
+				<Button variant="default" onClick={onClose}>
+					Cancel
+				</Button>
+				<Button onClick={handleSave} loading={updateLabels.isPending}>
+					Save
+				</Button>
+			</Group>
+		</Modal>
+	);
+}
+
 interface GroupedHistory {
 	date: string;
-	items: Array<{
-		id: string;
-		text: string;
-		timestamp: string;
-	}>;
+	items: CollapsedHistoryEntry[];
 }
 
 function groupHistoryByDate(
-	history: Array<{ id: string; text: string; timestamp: string }>,
+	history: CollapsedHistoryEntry[],
 ): GroupedHistory[] {
 	const groups: Record<string, GroupedHistory> = {};
 
@@ -49,34 +249,89 @@ function groupHistoryByDate(
 
 export function HistoryFeed() {
 	const queryClient = useQueryClient();
-	const { data: history, isLoading, error } = useHistory(100);
+	const [search, setSearch] = useState("");
+	const [debouncedSearch] = useDebouncedValue(search, 250);
+	// App the feed is narrowed to, and the period it covers then
+	const [targetApp, setTargetApp] = useState<string | null>(null);
+	const [period, setPeriod] = useState<Period>("all");
+	const range = periodRange(period);
+	const { data: apps } = useHistoryApps();
+	const { data: history, isLoading, error } = useHistory(
+		100,
+		debouncedSearch,
+		targetApp ? { app: targetApp, range } : null,
+	);
 	const deleteEntry = useDeleteHistoryEntry();
+	const setPinned = useSetHistoryEntryPinned();
 	const clearHistory = useClearHistory();
 	const clipboard = useClipboard();
+	const { data: locked } = useHistoryLocked();
+	const unlockHistory = useUnlockHistory();
+	const [passphrase, setPassphrase] = useState("");
+	// Entries whose collapsed near-duplicates are shown
+	const [expanded, setExpanded] = useState<Set<string>>(new Set());
+	// Entry whose recording is being played
+	const [playing, setPlaying] = useState<string | null>(null);
+	// Dictation being saved as a snippet
+	const [snippetDraft, setSnippetDraft] = useState<SnippetDraft | null>(null);
+	// Entry whose tags and note are being edited
+	const [labelling, setLabelling] = useState<CollapsedHistoryEntry | null>(
+		null,
+	);
 	const [confirmOpened, { open: openConfirm, close: closeConfirm }] =
 		useDisclosure(false);
 
-	// Listen for history changes from other windows (e.g., overlay after transcription)
+	// Reload as the backend reports changes, such as a dictation completing
 	useEffect(() => {
-		let unlisten: (() => void) | undefined;
+		const unlisteners: (() => void)[] = [];
+		let cancelled = false;
+		const reload = () => {
+			queryClient.invalidateQueries({ queryKey: ["history"] });
+		};
 
 		const setup = async () => {
-			unlisten = await tauriAPI.onHistoryChanged(() => {
-				queryClient.invalidateQueries({ queryKey: ["history"] });
-			});
+			const listeners = await Promise.all([
+				tauriAPI.onHistoryEntryAdded(reload),
+				tauriAPI.onHistoryEntryUpdated(reload),
+				tauriAPI.onHistoryEntryDeleted(reload),
+				tauriAPI.onHistoryChanged(reload),
+				tauriAPI.onHistoryPruned(reload),
+			]);
+			if (cancelled) {
+				for (const unlisten of listeners) unlisten();
+			} else {
+				unlisteners.push(...listeners);
+			}
 		};
 
 		setup();
 
 		return () => {
-			unlisten?.();
+			cancelled = true;
+			for (const unlisten of unlisteners) unlisten();
 		};
 	}, [queryClient]);
 
+	const toggleDuplicates = (id: string) => {
+		setExpanded((current) => {
+			const next = new Set(current);
+			if (!next.delete(id)) next.add(id);
+			return next;
+		});
+	};
+
 	const handleDelete = (id: string) => {
 		deleteEntry.mutate(id);
 	};
 
+	// Clean up the original transcript again with the current provider and prompt
+	const handleReclean = (entry: GroupedHistory["items"][number]) => {
+		tauriAPI.emitRecleanRequest({
+			id: entry.id,
+			text: entry.raw_text ?? entry.text,
+		});
+	};
+
 	const handleClearAll = () => {
 		clearHistory.mutate(undefined, {
 			onSuccess: () => {
@@ -85,6 +340,53 @@ export function HistoryFeed() {
 		});
 	};
 
+	const handleUnlock = () => {
+		unlockHistory.mutate(passphrase, {
+			onSuccess: () => {
+				setPassphrase("");
+			},
+		});
+	};
+
+	if (locked) {
+		return (
+			<div className="animate-in animate-in-delay-2">
+				<div className="section-header">
+					<span className="section-title">History</span>
+				</div>
+				<div className="empty-state">
+					<Lock className="empty-state-icon" />
+					<h4 className="empty-state-title">History is locked</h4>
+					<p className="empty-state-text">
+						Enter your history passphrase to view past dictations.
+					</p>
+					<Group gap="xs" mt="md" justify="center">
+						<PasswordInput
+							size="xs"
+							placeholder="Passphrase"
+							value={passphrase}
+							onChange={(event) => setPassphrase(event.currentTarget.value)}
+							onKeyDown={(event) => {
+								if (event.key === "Enter" && passphrase) handleUnlock();
+							}}
+							error={
+								unlockHistory.error ? errorMessage(unlockHistory.error) : null
+							}
+						/>
+						<Button
+							size="xs"
+							onClick={handleUnlock}
+							disabled={!passphrase}
+							loading={unlockHistory.isPending}
+						>
+							Unlock
+						</Button>
+					</Group>
+				</div>
+			</div>
+		);
+	}
+
 	if (isLoading) {
 		return (
 			<div className="animate-in animate-in-delay-2">
@@ -113,11 +415,12 @@ export function HistoryFeed() {
 		);
 	}
 
-	if (!history || history.length === 0) {
+	if (!search && (!history || history.length === 0)) {
 		return (
 			<div className="animate-in animate-in-delay-2">
 				<div className="section-header">
 					<span className="section-title">History</span>
+					<HistoryTrash />
 				</div>
 				<div className="empty-state">
 					<MessageSquare className="empty-state-icon" />
@@ -131,21 +434,24 @@ export function HistoryFeed() {
 		);
 	}
 
-	const groupedHistory = groupHistoryByDate(history);
+	const groupedHistory = groupHistoryByDate(history ?? []);
 
 	return (
 		<div className="animate-in animate-in-delay-2">
 			<div className="section-header">
 				<span className="section-title">History</span>
-				<Button
-					variant="subtle"
-					size="compact-sm"
-					color="gray"
-					onClick={openConfirm}
-					disabled={clearHistory.isPending}
-				>
-					Clear All
-				</Button>
+				<Group gap="xs">
+					<HistoryTrash />
+					<Button
+						variant="subtle"
+						size="compact-sm"
+						color="gray"
+						onClick={openConfirm}
+						disabled={clearHistory.isPending}
+					>
+						Clear All
+					</Button>
+				</Group>
 			</div>
 
 			<Modal
@@ -156,8 +462,8 @@ export function HistoryFeed() {
 				size="sm"
 			>
 				<Text size="sm" mb="lg">
-					Are you sure you want to clear all history? This action cannot be
-					undone.
+					Are you sure you want to clear all history? Entries move to the
+					trash, where they can be restored until it is emptied.
 				</Text>
 				<Group justify="flex-end">
 					<Button variant="default" onClick={closeConfirm}>
@@ -173,6 +479,46 @@ export function HistoryFeed() {
 				</Group>
 			</Modal>
 
+			<EntryLabelsModal entry={labelling} onClose={() => setLabelling(null)} />
+			<SnippetEditorModal
+				draft={snippetDraft}
+				onClose={() => setSnippetDraft(null)}
+			/>
+
+			<Group gap="xs" mb="md" wrap="nowrap">
+				<TextInput
+					placeholder="Search history, or #tag"
+					leftSection={<Search size={14} />}
+					value={search}
+					onChange={(event) => setSearch(event.currentTarget.value)}
+					style={{ flex: 1 }}
+				/>
+				<Select
+					placeholder="All apps"
+					data={apps?.map((known) => known.app) ?? []}
+					value={targetApp}
+					onChange={setTargetApp}
+					clearable
+					searchable
+					w={160}
+				/>
+				{targetApp && (
+					<Select
+						data={PERIOD_OPTIONS}
+						value={period}
+						onChange={(value) => setPeriod((value as Period) ?? "all")}
+						allowDeselect={false}
+						w={130}
+					/>
+				)}
+			</Group>
+
+			{groupedHistory.length === 0 && (
+				<div className="empty-state">
+					<p className="empty-state-text">No dictations match your search</p>
+				</div>
+			)}
+
 			{groupedHistory.map((group) => (
 				<div key={group.date} style={{ marginBottom: 24 }}>
 					<p
@@ -183,32 +529,177 @@ export function HistoryFeed() {
 					</p>
 					<div className="history-feed">
 						{group.items.map((entry) => (
-							<div key={entry.id} className="history-item">
-								<span className="history-time">
-									{formatTime(entry.timestamp)}
-								</span>
-								<p className="history-text">{entry.text}</p>
-								<div className="history-actions">
-									<ActionIcon
-										variant="subtle"
-										size="sm"
-										color="gray"
-										onClick={() => clipboard.copy(entry.text)}
-										title="Copy to clipboard"
-									>
-										<Copy size={14} />
-									</ActionIcon>
-									<ActionIcon
-										variant="subtle"
-										size="sm"
-										color="red"
-										onClick={() => handleDelete(entry.id)}
-										title="Delete"
-										disabled={deleteEntry.isPending}
-									>
-										<Trash2 size={14} />
-									</ActionIcon>
+							<div key={entry.id}>
+								<div className="history-item">
+									<span className="history-time">
+										{formatTime(entry.timestamp)}
+									</span>
+									<div className="history-text">
+										<p>{entry.revised_text ?? entry.text}</p>
+										{entry.note && (
+											<Text size="xs" c="dimmed" mt={4}>
+												{entry.note}
+											</Text>
+										)}
+										{entry.tags.length > 0 && (
+											<Group gap={4} mt={4}>
+												{entry.tags.map((tag) => (
+													<Badge
+														key={tag}
+														size="xs"
+														variant="light"
+														color="gray"
+														style={{ cursor: "pointer" }}
+														onClick={() => setSearch(`#${tag}`)}
+													>
+														#{tag}
+													</Badge>
+												))}
+											</Group>
+										)}
+									</div>
+									<div className="history-actions">
+										{entry.duplicates.length > 0 && (
+											<Button
+												variant="subtle"
+												size="compact-xs"
+												color="gray"
+												onClick={() => toggleDuplicates(entry.id)}
+												title="Show the earlier attempts at this dictation"
+											>
+												×{entry.duplicates.length + 1}
+											</Button>
+										)}
+										{entry.has_audio && (
+											<ActionIcon
+												variant={playing === entry.id ? "light" : "subtle"}
+												size="sm"
+												color="gray"
+												onClick={() =>
+													setPlaying(playing === entry.id ? null : entry.id)
+												}
+												title="Listen to the recording"
+											>
+												<Play size={14} />
+											</ActionIcon>
+										)}
+										<ActionIcon
+											variant="subtle"
+											size="sm"
+											color="gray"
+											onClick={() =>
+												setSnippetDraft({
+													kind: "new",
+													text: entry.revised_text ?? entry.text,
+													sourceEntryId: entry.id,
+												})
+											}
+											title="Save as snippet"
+										>
+											<BookmarkPlus size={14} />
+										</ActionIcon>
+										<ActionIcon
+											variant="subtle"
+											size="sm"
+											color="gray"
+											onClick={() => setLabelling(entry)}
+											title="Tags and note"
+										>
+											<Tag size={14} />
+										</ActionIcon>
+										<ActionIcon
+											variant={entry.pinned ? "light" : "subtle"}
+											size="sm"
+											color={entry.pinned ? "yellow" : "gray"}
+											onClick={() =>
+												setPinned.mutate({
+													id: entry.id,
+													pinned: !entry.pinned,
+												})
+											}
+											title={
+												entry.pinned
+													? "Unpin"
+													: "Pin to keep past the retention period"
+											}
+										>
+											<Pin size={14} />
+										</ActionIcon>
+										<ActionIcon
+											variant="subtle"
+											size="sm"
+											color="gray"
+											onClick={() =>
+												clipboard.copy(entry.revised_text ?? entry.text)
+											}
+											title="Copy to clipboard"
+										>
+											<Copy size={14} />
+										</ActionIcon>
+										<ActionIcon
+											variant="subtle"
+											size="sm"
+											color="gray"
+											onClick={() => handleReclean(entry)}
+											title="Clean up again with the current prompt"
+										>
+											<RefreshCw size={14} />
+										</ActionIcon>
+										<ActionIcon
+											variant="subtle"
+											size="sm"
+											color="red"
+											onClick={() => handleDelete(entry.id)}
+											title="Delete"
+											disabled={deleteEntry.isPending}
+										>
+											<Trash2 size={14} />
+										</ActionIcon>
+									</div>
 								</div>
+								{playing === entry.id && (
+									<HistoryAudioPlayer entryId={entry.id} />
+								)}
+								{expanded.has(entry.id) &&
+									entry.duplicates.map((duplicate) => (
+										<div
+											key={duplicate.id}
+											className="history-item"
+											style={{ marginLeft: 24, opacity: 0.7 }}
+										>
+											<span className="history-time">
+												{formatTime(duplicate.timestamp)}
+											</span>
+											<p className="history-text">
+												{duplicate.revised_text ?? duplicate.text}
+											</p>
+											<div className="history-actions">
+												<ActionIcon
+													variant="subtle"
+													size="sm"
+													color="gray"
+													onClick={() =>
+														clipboard.copy(
+															duplicate.revised_text ?? duplicate.text,
+														)
+													}
+													title="Copy to clipboard"
+												>
+													<Copy size={14} />
+												</ActionIcon>
+												<ActionIcon
+													variant="subtle"
+													size="sm"
+													color="red"
+													onClick={() => handleDelete(duplicate.id)}
+													title="Delete"
+													disabled={deleteEntry.isPending}
+												>
+													<Trash2 size={14} />
+												</ActionIcon>
+											</div>
+										</div>
+									))}
 							</div>
 						))}
 					</div>
@@ -0,0 +1,93 @@
+import { ActionIcon, Button, Group, Modal, Text, Tooltip } from "@mantine/core";
+import { useDisclosure } from "@mantine/hooks";
+import { format } from "date-fns";
+import { ArchiveRestore, Trash, Trash2 } from "lucide-react";
+import {
+	useEmptyHistoryTrash,
+	usePurgeHistoryEntry,
+	useRestoreHistoryEntry,
+	useSettings,
+	useTrashedHistory,
+} from "../lib/queries";
+
+const DEFAULT_TRASH_DAYS = 30;
+
+/**
+ * Button opening the history trash, where deleted dictations can be restored
+ * or removed for good. Hidden while the trash is empty.
+ */
+export function HistoryTrash() {
+	const { data: trashed } = useTrashedHistory();
+	const { data: settings } = useSettings();
+	const restore = useRestoreHistoryEntry();
+	const purge = usePurgeHistoryEntry();
+	const emptyTrash = useEmptyHistoryTrash();
+	const [opened, { open, close }] = useDisclosure(false);
+	const trashDays = settings?.history_trash_days ?? DEFAULT_TRASH_DAYS;
+
+	if (!trashed || trashed.length === 0) return null;
+
+	return (
+		<>
+			<Button
+				variant="subtle"
+				size="compact-sm"
+				color="gray"
+				leftSection={<Trash size={14} />}
+				onClick={open}
+			>
+				Trash ({trashed.length})
+			</Button>
+
+			<Modal opened={opened} onClose={close} title="Trash" centered>
+				<Text size="xs" c="dimmed" mb="md">
+					Deleted dictations can be restored for {trashDays} days.
+				</Text>
+				<div className="history-feed">
+					{trashed.map((entry) => (
+						<div key={entry.id} className="history-item">
+							<span className="history-time">
+								{format(new Date(entry.trashed_at), "MMM d")}
+							</span>
+							<div className="history-text">
+								<p style={{ margin: 0 }}>{entry.revised_text ?? entry.text}</p>
+							</div>
+							<div className="history-actions">
+								<Tooltip label="Restore">
+									<ActionIcon
+										variant="subtle"
+										size="sm"
+										color="gray"
+										onClick={() => restore.mutate(entry.id)}
+									>
+										<ArchiveRestore size={14} />
+									</ActionIcon>
+								</Tooltip>
+								<Tooltip label="Delete forever">
+									<ActionIcon
+										variant="subtle"
+										size="sm"
+										color="red"
+										onClick={() => purge.mutate(entry.id)}
+									>
+										<Trash2 size={14} />
+									</ActionIcon>
+								</Tooltip>
+							</div>
+						</div>
+					))}
+				</div>
+				<Group justify="flex-end" mt="md">
+					<Button
+						color="red"
+						variant="light"
+						onClick={() => emptyTrash.mutate(undefined, { onSuccess: close })}
+						loading={emptyTrash.isPending}
+					>
+						Empty Trash
+					</Button>
+				</Group>
+			</Modal>
+		</>
+	);
+}
@@ -0,0 +1,81 @@
+import { Button, Group, Modal, Text } from "@mantine/core";
+import { format } from "date-fns";
+import { useEffect, useState } from "react";
+import { describeError } from "../lib/errors";
+import { type CalendarMeeting, tauriAPI } from "../lib/tauri";
+
+/**
+ * Asks whether to begin meeting transcription when a meeting from the
+ * configured calendar is about to start.
+ */
+export function MeetingPrompt() {
+	const [meeting, setMeeting] = useState<CalendarMeeting | null>(null);
+	const [error, setError] = useState<string | null>(null);
+	const [starting, setStarting] = useState(false);
+
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onMeetingStarting((next) => {
+				setError(null);
+				setMeeting(next);
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, []);
+
+	const start = async () => {
+		if (!meeting) return;
+		setStarting(true);
+		try {
+			await tauriAPI.startMeeting(meeting.title);
+			setMeeting(null);
+		} catch (startError) {
+			setError(describeError(startError));
+		} finally {
+			setStarting(false);
+		}
+	};
+
+	return (
+		<Modal
+			opened={meeting !== null}
+			onClose={() => setMeeting(null)}
+			title="Meeting starting"
+			centered
+		>
+			{meeting && (
+				<>
+					<Text fw={500}>{meeting.title}</Text>
+					<Text size="sm" c="dimmed" mb="md">
+						{format(new Date(meeting.start), "h:mm a")}
+						{meeting.end && ` – ${format(new Date(meeting.end), "h:mm a")}`}
+					</Text>
+					<Text size="sm" mb="md">
+						Begin meeting transcription? Dictations are grouped into a session
+						named after the meeting.
+					</Text>
+					{error && (
+						<Text size="sm" c="red" mb="md">
+							{error}
+						</Text>
+					)}
+					<Group justify="flex-end">
+						<Button variant="default" onClick={() => setMeeting(null)}>
+							Not now
+						</Button>
+						<Button onClick={start} loading={starting}>
+							Start transcription
+						</Button>
+					</Group>
+				</>
+			)}
+		</Modal>
+	);
+}
@@ -0,0 +1,174 @@
+import {
+	ActionIcon,
+	Button,
+	Group,
+	Modal,
+	Textarea,
+	TextInput,
+} from "@mantine/core";
+import { BookMarked, ClipboardPaste, Pencil, Trash2 } from "lucide-react";
+import { useEffect, useState } from "react";
+import { errorMessage } from "../lib/errors";
+import {
+	useAddSnippet,
+	useDeleteSnippet,
+	useSnippets,
+	useUpdateSnippet,
+} from "../lib/queries";
+import { type Snippet, tauriAPI } from "../lib/tauri";
+
+/** What the snippet editor is open for: a new snippet or an existing one */
+export type SnippetDraft =
+	| { kind: "new"; text: string; sourceEntryId?: string }
+	| { kind: "edit"; snippet: Snippet };
+
+/** Editor for a snippet's name and text, creating or updating it on save */
+export function SnippetEditorModal({
+	draft,
+	onClose,
+}: {
+	draft: SnippetDraft | null;
+	onClose: () => void;
+}) {
+	const addSnippet = useAddSnippet();
+	const updateSnippet = useUpdateSnippet();
+	const [name, setName] = useState("");
+	const [text, setText] = useState("");
+
+	useEffect(() => {
+		setName(draft?.kind === "edit" ? draft.snippet.name : "");
+		setText(draft?.kind === "edit" ? draft.snippet.text : (draft?.text ?? ""));
+	}, [draft]);
+
+	const handleSave = () => {
+		if (!draft) return;
+		if (draft.kind === "edit") {
+			updateSnippet.mutate(
+				{ id: draft.snippet.id, name, text },
+				{ onSuccess: onClose },
+			);
+		} else {
+			addSnippet.mutate(
+				{ name, text, sourceEntryId: draft.sourceEntryId },
+				{ onSuccess: onClose },
+			);
+		}
+	};
+
+	const error = addSnippet.error ?? updateSnippet.error;
+
+	return (
+		<Modal
+			opened={draft !== null}
+			onClose={onClose}
+			title={draft?.kind === "edit" ? "Edit Snippet" : "Save as Snippet"}
+			centered
+			size="md"
+		>
+			<TextInput
+				label="Name"
+				description='Say "insert" and the name to paste it by voice'
+				placeholder="my email signature"
+				value={name}
+				onChange={(event) => setName(event.currentTarget.value)}
+				error={error ? errorMessage(error) : null}
+				mb="sm"
+				data-autofocus
+			/>
+			<Textarea
+				label="Text"
+				value={text}
+				onChange={(event) => setText(event.currentTarget.value)}
+				autosize
+				minRows={3}
+				maxRows={10}
+				mb="lg"
+			/>
+			<Group justify="flex-end">
+				<Button variant="default" onClick={onClose}>
+					Cancel
+				</Button>
+				<Button
+					onClick={handleSave}
+					disabled={!name.trim() || !text.trim()}
+					loading={addSnippet.isPending || updateSnippet.isPending}
+				>
+					Save
+				</Button>
+			</Group>
+		</Modal>
+	);
+}
+
+export function SnippetLibrary() {
+	const { data: snippets } = useSnippets();
+	const deleteSnippet = useDeleteSnippet();
+	const [draft, setDraft] = useState<SnippetDraft | null>(null);
+
+	return (
+		<div className="animate-in animate-in-delay-2" style={{ marginBottom: 32 }}>
+			<div className="section-header">
+				<span className="section-title">Snippets</span>
+				<Button
+					variant="subtle"
+					size="compact-sm"
+					color="gray"
+					onClick={() => setDraft({ kind: "new", text: "" })}
+				>
+					New Snippet
+				</Button>
+			</div>
+
+			<SnippetEditorModal draft={draft} onClose={() => setDraft(null)} />
+
+			{!snippets || snippets.length === 0 ? (
+				<div className="empty-state">
+					<BookMarked className="empty-state-icon" />
+					<p className="empty-state-text">
+						Save text you use often, or a dictation from history, and paste it
+						from the tray or by saying "insert" and its name.
+					</p>
+				</div>
+			) : (
+				<div className="history-feed">
+					{snippets.map((snippet) => (
+						<div key={snippet.id} className="history-item">
+							<span className="history-time">{snippet.name}</span>
+							<p className="history-text">{snippet.text}</p>
+							<div className="history-actions">
+								<ActionIcon
+									variant="subtle"
+									size="sm"
+									color="gray"
+									onClick={() => tauriAPI.pasteSnippet(snippet.id)}
+									title="Paste into the last focused app"
+								>
+									<ClipboardPaste size={14} />
+								</ActionIcon>
+								<ActionIcon
+									variant="subtle"
+									size="sm"
+									color="gray"
+									onClick={() => setDraft({ kind: "edit", snippet })}
+									title="Edit"
+								>
+									<Pencil size={14} />
+								</ActionIcon>
+								<ActionIcon
+									variant="subtle"
+									size="sm"
+									color="red"
+									onClick={() => deleteSnippet.mutate(snippet.id)}
+									title="Delete"
+									disabled={deleteSnippet.isPending}
+								>
+									<Trash2 size={14} />
+								</ActionIcon>
+							</div>
+						</div>
+					))}
+				</div>
+			)}
+		</div>
+	);
+}
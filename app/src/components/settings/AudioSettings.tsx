@@ -22,7 +22,10 @@ export function AudioSettings() {
 	};
 
 	return (
-		<div className="settings-section animate-in animate-in-delay-2">
+		<div
+			id="settings-audio"
+			className="settings-section animate-in animate-in-delay-2"
+		>
 			<h3 className="settings-section-title">Audio</h3>
 			<div className="settings-card">
 				<DeviceSelector />
@@ -1,28 +1,47 @@
-import { Alert, Button, Text } from "@mantine/core";
+import { Alert, Button, Switch, Text } from "@mantine/core";
 import { AlertCircle, RotateCcw } from "lucide-react";
 import { useState } from "react";
+import { errorAction, errorMessage } from "../../lib/errors";
 import {
+	DEFAULT_CANCEL_HOTKEY,
+	DEFAULT_CLIPBOARD_CLEANUP_HOTKEY,
 	DEFAULT_HOLD_HOTKEY,
 	DEFAULT_PASTE_LAST_HOTKEY,
 	DEFAULT_TOGGLE_HOTKEY,
+	DEFAULT_UNDO_PASTE_HOTKEY,
 } from "../../lib/hotkeyDefaults";
 import {
 	useResetHotkeysToDefaults,
 	useSettings,
+	useUpdateCancelHotkey,
+	useUpdateClipboardCleanupEnabled,
+	useUpdateClipboardCleanupHotkey,
 	useUpdateHoldHotkey,
 	useUpdatePasteLastHotkey,
 	useUpdateToggleHotkey,
+	useUpdateUndoPasteHotkey,
 } from "../../lib/queries";
 import type { HotkeyConfig } from "../../lib/tauri";
 import { HotkeyInput } from "../HotkeyInput";
 
-type RecordingInput = "toggle" | "hold" | "paste_last" | null;
+type RecordingInput =
+	| "toggle"
+	| "hold"
+	| "paste_last"
+	| "undo_paste"
+	| "clipboard_cleanup"
+	| "cancel"
+	| null;
 
 export function HotkeySettings() {
 	const { data: settings, isLoading } = useSettings();
 	const updateToggleHotkey = useUpdateToggleHotkey();
 	const updateHoldHotkey = useUpdateHoldHotkey();
 	const updatePasteLastHotkey = useUpdatePasteLastHotkey();
+	const updateUndoPasteHotkey = useUpdateUndoPasteHotkey();
+	const updateCancelHotkey = useUpdateCancelHotkey();
+	const updateClipboardCleanupHotkey = useUpdateClipboardCleanupHotkey();
+	const updateClipboardCleanupEnabled = useUpdateClipboardCleanupEnabled();
 	const resetHotkeys = useResetHotkeysToDefaults();
 
 	// Track which input is currently recording (only one at a time)
@@ -33,6 +52,10 @@ export function HotkeySettings() {
 		updateToggleHotkey.error ||
 		updateHoldHotkey.error ||
 		updatePasteLastHotkey.error ||
+		updateUndoPasteHotkey.error ||
+		updateCancelHotkey.error ||
+		updateClipboardCleanupHotkey.error ||
+		updateClipboardCleanupEnabled.error ||
 		resetHotkeys.error;
 
 	const handleToggleHotkeyChange = (config: HotkeyConfig) => {
@@ -47,8 +70,25 @@ export function HotkeySettings() {
 		updatePasteLastHotkey.mutate(config);
 	};
 
+	const handleUndoPasteHotkeyChange = (config: HotkeyConfig) => {
+		updateUndoPasteHotkey.mutate(config);
+	};
+
+	const handleCancelHotkeyChange = (config: HotkeyConfig) => {
+		updateCancelHotkey.mutate(config);
+	};
+
+	const handleClipboardCleanupHotkeyChange = (config: HotkeyConfig) => {
+		updateClipboardCleanupHotkey.mutate(config);
+	};
+
+	const clipboardCleanupEnabled = settings?.clipboard_cleanup_enabled ?? false;
+
 	return (
-		<div className="settings-section animate-in animate-in-delay-3">
+		<div
+			id="settings-hotkeys"
+			className="settings-section animate-in animate-in-delay-3"
+		>
 			<h3 className="settings-section-title">Hotkeys</h3>
 			{error && (
 				<Alert
@@ -57,7 +97,12 @@ export function HotkeySettings() {
 					mb="md"
 					title="Error"
 				>
-					{error instanceof Error ? error.message : String(error)}
+					{errorMessage(error)}
+					{errorAction(error) && (
+						<Text size="sm" mt={4}>
+							{errorAction(error)}
+						</Text>
+					)}
 				</Alert>
 			)}
 			<div className="settings-card">
@@ -98,6 +143,69 @@ export function HotkeySettings() {
 					/>
 				</div>
 
+				<div style={{ marginTop: 20 }}>
+					<HotkeyInput
+						label="Undo Last Paste"
+						description="Remove the most recent paste from the field it went into"
+						value={settings?.undo_paste_hotkey ?? DEFAULT_UNDO_PASTE_HOTKEY}
+						onChange={handleUndoPasteHotkeyChange}
+						disabled={isLoading || updateUndoPasteHotkey.isPending}
+						isRecording={recordingInput === "undo_paste"}
+						onStartRecording={() => setRecordingInput("undo_paste")}
+						onStopRecording={() => setRecordingInput(null)}
+					/>
+				</div>
+
+				<div style={{ marginTop: 20 }}>
+					<HotkeyInput
+						label="Cancel Dictation"
+						description="Drop the recording, or stop its transcription and cleanup so nothing is pasted"
+						value={settings?.cancel_hotkey ?? DEFAULT_CANCEL_HOTKEY}
+						onChange={handleCancelHotkeyChange}
+						disabled={isLoading || updateCancelHotkey.isPending}
+						isRecording={recordingInput === "cancel"}
+						onStartRecording={() => setRecordingInput("cancel")}
+						onStopRecording={() => setRecordingInput(null)}
+					/>
+				</div>
+
+				<div className="settings-row" style={{ marginTop: 20 }}>
+					<div>
+						<p className="settings-label">Clipboard cleanup</p>
+						<p className="settings-description">
+							Clean up copied text with a hotkey and put the result back on
+							the clipboard
+						</p>
+					</div>
+					<Switch
+						checked={clipboardCleanupEnabled}
+						onChange={(event) =>
+							updateClipboardCleanupEnabled.mutate(event.currentTarget.checked)
+						}
+						disabled={isLoading || updateClipboardCleanupEnabled.isPending}
+						color="gray"
+						size="md"
+					/>
+				</div>
+
+				{clipboardCleanupEnabled && (
+					<div style={{ marginTop: 20 }}>
+						<HotkeyInput
+							label="Clean Up Clipboard"
+							description="Copy text, then press to replace it with a cleaned up version"
+							value={
+								settings?.clipboard_cleanup_hotkey ??
+								DEFAULT_CLIPBOARD_CLEANUP_HOTKEY
+							}
+							onChange={handleClipboardCleanupHotkeyChange}
+							disabled={isLoading || updateClipboardCleanupHotkey.isPending}
+							isRecording={recordingInput === "clipboard_cleanup"}
+							onStartRecording={() => setRecordingInput("clipboard_cleanup")}
+							onStopRecording={() => setRecordingInput(null)}
+						/>
+					</div>
+				)}
+
 				<div
 					style={{
 						marginTop: 24,
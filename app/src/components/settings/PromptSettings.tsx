@@ -145,7 +145,10 @@ export function PromptSettings() {
 	};
 
 	return (
-		<div className="settings-section animate-in animate-in-delay-4">
+		<div
+			id="settings-prompts"
+			className="settings-section animate-in animate-in-delay-4"
+		>
 			<h3 className="settings-section-title">LLM Formatting Prompt</h3>
 			<div className="settings-card">
 				{isLoadingDefaultSections ? (
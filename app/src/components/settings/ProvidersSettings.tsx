@@ -100,7 +100,10 @@ export function ProvidersSettings() {
 	const isLlmProviderLocal = selectedLlmProvider?.is_local ?? false;
 
 	return (
-		<div className="settings-section animate-in animate-in-delay-1">
+		<div
+			id="settings-providers"
+			className="settings-section animate-in animate-in-delay-1"
+		>
 			<h3 className="settings-section-title">Providers</h3>
 			<div className="settings-card">
 				<div className="settings-row">
@@ -0,0 +1,178 @@
+import {
+	ActionIcon,
+	Button,
+	FileButton,
+	TagsInput,
+	Text,
+	Tooltip,
+} from "@mantine/core";
+import { Plus, Upload } from "lucide-react";
+import { describeError } from "../../lib/errors";
+import type { ImportSummary } from "../../lib/tauri";
+import {
+	useImportFromTool,
+	useSettings,
+	useUpdateSTTVocabulary,
+	useVocabularyInsights,
+} from "../../lib/queries";
+import { tauriAPI } from "../../lib/tauri";
+
+interface Suggestion {
+	term: string;
+	detail: string;
+}
+
+function SuggestionList({
+	title,
+	suggestions,
+	onAdd,
+}: {
+	title: string;
+	suggestions: Suggestion[];
+	onAdd: (term: string) => void;
+}) {
+	if (suggestions.length === 0) return null;
+
+	return (
+		<div style={{ marginTop: 16 }}>
+			<p className="settings-label">{title}</p>
+			{suggestions.map(({ term, detail }) => (
+				<div
+					key={term}
+					style={{
+						display: "flex",
+						alignItems: "center",
+						justifyContent: "space-between",
+						gap: 8,
+						marginTop: 6,
+					}}
+				>
+					<div>
+						<Text size="sm">{term}</Text>
+						<Text size="xs" c="dimmed">
+							{detail}
+						</Text>
+					</div>
+					<Tooltip label="Add to vocabulary">
+						<ActionIcon
+							variant="subtle"
+							size="sm"
+							color="gray"
+							onClick={() => onAdd(term)}
+						>
+							<Plus size={14} />
+						</ActionIcon>
+					</Tooltip>
+				</div>
+			))}
+		</div>
+	);
+}
+
+function describeImport(summary: ImportSummary): string {
+	const parts = [`Added ${summary.added} terms`];
+	if (summary.duplicates > 0) {
+		parts.push(`${summary.duplicates} already in the vocabulary`);
+	}
+	if (summary.instructions_imported) {
+		parts.push("instructions added to the advanced prompt");
+	}
+	return parts.join(", ");
+}
+
+/**
+ * The STT vocabulary, with suggestions from history: words the cleanup kept
+ * correcting and names or acronyms used often, each added with one click.
+ * Word lists from Talon, Dragon or superwhisper can be imported.
+ */
+export function VocabularySettings() {
+	const { data: settings } = useSettings();
+	const { data: insights } = useVocabularyInsights();
+	const updateVocabulary = useUpdateSTTVocabulary();
+	const importFromTool = useImportFromTool();
+	const vocabulary = settings?.stt_vocabulary ?? [];
+
+	const handleChange = (terms: string[]) => {
+		// Save to local settings (Tauri) then notify overlay window to sync to server
+		updateVocabulary.mutate(terms, {
+			onSuccess: () => {
+				tauriAPI.emitSettingsChanged();
+			},
+		});
+	};
+
+	const corrected: Suggestion[] =
+		insights?.corrected.map((word) => ({
+			term: word.word,
+			detail: `Corrected ${word.count}× from ${word.heard_as
+				.map((heard) => `"${heard}"`)
+				.join(", ")}`,
+		})) ?? [];
+	const frequent: Suggestion[] =
+		insights?.frequent.map((term) => ({
+			term: term.term,
+			detail: `Used in ${term.count} dictations`,
+		})) ?? [];
+
+	return (
+		<div
+			id="settings-vocabulary"
+			className="settings-section animate-in animate-in-delay-1"
+		>
+			<h3 className="settings-section-title">Vocabulary</h3>
+			<div className="settings-card">
+				<div>
+					<p className="settings-label">Custom Vocabulary</p>
+					<p className="settings-description">
+						Names and terms to recognize, spelled as they should appear
+					</p>
+					<TagsInput
+						mt={12}
+						value={vocabulary}
+						onChange={handleChange}
+						placeholder="Add a term and press Enter"
+						clearable
+					/>
+					<FileButton
+						onChange={(file) => file && importFromTool.mutate(file)}
+						accept=".talon-list,.csv,.txt,.json"
+					>
+						{(props) => (
+							<Button
+								{...props}
+								mt={12}
+								size="xs"
+								variant="light"
+								color="gray"
+								leftSection={<Upload size={14} />}
+								loading={importFromTool.isPending}
+							>
+								Import from Talon, Dragon or superwhisper
+							</Button>
+						)}
+					</FileButton>
+					{importFromTool.data && (
+						<Text size="xs" c="dimmed" mt={6}>
+							{describeImport(importFromTool.data)}
+						</Text>
+					)}
+					{importFromTool.error && (
+						<Text size="xs" c="red" mt={6}>
+							{describeError(importFromTool.error)}
+						</Text>
+					)}
+				</div>
+				<SuggestionList
+					title="Most corrected"
+					suggestions={corrected}
+					onAdd={(term) => handleChange([...vocabulary, term])}
+				/>
+				<SuggestionList
+					title="Frequently used"
+					suggestions={frequent}
+					onAdd={(term) => handleChange([...vocabulary, term])}
+				/>
+			</div>
+		</div>
+	);
+}
@@ -4,3 +4,4 @@ export type { PromptSectionEditorProps } from "./PromptSectionEditor";
 export { PromptSectionEditor } from "./PromptSectionEditor";
 export { PromptSettings } from "./PromptSettings";
 export { ProvidersSettings } from "./ProvidersSettings";
+export { VocabularySettings } from "./VocabularySettings";
@@ -0,0 +1,15 @@
+import { type OverlayState, tauriAPI } from "./lib/tauri";
+
+// Bar color for each pipeline step; transparent while idle
+const STATE_COLORS: Record<OverlayState["state"], string> = {
+	idle: "transparent",
+	recording: "#ef4444",
+	transcribing: "#f59e0b",
+	cleaning: "#3b82f6",
+	pasting: "#22c55e",
+	error: "#a855f7",
+};
+
+tauriAPI.onOverlayState((overlayState) => {
+	document.body.style.backgroundColor = STATE_COLORS[overlayState.state];
+});
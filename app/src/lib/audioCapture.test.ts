@@ -0,0 +1,51 @@
+import { describe, expect, it } from "vitest";
+import {
+	floatToPcm16,
+	pcmChunksBase64,
+	waveformPeaks,
+} from "./audioCapture";
+
+describe("waveformPeaks", () => {
+	it("takes the peak absolute level of each slice", () => {
+		const samples = new Float32Array([0.1, -0.5, 0.2, 0.3, -0.9, 0.4]);
+		expect(waveformPeaks(samples, 3)).toEqual([
+			Math.fround(0.5),
+			Math.fround(0.3),
+			Math.fround(0.9),
+		]);
+	});
+
+	it("never returns more points than samples", () => {
+		expect(waveformPeaks(new Float32Array([0.25, 0.5]), 10)).toEqual([
+			0.25, 0.5,
+		]);
+		expect(waveformPeaks(new Float32Array([]), 10)).toEqual([]);
+	});
+
+	it("clamps clipped samples to 1", () => {
+		expect(waveformPeaks(new Float32Array([1.5, -2]), 1)).toEqual([1]);
+	});
+});
+
+describe("floatToPcm16", () => {
+	it("writes clamped little-endian 16-bit samples", () => {
+		const pcm = floatToPcm16(new Float32Array([0, 1, -1, 2]));
+		const view = new DataView(pcm.buffer);
+		expect(pcm.length).toBe(8);
+		expect(view.getInt16(0, true)).toBe(0);
+		expect(view.getInt16(2, true)).toBe(0x7fff);
+		expect(view.getInt16(4, true)).toBe(-0x7fff);
+		expect(view.getInt16(6, true)).toBe(0x7fff);
+	});
+});
+
+describe("pcmChunksBase64", () => {
+	it("splits on whole samples and encodes each chunk", () => {
+		const pcm = new Uint8Array([1, 2, 3, 4, 5, 6]);
+		expect(pcmChunksBase64(pcm, 2)).toEqual([
+			btoa(String.fromCharCode(1, 2, 3, 4)),
+			btoa(String.fromCharCode(5, 6)),
+		]);
+		expect(pcmChunksBase64(new Uint8Array([]))).toEqual([]);
+	});
+});
@@ -0,0 +1,169 @@
+/** Points in the waveform preview sent with a kept recording */
+export const WAVEFORM_POINTS = 100;
+
+/** Peak absolute level of each of `points` even slices of `samples` */
+export function waveformPeaks(
+	samples: Float32Array,
+	points: number = WAVEFORM_POINTS,
+): number[] {
+	if (samples.length === 0 || points <= 0) return [];
+	const count = Math.min(points, samples.length);
+	const peaks: number[] = [];
+	for (let i = 0; i < count; i++) {
+		const start = Math.floor((i * samples.length) / count);
+		const end = Math.floor(((i + 1) * samples.length) / count);
+		let peak = 0;
+		for (let j = start; j < end; j++) {
+			peak = Math.max(peak, Math.abs(samples[j] ?? 0));
+		}
+		peaks.push(Math.min(peak, 1));
+	}
+	return peaks;
+}
+
+export interface CapturedAudio {
+	bytes: Uint8Array;
+	mimeType: string;
+	waveform: number[];
+}
+
+/** How often the recording so far is handed to the recovery file */
+export const RECOVERY_INTERVAL_MS = 1000;
+
+/** Keeps the recording on disk as it grows, so a crash doesn't lose it */
+export interface RecordingRecovery {
+	begin: (mimeType: string) => Promise<void>;
+	append: (bytes: Uint8Array) => Promise<void>;
+	finish: () => Promise<void>;
+}
+
+/**
+ * Record a microphone track alongside streaming it. `stop()` resolves with the
+ * recording and its waveform, or null when nothing was captured. With
+ * `recovery`, the recording is also written out every second until it stops.
+ */
+export function startAudioCapture(
+	track: MediaStreamTrack,
+	recovery?: RecordingRecovery,
+): {
+	stop: () => Promise<CapturedAudio | null>;
+} {
+	const recorder = new MediaRecorder(new MediaStream([track]));
+	const chunks: Blob[] = [];
+	// Writes go one after another so the file keeps the chunks in order
+	let recoveryWrites = Promise.resolve();
+	const keep = (write: (recovery: RecordingRecovery) => Promise<void>) => {
+		if (!recovery) return;
+		recoveryWrites = recoveryWrites
+			.then(() => write(recovery))
+			.catch((error) =>
+				console.warn("[Audio] Failed to write recovery file:", error),
+			);
+	};
+	recorder.addEventListener("start", () =>
+		keep((file) => file.begin(recorder.mimeType)),
+	);
+	// However the recording ends, it no longer needs recovering
+	recorder.addEventListener("stop", () =>
+		keep((file) => file.finish()),
+	);
+	recorder.ondataavailable = (event) => {
+		if (event.data.size === 0) return;
+		chunks.push(event.data);
+		keep(async (file) =>
+			file.append(new Uint8Array(await event.data.arrayBuffer())),
+		);
+	};
+	recorder.start(RECOVERY_INTERVAL_MS);
+
+	const stop = () =>
+		new Promise<CapturedAudio | null>((resolve) => {
+			if (recorder.state === "inactive") {
+				resolve(null);
+				return;
+			}
+			recorder.onstop = async () => {
+				if (chunks.length === 0) {
+					resolve(null);
+					return;
+				}
+				const blob = new Blob(chunks, { type: recorder.mimeType });
+				const bytes = new Uint8Array(await blob.arrayBuffer());
+				let waveform: number[] = [];
+				const context = new AudioContext();
+				try {
+					// decodeAudioData detaches the buffer it is given, so pass a copy
+					const decoded = await context.decodeAudioData(bytes.slice().buffer);
+					waveform = waveformPeaks(decoded.getChannelData(0));
+				} catch (error) {
+					console.warn("[Audio] Failed to decode recording:", error);
+				} finally {
+					context.close().catch(() => {});
+				}
+				resolve({ bytes, mimeType: recorder.mimeType, waveform });
+			};
+			recorder.stop();
+		});
+
+	return { stop };
+}
+
+/** Sample rate recordings are replayed to the server at */
+export const REPLAY_SAMPLE_RATE = 16000;
+
+/** Samples per replayed message, one second, to stay within data channel limits */
+export const REPLAY_CHUNK_SAMPLES = REPLAY_SAMPLE_RATE;
+
+/** Little-endian 16-bit PCM of samples from -1 to 1 */
+export function floatToPcm16(samples: Float32Array): Uint8Array {
+	const view = new DataView(new ArrayBuffer(samples.length * 2));
+	samples.forEach((sample, i) => {
+		const clamped = Math.max(-1, Math.min(1, sample));
+		view.setInt16(i * 2, Math.round(clamped * 0x7fff), true);
+	});
+	return new Uint8Array(view.buffer);
+}
+
+/** Base64 chunks of `pcm`, each at most `samplesPerChunk` 16-bit samples */
+export function pcmChunksBase64(
+	pcm: Uint8Array,
+	samplesPerChunk: number = REPLAY_CHUNK_SAMPLES,
+): string[] {
+	const chunks: string[] = [];
+	const chunkBytes = samplesPerChunk * 2;
+	for (let start = 0; start < pcm.length; start += chunkBytes) {
+		let binary = "";
+		for (const byte of pcm.subarray(start, start + chunkBytes)) {
+			binary += String.fromCharCode(byte);
+		}
+		chunks.push(btoa(binary));
+	}
+	return chunks;
+}
+
+/**
+ * Decode a kept recording to mono 16-bit PCM at `REPLAY_SAMPLE_RATE`, with its
+ * waveform preview
+ */
+export async function decodeForReplay(
+	bytes: Uint8Array,
+): Promise<{ pcm: Uint8Array; waveform: number[] }> {
+	const context = new AudioContext();
+	try {
+		// decodeAudioData detaches the buffer it is given, so pass a copy
+		const decoded = await context.decodeAudioData(bytes.slice().buffer);
+		const offline = new OfflineAudioContext(
+			1,
+			Math.ceil(decoded.duration * REPLAY_SAMPLE_RATE),
+			REPLAY_SAMPLE_RATE,
+		);
+		const source = offline.createBufferSource();
+		source.buffer = decoded;
+		source.connect(offline.destination);
+		source.start();
+		const samples = (await offline.startRendering()).getChannelData(0);
+		return { pcm: floatToPcm16(samples), waveform: waveformPeaks(samples) };
+	} finally {
+		context.close().catch(() => {});
+	}
+}
@@ -0,0 +1,22 @@
+import { describe, expect, it } from "vitest";
+import { summarizeSpectrum } from "./audioLevels";
+
+describe("summarizeSpectrum", () => {
+	it("averages bins into equal bands", () => {
+		const levels = summarizeSpectrum([255, 255, 0, 0], 2);
+		expect(levels.bands).toEqual([1, 0]);
+		expect(levels.level).toBe(0.5);
+	});
+
+	it("returns silence for empty data", () => {
+		const levels = summarizeSpectrum([], 3);
+		expect(levels.bands).toEqual([0, 0, 0]);
+		expect(levels.level).toBe(0);
+	});
+
+	it("handles more bands than bins", () => {
+		const levels = summarizeSpectrum([255], 2);
+		expect(levels.bands).toHaveLength(2);
+		expect(levels.bands[0]).toBe(1);
+	});
+});
@@ -0,0 +1,86 @@
+import { useEffect, useState } from "react";
+
+/** Number of frequency bands drawn by the overlay waveform */
+export const LEVEL_BAND_COUNT = 12;
+
+/** Input level and per-band spectrum, all normalized to 0..1 */
+export interface AudioLevels {
+	level: number;
+	bands: number[];
+}
+
+function silentLevels(bandCount: number): AudioLevels {
+	return { level: 0, bands: new Array(bandCount).fill(0) };
+}
+
+/**
+ * Reduce analyser frequency data (0-255 per bin) to an overall level and
+ * `bandCount` bands, each the average of an equal share of the bins.
+ */
+export function summarizeSpectrum(
+	data: ArrayLike<number>,
+	bandCount: number,
+): AudioLevels {
+	if (data.length === 0 || bandCount <= 0) {
+		return silentLevels(Math.max(bandCount, 0));
+	}
+
+	const bands: number[] = [];
+	for (let band = 0; band < bandCount; band++) {
+		const start = Math.floor((band * data.length) / bandCount);
+		const end = Math.max(
+			start + 1,
+			Math.floor(((band + 1) * data.length) / bandCount),
+		);
+		let sum = 0;
+		for (let i = start; i < end && i < data.length; i++) {
+			sum += data[i] ?? 0;
+		}
+		bands.push(sum / (end - start) / 255);
+	}
+
+	const level = bands.reduce((total, value) => total + value, 0) / bandCount;
+	return { level, bands };
+}
+
+/**
+ * Live levels of a microphone track, updated every animation frame while the
+ * track is live. Returns silence when there is no track.
+ */
+export function useAudioLevels(
+	track: MediaStreamTrack | null | undefined,
+	bandCount: number = LEVEL_BAND_COUNT,
+): AudioLevels {
+	const [levels, setLevels] = useState(() => silentLevels(bandCount));
+
+	useEffect(() => {
+		if (!track || track.readyState !== "live") {
+			setLevels(silentLevels(bandCount));
+			return;
+		}
+
+		const context = new AudioContext();
+		const source = context.createMediaStreamSource(new MediaStream([track]));
+		const analyser = context.createAnalyser();
+		analyser.fftSize = 256;
+		analyser.smoothingTimeConstant = 0.6;
+		source.connect(analyser);
+
+		// Speech sits in the lower half of the spectrum; skip the mostly empty top
+		const data = new Uint8Array(analyser.frequencyBinCount / 2);
+		let frame = requestAnimationFrame(function update() {
+			analyser.getByteFrequencyData(data);
+			setLevels(summarizeSpectrum(data, bandCount));
+			frame = requestAnimationFrame(update);
+		});
+
+		return () => {
+			cancelAnimationFrame(frame);
+			source.disconnect();
+			context.close().catch(() => {});
+			setLevels(silentLevels(bandCount));
+		};
+	}, [track, bandCount]);
+
+	return levels;
+}
@@ -0,0 +1,67 @@
+import { describe, expect, it } from "vitest";
+import {
+	type AppError,
+	describeError,
+	errorAction,
+	errorMessage,
+	isAppError,
+	isCancelled,
+} from "./errors";
+
+const serverDown: AppError = {
+	kind: "server_unavailable",
+	message: "Failed to clean up text: Connection refused",
+	recoverable: true,
+	suggested_action: "Make sure the Tambourine server is running",
+};
+
+describe("isAppError", () => {
+	it("recognizes command errors", () => {
+		expect(isAppError(serverDown)).toBe(true);
+		expect(isAppError("Failed")).toBe(false);
+		expect(isAppError(new Error("Failed"))).toBe(false);
+		expect(isAppError(null)).toBe(false);
+	});
+});
+
+describe("errorMessage", () => {
+	it("reads command errors, thrown errors and strings", () => {
+		expect(errorMessage(serverDown)).toBe(
+			"Failed to clean up text: Connection refused",
+		);
+		expect(errorMessage(new Error("Broken"))).toBe("Broken");
+		expect(errorMessage("Plain")).toBe("Plain");
+	});
+});
+
+describe("describeError", () => {
+	it("adds the suggested action", () => {
+		expect(errorAction(serverDown)).toBe(
+			"Make sure the Tambourine server is running",
+		);
+		expect(describeError(serverDown)).toBe(
+			"Failed to clean up text: Connection refused. Make sure the Tambourine server is running.",
+		);
+	});
+
+	it("leaves errors without an action as they are", () => {
+		expect(describeError({ ...serverDown, suggested_action: null })).toBe(
+			"Failed to clean up text: Connection refused",
+		);
+		expect(errorAction("Plain")).toBeNull();
+	});
+});
+
+describe("isCancelled", () => {
+	it("spots cancelled dictations", () => {
+		expect(
+			isCancelled({
+				kind: "cancelled",
+				message: "The dictation was cancelled",
+				recoverable: false,
+				suggested_action: null,
+			}),
+		).toBe(true);
+		expect(isCancelled(serverDown)).toBe(false);
+	});
+});
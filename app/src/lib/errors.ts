@@ -0,0 +1,55 @@
+/** What kind of failure a command reported (see error.rs) */
+export type AppErrorKind =
+	| "invalid_input"
+	| "not_found"
+	| "permission_denied"
+	| "server_unavailable"
+	| "provider"
+	| "storage"
+	| "locked"
+	| "cancelled"
+	| "failed";
+
+/** Error every Tauri command fails with */
+export interface AppError {
+	kind: AppErrorKind;
+	message: string;
+	/** Whether trying again (after the suggested action) can succeed */
+	recoverable: boolean;
+	suggested_action: string | null;
+}
+
+export function isAppError(error: unknown): error is AppError {
+	return (
+		typeof error === "object" &&
+		error !== null &&
+		"kind" in error &&
+		"message" in error &&
+		typeof error.message === "string"
+	);
+}
+
+/** The message of a command error, a thrown Error or anything else */
+export function errorMessage(error: unknown): string {
+	if (isAppError(error) || error instanceof Error) {
+		return error.message;
+	}
+	return String(error);
+}
+
+/** What the user can do about an error, when the command suggested something */
+export function errorAction(error: unknown): string | null {
+	return isAppError(error) ? error.suggested_action : null;
+}
+
+/** The message followed by the suggested action, for places with one line of text */
+export function describeError(error: unknown): string {
+	const action = errorAction(error);
+	const message = errorMessage(error);
+	return action ? `${message}. ${action}.` : message;
+}
+
+/** Whether the command gave up because its dictation was cancelled */
+export function isCancelled(error: unknown): boolean {
+	return isAppError(error) && error.kind === "cancelled";
+}
@@ -17,6 +17,15 @@ export const DEFAULT_HOLD_KEY = "Backquote";
 /** Default key for paste last transcription (Ctrl+Alt+.) */
 export const DEFAULT_PASTE_LAST_KEY = "Period";
 
+/** Default key for undoing the last paste (Ctrl+Alt+Z) */
+export const DEFAULT_UNDO_PASTE_KEY = "Z";
+
+/** Default key for cleaning up the copied text (Ctrl+Alt+C) */
+export const DEFAULT_CLIPBOARD_CLEANUP_KEY = "C";
+
+/** Default key for cancelling the dictation in progress (Ctrl+Alt+X) */
+export const DEFAULT_CANCEL_KEY = "X";
+
 // ============================================================================
 
 /** Default toggle hotkey config */
@@ -36,3 +45,21 @@ export const DEFAULT_PASTE_LAST_HOTKEY: HotkeyConfig = {
 	modifiers: DEFAULT_HOTKEY_MODIFIERS,
 	key: DEFAULT_PASTE_LAST_KEY,
 };
+
+/** Default undo last paste hotkey config */
+export const DEFAULT_UNDO_PASTE_HOTKEY: HotkeyConfig = {
+	modifiers: DEFAULT_HOTKEY_MODIFIERS,
+	key: DEFAULT_UNDO_PASTE_KEY,
+};
+
+/** Default clipboard cleanup hotkey config */
+export const DEFAULT_CLIPBOARD_CLEANUP_HOTKEY: HotkeyConfig = {
+	modifiers: DEFAULT_HOTKEY_MODIFIERS,
+	key: DEFAULT_CLIPBOARD_CLEANUP_KEY,
+};
+
+/** Default cancel dictation hotkey config */
+export const DEFAULT_CANCEL_HOTKEY: HotkeyConfig = {
+	modifiers: DEFAULT_HOTKEY_MODIFIERS,
+	key: DEFAULT_CANCEL_KEY,
+};
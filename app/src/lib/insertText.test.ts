@@ -0,0 +1,18 @@
+import { describe, expect, it } from "vitest";
+import { spliceText } from "./insertText";
+
+describe("spliceText", () => {
+	it("inserts at the cursor", () => {
+		expect(spliceText("Hello world", 5, 5, ",")).toEqual({
+			value: "Hello, world",
+			cursor: 6,
+		});
+	});
+
+	it("replaces the selection", () => {
+		expect(spliceText("Hello world", 6, 11, "there")).toEqual({
+			value: "Hello there",
+			cursor: 11,
+		});
+	});
+});
@@ -0,0 +1,73 @@
+// Inserting dictated text into a field of the app's own window, sent from the
+// backend when that window has focus (see in_app_insert.rs)
+
+/** Input types that take free text */
+const TEXT_INPUT_TYPES = new Set([
+	"text",
+	"search",
+	"url",
+	"email",
+	"tel",
+	"number",
+]);
+
+/** Whether an element is a field dictated text can go into */
+export function isTextField(element: Element | null): boolean {
+	if (element instanceof HTMLTextAreaElement) {
+		return !element.readOnly && !element.disabled;
+	}
+	if (element instanceof HTMLInputElement) {
+		return (
+			TEXT_INPUT_TYPES.has(element.type) &&
+			!element.readOnly &&
+			!element.disabled
+		);
+	}
+	return element instanceof HTMLElement && element.isContentEditable;
+}
+
+/** Replace the selection `start`..`end` of a value, with the cursor after the text */
+export function spliceText(
+	value: string,
+	start: number,
+	end: number,
+	text: string,
+): { value: string; cursor: number } {
+	return {
+		value: value.slice(0, start) + text + value.slice(end),
+		cursor: start + text.length,
+	};
+}
+
+/** Insert text at the cursor of the focused field; returns false if none has focus */
+export function insertIntoFocusedField(text: string): boolean {
+	const element = document.activeElement;
+	if (!isTextField(element)) {
+		return false;
+	}
+	// Keeps undo history and fires the input events React listens to
+	if (document.execCommand("insertText", false, text)) {
+		return true;
+	}
+	if (
+		element instanceof HTMLInputElement ||
+		element instanceof HTMLTextAreaElement
+	) {
+		const { value, cursor } = spliceText(
+			element.value,
+			element.selectionStart ?? element.value.length,
+			element.selectionEnd ?? element.value.length,
+			text,
+		);
+		// Set through the prototype so React sees the change
+		const setter = Object.getOwnPropertyDescriptor(
+			Object.getPrototypeOf(element),
+			"value",
+		)?.set;
+		setter?.call(element, value);
+		element.setSelectionRange(cursor, cursor);
+		element.dispatchEvent(new Event("input", { bubbles: true }));
+		return true;
+	}
+	return false;
+}
@@ -1,9 +1,16 @@
-import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import {
+	keepPreviousData,
+	useMutation,
+	useQuery,
+	useQueryClient,
+} from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
 import { useEffect, useRef } from "react";
 import {
 	type CleanupPromptSections,
+	type CollapsedHistoryEntry,
 	configAPI,
+	type HistoryRange,
 	type HotkeyConfig,
 	tauriAPI,
 	validateHotkeyNotDuplicate,
@@ -55,7 +62,13 @@ export function useServerUrl() {
 
 export function useTypeText() {
 	return useMutation({
-		mutationFn: (text: string) => invoke("type_text", { text }),
+		mutationFn: ({
+			text,
+			dictation,
+		}: {
+			text: string;
+			dictation: number | null;
+		}) => invoke("type_text", { text, dictation }),
 	});
 }
 
@@ -82,6 +95,9 @@ export function useUpdateToggleHotkey() {
 					toggle: settings.toggle_hotkey,
 					hold: settings.hold_hotkey,
 					paste_last: settings.paste_last_hotkey,
+					undo_paste: settings.undo_paste_hotkey,
+					clipboard_cleanup: settings.clipboard_cleanup_hotkey,
+					cancel: settings.cancel_hotkey,
 				},
 				"toggle",
 			);
@@ -111,6 +127,9 @@ export function useUpdateHoldHotkey() {
 					toggle: settings.toggle_hotkey,
 					hold: settings.hold_hotkey,
 					paste_last: settings.paste_last_hotkey,
+					undo_paste: settings.undo_paste_hotkey,
+					clipboard_cleanup: settings.clipboard_cleanup_hotkey,
+					cancel: settings.cancel_hotkey,
 				},
 				"hold",
 			);
@@ -140,6 +159,9 @@ export function useUpdatePasteLastHotkey() {
 					toggle: settings.toggle_hotkey,
 					hold: settings.hold_hotkey,
 					paste_last: settings.paste_last_hotkey,
+					undo_paste: settings.undo_paste_hotkey,
+					clipboard_cleanup: settings.clipboard_cleanup_hotkey,
+					cancel: settings.cancel_hotkey,
 				},
 				"paste_last",
 			);
@@ -155,6 +177,116 @@ export function useUpdatePasteLastHotkey() {
 	});
 }
 
+export function useUpdateUndoPasteHotkey() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: async (hotkey: HotkeyConfig) => {
+			// Get current settings for validation
+			const settings = await tauriAPI.getSettings();
+
+			// Validate no duplicate
+			const error = validateHotkeyNotDuplicate(
+				hotkey,
+				{
+					toggle: settings.toggle_hotkey,
+					hold: settings.hold_hotkey,
+					paste_last: settings.paste_last_hotkey,
+					undo_paste: settings.undo_paste_hotkey,
+					clipboard_cleanup: settings.clipboard_cleanup_hotkey,
+					cancel: settings.cancel_hotkey,
+				},
+				"undo_paste",
+			);
+			if (error) throw new Error(error);
+
+			// Save and re-register
+			await tauriAPI.updateUndoPasteHotkey(hotkey);
+			await tauriAPI.registerShortcuts();
+		},
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["settings"] });
+		},
+	});
+}
+
+export function useUpdateClipboardCleanupHotkey() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: async (hotkey: HotkeyConfig) => {
+			// Get current settings for validation
+			const settings = await tauriAPI.getSettings();
+
+			// Validate no duplicate
+			const error = validateHotkeyNotDuplicate(
+				hotkey,
+				{
+					toggle: settings.toggle_hotkey,
+					hold: settings.hold_hotkey,
+					paste_last: settings.paste_last_hotkey,
+					undo_paste: settings.undo_paste_hotkey,
+					clipboard_cleanup: settings.clipboard_cleanup_hotkey,
+					cancel: settings.cancel_hotkey,
+				},
+				"clipboard_cleanup",
+			);
+			if (error) throw new Error(error);
+
+			// Save and re-register
+			await tauriAPI.updateClipboardCleanupHotkey(hotkey);
+			await tauriAPI.registerShortcuts();
+		},
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["settings"] });
+		},
+	});
+}
+
+export function useUpdateCancelHotkey() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: async (hotkey: HotkeyConfig) => {
+			// Get current settings for validation
+			const settings = await tauriAPI.getSettings();
+
+			// Validate no duplicate
+			const error = validateHotkeyNotDuplicate(
+				hotkey,
+				{
+					toggle: settings.toggle_hotkey,
+					hold: settings.hold_hotkey,
+					paste_last: settings.paste_last_hotkey,
+					undo_paste: settings.undo_paste_hotkey,
+					clipboard_cleanup: settings.clipboard_cleanup_hotkey,
+					cancel: settings.cancel_hotkey,
+				},
+				"cancel",
+			);
+			if (error) throw new Error(error);
+
+			// Save and re-register
+			await tauriAPI.updateCancelHotkey(hotkey);
+			await tauriAPI.registerShortcuts();
+		},
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["settings"] });
+		},
+	});
+}
+
+export function useUpdateClipboardCleanupEnabled() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: async (enabled: boolean) => {
+			// The hotkey is only registered while the mode is on
+			await tauriAPI.updateClipboardCleanupEnabled(enabled);
+			await tauriAPI.registerShortcuts();
+		},
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["settings"] });
+		},
+	});
+}
+
 export function useUpdateSelectedMic() {
 	const queryClient = useQueryClient();
 	return useMutation({
@@ -208,7 +340,7 @@ export function useResetHotkeysToDefaults() {
 	const queryClient = useQueryClient();
 	return useMutation({
 		mutationFn: async () => {
-			await tauriAPI.resetHotkeysToDefaults();
+			await tauriAPI.resetSettings("hotkeys");
 			await tauriAPI.registerShortcuts();
 		},
 		onSuccess: () => {
@@ -221,21 +353,216 @@ export function useResetHotkeysToDefaults() {
 }
 
 // History queries and mutations
-export function useHistory(limit?: number) {
+/** Entries dictated into one app during a period */
+export interface HistoryAppFilter {
+	app: string;
+	range: HistoryRange;
+}
+
+function matchesSearch(entry: CollapsedHistoryEntry, query: string): boolean {
+	const needle = query.toLowerCase();
+	return [entry.text, entry.raw_text, entry.note, ...entry.tags].some((text) =>
+		text?.toLowerCase().includes(needle),
+	);
+}
+
+/**
+ * History, or matches for `search`; a search like "#email" lists that tag.
+ * With an app filter only that app's entries are listed, narrowed by `search`.
+ */
+export function useHistory(
+	limit?: number,
+	search?: string,
+	appFilter?: HistoryAppFilter | null,
+) {
+	const query = search?.trim() ?? "";
 	return useQuery({
-		queryKey: ["history", limit],
-		queryFn: () => tauriAPI.getHistory(limit),
+		queryKey: ["history", limit, query, appFilter ?? null],
+		queryFn: async () => {
+			if (appFilter) {
+				const entries = await tauriAPI.getHistoryByApp(
+					appFilter.app,
+					appFilter.range,
+					limit,
+				);
+				return query
+					? entries.filter((entry) => matchesSearch(entry, query))
+					: entries;
+			}
+			if (/^#\S+$/.test(query)) return tauriAPI.getHistoryByTag(query, limit);
+			return query
+				? tauriAPI.searchHistory(query, limit)
+				: tauriAPI.getHistory(limit);
+		},
+		// Keep showing the last results while a new search runs
+		placeholderData: keepPreviousData,
+	});
+}
+
+/** Apps dictated into during the range, most used first */
+export function useHistoryApps(range: HistoryRange = {}) {
+	return useQuery({
+		queryKey: ["history", "apps", range],
+		queryFn: () => tauriAPI.listHistoryApps(range),
 	});
 }
 
 export function useAddHistoryEntry() {
 	const queryClient = useQueryClient();
 	return useMutation({
-		mutationFn: (text: string) => tauriAPI.addHistoryEntry(text),
+		mutationFn: ({
+			text,
+			rawText,
+		}: {
+			text: string;
+			rawText?: string | null;
+		}) => tauriAPI.addHistoryEntry(text, rawText),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["history"] });
+		},
+	});
+}
+
+export function useSnippets() {
+	return useQuery({
+		queryKey: ["snippets"],
+		queryFn: () => tauriAPI.listSnippets(),
+	});
+}
+
+export function useAddSnippet() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: ({
+			name,
+			text,
+			sourceEntryId,
+		}: {
+			name: string;
+			text: string;
+			sourceEntryId?: string;
+		}) => tauriAPI.addSnippet(name, text, sourceEntryId),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["snippets"] });
+		},
+	});
+}
+
+export function useUpdateSnippet() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: ({
+			id,
+			name,
+			text,
+		}: {
+			id: string;
+			name: string;
+			text: string;
+		}) => tauriAPI.updateSnippet(id, name, text),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["snippets"] });
+		},
+	});
+}
+
+export function useDeleteSnippet() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: (id: string) => tauriAPI.deleteSnippet(id),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["snippets"] });
+		},
+	});
+}
+
+export function useFailedDictations() {
+	return useQuery({
+		queryKey: ["failedDictations"],
+		queryFn: () => tauriAPI.listFailedDictations(),
+	});
+}
+
+export function useDiscardFailedDictation() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: (id: string) => tauriAPI.discardFailedDictation(id),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["failedDictations"] });
+		},
+	});
+}
+
+export function useFileTranscriptions() {
+	return useQuery({
+		queryKey: ["fileTranscriptions"],
+		queryFn: () => tauriAPI.listFileTranscriptions(),
+	});
+}
+
+export function useTranscribeFiles() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: (paths: string[]) => tauriAPI.transcribeFiles(paths),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["fileTranscriptions"] });
+		},
+	});
+}
+
+export function useHistoryTags() {
+	return useQuery({
+		queryKey: ["history", "tags"],
+		queryFn: () => tauriAPI.listHistoryTags(),
+	});
+}
+
+export function useUpdateHistoryEntryLabels() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: async ({
+			id,
+			tags,
+			note,
+		}: {
+			id: string;
+			tags: string[];
+			note: string | null;
+		}) => {
+			await tauriAPI.setHistoryEntryTags(id, tags);
+			await tauriAPI.setHistoryEntryNote(id, note);
+		},
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["history"] });
+		},
+	});
+}
+
+export function useHistoryLocked() {
+	return useQuery({
+		queryKey: ["historyLocked"],
+		queryFn: () => tauriAPI.isHistoryLocked(),
+	});
+}
+
+export function useUnlockHistory() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: (passphrase: string) => tauriAPI.unlockHistory(passphrase),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["historyLocked"] });
+			queryClient.invalidateQueries({ queryKey: ["history"] });
+		},
+	});
+}
+
+export function useSetHistoryEntryPinned() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: ({ id, pinned }: { id: string; pinned: boolean }) =>
+			tauriAPI.setHistoryEntryPinned(id, pinned),
 		onSuccess: () => {
 			queryClient.invalidateQueries({ queryKey: ["history"] });
-			// Notify other windows about history change
-			tauriAPI.emitHistoryChanged();
 		},
 	});
 }
@@ -246,8 +573,6 @@ export function useDeleteHistoryEntry() {
 		mutationFn: (id: string) => tauriAPI.deleteHistoryEntry(id),
 		onSuccess: () => {
 			queryClient.invalidateQueries({ queryKey: ["history"] });
-			// Notify other windows about history change
-			tauriAPI.emitHistoryChanged();
 		},
 	});
 }
@@ -258,8 +583,44 @@ export function useClearHistory() {
 		mutationFn: () => tauriAPI.clearHistory(),
 		onSuccess: () => {
 			queryClient.invalidateQueries({ queryKey: ["history"] });
-			// Notify other windows about history change
-			tauriAPI.emitHistoryChanged();
+		},
+	});
+}
+
+/** Deleted entries, kept under the history key so history changes refresh it */
+export function useTrashedHistory() {
+	return useQuery({
+		queryKey: ["history", "trash"],
+		queryFn: () => tauriAPI.listTrashedHistory(),
+	});
+}
+
+export function useRestoreHistoryEntry() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: (id: string) => tauriAPI.restoreHistoryEntry(id),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["history"] });
+		},
+	});
+}
+
+export function usePurgeHistoryEntry() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: (id: string) => tauriAPI.purgeHistoryEntry(id),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["history", "trash"] });
+		},
+	});
+}
+
+export function useEmptyHistoryTrash() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: () => tauriAPI.emptyHistoryTrash(),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["history", "trash"] });
 		},
 	});
 }
@@ -284,6 +645,37 @@ export function useAvailableProviders() {
 	});
 }
 
+/**
+ * Keep the tray menu in step with the main window: send it the server's
+ * providers, and refetch settings when they are changed from the tray.
+ */
+export function useTrayMenuSync() {
+	const queryClient = useQueryClient();
+	const { data: availableProviders } = useAvailableProviders();
+
+	useEffect(() => {
+		if (availableProviders) {
+			tauriAPI.setTrayProviders(availableProviders.stt, availableProviders.llm);
+		}
+	}, [availableProviders]);
+
+	useEffect(() => {
+		let unlisten: (() => void) | undefined;
+
+		const setup = async () => {
+			unlisten = await tauriAPI.onSettingsChanged(() => {
+				queryClient.invalidateQueries({ queryKey: ["settings"] });
+			});
+		};
+
+		setup();
+
+		return () => {
+			unlisten?.();
+		};
+	}, [queryClient]);
+}
+
 export function useUpdateSTTProvider() {
 	const queryClient = useQueryClient();
 	return useMutation({
@@ -306,6 +698,36 @@ export function useUpdateLLMProvider() {
 	});
 }
 
+export function useUpdateSTTVocabulary() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: (terms: string[]) => tauriAPI.updateSTTVocabulary(terms),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["settings"] });
+			queryClient.invalidateQueries({ queryKey: ["vocabularyInsights"] });
+		},
+	});
+}
+
+export function useImportFromTool() {
+	const queryClient = useQueryClient();
+	return useMutation({
+		mutationFn: async (file: File) =>
+			tauriAPI.importFromTool(file.name, await file.text()),
+		onSuccess: () => {
+			queryClient.invalidateQueries({ queryKey: ["settings"] });
+			queryClient.invalidateQueries({ queryKey: ["vocabularyInsights"] });
+		},
+	});
+}
+
+export function useVocabularyInsights() {
+	return useQuery({
+		queryKey: ["vocabularyInsights"],
+		queryFn: () => tauriAPI.getVocabularyInsights(),
+	});
+}
+
 // STT Timeout mutation (local settings)
 export function useUpdateSTTTimeout() {
 	const queryClient = useQueryClient();
@@ -0,0 +1,39 @@
+import { describe, expect, it } from "vitest";
+import { formatRecordingTime, formatSeconds } from "./recordingTime";
+
+describe("formatSeconds", () => {
+	it("pads seconds and rounds down", () => {
+		expect(formatSeconds(0)).toBe("0:00");
+		expect(formatSeconds(5.9)).toBe("0:05");
+		expect(formatSeconds(105)).toBe("1:45");
+		expect(formatSeconds(3600)).toBe("60:00");
+	});
+
+	it("clamps negative values", () => {
+		expect(formatSeconds(-3)).toBe("0:00");
+	});
+});
+
+describe("formatRecordingTime", () => {
+	it("shows the limit when there is one", () => {
+		expect(
+			formatRecordingTime({
+				elapsed_seconds: 105.4,
+				audio_bytes: 3_372_800,
+				limit_seconds: 120,
+				near_limit: true,
+			}),
+		).toBe("1:45 / 2:00");
+	});
+
+	it("shows only the elapsed time without a limit", () => {
+		expect(
+			formatRecordingTime({
+				elapsed_seconds: 12,
+				audio_bytes: 384_000,
+				limit_seconds: null,
+				near_limit: false,
+			}),
+		).toBe("0:12");
+	});
+});
@@ -0,0 +1,18 @@
+import type { RecordingProgress } from "./tauri";
+
+/** Format seconds as m:ss, rounding down to whole seconds */
+export function formatSeconds(seconds: number): string {
+	const whole = Math.max(0, Math.floor(seconds));
+	const minutes = Math.floor(whole / 60);
+	const rest = whole % 60;
+	return `${minutes}:${rest.toString().padStart(2, "0")}`;
+}
+
+/** Elapsed recording time, with the limit when there is one (e.g. "1:45 / 2:00") */
+export function formatRecordingTime(progress: RecordingProgress): string {
+	const elapsed = formatSeconds(progress.elapsed_seconds);
+	if (progress.limit_seconds === null) {
+		return elapsed;
+	}
+	return `${elapsed} / ${formatSeconds(progress.limit_seconds)}`;
+}
@@ -90,6 +90,9 @@ describe("createHotkeyDuplicateSchema", () => {
 		toggle: { modifiers: ["ctrl", "alt"], key: "Space" },
 		hold: { modifiers: ["ctrl", "alt"], key: "Backquote" },
 		paste_last: { modifiers: ["ctrl", "alt"], key: "Period" },
+		undo_paste: { modifiers: ["ctrl", "alt"], key: "Z" },
+		clipboard_cleanup: { modifiers: ["ctrl", "alt"], key: "C" },
+		cancel: { modifiers: ["ctrl", "alt"], key: "X" },
 	};
 
 	it("allows a unique hotkey when editing toggle", () => {
@@ -142,6 +145,9 @@ describe("validateHotkeyNotDuplicate", () => {
 		toggle: { modifiers: ["ctrl", "alt"], key: "Space" },
 		hold: { modifiers: ["ctrl", "alt"], key: "Backquote" },
 		paste_last: { modifiers: ["ctrl", "alt"], key: "Period" },
+		undo_paste: { modifiers: ["ctrl", "alt"], key: "Z" },
+		clipboard_cleanup: { modifiers: ["ctrl", "alt"], key: "C" },
+		cancel: { modifiers: ["ctrl", "alt"], key: "X" },
 	};
 
 	it("returns null for a unique hotkey", () => {
@@ -4,6 +4,7 @@ import { getCurrentWindow } from "@tauri-apps/api/window";
 import { Store } from "@tauri-apps/plugin-store";
 import ky from "ky";
 import { z } from "zod";
+import { describeError } from "./errors";
 
 export type ConnectionState =
 	| "disconnected"
@@ -35,10 +36,227 @@ export const HotkeyConfigSchema = z.object({
 	key: z.string().min(1, "Key is required"),
 });
 
+export type HistoryExportFormat = "markdown" | "json" | "csv";
+
+export type HistoryEncryption = "off" | "keychain" | "passphrase";
+
+/** Limits history is pruned to; null or 0 means no limit */
+export interface HistoryLimits {
+	max_days: number | null;
+	max_entries: number | null;
+	max_disk_mb: number | null;
+}
+
+/** What a pruning pass removed, by the limit that removed it */
+export interface PruneSummary {
+	by_age: number;
+	by_count: number;
+	by_size: number;
+	freed_bytes: number;
+}
+
+/** What a history sync changed on this device */
+export interface SyncSummary {
+	imported: number;
+	deleted: number;
+	devices: number;
+}
+
+/** ISO timestamps; entries at or after `to` are left out */
+export interface HistoryRange {
+	from?: string;
+	to?: string;
+}
+
 interface HistoryEntry {
 	id: string;
 	timestamp: string;
 	text: string;
+	raw_text: string | null;
+	stt_provider: string | null;
+	llm_provider: string | null;
+	duration_seconds: number | null;
+	target_app: string | null;
+	latency_seconds: number | null;
+	pinned: boolean;
+	revised_text: string | null;
+	session_id: string | null;
+	has_audio: boolean;
+	/** Sorted, lowercase, without the leading # */
+	tags: string[];
+	note: string | null;
+}
+
+/** A named piece of text to paste again, kept apart from history */
+export interface Snippet {
+	id: string;
+	name: string;
+	text: string;
+	created_at: string;
+	source_entry_id: string | null;
+}
+
+export interface TagCount {
+	tag: string;
+	entries: number;
+}
+
+/** A kept recording of a history entry */
+/** An app dictated into, with how many entries went to it */
+export interface AppCount {
+	app: string;
+	entries: number;
+}
+
+export interface HistoryAudio {
+	path: string;
+	mime_type: string;
+	bytes: number[];
+	/** Peak levels from 0 to 1, evenly spaced over the recording */
+	waveform: number[];
+}
+
+/** A dictation whose pipeline run failed, kept to retry or discard */
+export interface FailedDictation {
+	id: string;
+	timestamp: string;
+	/** What went wrong, from the latest attempt */
+	error: string;
+	stt_provider: string | null;
+	llm_provider: string | null;
+	/** Transcript before cleanup, when the failure came after transcription */
+	raw_text: string | null;
+	/** MIME type of the kept recording, when there is one */
+	mime_type: string | null;
+	/** Retries that failed too */
+	retries: number;
+}
+
+/** Where a dropped file is in the transcription queue */
+export type FileJobStatus =
+	| { status: "queued" }
+	| { status: "extracting" }
+	| { status: "transcribing" }
+	| { status: "done"; entry_id: string; text: string }
+	| { status: "failed"; message: string }
+	| { status: "cancelled" };
+
+/** An audio or video file queued for transcription */
+export type FileJob = {
+	id: string;
+	path: string;
+	name: string;
+	cleanup: boolean;
+} & FileJobStatus;
+
+/** A failed dictation's recording, to replay through the pipeline */
+export interface FailedDictationAudio {
+	mime_type: string;
+	bytes: number[];
+}
+
+/** A history entry with older near-duplicates (retries) listed under it */
+/** A deleted entry, restorable until the trash expires */
+export interface TrashedHistoryEntry extends HistoryEntry {
+	trashed_at: string;
+}
+
+export interface CollapsedHistoryEntry extends HistoryEntry {
+	/** Newest first */
+	duplicates: HistoryEntry[];
+}
+
+/** A named group of dictations, such as a meeting */
+export interface Session {
+	id: string;
+	name: string;
+	started_at: string;
+	ended_at: string | null;
+}
+
+/** Where a phone opens the remote and the code it pairs with */
+export interface RemotePairingInfo {
+	urls: string[];
+	code: string;
+	/** Pairing is off after too many wrong codes, until a new code is made */
+	pairing_disabled: boolean;
+}
+
+/** A meeting from the configured calendar that is about to start */
+export interface CalendarMeeting {
+	uid: string;
+	title: string;
+	start: string;
+	end: string | null;
+}
+
+export interface PeriodCount {
+	/** First day of the period (Monday for weeks), as YYYY-MM-DD */
+	start: string;
+	dictations: number;
+	words: number;
+}
+
+export interface ProviderLatency {
+	stt_provider: string | null;
+	llm_provider: string | null;
+	samples: number;
+	p50_seconds: number;
+	p90_seconds: number;
+	p99_seconds: number;
+}
+
+export interface UsageStats {
+	dictations: number;
+	words: number;
+	audio_seconds: number;
+	time_saved_seconds: number;
+	daily: PeriodCount[];
+	weekly: PeriodCount[];
+	latency: ProviderLatency[];
+}
+
+/** A word the cleanup kept correcting, with what STT heard instead */
+export interface CorrectedWord {
+	word: string;
+	heard_as: string[];
+	count: number;
+}
+
+export interface TermCount {
+	term: string;
+	count: number;
+}
+
+/** Suggested vocabulary terms, most frequent first */
+export interface VocabularyInsights {
+	corrected: CorrectedWord[];
+	frequent: TermCount[];
+}
+
+/** Tool a vocabulary file was exported from */
+export type ImportSource = "talon" | "dragon" | "superwhisper";
+
+/** What importing a file from another dictation tool changed */
+export interface ImportSummary {
+	source: ImportSource;
+	added: number;
+	duplicates: number;
+	instructions_imported: boolean;
+}
+
+interface HistoryRevision {
+	id: string;
+	entry_id: string;
+	timestamp: string;
+	text: string;
+	llm_provider: string | null;
+}
+
+/** A history entry's transcript to clean up again (main -> overlay) */
+export interface RecleanRequest {
+	id: string;
+	text: string;
 }
 
 export interface PromptSection {
@@ -52,10 +270,163 @@ export interface CleanupPromptSections {
 	dictionary: PromptSection;
 }
 
+export type PasteMethod =
+	| "clipboard_paste"
+	| "simulate_typing"
+	| "copy_only"
+	| "accessibility";
+
+export type PasteFormat = "plain" | "rich";
+
+export type InsertMode = "insert" | "replace_selection";
+
+export type UndoPasteMethod = "select_back" | "app_undo";
+
+export type PasteKeystroke =
+	| "ctrl_v"
+	| "ctrl_shift_v"
+	| "shift_insert"
+	| "middle_click";
+
+export type EmailClient = "mailto" | "outlook" | "apple_mail";
+
+/** Output overrides for one target app; unset fields use the global settings */
+export interface AppOutputProfile {
+	paste_method?: PasteMethod | null;
+	paste_format?: PasteFormat | null;
+	paste_keystroke?: PasteKeystroke | null;
+	/** Put before every line, e.g. "// " for code comments or "> " for quotes */
+	line_prefix?: string | null;
+	/** Wrapper with `{text}` where the output goes */
+	template?: string | null;
+	/** Note in the Obsidian vault that gets the output instead of a paste */
+	obsidian_note?: string | null;
+	/** Mail app that opens a new draft with the output instead of a paste */
+	email_draft?: EmailClient | null;
+}
+
+export type AnalyticsMode = "off" | "local_only";
+
+export interface LocalMetrics {
+	recordings_started: number;
+	dictations_completed: number;
+	words_dictated: number;
+	paste_last_used: number;
+	since: string | null;
+}
+
+export interface LocalePreferences {
+	locale: string | null;
+	decimal_separator: "." | ",";
+	date_format:
+		| "YYYY-MM-DD"
+		| "DD.MM.YYYY"
+		| "DD/MM/YYYY"
+		| "MM/DD/YYYY"
+		| null;
+	number_style: "digits" | "small_as_words" | "as_spoken";
+}
+
+/** Dictation pipeline step, emitted by the backend as "overlay-state" */
+export type OverlayState =
+	| { state: "idle" }
+	| { state: "recording" }
+	| { state: "transcribing" }
+	| { state: "cleaning" }
+	| { state: "pasting" }
+	| { state: "error"; message: string };
+
+/** What started a recording */
+export type RecordingMode = "toggle" | "hold" | "overlay";
+
+/** Where the app is in a dictation */
+export type AppRecordingState =
+	| { state: "idle" }
+	| { state: "recording"; mode: RecordingMode }
+	| { state: "transcribing" }
+	| { state: "pasting" };
+
+export type OverlaySize = "compact" | "regular" | "large";
+
+/** Small pill, or a wider panel that also shows the transcript while recording */
+export type OverlayLayout = "pill" | "panel";
+
+export interface OverlayAppearance {
+	/** Waveform and logo color, as #rrggbb */
+	accent_color: string;
+	/** Background opacity, 0.2 to 1.0 */
+	opacity: number;
+	size: OverlaySize;
+	reduced_motion: boolean;
+}
+
+export const DEFAULT_OVERLAY_APPEARANCE: OverlayAppearance = {
+	accent_color: "#eeeeee",
+	opacity: 0.9,
+	size: "regular",
+	reduced_motion: false,
+};
+
+export type EdgeIndicator = "off" | "top" | "bottom" | "left" | "right";
+
+export type OverlayMonitor =
+	| { mode: "follow_focus" }
+	| { mode: "primary" }
+	| { mode: "fixed"; monitor: string };
+
+export type OverlayAnchor =
+	| "free"
+	| "top_left"
+	| "top_center"
+	| "top_right"
+	| "bottom_left"
+	| "bottom_center"
+	| "bottom_right"
+	| "near_cursor"
+	| "near_caret";
+
+/** Which pipeline outcomes are posted as OS notifications */
+export interface NotificationSettings {
+	transcription_complete: boolean;
+	provider_errors: boolean;
+	quota_warnings: boolean;
+}
+
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+/**
+ * URL each finished dictation is posted to. The template's `{{field}}`
+ * placeholders take the history entry's fields; without one the entry is
+ * posted as JSON.
+ */
+export interface Webhook {
+	url: string;
+	enabled: boolean;
+	template: string | null;
+	content_type: string;
+}
+
+/**
+ * Command each dictation is handed to, as JSON on stdin and in the
+ * `TAMBOURINE_TEXT` environment variable. Limited to `apps` when any are listed.
+ */
+export interface OutputSink {
+	name: string;
+	command: string;
+	args: string[];
+	enabled: boolean;
+	replaces_paste: boolean;
+	apps: string[];
+}
+
 export interface AppSettings {
 	toggle_hotkey: HotkeyConfig;
 	hold_hotkey: HotkeyConfig;
 	paste_last_hotkey: HotkeyConfig;
+	undo_paste_hotkey: HotkeyConfig;
+	clipboard_cleanup_hotkey: HotkeyConfig;
+	cancel_hotkey: HotkeyConfig;
+	clipboard_cleanup_enabled: boolean;
 	selected_mic_id: string | null;
 	sound_enabled: boolean;
 	cleanup_prompt_sections: CleanupPromptSections | null;
@@ -63,6 +434,150 @@ export interface AppSettings {
 	llm_provider: string | null;
 	auto_mute_audio: boolean;
 	stt_timeout_seconds: number | null;
+	stt_vocabulary: string[];
+	paste_method: PasteMethod;
+	copy_only_mode: boolean;
+	insert_mode: InsertMode;
+	smart_insertion: boolean;
+	paste_format: PasteFormat;
+	restore_clipboard: boolean;
+	verify_paste_target: boolean;
+	require_text_field_for_paste: boolean;
+	refuse_secure_fields: boolean;
+	typing_chars_per_second: number | null;
+	typing_jitter_percent: number;
+	output_file_path: string | null;
+	output_file_only: boolean;
+	obsidian_vault: string | null;
+	obsidian_tags: string[];
+	output_sinks: OutputSink[];
+	paste_ring_size: number | null;
+	undo_paste_method: UndoPasteMethod;
+	confirm_paste_over_chars: number | null;
+	app_output_profiles: Record<string, AppOutputProfile>;
+	review_before_paste: boolean;
+	start_at_login: boolean;
+	start_minimized: boolean;
+	tray_only_mode: boolean;
+	analytics_mode: AnalyticsMode;
+	history_retention_days: number | null;
+	history_max_entries: number | null;
+	history_trash_days: number | null;
+	history_max_disk_mb: number | null;
+	history_duplicate_threshold: number;
+	history_keep_audio: boolean;
+	history_encryption: HistoryEncryption;
+	history_sync_folder: string | null;
+	journal_folder: string | null;
+	journal_summarize: boolean;
+	calendar_ics_url: string | null;
+	meeting_prompt_minutes: number | null;
+	automation_api_enabled: boolean;
+	automation_api_port: number | null;
+	automation_api_token: string | null;
+	stream_deck_enabled: boolean;
+	stream_deck_port: number | null;
+	event_stream_enabled: boolean;
+	event_stream_port: number | null;
+	mqtt_broker_url: string | null;
+	mqtt_username: string | null;
+	mqtt_password: string | null;
+	mqtt_topic_prefix: string | null;
+	mqtt_home_assistant_discovery: boolean;
+	browser_extension_enabled: boolean;
+	browser_extension_port: number | null;
+	browser_extension_ids: string[];
+	obs_captions_enabled: boolean;
+	obs_websocket_url: string | null;
+	obs_websocket_password: string | null;
+	obs_caption_source: string | null;
+	obs_caption_clear_seconds: number | null;
+	obs_stream_captions: boolean;
+	remote_control_enabled: boolean;
+	remote_control_port: number | null;
+	webhooks: Webhook[];
+	typing_speed_wpm: number | null;
+	daily_audio_minutes_limit: number | null;
+	daily_cloud_request_limit: number | null;
+	max_recording_seconds: number | null;
+	locale_preferences: LocalePreferences | null;
+	overlay_monitor: OverlayMonitor;
+	overlay_anchor: OverlayAnchor;
+	overlay_positions: Record<string, { x: number; y: number }>;
+	overlay_click_through: boolean;
+	overlay_auto_hide_seconds: number | null;
+	exclude_overlay_from_capture: boolean;
+	overlay_appearance: OverlayAppearance;
+	overlay_layout: OverlayLayout;
+	edge_indicator: EdgeIndicator;
+	suppress_over_fullscreen: boolean;
+	notifications: NotificationSettings;
+	log_level: LogLevel;
+}
+
+/** Section of the settings view that can be opened directly */
+export type SettingsSection = "providers" | "audio" | "hotkeys" | "prompts";
+
+/** Result of the foreground fullscreen check made when a recording starts */
+export interface FullscreenStatus {
+	fullscreen: boolean;
+	suppressed: boolean;
+}
+
+/** Elapsed time and approximate audio size of the running recording */
+export interface RecordingProgress {
+	elapsed_seconds: number;
+	audio_bytes: number;
+	limit_seconds: number | null;
+	near_limit: boolean;
+}
+
+/** Text held back from pasting until it is confirmed */
+export interface HeldPaste {
+	chars: number;
+	reason: string;
+}
+
+export type QuotaLevel = "ok" | "warning" | "exceeded";
+
+export interface QuotaStatus {
+	level: QuotaLevel;
+	usage: {
+		date: string;
+		audio_seconds: number;
+		cloud_requests: number;
+	};
+	audio_minutes_limit: number | null;
+	cloud_requests_limit: number | null;
+}
+
+export type SettingsScope =
+	| "hotkeys"
+	| "audio"
+	| "providers"
+	| "prompts"
+	| "output"
+	| "all";
+
+export interface SettingDescriptor {
+	key: string;
+	value_type: string;
+	default: unknown;
+	description: string;
+	restart_required: boolean;
+	machine_local: boolean;
+	locked: boolean;
+}
+
+export type OnboardingStep =
+	| "mic_permission"
+	| "provider_setup"
+	| "hotkey_test";
+
+export interface OnboardingState {
+	completed_steps: OnboardingStep[];
+	current_step: OnboardingStep | null;
+	is_complete: boolean;
 }
 
 // ============================================================================
@@ -86,6 +601,21 @@ export const defaultPasteLastHotkey: HotkeyConfig = {
 	key: "Period",
 };
 
+export const defaultUndoPasteHotkey: HotkeyConfig = {
+	modifiers: DEFAULT_HOTKEY_MODIFIERS,
+	key: "Z",
+};
+
+export const defaultClipboardCleanupHotkey: HotkeyConfig = {
+	modifiers: DEFAULT_HOTKEY_MODIFIERS,
+	key: "C",
+};
+
+export const defaultCancelHotkey: HotkeyConfig = {
+	modifiers: DEFAULT_HOTKEY_MODIFIERS,
+	key: "X",
+};
+
 // ============================================================================
 // Store helpers
 // ============================================================================
@@ -94,11 +624,17 @@ let storeInstance: Store | null = null;
 
 async function getStore(): Promise<Store> {
 	if (!storeInstance) {
-		storeInstance = await Store.load("settings.json");
+		// Auto-save is disabled: persistence goes through the Rust SettingsManager,
+		// which writes atomically and keeps rolling backups
+		storeInstance = await Store.load("settings.json", { autoSave: false });
 	}
 	return storeInstance;
 }
 
+async function saveStore(): Promise<void> {
+	return invoke("save_settings");
+}
+
 // ============================================================================
 // Hotkey validation helpers (Zod-based)
 // ============================================================================
@@ -114,12 +650,21 @@ export function hotkeyIsSameAs(a: HotkeyConfig, b: HotkeyConfig): boolean {
 	);
 }
 
-type HotkeyType = "toggle" | "hold" | "paste_last";
+type HotkeyType =
+	| "toggle"
+	| "hold"
+	| "paste_last"
+	| "undo_paste"
+	| "clipboard_cleanup"
+	| "cancel";
 
 const HOTKEY_LABELS: Record<HotkeyType, string> = {
 	toggle: "toggle",
 	hold: "hold",
 	paste_last: "paste last",
+	undo_paste: "undo paste",
+	clipboard_cleanup: "clipboard cleanup",
+	cancel: "cancel",
 };
 
 /**
@@ -152,6 +697,9 @@ export function validateHotkeyNotDuplicate(
 		toggle: HotkeyConfig;
 		hold: HotkeyConfig;
 		paste_last: HotkeyConfig;
+		undo_paste: HotkeyConfig;
+		clipboard_cleanup: HotkeyConfig;
+		cancel: HotkeyConfig;
 	},
 	excludeType: HotkeyType,
 ): string | null {
@@ -173,7 +721,7 @@ export const tauriAPI = {
 			await invoke("type_text", { text });
 			return { success: true };
 		} catch (error) {
-			return { success: false, error: String(error) };
+			return { success: false, error: describeError(error) };
 		}
 	},
 
@@ -189,6 +737,45 @@ export const tauriAPI = {
 		return listen("recording-stop", callback);
 	},
 
+	/** The recording was cancelled; its audio should be dropped */
+	async onCancelRecording(callback: () => void): Promise<UnlistenFn> {
+		return listen("recording-cancel", callback);
+	},
+
+	/** The app is quitting before the dictation finished; keep it to retry */
+	async onDictationInterrupted(callback: () => void): Promise<UnlistenFn> {
+		return listen("dictation-interrupted", callback);
+	},
+
+	async onOverlayState(
+		callback: (state: OverlayState) => void,
+	): Promise<UnlistenFn> {
+		return listen<OverlayState>("overlay-state", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async reportOverlayState(state: OverlayState): Promise<void> {
+		return invoke("report_overlay_state", { state });
+	},
+
+	/** Start a dictation run; its ID goes back with the dictation's text */
+	async beginDictation(): Promise<number> {
+		return invoke("begin_dictation");
+	},
+
+	async getAppState(): Promise<AppRecordingState> {
+		return invoke("get_app_state");
+	},
+
+	async onAppStateChanged(
+		callback: (state: AppRecordingState) => void,
+	): Promise<UnlistenFn> {
+		return listen<AppRecordingState>("app-state-changed", (event) => {
+			callback(event.payload);
+		});
+	},
+
 	// Settings API - using store plugin directly
 	async getSettings(): Promise<AppSettings> {
 		const store = await getStore();
@@ -200,6 +787,16 @@ export const tauriAPI = {
 			paste_last_hotkey:
 				(await store.get<HotkeyConfig>("paste_last_hotkey")) ??
 				defaultPasteLastHotkey,
+			undo_paste_hotkey:
+				(await store.get<HotkeyConfig>("undo_paste_hotkey")) ??
+				defaultUndoPasteHotkey,
+			clipboard_cleanup_hotkey:
+				(await store.get<HotkeyConfig>("clipboard_cleanup_hotkey")) ??
+				defaultClipboardCleanupHotkey,
+			cancel_hotkey:
+				(await store.get<HotkeyConfig>("cancel_hotkey")) ?? defaultCancelHotkey,
+			clipboard_cleanup_enabled:
+				(await store.get<boolean>("clipboard_cleanup_enabled")) ?? false,
 			selected_mic_id:
 				(await store.get<string | null>("selected_mic_id")) ?? null,
 			sound_enabled: (await store.get<boolean>("sound_enabled")) ?? true,
@@ -212,37 +809,220 @@ export const tauriAPI = {
 			auto_mute_audio: (await store.get<boolean>("auto_mute_audio")) ?? false,
 			stt_timeout_seconds:
 				(await store.get<number | null>("stt_timeout_seconds")) ?? null,
+			stt_vocabulary: (await store.get<string[]>("stt_vocabulary")) ?? [],
+			paste_method:
+				(await store.get<PasteMethod>("paste_method")) ?? "clipboard_paste",
+			copy_only_mode: (await store.get<boolean>("copy_only_mode")) ?? false,
+			insert_mode: (await store.get<InsertMode>("insert_mode")) ?? "insert",
+			smart_insertion: (await store.get<boolean>("smart_insertion")) ?? false,
+			paste_format: (await store.get<PasteFormat>("paste_format")) ?? "plain",
+			restore_clipboard:
+				(await store.get<boolean>("restore_clipboard")) ?? true,
+			verify_paste_target:
+				(await store.get<boolean>("verify_paste_target")) ?? false,
+			require_text_field_for_paste:
+				(await store.get<boolean>("require_text_field_for_paste")) ?? false,
+			refuse_secure_fields:
+				(await store.get<boolean>("refuse_secure_fields")) ?? true,
+			typing_chars_per_second:
+				(await store.get<number | null>("typing_chars_per_second")) ?? null,
+			typing_jitter_percent:
+				(await store.get<number>("typing_jitter_percent")) ?? 0,
+			output_file_path:
+				(await store.get<string | null>("output_file_path")) ?? null,
+			output_file_only:
+				(await store.get<boolean>("output_file_only")) ?? false,
+			obsidian_vault:
+				(await store.get<string | null>("obsidian_vault")) ?? null,
+			obsidian_tags: (await store.get<string[]>("obsidian_tags")) ?? [
+				"dictation",
+			],
+			output_sinks: (await store.get<OutputSink[]>("output_sinks")) ?? [],
+			paste_ring_size:
+				(await store.get<number | null>("paste_ring_size")) ?? null,
+			undo_paste_method:
+				(await store.get<UndoPasteMethod>("undo_paste_method")) ??
+				"select_back",
+			confirm_paste_over_chars:
+				(await store.get<number | null>("confirm_paste_over_chars")) ?? null,
+			app_output_profiles:
+				(await store.get<Record<string, AppOutputProfile>>(
+					"app_output_profiles",
+				)) ?? {},
+			review_before_paste:
+				(await store.get<boolean>("review_before_paste")) ?? false,
+			start_at_login: (await store.get<boolean>("start_at_login")) ?? false,
+			start_minimized: (await store.get<boolean>("start_minimized")) ?? false,
+			tray_only_mode: (await store.get<boolean>("tray_only_mode")) ?? false,
+			analytics_mode:
+				(await store.get<AnalyticsMode>("analytics_mode")) ?? "off",
+			history_retention_days:
+				(await store.get<number | null>("history_retention_days")) ?? null,
+			history_max_entries:
+				(await store.get<number | null>("history_max_entries")) ?? null,
+			history_trash_days:
+				(await store.get<number | null>("history_trash_days")) ?? null,
+			history_max_disk_mb:
+				(await store.get<number | null>("history_max_disk_mb")) ?? null,
+			history_duplicate_threshold:
+				(await store.get<number>("history_duplicate_threshold")) ?? 0.9,
+			history_keep_audio:
+				(await store.get<boolean>("history_keep_audio")) ?? false,
+			history_encryption:
+				(await store.get<HistoryEncryption>("history_encryption")) ?? "off",
+			history_sync_folder:
+				(await store.get<string | null>("history_sync_folder")) ?? null,
+			journal_folder:
+				(await store.get<string | null>("journal_folder")) ?? null,
+			journal_summarize:
+				(await store.get<boolean>("journal_summarize")) ?? false,
+			calendar_ics_url:
+				(await store.get<string | null>("calendar_ics_url")) ?? null,
+			meeting_prompt_minutes:
+				(await store.get<number | null>("meeting_prompt_minutes")) ?? null,
+			automation_api_enabled:
+				(await store.get<boolean>("automation_api_enabled")) ?? false,
+			automation_api_port:
+				(await store.get<number | null>("automation_api_port")) ?? null,
+			automation_api_token:
+				(await store.get<string | null>("automation_api_token")) ?? null,
+			stream_deck_enabled:
+				(await store.get<boolean>("stream_deck_enabled")) ?? false,
+			stream_deck_port:
+				(await store.get<number | null>("stream_deck_port")) ?? null,
+			event_stream_enabled:
+				(await store.get<boolean>("event_stream_enabled")) ?? false,
+			event_stream_port:
+				(await store.get<number | null>("event_stream_port")) ?? null,
+			mqtt_broker_url:
+				(await store.get<string | null>("mqtt_broker_url")) ?? null,
+			mqtt_username: (await store.get<string | null>("mqtt_username")) ?? null,
+			mqtt_password: (await store.get<string | null>("mqtt_password")) ?? null,
+			mqtt_topic_prefix:
+				(await store.get<string | null>("mqtt_topic_prefix")) ?? null,
+			mqtt_home_assistant_discovery:
+				(await store.get<boolean>("mqtt_home_assistant_discovery")) ?? true,
+			browser_extension_enabled:
+				(await store.get<boolean>("browser_extension_enabled")) ?? false,
+			browser_extension_port:
+				(await store.get<number | null>("browser_extension_port")) ?? null,
+			browser_extension_ids:
+				(await store.get<string[]>("browser_extension_ids")) ?? [],
+			obs_captions_enabled:
+				(await store.get<boolean>("obs_captions_enabled")) ?? false,
+			obs_websocket_url:
+				(await store.get<string | null>("obs_websocket_url")) ?? null,
+			obs_websocket_password:
+				(await store.get<string | null>("obs_websocket_password")) ?? null,
+			obs_caption_source:
+				(await store.get<string | null>("obs_caption_source")) ?? null,
+			obs_caption_clear_seconds:
+				(await store.get<number | null>("obs_caption_clear_seconds")) ?? null,
+			obs_stream_captions:
+				(await store.get<boolean>("obs_stream_captions")) ?? false,
+			remote_control_enabled:
+				(await store.get<boolean>("remote_control_enabled")) ?? false,
+			remote_control_port:
+				(await store.get<number | null>("remote_control_port")) ?? null,
+			webhooks: (await store.get<Webhook[]>("webhooks")) ?? [],
+			typing_speed_wpm:
+				(await store.get<number | null>("typing_speed_wpm")) ?? null,
+			daily_audio_minutes_limit:
+				(await store.get<number | null>("daily_audio_minutes_limit")) ?? null,
+			daily_cloud_request_limit:
+				(await store.get<number | null>("daily_cloud_request_limit")) ?? null,
+			max_recording_seconds:
+				(await store.get<number | null>("max_recording_seconds")) ?? null,
+			locale_preferences:
+				(await store.get<LocalePreferences | null>("locale_preferences")) ??
+				null,
+			overlay_monitor: (await store.get<OverlayMonitor>("overlay_monitor")) ?? {
+				mode: "follow_focus",
+			},
+			overlay_anchor:
+				(await store.get<OverlayAnchor>("overlay_anchor")) ?? "free",
+			overlay_positions:
+				(await store.get<Record<string, { x: number; y: number }>>(
+					"overlay_positions",
+				)) ?? {},
+			overlay_click_through:
+				(await store.get<boolean>("overlay_click_through")) ?? false,
+			overlay_auto_hide_seconds:
+				(await store.get<number | null>("overlay_auto_hide_seconds")) ?? null,
+			exclude_overlay_from_capture:
+				(await store.get<boolean>("exclude_overlay_from_capture")) ?? false,
+			overlay_appearance: {
+				...DEFAULT_OVERLAY_APPEARANCE,
+				...(await store.get<Partial<OverlayAppearance>>("overlay_appearance")),
+			},
+			overlay_layout:
+				(await store.get<OverlayLayout>("overlay_layout")) ?? "pill",
+			edge_indicator:
+				(await store.get<EdgeIndicator>("edge_indicator")) ?? "off",
+			suppress_over_fullscreen:
+				(await store.get<boolean>("suppress_over_fullscreen")) ?? false,
+			notifications: {
+				transcription_complete: false,
+				provider_errors: false,
+				quota_warnings: false,
+				...(await store.get<Partial<NotificationSettings>>("notifications")),
+			},
+			log_level: (await store.get<LogLevel>("log_level")) ?? "info",
 		};
 	},
 
 	async updateToggleHotkey(hotkey: HotkeyConfig): Promise<void> {
 		const store = await getStore();
 		await store.set("toggle_hotkey", hotkey);
-		await store.save();
+		await saveStore();
 	},
 
 	async updateHoldHotkey(hotkey: HotkeyConfig): Promise<void> {
 		const store = await getStore();
 		await store.set("hold_hotkey", hotkey);
-		await store.save();
+		await saveStore();
 	},
 
 	async updatePasteLastHotkey(hotkey: HotkeyConfig): Promise<void> {
 		const store = await getStore();
 		await store.set("paste_last_hotkey", hotkey);
-		await store.save();
+		await saveStore();
+	},
+
+	async updateUndoPasteHotkey(hotkey: HotkeyConfig): Promise<void> {
+		const store = await getStore();
+		await store.set("undo_paste_hotkey", hotkey);
+		await saveStore();
+	},
+
+	async updateClipboardCleanupHotkey(hotkey: HotkeyConfig): Promise<void> {
+		const store = await getStore();
+		await store.set("clipboard_cleanup_hotkey", hotkey);
+		await saveStore();
+	},
+
+	async updateCancelHotkey(hotkey: HotkeyConfig): Promise<void> {
+		const store = await getStore();
+		await store.set("cancel_hotkey", hotkey);
+		await saveStore();
+	},
+
+	async updateClipboardCleanupEnabled(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("clipboard_cleanup_enabled", enabled);
+		await saveStore();
 	},
 
 	async updateSelectedMic(micId: string | null): Promise<void> {
 		const store = await getStore();
 		await store.set("selected_mic_id", micId);
-		await store.save();
+		await saveStore();
 	},
 
 	async updateSoundEnabled(enabled: boolean): Promise<void> {
 		const store = await getStore();
 		await store.set("sound_enabled", enabled);
-		await store.save();
+		await saveStore();
 	},
 
 	async updateCleanupPromptSections(
@@ -250,103 +1030,1112 @@ export const tauriAPI = {
 	): Promise<void> {
 		const store = await getStore();
 		await store.set("cleanup_prompt_sections", sections);
-		await store.save();
+		await saveStore();
 	},
 
 	async updateSTTProvider(provider: string | null): Promise<void> {
 		const store = await getStore();
 		await store.set("stt_provider", provider);
-		await store.save();
+		await saveStore();
 	},
 
 	async updateLLMProvider(provider: string | null): Promise<void> {
 		const store = await getStore();
 		await store.set("llm_provider", provider);
-		await store.save();
+		await saveStore();
 	},
 
 	async updateAutoMuteAudio(enabled: boolean): Promise<void> {
 		const store = await getStore();
 		await store.set("auto_mute_audio", enabled);
-		await store.save();
+		await saveStore();
 	},
 
 	async updateSTTTimeout(timeoutSeconds: number | null): Promise<void> {
 		const store = await getStore();
 		await store.set("stt_timeout_seconds", timeoutSeconds);
-		await store.save();
+		await saveStore();
 	},
 
-	async isAudioMuteSupported(): Promise<boolean> {
-		return invoke("is_audio_mute_supported");
+	async updateSTTVocabulary(terms: string[]): Promise<void> {
+		const store = await getStore();
+		await store.set("stt_vocabulary", terms);
+		await saveStore();
 	},
 
-	async resetHotkeysToDefaults(): Promise<void> {
+	async updateLocalePreferences(
+		preferences: LocalePreferences | null,
+	): Promise<void> {
 		const store = await getStore();
-		await store.set("toggle_hotkey", defaultToggleHotkey);
-		await store.set("hold_hotkey", defaultHoldHotkey);
-		await store.set("paste_last_hotkey", defaultPasteLastHotkey);
-		await store.save();
+		await store.set("locale_preferences", preferences);
+		await saveStore();
 	},
 
-	async registerShortcuts(): Promise<void> {
-		return invoke("register_shortcuts");
+	async updateOverlayMonitor(monitor: OverlayMonitor): Promise<void> {
+		const store = await getStore();
+		await store.set("overlay_monitor", monitor);
+		await saveStore();
+		await invoke("reposition_overlay");
+		await invoke("reposition_edge_indicator");
 	},
 
-	async unregisterShortcuts(): Promise<void> {
-		return invoke("unregister_shortcuts");
+	async updateOverlayAnchor(anchor: OverlayAnchor): Promise<void> {
+		const store = await getStore();
+		await store.set("overlay_anchor", anchor);
+		await saveStore();
+		await invoke("reposition_overlay");
 	},
 
-	// History API
-	async addHistoryEntry(text: string): Promise<HistoryEntry> {
-		return invoke("add_history_entry", { text });
+	async setTrayProviders(
+		stt: ProviderInfo[],
+		llm: ProviderInfo[],
+	): Promise<void> {
+		return invoke("set_tray_providers", { stt, llm });
 	},
 
-	async getHistory(limit?: number): Promise<HistoryEntry[]> {
-		return invoke("get_history", { limit });
+	async getOverlayScale(): Promise<number> {
+		return invoke("get_overlay_scale");
 	},
 
-	async deleteHistoryEntry(id: string): Promise<boolean> {
-		return invoke("delete_history_entry", { id });
+	async onOverlayScaleChanged(
+		callback: (scale: number) => void,
+	): Promise<UnlistenFn> {
+		return listen<number>("overlay-scale-changed", (event) => {
+			callback(event.payload);
+		});
 	},
 
-	async clearHistory(): Promise<void> {
-		return invoke("clear_history");
+	async getMonitors(): Promise<string[]> {
+		return invoke("get_monitors");
 	},
 
-	// Overlay API
-	async resizeOverlay(width: number, height: number): Promise<void> {
-		return invoke("resize_overlay", { width, height });
+	async resetOverlayPosition(): Promise<void> {
+		return invoke("reset_overlay_position");
 	},
 
-	async startDragging(): Promise<void> {
-		const window = getCurrentWindow();
-		return window.startDragging();
+	async updateOverlayClickThrough(enabled: boolean): Promise<void> {
+		return invoke("update_overlay_click_through", { enabled });
 	},
 
-	// Connection state sync between windows
-	async emitConnectionState(state: ConnectionState): Promise<void> {
-		return emit("connection-state-changed", { state });
+	async updateOverlayAutoHideSeconds(seconds: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("overlay_auto_hide_seconds", seconds);
+		await saveStore();
 	},
 
-	async onConnectionStateChanged(
-		callback: (state: ConnectionState) => void,
+	async setOverlayHovered(hovered: boolean): Promise<void> {
+		return invoke("set_overlay_hovered", { hovered });
+	},
+
+	async updateOverlayCaptureExclusion(enabled: boolean): Promise<void> {
+		return invoke("update_overlay_capture_exclusion", { enabled });
+	},
+
+	async getOverlayAppearance(): Promise<OverlayAppearance> {
+		return invoke("get_overlay_appearance");
+	},
+
+	async updateOverlayAppearance(appearance: OverlayAppearance): Promise<void> {
+		return invoke("update_overlay_appearance", { appearance });
+	},
+
+	async onOverlayAppearanceChanged(
+		callback: (appearance: OverlayAppearance) => void,
 	): Promise<UnlistenFn> {
-		return listen<{ state: ConnectionState }>(
-			"connection-state-changed",
-			(event) => {
-				callback(event.payload.state);
-			},
-		);
+		return listen<OverlayAppearance>("overlay-appearance-changed", (event) => {
+			callback(event.payload);
+		});
 	},
 
-	// History sync between windows
-	async emitHistoryChanged(): Promise<void> {
-		return emit("history-changed", {});
+	async updateOverlayLayout(layout: OverlayLayout): Promise<void> {
+		return invoke("update_overlay_layout", { layout });
 	},
 
-	async onHistoryChanged(callback: () => void): Promise<UnlistenFn> {
-		return listen("history-changed", () => {
+	async onOverlayLayoutChanged(
+		callback: (layout: OverlayLayout) => void,
+	): Promise<UnlistenFn> {
+		return listen<OverlayLayout>("overlay-layout-changed", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async updateEdgeIndicator(edge: EdgeIndicator): Promise<void> {
+		return invoke("update_edge_indicator", { edge });
+	},
+
+	async updateSuppressOverFullscreen(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("suppress_over_fullscreen", enabled);
+		await saveStore();
+	},
+
+	async onForegroundFullscreen(
+		callback: (status: FullscreenStatus) => void,
+	): Promise<UnlistenFn> {
+		return listen<FullscreenStatus>("foreground-fullscreen", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async updatePasteMethod(method: PasteMethod): Promise<void> {
+		const store = await getStore();
+		await store.set("paste_method", method);
+		await saveStore();
+	},
+
+	async updateInsertMode(mode: InsertMode): Promise<void> {
+		const store = await getStore();
+		await store.set("insert_mode", mode);
+		await saveStore();
+	},
+
+	async updateSmartInsertion(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("smart_insertion", enabled);
+		await saveStore();
+	},
+
+	async updatePasteFormat(format: PasteFormat): Promise<void> {
+		const store = await getStore();
+		await store.set("paste_format", format);
+		await saveStore();
+	},
+
+	async setCopyOnlyMode(enabled: boolean): Promise<void> {
+		return invoke("set_copy_only_mode", { enabled });
+	},
+
+	async setInAppFieldFocused(focused: boolean): Promise<void> {
+		return invoke("set_in_app_field_focused", { focused });
+	},
+
+	async onInsertText(callback: (text: string) => void): Promise<UnlistenFn> {
+		return listen<string>("insert-text", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async updateRestoreClipboard(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("restore_clipboard", enabled);
+		await saveStore();
+	},
+
+	async updateVerifyPasteTarget(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("verify_paste_target", enabled);
+		await saveStore();
+	},
+
+	async updateRequireTextFieldForPaste(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("require_text_field_for_paste", enabled);
+		await saveStore();
+	},
+
+	async updateRefuseSecureFields(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("refuse_secure_fields", enabled);
+		await saveStore();
+	},
+
+	async updateTypingCharsPerSecond(rate: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("typing_chars_per_second", rate);
+		await saveStore();
+	},
+
+	async updateTypingJitterPercent(percent: number): Promise<void> {
+		const store = await getStore();
+		await store.set("typing_jitter_percent", percent);
+		await saveStore();
+	},
+
+	async updateOutputFile(path: string | null, only: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("output_file_path", path);
+		await store.set("output_file_only", only);
+		await saveStore();
+	},
+
+	async updateObsidian(vault: string | null, tags: string[]): Promise<void> {
+		const store = await getStore();
+		await store.set("obsidian_vault", vault);
+		await store.set("obsidian_tags", tags);
+		await saveStore();
+	},
+
+	async updateUndoPasteMethod(method: UndoPasteMethod): Promise<void> {
+		const store = await getStore();
+		await store.set("undo_paste_method", method);
+		await saveStore();
+	},
+
+	async undoLastPaste(): Promise<void> {
+		return invoke("undo_last_paste");
+	},
+
+	async updatePasteRingSize(size: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("paste_ring_size", size);
+		await saveStore();
+	},
+
+	async pasteNthLast(n: number): Promise<void> {
+		return invoke("paste_nth_last", { n });
+	},
+
+	async updateConfirmPasteOverChars(limit: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("confirm_paste_over_chars", limit);
+		await saveStore();
+	},
+
+	async pasteHeld(): Promise<void> {
+		return invoke("paste_held");
+	},
+
+	async onHeldPasteChanged(
+		callback: (held: HeldPaste | null) => void,
+	): Promise<UnlistenFn> {
+		return listen<HeldPaste | null>("held-paste-changed", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async updateAppOutputProfiles(
+		profiles: Record<string, AppOutputProfile>,
+	): Promise<void> {
+		const store = await getStore();
+		await store.set("app_output_profiles", profiles);
+		await saveStore();
+	},
+
+	async updateReviewBeforePaste(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("review_before_paste", enabled);
+		await saveStore();
+	},
+
+	// Review window API
+	async reviewTranscript(text: string): Promise<void> {
+		return invoke("review_transcript", { text });
+	},
+
+	async getPendingPaste(): Promise<string | null> {
+		return invoke("get_pending_paste");
+	},
+
+	async onPasteReview(callback: (text: string) => void): Promise<UnlistenFn> {
+		return listen<string>("paste-review", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async confirmPaste(text: string): Promise<void> {
+		return invoke("confirm_paste", { text });
+	},
+
+	async discardPending(): Promise<void> {
+		return invoke("discard_pending");
+	},
+
+	async updateStartMinimized(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("start_minimized", enabled);
+		await saveStore();
+	},
+
+	async updateTrayOnlyMode(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("tray_only_mode", enabled);
+		await saveStore();
+	},
+
+	async enableAutostart(): Promise<void> {
+		return invoke("enable_autostart");
+	},
+
+	async disableAutostart(): Promise<void> {
+		return invoke("disable_autostart");
+	},
+
+	async isAutostartEnabled(): Promise<boolean> {
+		return invoke("is_autostart_enabled");
+	},
+
+	async updateAnalyticsMode(mode: AnalyticsMode): Promise<void> {
+		return invoke("update_analytics_mode", { mode });
+	},
+
+	async getLocalMetrics(): Promise<LocalMetrics> {
+		return invoke("get_local_metrics");
+	},
+
+	async clearLocalMetrics(): Promise<void> {
+		return invoke("clear_local_metrics");
+	},
+
+	async getStats(range: HistoryRange = {}): Promise<UsageStats> {
+		return invoke("get_stats", { range });
+	},
+
+	async getVocabularyInsights(
+		range: HistoryRange = {},
+	): Promise<VocabularyInsights> {
+		return invoke("get_vocabulary_insights", { range });
+	},
+
+	async updateHistoryKeepAudio(enabled: boolean): Promise<void> {
+		const store = await getStore();
+		await store.set("history_keep_audio", enabled);
+		await saveStore();
+	},
+
+	async updateJournal(
+		folder: string | null,
+		summarize: boolean,
+	): Promise<void> {
+		const store = await getStore();
+		await store.set("journal_folder", folder);
+		await store.set("journal_summarize", summarize);
+		await saveStore();
+	},
+
+	async updateMeetingCalendar(
+		icsUrl: string | null,
+		promptMinutes: number | null,
+	): Promise<void> {
+		const store = await getStore();
+		await store.set("calendar_ics_url", icsUrl);
+		await store.set("meeting_prompt_minutes", promptMinutes);
+		await saveStore();
+	},
+
+	/** Takes effect after a restart; the token is created when the API starts */
+	async updateAutomationApi(
+		enabled: boolean,
+		port: number | null,
+	): Promise<void> {
+		const store = await getStore();
+		await store.set("automation_api_enabled", enabled);
+		await store.set("automation_api_port", port);
+		await saveStore();
+	},
+
+	async updateStreamDeck(enabled: boolean, port: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("stream_deck_enabled", enabled);
+		await store.set("stream_deck_port", port);
+		await saveStore();
+	},
+
+	async updateEventStream(
+		enabled: boolean,
+		port: number | null,
+	): Promise<void> {
+		const store = await getStore();
+		await store.set("event_stream_enabled", enabled);
+		await store.set("event_stream_port", port);
+		await saveStore();
+	},
+
+	async updateMqtt(mqtt: {
+		broker_url: string | null;
+		username: string | null;
+		password: string | null;
+		topic_prefix: string | null;
+		home_assistant_discovery: boolean;
+	}): Promise<void> {
+		const store = await getStore();
+		await store.set("mqtt_broker_url", mqtt.broker_url);
+		await store.set("mqtt_username", mqtt.username);
+		await store.set("mqtt_password", mqtt.password);
+		await store.set("mqtt_topic_prefix", mqtt.topic_prefix);
+		await store.set(
+			"mqtt_home_assistant_discovery",
+			mqtt.home_assistant_discovery,
+		);
+		await saveStore();
+	},
+
+	async updateBrowserExtension(extension: {
+		enabled: boolean;
+		port: number | null;
+		ids: string[];
+	}): Promise<void> {
+		const store = await getStore();
+		await store.set("browser_extension_enabled", extension.enabled);
+		await store.set("browser_extension_port", extension.port);
+		await store.set("browser_extension_ids", extension.ids);
+		await saveStore();
+	},
+
+	/**
+	 * Register the app as the native messaging host of the browser extensions,
+	 * resolving to the manifest files or registry keys written
+	 */
+	async installBrowserExtensionHost(): Promise<string[]> {
+		return invoke("install_browser_extension_host");
+	},
+
+	async updateObsCaptions(obs: {
+		enabled: boolean;
+		websocket_url: string | null;
+		websocket_password: string | null;
+		caption_source: string | null;
+		caption_clear_seconds: number | null;
+		stream_captions: boolean;
+	}): Promise<void> {
+		const store = await getStore();
+		await store.set("obs_captions_enabled", obs.enabled);
+		await store.set("obs_websocket_url", obs.websocket_url);
+		await store.set("obs_websocket_password", obs.websocket_password);
+		await store.set("obs_caption_source", obs.caption_source);
+		await store.set("obs_caption_clear_seconds", obs.caption_clear_seconds);
+		await store.set("obs_stream_captions", obs.stream_captions);
+		await saveStore();
+	},
+
+	/** Show a sample caption in OBS with the saved settings */
+	async sendTestObsCaption(): Promise<void> {
+		return invoke("send_test_obs_caption");
+	},
+
+	async updateRemoteControl(
+		enabled: boolean,
+		port: number | null,
+	): Promise<void> {
+		const store = await getStore();
+		await store.set("remote_control_enabled", enabled);
+		await store.set("remote_control_port", port);
+		await saveStore();
+	},
+
+	/** Addresses and pairing code of the phone remote, when it is running */
+	async getRemoteControlPairing(): Promise<RemotePairingInfo> {
+		return invoke("get_remote_control_pairing");
+	},
+
+	async regenerateRemotePairingCode(): Promise<RemotePairingInfo> {
+		return invoke("regenerate_remote_pairing_code");
+	},
+
+	/** The pairing code was replaced after too many wrong codes */
+	async onRemotePairingCodeChanged(callback: () => void): Promise<UnlistenFn> {
+		return listen("remote-pairing-code-changed", () => {
+			callback();
+		});
+	},
+
+	async regenerateAutomationApiToken(): Promise<string> {
+		return invoke("regenerate_automation_api_token");
+	},
+
+	async updateWebhooks(webhooks: Webhook[]): Promise<void> {
+		const store = await getStore();
+		await store.set("webhooks", webhooks);
+		await saveStore();
+	},
+
+	/** Post a sample dictation to a webhook, resolving to the HTTP status */
+	async testWebhook(webhook: Webhook): Promise<number> {
+		return invoke("test_webhook", { webhook });
+	},
+
+	async updateOutputSinks(sinks: OutputSink[]): Promise<void> {
+		const store = await getStore();
+		await store.set("output_sinks", sinks);
+		await saveStore();
+	},
+
+	/** Run an output sink command with a sample dictation */
+	async testOutputSink(sink: OutputSink): Promise<void> {
+		return invoke("test_output_sink", { sink });
+	},
+
+	/**
+	 * Import vocabulary (and superwhisper instructions) from a file exported by
+	 * another dictation tool, detected from the file unless `source` is given
+	 */
+	async importFromTool(
+		fileName: string,
+		content: string,
+		source?: ImportSource,
+	): Promise<ImportSummary> {
+		return invoke("import_from_tool", { fileName, content, source });
+	},
+
+	async updateHistorySyncFolder(folder: string | null): Promise<void> {
+		const store = await getStore();
+		await store.set("history_sync_folder", folder);
+		await saveStore();
+	},
+
+	async syncHistory(): Promise<SyncSummary> {
+		return invoke("sync_history");
+	},
+
+	async updateHistoryDuplicateThreshold(threshold: number): Promise<void> {
+		const store = await getStore();
+		await store.set("history_duplicate_threshold", threshold);
+		await saveStore();
+	},
+
+	async updateTypingSpeedWpm(wordsPerMinute: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("typing_speed_wpm", wordsPerMinute);
+		await saveStore();
+	},
+
+	async updateDailyAudioMinutesLimit(minutes: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("daily_audio_minutes_limit", minutes);
+		await saveStore();
+	},
+
+	async updateDailyCloudRequestLimit(requests: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("daily_cloud_request_limit", requests);
+		await saveStore();
+	},
+
+	async updateMaxRecordingSeconds(seconds: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("max_recording_seconds", seconds);
+		await saveStore();
+	},
+
+	async onRecordingProgress(
+		callback: (progress: RecordingProgress) => void,
+	): Promise<UnlistenFn> {
+		return listen<RecordingProgress>("recording-progress", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async getQuotaStatus(): Promise<QuotaStatus> {
+		return invoke("get_quota_status");
+	},
+
+	async onQuotaWarning(
+		callback: (status: QuotaStatus) => void,
+	): Promise<UnlistenFn> {
+		return listen<QuotaStatus>("quota-warning", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async onQuotaExceeded(
+		callback: (status: QuotaStatus) => void,
+	): Promise<UnlistenFn> {
+		return listen<QuotaStatus>("quota-exceeded", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async updateNotificationSettings(
+		notifications: NotificationSettings,
+	): Promise<void> {
+		const store = await getStore();
+		await store.set("notifications", notifications);
+		await saveStore();
+	},
+
+	async updateLogLevel(level: LogLevel): Promise<void> {
+		return invoke("update_log_level", { level });
+	},
+
+	async getRecentLogs(lines?: number): Promise<string[]> {
+		return invoke("get_recent_logs", { lines });
+	},
+
+	async isAudioMuteSupported(): Promise<boolean> {
+		return invoke("is_audio_mute_supported");
+	},
+
+	async resetSettings(scope: SettingsScope): Promise<void> {
+		return invoke("reset_settings", { scope });
+	},
+
+	async getLockedSettings(): Promise<string[]> {
+		return invoke("get_locked_settings");
+	},
+
+	async describeSettings(): Promise<SettingDescriptor[]> {
+		return invoke("describe_settings");
+	},
+
+	async getPendingRestartChanges(): Promise<string[]> {
+		return invoke("get_pending_restart_changes");
+	},
+
+	async takeSettingsRecovery(): Promise<string | null> {
+		return invoke("take_settings_recovery");
+	},
+
+	async onPendingRestartChanged(
+		callback: (keys: string[]) => void,
+	): Promise<UnlistenFn> {
+		return listen<string[]>("pending-restart-changed", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async restartApp(): Promise<void> {
+		return invoke("restart_app");
+	},
+
+	// Live transcript window API
+	async openTranscriptWindow(): Promise<void> {
+		return invoke("open_transcript_window");
+	},
+
+	async closeTranscriptWindow(): Promise<void> {
+		return invoke("close_transcript_window");
+	},
+
+	async updateLiveTranscript(text: string): Promise<void> {
+		return invoke("update_live_transcript", { text });
+	},
+
+	async getLiveTranscript(): Promise<string> {
+		return invoke("get_live_transcript");
+	},
+
+	async copyLiveTranscript(): Promise<void> {
+		return invoke("copy_live_transcript");
+	},
+
+	async onLiveTranscript(
+		callback: (text: string) => void,
+	): Promise<UnlistenFn> {
+		return listen<string>("live-transcript", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async openSettings(section: SettingsSection | null = null): Promise<void> {
+		return invoke("open_settings", { section });
+	},
+
+	async onOpenSettings(
+		callback: (section: SettingsSection | null) => void,
+	): Promise<UnlistenFn> {
+		return listen<SettingsSection | null>("open-settings", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async registerShortcuts(): Promise<void> {
+		return invoke("register_shortcuts");
+	},
+
+	async unregisterShortcuts(): Promise<void> {
+		return invoke("unregister_shortcuts");
+	},
+
+	// History API
+	async addHistoryEntry(
+		text: string,
+		rawText?: string | null,
+	): Promise<HistoryEntry> {
+		return invoke("add_history_entry", { text, rawText: rawText ?? null });
+	},
+
+	async getHistory(limit?: number): Promise<CollapsedHistoryEntry[]> {
+		return invoke("get_history", { limit });
+	},
+
+	async searchHistory(
+		query: string,
+		limit?: number,
+	): Promise<CollapsedHistoryEntry[]> {
+		return invoke("search_history", { query, limit });
+	},
+
+	async saveHistoryAudio(
+		id: string,
+		bytes: Uint8Array,
+		mimeType: string,
+		waveform: number[],
+	): Promise<boolean> {
+		return invoke("save_history_audio", {
+			id,
+			bytes: Array.from(bytes),
+			mimeType,
+			waveform,
+		});
+	},
+
+	async getHistoryAudio(id: string): Promise<HistoryAudio | null> {
+		return invoke("get_history_audio", { id });
+	},
+
+	async recordFailedDictation(
+		error: string,
+		rawText: string | null,
+		bytes: Uint8Array | null,
+		mimeType: string | null,
+	): Promise<FailedDictation> {
+		return invoke("record_failed_dictation", {
+			error,
+			rawText,
+			bytes: bytes ? Array.from(bytes) : null,
+			mimeType,
+		});
+	},
+
+	async listFailedDictations(): Promise<FailedDictation[]> {
+		return invoke("list_failed_dictations");
+	},
+
+	async getFailedDictationAudio(
+		id: string,
+	): Promise<FailedDictationAudio | null> {
+		return invoke("get_failed_dictation_audio", { id });
+	},
+
+	async noteFailedDictationRetry(id: string, error: string): Promise<boolean> {
+		return invoke("note_failed_dictation_retry", { id, error });
+	},
+
+	/** Start keeping the recording in progress on disk, in case the app crashes */
+	async beginRecordingRecovery(mimeType: string): Promise<void> {
+		return invoke("begin_recording_recovery", { mimeType });
+	},
+
+	async appendRecordingRecovery(bytes: Uint8Array): Promise<void> {
+		return invoke("append_recording_recovery", { bytes: Array.from(bytes) });
+	},
+
+	async finishRecordingRecovery(): Promise<void> {
+		return invoke("finish_recording_recovery");
+	},
+
+	async discardFailedDictation(id: string): Promise<boolean> {
+		return invoke("discard_failed_dictation", { id });
+	},
+
+	/** Queue audio or video files to be transcribed into history */
+	async transcribeFiles(paths: string[], cleanup = true): Promise<FileJob[]> {
+		return invoke("transcribe_files", { paths, cleanup });
+	},
+
+	async listFileTranscriptions(): Promise<FileJob[]> {
+		return invoke("list_file_transcriptions");
+	},
+
+	async cancelFileTranscription(id: string): Promise<boolean> {
+		return invoke("cancel_file_transcription", { id });
+	},
+
+	async clearFinishedFileTranscriptions(): Promise<void> {
+		return invoke("clear_finished_file_transcriptions");
+	},
+
+	async onFileTranscription(
+		callback: (job: FileJob) => void,
+	): Promise<UnlistenFn> {
+		return listen<FileJob>("file-transcription", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async setHistoryEntryTags(
+		id: string,
+		tags: string[],
+	): Promise<string[] | null> {
+		return invoke("set_history_entry_tags", { id, tags });
+	},
+
+	async setHistoryEntryNote(id: string, note: string | null): Promise<boolean> {
+		return invoke("set_history_entry_note", { id, note });
+	},
+
+	async listHistoryTags(): Promise<TagCount[]> {
+		return invoke("list_history_tags");
+	},
+
+	async getHistoryByTag(
+		tag: string,
+		limit?: number,
+	): Promise<CollapsedHistoryEntry[]> {
+		return invoke("get_history_by_tag", { tag, limit });
+	},
+
+	async listHistoryApps(range: HistoryRange = {}): Promise<AppCount[]> {
+		return invoke("list_history_apps", { range });
+	},
+
+	async getHistoryByApp(
+		app: string,
+		range: HistoryRange = {},
+		limit?: number,
+	): Promise<CollapsedHistoryEntry[]> {
+		return invoke("get_history_by_app", { targetApp: app, range, limit });
+	},
+
+	// Snippet API
+	async listSnippets(): Promise<Snippet[]> {
+		return invoke("list_snippets");
+	},
+
+	async addSnippet(
+		name: string,
+		text: string,
+		sourceEntryId?: string,
+	): Promise<Snippet> {
+		return invoke("add_snippet", {
+			name,
+			text,
+			sourceEntryId: sourceEntryId ?? null,
+		});
+	},
+
+	async updateSnippet(
+		id: string,
+		name: string,
+		text: string,
+	): Promise<boolean> {
+		return invoke("update_snippet", { id, name, text });
+	},
+
+	async deleteSnippet(id: string): Promise<boolean> {
+		return invoke("delete_snippet", { id });
+	},
+
+	async pasteSnippet(id: string): Promise<void> {
+		return invoke("paste_snippet", { id });
+	},
+
+	/** The snippet a dictation like "insert my email signature" asks for */
+	async resolveSnippetCommand(text: string): Promise<Snippet | null> {
+		return invoke("resolve_snippet_command", { text });
+	},
+
+	async getPinnedHistory(): Promise<HistoryEntry[]> {
+		return invoke("get_pinned_history");
+	},
+
+	async setHistoryEntryPinned(id: string, pinned: boolean): Promise<boolean> {
+		return invoke("set_history_entry_pinned", { id, pinned });
+	},
+
+	async addHistoryRevision(
+		entryId: string,
+		text: string,
+	): Promise<HistoryRevision> {
+		return invoke("add_history_revision", { entryId, text });
+	},
+
+	async getHistoryRevisions(entryId: string): Promise<HistoryRevision[]> {
+		return invoke("get_history_revisions", { entryId });
+	},
+
+	async deleteHistoryEntry(id: string): Promise<boolean> {
+		return invoke("delete_history_entry", { id });
+	},
+
+	async clearHistory(): Promise<void> {
+		return invoke("clear_history");
+	},
+
+	async listTrashedHistory(): Promise<TrashedHistoryEntry[]> {
+		return invoke("list_trashed_history");
+	},
+
+	async restoreHistoryEntry(id: string): Promise<boolean> {
+		return invoke("restore_history_entry", { id });
+	},
+
+	async purgeHistoryEntry(id: string): Promise<boolean> {
+		return invoke("purge_history_entry", { id });
+	},
+
+	async emptyHistoryTrash(): Promise<number> {
+		return invoke("empty_history_trash");
+	},
+
+	async updateHistoryTrashDays(days: number | null): Promise<void> {
+		const store = await getStore();
+		await store.set("history_trash_days", days);
+		await saveStore();
+	},
+
+	async exportHistory(
+		format: HistoryExportFormat,
+		range: HistoryRange,
+		path: string,
+	): Promise<number> {
+		return invoke("export_history", { format, range, path });
+	},
+
+	async startSession(name: string): Promise<Session> {
+		return invoke("start_session", { name });
+	},
+
+	/** Start a session named after a meeting, the live transcript and recording */
+	async startMeeting(title: string): Promise<Session> {
+		return invoke("start_meeting", { title });
+	},
+
+	async onMeetingStarting(
+		callback: (meeting: CalendarMeeting) => void,
+	): Promise<UnlistenFn> {
+		return listen<CalendarMeeting>("meeting-starting", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async endSession(): Promise<Session | null> {
+		return invoke("end_session");
+	},
+
+	async getActiveSession(): Promise<Session | null> {
+		return invoke("get_active_session");
+	},
+
+	async listSessions(): Promise<Session[]> {
+		return invoke("list_sessions");
+	},
+
+	async exportSession(id: string, path: string): Promise<void> {
+		return invoke("export_session", { id, path });
+	},
+
+	async updateHistoryLimits(limits: HistoryLimits): Promise<void> {
+		return invoke("update_history_limits", { limits });
+	},
+
+	async isHistoryLocked(): Promise<boolean> {
+		return invoke("is_history_locked");
+	},
+
+	async unlockHistory(passphrase: string): Promise<void> {
+		return invoke("unlock_history", { passphrase });
+	},
+
+	async setHistoryEncryption(
+		mode: HistoryEncryption,
+		passphrase?: string,
+	): Promise<void> {
+		return invoke("set_history_encryption", {
+			mode,
+			passphrase: passphrase ?? null,
+		});
+	},
+
+	// Onboarding API
+	async getOnboardingState(): Promise<OnboardingState> {
+		return invoke("get_onboarding_state");
+	},
+
+	async completeOnboardingStep(
+		step: OnboardingStep,
+	): Promise<OnboardingState> {
+		return invoke("complete_onboarding_step", { step });
+	},
+
+	// Overlay API
+	async resizeOverlay(width: number, height: number): Promise<void> {
+		return invoke("resize_overlay", { width, height });
+	},
+
+	async startDragging(): Promise<void> {
+		const window = getCurrentWindow();
+		return window.startDragging();
+	},
+
+	// Connection state sync between windows
+	async emitConnectionState(state: ConnectionState): Promise<void> {
+		return emit("connection-state-changed", { state });
+	},
+
+	async onConnectionStateChanged(
+		callback: (state: ConnectionState) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ state: ConnectionState }>(
+			"connection-state-changed",
+			(event) => {
+				callback(event.payload.state);
+			},
+		);
+	},
+
+	// History changes, emitted by the backend as they happen
+	async onHistoryEntryAdded(
+		callback: (entry: HistoryEntry) => void,
+	): Promise<UnlistenFn> {
+		return listen<HistoryEntry>("history-entry-added", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async onHistoryEntryUpdated(
+		callback: (entry: HistoryEntry) => void,
+	): Promise<UnlistenFn> {
+		return listen<HistoryEntry>("history-entry-updated", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	async onHistoryEntryDeleted(
+		callback: (id: string) => void,
+	): Promise<UnlistenFn> {
+		return listen<string>("history-entry-deleted", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	/** Many entries changed at once, such as after clearing or a sync */
+	async onHistoryChanged(callback: () => void): Promise<UnlistenFn> {
+		return listen("history-changed", () => {
+			callback();
+		});
+	},
+
+	async onHistoryPruned(
+		callback: (summary: PruneSummary) => void,
+	): Promise<UnlistenFn> {
+		return listen<PruneSummary>("history-pruned", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	// Re-cleaning history entries (main -> overlay, which holds the connection)
+	async emitRecleanRequest(request: RecleanRequest): Promise<void> {
+		return emit("reclean-request", request);
+	},
+
+	async onRecleanRequest(
+		callback: (request: RecleanRequest) => void,
+	): Promise<UnlistenFn> {
+		return listen<RecleanRequest>("reclean-request", (event) => {
+			callback(event.payload);
+		});
+	},
+
+	// Retrying failed dictations (main -> overlay, which holds the connection)
+	async emitRetryFailedDictation(id: string): Promise<void> {
+		return emit("retry-failed-dictation", { id });
+	},
+
+	async onRetryFailedDictation(
+		callback: (id: string) => void,
+	): Promise<UnlistenFn> {
+		return listen<{ id: string }>("retry-failed-dictation", (event) => {
+			callback(event.payload.id);
+		});
+	},
+
+	// Failed dictations sync between windows (overlay -> main)
+	async emitFailedDictationsChanged(): Promise<void> {
+		return emit("failed-dictations-changed", {});
+	},
+
+	async onFailedDictationsChanged(callback: () => void): Promise<UnlistenFn> {
+		return listen("failed-dictations-changed", () => {
 			callback();
 		});
 	},
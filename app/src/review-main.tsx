@@ -0,0 +1,20 @@
+import { MantineProvider } from "@mantine/core";
+import "@mantine/core/styles.css";
+import { StrictMode } from "react";
+import { createRoot } from "react-dom/client";
+import ReviewApp from "./ReviewApp";
+
+// Styles are imported in ReviewApp.tsx via app.css
+
+const rootElement = document.getElementById("root");
+if (!rootElement) {
+	throw new Error("Root element not found");
+}
+
+createRoot(rootElement).render(
+	<StrictMode>
+		<MantineProvider defaultColorScheme="dark">
+			<ReviewApp />
+		</MantineProvider>
+	</StrictMode>,
+);
@@ -25,6 +25,7 @@ interface RecordingState {
 	handleDisconnected: () => void;
 	startRecording: () => Promise<boolean>; // Returns false if not in valid state
 	stopRecording: () => boolean; // Returns false if not in valid state
+	cancelRecording: () => boolean; // Returns false if not recording or processing
 	handleResponse: () => void;
 
 	// Configuration via data channel
@@ -112,6 +113,31 @@ export const useRecordingStore = create<RecordingState>((set, get) => ({
 		}
 	},
 
+	cancelRecording: () => {
+		const { state, client } = get();
+		if ((state !== "recording" && state !== "processing") || !client) {
+			return false;
+		}
+
+		// Release the mic as when stopping, but have the server drop the buffer
+		// (and abort the cleanup when the recording already went through)
+		if (state === "recording") {
+			try {
+				client.enableMic(false);
+				client.tracks()?.local?.audio?.stop();
+			} catch (error) {
+				console.warn("[Recording] Failed to release mic:", error);
+			}
+		}
+		try {
+			client.sendClientMessage("cancel-recording", {});
+		} catch (error) {
+			console.warn("[Recording] Failed to send cancel message:", error);
+		}
+		set({ state: "idle" });
+		return true;
+	},
+
 	handleResponse: () => {
 		const { state } = get();
 		if (state === "processing") {
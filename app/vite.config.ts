@@ -37,6 +37,9 @@ export default defineConfig({
 			input: {
 				main: "index.html",
 				overlay: "overlay.html",
+				review: "review.html",
+				edgeIndicator: "edge-indicator.html",
+				transcript: "transcript.html",
 			},
 		},
 	},
@@ -3,23 +3,49 @@
 This module provides REST endpoints for:
 - Getting default prompt sections
 - Getting available providers (static configuration)
+- Summarizing text with an LLM, outside of any pipeline (daily journals)
+- Cleaning up text like a dictation, outside of any pipeline (automation API)
+- Transcribing recordings, outside of any pipeline (command line)
+- Aborting one of those requests once the app stops waiting for it
 
 All runtime pipeline configuration is now handled via WebRTC data channel
 through the ConfigurationProcessor. This file only exposes static
-configuration data that doesn't require pipeline access.
+configuration data and one-off requests that don't require pipeline access.
+
+Summary, cleanup and transcription requests spend provider credits, so they are
+refused when sent by a web page: browsers always send an Origin header with
+cross-origin POSTs, while the app's own webview uses one of APP_ORIGINS and its
+Rust side sends none.
+
+The app tags cleanup and transcription requests with an ID in the
+X-Tambourine-Request header. When the user cancels, it aborts the request by that
+ID, which cancels the provider call instead of leaving it to run (and be paid for)
+with nobody waiting for the answer.
 """
 
 from __future__ import annotations
 
-from typing import Any
-
-from fastapi import APIRouter
+import asyncio
+import base64
+import binascii
+from collections import deque
+from collections.abc import Coroutine
+from typing import Annotated, Any
+
+from fastapi import APIRouter, Depends, Header, HTTPException
+from pipecat.frames.frames import ErrorFrame, TranscriptionFrame
+from pipecat.processors.aggregators.openai_llm_context import OpenAILLMContext
+from pipecat.services.stt_service import SegmentedSTTService
 from pydantic import BaseModel
 
 from processors.llm import (
     ADVANCED_PROMPT_DEFAULT,
     DICTIONARY_PROMPT_DEFAULT,
     MAIN_PROMPT_DEFAULT,
+    LocalePreferences,
+    build_locale_prompt,
+    build_vocabulary_prompt,
+    combine_prompt_sections,
 )
 from services.provider_registry import (
     LLMProviderId,
@@ -28,12 +54,52 @@ from services.provider_registry import (
     get_stt_provider_labels,
 )
 
+# Origins of the app's webview (tauri:// on macOS and Linux, http(s)://tauri.localhost
+# on Windows)
+APP_ORIGINS: tuple[str, ...] = (
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+)
+
+# Origin of the Vite dev server, only allowed when the server runs with --dev
+DEV_ORIGIN = "http://localhost:5173"
+
+_allow_dev_origin = False
+
+
+def allow_dev_origin(enabled: bool) -> None:
+    """Allow or refuse requests from the Vite dev server (called at startup)."""
+    global _allow_dev_origin
+    _allow_dev_origin = enabled
+
+
+def is_app_origin(origin: str | None) -> bool:
+    """Whether a request's Origin header comes from the app (or is absent, as for
+    requests made outside a browser)."""
+    if origin is None or origin in APP_ORIGINS:
+        return True
+    return _allow_dev_origin and origin == DEV_ORIGIN
+
+
+async def require_app_origin(origin: Annotated[str | None, Header()] = None) -> None:
+    """Refuse requests sent by web pages other than the app's.
+
+    Raises:
+        HTTPException: If the request comes from another origin
+    """
+    if not is_app_origin(origin):
+        raise HTTPException(status_code=403, detail="Requests from web pages are not allowed")
+
+
 # Create router for config endpoints
 config_router = APIRouter()
 
 # Store available providers (set at startup, static during runtime)
 _available_stt_providers: list[STTProviderId] = []
 _available_llm_providers: list[LLMProviderId] = []
+_llm_services: dict[LLMProviderId, Any] = {}
+_stt_services: dict[STTProviderId, Any] = {}
 
 
 def set_available_providers(
@@ -46,9 +112,11 @@ def set_available_providers(
         stt_services: Dictionary of available STT services
         llm_services: Dictionary of available LLM services
     """
-    global _available_stt_providers, _available_llm_providers
+    global _available_stt_providers, _available_llm_providers, _llm_services, _stt_services
     _available_stt_providers = list(stt_services.keys())
     _available_llm_providers = list(llm_services.keys())
+    _llm_services = llm_services
+    _stt_services = stt_services
 
 
 # =============================================================================
@@ -122,3 +190,297 @@ async def get_available_providers() -> AvailableProvidersResponse:
     ]
 
     return AvailableProvidersResponse(stt=stt_providers, llm=llm_providers)
+
+
+# =============================================================================
+# Summarization Endpoint
+# =============================================================================
+
+SUMMARY_PROMPT = """You summarize a person's dictations from one day for their journal.
+
+Write a short summary in the first person, as a few sentences or bullet points, \
+covering the main topics, decisions and tasks. Use the language of the \
+dictations. Do not add anything that is not in them. Reply with the summary only."""
+
+
+class SummarizeRequest(BaseModel):
+    """Text to summarize, with the LLM provider to use (first available if unset)."""
+
+    text: str
+    provider: str | None = None
+
+
+class SummarizeResponse(BaseModel):
+    """The summary of the requested text."""
+
+    summary: str
+
+
+def build_summary_context(text: str) -> OpenAILLMContext:
+    """Create an OpenAI-compatible context asking for a summary of the text."""
+    return OpenAILLMContext(
+        messages=[
+            {"role": "system", "content": SUMMARY_PROMPT},
+            {"role": "user", "content": text},
+        ]
+    )
+
+
+def select_llm_service(provider: str | None) -> Any:
+    """Pick the requested LLM service, falling back to the first available one.
+
+    Raises:
+        HTTPException: If no LLM service is available
+    """
+    for provider_id, service in _llm_services.items():
+        if provider_id.value == provider:
+            return service
+    if not _llm_services:
+        raise HTTPException(status_code=503, detail="No LLM provider is available")
+    return next(iter(_llm_services.values()))
+
+
+@config_router.post(
+    "/api/summarize",
+    response_model=SummarizeResponse,
+    dependencies=[Depends(require_app_origin)],
+)
+async def summarize(request: SummarizeRequest) -> SummarizeResponse:
+    """Summarize text with an LLM, without going through a dictation pipeline."""
+    service = select_llm_service(request.provider)
+    summary = await service.run_inference(build_summary_context(request.text))
+    if not summary:
+        raise HTTPException(status_code=502, detail="The LLM returned no summary")
+    return SummarizeResponse(summary=summary.strip())
+
+
+# =============================================================================
+# Aborting Requests
+# =============================================================================
+
+# Status of a request the app aborted (nginx's "client closed request")
+ABORTED_STATUS = 499
+
+# Provider work of running requests, by the ID the app tagged them with
+_abortable_requests: dict[str, asyncio.Task[Any]] = {}
+# IDs aborted before their request arrived, so it is refused when it does
+_aborted_early: deque[str] = deque(maxlen=100)
+
+
+async def run_abortable[T](request_id: str | None, work: Coroutine[Any, Any, T]) -> T:
+    """Run a request's provider work so the app can abort it by the request's ID.
+
+    Raises:
+        HTTPException: If the app aborted the request
+    """
+    if request_id is None:
+        return await work
+    if request_id in _aborted_early:
+        work.close()
+        raise HTTPException(status_code=ABORTED_STATUS, detail="The request was aborted")
+    task = asyncio.create_task(work)
+    _abortable_requests[request_id] = task
+    try:
+        return await task
+    except asyncio.CancelledError:
+        # Cancelled along with the request itself rather than aborted by the app
+        current = asyncio.current_task()
+        if current is not None and current.cancelling():
+            raise
+        raise HTTPException(status_code=ABORTED_STATUS, detail="The request was aborted") from None
+    finally:
+        _abortable_requests.pop(request_id, None)
+
+
+@config_router.post(
+    "/api/requests/{request_id}/abort",
+    status_code=204,
+    dependencies=[Depends(require_app_origin)],
+)
+async def abort_request(request_id: str) -> None:
+    """Cancel the provider work of a request the app no longer waits for."""
+    task = _abortable_requests.get(request_id)
+    if task is None:
+        _aborted_early.append(request_id)
+    else:
+        task.cancel()
+
+
+# =============================================================================
+# Cleanup Endpoint
+# =============================================================================
+
+
+class CleanupRequest(BaseModel):
+    """Text to clean up, with the client's prompt settings (defaults when unset)."""
+
+    text: str
+    provider: str | None = None
+    sections: dict[str, Any] | None = None
+    locale: LocalePreferences | None = None
+    vocabulary: list[str] = []
+
+
+class CleanupResponse(BaseModel):
+    """The cleaned up text."""
+
+    text: str
+
+
+def build_cleanup_context(request: CleanupRequest) -> OpenAILLMContext:
+    """Create an OpenAI-compatible context cleaning up the text like a dictation."""
+    sections = request.sections or {}
+    prompt = combine_prompt_sections(
+        main_custom=sections.get("main", {}).get("content"),
+        advanced_enabled=sections.get("advanced", {}).get("enabled", True),
+        advanced_custom=sections.get("advanced", {}).get("content"),
+        dictionary_enabled=sections.get("dictionary", {}).get("enabled", False),
+        dictionary_custom=sections.get("dictionary", {}).get("content"),
+    )
+    if request.locale:
+        prompt = f"{prompt}\n\n{build_locale_prompt(request.locale)}"
+    vocabulary = build_vocabulary_prompt(request.vocabulary)
+    if vocabulary:
+        prompt = f"{prompt}\n\n{vocabulary}"
+    return OpenAILLMContext(
+        messages=[
+            {"role": "system", "content": prompt},
+            {"role": "user", "content": request.text},
+        ]
+    )
+
+
+@config_router.post(
+    "/api/cleanup",
+    response_model=CleanupResponse,
+    dependencies=[Depends(require_app_origin)],
+)
+async def cleanup(
+    request: CleanupRequest,
+    x_tambourine_request: Annotated[str | None, Header()] = None,
+) -> CleanupResponse:
+    """Clean up text with an LLM as if it had been dictated."""
+    return CleanupResponse(text=await run_abortable(x_tambourine_request, clean_up_text(request)))
+
+
+async def clean_up_text(request: CleanupRequest) -> str:
+    """Run the cleanup LLM over the request's text.
+
+    Raises:
+        HTTPException: If no LLM is available or it returned nothing
+    """
+    service = select_llm_service(request.provider)
+    text = await service.run_inference(build_cleanup_context(request))
+    if not text:
+        raise HTTPException(status_code=502, detail="The LLM returned no text")
+    return text.strip()
+
+
+# =============================================================================
+# Transcription Endpoint
+# =============================================================================
+
+
+class TranscribeRequest(BaseModel):
+    """A recording to transcribe and optionally clean up, with the client's settings."""
+
+    audio: str  # base64-encoded WAV file
+    stt_provider: str | None = None
+    cleanup: bool = True
+    provider: str | None = None
+    sections: dict[str, Any] | None = None
+    locale: LocalePreferences | None = None
+    vocabulary: list[str] = []
+
+
+class TranscribeResponse(BaseModel):
+    """The transcript as heard, and as cleaned up (the same without cleanup)."""
+
+    raw_text: str
+    text: str
+
+
+def decode_wav(audio: str) -> bytes:
+    """Decode a base64-encoded WAV file.
+
+    Raises:
+        HTTPException: If the audio isn't base64 or not a WAV file
+    """
+    try:
+        wav = base64.b64decode(audio, validate=True)
+    except binascii.Error as e:
+        raise HTTPException(status_code=400, detail="The audio isn't base64") from e
+    if wav[:4] != b"RIFF" or wav[8:12] != b"WAVE":
+        raise HTTPException(status_code=400, detail="Only WAV recordings can be transcribed")
+    return wav
+
+
+def select_stt_service(provider: str | None) -> Any:
+    """Pick the requested STT service that transcribes whole recordings, falling back to
+    the first available one that does. Streaming-only services can't be used here.
+
+    Raises:
+        HTTPException: If no such STT service is available
+    """
+    services = {
+        provider_id: service
+        for provider_id, service in _stt_services.items()
+        if isinstance(service, SegmentedSTTService)
+    }
+    for provider_id, service in services.items():
+        if provider_id.value == provider:
+            return service
+    if not services:
+        raise HTTPException(
+            status_code=503, detail="No available STT provider can transcribe recordings"
+        )
+    return next(iter(services.values()))
+
+
+async def transcribe_wav(service: Any, wav: bytes) -> str:
+    """Run a WAV recording through an STT service and join what it heard.
+
+    Raises:
+        HTTPException: If the service reports an error
+    """
+    parts: list[str] = []
+    async for frame in service.run_stt(wav):
+        if isinstance(frame, TranscriptionFrame) and frame.text.strip():
+            parts.append(frame.text.strip())
+        elif isinstance(frame, ErrorFrame):
+            raise HTTPException(status_code=502, detail=frame.error)
+    return " ".join(parts)
+
+
+@config_router.post(
+    "/api/transcribe",
+    response_model=TranscribeResponse,
+    dependencies=[Depends(require_app_origin)],
+)
+async def transcribe(
+    request: TranscribeRequest,
+    x_tambourine_request: Annotated[str | None, Header()] = None,
+) -> TranscribeResponse:
+    """Transcribe a recording, then clean it up like a dictation unless asked not to."""
+    return await run_abortable(x_tambourine_request, transcribe_recording(request))
+
+
+async def transcribe_recording(request: TranscribeRequest) -> TranscribeResponse:
+    """Run the request's recording through STT and, unless turned off, cleanup.
+
+    Raises:
+        HTTPException: If the recording can't be read or a provider fails
+    """
+    wav = decode_wav(request.audio)
+    raw_text = await transcribe_wav(select_stt_service(request.stt_provider), wav)
+    if not request.cleanup or not raw_text:
+        return TranscribeResponse(raw_text=raw_text, text=raw_text)
+
+    cleanup_request = CleanupRequest(
+        text=raw_text,
+        provider=request.provider,
+        sections=request.sections,
+        locale=request.locale,
+        vocabulary=request.vocabulary,
+    )
+    return TranscribeResponse(raw_text=raw_text, text=await clean_up_text(cleanup_request))
@@ -0,0 +1,28 @@
+"""Startup steps that must run before the provider libraries are imported.
+
+faster-whisper and huggingface_hub resolve their cache location from the environment
+when first imported, which happens as soon as main.py imports the provider registry.
+main.py imports this module first so the configured cache directory is in place by then.
+"""
+
+from loguru import logger
+from pydantic_settings import BaseSettings
+
+from config.settings import Settings
+from utils.cache_dir import apply_cache_dir
+
+
+class CacheSettings(BaseSettings):
+    """The cache directory setting alone, read without loading the provider registry."""
+
+    model_config = Settings.model_config
+
+    cache_dir: str | None = None
+
+
+# Redirect model downloads and temp files before local models are imported
+try:
+    apply_cache_dir(CacheSettings().cache_dir)
+except ValueError as e:
+    logger.error(f"Cache directory error: {e}")
+    raise SystemExit(1) from e
@@ -48,6 +48,23 @@ class Settings(BaseSettings):
     )
     openrouter_api_key: str | None = Field(None, description="OpenRouter API key for LLM")
 
+    # Proxy (optional, applied to STT and LLM provider connections, see utils/proxy.py)
+    proxy_url: str | None = Field(
+        None,
+        description="HTTP(S) proxy URL (e.g. http://proxy.corp:8080)",
+    )
+    proxy_username: str | None = Field(None, description="Proxy username (optional)")
+    proxy_password: str | None = Field(None, description="Proxy password (optional)")
+    no_proxy: str | None = Field(
+        None, description="Comma-separated hosts that bypass the proxy (localhost is always added)"
+    )
+
+    # Storage (optional)
+    cache_dir: str | None = Field(
+        None,
+        description="Directory for model downloads and temporary files (default: system locations)",
+    )
+
     # Logging
     log_level: str = Field("INFO", description="Logging level")
 
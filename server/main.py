@@ -9,6 +9,9 @@ Usage:
     python main.py --port 8765
 """
 
+# Must come before every other import, see bootstrap.py
+import bootstrap  # noqa: F401  # isort: skip
+
 import asyncio
 from contextlib import asynccontextmanager
 from typing import Annotated, Any, cast
@@ -46,7 +49,7 @@ from pipecat.transports.smallwebrtc.request_handler import (
 from pipecat.transports.smallwebrtc.transport import SmallWebRTCTransport
 from pydantic import BaseModel
 
-from api.config_server import config_router, set_available_providers
+from api.config_server import allow_dev_origin, config_router, set_available_providers
 from config.settings import Settings
 from processors.configuration import ConfigurationProcessor
 from processors.llm import LLMResponseToRTVIConverter, TranscriptionToLLMConverter
@@ -58,6 +61,7 @@ from services.providers import (
     create_all_available_stt_services,
 )
 from utils.logger import configure_logging
+from utils.proxy import apply_proxy_settings
 
 # ICE servers for WebRTC NAT traversal
 ice_servers = [
@@ -368,6 +372,9 @@ def main(
     verbose: Annotated[
         bool, typer.Option("-v", "--verbose", help="Enable verbose logging")
     ] = False,
+    dev: Annotated[
+        bool, typer.Option("--dev", help="Accept requests from the Vite dev server")
+    ] = False,
 ) -> None:
     """Tambourine Server - Voice dictation with AI cleanup."""
     # Load settings first so we can use them as defaults
@@ -390,6 +397,15 @@ def main(
     if verbose:
         logger.info("Verbose logging enabled")
 
+    allow_dev_origin(dev)
+
+    # Route provider traffic through the proxy before any clients are created
+    try:
+        apply_proxy_settings(settings)
+    except ValueError as e:
+        logger.error(f"Proxy configuration error: {e}")
+        raise SystemExit(1) from e
+
     # Initialize services
     if not initialize_services(settings):
         raise SystemExit(1)
@@ -7,19 +7,28 @@ configuration of the pipeline without global state or REST API endpoints.
 from __future__ import annotations
 
 import asyncio
+import base64
+import binascii
 from typing import TYPE_CHECKING, Any, Literal
 
 from loguru import logger
 from pipecat.frames.frames import (
     Frame,
+    InputAudioRawFrame,
     InputTransportMessageFrame,
     ManuallySwitchServiceFrame,
     OutputTransportMessageFrame,
     StartFrame,
+    UserStartedSpeakingFrame,
+    UserStoppedSpeakingFrame,
+    VADUserStartedSpeakingFrame,
+    VADUserStoppedSpeakingFrame,
 )
 from pipecat.processors.frame_processor import FrameDirection, FrameProcessor
+from pipecat.services.whisper.base_stt import BaseWhisperSTTService
 from pydantic import BaseModel, ValidationError
 
+from processors.llm import LocalePreferences, RecleanRequestFrame
 from services.provider_registry import LLMProviderId, STTProviderId
 
 # =============================================================================
@@ -41,6 +50,49 @@ class ClientMessage(BaseModel):
     data: ClientMessageData
 
 
+def decode_replay_chunk(data: dict[str, Any]) -> tuple[bytes, int] | None:
+    """Decode a replay-audio payload into 16-bit mono PCM and its sample rate.
+
+    Args:
+        data: Payload with base64 "audio" and its "sample_rate"
+
+    Returns:
+        The PCM bytes and sample rate, or None if the payload is malformed.
+    """
+    audio = data.get("audio")
+    sample_rate = data.get("sample_rate")
+    if not isinstance(audio, str) or not isinstance(sample_rate, int) or sample_rate <= 0:
+        return None
+    try:
+        pcm = base64.b64decode(audio, validate=True)
+    except binascii.Error:
+        return None
+    if not pcm or len(pcm) % 2:
+        return None
+    return pcm, sample_rate
+
+
+def parse_vocabulary(terms: Any) -> list[str] | None:
+    """Validate a vocabulary payload, dropping blank and repeated terms.
+
+    Args:
+        terms: The "terms" value of a set-stt-vocabulary message
+
+    Returns:
+        The terms in their original order, or None if the payload isn't a list of strings.
+    """
+    if not isinstance(terms, list) or not all(isinstance(term, str) for term in terms):
+        return None
+    seen: set[str] = set()
+    vocabulary: list[str] = []
+    for term in terms:
+        term = term.strip()
+        if term and term.casefold() not in seen:
+            seen.add(term.casefold())
+            vocabulary.append(term)
+    return vocabulary
+
+
 if TYPE_CHECKING:
     from pipecat.pipeline.llm_switcher import LLMSwitcher
     from pipecat.pipeline.service_switcher import ServiceSwitcher
@@ -59,6 +111,10 @@ class ConfigurationProcessor(FrameProcessor):
     - set-llm-provider: Switch LLM service
     - set-prompt-sections: Update LLM prompt
     - set-stt-timeout: Update transcription timeout
+    - set-locale: Update number/date formatting conventions
+    - set-stt-vocabulary: Update the terms transcription and cleanup should recognize
+    - reclean-text: Clean up a history entry's transcript again
+    - replay-audio / replay-audio-end: Run a failed dictation's recording again
 
     All configuration is scoped to this pipeline instance, eliminating
     global state and enabling multi-client support.
@@ -97,6 +153,9 @@ class ConfigurationProcessor(FrameProcessor):
         # Queue for config messages received before pipeline is fully ready
         self._pending_config_messages: list[dict[str, Any]] = []
 
+        # Whether a failed dictation's recording is being replayed
+        self._replaying = False
+
         # Track current providers for logging
         self._current_stt_provider: STTProviderId | None = None
         self._current_llm_provider: LLMProviderId | None = None
@@ -115,14 +174,25 @@ class ConfigurationProcessor(FrameProcessor):
             logger.info("Pipeline started - waiting for full propagation")
         elif isinstance(frame, InputTransportMessageFrame):
             logger.debug(f"InputTransportMessageFrame received: {frame.message}")
+            msg_type, data = self._extract_message_type_and_data(frame.message)
+            if msg_type == "reclean-text":
+                await self._request_reclean(data)
+                return
+            if msg_type == "replay-audio":
+                await self._replay_audio(data)
+                return
+            if msg_type == "replay-audio-end":
+                await self._end_replay()
+                return
             # Queue config messages if pipeline is not fully ready yet
             if not self._pipeline_fully_ready:
-                msg_type, _ = self._extract_message_type_and_data(frame.message)
                 if msg_type in {
                     "set-stt-provider",
                     "set-llm-provider",
                     "set-prompt-sections",
                     "set-stt-timeout",
+                    "set-locale",
+                    "set-stt-vocabulary",
                 }:
                     logger.debug(f"Queuing config message: {msg_type} (pipeline not fully ready)")
                     self._pending_config_messages.append(frame.message)
@@ -199,6 +269,8 @@ class ConfigurationProcessor(FrameProcessor):
             "set-llm-provider",
             "set-prompt-sections",
             "set-stt-timeout",
+            "set-locale",
+            "set-stt-vocabulary",
         }:
             return False
 
@@ -212,6 +284,10 @@ class ConfigurationProcessor(FrameProcessor):
             await self._set_prompt_sections(data.get("sections"))
         elif msg_type == "set-stt-timeout":
             await self._set_stt_timeout(data.get("timeout_seconds"))
+        elif msg_type == "set-locale":
+            await self._set_locale(data.get("locale"))
+        elif msg_type == "set-stt-vocabulary":
+            await self._set_stt_vocabulary(data.get("terms"))
 
         return True
 
@@ -335,6 +411,94 @@ class ConfigurationProcessor(FrameProcessor):
         logger.info(f"Set STT timeout to: {timeout_seconds}s")
         await self._send_config_success("stt-timeout", timeout_seconds)
 
+    async def _set_locale(self, locale: dict[str, Any] | None) -> None:
+        """Update the locale preferences used by the cleanup prompt.
+
+        Args:
+            locale: The locale preferences, or None to clear them.
+        """
+        if not locale:
+            self._llm_converter.set_locale_preferences(None)
+            await self._send_config_success("locale", "default")
+            return
+
+        try:
+            preferences = LocalePreferences.model_validate(locale)
+        except ValidationError as e:
+            await self._send_config_error("locale", f"Invalid locale preferences: {e}")
+            return
+
+        self._llm_converter.set_locale_preferences(preferences)
+        await self._send_config_success("locale", preferences.model_dump())
+
+    async def _set_stt_vocabulary(self, terms: Any) -> None:
+        """Update the user's vocabulary.
+
+        Whisper-based STT services get the terms as their transcription prompt, which
+        biases them towards those spellings; for the other providers the cleanup
+        prompt corrects the terms instead.
+
+        Args:
+            terms: The vocabulary terms
+        """
+        vocabulary = parse_vocabulary(terms)
+        if vocabulary is None:
+            await self._send_config_error("stt-vocabulary", "Terms must be a list of strings")
+            return
+
+        self._llm_converter.set_vocabulary(vocabulary)
+        stt_prompt = ", ".join(vocabulary) or None
+        for service in self._stt_services.values():
+            if isinstance(service, BaseWhisperSTTService):
+                service._prompt = stt_prompt
+        logger.info(f"Set STT vocabulary: {len(vocabulary)} terms")
+        await self._send_config_success("stt-vocabulary", len(vocabulary))
+
+    async def _request_reclean(self, data: dict[str, Any]) -> None:
+        """Send a history entry's transcript through cleanup again.
+
+        Args:
+            data: Payload with the entry "id" and the "text" to clean
+        """
+        entry_id = data.get("id")
+        text = data.get("text")
+        if not isinstance(entry_id, str) or not isinstance(text, str):
+            logger.warning(f"Ignoring malformed reclean-text message: {data}")
+            return
+        await self.push_frame(RecleanRequestFrame(entry_id=entry_id, text=text))
+
+    async def _replay_audio(self, data: dict[str, Any]) -> None:
+        """Feed a chunk of a failed dictation's recording into the pipeline.
+
+        The client replays the recording between its start-recording and
+        stop-recording messages as if it were spoken into the microphone, so the
+        speech start a VAD would detect is signalled before the first chunk.
+
+        Args:
+            data: Payload with base64 16-bit mono PCM "audio" and its "sample_rate"
+        """
+        chunk = decode_replay_chunk(data)
+        if chunk is None:
+            logger.warning("Ignoring malformed replay-audio message")
+            return
+        pcm, sample_rate = chunk
+        if not self._replaying:
+            self._replaying = True
+            logger.info("Replaying a failed dictation")
+            await self.push_frame(VADUserStartedSpeakingFrame())
+            await self.push_frame(UserStartedSpeakingFrame())
+        await self.push_frame(
+            InputAudioRawFrame(audio=pcm, sample_rate=sample_rate, num_channels=1)
+        )
+
+    async def _end_replay(self) -> None:
+        """Signal the end of speech after a replayed recording, so STT finishes."""
+        if not self._replaying:
+            return
+        self._replaying = False
+        await self.push_frame(VADUserStoppedSpeakingFrame())
+        await self.push_frame(UserStoppedSpeakingFrame())
+
     async def _send_config_success(self, setting: str, value: Any) -> None:
         """Send a configuration success message to the client.
 
@@ -1,9 +1,12 @@
 """LLM-based text formatting processor for dictation using idiomatic Pipecat patterns."""
 
-from typing import Any
+from dataclasses import dataclass
+from typing import Any, Literal
 
 from pipecat.frames.frames import (
+    DataFrame,
     Frame,
+    InterruptionFrame,
     LLMFullResponseEndFrame,
     LLMFullResponseStartFrame,
     OutputTransportMessageFrame,
@@ -15,6 +18,7 @@ from pipecat.processors.aggregators.openai_llm_context import (
     OpenAILLMContextFrame,
 )
 from pipecat.processors.frame_processor import FrameDirection, FrameProcessor
+from pydantic import BaseModel
 
 from utils.logger import logger
 
@@ -132,6 +136,70 @@ Pipecat
 Tauri"""
 
 
+class LocalePreferences(BaseModel):
+    """Locale conventions for numbers, dates and units in the cleaned text."""
+
+    locale: str | None = None  # BCP 47 tag, e.g. "de-DE"
+    decimal_separator: Literal[".", ","] = "."
+    date_format: Literal["YYYY-MM-DD", "DD.MM.YYYY", "DD/MM/YYYY", "MM/DD/YYYY"] | None = None
+    number_style: Literal["digits", "small_as_words", "as_spoken"] = "digits"
+
+
+def build_locale_prompt(preferences: LocalePreferences) -> str:
+    """Build the prompt section describing locale formatting conventions."""
+    decimal = preferences.decimal_separator
+    thousands = "." if decimal == "," else ","
+
+    lines = ["## Numbers, Dates and Units"]
+    if preferences.locale:
+        lines.append(f"Format numbers, dates and units for the {preferences.locale} locale.")
+
+    if preferences.number_style == "digits":
+        lines.append(
+            f'- Write spoken numbers as digits: "three point five" = "3{decimal}5", '
+            f'"twenty five hundred" = "2{thousands}500"'
+        )
+    elif preferences.number_style == "small_as_words":
+        lines.append(
+            "- Spell out numbers from zero to nine; write larger numbers as digits: "
+            f'"three apples" = "three apples", "three point five" = "3{decimal}5"'
+        )
+    else:
+        lines.append("- Keep numbers as spoken words unless they were dictated as digits")
+
+    lines.append(
+        f'- Use "{decimal}" as the decimal separator and "{thousands}" as the thousands separator'
+    )
+
+    if preferences.date_format:
+        example = (
+            preferences.date_format.replace("YYYY", "2025").replace("MM", "03").replace("DD", "05")
+        )
+        lines.append(
+            f'- Write dates as {preferences.date_format}: "march fifth twenty twenty five" = '
+            f'"{example}"'
+        )
+
+    lines.append(
+        '- Keep units with their numbers using standard symbols: "five kilometers" = "5 km"'
+    )
+    return "\n".join(lines)
+
+
+def build_vocabulary_prompt(terms: list[str]) -> str | None:
+    """Build the prompt section listing the user's vocabulary, or None without terms."""
+    terms = [term.strip() for term in terms if term.strip()]
+    if not terms:
+        return None
+    lines = [
+        "## Vocabulary",
+        "The speaker often uses these terms. When the transcript has a word that sounds like "
+        "one of them, write the term exactly as spelled here:",
+    ]
+    lines.extend(f"- {term}" for term in terms)
+    return "\n".join(lines)
+
+
 def combine_prompt_sections(
     main_custom: str | None,
     advanced_enabled: bool,
@@ -159,6 +227,35 @@ def combine_prompt_sections(
     return "\n\n".join(parts)
 
 
+@dataclass
+class RecleanRequestFrame(DataFrame):
+    """Request to clean up an earlier transcript again for a history revision.
+
+    Travels ahead of the LLM context it triggers, so the response converter can
+    label the result with the history entry it belongs to.
+    """
+
+    entry_id: str
+    text: str
+
+
+def cleaned_text_message(cleaned_text: str, reclean_entry_id: str | None) -> dict[str, Any]:
+    """Build the RTVI message carrying cleaned text to the client.
+
+    Args:
+        cleaned_text: The LLM output
+        reclean_entry_id: History entry being re-cleaned, or None for a new dictation
+
+    Returns:
+        A "reclean-result" message for re-cleans, otherwise a "transcript" message
+    """
+    if reclean_entry_id is not None:
+        data = {"type": "reclean-result", "id": reclean_entry_id, "text": cleaned_text}
+    else:
+        data = {"type": "transcript", "text": cleaned_text}
+    return {"label": "rtvi-ai", "type": "server-message", "data": data}
+
+
 class TranscriptionToLLMConverter(FrameProcessor):
     """Converts TranscriptionFrame to OpenAILLMContextFrame for LLM formatting.
 
@@ -176,17 +273,35 @@ class TranscriptionToLLMConverter(FrameProcessor):
         self._advanced_custom: str | None = None
         self._dictionary_enabled: bool = False
         self._dictionary_custom: str | None = None
+        self._locale_preferences: LocalePreferences | None = None
+        self._vocabulary: list[str] = []
 
     @property
     def system_prompt(self) -> str:
         """Get the combined system prompt from all sections."""
-        return combine_prompt_sections(
+        prompt = combine_prompt_sections(
             main_custom=self._main_custom,
             advanced_enabled=self._advanced_enabled,
             advanced_custom=self._advanced_custom,
             dictionary_enabled=self._dictionary_enabled,
             dictionary_custom=self._dictionary_custom,
         )
+        if self._locale_preferences:
+            prompt = f"{prompt}\n\n{build_locale_prompt(self._locale_preferences)}"
+        vocabulary = build_vocabulary_prompt(self._vocabulary)
+        if vocabulary:
+            prompt = f"{prompt}\n\n{vocabulary}"
+        return prompt
+
+    def set_locale_preferences(self, preferences: LocalePreferences | None) -> None:
+        """Update the locale formatting conventions, or None to leave formatting unspecified."""
+        self._locale_preferences = preferences
+        logger.info("Locale preferences updated")
+
+    def set_vocabulary(self, terms: list[str]) -> None:
+        """Update the terms the cleanup should spell as given."""
+        self._vocabulary = terms
+        logger.info(f"Vocabulary updated ({len(terms)} terms)")
 
     def set_prompt_sections(
         self,
@@ -215,6 +330,15 @@ class TranscriptionToLLMConverter(FrameProcessor):
         self._dictionary_custom = dictionary_custom
         logger.info("Formatting prompt sections updated")
 
+    def _build_context(self, text: str) -> OpenAILLMContext:
+        """Create an OpenAI-compatible context with the formatting prompt."""
+        return OpenAILLMContext(
+            messages=[
+                {"role": "system", "content": self.system_prompt},
+                {"role": "user", "content": text},
+            ]
+        )
+
     async def process_frame(self, frame: Frame, direction: FrameDirection) -> None:
         """Convert transcription frames to LLM context frames.
 
@@ -229,18 +353,29 @@ class TranscriptionToLLMConverter(FrameProcessor):
             if text and text.strip():
                 logger.debug(f"Converting transcription to LLM context: {text[:50]}...")
 
-                # Create OpenAI-compatible context with formatting prompt
-                context = OpenAILLMContext(
-                    messages=[
-                        {"role": "system", "content": self.system_prompt},
-                        {"role": "user", "content": text},
-                    ]
-                )
+                context = self._build_context(text)
+
+                # Let the client know cleanup has started (transcription is done)
+                cleanup_message = {
+                    "label": "rtvi-ai",
+                    "type": "server-message",
+                    "data": {"type": "cleanup-started", "raw": text},
+                }
+                await self.push_frame(OutputTransportMessageFrame(message=cleanup_message), direction)
 
                 # Push context frame to trigger LLM processing
                 await self.push_frame(OpenAILLMContextFrame(context=context), direction)
             return
 
+        if isinstance(frame, RecleanRequestFrame):
+            if frame.text.strip():
+                logger.debug(f"Re-cleaning history entry {frame.entry_id}")
+                # The request goes first so the result is labelled with its entry
+                await self.push_frame(frame, direction)
+                context = self._build_context(frame.text)
+                await self.push_frame(OpenAILLMContextFrame(context=context), direction)
+            return
+
         # Pass through all other frames unchanged
         await self.push_frame(frame, direction)
 
@@ -250,7 +385,9 @@ class LLMResponseToRTVIConverter(FrameProcessor):
 
     This processor collects streamed TextFrames between LLMFullResponseStartFrame
     and LLMFullResponseEndFrame, then sends the complete cleaned text as an
-    RTVI server message to the client.
+    RTVI server message to the client. A response following a RecleanRequestFrame
+    is sent as a "reclean-result" for that history entry instead of a transcript.
+    An interruption (a cancelled dictation) drops the response collected so far.
     """
 
     def __init__(self, **kwargs: Any) -> None:
@@ -258,6 +395,13 @@ class LLMResponseToRTVIConverter(FrameProcessor):
         super().__init__(**kwargs)
         self._accumulator: str = ""
         self._is_accumulating: bool = False
+        self._reclean_entry_id: str | None = None
+
+    def discard_response(self) -> None:
+        """Forget the response being collected, so nothing is sent for it."""
+        self._accumulator = ""
+        self._is_accumulating = False
+        self._reclean_entry_id = None
 
     async def process_frame(self, frame: Frame, direction: FrameDirection) -> None:
         """Accumulate LLM response and convert to RTVI message.
@@ -268,6 +412,16 @@ class LLMResponseToRTVIConverter(FrameProcessor):
         """
         await super().process_frame(frame, direction)
 
+        if isinstance(frame, InterruptionFrame):
+            self.discard_response()
+            await self.push_frame(frame, direction)
+            return
+
+        if isinstance(frame, RecleanRequestFrame):
+            # The next response is a re-clean of this entry
+            self._reclean_entry_id = frame.entry_id
+            return
+
         if isinstance(frame, LLMFullResponseStartFrame):
             # Start accumulating LLM response
             self._accumulator = ""
@@ -284,15 +438,15 @@ class LLMResponseToRTVIConverter(FrameProcessor):
             self._is_accumulating = False
             cleaned_text = self._accumulator.strip()
 
-            if cleaned_text:
+            reclean_entry_id = self._reclean_entry_id
+            self._reclean_entry_id = None
+
+            # A re-clean always gets an answer so the client isn't left waiting
+            if cleaned_text or reclean_entry_id is not None:
                 logger.info(f"Cleaned text: '{cleaned_text}'")
 
                 # Create RTVI message for client
-                rtvi_message = {
-                    "label": "rtvi-ai",
-                    "type": "server-message",
-                    "data": {"type": "transcript", "text": cleaned_text},
-                }
+                rtvi_message = cleaned_text_message(cleaned_text, reclean_entry_id)
                 await self.push_frame(OutputTransportMessageFrame(message=rtvi_message), direction)
 
             self._accumulator = ""
@@ -7,6 +7,9 @@ Uses a state machine pattern with tagged unions for explicit state management:
 - IdleState: Not recording
 - RecordingState: Actively buffering transcriptions
 - WaitingForSTTState: Stop received, waiting for STT to catch up
+
+A cancel-recording message returns to IdleState from any state without emitting,
+and interrupts the processors downstream so a cleanup already running is aborted.
 """
 
 from __future__ import annotations
@@ -19,6 +22,7 @@ from typing import Any, Literal
 from pipecat.frames.frames import (
     Frame,
     InputTransportMessageFrame,
+    InterruptionFrame,
     OutputTransportMessageFrame,
     TranscriptionFrame,
     UserStartedSpeakingFrame,
@@ -116,12 +120,19 @@ class RecordingCompleteData(BaseModel):
     hasContent: bool
 
 
+class PartialTranscriptData(BaseModel):
+    """Data payload for partial-transcript server message."""
+
+    type: Literal["partial-transcript"]
+    text: str
+
+
 class RTVIServerMessage(BaseModel):
     """Server message in RTVI format."""
 
     label: Literal["rtvi-ai"]
     type: Literal["server-message"]
-    data: RecordingCompleteData
+    data: RecordingCompleteData | PartialTranscriptData
 
 
 # =============================================================================
@@ -188,6 +199,12 @@ class TranscriptionBufferProcessor(FrameProcessor):
                 await self._handle_stop_recording(direction)
                 return
 
+            if message_type == "cancel-recording":
+                self._handle_cancel_recording()
+                # Abort the LLM request of a dictation that already reached cleanup
+                await self.push_frame(InterruptionFrame(), FrameDirection.DOWNSTREAM)
+                return
+
         # Handle speech detection
         if isinstance(frame, UserStartedSpeakingFrame):
             self._handle_speech_started()
@@ -243,6 +260,13 @@ class TranscriptionBufferProcessor(FrameProcessor):
         logger.info("Start-recording received, entering RecordingState")
         self._state = RecordingState()
 
+    def _handle_cancel_recording(self) -> None:
+        """Drop the recording without emitting anything and return to IdleState."""
+        self._cancel_timeout()
+        self._cancel_draining()
+        logger.info("Cancel-recording received, discarding buffer")
+        self._state = IdleState()
+
     async def _handle_stop_recording(self, direction: FrameDirection) -> None:
         """Handle stop-recording based on current state."""
         match self._state:
@@ -333,6 +357,8 @@ class TranscriptionBufferProcessor(FrameProcessor):
                     speech_detected=state.speech_detected,
                 )
                 logger.debug(f"Buffered transcription: '{frame.text}' (total: '{new_buffer}')")
+                # Let the client show the transcript so far while still recording
+                await self._emit_partial_transcript(new_buffer, direction)
 
             case WaitingForSTTState() as state:
                 # Transcription arrived while waiting for speech to stop
@@ -458,6 +484,15 @@ class TranscriptionBufferProcessor(FrameProcessor):
         )
         await self.push_frame(consolidated_frame, direction)
 
+    async def _emit_partial_transcript(self, buffer: str, direction: FrameDirection) -> None:
+        """Send the raw transcript buffered so far to the client."""
+        message = RTVIServerMessage(
+            label="rtvi-ai",
+            type="server-message",
+            data=PartialTranscriptData(type="partial-transcript", text=buffer.strip()),
+        )
+        await self.push_frame(OutputTransportMessageFrame(message=message.model_dump()), direction)
+
     async def _emit_empty_response(self, direction: FrameDirection) -> None:
         """Send an empty response message to the client."""
         message = RTVIServerMessage(
@@ -0,0 +1,45 @@
+"""Tests for aborting the provider work of one-off requests."""
+
+import asyncio
+
+import pytest
+from fastapi import HTTPException
+
+from api.config_server import ABORTED_STATUS, abort_request, run_abortable
+
+
+async def answer(text: str, delay: float = 0) -> str:
+    """Provider work answering after a delay."""
+    await asyncio.sleep(delay)
+    return text
+
+
+class TestRunAbortable:
+    """Tests for run_abortable() and abort_request()."""
+
+    def test_returns_the_work_result(self) -> None:
+        """Requests with and without an ID get the work's answer."""
+        assert asyncio.run(run_abortable(None, answer("hello"))) == "hello"
+        assert asyncio.run(run_abortable("finished", answer("hello"))) == "hello"
+
+    def test_abort_cancels_running_work(self) -> None:
+        """Aborting a running request cancels its work and answers it at once."""
+
+        async def abort_while_running() -> None:
+            request = asyncio.create_task(run_abortable("running", answer("late", 10)))
+            await asyncio.sleep(0.01)
+            await abort_request("running")
+            await asyncio.wait_for(request, timeout=1)
+
+        with pytest.raises(HTTPException) as error:
+            asyncio.run(abort_while_running())
+        assert error.value.status_code == ABORTED_STATUS
+
+    def test_abort_before_the_request_arrives(self) -> None:
+        """A request aborted before it arrived is refused without running."""
+        asyncio.run(abort_request("early"))
+        work = answer("never")
+        with pytest.raises(HTTPException) as error:
+            asyncio.run(run_abortable("early", work))
+        assert error.value.status_code == ABORTED_STATUS
+        assert work.cr_frame is None
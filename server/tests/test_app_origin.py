@@ -0,0 +1,55 @@
+"""Tests for refusing one-off requests from web pages."""
+
+import asyncio
+
+import pytest
+from fastapi import HTTPException
+
+from api.config_server import (
+    APP_ORIGINS,
+    DEV_ORIGIN,
+    allow_dev_origin,
+    is_app_origin,
+    require_app_origin,
+)
+
+
+class TestIsAppOrigin:
+    """Tests for is_app_origin() function."""
+
+    def test_allows_requests_without_origin(self) -> None:
+        """The app's Rust side sends no Origin header."""
+        assert is_app_origin(None)
+
+    def test_allows_the_app_webview(self) -> None:
+        """Every origin the webview can have is allowed."""
+        assert all(is_app_origin(origin) for origin in APP_ORIGINS)
+
+    def test_refuses_other_origins(self) -> None:
+        """Web pages the user visits are refused."""
+        assert not is_app_origin("https://example.com")
+        assert not is_app_origin("null")
+
+    def test_dev_server_only_in_dev_mode(self) -> None:
+        """The Vite dev server is refused unless the server runs with --dev."""
+        assert not is_app_origin(DEV_ORIGIN)
+        allow_dev_origin(True)
+        try:
+            assert is_app_origin(DEV_ORIGIN)
+        finally:
+            allow_dev_origin(False)
+
+
+class TestRequireAppOrigin:
+    """Tests for require_app_origin() dependency."""
+
+    def test_raises_for_web_pages(self) -> None:
+        """A request from another origin gets a 403."""
+        with pytest.raises(HTTPException) as error:
+            asyncio.run(require_app_origin("https://example.com"))
+        assert error.value.status_code == 403
+
+    def test_passes_app_requests(self) -> None:
+        """Requests from the app go through."""
+        asyncio.run(require_app_origin("tauri://localhost"))
+        asyncio.run(require_app_origin(None))
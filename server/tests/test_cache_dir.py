@@ -0,0 +1,59 @@
+"""Tests for cache directory validation and model migration."""
+
+from pathlib import Path
+
+import pytest
+
+from utils.cache_dir import migrate_cached_models, validate_writable
+
+
+class TestValidateWritable:
+    """Tests for validate_writable() function."""
+
+    def test_creates_missing_directory(self, tmp_path: Path) -> None:
+        """A missing directory is created."""
+        target = tmp_path / "nested" / "cache"
+        validate_writable(target)
+        assert target.is_dir()
+        assert list(target.iterdir()) == []
+
+    def test_rejects_path_that_is_a_file(self, tmp_path: Path) -> None:
+        """A path pointing at a file cannot be used as a cache directory."""
+        target = tmp_path / "file"
+        target.write_text("x")
+        with pytest.raises(ValueError, match="not writable"):
+            validate_writable(target)
+
+
+class TestMigrateCachedModels:
+    """Tests for migrate_cached_models() function."""
+
+    def test_moves_whisper_models(self, tmp_path: Path) -> None:
+        """faster-whisper models are moved to the new hub cache."""
+        source = tmp_path / "old"
+        model = source / "models--Systran--faster-whisper-small"
+        model.mkdir(parents=True)
+        (model / "model.bin").write_text("weights")
+        (source / "models--other--model").mkdir()
+        target = tmp_path / "new"
+
+        moved = migrate_cached_models(source, target)
+
+        assert moved == ["models--Systran--faster-whisper-small"]
+        assert (target / "models--Systran--faster-whisper-small" / "model.bin").exists()
+        assert not model.exists()
+        assert (source / "models--other--model").exists()
+
+    def test_keeps_models_already_in_target(self, tmp_path: Path) -> None:
+        """Existing models in the target are not overwritten."""
+        source = tmp_path / "old"
+        (source / "models--Systran--faster-whisper-base").mkdir(parents=True)
+        target = tmp_path / "new"
+        (target / "models--Systran--faster-whisper-base").mkdir(parents=True)
+
+        assert migrate_cached_models(source, target) == []
+        assert (source / "models--Systran--faster-whisper-base").exists()
+
+    def test_missing_source_is_noop(self, tmp_path: Path) -> None:
+        """Nothing happens when there is no previous cache."""
+        assert migrate_cached_models(tmp_path / "missing", tmp_path / "new") == []
@@ -0,0 +1,43 @@
+"""Tests for the cleanup endpoint helpers."""
+
+from api.config_server import CleanupRequest, build_cleanup_context
+from processors.llm import ADVANCED_PROMPT_DEFAULT, MAIN_PROMPT_DEFAULT, LocalePreferences
+
+
+def system_prompt(request: CleanupRequest) -> str:
+    """The system prompt a cleanup request is sent with."""
+    return build_cleanup_context(request).messages[0]["content"]
+
+
+class TestBuildCleanupContext:
+    """Tests for build_cleanup_context() function."""
+
+    def test_uses_default_prompt_without_settings(self) -> None:
+        """Without prompt settings the default sections clean up the text."""
+        context = build_cleanup_context(CleanupRequest(text="um hello there"))
+        assert context.messages == [
+            {"role": "system", "content": f"{MAIN_PROMPT_DEFAULT}\n\n{ADVANCED_PROMPT_DEFAULT}"},
+            {"role": "user", "content": "um hello there"},
+        ]
+
+    def test_uses_custom_sections(self) -> None:
+        """Custom and disabled sections are applied as in a dictation."""
+        request = CleanupRequest(
+            text="hello",
+            sections={
+                "main": {"enabled": True, "content": "Fix the text."},
+                "advanced": {"enabled": False, "content": None},
+            },
+        )
+        assert system_prompt(request) == "Fix the text."
+
+    def test_adds_locale_and_vocabulary(self) -> None:
+        """Locale conventions and vocabulary follow the prompt sections."""
+        request = CleanupRequest(
+            text="hello",
+            locale=LocalePreferences(decimal_separator=","),
+            vocabulary=["Tambourine"],
+        )
+        prompt = system_prompt(request)
+        assert "## Numbers, Dates and Units" in prompt
+        assert prompt.endswith("- Tambourine")
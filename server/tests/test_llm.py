@@ -4,6 +4,11 @@ from processors.llm import (
     ADVANCED_PROMPT_DEFAULT,
     DICTIONARY_PROMPT_DEFAULT,
     MAIN_PROMPT_DEFAULT,
+    LLMResponseToRTVIConverter,
+    LocalePreferences,
+    build_locale_prompt,
+    build_vocabulary_prompt,
+    cleaned_text_message,
     combine_prompt_sections,
 )
 
@@ -118,3 +123,75 @@ class TestCombinePromptSections:
         assert MAIN_PROMPT_DEFAULT in result
         assert ADVANCED_PROMPT_DEFAULT in result
         assert DICTIONARY_PROMPT_DEFAULT not in result
+
+
+class TestBuildLocalePrompt:
+    """Tests for build_locale_prompt() function."""
+
+    def test_comma_decimal_separator(self) -> None:
+        """German-style preferences produce comma decimals and period thousands."""
+        result = build_locale_prompt(
+            LocalePreferences(locale="de-DE", decimal_separator=",", date_format="DD.MM.YYYY")
+        )
+        assert "de-DE" in result
+        assert '"three point five" = "3,5"' in result
+        assert '"2.500"' in result
+        assert '"05.03.2025"' in result
+
+    def test_defaults_use_period_and_omit_dates(self) -> None:
+        """Default preferences use a period decimal separator and no date rule."""
+        result = build_locale_prompt(LocalePreferences())
+        assert '"3.5"' in result
+        assert "Write dates" not in result
+
+    def test_as_spoken_keeps_words(self) -> None:
+        """The as_spoken number style does not ask for digit conversion."""
+        result = build_locale_prompt(LocalePreferences(number_style="as_spoken"))
+        assert "Keep numbers as spoken words" in result
+        assert "Write spoken numbers as digits" not in result
+
+
+class TestBuildVocabularyPrompt:
+    """Tests for build_vocabulary_prompt() function."""
+
+    def test_lists_each_term(self) -> None:
+        """Every term is listed as spelled."""
+        result = build_vocabulary_prompt(["Tambourine", "Pipecat"])
+        assert result is not None
+        assert "- Tambourine" in result
+        assert "- Pipecat" in result
+
+    def test_no_terms_no_section(self) -> None:
+        """An empty or blank vocabulary adds nothing to the prompt."""
+        assert build_vocabulary_prompt([]) is None
+        assert build_vocabulary_prompt(["  "]) is None
+
+
+class TestCleanedTextMessage:
+    """Tests for cleaned_text_message() function."""
+
+    def test_new_dictation_is_a_transcript(self) -> None:
+        """Without a history entry, cleaned text is sent as a transcript."""
+        message = cleaned_text_message("Hello.", None)
+        assert message["type"] == "server-message"
+        assert message["data"] == {"type": "transcript", "text": "Hello."}
+
+    def test_reclean_is_labelled_with_entry(self) -> None:
+        """A re-clean result carries the history entry ID."""
+        message = cleaned_text_message("Hello.", "entry-1")
+        assert message["data"] == {"type": "reclean-result", "id": "entry-1", "text": "Hello."}
+
+
+class TestDiscardResponse:
+    """Tests for dropping the response of a cancelled dictation."""
+
+    def test_discard_forgets_collected_text(self) -> None:
+        """Text collected before the interruption is never sent."""
+        converter = LLMResponseToRTVIConverter()
+        converter._accumulator = "Half a clean"
+        converter._is_accumulating = True
+        converter._reclean_entry_id = "entry-1"
+        converter.discard_response()
+        assert converter._accumulator == ""
+        assert not converter._is_accumulating
+        assert converter._reclean_entry_id is None
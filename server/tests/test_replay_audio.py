@@ -0,0 +1,27 @@
+"""Tests for decoding replayed recordings of failed dictations."""
+
+import base64
+
+from processors.configuration import decode_replay_chunk
+
+
+class TestDecodeReplayChunk:
+    """Tests for decode_replay_chunk() function."""
+
+    def test_decodes_pcm_and_sample_rate(self) -> None:
+        """A well-formed chunk yields its PCM bytes and sample rate."""
+        pcm = b"\x01\x00\xff\x7f"
+        data = {"audio": base64.b64encode(pcm).decode(), "sample_rate": 16000}
+        assert decode_replay_chunk(data) == (pcm, 16000)
+
+    def test_rejects_missing_or_invalid_fields(self) -> None:
+        """Chunks without audio, with bad base64 or a bad rate are ignored."""
+        assert decode_replay_chunk({"sample_rate": 16000}) is None
+        assert decode_replay_chunk({"audio": "not base64!", "sample_rate": 16000}) is None
+        assert decode_replay_chunk({"audio": "AAA=", "sample_rate": 0}) is None
+        assert decode_replay_chunk({"audio": "AAA=", "sample_rate": "16000"}) is None
+
+    def test_rejects_partial_samples(self) -> None:
+        """Audio must be whole 16-bit samples."""
+        data = {"audio": base64.b64encode(b"\x01\x00\x02").decode(), "sample_rate": 16000}
+        assert decode_replay_chunk(data) is None
@@ -0,0 +1,48 @@
+"""Tests for the journal summary endpoint helpers."""
+
+import pytest
+from fastapi import HTTPException
+
+from api.config_server import (
+    SUMMARY_PROMPT,
+    build_summary_context,
+    select_llm_service,
+    set_available_providers,
+)
+from services.provider_registry import LLMProviderId
+
+LLM_SERVICES = {LLMProviderId.OPENAI: "openai", LLMProviderId.OLLAMA: "ollama"}
+
+
+class TestBuildSummaryContext:
+    """Tests for build_summary_context() function."""
+
+    def test_puts_prompt_before_text(self) -> None:
+        """The summary prompt is the system message and the text the user message."""
+        context = build_summary_context("- 09:00 Call the bank")
+        assert context.messages == [
+            {"role": "system", "content": SUMMARY_PROMPT},
+            {"role": "user", "content": "- 09:00 Call the bank"},
+        ]
+
+
+class TestSelectLlmService:
+    """Tests for select_llm_service() function."""
+
+    def test_uses_requested_provider(self) -> None:
+        """The requested provider is used when it is available."""
+        set_available_providers({}, LLM_SERVICES)
+        assert select_llm_service("ollama") == "ollama"
+
+    def test_falls_back_to_first_available(self) -> None:
+        """An unknown or missing provider falls back to the first available one."""
+        set_available_providers({}, LLM_SERVICES)
+        assert select_llm_service("missing") == "openai"
+        assert select_llm_service(None) == "openai"
+
+    def test_rejects_when_none_available(self) -> None:
+        """Without any LLM service the request fails as unavailable."""
+        set_available_providers({}, {})
+        with pytest.raises(HTTPException) as error:
+            select_llm_service(None)
+        assert error.value.status_code == 503
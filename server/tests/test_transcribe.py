@@ -0,0 +1,98 @@
+"""Tests for the transcription endpoint helpers."""
+
+import asyncio
+import base64
+from collections.abc import AsyncGenerator
+
+import pytest
+from fastapi import HTTPException
+from pipecat.frames.frames import ErrorFrame, Frame, TranscriptionFrame
+from pipecat.services.stt_service import SegmentedSTTService
+
+from api.config_server import (
+    decode_wav,
+    select_stt_service,
+    set_available_providers,
+    transcribe_wav,
+)
+from services.provider_registry import STTProviderId
+
+WAV_HEADER = b"RIFF\x24\x00\x00\x00WAVEfmt "
+
+
+class FakeSegmentedSTT(SegmentedSTTService):
+    """STT service answering with fixed frames, created without a pipeline."""
+
+    frames: list[Frame]
+
+    async def run_stt(self, audio: bytes) -> AsyncGenerator[Frame, None]:
+        """Yield the fixed frames."""
+        for frame in self.frames:
+            yield frame
+
+
+def fake_stt(*frames: Frame) -> FakeSegmentedSTT:
+    """A fake segmented STT service, skipping the service setup it doesn't need."""
+    service = FakeSegmentedSTT.__new__(FakeSegmentedSTT)
+    service.frames = list(frames)
+    return service
+
+
+class TestDecodeWav:
+    """Tests for decode_wav() function."""
+
+    def test_decodes_wav(self) -> None:
+        """A base64-encoded WAV file is returned as bytes."""
+        assert decode_wav(base64.b64encode(WAV_HEADER).decode()) == WAV_HEADER
+
+    def test_rejects_other_audio(self) -> None:
+        """Other formats and broken base64 are refused as bad requests."""
+        for audio in [base64.b64encode(b"ID3\x04 an mp3").decode(), "not base64!"]:
+            with pytest.raises(HTTPException) as error:
+                decode_wav(audio)
+            assert error.value.status_code == 400
+
+
+class TestSelectSttService:
+    """Tests for select_stt_service() function."""
+
+    def test_uses_requested_segmented_provider(self) -> None:
+        """The requested provider is used when it transcribes whole recordings."""
+        openai, groq = fake_stt(), fake_stt()
+        set_available_providers({STTProviderId.OPENAI: openai, STTProviderId.GROQ: groq}, {})
+        assert select_stt_service("groq") is groq
+        assert select_stt_service(None) is openai
+
+    def test_skips_streaming_providers(self) -> None:
+        """Streaming-only providers are passed over, or refused when they are all there is."""
+        openai = fake_stt()
+        set_available_providers(
+            {STTProviderId.DEEPGRAM: "deepgram", STTProviderId.OPENAI: openai}, {}
+        )
+        assert select_stt_service("deepgram") is openai
+
+        set_available_providers({STTProviderId.DEEPGRAM: "deepgram"}, {})
+        with pytest.raises(HTTPException) as error:
+            select_stt_service(None)
+        assert error.value.status_code == 503
+
+
+class TestTranscribeWav:
+    """Tests for transcribe_wav() function."""
+
+    def test_joins_transcriptions(self) -> None:
+        """Every transcription the service yields is joined into one text."""
+        service = fake_stt(
+            TranscriptionFrame("Hello there.", "", ""),
+            TranscriptionFrame(" ", "", ""),
+            TranscriptionFrame(" How are you?", "", ""),
+        )
+        assert asyncio.run(transcribe_wav(service, WAV_HEADER)) == "Hello there. How are you?"
+
+    def test_reports_service_errors(self) -> None:
+        """An error from the service fails the request."""
+        service = fake_stt(ErrorFrame("quota exceeded"))
+        with pytest.raises(HTTPException) as error:
+            asyncio.run(transcribe_wav(service, WAV_HEADER))
+        assert error.value.status_code == 502
+        assert error.value.detail == "quota exceeded"
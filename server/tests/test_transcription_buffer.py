@@ -0,0 +1,24 @@
+"""Tests for cancelling a recording in the transcription buffer."""
+
+from processors.transcription_buffer import (
+    IdleState,
+    RecordingState,
+    TranscriptionBufferProcessor,
+)
+
+
+class TestCancelRecording:
+    """Tests for the cancel-recording client message."""
+
+    def test_cancel_discards_the_buffer(self) -> None:
+        """A cancelled recording goes back to idle with nothing buffered."""
+        processor = TranscriptionBufferProcessor()
+        processor._state = RecordingState(buffer="never mind", speech_detected=True)
+        processor._handle_cancel_recording()
+        assert processor._state == IdleState()
+
+    def test_cancel_while_idle_is_harmless(self) -> None:
+        """Cancelling without a recording leaves the processor idle."""
+        processor = TranscriptionBufferProcessor()
+        processor._handle_cancel_recording()
+        assert processor._state == IdleState()
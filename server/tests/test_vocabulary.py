@@ -0,0 +1,18 @@
+"""Tests for validating vocabulary messages."""
+
+from processors.configuration import parse_vocabulary
+
+
+class TestParseVocabulary:
+    """Tests for parse_vocabulary() function."""
+
+    def test_keeps_order_and_drops_blank_and_repeated_terms(self) -> None:
+        """Terms are trimmed, and blanks and case-insensitive repeats are dropped."""
+        terms = [" Tambourine ", "", "pipecat", "tambourine", "Pipecat"]
+        assert parse_vocabulary(terms) == ["Tambourine", "pipecat"]
+
+    def test_rejects_non_lists(self) -> None:
+        """Payloads that aren't lists of strings are rejected."""
+        assert parse_vocabulary(None) is None
+        assert parse_vocabulary("Tambourine") is None
+        assert parse_vocabulary(["Tambourine", 3]) is None
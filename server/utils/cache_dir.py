@@ -0,0 +1,97 @@
+"""Configurable location for model downloads and temporary files.
+
+Local models (e.g. faster-whisper for the Whisper provider) are downloaded into the
+Hugging Face cache, and temporary files go to the system temp directory. Both can be
+redirected to a user-chosen directory, e.g. on a larger drive. Models already
+downloaded to the previous cache are moved over so they aren't downloaded again.
+"""
+
+import os
+import shutil
+import tempfile
+import uuid
+from pathlib import Path
+
+from loguru import logger
+
+# Cached model directories moved when the cache location changes
+MIGRATED_MODEL_PATTERNS = ("models--*faster-whisper*",)
+
+
+def validate_writable(directory: Path) -> None:
+    """Ensure a directory exists and files can be written to it.
+
+    Raises:
+        ValueError: If the directory cannot be created or written to.
+    """
+    try:
+        directory.mkdir(parents=True, exist_ok=True)
+        probe = directory / f".write-test-{uuid.uuid4().hex}"
+        probe.write_text("ok")
+        probe.unlink()
+    except OSError as e:
+        raise ValueError(f"Cache directory '{directory}' is not writable: {e}") from e
+
+
+def default_hub_cache() -> Path:
+    """Hugging Face hub cache location as resolved from the current environment."""
+    if hub_cache := os.getenv("HF_HUB_CACHE"):
+        return Path(hub_cache)
+    if hf_home := os.getenv("HF_HOME"):
+        return Path(hf_home) / "hub"
+    return Path.home() / ".cache" / "huggingface" / "hub"
+
+
+def migrate_cached_models(source_hub: Path, target_hub: Path) -> list[str]:
+    """Move cached model directories from one hub cache to another.
+
+    Models already present in the target are left in place in the source.
+
+    Returns:
+        Names of the model directories that were moved.
+    """
+    if not source_hub.is_dir() or source_hub.resolve() == target_hub.resolve():
+        return []
+
+    target_hub.mkdir(parents=True, exist_ok=True)
+    moved: list[str] = []
+    for pattern in MIGRATED_MODEL_PATTERNS:
+        for model_dir in sorted(source_hub.glob(pattern)):
+            destination = target_hub / model_dir.name
+            if destination.exists():
+                continue
+            shutil.move(str(model_dir), str(destination))
+            moved.append(model_dir.name)
+    return moved
+
+
+def apply_cache_dir(configured_dir: str | None) -> None:
+    """Redirect model downloads and temporary files to the configured cache directory.
+
+    Must be called before the Hugging Face libraries are imported, which bootstrap.py
+    takes care of. Does nothing if no cache directory is configured.
+
+    Raises:
+        ValueError: If the configured directory is not writable.
+    """
+    if not configured_dir:
+        return
+
+    cache_dir = Path(configured_dir).expanduser()
+    validate_writable(cache_dir)
+
+    hf_home = cache_dir / "huggingface"
+    target_hub = hf_home / "hub"
+    moved = migrate_cached_models(default_hub_cache(), target_hub)
+    if moved:
+        logger.info(f"Moved cached models to {target_hub}: {', '.join(moved)}")
+
+    os.environ["HF_HOME"] = str(hf_home)
+    os.environ["HF_HUB_CACHE"] = str(target_hub)
+
+    temp_dir = cache_dir / "tmp"
+    temp_dir.mkdir(parents=True, exist_ok=True)
+    os.environ["TMPDIR"] = str(temp_dir)
+    tempfile.tempdir = str(temp_dir)
+
+    logger.info(f"Using cache directory {cache_dir}")
@@ -0,0 +1,89 @@
+"""Proxy configuration shared by provider HTTP and WebSocket clients.
+
+Clients built on httpx, websockets and botocore read the standard proxy environment
+variables, so configuring those once at startup applies the proxy to the providers
+using them. Two kinds of clients ignore these variables and connect directly:
+- The Azure Speech SDK, which only takes a proxy through SpeechConfig.set_proxy
+- aiohttp sessions not created with trust_env=True
+
+Only HTTP(S) proxies are supported, as SOCKS needs extra packages (socksio,
+python-socks) the server doesn't install.
+"""
+
+import os
+from urllib.parse import quote, urlsplit, urlunsplit
+
+from loguru import logger
+
+from config.settings import Settings
+
+# Hosts that must never go through the proxy (the Tauri client and local providers)
+LOCAL_NO_PROXY_HOSTS = ("localhost", "127.0.0.1", "::1")
+
+SUPPORTED_PROXY_SCHEMES = ("http", "https")
+
+
+def build_proxy_url(proxy_url: str, username: str | None, password: str | None) -> str:
+    """Return the proxy URL with credentials embedded when provided.
+
+    Credentials already present in the URL are kept unless a username is given.
+
+    Raises:
+        ValueError: If the URL has no host or an unsupported scheme.
+    """
+    parts = urlsplit(proxy_url)
+    if parts.scheme not in SUPPORTED_PROXY_SCHEMES or not parts.hostname:
+        schemes = ", ".join(SUPPORTED_PROXY_SCHEMES)
+        raise ValueError(
+            f"Invalid proxy URL '{proxy_url}'. Expected <scheme>://host:port with scheme: {schemes}"
+        )
+
+    if not username:
+        return proxy_url
+
+    credentials = quote(username, safe="")
+    if password:
+        credentials += ":" + quote(password, safe="")
+
+    host = parts.hostname
+    if ":" in host:
+        host = f"[{host}]"
+    netloc = f"{credentials}@{host}"
+    if parts.port is not None:
+        netloc += f":{parts.port}"
+
+    return urlunsplit((parts.scheme, netloc, parts.path, parts.query, parts.fragment))
+
+
+def build_no_proxy(no_proxy: str | None) -> str:
+    """Combine user-provided bypass hosts with the local hosts that are always bypassed."""
+    hosts = [host.strip() for host in (no_proxy or "").split(",") if host.strip()]
+    for local_host in LOCAL_NO_PROXY_HOSTS:
+        if local_host not in hosts:
+            hosts.append(local_host)
+    return ",".join(hosts)
+
+
+def apply_proxy_settings(settings: Settings) -> None:
+    """Export proxy environment variables so provider clients use the proxy.
+
+    Must be called before provider services are created. Does nothing if no proxy
+    is configured, leaving any proxy variables from the environment untouched.
+    """
+    if not settings.proxy_url:
+        return
+
+    proxy = build_proxy_url(settings.proxy_url, settings.proxy_username, settings.proxy_password)
+    no_proxy = build_no_proxy(settings.no_proxy)
+
+    for name in ("HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY"):
+        os.environ[name] = proxy
+        os.environ[name.lower()] = proxy
+    os.environ["NO_PROXY"] = no_proxy
+    os.environ["no_proxy"] = no_proxy
+
+    # Never log credentials
+    parts = urlsplit(proxy)
+    logger.info(f"Using proxy {parts.scheme}://{parts.hostname}:{parts.port} (bypass: {no_proxy})")
+    if settings.azure_speech_key:
+        logger.warning("Azure Speech doesn't support the proxy and connects directly")